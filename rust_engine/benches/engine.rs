@@ -0,0 +1,124 @@
+//! Engine benchmarks: pairwise distances, conjunction screening and propagation
+//!
+//! Populations come from `synthetic::benchmark_synthetic_catalog`, so the
+//! same case measures the same work on every commit. The Python extension
+//! module does not link into a standalone binary, so run without it; a
+//! substring argument selects cases:
+//!
+//! ```text
+//! cargo bench --no-default-features
+//! cargo bench --no-default-features -- screen/snapshot
+//! ```
+//!
+//! Cases are grouped by stage and measured with criterion, which keeps the
+//! previous run's numbers in `target/criterion` and reports the change.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use nalgebra::Vector6;
+use orbit_core::kernel::SoaPositions;
+use orbit_core::propagation::{integrate, ForceModel, Integrator};
+use orbit_core::screening::{ScreeningFilter, ScreeningPipeline, Trajectory};
+use orbit_core::sgp4::Sgp4Propagator;
+use orbit_core::synthetic::{benchmark_synthetic_catalog, SYNTHETIC_EPOCH_JD};
+use orbit_core::{conjunctions_from_states, Precision};
+
+/// Object ids and TEME states of a synthetic catalog at its epoch
+fn states(n: usize) -> (Vec<i32>, Vec<[f64; 3]>, Vec<[f64; 3]>) {
+    let mut ids = Vec::with_capacity(n);
+    let mut positions = Vec::with_capacity(n);
+    let mut velocities = Vec::with_capacity(n);
+    for elements in benchmark_synthetic_catalog(n, 0) {
+        let norad_id = elements.norad_id;
+        let (r, v) = Sgp4Propagator::from_mean_elements(elements).position_velocity(0.0).expect("synthetic orbit");
+        ids.push(norad_id);
+        positions.push(r);
+        velocities.push(v);
+    }
+    (ids, positions, velocities)
+}
+
+fn trajectories(n: usize) -> Vec<Trajectory> {
+    benchmark_synthetic_catalog(n, 0).into_iter().map(|e| Sgp4Propagator::from_mean_elements(e).into()).collect()
+}
+
+fn distances(c: &mut Criterion) {
+    let mut group = c.benchmark_group("distances");
+    group.sample_size(10);
+    for (label, n) in [("1k", 1_000), ("10k", 10_000)] {
+        let (_, positions, _) = states(n);
+        let soa = SoaPositions::<f64>::from_rows(&positions);
+        let mut row = vec![0.0; n];
+        group.bench_function(BenchmarkId::new("all_pairs", label), |b| {
+            b.iter(|| {
+                let mut closest = f64::INFINITY;
+                for i in 0..n - 1 {
+                    let out = &mut row[..n - i - 1];
+                    soa.squared_distances(soa.point(i), i + 1, out);
+                    closest = out.iter().fold(closest, |a, &b| a.min(b));
+                }
+                closest
+            })
+        });
+    }
+    group.finish();
+}
+
+fn screening(c: &mut Criterion) {
+    let mut group = c.benchmark_group("screen");
+    group.sample_size(10);
+    for (label, n) in [("1k", 1_000), ("10k", 10_000), ("30k", 30_000)] {
+        let (ids, positions, velocities) = states(n);
+        group.bench_function(BenchmarkId::new("snapshot", label), |b| {
+            b.iter(|| conjunctions_from_states(&ids, &positions, &velocities, 10.0, false, Precision::Double))
+        });
+        group.bench_function(BenchmarkId::new("snapshot_mixed", label), |b| {
+            b.iter(|| conjunctions_from_states(&ids, &positions, &velocities, 10.0, true, Precision::Mixed))
+        });
+    }
+
+    // one hour at one-minute steps behind the orbit pre-filters; the windowed
+    // search is seconds per run at 1k already, so larger catalogs are left out
+    let satellites = trajectories(1_000);
+    let mut pipeline = ScreeningPipeline::with_threshold(5.0);
+    pipeline.filters = vec![
+        ScreeningFilter::AltitudeBand { pad_km: 10.0 },
+        ScreeningFilter::OrbitPath { pad_km: 10.0 },
+    ];
+    group.bench_function(BenchmarkId::new("window_1h", "1k"), |b| {
+        b.iter(|| {
+            pipeline.screen(&satellites, SYNTHETIC_EPOCH_JD, SYNTHETIC_EPOCH_JD + 1.0 / 24.0, 60.0).expect("valid window")
+        })
+    });
+    group.finish();
+}
+
+fn propagation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("propagate");
+    let propagators: Vec<Sgp4Propagator> =
+        benchmark_synthetic_catalog(1_000, 0).into_iter().map(Sgp4Propagator::from_mean_elements).collect();
+    group.bench_function(BenchmarkId::new("sgp4", "1k_x_60_steps"), |b| {
+        b.iter(|| {
+            let mut count = 0;
+            for propagator in &propagators {
+                for minute in 0..60 {
+                    count += propagator.position_velocity(minute as f64).is_ok() as usize;
+                }
+            }
+            count
+        })
+    });
+
+    let (_, positions, velocities) = states(1);
+    let (r, v) = (positions[0], velocities[0]);
+    let y0 = Vector6::new(r[0], r[1], r[2], v[0], v[1], v[2]);
+    group.bench_function(BenchmarkId::new("rk4_j2", "1_day"), |b| {
+        b.iter(|| integrate(&y0, 86400.0, ForceModel::J2, Integrator::Rk4 { step: 10.0 }))
+    });
+    group.bench_function(BenchmarkId::new("dopri_j2", "1_day"), |b| {
+        b.iter(|| integrate(&y0, 86400.0, ForceModel::J2, Integrator::DormandPrince { initial_step: 60.0 }))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, distances, screening, propagation);
+criterion_main!(benches);
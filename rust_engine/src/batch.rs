@@ -0,0 +1,150 @@
+//! Array-based batch entry points
+//!
+//! Accepts objects implementing the Python buffer protocol (numpy arrays,
+//! memoryviews) so large catalogs can be screened without building one
+//! `Satellite` object per row. C-contiguous float64 N x 3 buffers are read
+//! in place; any other sequence of 3-vectors is copied.
+
+use pyo3::buffer::PyBuffer;
+use pyo3::prelude::*;
+
+use crate::volume::{self, ScreeningVolume};
+use crate::{check_state, conjunctions_from_states, sorted, Conjunction, Precision};
+
+/// N x 3 rows of f64, either borrowed from a Python buffer or copied
+enum Rows3 {
+    Borrowed(PyBuffer<f64>),
+    Owned(Vec<[f64; 3]>),
+}
+
+impl Rows3 {
+    fn extract(obj: &PyAny, name: &str) -> PyResult<Self> {
+        if let Ok(buffer) = PyBuffer::<f64>::get(obj) {
+            let shape = buffer.shape();
+            if buffer.dimensions() != 2 || shape[1] != 3 {
+                return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                    "{} must have shape (N, 3), got {:?}",
+                    name, shape
+                )));
+            }
+            if buffer.is_c_contiguous() {
+                return Ok(Rows3::Borrowed(buffer));
+            }
+        }
+
+        let rows: Vec<Vec<f64>> = obj.extract().map_err(|_| {
+            pyo3::exceptions::PyTypeError::new_err(format!(
+                "{} must be a float64 array or a sequence of 3-element rows",
+                name
+            ))
+        })?;
+        rows.iter()
+            .map(|row| {
+                <[f64; 3]>::try_from(row.as_slice()).map_err(|_| {
+                    pyo3::exceptions::PyValueError::new_err(format!("{} rows must have 3 components", name))
+                })
+            })
+            .collect::<PyResult<Vec<_>>>()
+            .map(Rows3::Owned)
+    }
+
+    fn as_slice(&self) -> &[[f64; 3]] {
+        match self {
+            Rows3::Borrowed(buffer) => {
+                let rows = buffer.item_count() / 3;
+                // SAFETY: the buffer is a C-contiguous float64 array of shape (rows, 3),
+                // `[f64; 3]` has the alignment of f64, and the exporter is kept alive
+                // (and, with the GIL held, unmodified) for as long as `self` is borrowed.
+                unsafe { std::slice::from_raw_parts(buffer.buf_ptr() as *const [f64; 3], rows) }
+            }
+            Rows3::Owned(rows) => rows,
+        }
+    }
+}
+
+/// Extract NORAD IDs from an integer array or sequence
+fn extract_ids(obj: &PyAny) -> PyResult<Vec<i32>> {
+    let py = obj.py();
+    if let Ok(buffer) = PyBuffer::<i64>::get(obj) {
+        return buffer
+            .to_vec(py)?
+            .into_iter()
+            .map(|id| {
+                i32::try_from(id).map_err(|_| {
+                    pyo3::exceptions::PyOverflowError::new_err(format!("NORAD ID {} out of range", id))
+                })
+            })
+            .collect();
+    }
+    if let Ok(buffer) = PyBuffer::<i32>::get(obj) {
+        return buffer.to_vec(py);
+    }
+    obj.extract()
+}
+
+/// Screen N x 3 position/velocity arrays; results are unsorted
+///
+/// The arrays are read with the GIL held and screened without it. With
+/// `validate`, rows are checked as by `Satellite.validate()` first.
+pub(crate) fn screen_arrays(
+    ids: &PyAny,
+    positions: &PyAny,
+    velocities: &PyAny,
+    volume: ScreeningVolume,
+    apogee_perigee_filter: bool,
+    precision: Precision,
+    validate: bool,
+) -> PyResult<Vec<Conjunction>> {
+    let py = ids.py();
+    let ids = extract_ids(ids)?;
+    let positions = Rows3::extract(positions, "positions")?;
+    let velocities = Rows3::extract(velocities, "velocities")?;
+
+    let (positions, velocities) = (positions.as_slice(), velocities.as_slice());
+    if positions.len() != ids.len() || velocities.len() != ids.len() {
+        return Err(pyo3::exceptions::PyValueError::new_err(format!(
+            "ids, positions and velocities must have the same length ({}, {}, {})",
+            ids.len(),
+            positions.len(),
+            velocities.len()
+        )));
+    }
+    if validate {
+        for ((&id, position), velocity) in ids.iter().zip(positions).zip(velocities) {
+            check_state(id, position, velocity, false)?;
+        }
+    }
+
+    Ok(py.allow_threads(|| conjunctions_from_states(&ids, positions, velocities, volume, apogee_perigee_filter, precision)))
+}
+
+/// Find close approaches from N x 3 position/velocity arrays (km, km/s)
+///
+/// `precision` is "f64" (default) or "mixed" and `validate` rejects
+/// NaN/infinite or zero-norm rows, as for `find_conjunctions`.
+#[pyfunction]
+#[pyo3(signature = (
+    ids,
+    positions,
+    velocities,
+    threshold_km,
+    apogee_perigee_filter = false,
+    sort_by = "ids",
+    precision = "f64",
+    validate = true
+))]
+#[allow(clippy::too_many_arguments)]
+pub fn find_conjunctions_array(
+    ids: &PyAny,
+    positions: &PyAny,
+    velocities: &PyAny,
+    threshold_km: &PyAny,
+    apogee_perigee_filter: bool,
+    sort_by: &str,
+    precision: &str,
+    validate: bool,
+) -> PyResult<Vec<Conjunction>> {
+    let volume = volume::extract_threshold(threshold_km)?;
+    let precision: Precision = precision.parse().map_err(pyo3::exceptions::PyValueError::new_err)?;
+    sorted(screen_arrays(ids, positions, velocities, volume, apogee_perigee_filter, precision, validate)?, sort_by)
+}
@@ -0,0 +1,314 @@
+//! `orbitguard`: batch conjunction screening from the command line
+//!
+//! Reads a TLE file, screens every pair over a time window and writes the
+//! close approaches as CSV, JSON or one CCSDS CDM per event. Built without
+//! Python so it can run from cron:
+//!
+//! ```text
+//! cargo build --release --no-default-features --features cli
+//! orbitguard --tle catalog.tle --hours 24 --threshold 5 --format csv --output today.csv
+//! ```
+
+#[cfg(feature = "python")]
+compile_error!("build orbitguard with `--no-default-features --features cli`");
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use std::process::ExitCode;
+
+use orbit_core::cdm::{CdmHeader, CdmMessage, CdmObject};
+use orbit_core::epoch::{self, MJD_OFFSET};
+use orbit_core::screening::{ScreeningFilter, ScreeningPipeline, Trajectory};
+use orbit_core::sgp4::Sgp4Propagator;
+use orbit_core::{sort_conjunctions, tle, Conjunction, SortOrder};
+
+const USAGE: &str = "\
+Usage: orbitguard --tle FILE [options]
+
+Options:
+  --tle FILE          TLE catalog (two- or three-line format)
+  --start TIME        window start, ISO-8601 UTC or 'now' (default: now)
+  --end TIME          window end, ISO-8601 UTC (default: start + --hours)
+  --hours H           window length in hours (default: 24)
+  --step S            sampling step in seconds (default: 60)
+  --threshold KM      miss-distance threshold in km (default: 5)
+  --format FMT        csv, json, cdm or cdm-xml (default: csv)
+  --output PATH       output file, or directory for CDMs (default: stdout)
+  --sort ORDER        ids, distance, tca or none (default: tca)
+  --originator NAME   CDM ORIGINATOR (default: ORBITGUARDAI)
+  --no-filters        skip the altitude and orbit-path pre-filters
+  --no-checksum       accept TLE lines with bad checksums
+  -v, --verbose       log screening stages (filters, timings, hits) to stderr
+  -h, --help          show this message";
+
+/// Output format selected with `--format`
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Format {
+    Csv,
+    Json,
+    Cdm { xml: bool },
+}
+
+impl std::str::FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "csv" => Ok(Format::Csv),
+            "json" => Ok(Format::Json),
+            "cdm" | "kvn" => Ok(Format::Cdm { xml: false }),
+            "cdm-xml" | "xml" => Ok(Format::Cdm { xml: true }),
+            _ => Err(format!("Unknown format '{}' (expected csv, json, cdm or cdm-xml)", s)),
+        }
+    }
+}
+
+/// Parsed command line
+#[derive(Debug)]
+struct Options {
+    tle: String,
+    start: Option<String>,
+    end: Option<String>,
+    hours: f64,
+    step: f64,
+    threshold_km: f64,
+    format: Format,
+    output: Option<String>,
+    sort: SortOrder,
+    originator: String,
+    filters: bool,
+    checksum: bool,
+    verbose: bool,
+}
+
+impl Options {
+    /// Parse arguments (without the program name); Ok(None) means `--help`
+    fn parse(args: impl IntoIterator<Item = String>) -> Result<Option<Self>, String> {
+        let mut options = Options {
+            tle: String::new(),
+            start: None,
+            end: None,
+            hours: 24.0,
+            step: 60.0,
+            threshold_km: 5.0,
+            format: Format::Csv,
+            output: None,
+            sort: SortOrder::Tca,
+            originator: "ORBITGUARDAI".to_string(),
+            filters: true,
+            checksum: true,
+            verbose: false,
+        };
+        let mut args = args.into_iter();
+        while let Some(flag) = args.next() {
+            let mut value = || args.next().ok_or_else(|| format!("{} needs a value", flag));
+            let number = |text: String| -> Result<f64, String> {
+                match text.parse::<f64>() {
+                    Ok(x) if x > 0.0 && x.is_finite() => Ok(x),
+                    _ => Err(format!("{} must be a positive number, got '{}'", flag, text)),
+                }
+            };
+            match flag.as_str() {
+                "--tle" => options.tle = value()?,
+                "--start" => options.start = Some(value()?),
+                "--end" => options.end = Some(value()?),
+                "--hours" => options.hours = number(value()?)?,
+                "--step" => options.step = number(value()?)?,
+                "--threshold" => options.threshold_km = number(value()?)?,
+                "--format" => options.format = value()?.parse()?,
+                "--output" => options.output = Some(value()?),
+                "--sort" => options.sort = value()?.parse()?,
+                "--originator" => options.originator = value()?,
+                "--no-filters" => options.filters = false,
+                "--no-checksum" => options.checksum = false,
+                "-v" | "--verbose" => options.verbose = true,
+                "-h" | "--help" => return Ok(None),
+                _ => return Err(format!("Unknown option '{}'", flag)),
+            }
+        }
+        if options.tle.is_empty() {
+            return Err("--tle is required".to_string());
+        }
+        Ok(Some(options))
+    }
+
+    /// Screening window as Julian dates (UTC)
+    fn window(&self) -> Result<(f64, f64), String> {
+        let start_mjd = match self.start.as_deref() {
+            None | Some("now") => epoch::now_mjd_utc(),
+            Some(text) => epoch::parse_iso(text)?,
+        };
+        let end_mjd = match &self.end {
+            Some(text) => epoch::parse_iso(text)?,
+            None => start_mjd + self.hours / 24.0,
+        };
+        if end_mjd <= start_mjd {
+            return Err("--end must be after --start".to_string());
+        }
+        Ok((start_mjd + MJD_OFFSET, end_mjd + MJD_OFFSET))
+    }
+}
+
+/// Quote a CSV field when it contains a separator, quote or line break
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+fn to_csv(conjunctions: &[Conjunction], name: impl Fn(i32) -> String) -> String {
+    let mut out = String::from(
+        "norad_id_1,name_1,norad_id_2,name_2,tca_utc,distance_km,relative_velocity_km_s,radial_km,in_track_km,cross_track_km\n",
+    );
+    for c in conjunctions {
+        let tca = c.tca_jd.map_or_else(String::new, |jd| epoch::format_iso(jd - MJD_OFFSET));
+        out.push_str(&format!(
+            "{},{},{},{},{},{:.6},{:.6},{:.6},{:.6},{:.6}\n",
+            c.norad_id_1,
+            csv_field(&name(c.norad_id_1)),
+            c.norad_id_2,
+            csv_field(&name(c.norad_id_2)),
+            tca,
+            c.distance_km,
+            c.relative_velocity_km_s,
+            c.radial_km,
+            c.in_track_km,
+            c.cross_track_km,
+        ));
+    }
+    out
+}
+
+/// CDM object block with the object's SGP4 (TEME) state at TCA and its catalog name
+fn cdm_object(trajectory: &Trajectory, name: Option<&String>, start_jd: f64, tca_jd: f64) -> CdmObject {
+    let mut object = CdmObject {
+        norad_id: trajectory.norad_id(),
+        state: trajectory.state_at(start_jd, (tca_jd - start_jd) * 86400.0),
+        ..Default::default()
+    };
+    object.metadata.insert("REF_FRAME".to_string(), "TEME".to_string());
+    if let Some(name) = name {
+        object.metadata.insert("OBJECT_NAME".to_string(), name.clone());
+    }
+    object
+}
+
+fn write_output(path: Option<&str>, text: &str) -> Result<(), String> {
+    match path {
+        Some(path) => fs::write(path, text).map_err(|e| format!("{}: {}", path, e)),
+        None => io::stdout().write_all(text.as_bytes()).map_err(|e| e.to_string()),
+    }
+}
+
+fn run(options: &Options) -> Result<usize, String> {
+    let text = fs::read_to_string(&options.tle).map_err(|e| format!("{}: {}", options.tle, e))?;
+    let elements = tle::parse_text(&text, options.checksum).map_err(|e| format!("{}: {}", options.tle, e))?;
+    let names: Vec<Option<String>> = elements.iter().map(|e| e.name.clone()).collect();
+    let satellites: Vec<Trajectory> =
+        elements.into_iter().map(|e| Sgp4Propagator::from_mean_elements(e).into()).collect();
+    let index: HashMap<i32, usize> = satellites.iter().enumerate().map(|(k, s)| (s.norad_id(), k)).collect();
+    let name = |norad_id: i32| names[index[&norad_id]].clone().unwrap_or_default();
+
+    let (start_jd, end_jd) = options.window()?;
+    let mut pipeline = ScreeningPipeline::with_threshold(options.threshold_km);
+    if options.filters {
+        pipeline.filters = vec![
+            ScreeningFilter::AltitudeBand { pad_km: 10.0 },
+            ScreeningFilter::OrbitPath { pad_km: 10.0 },
+        ];
+    }
+    let mut conjunctions = pipeline
+        .screen(&satellites, start_jd, end_jd, options.step)
+        .map_err(|e| e.to_string())?;
+    sort_conjunctions(&mut conjunctions, options.sort);
+
+    match options.format {
+        Format::Csv => write_output(options.output.as_deref(), &to_csv(&conjunctions, name))?,
+        Format::Json => {
+            let json = serde_json::to_string_pretty(&conjunctions).map_err(|e| e.to_string())?;
+            write_output(options.output.as_deref(), &(json + "\n"))?
+        }
+        Format::Cdm { xml } => {
+            let dir = options.output.as_deref().ok_or("--format cdm needs --output DIRECTORY")?;
+            fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir, e))?;
+            let header = CdmHeader {
+                originator: options.originator.clone(),
+                message_id: None,
+                creation_mjd_utc: epoch::now_mjd_utc(),
+            };
+            for c in &conjunctions {
+                let tca_jd = c.tca_jd.unwrap_or(start_jd);
+                let object = |norad_id: i32| {
+                    let k = index[&norad_id];
+                    cdm_object(&satellites[k], names[k].as_ref(), start_jd, tca_jd)
+                };
+                let message = CdmMessage::build(c, &object(c.norad_id_1), &object(c.norad_id_2), &header)?;
+                let message_id = &message.header.iter().find(|f| f.key == "MESSAGE_ID").expect("CDM header").value;
+                let (body, extension) = if xml { (message.to_xml(), "xml") } else { (message.to_kvn(), "kvn") };
+                let path = Path::new(dir).join(format!("{}.{}", message_id, extension));
+                fs::write(&path, body).map_err(|e| format!("{}: {}", path.display(), e))?;
+            }
+        }
+    }
+    Ok(conjunctions.len())
+}
+
+fn main() -> ExitCode {
+    let options = match Options::parse(std::env::args().skip(1)) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Err(err) => {
+            eprintln!("orbitguard: {}\n\n{}", err, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+    if options.verbose {
+        tracing_subscriber::fmt().with_max_level(tracing::Level::DEBUG).with_writer(std::io::stderr).init();
+    }
+    match run(&options) {
+        Ok(count) => {
+            eprintln!("orbitguard: {} conjunction(s) below {} km", count, options.threshold_km);
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("orbitguard: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(text: &str) -> Vec<String> {
+        text.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_parse_options() {
+        let options = Options::parse(args("--tle cat.tle --start 2024-01-01 --hours 12 --format json")).unwrap().unwrap();
+        assert_eq!(options.format, Format::Json);
+        let (start, end) = options.window().unwrap();
+        assert!((start - 2460310.5).abs() < 1e-9);
+        assert!((end - start - 0.5).abs() < 1e-9);
+
+        assert!(Options::parse(args("--help")).unwrap().is_none());
+        assert!(Options::parse(args("--hours 12")).is_err());
+        assert!(Options::parse(args("--tle cat.tle --step -5")).is_err());
+        assert!(Options::parse(args("--tle cat.tle --format xlsx")).is_err());
+    }
+
+    #[test]
+    fn test_csv_quotes_names() {
+        assert_eq!(csv_field("ISS (ZARYA)"), "ISS (ZARYA)");
+        assert_eq!(csv_field("DEB, \"A\""), "\"DEB, \"\"A\"\"\"");
+    }
+}
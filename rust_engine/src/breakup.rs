@@ -0,0 +1,374 @@
+//! Fragmentation events: the NASA standard breakup model
+//!
+//! Follows Johnson, Krisko, Liou & Anz-Meador, "NASA's new breakup model of
+//! EVOLVE 4.0" (Adv. Space Res. 28(9), 2001). Fragment sizes follow a power
+//! law in characteristic length Lc; area-to-mass ratios are drawn from the
+//! bimodal (spacecraft or rocket body, Lc > 11 cm) or single (Lc < 8 cm)
+//! normal distributions in log10(A/M), with a random choice between the two
+//! in the bridge region; ejection speeds are log-normal in A/M and isotropic.
+//!
+//! As in the original model, fragment masses are not forced to add up to
+//! the parent mass. The largest fragment is the size above which one object
+//! is expected, so the power law never runs away.
+
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use nalgebra::Vector3;
+
+use crate::error::OrbitError;
+use crate::random::SplitMix64;
+#[cfg(feature = "python")]
+use crate::columnar::Column;
+#[cfg(feature = "python")]
+use crate::metadata::ObjectType;
+#[cfg(feature = "python")]
+use crate::Satellite;
+
+/// Specific energy (J/g) above which a collision is catastrophic
+const CATASTROPHIC_ENERGY_J_G: f64 = 40.0;
+
+/// Fragments above which a breakup is refused rather than allocated
+const MAX_FRAGMENTS: f64 = 1.0e6;
+
+/// Bridge between the small- and large-fragment A/M distributions (m)
+const SMALL_FRAGMENT_M: f64 = 0.08;
+const LARGE_FRAGMENT_M: f64 = 0.11;
+
+/// Kind of parent body, which sets the area-to-mass distribution of large fragments
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParentType {
+    Spacecraft,
+    RocketBody,
+}
+
+impl std::str::FromStr for ParentType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().replace('_', " ").as_str() {
+            "spacecraft" | "sc" | "payload" => Ok(ParentType::Spacecraft),
+            "rocket body" | "rb" | "r/b" => Ok(ParentType::RocketBody),
+            _ => Err(format!("Unknown parent type '{}' (expected spacecraft or rocket body)", s)),
+        }
+    }
+}
+
+/// What breaks the parent up
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BreakupEvent {
+    /// Explosion; `scale` is the model's type-dependent factor S (1 for most bodies)
+    Explosion { scale: f64 },
+    /// Hypervelocity impact of a projectile on the parent (masses in kg, speed in km/s)
+    Collision { target_mass_kg: f64, projectile_mass_kg: f64, impact_speed_km_s: f64 },
+}
+
+impl BreakupEvent {
+    /// Whether a collision destroys the target (energy above 40 J per gram of target)
+    pub fn catastrophic(&self) -> bool {
+        match *self {
+            BreakupEvent::Explosion { .. } => true,
+            BreakupEvent::Collision { target_mass_kg, projectile_mass_kg, impact_speed_km_s } => {
+                // 0.5 m v^2 / M with v in m/s and M in g
+                500.0 * projectile_mass_kg * impact_speed_km_s.powi(2) / target_mass_kg >= CATASTROPHIC_ENERGY_J_G
+            }
+        }
+    }
+
+    /// Coefficient and exponent of the cumulative size law N(>Lc) = k Lc^-b (Lc in m)
+    fn size_law(&self) -> (f64, f64) {
+        match *self {
+            BreakupEvent::Explosion { scale } => (6.0 * scale, 1.6),
+            BreakupEvent::Collision { target_mass_kg, projectile_mass_kg, impact_speed_km_s } => {
+                let mass = if self.catastrophic() {
+                    target_mass_kg + projectile_mass_kg
+                } else {
+                    projectile_mass_kg * impact_speed_km_s.powi(2)
+                };
+                (0.1 * mass.powf(0.75), 1.71)
+            }
+        }
+    }
+
+    /// Mean and standard deviation of log10 of the ejection speed (m/s) at log10(A/M)
+    fn delta_v_distribution(&self, chi: f64) -> (f64, f64) {
+        match self {
+            BreakupEvent::Explosion { .. } => (0.2 * chi + 1.85, 0.4),
+            BreakupEvent::Collision { .. } => (0.9 * chi + 2.9, 0.4),
+        }
+    }
+
+    fn validate(&self) -> Result<(), OrbitError> {
+        let valid = match *self {
+            BreakupEvent::Explosion { scale } => scale > 0.0 && scale.is_finite(),
+            BreakupEvent::Collision { target_mass_kg, projectile_mass_kg, impact_speed_km_s } => {
+                [target_mass_kg, projectile_mass_kg, impact_speed_km_s].iter().all(|x| *x > 0.0 && x.is_finite())
+            }
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(OrbitError::InvalidInput(
+                "Breakup scale, masses and impact speed must be positive numbers".to_string(),
+            ))
+        }
+    }
+}
+
+/// One fragment: its size, area-to-mass ratio and ejection velocity
+#[derive(Clone, Debug, PartialEq)]
+pub struct Fragment {
+    pub characteristic_length_m: f64,
+    pub area_to_mass: f64, // m^2/kg
+    pub area_m2: f64,      // average cross-section
+    pub mass_kg: f64,
+    pub delta_v: Vector3<f64>, // km/s, added to the parent velocity
+}
+
+/// Piecewise-linear function of chi: `low` up to `x0`, `high` from `x1`, linear between
+fn ramp(chi: f64, x0: f64, low: f64, x1: f64, high: f64) -> f64 {
+    if chi <= x0 {
+        low
+    } else if chi >= x1 {
+        high
+    } else {
+        low + (high - low) * (chi - x0) / (x1 - x0)
+    }
+}
+
+/// log10(A/M) of a fragment with log10(Lc) = `lambda`
+fn log_area_to_mass(rng: &mut SplitMix64, parent: ParentType, lambda: f64, large: bool) -> f64 {
+    if !large {
+        let mean = ramp(lambda, -1.75, -0.3, -1.25, -1.0);
+        let sigma = if lambda <= -3.5 { 0.2 } else { 0.2 + 0.1333 * (lambda + 3.5) };
+        return mean + sigma * rng.normal();
+    }
+    let (alpha, mean_1, sigma_1, mean_2, sigma_2) = match parent {
+        ParentType::Spacecraft => (
+            ramp(lambda, -1.95, 0.0, 0.55, 1.0),
+            ramp(lambda, -1.1, -0.6, 0.0, -0.95),
+            ramp(lambda, -1.3, 0.1, -0.3, 0.3),
+            ramp(lambda, -0.7, -1.2, -0.1, -2.0),
+            ramp(lambda, -0.5, 0.5, -0.3, 0.3),
+        ),
+        ParentType::RocketBody => (
+            ramp(lambda, -1.4, 1.0, 0.0, 0.5),
+            ramp(lambda, -0.5, -0.45, 0.0, -0.9),
+            0.55,
+            -0.9,
+            ramp(lambda, -1.0, 0.28, 0.1, 0.1),
+        ),
+    };
+    if rng.next_f64() <= alpha {
+        mean_1 + sigma_1 * rng.normal()
+    } else {
+        mean_2 + sigma_2 * rng.normal()
+    }
+}
+
+/// Average cross-sectional area (m^2) of a fragment of characteristic length `lc` (m)
+fn area(lc: f64) -> f64 {
+    if lc < 0.00167 {
+        0.540424 * lc * lc
+    } else {
+        0.556945 * lc.powf(2.0047077)
+    }
+}
+
+/// Draw the fragments of a breakup down to `min_length_m`
+///
+/// The count is the size law between `min_length_m` and the largest
+/// expected fragment, rounded at random so its mean is exact. The same
+/// seed always gives the same fragments.
+pub fn generate_fragments(
+    event: &BreakupEvent,
+    parent: ParentType,
+    min_length_m: f64,
+    seed: u64,
+) -> Result<Vec<Fragment>, OrbitError> {
+    event.validate()?;
+    if !(min_length_m > 0.0 && min_length_m.is_finite()) {
+        return Err(OrbitError::InvalidInput("min_length_m must be a positive length".to_string()));
+    }
+    let (k, b) = event.size_law();
+    let max_length_m = k.powf(1.0 / b);
+    if min_length_m >= max_length_m {
+        return Ok(Vec::new());
+    }
+    let expected = k * min_length_m.powf(-b) - 1.0;
+    if expected > MAX_FRAGMENTS {
+        return Err(OrbitError::InvalidInput(format!(
+            "Breakup would produce about {:.0} fragments; raise min_length_m",
+            expected
+        )));
+    }
+
+    let mut rng = SplitMix64::new(seed, 0);
+    let count = expected.floor() as usize + usize::from(rng.next_f64() <= expected.fract());
+    let (low, high) = (min_length_m.powf(-b), max_length_m.powf(-b));
+    Ok((0..count)
+        .map(|_| {
+            // inverse transform of the power law truncated to [min, max]
+            let lc = (low - rng.next_f64() * (low - high)).powf(-1.0 / b);
+            let lambda = lc.log10();
+            let bridge = (lc - SMALL_FRAGMENT_M) / (LARGE_FRAGMENT_M - SMALL_FRAGMENT_M);
+            let large = rng.next_f64() <= bridge;
+            let chi = log_area_to_mass(&mut rng, parent, lambda, large);
+            let (mean, sigma) = event.delta_v_distribution(chi);
+            let speed_km_s = 10f64.powf(mean + sigma * rng.normal()) * 1.0e-3;
+            let z = rng.uniform(-1.0, 1.0);
+            let phi = rng.uniform(0.0, 2.0 * std::f64::consts::PI);
+            let horizontal = (1.0 - z * z).sqrt();
+            let area_to_mass = 10f64.powf(chi);
+            let area_m2 = area(lc);
+            Fragment {
+                characteristic_length_m: lc,
+                area_to_mass,
+                area_m2,
+                mass_kg: area_m2 / area_to_mass,
+                delta_v: Vector3::new(horizontal * phi.cos(), horizontal * phi.sin(), z) * speed_km_s,
+            }
+        })
+        .collect())
+}
+
+/// Synthetic fragment cloud from the breakup of a satellite
+///
+/// `event` is "explosion" or "collision"; a collision needs the parent's
+/// `mass_kg`, the `projectile_mass_kg` and the `impact_speed_km_s`, and is
+/// catastrophic above 40 J per gram of parent. `parent_type` ("spacecraft"
+/// or "rocket body") defaults from the parent's object type. Fragments down
+/// to `min_length_m` get consecutive NORAD ids from `first_id`, the parent's
+/// epoch and position, and its velocity plus their ejection velocity, ready
+/// to screen against other assets.
+///
+/// Returns a dict with `satellites` (a list of `Satellite`) and the columns
+/// `characteristic_length_m`, `area_to_mass` (m^2/kg), `mass_kg` and
+/// `delta_v` (fragments x 3, km/s).
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(signature = (
+    parent,
+    event = "explosion",
+    mass_kg = None,
+    projectile_mass_kg = None,
+    impact_speed_km_s = None,
+    parent_type = None,
+    min_length_m = 0.1,
+    scale = 1.0,
+    first_id = 900000,
+    seed = 0
+))]
+#[allow(clippy::too_many_arguments)]
+pub fn breakup(
+    py: Python<'_>,
+    parent: &Satellite,
+    event: &str,
+    mass_kg: Option<f64>,
+    projectile_mass_kg: Option<f64>,
+    impact_speed_km_s: Option<f64>,
+    parent_type: Option<&str>,
+    min_length_m: f64,
+    scale: f64,
+    first_id: i32,
+    seed: u64,
+) -> PyResult<PyObject> {
+    let event = match event.trim().to_ascii_lowercase().as_str() {
+        "explosion" => BreakupEvent::Explosion { scale },
+        "collision" => match (mass_kg, projectile_mass_kg, impact_speed_km_s) {
+            (Some(target_mass_kg), Some(projectile_mass_kg), Some(impact_speed_km_s)) => {
+                BreakupEvent::Collision { target_mass_kg, projectile_mass_kg, impact_speed_km_s }
+            }
+            _ => {
+                return Err(pyo3::exceptions::PyValueError::new_err(
+                    "A collision needs mass_kg, projectile_mass_kg and impact_speed_km_s",
+                ))
+            }
+        },
+        other => {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Unknown breakup event '{}' (expected explosion or collision)",
+                other
+            )))
+        }
+    };
+    let parent_type = match parent_type {
+        Some(name) => name.parse().map_err(pyo3::exceptions::PyValueError::new_err)?,
+        None if parent.object_type == Some(ObjectType::RocketBody) => ParentType::RocketBody,
+        None => ParentType::Spacecraft,
+    };
+    let fragments = py.allow_threads(|| generate_fragments(&event, parent_type, min_length_m, seed))?;
+
+    let satellites: Vec<Satellite> = fragments
+        .iter()
+        .zip(first_id..)
+        .map(|(fragment, norad_id)| {
+            let velocity = parent.v() + fragment.delta_v;
+            let mut sat = Satellite::from_state(norad_id, parent.position, velocity.into(), parent.epoch);
+            sat.name = parent.name.as_ref().map(|name| format!("{} DEB", name));
+            sat.object_type = Some(ObjectType::Debris);
+            sat.hard_body_radius = Some(0.5e-3 * fragment.characteristic_length_m);
+            sat
+        })
+        .collect();
+    let column = |value: fn(&Fragment) -> f64| Column::float64(fragments.iter().map(value).collect());
+    let delta_v: Vec<f64> = fragments.iter().flat_map(|f| f.delta_v.iter().copied()).collect();
+
+    let table = pyo3::types::PyDict::new(py);
+    table.set_item("satellites", satellites.into_py(py))?;
+    table.set_item("characteristic_length_m", Py::new(py, column(|f| f.characteristic_length_m))?)?;
+    table.set_item("area_to_mass", Py::new(py, column(|f| f.area_to_mass))?)?;
+    table.set_item("mass_kg", Py::new(py, column(|f| f.mass_kg))?)?;
+    table.set_item("delta_v", Py::new(py, Column::matrix(delta_v, fragments.len(), 3))?)?;
+    Ok(table.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_explosion_follows_the_size_law() {
+        let event = BreakupEvent::Explosion { scale: 1.0 };
+        let fragments = generate_fragments(&event, ParentType::RocketBody, 0.1, 7).unwrap();
+        // N(>Lc) = 6 Lc^-1.6 above 10 cm, less the one fragment expected above the largest size
+        let expected = 6.0 * 0.1f64.powf(-1.6) - 1.0;
+        assert!((fragments.len() as f64 - expected).abs() < 1.0, "{}", fragments.len());
+        assert_eq!(fragments, generate_fragments(&event, ParentType::RocketBody, 0.1, 7).unwrap());
+
+        let largest = 6f64.powf(1.0 / 1.6);
+        let above_20cm = fragments.iter().filter(|f| f.characteristic_length_m > 0.2).count() as f64;
+        assert!((above_20cm - (6.0 * 0.2f64.powf(-1.6) - 1.0)).abs() < 4.0 * (6.0 * 0.2f64.powf(-1.6)).sqrt());
+        for f in &fragments {
+            assert!(f.characteristic_length_m >= 0.1 && f.characteristic_length_m <= largest);
+            assert!((f.mass_kg * f.area_to_mass - f.area_m2).abs() < 1e-12 * f.area_m2.max(1.0));
+            // explosion ejection speeds are tens of m/s, rarely above 1 km/s
+            assert!(f.delta_v.norm() < 2.0);
+        }
+        let median_dv = {
+            let mut speeds: Vec<f64> = fragments.iter().map(|f| f.delta_v.norm()).collect();
+            speeds.sort_by(f64::total_cmp);
+            speeds[speeds.len() / 2]
+        };
+        assert!(median_dv > 0.01 && median_dv < 0.2, "{}", median_dv);
+    }
+
+    #[test]
+    fn test_collision_energy_threshold() {
+        // 10 kg at 10 km/s destroys a 1000 kg satellite (500 J/g); 10 g does not (0.5 J/g)
+        let catastrophic = BreakupEvent::Collision { target_mass_kg: 1000.0, projectile_mass_kg: 10.0, impact_speed_km_s: 10.0 };
+        let cratering = BreakupEvent::Collision { target_mass_kg: 1000.0, projectile_mass_kg: 0.01, impact_speed_km_s: 10.0 };
+        assert!(catastrophic.catastrophic() && !cratering.catastrophic());
+
+        let debris = generate_fragments(&catastrophic, ParentType::Spacecraft, 0.1, 1).unwrap();
+        let expected = 0.1 * 1010f64.powf(0.75) * 0.1f64.powf(-1.71) - 1.0;
+        assert!((debris.len() as f64 - expected).abs() < 1.0);
+        // a cratering impact ejects the equivalent of 0.01 kg x (10 km/s)^2 = 1 kg, nothing above 26 cm
+        let craters = generate_fragments(&cratering, ParentType::Spacecraft, 0.1, 1).unwrap();
+        assert!((craters.len() as f64 - (0.1 * 0.1f64.powf(-1.71) - 1.0)).abs() < 1.0);
+        assert!(generate_fragments(&cratering, ParentType::Spacecraft, 0.3, 1).unwrap().is_empty());
+
+        assert!(generate_fragments(&catastrophic, ParentType::Spacecraft, 1e-4, 1).is_err());
+        let invalid = BreakupEvent::Collision { target_mass_kg: 0.0, projectile_mass_kg: 1.0, impact_speed_km_s: 1.0 };
+        assert!(generate_fragments(&invalid, ParentType::Spacecraft, 0.1, 1).is_err());
+    }
+}
@@ -0,0 +1,703 @@
+//! Satellite catalogues and primary-versus-catalog screening
+//!
+//! A `Catalog` keeps validated satellites on the Rust side, indexed by
+//! NORAD id, so repeated screens do not re-extract Python lists.
+//!
+//! Operators usually protect a few assets rather than screening every
+//! catalogue object against every other. Only primary/secondary pairs are
+//! evaluated: the grid is built over the catalogue once and each primary
+//! queries its neighbourhood, so the cost scales with the number of
+//! primaries instead of the square of the catalogue size.
+//!
+//! `Catalog.diff` compares two releases: objects that appeared or
+//! disappeared, and objects whose semi-major axis, eccentricity or
+//! inclination moved beyond thresholds. The older state is first carried
+//! to the newer epoch under J2, so ordinary orbital motion and the
+//! short-period terms do not register as changes.
+
+use nalgebra::{Vector3, Vector6};
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use rayon::prelude::*;
+use std::collections::HashMap;
+
+use crate::constants::EARTH_RADIUS_KM;
+use crate::elements;
+use crate::error::OrbitError;
+use crate::metadata::{ConstellationPairs, Regime};
+use crate::parallel;
+use crate::propagation::{self, ForceModel, Integrator};
+use crate::screening::PairSelection;
+use crate::sieve;
+use crate::spatial::UniformGrid;
+#[cfg(feature = "python")]
+use crate::volume;
+use crate::volume::ScreeningVolume;
+use crate::{check_common_epoch, satellite_conjunction, sort_conjunctions, Conjunction, Satellite, SortOrder};
+#[cfg(feature = "python")]
+use crate::{check_states, screen_satellites, Precision, ResultLimits, ScreenOptions};
+
+fn band(sat: &Satellite) -> (f64, f64) {
+    sieve::perigee_apogee(&sat.r(), &sat.v())
+}
+
+/// Index pairs `(primary, catalog)` inside the volume padded by both hard-body radii
+///
+/// Catalogue entries sharing a primary's NORAD id are the primary itself
+/// and are skipped. Each primary's partners are returned in catalogue order.
+pub fn primary_pairs(
+    primaries: &[Satellite],
+    catalog: &[Satellite],
+    volume: impl Into<ScreeningVolume>,
+    apogee_perigee_filter: bool,
+) -> Vec<(usize, usize)> {
+    let volume = volume.into();
+    if primaries.is_empty() || catalog.is_empty() || volume.is_empty() {
+        return Vec::new();
+    }
+    let max_radius = |sats: &[Satellite]| sats.iter().map(Satellite::collision_radius).fold(0.0, f64::max);
+    let cell_size = volume.reach() + volume.pad(max_radius(primaries), max_radius(catalog));
+
+    parallel::install(|| {
+        let positions: Vec<[f64; 3]> = catalog.iter().map(|sat| sat.position).collect();
+        let grid = UniformGrid::build(&positions, cell_size);
+        let bands: Option<Vec<(f64, f64)>> = apogee_perigee_filter.then(|| catalog.par_iter().map(band).collect());
+
+        primaries
+            .par_iter()
+            .enumerate()
+            .flat_map(|(p, primary)| {
+                let primary_band = bands.as_ref().map(|_| band(primary));
+                let origin = Vector3::from(primary.position);
+                let velocity = primary.v();
+                let mut partners: Vec<usize> = grid
+                    .neighbors(&primary.position)
+                    .into_iter()
+                    .filter(|&j| catalog[j].norad_id != primary.norad_id)
+                    .filter(|&j| {
+                        let pad = volume.pad(primary.collision_radius(), catalog[j].collision_radius());
+                        let bands_ok = match (&bands, primary_band) {
+                            (Some(bands), Some(own)) => sieve::apsis_bands_overlap(own, bands[j], volume.reach() + pad),
+                            _ => true,
+                        };
+                        bands_ok && volume.contains((&origin, &velocity), &Vector3::from(positions[j]), pad)
+                    })
+                    .collect();
+                partners.sort_unstable();
+                partners.into_iter().map(|j| (p, j)).collect::<Vec<_>>()
+            })
+            .collect()
+    })
+}
+
+/// Screen protected primaries against a catalogue at a common epoch
+///
+/// Only primary/catalogue pairs are evaluated; object 1 of every event is
+/// the primary. Thresholds and Pc account for hard-body radii as in
+/// `find_conjunctions`; a `ScreeningVolume` threshold is centred on each
+/// primary. `sort_by` is "ids" (default), "distance", "tca" or "none";
+/// `validate` rejects NaN/infinite or zero-norm states; `constellation_pairs`,
+/// `exclude_pairs` and `watch_list` select pairs as in `find_conjunctions`.
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(signature = (
+    primaries,
+    catalog,
+    threshold_km,
+    apogee_perigee_filter = false,
+    sort_by = "ids",
+    validate = true,
+    constellation_pairs = "include",
+    exclude_pairs = None,
+    watch_list = None
+))]
+#[allow(clippy::too_many_arguments)]
+pub fn screen_against_catalog(
+    py: Python<'_>,
+    primaries: Vec<Satellite>,
+    catalog: Vec<Satellite>,
+    threshold_km: &PyAny,
+    apogee_perigee_filter: bool,
+    sort_by: &str,
+    validate: bool,
+    constellation_pairs: &str,
+    exclude_pairs: Option<Vec<(i32, i32)>>,
+    watch_list: Option<Vec<i32>>,
+) -> PyResult<Vec<Conjunction>> {
+    let pairs: ConstellationPairs = constellation_pairs.parse().map_err(pyo3::exceptions::PyValueError::new_err)?;
+    let selection = PairSelection::new(exclude_pairs.unwrap_or_default(), watch_list);
+    if validate {
+        check_states(&primaries)?;
+        check_states(&catalog)?;
+    }
+    let order: SortOrder = sort_by.parse().map_err(pyo3::exceptions::PyValueError::new_err)?;
+    let volume = volume::extract_threshold(threshold_km)?;
+    py.allow_threads(|| {
+        Ok(screen_primaries(&primaries, &catalog, volume, apogee_perigee_filter, order, pairs, &selection)?)
+    })
+}
+
+/// Screen protected primaries against a catalogue at a common epoch; object 1 of every event is the primary
+pub fn screen_primaries(
+    primaries: &[Satellite],
+    catalog: &[Satellite],
+    volume: ScreeningVolume,
+    apogee_perigee_filter: bool,
+    order: SortOrder,
+    constellation_pairs: ConstellationPairs,
+    selection: &PairSelection,
+) -> Result<Vec<Conjunction>, OrbitError> {
+    let everything: Vec<Satellite> = primaries.iter().chain(catalog).cloned().collect();
+    check_common_epoch(&everything)?;
+
+    let pairs = primary_pairs(primaries, catalog, volume, apogee_perigee_filter);
+    let mut conjunctions: Vec<Conjunction> = parallel::install(|| {
+        pairs
+            .into_par_iter()
+            .filter(|&(p, j)| selection.keeps(primaries[p].norad_id, catalog[j].norad_id))
+            .filter(|&(p, j)| constellation_pairs.keeps(primaries[p].same_constellation(&catalog[j])))
+            .map(|(p, j)| satellite_conjunction(&primaries[p], &catalog[j]))
+            .collect()
+    });
+    sort_conjunctions(&mut conjunctions, order);
+    Ok(conjunctions)
+}
+
+/// Inclination of the osculating orbit (degrees)
+fn inclination_deg(sat: &Satellite) -> f64 {
+    let h = sat.r().cross(&sat.v());
+    (h.z / h.norm()).clamp(-1.0, 1.0).acos().to_degrees()
+}
+
+/// Satellites indexed by NORAD id, in insertion order
+#[cfg_attr(feature = "python", pyclass(module = "orbit_core"))]
+#[derive(Clone, Default)]
+pub struct Catalog {
+    satellites: Vec<Satellite>,
+    index: HashMap<i32, usize>,
+}
+
+impl Catalog {
+    /// Catalogue from satellites with distinct NORAD ids
+    pub fn from_satellites(satellites: Vec<Satellite>) -> Result<Self, String> {
+        let mut catalog = Catalog::default();
+        for sat in satellites {
+            catalog.insert(sat)?;
+        }
+        Ok(catalog)
+    }
+
+    /// Add a satellite; its NORAD id must be new
+    pub fn insert(&mut self, sat: Satellite) -> Result<(), String> {
+        if self.index.contains_key(&sat.norad_id) {
+            return Err(format!("NORAD id {} is already in the catalog", sat.norad_id));
+        }
+        self.index.insert(sat.norad_id, self.satellites.len());
+        self.satellites.push(sat);
+        Ok(())
+    }
+
+    /// Replace the satellite with the same NORAD id; false if there is none
+    pub fn update(&mut self, sat: Satellite) -> bool {
+        match self.index.get(&sat.norad_id) {
+            Some(&i) => {
+                self.satellites[i] = sat;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Remove and return a satellite by NORAD id
+    pub fn remove(&mut self, norad_id: i32) -> Option<Satellite> {
+        let i = self.index.remove(&norad_id)?;
+        let removed = self.satellites.remove(i);
+        for sat in &self.satellites[i..] {
+            if let Some(position) = self.index.get_mut(&sat.norad_id) {
+                *position -= 1;
+            }
+        }
+        Some(removed)
+    }
+
+    pub fn satellites(&self) -> &[Satellite] {
+        &self.satellites
+    }
+
+    pub fn get(&self, norad_id: i32) -> Option<&Satellite> {
+        self.index.get(&norad_id).map(|&i| &self.satellites[i])
+    }
+
+    pub fn contains(&self, norad_id: i32) -> bool {
+        self.index.contains_key(&norad_id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.satellites.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.satellites.is_empty()
+    }
+
+    /// NORAD ids in catalogue order
+    pub fn ids(&self) -> Vec<i32> {
+        self.satellites.iter().map(|sat| sat.norad_id).collect()
+    }
+
+    /// Sub-catalogue of objects matching every given criterion
+    ///
+    /// Altitude limits (km) select orbits whose perigee-to-apogee band
+    /// overlaps the range; inclinations are in degrees.
+    pub fn filter(
+        &self,
+        (min_altitude_km, max_altitude_km): (Option<f64>, Option<f64>),
+        (min_inclination_deg, max_inclination_deg): (Option<f64>, Option<f64>),
+        regime: Option<Regime>,
+    ) -> Catalog {
+        let keep = |sat: &&Satellite| {
+            let (perigee, apogee) = sat.perigee_apogee();
+            let inclination = inclination_deg(sat);
+            min_altitude_km.is_none_or(|min| apogee - EARTH_RADIUS_KM >= min)
+                && max_altitude_km.is_none_or(|max| perigee - EARTH_RADIUS_KM <= max)
+                && min_inclination_deg.is_none_or(|min| inclination >= min)
+                && max_inclination_deg.is_none_or(|max| inclination <= max)
+                && regime.is_none_or(|regime| Regime::classify(perigee, apogee) == regime)
+        };
+        let selected = self.satellites.iter().filter(keep).cloned().collect();
+        Catalog::from_satellites(selected).expect("ids are unique within a catalog")
+    }
+}
+
+/// Initial step when carrying an older state to a newer epoch (seconds)
+const DIFF_INITIAL_STEP_S: f64 = 60.0;
+
+/// Limits beyond which an object counts as changed between releases
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DiffThresholds {
+    pub sma_km: f64,
+    pub eccentricity: f64,
+    pub inclination_deg: f64,
+}
+
+impl Default for DiffThresholds {
+    fn default() -> Self {
+        DiffThresholds { sma_km: 0.5, eccentricity: 5.0e-4, inclination_deg: 0.01 }
+    }
+}
+
+/// Element changes of one object between two releases (newer minus older)
+#[cfg_attr(feature = "python", pyclass(get_all))]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ElementChange {
+    pub norad_id: i32,
+    pub sma_change_km: f64,
+    pub eccentricity_change: f64,
+    pub inclination_change_deg: f64,
+    /// Distance between the older state carried to the newer epoch and the newer state
+    pub position_change_km: f64,
+    /// Quantities beyond their threshold: "sma", "eccentricity", "inclination"
+    pub exceeded: Vec<&'static str>,
+}
+
+/// Differences between an older and a newer catalogue
+#[cfg_attr(feature = "python", pyclass(get_all))]
+#[derive(Clone, Debug, Default)]
+pub struct CatalogDiff {
+    /// NORAD ids only in the newer catalogue, in its order
+    pub added: Vec<i32>,
+    /// NORAD ids only in the older catalogue, in its order
+    pub removed: Vec<i32>,
+    /// Objects in both whose elements changed beyond the thresholds
+    pub changed: Vec<ElementChange>,
+    /// Number of objects in both within the thresholds
+    pub unchanged: usize,
+}
+
+/// State of `sat` at the epoch of `target` under J2 (unchanged without both epochs)
+fn carried_to(sat: &Satellite, target: &Satellite) -> Option<(Vector3<f64>, Vector3<f64>)> {
+    let dt = match (&sat.epoch, &target.epoch) {
+        (Some(from), Some(to)) => to.seconds_since(from),
+        _ => 0.0,
+    };
+    if dt == 0.0 {
+        return Some((sat.r(), sat.v()));
+    }
+    let (r, v) = (sat.r(), sat.v());
+    let y0 = Vector6::new(r.x, r.y, r.z, v.x, v.y, v.z);
+    let integrator = Integrator::DormandPrince { initial_step: DIFF_INITIAL_STEP_S };
+    let y = propagation::integrate(&y0, dt, ForceModel::J2, integrator)?;
+    Some((y.fixed_rows::<3>(0).into_owned(), y.fixed_rows::<3>(3).into_owned()))
+}
+
+/// Element change of one object, or None when it is within `thresholds`
+///
+/// An object whose elements cannot be compared (propagation failure,
+/// unbound orbit) is always reported, with NaN changes.
+fn element_change(older: &Satellite, newer: &Satellite, thresholds: &DiffThresholds) -> Option<ElementChange> {
+    let compared = carried_to(older, newer).and_then(|(r, v)| {
+        let before = elements::from_state(&r, &v)?;
+        let after = elements::from_state(&newer.r(), &newer.v())?;
+        Some((before, after, (newer.r() - r).norm()))
+    });
+    let Some((before, after, distance)) = compared else {
+        return Some(ElementChange {
+            norad_id: newer.norad_id,
+            sma_change_km: f64::NAN,
+            eccentricity_change: f64::NAN,
+            inclination_change_deg: f64::NAN,
+            position_change_km: f64::NAN,
+            exceeded: vec!["sma", "eccentricity", "inclination"],
+        });
+    };
+    let da = after.semi_major_axis_km - before.semi_major_axis_km;
+    let de = after.eccentricity - before.eccentricity;
+    let di = after.inclination_deg - before.inclination_deg;
+    let limits = [
+        ("sma", da, thresholds.sma_km),
+        ("eccentricity", de, thresholds.eccentricity),
+        ("inclination", di, thresholds.inclination_deg),
+    ];
+    let exceeded: Vec<&'static str> = limits
+        .into_iter()
+        .filter(|(_, change, limit)| change.abs() > *limit)
+        .map(|(name, _, _)| name)
+        .collect();
+    (!exceeded.is_empty()).then_some(ElementChange {
+        norad_id: newer.norad_id,
+        sma_change_km: da,
+        eccentricity_change: de,
+        inclination_change_deg: di,
+        position_change_km: distance,
+        exceeded,
+    })
+}
+
+/// Compare an older catalogue with a newer one
+pub fn diff_catalogs(older: &Catalog, newer: &Catalog, thresholds: &DiffThresholds) -> CatalogDiff {
+    let added = newer.satellites.iter().map(|sat| sat.norad_id).filter(|id| older.get(*id).is_none()).collect();
+    let removed = older.satellites.iter().map(|sat| sat.norad_id).filter(|id| newer.get(*id).is_none()).collect();
+    let common: Vec<(&Satellite, &Satellite)> =
+        newer.satellites.iter().filter_map(|sat| Some((older.get(sat.norad_id)?, sat))).collect();
+    let changed: Vec<ElementChange> = parallel::install(|| {
+        common.par_iter().filter_map(|(before, after)| element_change(before, after, thresholds)).collect()
+    });
+    CatalogDiff { added, removed, unchanged: common.len() - changed.len(), changed }
+}
+
+#[cfg(feature = "python")]
+fn missing(norad_id: i32) -> PyErr {
+    pyo3::exceptions::PyKeyError::new_err(format!("NORAD id {} is not in the catalog", norad_id))
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl Catalog {
+    #[new]
+    #[pyo3(signature = (satellites = None))]
+    fn new(satellites: Option<Vec<Satellite>>) -> PyResult<Self> {
+        Catalog::from_satellites(satellites.unwrap_or_default()).map_err(pyo3::exceptions::PyValueError::new_err)
+    }
+
+    /// Add a satellite; its NORAD id must be new
+    fn add(&mut self, satellite: Satellite) -> PyResult<()> {
+        self.insert(satellite).map_err(pyo3::exceptions::PyValueError::new_err)
+    }
+
+    /// Replace the satellite with the same NORAD id
+    #[pyo3(name = "update")]
+    fn py_update(&mut self, satellite: Satellite) -> PyResult<()> {
+        let norad_id = satellite.norad_id;
+        if self.update(satellite) {
+            Ok(())
+        } else {
+            Err(missing(norad_id))
+        }
+    }
+
+    /// Remove and return a satellite by NORAD id
+    #[pyo3(name = "remove")]
+    fn py_remove(&mut self, norad_id: i32) -> PyResult<Satellite> {
+        self.remove(norad_id).ok_or_else(|| missing(norad_id))
+    }
+
+    /// Satellite by NORAD id, or None
+    #[pyo3(name = "get")]
+    fn py_get(&self, norad_id: i32) -> Option<Satellite> {
+        self.get(norad_id).cloned()
+    }
+
+    /// NORAD ids in catalogue order
+    #[pyo3(name = "ids")]
+    fn py_ids(&self) -> Vec<i32> {
+        self.ids()
+    }
+
+    /// Copy of the satellites as a list
+    #[pyo3(name = "satellites")]
+    fn py_satellites(&self) -> Vec<Satellite> {
+        self.satellites.clone()
+    }
+
+    /// Sub-catalogue of objects matching every given criterion
+    ///
+    /// Altitude limits (km) select orbits whose perigee-to-apogee band
+    /// overlaps the range; inclinations are in degrees; `regime` is "LEO",
+    /// "MEO", "GEO", "HEO" or "OTHER".
+    #[pyo3(signature = (
+        min_altitude_km = None,
+        max_altitude_km = None,
+        min_inclination_deg = None,
+        max_inclination_deg = None,
+        regime = None
+    ))]
+    #[pyo3(name = "filter")]
+    fn py_filter(
+        &self,
+        min_altitude_km: Option<f64>,
+        max_altitude_km: Option<f64>,
+        min_inclination_deg: Option<f64>,
+        max_inclination_deg: Option<f64>,
+        regime: Option<&str>,
+    ) -> PyResult<Catalog> {
+        let regime: Option<Regime> =
+            regime.map(str::parse).transpose().map_err(pyo3::exceptions::PyValueError::new_err)?;
+        Ok(self.filter((min_altitude_km, max_altitude_km), (min_inclination_deg, max_inclination_deg), regime))
+    }
+
+    /// All-vs-all screening of the catalogue (see `find_conjunctions`)
+    #[pyo3(signature = (
+        threshold_km,
+        apogee_perigee_filter = false,
+        sort_by = "ids",
+        precision = "f64",
+        validate = true,
+        constellation_pairs = "include",
+        exclude_pairs = None,
+        watch_list = None,
+        max_results = None,
+        top_k_by_distance = None,
+        min_separation_km = None
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn find_conjunctions(
+        &self,
+        py: Python<'_>,
+        threshold_km: &PyAny,
+        apogee_perigee_filter: bool,
+        sort_by: &str,
+        precision: &str,
+        validate: bool,
+        constellation_pairs: &str,
+        exclude_pairs: Option<Vec<(i32, i32)>>,
+        watch_list: Option<Vec<i32>>,
+        max_results: Option<usize>,
+        top_k_by_distance: Option<usize>,
+        min_separation_km: Option<f64>,
+    ) -> PyResult<Vec<Conjunction>> {
+        let precision: Precision = precision.parse().map_err(pyo3::exceptions::PyValueError::new_err)?;
+        let pairs: ConstellationPairs = constellation_pairs.parse().map_err(pyo3::exceptions::PyValueError::new_err)?;
+        let selection = PairSelection::new(exclude_pairs.unwrap_or_default(), watch_list);
+        let limits = ResultLimits { max_results, top_k_by_distance, min_separation_km };
+        limits.validate().map_err(pyo3::exceptions::PyValueError::new_err)?;
+        if validate {
+            check_states(&self.satellites)?;
+        }
+        let order: SortOrder = sort_by.parse().map_err(pyo3::exceptions::PyValueError::new_err)?;
+        let volume = volume::extract_threshold(threshold_km)?;
+        py.allow_threads(|| {
+            let options = ScreenOptions { apogee_perigee_filter, precision, order, constellation_pairs: pairs, selection, limits };
+            Ok(screen_satellites(&self.satellites, volume, &options)?)
+        })
+    }
+
+    /// Screen primaries against the catalogue (see `screen_against_catalog`)
+    #[pyo3(signature = (
+        primaries,
+        threshold_km,
+        apogee_perigee_filter = false,
+        sort_by = "ids",
+        validate = true,
+        constellation_pairs = "include",
+        exclude_pairs = None,
+        watch_list = None
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn screen(
+        &self,
+        py: Python<'_>,
+        primaries: Vec<Satellite>,
+        threshold_km: &PyAny,
+        apogee_perigee_filter: bool,
+        sort_by: &str,
+        validate: bool,
+        constellation_pairs: &str,
+        exclude_pairs: Option<Vec<(i32, i32)>>,
+        watch_list: Option<Vec<i32>>,
+    ) -> PyResult<Vec<Conjunction>> {
+        let pairs: ConstellationPairs = constellation_pairs.parse().map_err(pyo3::exceptions::PyValueError::new_err)?;
+        let selection = PairSelection::new(exclude_pairs.unwrap_or_default(), watch_list);
+        if validate {
+            check_states(&primaries)?;
+            check_states(&self.satellites)?;
+        }
+        let order: SortOrder = sort_by.parse().map_err(pyo3::exceptions::PyValueError::new_err)?;
+        let volume = volume::extract_threshold(threshold_km)?;
+        py.allow_threads(|| {
+            Ok(screen_primaries(&primaries, &self.satellites, volume, apogee_perigee_filter, order, pairs, &selection)?)
+        })
+    }
+
+    /// Changes from this (older) catalogue to `other` (newer)
+    ///
+    /// Reports NORAD ids `added` and `removed`, and as `changed` every
+    /// common object whose semi-major axis (km), eccentricity or
+    /// inclination (degrees) moved beyond its threshold once the older
+    /// state is propagated (J2) to the newer epoch: possible manoeuvres or
+    /// re-determined orbits.
+    #[pyo3(signature = (other, sma_threshold_km = 0.5, eccentricity_threshold = 5.0e-4, inclination_threshold_deg = 0.01))]
+    fn diff(
+        &self,
+        py: Python<'_>,
+        other: &Catalog,
+        sma_threshold_km: f64,
+        eccentricity_threshold: f64,
+        inclination_threshold_deg: f64,
+    ) -> PyResult<CatalogDiff> {
+        if [sma_threshold_km, eccentricity_threshold, inclination_threshold_deg].iter().any(|x| x.is_nan() || *x < 0.0) {
+            return Err(pyo3::exceptions::PyValueError::new_err("Thresholds must be non-negative"));
+        }
+        let thresholds = DiffThresholds {
+            sma_km: sma_threshold_km,
+            eccentricity: eccentricity_threshold,
+            inclination_deg: inclination_threshold_deg,
+        };
+        Ok(py.allow_threads(|| diff_catalogs(self, other, &thresholds)))
+    }
+
+    fn __getitem__(&self, norad_id: i32) -> PyResult<Satellite> {
+        self.get(norad_id).cloned().ok_or_else(|| missing(norad_id))
+    }
+
+    fn __contains__(&self, norad_id: i32) -> bool {
+        self.contains(norad_id)
+    }
+
+    fn __len__(&self) -> usize {
+        self.len()
+    }
+
+    fn __iter__(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let list = pyo3::types::PyList::new(py, self.satellites.iter().map(|sat| sat.clone().into_py(py)));
+        Ok(list.call_method0("__iter__")?.into())
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Catalog({} satellites)", self.satellites.len())
+    }
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl ElementChange {
+    fn __repr__(&self) -> String {
+        format!(
+            "ElementChange(norad_id={}, da={:.3}km, de={:.2e}, di={:.4}deg, exceeded={:?})",
+            self.norad_id, self.sma_change_km, self.eccentricity_change, self.inclination_change_deg, self.exceeded
+        )
+    }
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl CatalogDiff {
+    fn __repr__(&self) -> String {
+        format!(
+            "CatalogDiff(added={}, removed={}, changed={}, unchanged={})",
+            self.added.len(),
+            self.removed.len(),
+            self.changed.len(),
+            self.unchanged
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::epoch::Epoch;
+
+    fn satellite(id: i32, x: f64, y: f64) -> Satellite {
+        Satellite::from_state(id, [x, y, 0.0], [0.0, 7.5, 0.0], None)
+    }
+
+    #[test]
+    fn test_only_primary_pairs_are_reported() {
+        let primaries = vec![satellite(1, 7000.0, 0.0)];
+        // 2 and 3 are close to each other but only 2 is near the primary
+        let catalog = vec![satellite(1, 7000.0, 0.0), satellite(2, 7000.5, 0.0), satellite(3, 7000.9, 0.0)];
+        let pairs = primary_pairs(&primaries, &catalog, 0.6, false);
+        assert_eq!(pairs, vec![(0, 1)]);
+    }
+
+    #[test]
+    fn test_matches_all_vs_all_subset() {
+        let catalog: Vec<Satellite> =
+            (0..300).map(|id| satellite(id, 7000.0 + 0.37 * id as f64, 0.2 * (id % 7) as f64)).collect();
+        let primaries = vec![catalog[10].clone(), catalog[150].clone()];
+
+        let mut expected = Vec::new();
+        for (p, primary) in primaries.iter().enumerate() {
+            for (j, other) in catalog.iter().enumerate() {
+                if other.norad_id != primary.norad_id && primary.distance_to(other) < 2.0 {
+                    expected.push((p, j));
+                }
+            }
+        }
+        assert!(!expected.is_empty());
+        assert_eq!(primary_pairs(&primaries, &catalog, 2.0, false), expected);
+        assert_eq!(primary_pairs(&primaries, &catalog, 2.0, true), expected);
+    }
+
+    #[test]
+    fn test_catalog_index_survives_removal() {
+        let mut catalog = Catalog::from_satellites((1..=4).map(|id| satellite(id, 7000.0 + id as f64, 0.0)).collect())
+            .unwrap();
+        assert!(Catalog::from_satellites(vec![satellite(1, 7000.0, 0.0), satellite(1, 7001.0, 0.0)]).is_err());
+        assert_eq!(catalog.remove(2).unwrap().norad_id, 2);
+        assert_eq!(catalog.ids(), vec![1, 3, 4]);
+        assert_eq!(catalog.get(4).unwrap().position[0], 7004.0);
+        assert!(catalog.get(2).is_none());
+    }
+
+    #[test]
+    fn test_diff_reports_new_removed_and_changed() {
+        let epoch = Epoch::from_jd_utc(2460000.5);
+        let later = epoch.plus_seconds(3600.0);
+        let circular = |id: i32, speed: f64| Satellite::from_state(id, [7000.0, 0.0, 0.0], [0.0, speed, 1.0], Some(epoch));
+        let older = Catalog::from_satellites(vec![circular(1, 7.45), circular(2, 7.45), circular(3, 7.45)]).unwrap();
+
+        // the same orbits an hour on, except object 2 burned 5 m/s prograde
+        let carried = |sat: &Satellite, dv: f64| {
+            let (r, v) = carried_to(sat, &Satellite { epoch: Some(later), ..sat.clone() }).unwrap();
+            let v = v + v.normalize() * dv;
+            Satellite::from_state(sat.norad_id, r.into(), v.into(), Some(later))
+        };
+        let newer = Catalog::from_satellites(vec![
+            carried(older.get(2).unwrap(), 5e-3),
+            carried(older.get(1).unwrap(), 0.0),
+            circular(4, 7.45),
+        ])
+        .unwrap();
+
+        let diff = diff_catalogs(&older, &newer, &DiffThresholds::default());
+        assert_eq!(diff.added, vec![4]);
+        assert_eq!(diff.removed, vec![3]);
+        assert_eq!(diff.unchanged, 1);
+        assert_eq!(diff.changed.len(), 1);
+        let change = &diff.changed[0];
+        assert_eq!(change.norad_id, 2);
+        assert!(change.exceeded.contains(&"sma"));
+        assert!(change.sma_change_km > 5.0 && change.position_change_km < 1e-6, "{:?}", change);
+    }
+}
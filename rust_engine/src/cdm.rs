@@ -0,0 +1,522 @@
+//! CCSDS Conjunction Data Message (CDM) export
+//!
+//! Follows CCSDS 508.0-B-1. A message is assembled once as ordered
+//! keyword/value fields and rendered either as KVN or XML. Distances are
+//! written in metres as the standard requires; object states stay in km.
+//! Covariances are rotated from the inertial frame of the state into the
+//! object's own RTN frame before writing.
+
+use nalgebra::{Matrix6, Vector3};
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use std::collections::HashMap;
+
+use crate::epoch::{self, MJD_OFFSET};
+#[cfg(feature = "python")]
+use crate::epoch::now_mjd_utc;
+#[cfg(feature = "python")]
+use crate::epoch::{Epoch, TimeScale};
+use crate::frames;
+use crate::Conjunction;
+#[cfg(feature = "python")]
+use crate::Satellite;
+
+const CDM_VERSION: &str = "1.0";
+
+/// Covariance element names in CDM order (lower triangle, row by row)
+#[rustfmt::skip]
+const COVARIANCE_KEYS: [&str; 21] = [
+    "CR_R",
+    "CT_R", "CT_T",
+    "CN_R", "CN_T", "CN_N",
+    "CRDOT_R", "CRDOT_T", "CRDOT_N", "CRDOT_RDOT",
+    "CTDOT_R", "CTDOT_T", "CTDOT_N", "CTDOT_RDOT", "CTDOT_TDOT",
+    "CNDOT_R", "CNDOT_T", "CNDOT_N", "CNDOT_RDOT", "CNDOT_TDOT", "CNDOT_NDOT",
+];
+
+/// Object metadata keywords a caller may override, in CDM order
+const METADATA_KEYS: [&str; 9] = [
+    "OBJECT_DESIGNATOR",
+    "CATALOG_NAME",
+    "OBJECT_NAME",
+    "INTERNATIONAL_DESIGNATOR",
+    "OBJECT_TYPE",
+    "EPHEMERIS_NAME",
+    "COVARIANCE_METHOD",
+    "MANEUVERABLE",
+    "REF_FRAME",
+];
+
+/// One keyword = value [unit] line
+#[derive(Clone, Debug, PartialEq)]
+pub struct CdmField {
+    pub key: String,
+    pub value: String,
+    pub unit: Option<&'static str>,
+}
+
+impl CdmField {
+    fn new(key: &str, value: impl Into<String>) -> Self {
+        CdmField { key: key.to_string(), value: value.into(), unit: None }
+    }
+
+    fn with_unit(key: &str, value: impl Into<String>, unit: &'static str) -> Self {
+        CdmField { key: key.to_string(), value: value.into(), unit: Some(unit) }
+    }
+}
+
+/// Data for one of the two objects in a CDM
+#[derive(Clone, Debug, Default)]
+pub struct CdmObject {
+    pub norad_id: i32,
+    pub state: Option<(Vector3<f64>, Vector3<f64>)>, // km, km/s
+    pub covariance: Option<Matrix6<f64>>,            // inertial, km and km/s
+    pub metadata: HashMap<String, String>,
+}
+
+impl CdmObject {
+    #[cfg(feature = "python")]
+    pub fn from_satellite(satellite: &Satellite) -> Self {
+        let mut metadata = HashMap::new();
+        if let Some(name) = &satellite.name {
+            metadata.insert("OBJECT_NAME".to_string(), name.clone());
+        }
+        if let Some(object_type) = satellite.object_type {
+            metadata.insert("OBJECT_TYPE".to_string(), object_type.as_str().to_string());
+        }
+        if let Some(designator) = &satellite.international_designator {
+            metadata.insert("INTERNATIONAL_DESIGNATOR".to_string(), designator.clone());
+        }
+        CdmObject {
+            norad_id: satellite.norad_id,
+            state: Some((
+                satellite.r(),
+                satellite.v(),
+            )),
+            covariance: satellite.covariance,
+            metadata,
+        }
+    }
+
+    fn metadata_fields(&self, label: &str) -> Vec<CdmField> {
+        let designator = format!("{:05}", self.norad_id);
+        let default = |key: &str| -> String {
+            match key {
+                "OBJECT_DESIGNATOR" => designator.clone(),
+                "CATALOG_NAME" => "SATCAT".to_string(),
+                "OBJECT_NAME" | "INTERNATIONAL_DESIGNATOR" | "OBJECT_TYPE" => "UNKNOWN".to_string(),
+                "EPHEMERIS_NAME" => "NONE".to_string(),
+                "COVARIANCE_METHOD" => "CALCULATED".to_string(),
+                "MANEUVERABLE" => "N/A".to_string(),
+                "REF_FRAME" => "GCRF".to_string(),
+                _ => String::new(),
+            }
+        };
+
+        let mut fields = vec![CdmField::new("OBJECT", label)];
+        fields.extend(METADATA_KEYS.iter().map(|key| {
+            let value = self.metadata.get(*key).cloned().unwrap_or_else(|| default(key));
+            CdmField::new(key, value)
+        }));
+        fields
+    }
+
+    fn state_fields(&self) -> Vec<CdmField> {
+        let Some((r, v)) = &self.state else {
+            return Vec::new();
+        };
+        let mut fields: Vec<CdmField> = ["X", "Y", "Z"]
+            .iter()
+            .zip(r.iter())
+            .map(|(key, value)| CdmField::with_unit(key, format!("{:.6}", value), "km"))
+            .collect();
+        fields.extend(
+            ["X_DOT", "Y_DOT", "Z_DOT"]
+                .iter()
+                .zip(v.iter())
+                .map(|(key, value)| CdmField::with_unit(key, format!("{:.9}", value), "km/s")),
+        );
+        fields
+    }
+
+    /// RTN covariance elements in m and m/s, or nothing without state and covariance
+    fn covariance_fields(&self) -> Vec<CdmField> {
+        let (Some((r, v)), Some(cov)) = (&self.state, &self.covariance) else {
+            return Vec::new();
+        };
+        let Some(rtn) = rtn_covariance(r, v, cov) else {
+            return Vec::new();
+        };
+
+        let mut fields = Vec::with_capacity(COVARIANCE_KEYS.len());
+        let mut keys = COVARIANCE_KEYS.iter();
+        for i in 0..6 {
+            for j in 0..=i {
+                let unit = match (i < 3, j < 3) {
+                    (true, true) => "m**2",
+                    (false, false) => "m**2/s**2",
+                    _ => "m**2/s",
+                };
+                let key = keys.next().expect("21 covariance keys");
+                fields.push(CdmField::with_unit(key, format!("{:.6e}", rtn[(i, j)] * 1.0e6), unit));
+            }
+        }
+        fields
+    }
+}
+
+/// Rotate an inertial 6x6 covariance into the RTN frame of (r, v)
+pub fn rtn_covariance(r: &Vector3<f64>, v: &Vector3<f64>, cov: &Matrix6<f64>) -> Option<Matrix6<f64>> {
+    let basis = frames::rtn_basis(r, v)?;
+    let mut rotation = Matrix6::zeros();
+    for (row, axis) in basis.iter().enumerate() {
+        for col in 0..3 {
+            rotation[(row, col)] = axis[col];
+            rotation[(row + 3, col + 3)] = axis[col];
+        }
+    }
+    Some(rotation * cov * rotation.transpose())
+}
+
+/// A complete CDM ready to be rendered
+#[derive(Clone, Debug)]
+pub struct CdmMessage {
+    pub header: Vec<CdmField>,
+    pub relative: Vec<CdmField>,
+    pub objects: [(Vec<CdmField>, Vec<CdmField>, Vec<CdmField>); 2], // metadata, state, covariance
+}
+
+/// Header fields a caller may set
+#[derive(Clone, Debug)]
+pub struct CdmHeader {
+    pub originator: String,
+    pub message_id: Option<String>,
+    pub creation_mjd_utc: f64,
+}
+
+impl CdmMessage {
+    /// Assemble a CDM; object 1 is the conjunction's primary
+    pub fn build(
+        conjunction: &Conjunction,
+        object1: &CdmObject,
+        object2: &CdmObject,
+        header: &CdmHeader,
+    ) -> Result<Self, String> {
+        let tca_jd = conjunction
+            .tca_jd
+            .ok_or_else(|| "Conjunction has no TCA; screen over a time window first".to_string())?;
+        let tca = epoch::format_iso(tca_jd - MJD_OFFSET);
+        let message_id = header.message_id.clone().unwrap_or_else(|| {
+            format!("{}_{}_{}", conjunction.norad_id_1, conjunction.norad_id_2, tca.replace([':', '-'], ""))
+        });
+
+        let header_fields = vec![
+            CdmField::new("CCSDS_CDM_VERS", CDM_VERSION),
+            CdmField::new("CREATION_DATE", epoch::format_iso(header.creation_mjd_utc)),
+            CdmField::new("ORIGINATOR", header.originator.clone()),
+            CdmField::new("MESSAGE_ID", message_id),
+        ];
+
+        let mut relative = vec![
+            CdmField::new("TCA", tca),
+            CdmField::with_unit("MISS_DISTANCE", format!("{:.3}", conjunction.distance_km * 1.0e3), "m"),
+            CdmField::with_unit(
+                "RELATIVE_SPEED",
+                format!("{:.3}", conjunction.relative_velocity_km_s * 1.0e3),
+                "m/s",
+            ),
+            CdmField::with_unit("RELATIVE_POSITION_R", format!("{:.3}", conjunction.radial_km * 1.0e3), "m"),
+            CdmField::with_unit("RELATIVE_POSITION_T", format!("{:.3}", conjunction.in_track_km * 1.0e3), "m"),
+            CdmField::with_unit("RELATIVE_POSITION_N", format!("{:.3}", conjunction.cross_track_km * 1.0e3), "m"),
+        ];
+        if let (Some((r1, v1)), Some((_, v2))) = (&object1.state, &object2.state) {
+            if let Some(components) = frames::to_rtn(r1, v1, &(v2 - v1)) {
+                for (key, value) in ["RELATIVE_VELOCITY_R", "RELATIVE_VELOCITY_T", "RELATIVE_VELOCITY_N"]
+                    .iter()
+                    .zip(components)
+                {
+                    relative.push(CdmField::with_unit(key, format!("{:.3}", value * 1.0e3), "m/s"));
+                }
+            }
+        }
+        if let Some(pc) = conjunction.probability {
+            relative.push(CdmField::new("COLLISION_PROBABILITY", format!("{:.6e}", pc)));
+            relative.push(CdmField::new("COLLISION_PROBABILITY_METHOD", "FOSTER-1992"));
+        }
+
+        let block = |object: &CdmObject, label: &str| {
+            (object.metadata_fields(label), object.state_fields(), object.covariance_fields())
+        };
+
+        Ok(CdmMessage {
+            header: header_fields,
+            relative,
+            objects: [block(object1, "OBJECT1"), block(object2, "OBJECT2")],
+        })
+    }
+
+    /// Keyword = value notation
+    pub fn to_kvn(&self) -> String {
+        let width = self
+            .header
+            .iter()
+            .chain(&self.relative)
+            .chain(self.objects.iter().flat_map(|(m, s, c)| m.iter().chain(s).chain(c)))
+            .map(|f| f.key.len())
+            .max()
+            .unwrap_or(0);
+
+        let mut out = String::new();
+        let mut write = |field: &CdmField| {
+            out.push_str(&format!("{:<width$} = {}", field.key, field.value, width = width));
+            if let Some(unit) = field.unit {
+                out.push_str(&format!(" [{}]", unit));
+            }
+            out.push('\n');
+        };
+        for field in self.header.iter().chain(&self.relative) {
+            write(field);
+        }
+        for (metadata, state, covariance) in &self.objects {
+            for field in metadata.iter().chain(state).chain(covariance) {
+                write(field);
+            }
+        }
+        out
+    }
+
+    /// CCSDS NDM/XML notation
+    pub fn to_xml(&self) -> String {
+        fn element(out: &mut String, indent: usize, field: &CdmField) {
+            out.push_str(&" ".repeat(indent));
+            match field.unit {
+                Some(unit) => out.push_str(&format!(
+                    "<{} units=\"{}\">{}</{}>\n",
+                    field.key,
+                    unit,
+                    escape_xml(&field.value),
+                    field.key
+                )),
+                None => out.push_str(&format!("<{}>{}</{}>\n", field.key, escape_xml(&field.value), field.key)),
+            }
+        }
+
+        let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        out.push_str(&format!("<cdm id=\"CCSDS_CDM_VERS\" version=\"{}\">\n", CDM_VERSION));
+        out.push_str("  <header>\n");
+        for field in self.header.iter().filter(|f| f.key != "CCSDS_CDM_VERS") {
+            element(&mut out, 4, field);
+        }
+        out.push_str("  </header>\n  <body>\n    <relativeMetadataData>\n");
+        let (vector, scalars): (Vec<&CdmField>, Vec<&CdmField>) = self
+            .relative
+            .iter()
+            .partition(|f| f.key.starts_with("RELATIVE_POSITION") || f.key.starts_with("RELATIVE_VELOCITY"));
+        for field in scalars.iter().filter(|f| !f.key.starts_with("COLLISION")) {
+            element(&mut out, 6, field);
+        }
+        out.push_str("      <relativeStateVector>\n");
+        for field in vector {
+            element(&mut out, 8, field);
+        }
+        out.push_str("      </relativeStateVector>\n");
+        for field in scalars.iter().filter(|f| f.key.starts_with("COLLISION")) {
+            element(&mut out, 6, field);
+        }
+        out.push_str("    </relativeMetadataData>\n");
+
+        for (metadata, state, covariance) in &self.objects {
+            out.push_str("    <segment>\n      <metadata>\n");
+            for field in metadata {
+                element(&mut out, 8, field);
+            }
+            out.push_str("      </metadata>\n      <data>\n");
+            if !state.is_empty() {
+                out.push_str("        <stateVector>\n");
+                for field in state {
+                    element(&mut out, 10, field);
+                }
+                out.push_str("        </stateVector>\n");
+            }
+            if !covariance.is_empty() {
+                out.push_str("        <covarianceMatrix>\n");
+                for field in covariance {
+                    element(&mut out, 10, field);
+                }
+                out.push_str("        </covarianceMatrix>\n");
+            }
+            out.push_str("      </data>\n    </segment>\n");
+        }
+        out.push_str("  </body>\n</cdm>\n");
+        out
+    }
+}
+
+pub(crate) fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Build the CDM object block from an optional state and metadata overrides
+#[cfg(feature = "python")]
+fn cdm_object(norad_id: i32, satellite: Option<&Satellite>, metadata: Option<HashMap<String, String>>) -> PyResult<CdmObject> {
+    let mut object = match satellite {
+        Some(sat) if sat.norad_id != norad_id => {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Satellite {} does not match conjunction object {}",
+                sat.norad_id, norad_id
+            )));
+        }
+        Some(sat) => CdmObject::from_satellite(sat),
+        None => CdmObject { norad_id, ..Default::default() },
+    };
+    if let Some(metadata) = metadata {
+        for key in metadata.keys() {
+            if !METADATA_KEYS.contains(&key.as_str()) {
+                return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                    "Unknown CDM metadata keyword '{}' (expected one of {})",
+                    key,
+                    METADATA_KEYS.join(", ")
+                )));
+            }
+        }
+        object.metadata.extend(metadata);
+    }
+    Ok(object)
+}
+
+/// Serialize a conjunction as a CCSDS CDM (format: "kvn" or "xml")
+///
+/// `sat1`/`sat2` are the object states at TCA; when given, their states and
+/// covariances are written too. Otherwise the states recorded in the
+/// conjunction by screening are used, without covariances. `metadata1`/`metadata2` override object
+/// metadata keywords such as OBJECT_NAME or REF_FRAME.
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(signature = (
+    conjunction,
+    sat1 = None,
+    sat2 = None,
+    format = "kvn",
+    originator = "ORBITGUARDAI",
+    message_id = None,
+    metadata1 = None,
+    metadata2 = None,
+    creation_date = None
+))]
+#[allow(clippy::too_many_arguments)]
+pub fn conjunction_to_cdm(
+    conjunction: &Conjunction,
+    sat1: Option<Satellite>,
+    sat2: Option<Satellite>,
+    format: &str,
+    originator: &str,
+    message_id: Option<String>,
+    metadata1: Option<HashMap<String, String>>,
+    metadata2: Option<HashMap<String, String>>,
+    creation_date: Option<Epoch>,
+) -> PyResult<String> {
+    let at_tca = conjunction.states_at_tca();
+    let sat1 = sat1.or_else(|| at_tca.clone().map(|(primary, _)| primary));
+    let sat2 = sat2.or_else(|| at_tca.map(|(_, secondary)| secondary));
+    let mut object1 = cdm_object(conjunction.norad_id_1, sat1.as_ref(), metadata1)?;
+    let mut object2 = cdm_object(conjunction.norad_id_2, sat2.as_ref(), metadata2)?;
+    for (object, designator) in [
+        (&mut object1, &conjunction.international_designator_1),
+        (&mut object2, &conjunction.international_designator_2),
+    ] {
+        if let Some(designator) = designator {
+            object.metadata.entry("INTERNATIONAL_DESIGNATOR".to_string()).or_insert_with(|| designator.clone());
+        }
+    }
+    let header = CdmHeader {
+        originator: originator.to_string(),
+        message_id,
+        creation_mjd_utc: creation_date.map_or_else(now_mjd_utc, |e| e.mjd_in(TimeScale::Utc)),
+    };
+    let message = CdmMessage::build(conjunction, &object1, &object2, &header)
+        .map_err(pyo3::exceptions::PyValueError::new_err)?;
+
+    match format.to_ascii_lowercase().as_str() {
+        "kvn" => Ok(message.to_kvn()),
+        "xml" => Ok(message.to_xml()),
+        _ => Err(pyo3::exceptions::PyValueError::new_err(format!(
+            "Unknown CDM format '{}' (expected 'kvn' or 'xml')",
+            format
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encounter() -> (Conjunction, CdmObject, CdmObject) {
+        let (r1, v1) = (Vector3::new(7000.0, 0.0, 0.0), Vector3::new(0.0, 7.5, 0.0));
+        let (r2, v2) = (Vector3::new(7000.1, 0.0, 0.2), Vector3::new(0.0, 0.0, 7.5));
+        let mut conjunction = Conjunction::from_states(1, 2, (&r1, &v1), (&r2, &v2), Some(2460000.5));
+        conjunction.probability = Some(1.5e-5);
+
+        let mut object1 = CdmObject { norad_id: 1, state: Some((r1, v1)), ..Default::default() };
+        object1.covariance = Some(Matrix6::from_diagonal(&nalgebra::Vector6::new(1e-2, 4e-2, 9e-2, 1e-6, 1e-6, 1e-6)));
+        object1.metadata.insert("OBJECT_NAME".to_string(), "SAT <A>".to_string());
+        let object2 = CdmObject { norad_id: 2, state: Some((r2, v2)), ..Default::default() };
+        (conjunction, object1, object2)
+    }
+
+    fn header() -> CdmHeader {
+        CdmHeader { originator: "TEST".to_string(), message_id: None, creation_mjd_utc: 60000.0 }
+    }
+
+    /// KVN lines as (key, value-with-unit) pairs
+    fn kvn_pairs(kvn: &str) -> Vec<(String, String)> {
+        kvn.lines()
+            .filter_map(|line| line.split_once(" = "))
+            .map(|(key, value)| (key.trim().to_string(), value.to_string()))
+            .collect()
+    }
+
+    fn value<'a>(pairs: &'a [(String, String)], key: &str) -> &'a str {
+        &pairs.iter().find(|(k, _)| k == key).unwrap().1
+    }
+
+    #[test]
+    fn test_kvn_fields_and_units() {
+        let (conjunction, object1, object2) = encounter();
+        let kvn = CdmMessage::build(&conjunction, &object1, &object2, &header()).unwrap().to_kvn();
+        let pairs = kvn_pairs(&kvn);
+
+        assert_eq!(value(&pairs, "TCA"), "2023-02-25T00:00:00.000000");
+        assert_eq!(value(&pairs, "MISS_DISTANCE"), "223.607 [m]");
+        assert_eq!(value(&pairs, "RELATIVE_POSITION_R"), "100.000 [m]");
+        assert_eq!(value(&pairs, "RELATIVE_POSITION_N"), "200.000 [m]");
+        assert_eq!(value(&pairs, "COLLISION_PROBABILITY"), "1.500000e-5");
+        assert_eq!(value(&pairs, "OBJECT_NAME"), "SAT <A>");
+        // radial = x, in-track = y, cross-track = z for this primary
+        assert_eq!(value(&pairs, "CR_R"), "1.000000e4 [m**2]");
+        assert_eq!(value(&pairs, "CN_N"), "9.000000e4 [m**2]");
+        assert_eq!(pairs.iter().filter(|(k, _)| k == "OBJECT").count(), 2);
+        assert_eq!(pairs.iter().filter(|(k, _)| k == "CRDOT_R").count(), 1);
+    }
+
+    #[test]
+    fn test_xml_structure() {
+        let (conjunction, object1, object2) = encounter();
+        let xml = CdmMessage::build(&conjunction, &object1, &object2, &header()).unwrap().to_xml();
+
+        assert!(xml.contains("<MISS_DISTANCE units=\"m\">223.607</MISS_DISTANCE>"));
+        assert!(xml.contains("<OBJECT_NAME>SAT &lt;A&gt;</OBJECT_NAME>"));
+        assert_eq!(xml.matches("<segment>").count(), 2);
+        assert_eq!(xml.matches("<covarianceMatrix>").count(), 1);
+        assert!(xml.find("<relativeStateVector>").unwrap() < xml.find("<COLLISION_PROBABILITY>").unwrap());
+    }
+
+    #[test]
+    fn test_requires_tca() {
+        let (mut conjunction, object1, object2) = encounter();
+        conjunction.tca_jd = None;
+        assert!(CdmMessage::build(&conjunction, &object1, &object2, &header()).is_err());
+    }
+}
@@ -0,0 +1,132 @@
+//! Low-precision Sun and Moon ephemerides
+//!
+//! Analytical series of Montenbruck & Gill (Satellite Orbits, 3.3.2):
+//! about 0.1% in the Sun's position and a few hundred km for the Moon,
+//! ample for third-body accelerations and radiation pressure. Positions
+//! are geocentric, mean equator and equinox of J2000 (km). Julian dates may
+//! be given in UTC; the TT offset is below the accuracy of the series.
+
+use nalgebra::{Rotation3, Vector3};
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+
+/// Obliquity of the ecliptic at J2000 (deg)
+const OBLIQUITY_DEG: f64 = 23.43929111;
+
+/// Julian centuries since J2000
+fn centuries(jd: f64) -> f64 {
+    (jd - 2451545.0) / 36525.0
+}
+
+/// Ecliptic spherical coordinates (rad, rad, km) to equatorial Cartesian
+fn from_ecliptic(longitude: f64, latitude: f64, distance: f64) -> Vector3<f64> {
+    let ecliptic = Vector3::new(
+        distance * latitude.cos() * longitude.cos(),
+        distance * latitude.cos() * longitude.sin(),
+        distance * latitude.sin(),
+    );
+    Rotation3::from_axis_angle(&Vector3::x_axis(), OBLIQUITY_DEG.to_radians()) * ecliptic
+}
+
+/// Geocentric position of the Sun (km)
+pub fn sun_position(jd: f64) -> Vector3<f64> {
+    let t = centuries(jd);
+    let mean_anomaly = (357.5256 + 35999.049 * t).to_radians();
+    let arcsec = |value: f64| (value / 3600.0).to_radians();
+    let longitude = (282.9400f64).to_radians()
+        + mean_anomaly
+        + arcsec(6892.0) * mean_anomaly.sin()
+        + arcsec(72.0) * (2.0 * mean_anomaly).sin();
+    let distance = (149.619 - 2.499 * mean_anomaly.cos() - 0.021 * (2.0 * mean_anomaly).cos()) * 1.0e6;
+    from_ecliptic(longitude, 0.0, distance)
+}
+
+/// Geocentric position of the Moon (km)
+pub fn moon_position(jd: f64) -> Vector3<f64> {
+    let t = centuries(jd);
+    let deg = |value: f64| value.to_radians();
+    let arcsec = |value: f64| (value / 3600.0).to_radians();
+    // mean longitude, anomalies of Moon and Sun, argument of latitude, elongation
+    let l0 = deg(218.31617 + 481267.88088 * t - 1.3972 * t);
+    let l = deg(134.96292 + 477198.86753 * t);
+    let lp = deg(357.52543 + 35999.04944 * t);
+    let f = deg(93.27283 + 483202.01873 * t);
+    let d = deg(297.85027 + 445267.11135 * t);
+
+    let longitude = l0
+        + arcsec(
+            22640.0 * l.sin() + 769.0 * (2.0 * l).sin() - 4586.0 * (l - 2.0 * d).sin() + 2370.0 * (2.0 * d).sin()
+                - 668.0 * lp.sin()
+                - 412.0 * (2.0 * f).sin()
+                - 212.0 * (2.0 * l - 2.0 * d).sin()
+                - 206.0 * (l + lp - 2.0 * d).sin()
+                + 192.0 * (l + 2.0 * d).sin()
+                - 165.0 * (lp - 2.0 * d).sin()
+                + 148.0 * (l - lp).sin()
+                - 125.0 * d.sin()
+                - 110.0 * (l + lp).sin()
+                - 55.0 * (2.0 * f - 2.0 * d).sin(),
+        );
+    let latitude = arcsec(
+        18520.0 * (f + longitude - l0 + arcsec(412.0 * (2.0 * f).sin() + 541.0 * lp.sin())).sin()
+            - 526.0 * (f - 2.0 * d).sin()
+            + 44.0 * (l + f - 2.0 * d).sin()
+            - 31.0 * (-l + f - 2.0 * d).sin()
+            - 25.0 * (-2.0 * l + f).sin()
+            - 23.0 * (lp + f - 2.0 * d).sin()
+            + 21.0 * (-l + f).sin()
+            + 11.0 * (-lp + f - 2.0 * d).sin(),
+    );
+    let distance = 385000.0 - 20905.0 * l.cos() - 3699.0 * (2.0 * d - l).cos() - 2956.0 * (2.0 * d).cos()
+        - 570.0 * (2.0 * l).cos()
+        + 246.0 * (2.0 * l - 2.0 * d).cos()
+        - 205.0 * (lp - 2.0 * d).cos()
+        - 171.0 * (l + 2.0 * d).cos()
+        - 152.0 * (l + lp - 2.0 * d).cos();
+    from_ecliptic(longitude, latitude, distance)
+}
+
+/// Geocentric Sun position (km, J2000 equator) at a Julian date
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(name = "sun_position")]
+pub fn py_sun_position(jd: f64) -> Vec<f64> {
+    sun_position(jd).as_slice().to_vec()
+}
+
+/// Geocentric Moon position (km, J2000 equator) at a Julian date
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(name = "moon_position")]
+pub fn py_moon_position(jd: f64) -> Vec<f64> {
+    moon_position(jd).as_slice().to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::AU_KM;
+
+    #[test]
+    fn test_sun_near_equinox_and_solstice() {
+        // 2000 March 20 07:35 UT (equinox): Sun on the +x axis
+        let equinox = sun_position(2451623.816);
+        assert!((equinox.norm() / AU_KM - 0.996).abs() < 2e-3);
+        assert!(equinox.y.atan2(equinox.x).abs() < 1e-3);
+        // 2000 June 21 01:48 UT (solstice): declination at the obliquity
+        let solstice = sun_position(2451716.575).normalize();
+        assert!((solstice.z.asin().to_degrees() - OBLIQUITY_DEG).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_moon_distance_and_latitude_bounds() {
+        let mut jd = 2460000.5;
+        while jd < 2460030.5 {
+            let moon = moon_position(jd);
+            assert!((356000.0..407000.0).contains(&moon.norm()), "{}", moon.norm());
+            // within the 5.1 degree inclination of the lunar orbit to the ecliptic, plus the obliquity
+            assert!(moon.normalize().z.asin().to_degrees().abs() < OBLIQUITY_DEG + 5.2);
+            jd += 0.5;
+        }
+    }
+}
@@ -0,0 +1,389 @@
+//! Columnar conjunction output
+//!
+//! Large screens can produce millions of events, and one Python
+//! `Conjunction` per event dominates the runtime when the results only go
+//! into a DataFrame. Here results come back as one column per field. Each
+//! column exports the buffer protocol, so `numpy.asarray(column)` and
+//! `memoryview(column)` read it without copying, and pandas accepts the
+//! columns directly. The international designators are text, which no
+//! buffer can hold, so they come back as lists of str or None. An Arrow
+//! RecordBatch is built on request when pyarrow is installed.
+
+use pyo3::exceptions::PyBufferError;
+use pyo3::ffi;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use std::ffi::c_void;
+use std::os::raw::{c_char, c_int};
+use std::ptr;
+
+use crate::batch::screen_arrays;
+use crate::volume;
+use crate::{sort_conjunctions, Conjunction, Precision, SortOrder};
+
+/// Column names in output order
+const COLUMNS: [&str; 7] = [
+    "norad_id_1",
+    "norad_id_2",
+    "distance_km",
+    "relative_velocity_km_s",
+    "radial_km",
+    "in_track_km",
+    "cross_track_km",
+];
+
+/// Names of the text columns, which follow `COLUMNS`
+const DESIGNATOR_COLUMNS: [&str; 2] = ["international_designator_1", "international_designator_2"];
+
+enum ColumnData {
+    Int32(Vec<i32>),
+    Float64(Vec<f64>),
+}
+
+/// Read-only C-contiguous 1-D or 2-D array exporting the buffer protocol
+#[pyclass]
+pub struct Column {
+    data: ColumnData,
+    shape: Vec<isize>,
+    strides: Vec<isize>,
+}
+
+impl Column {
+    fn new(data: ColumnData) -> Self {
+        let len = match &data {
+            ColumnData::Int32(values) => values.len(),
+            ColumnData::Float64(values) => values.len(),
+        };
+        Column::with_shape(data, &[len])
+    }
+
+    fn with_shape(data: ColumnData, shape: &[usize]) -> Self {
+        let itemsize = match &data {
+            ColumnData::Int32(_) => std::mem::size_of::<i32>(),
+            ColumnData::Float64(_) => std::mem::size_of::<f64>(),
+        };
+        let mut strides = vec![itemsize as isize; shape.len()];
+        for axis in (0..shape.len().saturating_sub(1)).rev() {
+            strides[axis] = strides[axis + 1] * shape[axis + 1] as isize;
+        }
+        Column {
+            data,
+            shape: shape.iter().map(|&n| n as isize).collect(),
+            strides,
+        }
+    }
+
+    /// Float64 column over arbitrary values
+    pub(crate) fn float64(values: Vec<f64>) -> Self {
+        Column::new(ColumnData::Float64(values))
+    }
+
+    /// Int32 column over arbitrary values
+    pub(crate) fn int32(values: Vec<i32>) -> Self {
+        Column::new(ColumnData::Int32(values))
+    }
+
+    /// Row-major float64 matrix; `values` holds `rows * cols` elements
+    pub(crate) fn matrix(values: Vec<f64>, rows: usize, cols: usize) -> Self {
+        debug_assert_eq!(values.len(), rows * cols);
+        Column::with_shape(ColumnData::Float64(values), &[rows, cols])
+    }
+
+    /// Row-major float64 array of any shape; `values` holds the product of `shape` elements
+    pub(crate) fn array(values: Vec<f64>, shape: &[usize]) -> Self {
+        debug_assert_eq!(values.len(), shape.iter().product::<usize>());
+        Column::with_shape(ColumnData::Float64(values), shape)
+    }
+
+    fn itemsize(&self) -> isize {
+        self.strides[self.strides.len() - 1]
+    }
+
+    /// Elements per entry along the first axis (1 for a 1-D column)
+    fn row_len(&self) -> usize {
+        self.shape[1..].iter().product::<isize>() as usize
+    }
+
+    fn ids(conjunctions: &[Conjunction], field: fn(&Conjunction) -> i32) -> Self {
+        Column::new(ColumnData::Int32(conjunctions.iter().map(field).collect()))
+    }
+
+    fn floats(conjunctions: &[Conjunction], field: fn(&Conjunction) -> f64) -> Self {
+        Column::new(ColumnData::Float64(conjunctions.iter().map(field).collect()))
+    }
+
+    /// Struct-module format character of the elements
+    fn format(&self) -> &'static [u8] {
+        match self.data {
+            ColumnData::Int32(_) => b"i\0",
+            ColumnData::Float64(_) => b"d\0",
+        }
+    }
+
+    fn data_ptr(&self) -> *const c_void {
+        match &self.data {
+            ColumnData::Int32(values) => values.as_ptr() as *const c_void,
+            ColumnData::Float64(values) => values.as_ptr() as *const c_void,
+        }
+    }
+}
+
+/// Row-major `values` as nested lists following `shape`
+fn nested<T: ToPyObject>(py: Python<'_>, values: &[T], shape: &[isize]) -> PyObject {
+    if shape.len() <= 1 {
+        return values.to_object(py);
+    }
+    let width = shape[1..].iter().product::<isize>() as usize;
+    values
+        .chunks(width.max(1))
+        .map(|chunk| nested(py, chunk, &shape[1..]))
+        .collect::<Vec<_>>()
+        .to_object(py)
+}
+
+#[pymethods]
+impl Column {
+    /// Element type ("int32" or "float64")
+    #[getter]
+    fn dtype(&self) -> &'static str {
+        match self.data {
+            ColumnData::Int32(_) => "int32",
+            ColumnData::Float64(_) => "float64",
+        }
+    }
+
+    /// Dimensions, e.g. (n,) or (rows, cols)
+    #[getter]
+    fn shape(&self) -> Vec<usize> {
+        self.shape.iter().map(|&n| n as usize).collect()
+    }
+
+    /// Values as a (nested, for 2-D and up) Python list
+    fn to_list(&self, py: Python<'_>) -> PyObject {
+        match &self.data {
+            ColumnData::Int32(values) => nested(py, values, &self.shape),
+            ColumnData::Float64(values) => nested(py, values, &self.shape),
+        }
+    }
+
+    /// Element of a 1-D column, or an entry (as a nested list) of a higher-dimensional one
+    fn __getitem__(&self, py: Python<'_>, index: isize) -> PyResult<PyObject> {
+        let len = self.shape[0];
+        let i = if index < 0 { index + len } else { index };
+        if !(0..len).contains(&i) {
+            return Err(pyo3::exceptions::PyIndexError::new_err("Column index out of range"));
+        }
+        let (width, i) = (self.row_len(), i as usize);
+        let row = i * width..(i + 1) * width;
+        Ok(match (&self.data, self.shape.len()) {
+            (ColumnData::Int32(values), 1) => values[i].to_object(py),
+            (ColumnData::Float64(values), 1) => values[i].to_object(py),
+            (ColumnData::Int32(values), _) => nested(py, &values[row], &self.shape[1..]),
+            (ColumnData::Float64(values), _) => nested(py, &values[row], &self.shape[1..]),
+        })
+    }
+
+    /// numpy conversion (zero-copy, read-only)
+    #[pyo3(signature = (dtype = None))]
+    fn __array__(slf: &PyCell<Self>, dtype: Option<&PyAny>) -> PyResult<PyObject> {
+        let py = slf.py();
+        let numpy = py.import("numpy")?;
+        let array = numpy
+            .call_method1("frombuffer", (slf, slf.borrow().dtype()))?
+            .call_method1("reshape", (slf.borrow().shape(),))?;
+        match dtype {
+            Some(dtype) => Ok(array.call_method1("astype", (dtype,))?.into()),
+            None => Ok(array.into()),
+        }
+    }
+
+    fn __len__(&self) -> usize {
+        self.shape[0] as usize
+    }
+
+    unsafe fn __getbuffer__(slf: &PyCell<Self>, view: *mut ffi::Py_buffer, flags: c_int) -> PyResult<()> {
+        if view.is_null() {
+            return Err(PyBufferError::new_err("View is null"));
+        }
+        if (flags & ffi::PyBUF_WRITABLE) == ffi::PyBUF_WRITABLE {
+            return Err(PyBufferError::new_err("Column is read-only"));
+        }
+        // the data, shape and strides live in the pyclass, which `obj` keeps alive
+        let column = slf.borrow();
+        (*view).obj = ffi::_Py_NewRef(slf.as_ptr());
+        (*view).buf = column.data_ptr() as *mut c_void;
+        (*view).len = column.shape.iter().product::<isize>() * column.itemsize();
+        (*view).readonly = 1;
+        (*view).itemsize = column.itemsize();
+        (*view).format = if (flags & ffi::PyBUF_FORMAT) == ffi::PyBUF_FORMAT {
+            column.format().as_ptr() as *mut c_char
+        } else {
+            ptr::null_mut()
+        };
+        (*view).ndim = column.shape.len() as c_int;
+        (*view).shape = if (flags & ffi::PyBUF_ND) == ffi::PyBUF_ND {
+            column.shape.as_ptr() as *mut isize
+        } else {
+            ptr::null_mut()
+        };
+        (*view).strides = if (flags & ffi::PyBUF_STRIDES) == ffi::PyBUF_STRIDES {
+            column.strides.as_ptr() as *mut isize
+        } else {
+            ptr::null_mut()
+        };
+        (*view).suboffsets = ptr::null_mut();
+        (*view).internal = ptr::null_mut();
+        Ok(())
+    }
+
+    unsafe fn __releasebuffer__(&self, _view: *mut ffi::Py_buffer) {}
+}
+
+/// Split conjunctions into one column per field, in `COLUMNS` order
+pub fn to_columns(conjunctions: &[Conjunction]) -> Vec<Column> {
+    vec![
+        Column::ids(conjunctions, |c| c.norad_id_1),
+        Column::ids(conjunctions, |c| c.norad_id_2),
+        Column::floats(conjunctions, |c| c.distance_km),
+        Column::floats(conjunctions, |c| c.relative_velocity_km_s),
+        Column::floats(conjunctions, |c| c.radial_km),
+        Column::floats(conjunctions, |c| c.in_track_km),
+        Column::floats(conjunctions, |c| c.cross_track_km),
+    ]
+}
+
+/// International designators of both objects, in `DESIGNATOR_COLUMNS` order
+pub fn designators(conjunctions: &[Conjunction]) -> [Vec<Option<String>>; 2] {
+    [
+        conjunctions.iter().map(|c| c.international_designator_1.clone()).collect(),
+        conjunctions.iter().map(|c| c.international_designator_2.clone()).collect(),
+    ]
+}
+
+/// Arrow RecordBatch over the column buffers, with the designators as string arrays
+fn record_batch(py: Python<'_>, columns: Vec<Column>, designators: [Vec<Option<String>>; 2]) -> PyResult<PyObject> {
+    let pyarrow = py.import("pyarrow")?;
+    let mut arrays = columns
+        .into_iter()
+        .map(|column| {
+            let (len, dtype) = (column.__len__(), column.dtype());
+            let arrow_type = pyarrow.call_method0(if dtype == "int32" { "int32" } else { "float64" })?;
+            let buffer = pyarrow.call_method1("py_buffer", (Py::new(py, column)?,))?;
+            let buffers = vec![py.None(), buffer.into()];
+            pyarrow.getattr("Array")?.call_method1("from_buffers", (arrow_type, len, buffers))
+        })
+        .collect::<PyResult<Vec<_>>>()?;
+    for values in designators {
+        arrays.push(pyarrow.call_method1("array", (values, pyarrow.call_method0("string")?))?);
+    }
+    let kwargs = PyDict::new(py);
+    kwargs.set_item("names", COLUMNS.iter().chain(&DESIGNATOR_COLUMNS).collect::<Vec<_>>())?;
+    Ok(pyarrow
+        .getattr("RecordBatch")?
+        .call_method("from_arrays", (arrays,), Some(kwargs))?
+        .into())
+}
+
+/// Find close approaches from arrays and return the results column-wise
+///
+/// Inputs are as for `find_conjunctions_array`. With `output="columns"`
+/// (default) a dict of read-only `Column` buffers keyed by field name is
+/// returned (ready for `pandas.DataFrame` or `numpy.asarray`), followed by
+/// the designator lists; with `output="arrow"` a `pyarrow.RecordBatch`.
+/// Undefined RTN components are NaN.
+#[pyfunction]
+#[pyo3(signature = (
+    ids,
+    positions,
+    velocities,
+    threshold_km,
+    apogee_perigee_filter = false,
+    sort_by = "ids",
+    output = "columns",
+    precision = "f64",
+    validate = true
+))]
+#[allow(clippy::too_many_arguments)]
+pub fn find_conjunctions_columnar(
+    py: Python<'_>,
+    ids: &PyAny,
+    positions: &PyAny,
+    velocities: &PyAny,
+    threshold_km: &PyAny,
+    apogee_perigee_filter: bool,
+    sort_by: &str,
+    output: &str,
+    precision: &str,
+    validate: bool,
+) -> PyResult<PyObject> {
+    let order: SortOrder = sort_by.parse().map_err(pyo3::exceptions::PyValueError::new_err)?;
+    let precision: Precision = precision.parse().map_err(pyo3::exceptions::PyValueError::new_err)?;
+    if output != "columns" && output != "arrow" {
+        return Err(pyo3::exceptions::PyValueError::new_err(format!(
+            "Unknown output '{}' (expected 'columns' or 'arrow')",
+            output
+        )));
+    }
+    let volume = volume::extract_threshold(threshold_km)?;
+    let mut conjunctions = screen_arrays(ids, positions, velocities, volume, apogee_perigee_filter, precision, validate)?;
+    sort_conjunctions(&mut conjunctions, order);
+    let columns = to_columns(&conjunctions);
+    let designators = designators(&conjunctions);
+    drop(conjunctions);
+
+    if output == "arrow" {
+        return record_batch(py, columns, designators);
+    }
+    let table = PyDict::new(py);
+    for (name, column) in COLUMNS.iter().zip(columns) {
+        table.set_item(name, Py::new(py, column)?)?;
+    }
+    for (name, values) in DESIGNATOR_COLUMNS.iter().zip(designators) {
+        table.set_item(name, values)?;
+    }
+    Ok(table.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nalgebra::Vector3;
+
+    #[test]
+    fn test_columns_follow_event_order() {
+        let (r, v) = (Vector3::new(7000.0, 0.0, 0.0), Vector3::new(0.0, 7.5, 0.0));
+        let events: Vec<Conjunction> = (1..4)
+            .map(|k| {
+                let other = r + Vector3::new(0.1 * k as f64, 0.0, 0.0);
+                Conjunction::from_states(0, k, (&r, &v), (&other, &v), None)
+            })
+            .collect();
+        let columns = to_columns(&events);
+        assert_eq!(columns.len(), COLUMNS.len());
+        assert!(columns.iter().all(|column| column.__len__() == 3));
+        let ColumnData::Int32(ids) = &columns[1].data else { panic!("ids must be int32") };
+        assert_eq!(ids, &vec![1, 2, 3]);
+        let ColumnData::Float64(distances) = &columns[2].data else { panic!("distances must be float64") };
+        assert!((distances[2] - 0.3).abs() < 1e-9);
+        assert_eq!(columns[4].strides, [8]);
+    }
+
+    #[test]
+    fn test_designators_follow_event_order() {
+        let (r, v) = (Vector3::new(7000.0, 0.0, 0.0), Vector3::new(0.0, 7.5, 0.0));
+        let mut events: Vec<Conjunction> =
+            (1..3).map(|k| Conjunction::from_states(0, k, (&r, &v), (&r, &v), None)).collect();
+        events[1].international_designator_2 = Some("1998-067A".to_string());
+        let [first, second] = designators(&events);
+        assert_eq!(first, vec![None, None]);
+        assert_eq!(second, vec![None, Some("1998-067A".to_string())]);
+    }
+
+    #[test]
+    fn test_matrix_is_row_major() {
+        let matrix = Column::matrix(vec![0.0; 6], 2, 3);
+        assert_eq!(matrix.shape, [2, 3]);
+        assert_eq!(matrix.strides, [24, 8]);
+        assert_eq!((matrix.__len__(), matrix.row_len()), (2, 3));
+    }
+}
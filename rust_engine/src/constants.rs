@@ -0,0 +1,44 @@
+//! Physical constants shared across the engine (WGS-84 unless noted), plus
+//! the time and propagation defaults every module uses the same way
+
+/// Earth gravitational parameter (km^3/s^2)
+pub const MU_EARTH: f64 = 398600.4418;
+
+/// Earth equatorial radius (km)
+pub const EARTH_RADIUS_KM: f64 = 6378.137;
+
+/// Earth flattening (WGS-84)
+pub const EARTH_FLATTENING: f64 = 1.0 / 298.257223563;
+
+/// Earth rotation rate (rad/s)
+pub const EARTH_ROTATION_RATE: f64 = 7.292115146706979e-5;
+
+/// Earth second zonal harmonic (EGM-96, unnormalized)
+pub const J2_EARTH: f64 = 1.08262668e-3;
+
+/// Earth third zonal harmonic (EGM-96, unnormalized)
+pub const J3_EARTH: f64 = -2.53265649e-6;
+
+/// Sun gravitational parameter (km^3/s^2, DE-405)
+pub const MU_SUN: f64 = 1.32712440018e11;
+
+/// Moon gravitational parameter (km^3/s^2, DE-405)
+pub const MU_MOON: f64 = 4902.800066;
+
+/// Speed of light in vacuum (km/s)
+pub const SPEED_OF_LIGHT_KM_S: f64 = 299792.458;
+
+/// Astronomical unit (km)
+pub const AU_KM: f64 = 149597870.7;
+
+/// Solar radiation pressure at 1 AU (N/m^2)
+pub const SOLAR_PRESSURE_1AU: f64 = 4.56e-6;
+
+/// Solar photospheric radius (km)
+pub const SUN_RADIUS_KM: f64 = 696000.0;
+
+/// Seconds in a (Julian) day
+pub const SECONDS_PER_DAY: f64 = 86400.0;
+
+/// Initial step of the numerical propagator for state vectors (seconds)
+pub const PROPAGATOR_INITIAL_STEP_S: f64 = 30.0;
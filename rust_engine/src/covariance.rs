@@ -0,0 +1,97 @@
+//! State covariance helpers
+//!
+//! Covariances are 6x6 position-velocity matrices in km and km/s units,
+//! expressed in the same inertial frame as the state they belong to.
+
+use nalgebra::{Matrix3, Matrix6, Vector3};
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+
+#[cfg(feature = "python")]
+use crate::error::OrbitError;
+
+/// Relative tolerance used when checking covariance symmetry
+const SYMMETRY_TOLERANCE: f64 = 1.0e-9;
+
+/// Build a 6x6 covariance from a flat 36-element list or a nested 6x6 sequence
+///
+/// Accepts anything that extracts as a Python sequence of floats, which
+/// includes 1-D and 2-D numpy arrays.
+#[cfg(feature = "python")]
+pub fn covariance_from_py(value: &PyAny) -> PyResult<Matrix6<f64>> {
+    let matrix = if let Ok(flat) = value.extract::<Vec<f64>>() {
+        if flat.len() != 36 {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "Flat covariance must have 36 elements"
+            ));
+        }
+        Matrix6::from_row_slice(&flat)
+    } else if let Ok(rows) = value.extract::<Vec<Vec<f64>>>() {
+        if rows.len() != 6 || rows.iter().any(|row| row.len() != 6) {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "Covariance must be a 6x6 matrix"
+            ));
+        }
+        Matrix6::from_fn(|i, j| rows[i][j])
+    } else {
+        return Err(pyo3::exceptions::PyTypeError::new_err(
+            "Covariance must be a flat list of 36 floats or a 6x6 nested sequence"
+        ));
+    };
+
+    validate(&matrix).map_err(OrbitError::InvalidState)?;
+    Ok(matrix)
+}
+
+/// Check that a covariance is finite, symmetric and has a non-negative diagonal
+pub fn validate(cov: &Matrix6<f64>) -> Result<(), String> {
+    if cov.iter().any(|v| !v.is_finite()) {
+        return Err("Covariance contains NaN or infinite values".to_string());
+    }
+    if (0..6).any(|i| cov[(i, i)] < 0.0) {
+        return Err("Covariance diagonal must be non-negative".to_string());
+    }
+    let scale = cov.amax().max(f64::MIN_POSITIVE);
+    if (cov - cov.transpose()).amax() > SYMMETRY_TOLERANCE * scale {
+        return Err("Covariance must be symmetric".to_string());
+    }
+    Ok(())
+}
+
+/// Nested-list view of a 6x6 covariance (row-major)
+pub fn to_rows(cov: &Matrix6<f64>) -> Vec<Vec<f64>> {
+    (0..6).map(|i| (0..6).map(|j| cov[(i, j)]).collect()).collect()
+}
+
+/// Upper-left 3x3 position block
+pub fn position_block(cov: &Matrix6<f64>) -> Matrix3<f64> {
+    cov.fixed_view::<3, 3>(0, 0).into_owned()
+}
+
+/// Mahalanobis distance of a separation under a 3x3 covariance
+pub fn mahalanobis(separation: &Vector3<f64>, cov: &Matrix3<f64>) -> Option<f64> {
+    let inverse = cov.try_inverse()?;
+    let squared = separation.dot(&(inverse * separation));
+    (squared >= 0.0).then(|| squared.sqrt())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_rejects_asymmetric() {
+        let mut cov = Matrix6::identity();
+        assert!(validate(&cov).is_ok());
+        cov[(0, 1)] = 0.5;
+        assert!(validate(&cov).is_err());
+    }
+
+    #[test]
+    fn test_mahalanobis_scales_with_sigma() {
+        let cov = Matrix3::from_diagonal(&Vector3::new(4.0, 1.0, 1.0));
+        let d = mahalanobis(&Vector3::new(2.0, 0.0, 0.0), &cov).unwrap();
+        assert!((d - 1.0).abs() < 1e-12);
+        assert!(mahalanobis(&Vector3::new(1.0, 0.0, 0.0), &Matrix3::zeros()).is_none());
+    }
+}
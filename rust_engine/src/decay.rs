@@ -0,0 +1,722 @@
+//! Orbital lifetime and re-entry prediction
+//!
+//! Drag is applied in orbit-averaged form: the Gauss equations for the
+//! semi-major axis and eccentricity under a tangential drag acceleration
+//! `-1/2 B rho v^2` are averaged over one revolution by quadrature in
+//! eccentric anomaly, then integrated in time with steps that let the
+//! perigee fall by a small fraction of the local scale height. The
+//! atmosphere is static and non-rotating, so predictions carry the usual
+//! lifetime uncertainty; the window brackets it by scaling the density.
+//!
+//! Long-term lifetime estimates for disposal compliance follow a solar
+//! cycle instead: the F10.7 flux of a `SolarCycle` profile stretches the
+//! thermosphere as in `density::Atmosphere`, updated every step.
+
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use rayon::prelude::*;
+use std::f64::consts::PI;
+
+use crate::density::{self, SpaceWeather};
+use crate::error::OrbitError;
+use crate::constants::{EARTH_RADIUS_KM, MU_EARTH, SECONDS_PER_DAY};
+use crate::parallel;
+use crate::sgp4::MeanElements;
+#[cfg(feature = "python")]
+use crate::sgp4::Sgp4Propagator;
+use crate::Satellite;
+
+const DAYS_PER_YEAR: f64 = 365.25;
+
+/// Drag coefficient assumed when only the area-to-mass ratio is given
+pub const DEFAULT_DRAG_COEFFICIENT: f64 = 2.2;
+
+/// SGP4 reference density for B* (kg/m^2 per Earth radius): B* = B rho0 / 2
+const BSTAR_RHO0: f64 = 0.15696615;
+
+/// Quadrature points per revolution
+const ORBIT_SAMPLES: usize = 64;
+
+/// Fraction of a scale height the perigee may fall per step
+const STEP_FRACTION: f64 = 0.02;
+
+/// Longest integration step (days)
+const MAX_STEP_DAYS: f64 = 5.0;
+
+/// Upper bound on integration steps
+const MAX_STEPS: usize = 2_000_000;
+
+/// Solar activity over a lifetime run, as the F10.7 flux (sfu) by date
+#[derive(Clone, Debug, PartialEq)]
+pub enum SolarCycle {
+    /// The same flux throughout
+    Constant(f64),
+    /// Cosine cycle between `min_f107` and `max_f107`, peaking at `max_jd`
+    Sinusoidal { min_f107: f64, max_f107: f64, period_days: f64, max_jd: f64 },
+    /// Piecewise-linear (Julian date, F10.7) table, held at its end values outside
+    Table(Vec<(f64, f64)>),
+}
+
+impl SolarCycle {
+    /// Eleven-year cycle between 70 and 180 sfu, with the maximum of cycle 25 in October 2024
+    pub fn standard() -> Self {
+        SolarCycle::Sinusoidal { min_f107: 70.0, max_f107: 180.0, period_days: 11.0 * DAYS_PER_YEAR, max_jd: 2460584.5 }
+    }
+
+    /// Flux at a Julian date
+    pub fn f107_at(&self, jd: f64) -> f64 {
+        match self {
+            SolarCycle::Constant(f107) => *f107,
+            SolarCycle::Sinusoidal { min_f107, max_f107, period_days, max_jd } => {
+                let phase = 2.0 * PI * (jd - max_jd) / period_days;
+                min_f107 + (max_f107 - min_f107) * 0.5 * (1.0 + phase.cos())
+            }
+            SolarCycle::Table(points) => {
+                let after = points.partition_point(|&(t, _)| t <= jd);
+                match (after.checked_sub(1).map(|k| points[k]), points.get(after)) {
+                    (Some((t0, f0)), Some(&(t1, f1))) => f0 + (f1 - f0) * (jd - t0) / (t1 - t0),
+                    (Some((_, f)), None) | (None, Some(&(_, f))) => f,
+                    (None, None) => f64::NAN,
+                }
+            }
+        }
+    }
+
+    /// Check the profile can be evaluated and every flux is positive
+    pub fn validate(&self) -> Result<(), String> {
+        let fluxes: Vec<f64> = match self {
+            SolarCycle::Constant(f107) => vec![*f107],
+            SolarCycle::Sinusoidal { min_f107, max_f107, period_days, .. } => {
+                if !(period_days.is_finite() && *period_days > 0.0) {
+                    return Err("The solar cycle period must be positive".to_string());
+                }
+                vec![*min_f107, *max_f107]
+            }
+            SolarCycle::Table(points) => {
+                if points.is_empty() || !points.windows(2).all(|w| w[1].0 > w[0].0) {
+                    return Err("A solar flux table needs dates in increasing order".to_string());
+                }
+                points.iter().map(|p| p.1).collect()
+            }
+        };
+        if fluxes.iter().all(|f| *f > 0.0 && f.is_finite()) {
+            Ok(())
+        } else {
+            Err("Solar flux values must be positive".to_string())
+        }
+    }
+
+    /// Thermosphere stretch at a date, with the flux as both daily and 81-day value and mean Ap
+    fn stretch_at(&self, jd: f64) -> f64 {
+        let f107 = self.f107_at(jd);
+        SpaceWeather { f107, f107_avg: f107, ..SpaceWeather::default() }.altitude_stretch()
+    }
+}
+
+/// Orbit-averaged (da/dt, de/dt) in km/s and 1/s
+///
+/// `drag` converts density (kg/m^3) and speed (km/s) into the drag
+/// acceleration `drag * rho * v^2` (km/s^2): B/2 with B in m^2/kg, times 1e3.
+/// `stretch` is the thermosphere's altitude stretch (1 for mean activity).
+fn averaged_rates(a: f64, e: f64, drag: f64, stretch: f64) -> (f64, f64) {
+    let mut da = 0.0;
+    let mut de = 0.0;
+    for k in 0..ORBIT_SAMPLES {
+        let ecc_anomaly = 2.0 * PI * (k as f64 + 0.5) / ORBIT_SAMPLES as f64;
+        let cos_e = ecc_anomaly.cos();
+        let r = a * (1.0 - e * cos_e);
+        let v = (MU_EARTH * (2.0 / r - 1.0 / a)).sqrt();
+        let cos_nu = (cos_e - e) / (1.0 - e * cos_e);
+        let rho = density::exponential(density::stretched_altitude(r - EARTH_RADIUS_KM, stretch));
+        // time weight dM = (1 - e cos E) dE
+        let weight = 1.0 - e * cos_e;
+        let acceleration = drag * rho * v * v;
+        // Gauss: da/dt = 2 a^2 v f_T / mu, de/dt = 2 (e + cos nu) f_T / v
+        da += weight * (-2.0 * a * a * v * acceleration / MU_EARTH);
+        de += weight * (-2.0 * (e + cos_nu) * acceleration / v);
+    }
+    (da / ORBIT_SAMPLES as f64, de / ORBIT_SAMPLES as f64)
+}
+
+/// March (a, e) under orbit-averaged drag for up to `max_seconds`
+///
+/// `drag` is as in `averaged_rates`; `stretch` gives the thermosphere's
+/// altitude stretch at seconds from the start. Returns the final (a, e),
+/// the elapsed seconds and whether the perigee reached `reentry_altitude_km`.
+fn march(
+    mut a: f64,
+    mut e: f64,
+    drag: f64,
+    stretch: impl Fn(f64) -> f64,
+    reentry_altitude_km: f64,
+    max_seconds: f64,
+) -> (f64, f64, f64, bool) {
+    let mut t = 0.0;
+    for _ in 0..MAX_STEPS {
+        let perigee_altitude = a * (1.0 - e) - EARTH_RADIUS_KM;
+        if perigee_altitude <= reentry_altitude_km {
+            return (a, e, t, true);
+        }
+        if t >= max_seconds {
+            break;
+        }
+        let factor = stretch(t);
+        let (da, de) = averaged_rates(a, e, drag, factor);
+        let perigee_rate = da * (1.0 - e) - a * de;
+        if perigee_rate >= 0.0 {
+            // no drag (zero coefficient): the orbit never decays
+            return (a, e, max_seconds, false);
+        }
+        // the stretched profile's scale height at the perigee
+        let scale_height = density::scale_height(density::stretched_altitude(perigee_altitude, factor)) / factor;
+        let dt = (STEP_FRACTION * scale_height / -perigee_rate)
+            .min(MAX_STEP_DAYS * SECONDS_PER_DAY)
+            .min(max_seconds - t);
+
+        // midpoint step
+        let (a_mid, e_mid) = (a + 0.5 * dt * da, (e + 0.5 * dt * de).max(0.0));
+        let (da_mid, de_mid) = averaged_rates(a_mid, e_mid, drag, stretch(t + 0.5 * dt));
+        a += dt * da_mid;
+        e = (e + dt * de_mid).max(0.0);
+        t += dt;
+    }
+    (a, e, t, false)
+}
+
+/// Days until the perigee altitude reaches `reentry_altitude_km`, or None beyond `max_days`
+///
+/// `ballistic_coefficient` is Cd * A / m (m^2/kg); `density_scale` multiplies the atmosphere.
+pub fn lifetime_days(
+    semi_major_axis_km: f64,
+    eccentricity: f64,
+    ballistic_coefficient: f64,
+    density_scale: f64,
+    reentry_altitude_km: f64,
+    max_days: f64,
+) -> Option<f64> {
+    let drag = 0.5 * ballistic_coefficient * density_scale * 1.0e3;
+    let (_, _, t, reentered) = march(
+        semi_major_axis_km,
+        eccentricity.max(0.0),
+        drag,
+        |_| 1.0,
+        reentry_altitude_km,
+        max_days * SECONDS_PER_DAY,
+    );
+    reentered.then_some(t / SECONDS_PER_DAY)
+}
+
+/// As `lifetime_days`, with the atmosphere following `cycle` from `epoch_jd` on
+pub fn lifetime_days_in_cycle(
+    semi_major_axis_km: f64,
+    eccentricity: f64,
+    ballistic_coefficient: f64,
+    epoch_jd: f64,
+    cycle: &SolarCycle,
+    reentry_altitude_km: f64,
+    max_days: f64,
+) -> Option<f64> {
+    let drag = 0.5 * ballistic_coefficient * 1.0e3;
+    let stretch = |t: f64| cycle.stretch_at(epoch_jd + t / SECONDS_PER_DAY);
+    let (_, _, t, reentered) = march(
+        semi_major_axis_km,
+        eccentricity.max(0.0),
+        drag,
+        stretch,
+        reentry_altitude_km,
+        max_days * SECONDS_PER_DAY,
+    );
+    reentered.then_some(t / SECONDS_PER_DAY)
+}
+
+/// Semi-major axis (km) and eccentricity after `days` of drag, or None once re-entered
+pub fn decay_forward(
+    semi_major_axis_km: f64,
+    eccentricity: f64,
+    ballistic_coefficient: f64,
+    reentry_altitude_km: f64,
+    days: f64,
+) -> Option<(f64, f64)> {
+    let drag = 0.5 * ballistic_coefficient * 1.0e3;
+    let (a, e, _, reentered) =
+        march(semi_major_axis_km, eccentricity.max(0.0), drag, |_| 1.0, reentry_altitude_km, days * SECONDS_PER_DAY);
+    (!reentered).then_some((a, e))
+}
+
+/// Mean orbit of a state or element set, as used for lifetime estimates
+pub struct MeanOrbit {
+    pub norad_id: i32,
+    pub semi_major_axis_km: f64,
+    pub eccentricity: f64,
+    pub epoch_jd: Option<f64>,
+    pub implied_ballistic_coefficient: Option<f64>, // from B*, for element sets
+}
+
+impl MeanOrbit {
+    /// Osculating two-body orbit of a state vector
+    pub fn from_satellite(sat: &Satellite) -> Result<Self, OrbitError> {
+        let (perigee, apogee) = sat.perigee_apogee();
+        if !apogee.is_finite() {
+            return Err(OrbitError::InvalidState("State is not on a bound orbit".to_string()));
+        }
+        Ok(MeanOrbit {
+            norad_id: sat.norad_id,
+            semi_major_axis_km: 0.5 * (perigee + apogee),
+            eccentricity: (apogee - perigee) / (apogee + perigee),
+            epoch_jd: sat.epoch.map(|e| e.jd_utc()),
+            implied_ballistic_coefficient: None,
+        })
+    }
+
+    /// Mean orbit of a TLE, with the ballistic coefficient implied by B*
+    pub fn from_elements(elements: &MeanElements) -> Self {
+        let n = elements.mean_motion_rev_per_day * 2.0 * PI / SECONDS_PER_DAY;
+        MeanOrbit {
+            norad_id: elements.norad_id,
+            semi_major_axis_km: (MU_EARTH / (n * n)).cbrt(),
+            eccentricity: elements.eccentricity,
+            epoch_jd: Some(elements.epoch_jd),
+            implied_ballistic_coefficient: Some(2.0 * elements.bstar / BSTAR_RHO0),
+        }
+    }
+
+    /// From a `Satellite` (osculating two-body orbit), `MeanElements` or `Sgp4Propagator`
+    #[cfg(feature = "python")]
+    pub(crate) fn extract(obj: &PyAny) -> PyResult<Self> {
+        if let Ok(sat) = obj.extract::<PyRef<Satellite>>() {
+            return Ok(MeanOrbit::from_satellite(&sat)?);
+        }
+        if let Ok(elements) = obj.extract::<PyRef<MeanElements>>() {
+            Ok(MeanOrbit::from_elements(&elements))
+        } else if let Ok(propagator) = obj.extract::<PyRef<Sgp4Propagator>>() {
+            Ok(MeanOrbit::from_elements(&propagator.elements))
+        } else {
+            Err(pyo3::exceptions::PyTypeError::new_err(
+                "Expected a Satellite, MeanElements or Sgp4Propagator",
+            ))
+        }
+    }
+}
+
+/// Estimated re-entry of one object
+#[cfg_attr(feature = "python", pyclass(get_all))]
+#[derive(Clone, Debug)]
+pub struct ReentryPrediction {
+    pub norad_id: i32,
+    pub days: Option<f64>,          // nominal lifetime from the epoch; None beyond the search horizon
+    pub earliest_days: Option<f64>, // with the density scaled up by the uncertainty
+    pub latest_days: Option<f64>,   // with the density scaled down
+    pub epoch_jd: Option<f64>,
+}
+
+impl ReentryPrediction {
+    /// Nominal re-entry date (Julian date, UTC), when the epoch is known
+    pub fn decay_jd(&self) -> Option<f64> {
+        Some(self.epoch_jd? + self.days?)
+    }
+
+    /// Window (earliest_jd, latest_jd); latest is None when beyond the horizon
+    pub fn window_jd(&self) -> Option<(f64, Option<f64>)> {
+        let epoch = self.epoch_jd?;
+        Some((epoch + self.earliest_days?, self.latest_days.map(|days| epoch + days)))
+    }
+
+    /// True if re-entry may happen within `days` (earliest estimate)
+    pub fn may_decay_within(&self, days: f64) -> bool {
+        self.earliest_days.is_some_and(|earliest| earliest <= days)
+    }
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl ReentryPrediction {
+    /// Nominal re-entry date (Julian date, UTC), when the epoch is known
+    #[getter]
+    #[pyo3(name = "decay_jd")]
+    fn py_decay_jd(&self) -> Option<f64> {
+        self.decay_jd()
+    }
+
+    /// Window (earliest_jd, latest_jd); latest is None when beyond the horizon
+    #[getter]
+    #[pyo3(name = "window_jd")]
+    fn py_window_jd(&self) -> Option<(f64, Option<f64>)> {
+        self.window_jd()
+    }
+
+    /// True if re-entry may happen within `days` (earliest estimate)
+    #[pyo3(name = "may_decay_within")]
+    fn py_may_decay_within(&self, days: f64) -> bool {
+        self.may_decay_within(days)
+    }
+
+    fn __repr__(&self) -> String {
+        match self.days {
+            Some(days) => format!("ReentryPrediction({}, {:.1} days)", self.norad_id, days),
+            None => format!("ReentryPrediction({}, beyond horizon)", self.norad_id),
+        }
+    }
+}
+
+/// Predict re-entry of a mean orbit
+///
+/// `ballistic_coefficient` is Cd * A / m in m^2/kg; for TLEs it defaults to
+/// the value implied by B*. The uncertainty window rescales the density by
+/// `1 +- density_uncertainty`. Re-entry is declared when the perigee falls
+/// to `reentry_altitude_km`; lifetimes beyond `max_days` are reported as None.
+pub fn predict_reentry(
+    orbit: &MeanOrbit,
+    ballistic_coefficient: Option<f64>,
+    density_uncertainty: f64,
+    reentry_altitude_km: f64,
+    max_days: f64,
+) -> Result<ReentryPrediction, OrbitError> {
+    if !(0.0..1.0).contains(&density_uncertainty) {
+        return Err(OrbitError::InvalidInput("density_uncertainty must be in [0, 1)".to_string()));
+    }
+    let coefficient = ballistic_coefficient.or(orbit.implied_ballistic_coefficient).ok_or_else(|| {
+        OrbitError::InvalidInput("ballistic_coefficient is required for state vectors".to_string())
+    })?;
+    if !(coefficient >= 0.0 && coefficient.is_finite()) {
+        return Err(OrbitError::InvalidInput(
+            "ballistic_coefficient must be non-negative (a negative B* gives no drag estimate)".to_string(),
+        ));
+    }
+
+    let (a, e) = (orbit.semi_major_axis_km, orbit.eccentricity);
+    let lifetime = |scale: f64| lifetime_days(a, e, coefficient, scale, reentry_altitude_km, max_days);
+    Ok(ReentryPrediction {
+        norad_id: orbit.norad_id,
+        days: lifetime(1.0),
+        earliest_days: lifetime(1.0 + density_uncertainty),
+        latest_days: lifetime(1.0 - density_uncertainty),
+        epoch_jd: orbit.epoch_jd,
+    })
+}
+
+/// Predict re-entry from a state (`Satellite`) or TLE (`MeanElements` or `Sgp4Propagator`)
+///
+/// `ballistic_coefficient` is Cd * A / m in m^2/kg; for TLEs it defaults to
+/// the value implied by B*. The uncertainty window rescales the density by
+/// `1 +- density_uncertainty`. Re-entry is declared when the perigee falls
+/// to `reentry_altitude_km`; lifetimes beyond `max_days` are reported as None.
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(name = "predict_reentry", signature = (
+    obj,
+    ballistic_coefficient = None,
+    density_uncertainty = 0.3,
+    reentry_altitude_km = 120.0,
+    max_days = 36525.0
+))]
+pub fn py_predict_reentry(
+    obj: &PyAny,
+    ballistic_coefficient: Option<f64>,
+    density_uncertainty: f64,
+    reentry_altitude_km: f64,
+    max_days: f64,
+) -> PyResult<ReentryPrediction> {
+    let orbit = MeanOrbit::extract(obj)?;
+    let prediction = obj.py().allow_threads(|| {
+        predict_reentry(&orbit, ballistic_coefficient, density_uncertainty, reentry_altitude_km, max_days)
+    })?;
+    Ok(prediction)
+}
+
+/// Estimated orbital lifetime of one object, for disposal compliance
+#[cfg_attr(feature = "python", pyclass(get_all))]
+#[derive(Clone, Debug)]
+pub struct OrbitLifetime {
+    pub norad_id: i32,
+    pub epoch_jd: f64,
+    pub days: Option<f64>,          // None beyond the search horizon
+    pub ballistic_coefficient: f64, // Cd * A / m, m^2/kg
+}
+
+impl OrbitLifetime {
+    /// Lifetime in Julian years, None beyond the search horizon
+    pub fn years(&self) -> Option<f64> {
+        self.days.map(|days| days / DAYS_PER_YEAR)
+    }
+
+    /// Estimated decay date (Julian date, UTC)
+    pub fn decay_jd(&self) -> Option<f64> {
+        self.days.map(|days| self.epoch_jd + days)
+    }
+
+    /// True if the object decays within `years` of the epoch
+    pub fn complies(&self, years: f64) -> bool {
+        self.days.is_some_and(|days| days <= years * DAYS_PER_YEAR)
+    }
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl OrbitLifetime {
+    /// Lifetime in Julian years, None beyond the search horizon
+    #[getter]
+    #[pyo3(name = "years")]
+    fn py_years(&self) -> Option<f64> {
+        self.years()
+    }
+
+    /// Estimated decay date (Julian date, UTC)
+    #[getter]
+    #[pyo3(name = "decay_jd")]
+    fn py_decay_jd(&self) -> Option<f64> {
+        self.decay_jd()
+    }
+
+    /// True if the object decays within `years` of the epoch
+    #[pyo3(name = "complies")]
+    fn py_complies(&self, years: f64) -> bool {
+        self.complies(years)
+    }
+
+    /// The 25-year post-mission disposal rule
+    #[getter]
+    fn compliant_25_year(&self) -> bool {
+        self.complies(25.0)
+    }
+
+    /// The 5-year post-mission disposal rule
+    #[getter]
+    fn compliant_5_year(&self) -> bool {
+        self.complies(5.0)
+    }
+
+    fn __repr__(&self) -> String {
+        match self.days {
+            Some(days) => format!("OrbitLifetime({}, {:.2} years)", self.norad_id, days / DAYS_PER_YEAR),
+            None => format!("OrbitLifetime({}, beyond horizon)", self.norad_id),
+        }
+    }
+}
+
+/// Lifetime inputs checked up front, then estimated (in parallel for a batch)
+struct LifetimeCase {
+    norad_id: i32,
+    semi_major_axis_km: f64,
+    eccentricity: f64,
+    epoch_jd: f64,
+    ballistic_coefficient: f64,
+}
+
+impl LifetimeCase {
+    fn new(orbit: &MeanOrbit, area_to_mass: Option<f64>, drag_coefficient: f64) -> Result<Self, OrbitError> {
+        let coefficient = area_to_mass
+            .map(|ratio| drag_coefficient * ratio)
+            .or(orbit.implied_ballistic_coefficient)
+            .ok_or_else(|| OrbitError::InvalidInput("area_to_mass is required for state vectors".to_string()))?;
+        if !(coefficient >= 0.0 && coefficient.is_finite()) {
+            return Err(OrbitError::InvalidInput(
+                "The ballistic coefficient must be non-negative (a negative B* gives no drag estimate)".to_string(),
+            ));
+        }
+        let epoch_jd = orbit.epoch_jd.ok_or_else(|| {
+            OrbitError::InvalidInput("An epoch is required to follow the solar cycle".to_string())
+        })?;
+        Ok(LifetimeCase {
+            norad_id: orbit.norad_id,
+            semi_major_axis_km: orbit.semi_major_axis_km,
+            eccentricity: orbit.eccentricity,
+            epoch_jd,
+            ballistic_coefficient: coefficient,
+        })
+    }
+
+    fn estimate(&self, cycle: &SolarCycle, reentry_altitude_km: f64, max_days: f64) -> OrbitLifetime {
+        OrbitLifetime {
+            norad_id: self.norad_id,
+            epoch_jd: self.epoch_jd,
+            days: lifetime_days_in_cycle(
+                self.semi_major_axis_km,
+                self.eccentricity,
+                self.ballistic_coefficient,
+                self.epoch_jd,
+                cycle,
+                reentry_altitude_km,
+                max_days,
+            ),
+            ballistic_coefficient: self.ballistic_coefficient,
+        }
+    }
+}
+
+/// Orbital lifetime of a mean orbit through a solar cycle
+///
+/// The ballistic coefficient is `drag_coefficient * area_to_mass` (m^2/kg);
+/// for TLEs it defaults to the value implied by B*. Lifetimes beyond
+/// `max_years` come back as None.
+pub fn orbit_lifetime(
+    orbit: &MeanOrbit,
+    area_to_mass: Option<f64>,
+    cycle: &SolarCycle,
+    drag_coefficient: f64,
+    reentry_altitude_km: f64,
+    max_years: f64,
+) -> Result<OrbitLifetime, OrbitError> {
+    cycle.validate().map_err(OrbitError::InvalidInput)?;
+    let case = LifetimeCase::new(orbit, area_to_mass, drag_coefficient)?;
+    Ok(case.estimate(cycle, reentry_altitude_km, max_years * DAYS_PER_YEAR))
+}
+
+/// `orbit_lifetime` over a catalogue in parallel, with one area-to-mass ratio (or None) per orbit
+pub fn orbit_lifetime_batch(
+    orbits: &[MeanOrbit],
+    area_to_mass: &[Option<f64>],
+    cycle: &SolarCycle,
+    drag_coefficient: f64,
+    reentry_altitude_km: f64,
+    max_years: f64,
+) -> Result<Vec<OrbitLifetime>, OrbitError> {
+    cycle.validate().map_err(OrbitError::InvalidInput)?;
+    if area_to_mass.len() != orbits.len() {
+        return Err(OrbitError::InvalidInput("area_to_mass needs one ratio per object".to_string()));
+    }
+    let cases: Vec<LifetimeCase> = orbits
+        .iter()
+        .zip(area_to_mass)
+        .map(|(orbit, &ratio)| LifetimeCase::new(orbit, ratio, drag_coefficient))
+        .collect::<Result<_, _>>()?;
+    let max_days = max_years * DAYS_PER_YEAR;
+    Ok(parallel::install(|| cases.par_iter().map(|case| case.estimate(cycle, reentry_altitude_km, max_days)).collect()))
+}
+
+/// `solar_cycle_profile` as a constant F10.7, a [(jd, f107)] table, or None for the standard cycle
+#[cfg(feature = "python")]
+fn solar_cycle(profile: Option<&PyAny>) -> PyResult<SolarCycle> {
+    Ok(match profile {
+        None => SolarCycle::standard(),
+        Some(profile) => match profile.extract::<f64>() {
+            Ok(f107) => SolarCycle::Constant(f107),
+            Err(_) => SolarCycle::Table(profile.extract::<Vec<(f64, f64)>>()?),
+        },
+    })
+}
+
+/// Estimate the orbital lifetime of a state (`Satellite`) or TLE (`MeanElements` or `Sgp4Propagator`)
+///
+/// The ballistic coefficient is `drag_coefficient * area_to_mass` (m^2/kg);
+/// for TLEs it defaults to the value implied by B*. The atmosphere follows
+/// `solar_cycle_profile`: a constant F10.7, a [(jd, f107)] table, or None for
+/// a standard eleven-year cycle. Lifetimes beyond `max_years` come back as None.
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(name = "orbit_lifetime", signature = (
+    obj,
+    area_to_mass = None,
+    solar_cycle_profile = None,
+    drag_coefficient = DEFAULT_DRAG_COEFFICIENT,
+    reentry_altitude_km = 120.0,
+    max_years = 100.0
+))]
+pub fn py_orbit_lifetime(
+    obj: &PyAny,
+    area_to_mass: Option<f64>,
+    solar_cycle_profile: Option<&PyAny>,
+    drag_coefficient: f64,
+    reentry_altitude_km: f64,
+    max_years: f64,
+) -> PyResult<OrbitLifetime> {
+    let cycle = solar_cycle(solar_cycle_profile)?;
+    let orbit = MeanOrbit::extract(obj)?;
+    let lifetime = obj.py().allow_threads(|| {
+        orbit_lifetime(&orbit, area_to_mass, &cycle, drag_coefficient, reentry_altitude_km, max_years)
+    })?;
+    Ok(lifetime)
+}
+
+/// `orbit_lifetime` over a catalogue in parallel
+///
+/// `area_to_mass` is None (B*-implied coefficients), one ratio for every
+/// object, or a list with one ratio per object.
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(name = "orbit_lifetime_batch", signature = (
+    objects,
+    area_to_mass = None,
+    solar_cycle_profile = None,
+    drag_coefficient = DEFAULT_DRAG_COEFFICIENT,
+    reentry_altitude_km = 120.0,
+    max_years = 100.0
+))]
+pub fn py_orbit_lifetime_batch(
+    py: Python<'_>,
+    objects: Vec<&PyAny>,
+    area_to_mass: Option<&PyAny>,
+    solar_cycle_profile: Option<&PyAny>,
+    drag_coefficient: f64,
+    reentry_altitude_km: f64,
+    max_years: f64,
+) -> PyResult<Vec<OrbitLifetime>> {
+    let cycle = solar_cycle(solar_cycle_profile)?;
+    let ratios: Vec<Option<f64>> = match area_to_mass {
+        None => vec![None; objects.len()],
+        Some(ratios) => match ratios.extract::<f64>() {
+            Ok(ratio) => vec![Some(ratio); objects.len()],
+            Err(_) => ratios.extract::<Vec<f64>>()?.into_iter().map(Some).collect(),
+        },
+    };
+    let orbits: Vec<MeanOrbit> = objects.into_iter().map(MeanOrbit::extract).collect::<PyResult<_>>()?;
+    let lifetimes = py.allow_threads(|| {
+        orbit_lifetime_batch(&orbits, &ratios, &cycle, drag_coefficient, reentry_altitude_km, max_years)
+    })?;
+    Ok(lifetimes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lifetime_scales_inversely_with_drag() {
+        let a = EARTH_RADIUS_KM + 300.0;
+        let nominal = lifetime_days(a, 0.0, 0.01, 1.0, 120.0, 3650.0).unwrap();
+        // a 300 km, 0.01 m^2/kg object lasts on the order of a month
+        assert!((10.0..120.0).contains(&nominal), "{}", nominal);
+        let doubled = lifetime_days(a, 0.0, 0.02, 1.0, 120.0, 3650.0).unwrap();
+        assert!((nominal / doubled - 2.0).abs() < 0.05);
+        assert!(lifetime_days(a, 0.0, 0.0, 1.0, 120.0, 3650.0).is_none());
+    }
+
+    #[test]
+    fn test_eccentric_orbit_circularises() {
+        let (perigee, apogee) = (EARTH_RADIUS_KM + 200.0, EARTH_RADIUS_KM + 2000.0);
+        let a = 0.5 * (perigee + apogee);
+        let e = (apogee - perigee) / (apogee + perigee);
+        let (da, de) = averaged_rates(a, e, 0.5 * 0.01 * 1.0e3, 1.0);
+        assert!(da < 0.0 && de < 0.0);
+        // lasts longer than a circular orbit at its perigee altitude
+        let eccentric = lifetime_days(a, e, 0.01, 1.0, 120.0, 36525.0).unwrap();
+        let circular = lifetime_days(perigee, 0.0, 0.01, 1.0, 120.0, 36525.0).unwrap();
+        assert!(eccentric > circular);
+    }
+
+    #[test]
+    fn test_solar_activity_shortens_lifetime() {
+        let a = EARTH_RADIUS_KM + 500.0;
+        let epoch = 2460676.5;
+        // mean activity leaves the static profile unstretched
+        let mean = SpaceWeather::default().f107_avg;
+        let static_days = lifetime_days(a, 0.0, 0.02, 1.0, 120.0, 36525.0).unwrap();
+        let mean_days = lifetime_days_in_cycle(a, 0.0, 0.02, epoch, &SolarCycle::Constant(mean), 120.0, 36525.0);
+        assert!((mean_days.unwrap() / static_days - 1.0).abs() < 1e-6);
+
+        let quiet = lifetime_days_in_cycle(a, 0.0, 0.02, epoch, &SolarCycle::Constant(70.0), 120.0, 36525.0).unwrap();
+        let active = lifetime_days_in_cycle(a, 0.0, 0.02, epoch, &SolarCycle::Constant(200.0), 120.0, 36525.0);
+        assert!(active.unwrap() < static_days && static_days < quiet);
+        let cycle = lifetime_days_in_cycle(a, 0.0, 0.02, epoch, &SolarCycle::standard(), 120.0, 36525.0).unwrap();
+        assert!(active.unwrap() < cycle && cycle < quiet);
+    }
+
+    #[test]
+    fn test_flux_table_interpolates() {
+        let table = SolarCycle::Table(vec![(2460000.5, 100.0), (2460010.5, 200.0)]);
+        assert_eq!(table.f107_at(2460005.5), 150.0);
+        assert_eq!(table.f107_at(2459000.5), 100.0);
+        assert_eq!(table.f107_at(2461000.5), 200.0);
+        assert!(SolarCycle::Table(vec![(2.0, 100.0), (1.0, 100.0)]).validate().is_err());
+        assert!((SolarCycle::standard().f107_at(2460584.5) - 180.0).abs() < 1e-9);
+    }
+}
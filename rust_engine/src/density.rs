@@ -0,0 +1,304 @@
+//! Atmospheric density models
+//!
+//! * Exponential: piecewise-exponential static model of Vallado
+//!   (Fundamentals of Astrodynamics, table 8-4), derived from CIRA-72
+//! * Harris-Priester: minimum/maximum density tables of Montenbruck & Gill
+//!   (Satellite Orbits, table 3.8) blended by the angle to the diurnal
+//!   bulge, which lags the Sun by 30 degrees in right ascension
+//!
+//! Both tables describe mean solar activity. Space weather enters through
+//! the Jacchia exospheric temperature: above 120 km the altitude profile is
+//! stretched by T_ref / T, so a hotter thermosphere is denser at a given
+//! height. This is a first-order correction, not a thermospheric model.
+//! Positions are inertial (km); altitudes are WGS-84 geodetic.
+
+use nalgebra::{Rotation3, Vector3};
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+
+use crate::{celestial, frames};
+
+/// Exponential model: (base altitude km, base density kg/m^3, scale height km)
+#[rustfmt::skip]
+const BANDS: [(f64, f64, f64); 28] = [
+    (0.0, 1.225, 7.249),        (25.0, 3.899e-2, 6.349),    (30.0, 1.774e-2, 6.682),
+    (40.0, 3.972e-3, 7.554),    (50.0, 1.057e-3, 8.382),    (60.0, 3.206e-4, 7.714),
+    (70.0, 8.770e-5, 6.549),    (80.0, 1.905e-5, 5.799),    (90.0, 3.396e-6, 5.382),
+    (100.0, 5.297e-7, 5.877),   (110.0, 9.661e-8, 7.263),   (120.0, 2.438e-8, 9.473),
+    (130.0, 8.484e-9, 12.636),  (140.0, 3.845e-9, 16.149),  (150.0, 2.070e-9, 22.523),
+    (180.0, 5.464e-10, 29.740), (200.0, 2.789e-10, 37.105), (250.0, 7.248e-11, 45.546),
+    (300.0, 2.418e-11, 53.628), (350.0, 9.518e-12, 53.298), (400.0, 3.725e-12, 58.515),
+    (450.0, 1.585e-12, 60.828), (500.0, 6.967e-13, 63.822), (600.0, 1.454e-13, 71.835),
+    (700.0, 3.614e-14, 88.667), (800.0, 1.170e-14, 124.64), (900.0, 5.245e-15, 181.05),
+    (1000.0, 3.019e-15, 268.00),
+];
+
+/// Harris-Priester: (altitude km, minimum density g/km^3, maximum density g/km^3)
+#[rustfmt::skip]
+const HARRIS_PRIESTER: [(f64, f64, f64); 50] = [
+    (100.0, 497400.0, 497400.0), (120.0, 24900.0, 24900.0),   (130.0, 8377.0, 8710.0),
+    (140.0, 3899.0, 4059.0),     (150.0, 2122.0, 2215.0),     (160.0, 1263.0, 1344.0),
+    (170.0, 800.8, 875.8),       (180.0, 528.3, 601.0),       (190.0, 361.7, 429.7),
+    (200.0, 255.7, 316.2),       (210.0, 183.9, 239.6),       (220.0, 134.1, 185.3),
+    (230.0, 99.49, 145.5),       (240.0, 74.88, 115.7),       (250.0, 57.09, 93.08),
+    (260.0, 44.03, 75.55),       (270.0, 34.30, 61.82),       (280.0, 26.97, 50.95),
+    (290.0, 21.39, 42.26),       (300.0, 17.08, 35.26),       (320.0, 10.99, 25.11),
+    (340.0, 7.214, 18.19),       (360.0, 4.824, 13.37),       (380.0, 3.274, 9.955),
+    (400.0, 2.249, 7.492),       (420.0, 1.558, 5.684),       (440.0, 1.091, 4.355),
+    (460.0, 0.7701, 3.362),      (480.0, 0.5474, 2.612),      (500.0, 0.3916, 2.042),
+    (520.0, 0.2819, 1.605),      (540.0, 0.2042, 1.267),      (560.0, 0.1488, 1.005),
+    (580.0, 0.1092, 0.7997),     (600.0, 0.08070, 0.6390),    (620.0, 0.06012, 0.5123),
+    (640.0, 0.04519, 0.4121),    (660.0, 0.03430, 0.3325),    (680.0, 0.02632, 0.2691),
+    (700.0, 0.02043, 0.2185),    (720.0, 0.01607, 0.1779),    (740.0, 0.01281, 0.1452),
+    (760.0, 0.01036, 0.1190),    (780.0, 0.008496, 0.09776),  (800.0, 0.007069, 0.08059),
+    (840.0, 0.004680, 0.05741),  (880.0, 0.003200, 0.04210),  (920.0, 0.002210, 0.03130),
+    (960.0, 0.001560, 0.02360),  (1000.0, 0.001150, 0.01810),
+];
+
+/// Harris-Priester cosine exponent (2 for low inclinations up to 6 for polar orbits)
+const HARRIS_PRIESTER_EXPONENT: f64 = 4.0;
+
+/// Lag of the diurnal bulge behind the Sun in right ascension (deg)
+const BULGE_LAG_DEG: f64 = 30.0;
+
+/// Altitude above which space weather stretches the profile (km)
+const THERMOSPHERE_BASE_KM: f64 = 120.0;
+
+/// Conditions the density tables represent
+const MEAN_WEATHER: SpaceWeather = SpaceWeather { f107: 150.0, f107_avg: 150.0, ap: 15.0 };
+
+/// Density model selection
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DensityModel {
+    Exponential,
+    HarrisPriester,
+}
+
+impl std::str::FromStr for DensityModel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "exponential" => Ok(DensityModel::Exponential),
+            "harris-priester" | "harris_priester" | "hp" => Ok(DensityModel::HarrisPriester),
+            _ => Err(format!(
+                "Unknown density model '{}' (expected 'exponential' or 'harris-priester')",
+                s
+            )),
+        }
+    }
+}
+
+/// Solar and geomagnetic activity indices
+#[cfg_attr(feature = "python", pyclass(get_all, set_all))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SpaceWeather {
+    pub f107: f64, // daily 10.7 cm solar radio flux (sfu)
+    pub f107_avg: f64, // 81-day centred average of F10.7 (sfu)
+    pub ap: f64, // daily planetary geomagnetic index
+}
+
+impl Default for SpaceWeather {
+    fn default() -> Self {
+        MEAN_WEATHER
+    }
+}
+
+impl SpaceWeather {
+    /// Exospheric temperature (K): Jacchia 1970 solar terms plus the Jacchia 1971 Ap term
+    pub fn exospheric_temperature(&self) -> f64 {
+        let solar = 379.0 + 3.24 * self.f107_avg + 1.3 * (self.f107 - self.f107_avg);
+        let geomagnetic = self.ap + 100.0 * (1.0 - (-0.08 * self.ap).exp());
+        solar + geomagnetic
+    }
+
+    /// Factor on heights above 120 km: mean exospheric temperature over this one
+    pub fn altitude_stretch(&self) -> f64 {
+        MEAN_WEATHER.exospheric_temperature() / self.exospheric_temperature()
+    }
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl SpaceWeather {
+    /// `f107_avg` defaults to `f107`
+    #[new]
+    #[pyo3(signature = (f107 = 150.0, f107_avg = None, ap = 15.0))]
+    fn new(f107: f64, f107_avg: Option<f64>, ap: f64) -> PyResult<Self> {
+        let f107_avg = f107_avg.unwrap_or(f107);
+        if !(f107 > 0.0 && f107_avg > 0.0 && ap >= 0.0 && (f107 + f107_avg + ap).is_finite()) {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "F10.7 values must be positive and Ap non-negative"
+            ));
+        }
+        Ok(SpaceWeather { f107, f107_avg, ap })
+    }
+
+    fn __repr__(&self) -> String {
+        format!("SpaceWeather(f107={}, f107_avg={}, ap={})", self.f107, self.f107_avg, self.ap)
+    }
+}
+
+fn band(altitude_km: f64) -> (f64, f64, f64) {
+    let index = BANDS.partition_point(|&(base, _, _)| base <= altitude_km);
+    BANDS[index.saturating_sub(1)]
+}
+
+/// Exponential-model density (kg/m^3) at a geometric altitude (km); altitudes below 0 use the sea-level band
+pub fn exponential(altitude_km: f64) -> f64 {
+    let (base, rho0, scale_height) = band(altitude_km);
+    rho0 * (-(altitude_km - base) / scale_height).exp()
+}
+
+/// Altitude in the mean-activity tables equivalent to `altitude_km` under an altitude stretch
+pub fn stretched_altitude(altitude_km: f64, stretch: f64) -> f64 {
+    if altitude_km > THERMOSPHERE_BASE_KM {
+        THERMOSPHERE_BASE_KM + (altitude_km - THERMOSPHERE_BASE_KM) * stretch
+    } else {
+        altitude_km
+    }
+}
+
+/// Local density scale height of the exponential model (km)
+pub fn scale_height(altitude_km: f64) -> f64 {
+    band(altitude_km).2
+}
+
+/// Harris-Priester density (kg/m^3); `cos_psi` is the cosine of the angle to the bulge apex
+///
+/// Outside 100-1000 km the end bands are extrapolated.
+pub fn harris_priester(altitude_km: f64, cos_psi: f64) -> f64 {
+    let upper = HARRIS_PRIESTER
+        .partition_point(|&(base, _, _)| base <= altitude_km)
+        .clamp(1, HARRIS_PRIESTER.len() - 1);
+    let (h0, min0, max0) = HARRIS_PRIESTER[upper - 1];
+    let (h1, min1, max1) = HARRIS_PRIESTER[upper];
+    let fraction = (altitude_km - h0) / (h1 - h0);
+    let rho_min = min0 * (min1 / min0).powf(fraction);
+    let rho_max = max0 * (max1 / max0).powf(fraction);
+    // cos^n(psi / 2) = ((1 + cos psi) / 2)^(n / 2)
+    let weight = (0.5 * (1.0 + cos_psi)).max(0.0).powf(0.5 * HARRIS_PRIESTER_EXPONENT);
+    // g/km^3 -> kg/m^3
+    1.0e-12 * (rho_min + (rho_max - rho_min) * weight)
+}
+
+/// A density model configured for one epoch and set of space weather indices
+#[derive(Clone, Copy, Debug)]
+pub struct Atmosphere {
+    pub model: DensityModel,
+    pub weather: SpaceWeather,
+    bulge: Vector3<f64>,
+    stretch: f64,
+}
+
+impl Atmosphere {
+    /// `jd` (UTC) places the diurnal bulge, which Harris-Priester requires
+    ///
+    /// The bulge direction is frozen at `jd`; the Sun moves about a degree
+    /// a day, which is negligible over typical propagation arcs.
+    pub fn new(model: DensityModel, weather: SpaceWeather, jd: Option<f64>) -> Result<Self, String> {
+        let bulge = match (model, jd) {
+            (DensityModel::HarrisPriester, None) => {
+                return Err("Harris-Priester density needs an epoch (Julian date)".to_string())
+            }
+            (_, Some(jd)) => {
+                Rotation3::from_axis_angle(&Vector3::z_axis(), BULGE_LAG_DEG.to_radians()) * celestial::sun_position(jd).normalize()
+            }
+            (_, None) => Vector3::zeros(),
+        };
+        Ok(Atmosphere {
+            model,
+            weather,
+            bulge,
+            stretch: weather.altitude_stretch(),
+        })
+    }
+
+    /// Build from Python-style options
+    #[cfg(feature = "python")]
+    pub fn parse(model: &str, weather: Option<SpaceWeather>, jd: Option<f64>) -> PyResult<Self> {
+        let model: DensityModel = model.parse().map_err(pyo3::exceptions::PyValueError::new_err)?;
+        Atmosphere::new(model, weather.unwrap_or_default(), jd).map_err(pyo3::exceptions::PyValueError::new_err)
+    }
+
+    /// Density (kg/m^3) at an inertial position (km)
+    pub fn density(&self, r: &Vector3<f64>) -> f64 {
+        let (_, _, altitude) = frames::ecef_to_geodetic_rad(r);
+        let altitude = stretched_altitude(altitude, self.stretch);
+        match self.model {
+            DensityModel::Exponential => exponential(altitude),
+            DensityModel::HarrisPriester => harris_priester(altitude, self.bulge.dot(&r.normalize())),
+        }
+    }
+}
+
+/// Atmospheric density (kg/m^3) at an inertial position (km)
+///
+/// `model` is "exponential" or "harris-priester"; the latter needs the
+/// epoch `jd` (UTC) to place the diurnal bulge. `space_weather` defaults to
+/// the mean conditions of the tables (F10.7 = 150, Ap = 15).
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(signature = (position, jd = None, model = "exponential", space_weather = None))]
+pub fn atmospheric_density(
+    position: Vec<f64>,
+    jd: Option<f64>,
+    model: &str,
+    space_weather: Option<SpaceWeather>,
+) -> PyResult<f64> {
+    if position.len() != 3 {
+        return Err(pyo3::exceptions::PyValueError::new_err("position must have 3 components"));
+    }
+    let atmosphere = Atmosphere::parse(model, space_weather, jd)?;
+    Ok(atmosphere.density(&Vector3::from_column_slice(&position)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::EARTH_RADIUS_KM;
+
+    #[test]
+    fn test_exponential_is_continuous_and_decreasing() {
+        assert!((exponential(0.0) - 1.225).abs() < 1e-12);
+        assert!((exponential(400.0) - 3.725e-12).abs() < 1e-24);
+        let mut previous = exponential(0.0);
+        for step in 1..1200 {
+            let rho = exponential(step as f64);
+            assert!(rho < previous);
+            previous = rho;
+        }
+        // band edges match to a few percent
+        for &(base, rho0, _) in &BANDS[1..] {
+            assert!((exponential(base - 1e-9) / rho0 - 1.0).abs() < 0.1, "jump at {} km", base);
+        }
+    }
+
+    #[test]
+    fn test_harris_priester_bulge() {
+        assert!((harris_priester(400.0, 1.0) - 7.492e-12).abs() < 1e-18);
+        assert!((harris_priester(400.0, -1.0) - 2.249e-12).abs() < 1e-18);
+        let between = harris_priester(410.0, 0.0);
+        assert!(harris_priester(410.0, -1.0) < between && between < harris_priester(410.0, 1.0));
+
+        // equatorial point under the bulge (about 2 pm local time) vs the night side
+        let jd = 2460000.5;
+        let atmosphere = Atmosphere::new(DensityModel::HarrisPriester, SpaceWeather::default(), Some(jd)).unwrap();
+        let apex = atmosphere.bulge.normalize();
+        let day = atmosphere.density(&(apex * (EARTH_RADIUS_KM + 400.0)));
+        let night = atmosphere.density(&(-apex * (EARTH_RADIUS_KM + 400.0)));
+        assert!(day / night > 3.0, "{} {}", day, night);
+        assert!(Atmosphere::new(DensityModel::HarrisPriester, SpaceWeather::default(), None).is_err());
+    }
+
+    #[test]
+    fn test_space_weather_scales_thermosphere() {
+        let r = Vector3::new(0.0, 0.0, EARTH_RADIUS_KM * (1.0 - 1.0 / 298.257223563) + 400.0);
+        let density = |weather: SpaceWeather| Atmosphere::new(DensityModel::Exponential, weather, None).unwrap().density(&r);
+        let mean = density(SpaceWeather::default());
+        assert!((mean / 3.725e-12 - 1.0).abs() < 1e-6);
+        let active = density(SpaceWeather { f107: 250.0, f107_avg: 220.0, ap: 50.0 });
+        let quiet = density(SpaceWeather { f107: 70.0, f107_avg: 70.0, ap: 4.0 });
+        assert!(active > 2.0 * mean && quiet < 0.5 * mean);
+    }
+}
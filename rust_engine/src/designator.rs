@@ -0,0 +1,152 @@
+//! Satellite catalog numbers and international designators
+//!
+//! Five-column catalog numbers run out at 99999. The Alpha-5 scheme used in
+//! TLEs replaces the leading digit with a letter (A = 10 ... Z = 33, skipping
+//! I and O), so "A0001" is 100001 and "Z9999" is 339999. NORAD ids stay
+//! `i32` throughout the crate; Alpha-5 is only their text form.
+//!
+//! International (COSPAR) designators are kept in the canonical form
+//! "YYYY-NNNP": launch year, launch number within the year and one to three
+//! piece letters (again skipping I and O). The TLE form "YYNNNP" is accepted
+//! as well, with two-digit years 57-99 in the 1900s.
+
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+
+/// Leading Alpha-5 letters; the position plus 10 is the value of the leading digit pair
+const ALPHA5_LETTERS: &str = "ABCDEFGHJKLMNPQRSTUVWXYZ";
+
+/// Largest catalog number with an Alpha-5 form
+pub const MAX_ALPHA5: i32 = 339_999;
+
+/// Catalog number from digits ("25544", "00005") or Alpha-5 text ("A0001")
+pub fn parse_catalog_number(text: &str) -> Result<i32, String> {
+    let text = text.trim();
+    let invalid = || format!("Invalid catalog number {:?} (expected digits or Alpha-5, e.g. A0001)", text);
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) if first.is_ascii_alphabetic() => {
+            let digits = chars.as_str();
+            if digits.len() != 4 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(invalid());
+            }
+            let lead = ALPHA5_LETTERS.find(first.to_ascii_uppercase()).ok_or_else(invalid)? as i32 + 10;
+            Ok(lead * 10_000 + digits.parse::<i32>().map_err(|_| invalid())?)
+        }
+        Some(_) if text.bytes().all(|b| b.is_ascii_digit()) => text.parse().map_err(|_| invalid()),
+        _ => Err(invalid()),
+    }
+}
+
+/// Five-character catalog number: zero-padded digits below 100000, Alpha-5 above
+pub fn format_alpha5(norad_id: i32) -> Result<String, String> {
+    match norad_id {
+        0..=99_999 => Ok(format!("{:05}", norad_id)),
+        100_000..=MAX_ALPHA5 => {
+            let letter = ALPHA5_LETTERS.as_bytes()[(norad_id / 10_000 - 10) as usize] as char;
+            Ok(format!("{}{:04}", letter, norad_id % 10_000))
+        }
+        _ => Err(format!("Catalog number {} has no Alpha-5 form (0 to {})", norad_id, MAX_ALPHA5)),
+    }
+}
+
+/// Canonical "YYYY-NNNP" form of an international designator ("1998-067A" or TLE "98067A")
+pub fn parse_international_designator(text: &str) -> Result<String, String> {
+    let upper = text.trim().to_ascii_uppercase();
+    let invalid = || format!("Invalid international designator {:?} (expected e.g. 1998-067A or 98067A)", text);
+    if !upper.is_ascii() {
+        return Err(invalid());
+    }
+    let (year, rest) = match upper.split_once('-') {
+        Some((year, rest)) if year.len() == 4 => (year.parse::<i32>().map_err(|_| invalid())?, rest),
+        Some(_) => return Err(invalid()),
+        None if upper.len() > 2 && upper.as_bytes()[..2].iter().all(u8::is_ascii_digit) => {
+            let (year, rest) = upper.split_at(2);
+            let year = year.parse::<i32>().map_err(|_| invalid())?;
+            (if year < 57 { year + 2000 } else { year + 1900 }, rest)
+        }
+        None => return Err(invalid()),
+    };
+    let (launch, piece) = rest.split_at(rest.len().min(3));
+    let valid_piece = (1..=3).contains(&piece.len())
+        && piece.chars().all(|c| c.is_ascii_uppercase() && c != 'I' && c != 'O');
+    if year < 1957 || launch.len() != 3 || !launch.bytes().all(|b| b.is_ascii_digit()) || !valid_piece {
+        return Err(invalid());
+    }
+    Ok(format!("{}-{}{}", year, launch, piece))
+}
+
+/// TLE form "YYNNNP" of an international designator
+pub fn tle_international_designator(text: &str) -> Result<String, String> {
+    let canonical = parse_international_designator(text)?;
+    Ok(format!("{}{}", &canonical[2..4], &canonical[5..]))
+}
+
+/// NORAD id from a Python int or catalog number string
+#[cfg(feature = "python")]
+pub(crate) fn norad_id_from_py(value: &PyAny) -> PyResult<i32> {
+    match value.extract::<&str>() {
+        Ok(text) => parse_catalog_number(text).map_err(pyo3::exceptions::PyValueError::new_err),
+        Err(_) => value.extract(),
+    }
+}
+
+/// Alpha-5 text of a catalog number ("25544", "A0001")
+#[cfg(feature = "python")]
+#[pyfunction]
+pub fn norad_to_alpha5(norad_id: i32) -> PyResult<String> {
+    format_alpha5(norad_id).map_err(pyo3::exceptions::PyValueError::new_err)
+}
+
+/// Catalog number from digits or Alpha-5 text
+#[cfg(feature = "python")]
+#[pyfunction]
+pub fn alpha5_to_norad(text: &str) -> PyResult<i32> {
+    parse_catalog_number(text).map_err(pyo3::exceptions::PyValueError::new_err)
+}
+
+/// Canonical "YYYY-NNNP" international designator
+#[cfg(feature = "python")]
+#[pyfunction(name = "parse_international_designator")]
+pub fn py_parse_international_designator(text: &str) -> PyResult<String> {
+    parse_international_designator(text).map_err(pyo3::exceptions::PyValueError::new_err)
+}
+
+/// TLE "YYNNNP" form of an international designator
+#[cfg(feature = "python")]
+#[pyfunction(name = "tle_international_designator")]
+pub fn py_tle_international_designator(text: &str) -> PyResult<String> {
+    tle_international_designator(text).map_err(pyo3::exceptions::PyValueError::new_err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alpha5_round_trip() {
+        for (text, id) in [("00005", 5), ("25544", 25544), ("A0000", 100_000), ("E8493", 148_493), ("Z9999", 339_999)] {
+            assert_eq!(parse_catalog_number(text).unwrap(), id);
+            assert_eq!(format_alpha5(id).unwrap(), text);
+        }
+        assert_eq!(parse_catalog_number("J0001").unwrap(), 180_001); // I is skipped
+        assert_eq!(parse_catalog_number(" 123456789 ").unwrap(), 123_456_789);
+        for bad in ["I0001", "O1234", "A001", "A00001", "", "-5", "25 44"] {
+            assert!(parse_catalog_number(bad).is_err(), "{:?}", bad);
+        }
+        assert!(format_alpha5(340_000).is_err());
+        assert!(format_alpha5(-1).is_err());
+    }
+
+    #[test]
+    fn test_international_designators() {
+        assert_eq!(parse_international_designator("98067A").unwrap(), "1998-067A");
+        assert_eq!(parse_international_designator(" 2024-001abc ").unwrap(), "2024-001ABC");
+        assert_eq!(parse_international_designator("57001B").unwrap(), "1957-001B");
+        assert_eq!(parse_international_designator("19029BZ").unwrap(), "2019-029BZ");
+        assert_eq!(tle_international_designator("1998-067A").unwrap(), "98067A");
+        for bad in ["1998-67A", "98067", "1998-067I", "1956-001A", "98067ABCD", "", "é8067A", "1998-12é", "+9067A"] {
+            assert!(parse_international_designator(bad).is_err(), "{:?}", bad);
+        }
+    }
+}
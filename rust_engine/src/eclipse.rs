@@ -0,0 +1,187 @@
+//! Eclipse and illumination state
+//!
+//! Two shadow models for the Earth's shadow on a satellite:
+//! * cylindrical: umbra is the cylinder of Earth radius behind the Earth,
+//!   with no penumbra; cheap and adequate for force models
+//! * conical: the Sun and Earth discs as seen from the satellite (Montenbruck
+//!   & Gill 3.4.2) give the visible fraction of the solar disc, resolving
+//!   the penumbra for sensor tasking
+//!
+//! Positions are geocentric inertial (km); the Earth is a sphere of
+//! equatorial radius.
+
+use nalgebra::Vector3;
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+#[cfg(feature = "python")]
+use rayon::prelude::*;
+use std::f64::consts::PI;
+
+use crate::celestial;
+#[cfg(feature = "python")]
+use crate::error::OrbitError;
+use crate::constants::{EARTH_RADIUS_KM, SUN_RADIUS_KM};
+#[cfg(feature = "python")]
+use crate::{parallel, Satellite};
+
+/// Shadow geometry
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShadowModel {
+    Cylindrical,
+    Conical,
+}
+
+impl std::str::FromStr for ShadowModel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "cylindrical" => Ok(ShadowModel::Cylindrical),
+            "conical" => Ok(ShadowModel::Conical),
+            _ => Err(format!("Unknown shadow model '{}' (expected 'cylindrical' or 'conical')", s)),
+        }
+    }
+}
+
+/// Illumination state of a satellite
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Illumination {
+    Sunlit,
+    Penumbra,
+    Umbra,
+}
+
+impl Illumination {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Illumination::Sunlit => "sunlit",
+            Illumination::Penumbra => "penumbra",
+            Illumination::Umbra => "umbra",
+        }
+    }
+}
+
+/// Visible fraction of the solar disc (0 in umbra, 1 in full sunlight)
+pub fn sunlit_fraction(r: &Vector3<f64>, sun: &Vector3<f64>, model: ShadowModel) -> f64 {
+    match model {
+        ShadowModel::Cylindrical => {
+            let sun_hat = sun.normalize();
+            let along = r.dot(&sun_hat);
+            if along < 0.0 && (r - along * sun_hat).norm() < EARTH_RADIUS_KM {
+                0.0
+            } else {
+                1.0
+            }
+        }
+        ShadowModel::Conical => {
+            let to_sun = sun - r;
+            // apparent radii of the Sun and Earth, and their separation
+            let a = (SUN_RADIUS_KM / to_sun.norm()).asin();
+            let b = (EARTH_RADIUS_KM / r.norm()).min(1.0).asin();
+            let c = (-r.dot(&to_sun) / (r.norm() * to_sun.norm())).clamp(-1.0, 1.0).acos();
+            if c >= a + b {
+                1.0
+            } else if c <= b - a {
+                0.0
+            } else if c <= a - b {
+                // annular: the Earth disc lies inside the solar disc
+                1.0 - (b * b) / (a * a)
+            } else {
+                // overlap of two discs of radii a and b at distance c
+                let x = (c * c + a * a - b * b) / (2.0 * c);
+                let y = (a * a - x * x).max(0.0).sqrt();
+                let area = a * a * (x / a).clamp(-1.0, 1.0).acos() + b * b * ((c - x) / b).clamp(-1.0, 1.0).acos() - c * y;
+                (1.0 - area / (PI * a * a)).clamp(0.0, 1.0)
+            }
+        }
+    }
+}
+
+/// Illumination state and sunlit fraction of a position at a Julian date
+pub fn illumination(r: &Vector3<f64>, jd: f64, model: ShadowModel) -> (Illumination, f64) {
+    let fraction = sunlit_fraction(r, &celestial::sun_position(jd), model);
+    let state = if fraction >= 1.0 {
+        Illumination::Sunlit
+    } else if fraction <= 0.0 {
+        Illumination::Umbra
+    } else {
+        Illumination::Penumbra
+    };
+    (state, fraction)
+}
+
+/// Illumination of a position (km, inertial) at a Julian date
+///
+/// Returns ("sunlit" | "penumbra" | "umbra", visible fraction of the solar
+/// disc). `model` is "conical" (default) or "cylindrical".
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(signature = (position, jd, model = "conical"))]
+pub fn eclipse_state(position: Vec<f64>, jd: f64, model: &str) -> PyResult<(&'static str, f64)> {
+    if position.len() != 3 {
+        return Err(pyo3::exceptions::PyValueError::new_err("position must have 3 components"));
+    }
+    let model: ShadowModel = model.parse().map_err(pyo3::exceptions::PyValueError::new_err)?;
+    let (state, fraction) = illumination(&Vector3::from_column_slice(&position), jd, model);
+    Ok((state.name(), fraction))
+}
+
+/// Illumination of each satellite at its own epoch, in input order
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(signature = (satellites, model = "conical"))]
+pub fn eclipse_states(py: Python<'_>, satellites: Vec<Satellite>, model: &str) -> PyResult<Vec<(&'static str, f64)>> {
+    let model: ShadowModel = model.parse().map_err(pyo3::exceptions::PyValueError::new_err)?;
+    let epochs = satellites
+        .iter()
+        .map(|sat| {
+            sat.epoch.map(|e| e.jd_utc()).ok_or_else(|| {
+                OrbitError::InvalidState(format!("Object {} has no epoch", sat.norad_id)).into()
+            })
+        })
+        .collect::<PyResult<Vec<f64>>>()?;
+    Ok(py.allow_threads(|| {
+        parallel::install(|| {
+            satellites
+                .par_iter()
+                .zip(epochs.par_iter())
+                .map(|(sat, &jd)| {
+                    let (state, fraction) = illumination(&sat.r(), jd, model);
+                    (state.name(), fraction)
+                })
+                .collect()
+        })
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::AU_KM;
+
+    #[test]
+    fn test_conical_shadow_regions() {
+        let sun = Vector3::new(AU_KM, 0.0, 0.0);
+        let radius = 7000.0;
+        let at = |angle_deg: f64| {
+            let angle = angle_deg.to_radians();
+            sunlit_fraction(&Vector3::new(radius * angle.cos(), radius * angle.sin(), 0.0), &sun, ShadowModel::Conical)
+        };
+        assert_eq!(at(0.0), 1.0);
+        assert_eq!(at(180.0), 0.0);
+        // the shadow edge is near asin(R / r) from the anti-Sun direction
+        let edge = 180.0 - (EARTH_RADIUS_KM / radius).asin().to_degrees();
+        let partial = at(edge);
+        assert!(partial > 0.0 && partial < 1.0, "{}", partial);
+        let mut previous = 1.0;
+        for step in 0..=40 {
+            let fraction = at(edge - 1.0 + 0.05 * step as f64);
+            assert!(fraction <= previous + 1e-12);
+            previous = fraction;
+        }
+
+        let behind = Vector3::new(-radius, 0.0, 0.0);
+        assert_eq!(sunlit_fraction(&behind, &sun, ShadowModel::Cylindrical), 0.0);
+        assert_eq!(sunlit_fraction(&Vector3::new(-radius, 6400.0, 0.0), &sun, ShadowModel::Cylindrical), 1.0);
+    }
+}
@@ -0,0 +1,453 @@
+//! Extended Kalman filter for sequential track maintenance
+//!
+//! The filter carries a TEME state and its covariance between measurement
+//! epochs with the numerical propagator and its state transition matrix,
+//! adding state noise compensation for unmodelled accelerations (white
+//! acceleration noise of spectral density q, km^2/s^3). Measurements are
+//! range, range-rate, topocentric right ascension/declination or
+//! azimuth/elevation from a site, processed one epoch at a time with the
+//! Joseph form of the covariance update. Measurement partials are central
+//! differences of the measurement model. An optional gate on the normalised
+//! innovation squared rejects outliers and cross-tags without touching the
+//! estimate.
+
+use nalgebra::{DMatrix, DVector, Matrix3, Matrix6, Vector3, Vector6};
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use std::f64::consts::PI;
+use std::str::FromStr;
+
+#[cfg(feature = "python")]
+use crate::epoch::Epoch;
+use crate::frames;
+use crate::ground_station::GroundStation;
+use crate::propagation::{self, Dynamics, Integrator};
+#[cfg(feature = "python")]
+use crate::propagation::ForceModel;
+#[cfg(feature = "python")]
+use crate::Satellite;
+
+/// Central-difference steps of the measurement partials (km, km/s)
+const POSITION_STEP: f64 = 1.0e-3;
+const VELOCITY_STEP: f64 = 1.0e-6;
+
+/// Observable of a tracking measurement
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MeasurementKind {
+    Range,          // km
+    RangeRate,      // km/s, positive when receding
+    RightAscension, // topocentric, rad
+    Declination,    // topocentric, rad
+    Azimuth,        // rad, from north through east
+    Elevation,      // rad
+}
+
+impl FromStr for MeasurementKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "range" => Ok(MeasurementKind::Range),
+            "range_rate" | "range-rate" | "doppler" => Ok(MeasurementKind::RangeRate),
+            "ra" | "right_ascension" => Ok(MeasurementKind::RightAscension),
+            "dec" | "declination" => Ok(MeasurementKind::Declination),
+            "az" | "azimuth" => Ok(MeasurementKind::Azimuth),
+            "el" | "elevation" => Ok(MeasurementKind::Elevation),
+            _ => Err(format!(
+                "Unknown measurement '{}' (expected range, range_rate, ra, dec, azimuth or elevation)",
+                s
+            )),
+        }
+    }
+}
+
+impl MeasurementKind {
+    /// Key of the measurement in Python dicts
+    pub fn name(&self) -> &'static str {
+        match self {
+            MeasurementKind::Range => "range",
+            MeasurementKind::RangeRate => "range_rate",
+            MeasurementKind::RightAscension => "ra",
+            MeasurementKind::Declination => "dec",
+            MeasurementKind::Azimuth => "azimuth",
+            MeasurementKind::Elevation => "elevation",
+        }
+    }
+
+    pub fn is_angle(&self) -> bool {
+        !matches!(self, MeasurementKind::Range | MeasurementKind::RangeRate)
+    }
+
+    /// Predicted measurement of a TEME state from `site`
+    pub fn evaluate(&self, state: &Vector6<f64>, site: &Site) -> f64 {
+        let rho = Vector3::new(state[0], state[1], state[2]) - site.position;
+        let range = rho.norm();
+        let [east, north, up] = &site.axes;
+        match self {
+            MeasurementKind::Range => range,
+            MeasurementKind::RangeRate => rho.dot(&(Vector3::new(state[3], state[4], state[5]) - site.velocity)) / range,
+            MeasurementKind::RightAscension => rho.y.atan2(rho.x),
+            MeasurementKind::Declination => (rho.z / range).asin(),
+            MeasurementKind::Azimuth => rho.dot(east).atan2(rho.dot(north)),
+            MeasurementKind::Elevation => (rho.dot(up) / range).asin(),
+        }
+    }
+}
+
+/// Observer state in TEME at the measurement epoch
+#[derive(Clone, Copy, Debug)]
+pub struct Site {
+    pub position: Vector3<f64>, // km
+    pub velocity: Vector3<f64>, // km/s
+    pub axes: [Vector3<f64>; 3], // local east, north and up unit vectors
+}
+
+impl Site {
+    /// A ground station at Julian date `jd` (UTC)
+    pub fn from_station(station: &GroundStation, jd: f64) -> Self {
+        let (position, velocity) = frames::ecef_to_teme_state(&station.ecef_km(), &Vector3::zeros(), jd, 0.0, 0.0);
+        let axes = station
+            .enu_axes()
+            .map(|axis| frames::ecef_to_teme_state(&axis, &Vector3::zeros(), jd, 0.0, 0.0).0);
+        Site { position, velocity, axes }
+    }
+}
+
+/// One scalar measurement and its noise
+#[derive(Clone, Copy, Debug)]
+pub struct Measurement {
+    pub kind: MeasurementKind,
+    pub value: f64, // km, km/s or rad
+    pub sigma: f64,
+}
+
+/// Outcome of a measurement update
+#[derive(Clone, Debug)]
+pub struct Innovation {
+    pub residuals: Vec<f64>, // measured minus predicted, before the update
+    pub nis: f64,            // normalised innovation squared
+    pub accepted: bool,
+}
+
+/// Sequential estimator of one object's state
+#[derive(Clone, Debug)]
+pub struct Ekf {
+    pub state: Vector6<f64>,
+    pub covariance: Matrix6<f64>,
+    pub time: f64, // seconds from the reference epoch of the filter
+    pub dynamics: Dynamics,
+    pub integrator: Integrator,
+    pub process_noise: f64,  // acceleration spectral density (km^2/s^3)
+    pub gate: Option<f64>,   // largest accepted normalised innovation squared
+}
+
+impl Ekf {
+    pub fn new(state: Vector6<f64>, covariance: Matrix6<f64>, dynamics: Dynamics, integrator: Integrator) -> Self {
+        Ekf { state, covariance, time: 0.0, dynamics, integrator, process_noise: 0.0, gate: None }
+    }
+
+    /// Propagate the state and covariance to `time` (seconds from the reference epoch)
+    pub fn predict(&mut self, time: f64) -> Result<(), String> {
+        let dt = time - self.time;
+        if dt == 0.0 {
+            return Ok(());
+        }
+        let (state, stm) = propagation::integrate_with_stm(&self.state, dt, self.dynamics.advanced(self.time), self.integrator)
+            .ok_or("Propagation failed during the filter prediction")?;
+        let mut covariance = propagation::map_covariance(&stm, &self.covariance);
+        // state noise compensation: white acceleration noise over |dt|
+        let h = dt.abs();
+        let q = self.process_noise;
+        let identity = Matrix3::<f64>::identity();
+        let mut noise = Matrix6::zeros();
+        noise.fixed_view_mut::<3, 3>(0, 0).copy_from(&(identity * (q * h.powi(3) / 3.0)));
+        noise.fixed_view_mut::<3, 3>(0, 3).copy_from(&(identity * (q * h * h / 2.0)));
+        noise.fixed_view_mut::<3, 3>(3, 0).copy_from(&(identity * (q * h * h / 2.0)));
+        noise.fixed_view_mut::<3, 3>(3, 3).copy_from(&(identity * (q * h)));
+        covariance += noise;
+
+        self.state = state;
+        self.covariance = covariance;
+        self.time = time;
+        Ok(())
+    }
+
+    /// Process measurements taken together from `site` at the current filter time
+    pub fn update(&mut self, site: &Site, measurements: &[Measurement]) -> Result<Innovation, String> {
+        if measurements.is_empty() {
+            return Err("No measurements to process".to_string());
+        }
+        if measurements.iter().any(|m| m.sigma.is_nan() || m.sigma <= 0.0) {
+            return Err("Measurement sigmas must be positive".to_string());
+        }
+        let m = measurements.len();
+        let mut h = DMatrix::<f64>::zeros(m, 6);
+        let mut residuals = DVector::<f64>::zeros(m);
+        for (i, measurement) in measurements.iter().enumerate() {
+            let mut residual = measurement.value - measurement.kind.evaluate(&self.state, site);
+            if measurement.kind.is_angle() {
+                residual = (residual + PI).rem_euclid(2.0 * PI) - PI;
+            }
+            residuals[i] = residual;
+            for j in 0..6 {
+                let step = if j < 3 { POSITION_STEP } else { VELOCITY_STEP };
+                let (mut plus, mut minus) = (self.state, self.state);
+                plus[j] += step;
+                minus[j] -= step;
+                let mut difference = measurement.kind.evaluate(&plus, site) - measurement.kind.evaluate(&minus, site);
+                if measurement.kind.is_angle() {
+                    difference = (difference + PI).rem_euclid(2.0 * PI) - PI;
+                }
+                h[(i, j)] = difference / (2.0 * step);
+            }
+        }
+        let noise = DMatrix::from_diagonal(&DVector::from_iterator(m, measurements.iter().map(|m| m.sigma * m.sigma)));
+        let covariance = DMatrix::from_column_slice(6, 6, self.covariance.as_slice());
+        let innovation_covariance = &h * &covariance * h.transpose() + &noise;
+        let inverse = innovation_covariance
+            .clone()
+            .cholesky()
+            .ok_or("Singular innovation covariance")?
+            .inverse();
+        let nis = (residuals.transpose() * &inverse * &residuals)[(0, 0)];
+        let innovation = Innovation { residuals: residuals.iter().copied().collect(), nis, accepted: true };
+        if self.gate.is_some_and(|gate| nis > gate) {
+            return Ok(Innovation { accepted: false, ..innovation });
+        }
+
+        let gain = &covariance * h.transpose() * inverse;
+        let correction = &gain * &residuals;
+        // Joseph form keeps the covariance symmetric and positive definite
+        let reduction = DMatrix::<f64>::identity(6, 6) - &gain * &h;
+        let updated = &reduction * &covariance * reduction.transpose() + &gain * noise * gain.transpose();
+        for j in 0..6 {
+            self.state[j] += correction[j];
+        }
+        self.covariance = Matrix6::from_column_slice(updated.as_slice());
+        self.covariance = (self.covariance + self.covariance.transpose()) * 0.5;
+        Ok(innovation)
+    }
+}
+
+/// Result of one `EkfTracker.update`
+#[cfg(feature = "python")]
+#[pyclass(get_all)]
+#[derive(Clone, Debug)]
+pub struct EkfUpdate {
+    pub accepted: bool,
+    pub residuals: Vec<f64>, // measured minus predicted, in the units of the input (angles in degrees)
+    pub nis: f64,            // normalised innovation squared
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl EkfUpdate {
+    fn __repr__(&self) -> String {
+        format!("EkfUpdate(accepted={}, nis={:.3})", self.accepted, self.nis)
+    }
+}
+
+/// Extended Kalman filter maintaining custody of one object
+///
+/// Starts from a `Satellite` with an epoch and covariance. `predict(jd)`
+/// moves the estimate to a new time and `update(station, jd, measurements)`
+/// processes simultaneous measurements given as `{kind: (value, sigma)}`,
+/// with kinds `range` (km), `range_rate` (km/s), `ra`/`dec` (topocentric,
+/// degrees) and `azimuth`/`elevation` (degrees). `process_noise` is the
+/// white acceleration spectral density (km^2/s^3) absorbing unmodelled
+/// forces; `gate` rejects updates whose normalised innovation squared
+/// exceeds it (a chi-squared quantile for the number of measurements).
+#[cfg(feature = "python")]
+#[pyclass]
+#[derive(Clone)]
+pub struct EkfTracker {
+    filter: Ekf,
+    reference: Epoch,
+    template: Satellite,
+
+    #[pyo3(get)]
+    pub updates: usize,
+
+    #[pyo3(get)]
+    pub rejected: usize,
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl EkfTracker {
+    #[new]
+    #[pyo3(signature = (satellite, process_noise = 1.0e-12, gate = None, j2 = true, method = "dp45", step = 30.0))]
+    fn new(
+        satellite: &Satellite,
+        process_noise: f64,
+        gate: Option<f64>,
+        j2: bool,
+        method: &str,
+        step: f64,
+    ) -> PyResult<Self> {
+        let integrator = Integrator::parse(method, step)?;
+        let reference = satellite
+            .epoch
+            .ok_or_else(|| pyo3::exceptions::PyValueError::new_err("The initial state needs an epoch"))?;
+        let covariance = satellite
+            .covariance
+            .ok_or_else(|| pyo3::exceptions::PyValueError::new_err("The initial state needs a covariance"))?;
+        if process_noise.is_nan() || process_noise < 0.0 {
+            return Err(pyo3::exceptions::PyValueError::new_err("process_noise must be non-negative"));
+        }
+        let (r, v) = (satellite.position, satellite.velocity);
+        let state = Vector6::new(r[0], r[1], r[2], v[0], v[1], v[2]);
+        let dynamics = Dynamics::from(if j2 { ForceModel::J2 } else { ForceModel::TwoBody });
+        let filter = Ekf { process_noise, gate, ..Ekf::new(state, covariance, dynamics, integrator) };
+        Ok(EkfTracker { filter, reference, template: satellite.clone(), updates: 0, rejected: 0 })
+    }
+
+    /// Current estimate with its covariance and epoch
+    #[getter]
+    fn satellite(&self) -> Satellite {
+        let y = &self.filter.state;
+        Satellite {
+            position: [y[0], y[1], y[2]],
+            velocity: [y[3], y[4], y[5]],
+            covariance: Some(self.filter.covariance),
+            epoch: Some(self.reference.plus_seconds(self.filter.time)),
+            ..self.template.clone()
+        }
+    }
+
+    /// Julian date (UTC) of the current estimate
+    #[getter]
+    fn jd(&self) -> f64 {
+        self.reference.plus_seconds(self.filter.time).jd_utc()
+    }
+
+    /// Propagate the estimate and covariance to `jd` (UTC)
+    fn predict(&mut self, py: Python<'_>, jd: f64) -> PyResult<()> {
+        let time = Epoch::from_jd_utc(jd).seconds_since(&self.reference);
+        let filter = &mut self.filter;
+        py.allow_threads(|| filter.predict(time)).map_err(pyo3::exceptions::PyValueError::new_err)
+    }
+
+    /// Predict to `jd` and process measurements from `station`
+    fn update(
+        &mut self,
+        py: Python<'_>,
+        station: &GroundStation,
+        jd: f64,
+        measurements: std::collections::HashMap<String, (f64, f64)>,
+    ) -> PyResult<EkfUpdate> {
+        let measurements: Vec<Measurement> = measurements
+            .iter()
+            .map(|(kind, &(value, sigma))| {
+                let kind: MeasurementKind = kind.parse().map_err(pyo3::exceptions::PyValueError::new_err)?;
+                let scale = if kind.is_angle() { PI / 180.0 } else { 1.0 };
+                Ok(Measurement { kind, value: value * scale, sigma: sigma * scale })
+            })
+            .collect::<PyResult<_>>()?;
+        let site = Site::from_station(station, jd);
+        self.predict(py, jd)?;
+        let innovation = self.filter.update(&site, &measurements).map_err(pyo3::exceptions::PyValueError::new_err)?;
+        if innovation.accepted {
+            self.updates += 1;
+        } else {
+            self.rejected += 1;
+        }
+        Ok(EkfUpdate {
+            accepted: innovation.accepted,
+            residuals: measurements
+                .iter()
+                .zip(&innovation.residuals)
+                .map(|(m, r)| if m.kind.is_angle() { r.to_degrees() } else { *r })
+                .collect(),
+            nis: innovation.nis,
+        })
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "EkfTracker(norad_id={}, jd={:.6}, updates={}, rejected={})",
+            self.template.norad_id,
+            self.jd(),
+            self.updates,
+            self.rejected
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::EARTH_ROTATION_RATE;
+    use crate::propagation::ForceModel;
+
+    /// Site on the equator at longitude 0 of a non-rotating TEME frame at t = 0
+    fn site_at(t: f64) -> Site {
+        let angle = EARTH_ROTATION_RATE * t;
+        let up = Vector3::new(angle.cos(), angle.sin(), 0.0);
+        let position = 6378.137 * up;
+        Site {
+            position,
+            velocity: Vector3::new(0.0, 0.0, EARTH_ROTATION_RATE).cross(&position),
+            axes: [Vector3::new(-angle.sin(), angle.cos(), 0.0), Vector3::z(), up],
+        }
+    }
+
+    #[test]
+    fn test_ekf_converges_on_range_and_rate() {
+        let integrator = Integrator::DormandPrince { initial_step: 30.0 };
+        let truth = Vector6::new(7000.0, 0.0, 0.0, 0.0, 5.2, 5.2);
+        let offset = Vector6::new(2.0, -1.5, 1.0, 0.002, -0.001, 0.0015);
+        let covariance = Matrix6::from_diagonal(&Vector6::new(9.0, 9.0, 9.0, 1e-5, 1e-5, 1e-5));
+        let mut filter = Ekf::new(truth + offset, covariance, ForceModel::J2.into(), integrator);
+
+        for k in 1..=60 {
+            let t = k as f64 * 10.0;
+            let y = propagation::integrate(&truth, t, ForceModel::J2, integrator).unwrap();
+            let site = site_at(t);
+            let measurements = [
+                Measurement { kind: MeasurementKind::Range, value: MeasurementKind::Range.evaluate(&y, &site), sigma: 0.01 },
+                Measurement {
+                    kind: MeasurementKind::RightAscension,
+                    value: MeasurementKind::RightAscension.evaluate(&y, &site),
+                    sigma: 1e-4,
+                },
+                Measurement {
+                    kind: MeasurementKind::Declination,
+                    value: MeasurementKind::Declination.evaluate(&y, &site),
+                    sigma: 1e-4,
+                },
+            ];
+            filter.predict(t).unwrap();
+            assert!(filter.update(&site, &measurements).unwrap().accepted);
+        }
+        let y = propagation::integrate(&truth, 600.0, ForceModel::J2, integrator).unwrap();
+        let error = (filter.state - y).fixed_rows::<3>(0).norm();
+        let sigma = filter.covariance.fixed_view::<3, 3>(0, 0).trace().sqrt();
+        assert!(error < 0.05 && error < 3.0 * sigma, "{} {}", error, sigma);
+        assert!(sigma < 0.1, "{}", sigma);
+    }
+
+    #[test]
+    fn test_ekf_gate_rejects_outlier() {
+        let integrator = Integrator::DormandPrince { initial_step: 30.0 };
+        let state = Vector6::new(7000.0, 0.0, 0.0, 0.0, 5.2, 5.2);
+        let covariance = Matrix6::from_diagonal(&Vector6::new(0.01, 0.01, 0.01, 1e-8, 1e-8, 1e-8));
+        let mut filter = Ekf { gate: Some(9.0), process_noise: 1e-12, ..Ekf::new(state, covariance, ForceModel::TwoBody.into(), integrator) };
+        filter.predict(60.0).unwrap();
+        let before = filter.clone();
+        assert!(filter.covariance[(0, 0)] > 0.01);
+
+        let site = site_at(60.0);
+        let range = MeasurementKind::Range.evaluate(&filter.state, &site);
+        let outlier = [Measurement { kind: MeasurementKind::Range, value: range + 50.0, sigma: 0.01 }];
+        let innovation = filter.update(&site, &outlier).unwrap();
+        assert!(!innovation.accepted && innovation.nis > 9.0);
+        assert_eq!(filter.state, before.state);
+
+        let good = [Measurement { kind: MeasurementKind::Range, value: range + 0.01, sigma: 0.01 }];
+        assert!(filter.update(&site, &good).unwrap().accepted);
+        assert!(filter.covariance.trace() < before.covariance.trace());
+        assert!(filter.update(&site, &[]).is_err());
+        assert!("doppler".parse::<MeasurementKind>().is_ok() && "phase".parse::<MeasurementKind>().is_err());
+    }
+}
@@ -0,0 +1,547 @@
+//! Classical (Keplerian) and equinoctial orbital elements
+//!
+//! Conversions follow Vallado's RV2COE/COE2RV. For circular or equatorial
+//! orbits the undefined angles are set to zero and the remaining angle
+//! carries the argument of latitude, longitude of periapsis or true
+//! longitude, so a round trip always reproduces the state.
+//!
+//! Equinoctial elements (Broucke & Cefola 1972) have no such special cases:
+//! they stay smooth as eccentricity and inclination go to zero, which is
+//! where most of the GEO belt sits. The retrograde factor moves their one
+//! singularity from equatorial retrograde orbits to equatorial prograde ones.
+
+use nalgebra::{Rotation3, Vector3};
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use std::f64::consts::PI;
+
+use crate::constants::MU_EARTH;
+
+/// Eccentricity / inclination below which an orbit is treated as circular / equatorial
+const SMALL: f64 = 1.0e-10;
+
+/// Classical orbital elements (angles in degrees)
+#[cfg_attr(feature = "python", pyclass(get_all, set_all))]
+#[derive(Clone, Debug)]
+pub struct KeplerianElements {
+    pub semi_major_axis_km: f64, // negative for hyperbolic orbits
+    pub eccentricity: f64,
+    pub inclination_deg: f64,
+    pub raan_deg: f64,
+    pub arg_perigee_deg: f64,
+    pub true_anomaly_deg: f64,
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl KeplerianElements {
+    /// Orbital period in seconds (None for unbound orbits)
+    fn period(&self) -> Option<f64> {
+        (self.eccentricity < 1.0 && self.semi_major_axis_km > 0.0)
+            .then(|| 2.0 * PI * (self.semi_major_axis_km.powi(3) / MU_EARTH).sqrt())
+    }
+
+    /// Perigee radius (km)
+    fn perigee_radius(&self) -> f64 {
+        self.semi_major_axis_km * (1.0 - self.eccentricity)
+    }
+
+    /// Apogee radius (km, infinite for unbound orbits)
+    fn apogee_radius(&self) -> f64 {
+        if self.eccentricity < 1.0 {
+            self.semi_major_axis_km * (1.0 + self.eccentricity)
+        } else {
+            f64::INFINITY
+        }
+    }
+
+    /// Equivalent equinoctial elements (None for unbound orbits)
+    fn to_equinoctial(&self) -> Option<EquinoctialElements> {
+        EquinoctialElements::from_keplerian(self)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "KeplerianElements(a={:.3}km, e={:.6}, i={:.4}deg, raan={:.4}deg, argp={:.4}deg, nu={:.4}deg)",
+            self.semi_major_axis_km,
+            self.eccentricity,
+            self.inclination_deg,
+            self.raan_deg,
+            self.arg_perigee_deg,
+            self.true_anomaly_deg
+        )
+    }
+}
+
+/// Equinoctial elements of a bound orbit (angles in degrees)
+#[cfg_attr(feature = "python", pyclass(get_all, set_all))]
+#[derive(Clone, Debug, PartialEq)]
+pub struct EquinoctialElements {
+    pub semi_major_axis_km: f64,
+    pub h: f64,                  // e sin(argp + I raan)
+    pub k: f64,                  // e cos(argp + I raan)
+    pub p: f64,                  // tan(i/2)^I sin(raan)
+    pub q: f64,                  // tan(i/2)^I cos(raan)
+    pub mean_longitude_deg: f64, // M + argp + I raan
+    pub retrograde: bool,        // retrograde factor I = -1, for inclinations above 90 deg
+}
+
+impl EquinoctialElements {
+    fn factor(&self) -> f64 {
+        if self.retrograde {
+            -1.0
+        } else {
+            1.0
+        }
+    }
+
+    /// Equinoctial frame (f, g, w): w is the orbit normal, f and g span the plane
+    pub fn frame(&self) -> [Vector3<f64>; 3] {
+        let (p, q, i) = (self.p, self.q, self.factor());
+        let s = 1.0 + p * p + q * q;
+        [
+            Vector3::new(1.0 - p * p + q * q, 2.0 * p * q, -2.0 * i * p) / s,
+            Vector3::new(2.0 * i * p * q, (1.0 + p * p - q * q) * i, 2.0 * q) / s,
+            Vector3::new(2.0 * p, -2.0 * q, (1.0 - p * p - q * q) * i) / s,
+        ]
+    }
+
+    pub fn eccentricity(&self) -> f64 {
+        self.h.hypot(self.k)
+    }
+
+    pub fn inclination_deg(&self) -> f64 {
+        let i = 2.0 * self.p.hypot(self.q).atan();
+        if self.retrograde {
+            180.0 - i.to_degrees()
+        } else {
+            i.to_degrees()
+        }
+    }
+
+    /// Mean motion (rad/s)
+    pub fn mean_motion(&self) -> f64 {
+        (MU_EARTH / self.semi_major_axis_km.powi(3)).sqrt()
+    }
+
+    /// Longitude of periapsis, argp + I raan (rad)
+    pub fn longitude_of_periapsis(&self) -> f64 {
+        self.h.atan2(self.k)
+    }
+
+    /// Eccentric longitude (rad) from the generalised Kepler equation
+    fn eccentric_longitude(&self) -> f64 {
+        let (h, k) = (self.h, self.k);
+        let lambda = self.mean_longitude_deg.to_radians();
+        let mut f = lambda;
+        for _ in 0..50 {
+            let step = (f + h * f.cos() - k * f.sin() - lambda) / (1.0 - h * f.sin() - k * f.cos());
+            f -= step;
+            if step.abs() < 1.0e-14 {
+                break;
+            }
+        }
+        f
+    }
+
+    /// In-plane position and velocity along (f, g)
+    fn in_plane(&self) -> ([f64; 2], [f64; 2]) {
+        let (a, h, k) = (self.semi_major_axis_km, self.h, self.k);
+        let beta = 1.0 / (1.0 + (1.0 - h * h - k * k).sqrt());
+        let ecc_longitude = self.eccentric_longitude();
+        let (sin_f, cos_f) = ecc_longitude.sin_cos();
+        let r = a * (1.0 - k * cos_f - h * sin_f);
+        let x = a * ((1.0 - h * h * beta) * cos_f + h * k * beta * sin_f - k);
+        let y = a * (h * k * beta * cos_f + (1.0 - k * k * beta) * sin_f - h);
+        let scale = self.mean_motion() * a * a / r;
+        let vx = scale * (h * k * beta * cos_f - (1.0 - h * h * beta) * sin_f);
+        let vy = scale * ((1.0 - k * k * beta) * cos_f - h * k * beta * sin_f);
+        ([x, y], [vx, vy])
+    }
+
+    /// True longitude, argp + I raan + true anomaly (rad)
+    pub fn true_longitude(&self) -> f64 {
+        let ([x, y], _) = self.in_plane();
+        y.atan2(x)
+    }
+
+    /// Equinoctial form of classical elements; None for unbound orbits
+    pub fn from_keplerian(el: &KeplerianElements) -> Option<Self> {
+        let e = el.eccentricity;
+        if !(0.0..1.0).contains(&e) || el.semi_major_axis_km <= 0.0 {
+            return None;
+        }
+        let i = el.inclination_deg.to_radians();
+        let retrograde = i > PI / 2.0;
+        let (factor, tan_half) = if retrograde { (-1.0, 1.0 / (i / 2.0).tan()) } else { (1.0, (i / 2.0).tan()) };
+        let raan = el.raan_deg.to_radians();
+        let periapsis = el.arg_perigee_deg.to_radians() + factor * raan;
+        let nu = el.true_anomaly_deg.to_radians();
+        let big_e = 2.0 * (((1.0 - e) / (1.0 + e)).sqrt() * (nu / 2.0).tan()).atan();
+        let mean_anomaly = big_e - e * big_e.sin();
+        Some(EquinoctialElements {
+            semi_major_axis_km: el.semi_major_axis_km,
+            h: e * periapsis.sin(),
+            k: e * periapsis.cos(),
+            p: tan_half * raan.sin(),
+            q: tan_half * raan.cos(),
+            mean_longitude_deg: (mean_anomaly + periapsis).to_degrees().rem_euclid(360.0),
+            retrograde,
+        })
+    }
+
+    /// Classical elements, with the conventions of `from_state` for circular or equatorial orbits
+    pub fn to_keplerian(&self) -> KeplerianElements {
+        let e = self.eccentricity();
+        let raan = if self.p.hypot(self.q) < SMALL { 0.0 } else { self.p.atan2(self.q) };
+        let node_longitude = self.factor() * raan;
+        let periapsis = if e < SMALL { node_longitude } else { self.longitude_of_periapsis() };
+        KeplerianElements {
+            semi_major_axis_km: self.semi_major_axis_km,
+            eccentricity: e,
+            inclination_deg: self.inclination_deg(),
+            raan_deg: raan.to_degrees().rem_euclid(360.0),
+            arg_perigee_deg: (periapsis - node_longitude).to_degrees().rem_euclid(360.0),
+            true_anomaly_deg: (self.true_longitude() - periapsis).to_degrees().rem_euclid(360.0),
+        }
+    }
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl EquinoctialElements {
+    #[new]
+    #[pyo3(signature = (semi_major_axis_km, h, k, p, q, mean_longitude_deg, retrograde = false))]
+    fn py_new(semi_major_axis_km: f64, h: f64, k: f64, p: f64, q: f64, mean_longitude_deg: f64, retrograde: bool) -> Self {
+        EquinoctialElements { semi_major_axis_km, h, k, p, q, mean_longitude_deg, retrograde }
+    }
+
+    #[pyo3(name = "eccentricity")]
+    fn py_eccentricity(&self) -> f64 {
+        self.eccentricity()
+    }
+
+    #[pyo3(name = "inclination_deg")]
+    fn py_inclination_deg(&self) -> f64 {
+        self.inclination_deg()
+    }
+
+    /// Orbital period in seconds
+    fn period(&self) -> f64 {
+        2.0 * PI / self.mean_motion()
+    }
+
+    #[pyo3(name = "to_keplerian")]
+    fn py_to_keplerian(&self) -> KeplerianElements {
+        self.to_keplerian()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "EquinoctialElements(a={:.3}km, h={:.6}, k={:.6}, p={:.6}, q={:.6}, lambda={:.4}deg{})",
+            self.semi_major_axis_km,
+            self.h,
+            self.k,
+            self.p,
+            self.q,
+            self.mean_longitude_deg,
+            if self.retrograde { ", retrograde" } else { "" }
+        )
+    }
+}
+
+/// Angle between two vectors in [0, pi], robust to rounding
+fn angle_between(a: &Vector3<f64>, b: &Vector3<f64>) -> f64 {
+    (a.dot(b) / (a.norm() * b.norm())).clamp(-1.0, 1.0).acos()
+}
+
+/// Cartesian state (km, km/s) to classical elements
+pub fn from_state(r: &Vector3<f64>, v: &Vector3<f64>) -> Option<KeplerianElements> {
+    let r_mag = r.norm();
+    let h = r.cross(v);
+    if r_mag == 0.0 || h.norm() == 0.0 {
+        return None; // rectilinear or degenerate state
+    }
+
+    let node = Vector3::new(-h.y, h.x, 0.0);
+    let e_vec = ((v.norm_squared() - MU_EARTH / r_mag) * r - r.dot(v) * v) / MU_EARTH;
+    let e = e_vec.norm();
+    let energy = v.norm_squared() / 2.0 - MU_EARTH / r_mag;
+    let a = if (e - 1.0).abs() > SMALL {
+        -MU_EARTH / (2.0 * energy)
+    } else {
+        f64::INFINITY
+    };
+    let i = (h.z / h.norm()).clamp(-1.0, 1.0).acos();
+
+    let circular = e < SMALL;
+    let equatorial = i < SMALL || (PI - i) < SMALL;
+    let retrograde = i > PI / 2.0;
+
+    let mut raan = 0.0;
+    if !equatorial {
+        raan = (node.x / node.norm()).clamp(-1.0, 1.0).acos();
+        if node.y < 0.0 {
+            raan = 2.0 * PI - raan;
+        }
+    }
+
+    let (argp, nu) = match (circular, equatorial) {
+        (false, false) => {
+            let mut argp = angle_between(&node, &e_vec);
+            if e_vec.z < 0.0 {
+                argp = 2.0 * PI - argp;
+            }
+            (argp, true_anomaly(&e_vec, r, v))
+        }
+        (true, false) => {
+            // circular inclined: argument of latitude
+            let mut u = angle_between(&node, r);
+            if r.z < 0.0 {
+                u = 2.0 * PI - u;
+            }
+            (0.0, u)
+        }
+        (false, true) => {
+            // elliptical equatorial: longitude of periapsis
+            let mut lonper = (e_vec.x / e).clamp(-1.0, 1.0).acos();
+            if e_vec.y < 0.0 {
+                lonper = 2.0 * PI - lonper;
+            }
+            if retrograde {
+                lonper = 2.0 * PI - lonper;
+            }
+            (lonper, true_anomaly(&e_vec, r, v))
+        }
+        (true, true) => {
+            // circular equatorial: true longitude
+            let mut truelon = (r.x / r_mag).clamp(-1.0, 1.0).acos();
+            if r.y < 0.0 {
+                truelon = 2.0 * PI - truelon;
+            }
+            if retrograde {
+                truelon = 2.0 * PI - truelon;
+            }
+            (0.0, truelon)
+        }
+    };
+
+    Some(KeplerianElements {
+        semi_major_axis_km: a,
+        eccentricity: e,
+        inclination_deg: i.to_degrees(),
+        raan_deg: raan.to_degrees(),
+        arg_perigee_deg: argp.to_degrees(),
+        true_anomaly_deg: nu.to_degrees(),
+    })
+}
+
+fn true_anomaly(e_vec: &Vector3<f64>, r: &Vector3<f64>, v: &Vector3<f64>) -> f64 {
+    let nu = angle_between(e_vec, r);
+    if r.dot(v) < 0.0 {
+        2.0 * PI - nu
+    } else {
+        nu
+    }
+}
+
+/// Classical elements to Cartesian state (km, km/s)
+pub fn to_state(el: &KeplerianElements) -> Option<(Vector3<f64>, Vector3<f64>)> {
+    let e = el.eccentricity;
+    let p = el.semi_major_axis_km * (1.0 - e * e);
+    if p <= 0.0 || !p.is_finite() {
+        return None;
+    }
+
+    let i = el.inclination_deg.to_radians();
+    let raan = el.raan_deg.to_radians();
+    let argp = el.arg_perigee_deg.to_radians();
+    let nu = el.true_anomaly_deg.to_radians();
+
+    let denom = 1.0 + e * nu.cos();
+    if denom <= 0.0 {
+        return None; // beyond the asymptote of a hyperbolic orbit
+    }
+    let r_pqw = Vector3::new(p * nu.cos() / denom, p * nu.sin() / denom, 0.0);
+    let scale = (MU_EARTH / p).sqrt();
+    let v_pqw = Vector3::new(-scale * nu.sin(), scale * (e + nu.cos()), 0.0);
+
+    let rotation = Rotation3::from_axis_angle(&Vector3::z_axis(), raan)
+        * Rotation3::from_axis_angle(&Vector3::x_axis(), i)
+        * Rotation3::from_axis_angle(&Vector3::z_axis(), argp);
+
+    Some((rotation * r_pqw, rotation * v_pqw))
+}
+
+/// Cartesian state (km, km/s) to equinoctial elements; None for unbound or degenerate states
+///
+/// The retrograde factor follows the inclination.
+pub fn equinoctial_from_state(r: &Vector3<f64>, v: &Vector3<f64>) -> Option<EquinoctialElements> {
+    let angular = r.cross(v);
+    equinoctial_with_factor(r, v, angular.z < 0.0)
+}
+
+/// Equinoctial elements with a given retrograde factor
+///
+/// Iterations that must not switch frames mid-way pin the factor; None also
+/// when the orbit lies at the singularity of that frame.
+pub(crate) fn equinoctial_with_factor(r: &Vector3<f64>, v: &Vector3<f64>, retrograde: bool) -> Option<EquinoctialElements> {
+    let r_mag = r.norm();
+    let angular = r.cross(v);
+    if r_mag == 0.0 || angular.norm() == 0.0 {
+        return None;
+    }
+    let a = 1.0 / (2.0 / r_mag - v.norm_squared() / MU_EARTH);
+    if !a.is_finite() || a <= 0.0 {
+        return None;
+    }
+    let factor = if retrograde { -1.0 } else { 1.0 };
+    let w = angular / angular.norm();
+    let denom = 1.0 + factor * w.z;
+    if denom < 1.0e-12 {
+        return None;
+    }
+    let mut el = EquinoctialElements {
+        semi_major_axis_km: a,
+        h: 0.0,
+        k: 0.0,
+        p: w.x / denom,
+        q: -w.y / denom,
+        mean_longitude_deg: 0.0,
+        retrograde,
+    };
+    let [f, g, _] = el.frame();
+    let e_vec = ((v.norm_squared() - MU_EARTH / r_mag) * r - r.dot(v) * v) / MU_EARTH;
+    let (h, k) = (e_vec.dot(&g), e_vec.dot(&f));
+    if h * h + k * k >= 1.0 {
+        return None;
+    }
+
+    // eccentric longitude from the in-plane coordinates, then Kepler's equation
+    let (x1, y1) = (r.dot(&f), r.dot(&g));
+    let root = (1.0 - h * h - k * k).sqrt();
+    let beta = 1.0 / (1.0 + root);
+    let cos_f = k + ((1.0 - k * k * beta) * x1 - h * k * beta * y1) / (a * root);
+    let sin_f = h + ((1.0 - h * h * beta) * y1 - h * k * beta * x1) / (a * root);
+    let ecc_longitude = sin_f.atan2(cos_f);
+    el.h = h;
+    el.k = k;
+    el.mean_longitude_deg = (ecc_longitude + h * cos_f - k * sin_f).to_degrees().rem_euclid(360.0);
+    Some(el)
+}
+
+/// Equinoctial elements to Cartesian state (km, km/s); None for unbound elements
+pub fn equinoctial_to_state(el: &EquinoctialElements) -> Option<(Vector3<f64>, Vector3<f64>)> {
+    if el.semi_major_axis_km <= 0.0 || !el.semi_major_axis_km.is_finite() || el.h * el.h + el.k * el.k >= 1.0 {
+        return None;
+    }
+    let [f, g, _] = el.frame();
+    let ([x, y], [vx, vy]) = el.in_plane();
+    Some((f * x + g * y, f * vx + g * vy))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(a: f64, e: f64, i: f64, raan: f64, argp: f64, nu: f64) {
+        let el = KeplerianElements {
+            semi_major_axis_km: a,
+            eccentricity: e,
+            inclination_deg: i,
+            raan_deg: raan,
+            arg_perigee_deg: argp,
+            true_anomaly_deg: nu,
+        };
+        let (r, v) = to_state(&el).unwrap();
+        let back = from_state(&r, &v).unwrap();
+        let (r2, v2) = to_state(&back).unwrap();
+        assert!((r - r2).norm() < 1e-6, "position mismatch for {:?} -> {:?}", el, back);
+        assert!((v - v2).norm() < 1e-9, "velocity mismatch for {:?} -> {:?}", el, back);
+        assert!((back.semi_major_axis_km - a).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_round_trip_general() {
+        round_trip(7000.0, 0.01, 51.6, 120.0, 45.0, 300.0);
+        round_trip(26600.0, 0.74, 63.4, 250.0, 270.0, 10.0);
+    }
+
+    #[test]
+    fn test_round_trip_edge_cases() {
+        round_trip(7000.0, 0.0, 98.0, 30.0, 0.0, 75.0); // circular inclined
+        round_trip(24000.0, 0.7, 0.0, 0.0, 178.0, 20.0); // elliptical equatorial
+        round_trip(42164.0, 0.0, 0.0, 0.0, 0.0, 200.0); // circular equatorial
+        round_trip(42164.0, 0.0, 180.0, 0.0, 0.0, 200.0); // retrograde equatorial
+    }
+
+    #[test]
+    fn test_equinoctial_round_trip() {
+        let cases = [
+            (42164.0, 0.0, 0.0, 0.0, 0.0, 200.0),     // GEO: circular equatorial
+            (42164.0, 0.0002, 0.05, 80.0, 10.0, 30.0), // near-GEO
+            (7000.0, 0.01, 51.6, 120.0, 45.0, 300.0),
+            (26600.0, 0.74, 63.4, 250.0, 270.0, 10.0),
+            (7000.0, 0.001, 98.0, 30.0, 60.0, 75.0),
+            (42164.0, 0.0, 180.0, 0.0, 0.0, 200.0), // retrograde equatorial
+        ];
+        for (a, e, i, raan, argp, nu) in cases {
+            let el = KeplerianElements {
+                semi_major_axis_km: a,
+                eccentricity: e,
+                inclination_deg: i,
+                raan_deg: raan,
+                arg_perigee_deg: argp,
+                true_anomaly_deg: nu,
+            };
+            let (r, v) = to_state(&el).unwrap();
+            let eq = equinoctial_from_state(&r, &v).unwrap();
+            assert_eq!(eq.retrograde, i > 90.0);
+            assert!((eq.eccentricity() - e).abs() < 1e-9 && (eq.inclination_deg() - i).abs() < 1e-7);
+            let (r2, v2) = equinoctial_to_state(&eq).unwrap();
+            assert!((r - r2).norm() < 1e-6 && (v - v2).norm() < 1e-9, "{:?}", eq);
+
+            let direct = EquinoctialElements::from_keplerian(&el).unwrap();
+            assert!((direct.semi_major_axis_km - eq.semi_major_axis_km).abs() < 1e-6);
+            assert!((direct.h - eq.h).abs() < 1e-9 && (direct.k - eq.k).abs() < 1e-9);
+            assert!((direct.p - eq.p).abs() < 1e-9 && (direct.q - eq.q).abs() < 1e-9);
+            let (r3, _) = to_state(&eq.to_keplerian()).unwrap();
+            assert!((r - r3).norm() < 1e-6, "{:?}", eq.to_keplerian());
+        }
+    }
+
+    #[test]
+    fn test_equinoctial_smooth_near_geo() {
+        // Keplerian angles jump as the orbit approaches circular equatorial; equinoctial ones do not
+        let state = |e: f64, i: f64| {
+            let el = KeplerianElements {
+                semi_major_axis_km: 42164.0,
+                eccentricity: e,
+                inclination_deg: i,
+                raan_deg: 75.0,
+                arg_perigee_deg: 40.0,
+                true_anomaly_deg: 100.0,
+            };
+            to_state(&el).unwrap()
+        };
+        let (r1, v1) = state(1e-7, 1e-6);
+        let (r2, v2) = state(2e-7, 2e-6);
+        let (a, b) = (equinoctial_from_state(&r1, &v1).unwrap(), equinoctial_from_state(&r2, &v2).unwrap());
+        assert!((a.mean_longitude_deg - b.mean_longitude_deg).abs() < 1e-4);
+        assert!(a.h.hypot(a.k) < 1e-6 && a.p.hypot(a.q) < 1e-7);
+    }
+
+    #[test]
+    fn test_known_state() {
+        // Vallado example 2-5
+        let r = Vector3::new(6524.834, 6862.875, 6448.296);
+        let v = Vector3::new(4.901327, 5.533756, -1.976341);
+        let el = from_state(&r, &v).unwrap();
+        assert!((el.semi_major_axis_km - 36127.343).abs() < 1.0);
+        assert!((el.eccentricity - 0.832853).abs() < 1e-4);
+        assert!((el.inclination_deg - 87.870).abs() < 1e-2);
+        assert!((el.raan_deg - 227.89).abs() < 1e-2);
+        assert!((el.arg_perigee_deg - 53.38).abs() < 1e-2);
+        assert!((el.true_anomaly_deg - 92.335).abs() < 1e-2);
+    }
+}
@@ -0,0 +1,711 @@
+//! Time-tagged ephemerides and CCSDS Orbit Ephemeris Message (OEM) input
+//!
+//! An `Ephemeris` holds state vectors at increasing epochs, stored as
+//! seconds from the first sample so interpolation keeps sub-millisecond
+//! resolution. States between samples are obtained with a sliding-window
+//! interpolator: Hermite (default) fits one polynomial to positions and
+//! velocities together and differentiates it for the velocity; Lagrange
+//! interpolates position and velocity separately, as some OEMs request.
+//!
+//! The OEM reader follows CCSDS 502.0-B-2 KVN. Segments for the same object
+//! are concatenated; covariance blocks are skipped.
+
+use nalgebra::Vector3;
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "python")]
+use std::fs;
+
+use crate::designator;
+use crate::epoch::{self, Epoch, TimeScale};
+use crate::frames;
+#[cfg(feature = "python")]
+use crate::json;
+#[cfg(feature = "python")]
+use crate::tabular;
+#[cfg(feature = "python")]
+use crate::Satellite;
+
+/// Interpolation degree used when none is specified
+const DEFAULT_DEGREE: usize = 7;
+
+type State = (Vector3<f64>, Vector3<f64>);
+
+/// Interpolation scheme between samples
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Interpolation {
+    /// Position and velocity interpolated independently
+    Lagrange,
+    /// Position polynomial matching both positions and velocities
+    Hermite,
+}
+
+impl Interpolation {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Interpolation::Lagrange => "lagrange",
+            Interpolation::Hermite => "hermite",
+        }
+    }
+}
+
+impl std::str::FromStr for Interpolation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "lagrange" => Ok(Interpolation::Lagrange),
+            "hermite" => Ok(Interpolation::Hermite),
+            _ => Err(format!("Unknown interpolation '{}' (expected 'hermite' or 'lagrange')", s)),
+        }
+    }
+}
+
+/// Tabulated states of one object
+#[cfg_attr(feature = "python", pyclass)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Ephemeris {
+    pub norad_id: i32,
+
+    #[serde(default)]
+    pub name: Option<String>,
+
+    pub ref_frame: String,
+
+    pub reference: Epoch, // epoch of the first sample
+
+    pub times: Vec<f64>, // seconds from `reference`, strictly increasing
+
+    #[serde(with = "crate::json::state_rows")]
+    pub states: Vec<State>, // km, km/s
+
+    pub degree: usize, // polynomial degree of the interpolant
+
+    pub interpolation: Interpolation,
+}
+
+impl Ephemeris {
+    /// Build from epochs and states; samples are sorted and must be distinct
+    pub fn from_samples(
+        norad_id: i32,
+        mut samples: Vec<(Epoch, State)>,
+        ref_frame: &str,
+        interpolation: Interpolation,
+        degree: usize,
+    ) -> Result<Self, String> {
+        if samples.len() < 2 {
+            return Err("An ephemeris needs at least two samples".to_string());
+        }
+        samples.sort_by(|a, b| a.0.mjd_tai.total_cmp(&b.0.mjd_tai));
+        let reference = samples[0].0;
+        let times: Vec<f64> = samples.iter().map(|(e, _)| e.seconds_since(&reference)).collect();
+        if times.windows(2).any(|w| w[1] <= w[0]) {
+            return Err("Ephemeris epochs must be distinct".to_string());
+        }
+
+        Ok(Ephemeris {
+            norad_id,
+            name: None,
+            ref_frame: ref_frame.to_string(),
+            reference,
+            times,
+            states: samples.into_iter().map(|(_, s)| s).collect(),
+            degree: degree.max(1),
+            interpolation,
+        })
+    }
+
+    /// Check the sample table of an ephemeris not built by `from_samples`
+    pub fn validate(&self) -> Result<(), String> {
+        if self.times.len() != self.states.len() {
+            return Err(format!(
+                "Ephemeris has {} times but {} states",
+                self.times.len(),
+                self.states.len()
+            ));
+        }
+        if self.times.len() < 2 {
+            return Err("An ephemeris needs at least two samples".to_string());
+        }
+        if self.times[0] != 0.0 || self.times.windows(2).any(|w| w[1] <= w[0]) {
+            return Err("Ephemeris times must start at 0 and strictly increase".to_string());
+        }
+        if self.degree == 0 {
+            return Err("Interpolation degree must be at least 1".to_string());
+        }
+        Ok(())
+    }
+
+    /// Seconds from the first to the last sample
+    pub fn span(&self) -> f64 {
+        self.times[self.times.len() - 1]
+    }
+
+    /// Interpolated state at `seconds` after the first sample; None outside the span
+    pub fn state_at_offset(&self, seconds: f64) -> Option<State> {
+        if !(0.0..=self.span()).contains(&seconds) {
+            return None;
+        }
+        let n = self.times.len();
+        let points = match self.interpolation {
+            Interpolation::Lagrange => self.degree + 1,
+            Interpolation::Hermite => self.degree.div_ceil(2),
+        };
+        let points = points.max(2).min(n);
+
+        // window of `points` samples centred on the requested time
+        let upper = self.times.partition_point(|&t| t < seconds);
+        let start = upper.saturating_sub(points / 2).min(n - points);
+        let window = start..start + points;
+
+        let times = &self.times[window.clone()];
+        let states = &self.states[window];
+        if let Some(k) = times.iter().position(|&t| t == seconds) {
+            return Some(states[k]);
+        }
+        Some(match self.interpolation {
+            Interpolation::Lagrange => lagrange(times, states, seconds),
+            Interpolation::Hermite => hermite(times, states, seconds),
+        })
+    }
+
+    /// Interpolated state at a Julian date (UTC)
+    pub fn state_at_jd(&self, jd: f64) -> Option<State> {
+        self.state_at_offset(Epoch::from_jd_utc(jd).seconds_since(&self.reference))
+    }
+
+    /// Copy with every sample rotated into TEME, the frame SGP4 states are in
+    ///
+    /// Inertial frames (EME2000, GCRF, ICRF) and Earth-fixed ones (ITRF,
+    /// ECEF) are converted without polar motion, taking UT1 = UTC.
+    pub fn to_teme(&self) -> Result<Ephemeris, String> {
+        let frame = self.ref_frame.to_ascii_uppercase();
+        let convert: fn(&Vector3<f64>, &Vector3<f64>, f64) -> State = match frame.as_str() {
+            "TEME" => return Ok(self.clone()),
+            "EME2000" | "J2000" | "GCRF" | "ICRF" => |r, v, jd| {
+                let (r, v) = frames::gcrf_to_ecef_state(r, v, jd, 0.0, 0.0);
+                frames::ecef_to_teme_state(&r, &v, jd, 0.0, 0.0)
+            },
+            "ECEF" | "ECF" => |r, v, jd| frames::ecef_to_teme_state(r, v, jd, 0.0, 0.0),
+            itrf if itrf.starts_with("ITRF") => |r, v, jd| frames::ecef_to_teme_state(r, v, jd, 0.0, 0.0),
+            _ => return Err(format!("Cannot convert ephemeris frame {} to TEME", self.ref_frame)),
+        };
+        let states = self
+            .times
+            .iter()
+            .zip(&self.states)
+            .map(|(&t, (r, v))| convert(r, v, self.reference.plus_seconds(t).jd_utc()))
+            .collect();
+        Ok(Ephemeris { ref_frame: "TEME".to_string(), states, ..self.clone() })
+    }
+
+    #[cfg(feature = "python")]
+    fn satellite(&self, seconds: f64) -> PyResult<Satellite> {
+        let (r, v) = self.state_at_offset(seconds).ok_or_else(|| {
+            pyo3::exceptions::PyValueError::new_err(format!(
+                "Epoch outside the ephemeris span of object {}",
+                self.norad_id
+            ))
+        })?;
+        Ok(Satellite {
+            name: self.name.clone(),
+            ..Satellite::from_state(
+                self.norad_id,
+                r.into(),
+                v.into(),
+                Some(self.reference.plus_seconds(seconds)),
+            )
+        })
+    }
+}
+
+/// Lagrange interpolation of position and velocity through all given samples
+fn lagrange(times: &[f64], states: &[State], t: f64) -> State {
+    let mut position = Vector3::zeros();
+    let mut velocity = Vector3::zeros();
+    for (i, (ti, (ri, vi))) in times.iter().zip(states).enumerate() {
+        let weight: f64 = times
+            .iter()
+            .enumerate()
+            .filter(|&(j, _)| j != i)
+            .map(|(_, tj)| (t - tj) / (ti - tj))
+            .product();
+        position += ri * weight;
+        velocity += vi * weight;
+    }
+    (position, velocity)
+}
+
+/// Hermite interpolation through positions and velocities of all given samples
+///
+/// Newton divided differences over doubled nodes; the velocity is the
+/// derivative of the position polynomial.
+fn hermite(times: &[f64], states: &[State], t: f64) -> State {
+    let n = 2 * times.len();
+    let nodes: Vec<f64> = (0..n).map(|k| times[k / 2]).collect();
+    let mut coef: Vec<Vector3<f64>> = (0..n).map(|k| states[k / 2].0).collect();
+
+    for level in 1..n {
+        for k in (level..n).rev() {
+            coef[k] = if level == 1 && k % 2 == 1 {
+                states[k / 2].1
+            } else {
+                (coef[k] - coef[k - 1]) / (nodes[k] - nodes[k - level])
+            };
+        }
+    }
+
+    // Horner evaluation of the Newton form and its derivative
+    let mut position = coef[n - 1];
+    let mut velocity = Vector3::zeros();
+    for k in (0..n - 1).rev() {
+        velocity = velocity * (t - nodes[k]) + position;
+        position = position * (t - nodes[k]) + coef[k];
+    }
+    (position, velocity)
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl Ephemeris {
+    /// Ephemeris from epochs and matching position (km) / velocity (km/s) rows
+    #[new]
+    #[pyo3(signature = (norad_id, epochs, positions, velocities, interpolation = "hermite", degree = DEFAULT_DEGREE, ref_frame = "TEME", name = None))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        norad_id: i32,
+        epochs: Vec<Epoch>,
+        positions: Vec<Vec<f64>>,
+        velocities: Vec<Vec<f64>>,
+        interpolation: &str,
+        degree: usize,
+        ref_frame: &str,
+        name: Option<String>,
+    ) -> PyResult<Self> {
+        if positions.len() != epochs.len() || velocities.len() != epochs.len() {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "epochs, positions and velocities must have the same length ({}, {}, {})",
+                epochs.len(),
+                positions.len(),
+                velocities.len()
+            )));
+        }
+        if positions.iter().chain(&velocities).any(|row| row.len() != 3) {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "Position and velocity rows must have 3 components"
+            ));
+        }
+        let interpolation = interpolation.parse().map_err(pyo3::exceptions::PyValueError::new_err)?;
+        let samples = epochs
+            .into_iter()
+            .zip(positions.iter().zip(&velocities))
+            .map(|(epoch, (r, v))| (epoch, (Vector3::from_column_slice(r), Vector3::from_column_slice(v))))
+            .collect();
+
+        let mut ephemeris = Ephemeris::from_samples(norad_id, samples, ref_frame, interpolation, degree)
+            .map_err(pyo3::exceptions::PyValueError::new_err)?;
+        ephemeris.name = name;
+        Ok(ephemeris)
+    }
+
+    #[getter]
+    fn get_norad_id(&self) -> i32 {
+        self.norad_id
+    }
+
+    #[setter]
+    fn set_norad_id(&mut self, value: i32) {
+        self.norad_id = value;
+    }
+
+    #[getter]
+    fn get_name(&self) -> Option<String> {
+        self.name.clone()
+    }
+
+    #[setter]
+    fn set_name(&mut self, value: Option<String>) {
+        self.name = value;
+    }
+
+    #[getter]
+    fn get_ref_frame(&self) -> String {
+        self.ref_frame.clone()
+    }
+
+    /// Epoch of the first sample
+    #[getter]
+    fn get_reference(&self) -> Epoch {
+        self.reference
+    }
+
+    #[getter]
+    fn get_degree(&self) -> usize {
+        self.degree
+    }
+
+    #[setter]
+    fn set_degree(&mut self, value: usize) {
+        self.degree = value;
+    }
+
+    /// Interpolation scheme ("hermite" or "lagrange")
+    #[getter(interpolation)]
+    fn get_interpolation(&self) -> &'static str {
+        self.interpolation.name()
+    }
+
+    #[setter(interpolation)]
+    fn set_interpolation(&mut self, value: &str) -> PyResult<()> {
+        self.interpolation = value.parse().map_err(pyo3::exceptions::PyValueError::new_err)?;
+        Ok(())
+    }
+
+    /// JSON text with every sample; `pretty` indents it
+    #[pyo3(signature = (pretty = false))]
+    fn to_json(&self, pretty: bool) -> PyResult<String> {
+        json::to_json(self, pretty)
+    }
+
+    /// Ephemeris from `to_json` output
+    #[staticmethod]
+    fn from_json(text: &str) -> PyResult<Self> {
+        let ephemeris: Ephemeris = json::from_json(text)?;
+        ephemeris.validate().map_err(pyo3::exceptions::PyValueError::new_err)?;
+        Ok(ephemeris)
+    }
+
+    /// Write every sample to `path` as CSV
+    fn write_csv(&self, py: Python<'_>, path: &str) -> PyResult<()> {
+        let table = tabular::ephemeris_table(self);
+        py.allow_threads(|| tabular::write_csv(&table, std::path::Path::new(path)))
+            .map_err(|e| tabular::io_error(path, e))
+    }
+
+    /// Write every sample to `path` as Parquet
+    #[cfg(feature = "parquet")]
+    fn write_parquet(&self, py: Python<'_>, path: &str) -> PyResult<()> {
+        let table = tabular::ephemeris_table(self);
+        py.allow_threads(|| tabular::write_parquet(&table, std::path::Path::new(path)))
+            .map_err(|e| tabular::io_error(path, e))
+    }
+
+    /// Julian date (UTC) of the first sample
+    #[getter]
+    fn start_jd(&self) -> f64 {
+        self.reference.jd_utc()
+    }
+
+    /// Julian date (UTC) of the last sample
+    #[getter]
+    fn stop_jd(&self) -> f64 {
+        self.reference.plus_seconds(self.span()).jd_utc()
+    }
+
+    /// Epochs of all samples
+    #[getter]
+    fn epochs(&self) -> Vec<Epoch> {
+        self.times.iter().map(|&t| self.reference.plus_seconds(t)).collect()
+    }
+
+    /// Interpolated state at an absolute Julian date (UTC)
+    fn propagate_to(&self, jd: f64) -> PyResult<Satellite> {
+        self.satellite(Epoch::from_jd_utc(jd).seconds_since(&self.reference))
+    }
+
+    /// Interpolated state at an `Epoch`
+    fn state_at(&self, epoch: &Epoch) -> PyResult<Satellite> {
+        self.satellite(epoch.seconds_since(&self.reference))
+    }
+
+    fn __len__(&self) -> usize {
+        self.times.len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Ephemeris(norad_id={}, samples={}, start={} UTC, span={:.1}s, frame={}, {}({}))",
+            self.norad_id,
+            self.times.len(),
+            epoch::format_iso(self.reference.mjd_in(TimeScale::Utc)),
+            self.span(),
+            self.ref_frame,
+            self.interpolation.name(),
+            self.degree
+        )
+    }
+}
+
+/// Metadata of the OEM segment being read
+#[derive(Default)]
+struct OemMetadata {
+    object_name: Option<String>,
+    object_id: Option<String>,
+    ref_frame: Option<String>,
+    time_system: Option<TimeScale>,
+    interpolation: Option<Interpolation>,
+    degree: Option<usize>,
+}
+
+/// Split a `KEY = value` line
+fn keyword(line: &str) -> Option<(&str, &str)> {
+    line.split_once('=').map(|(k, v)| (k.trim(), v.trim()))
+}
+
+/// Parse the text of an OEM (KVN) into one ephemeris
+///
+/// `norad_id` overrides the object identifier; otherwise OBJECT_ID must be
+/// a catalog number.
+pub fn parse_oem(text: &str, norad_id: Option<i32>) -> Result<Ephemeris, String> {
+    let mut samples = Vec::new();
+    let mut segments: Vec<OemMetadata> = Vec::new();
+    let mut in_metadata = false;
+    let mut in_covariance = false;
+    let mut seen_version = false;
+
+    for (idx, raw) in text.lines().enumerate() {
+        let lineno = idx + 1;
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with("COMMENT") {
+            continue;
+        }
+        let err = |msg: &str| format!("line {}: {}", lineno, msg);
+
+        match line {
+            "META_START" => {
+                in_metadata = true;
+                segments.push(OemMetadata::default());
+                continue;
+            }
+            "META_STOP" => {
+                in_metadata = false;
+                let meta = segments.last().ok_or_else(|| err("META_STOP without META_START"))?;
+                if meta.time_system.is_none() {
+                    return Err(err("segment has no supported TIME_SYSTEM (UTC, TAI or TT)"));
+                }
+                if meta.object_id != segments[0].object_id {
+                    return Err(err("segments describe different objects"));
+                }
+                if meta.ref_frame != segments[0].ref_frame {
+                    return Err(err("segments use different reference frames"));
+                }
+                continue;
+            }
+            "COVARIANCE_START" => {
+                in_covariance = true;
+                continue;
+            }
+            "COVARIANCE_STOP" => {
+                in_covariance = false;
+                continue;
+            }
+            _ => {}
+        }
+        if in_covariance {
+            continue;
+        }
+
+        if in_metadata {
+            let meta = segments.last_mut().expect("inside META_START");
+            let (key, value) = keyword(line).ok_or_else(|| err("expected KEY = value"))?;
+            match key {
+                "OBJECT_NAME" => meta.object_name = Some(value.to_string()),
+                "OBJECT_ID" => meta.object_id = Some(value.to_string()),
+                "REF_FRAME" => meta.ref_frame = Some(value.to_string()),
+                "TIME_SYSTEM" => meta.time_system = value.parse().ok(),
+                "INTERPOLATION" => meta.interpolation = Some(value.parse().map_err(|e: String| err(&e))?),
+                "INTERPOLATION_DEGREE" => {
+                    meta.degree = Some(value.parse().map_err(|_| err("invalid INTERPOLATION_DEGREE"))?)
+                }
+                _ => {}
+            }
+            continue;
+        }
+
+        if let Some((key, value)) = keyword(line) {
+            if key == "CCSDS_OEM_VERS" {
+                seen_version = true;
+                if !value.starts_with('1') && !value.starts_with('2') {
+                    return Err(err("unsupported OEM version"));
+                }
+            }
+            continue;
+        }
+
+        // ephemeris data line: epoch x y z vx vy vz [ax ay az]
+        let scale = segments
+            .last()
+            .and_then(|meta| meta.time_system)
+            .ok_or_else(|| err("data line before the first metadata block"))?;
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() != 7 && fields.len() != 10 {
+            return Err(err("expected epoch followed by 6 or 9 values"));
+        }
+        let mjd = epoch::parse_iso(fields[0]).map_err(|e| err(&e))?;
+        let values = fields[1..7]
+            .iter()
+            .map(|f| f.parse::<f64>())
+            .collect::<Result<Vec<f64>, _>>()
+            .map_err(|_| err("invalid state value"))?;
+        samples.push((
+            Epoch::from_mjd_scale(mjd, scale),
+            (Vector3::new(values[0], values[1], values[2]), Vector3::new(values[3], values[4], values[5])),
+        ));
+    }
+
+    if !seen_version {
+        return Err("missing CCSDS_OEM_VERS header".to_string());
+    }
+    if samples.is_empty() {
+        return Err("OEM contains no ephemeris data".to_string());
+    }
+    let meta = segments.swap_remove(0);
+    let norad_id = match norad_id {
+        Some(id) => id,
+        None => meta
+            .object_id
+            .as_deref()
+            .and_then(|id| designator::parse_catalog_number(id).ok())
+            .ok_or_else(|| {
+                format!(
+                    "OBJECT_ID {:?} is not a catalog number; pass norad_id explicitly",
+                    meta.object_id.as_deref().unwrap_or("")
+                )
+            })?,
+    };
+
+    let mut ephemeris = Ephemeris::from_samples(
+        norad_id,
+        samples,
+        meta.ref_frame.as_deref().unwrap_or("UNKNOWN"),
+        meta.interpolation.unwrap_or(Interpolation::Hermite),
+        meta.degree.unwrap_or(DEFAULT_DEGREE),
+    )?;
+    ephemeris.name = meta.object_name;
+    Ok(ephemeris)
+}
+
+/// Parse OEM text (KVN) into an ephemeris
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(signature = (text, norad_id = None))]
+pub fn parse_oem_text(text: &str, norad_id: Option<i32>) -> PyResult<Ephemeris> {
+    parse_oem(text, norad_id).map_err(pyo3::exceptions::PyValueError::new_err)
+}
+
+/// Read an OEM file (KVN) into an ephemeris
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(signature = (path, norad_id = None))]
+pub fn read_oem(path: &str, norad_id: Option<i32>) -> PyResult<Ephemeris> {
+    let text = fs::read_to_string(path)
+        .map_err(|e| pyo3::exceptions::PyIOError::new_err(format!("{}: {}", path, e)))?;
+    parse_oem(&text, norad_id).map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("{}: {}", path, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::{MU_EARTH, SECONDS_PER_DAY};
+
+    /// OEM text sampling a circular equatorial orbit every 60 s
+    fn circular_oem(samples: usize) -> String {
+        let radius: f64 = 7000.0;
+        let n = (MU_EARTH / radius.powi(3)).sqrt();
+        let speed = n * radius;
+        let mut text = String::from(
+            "CCSDS_OEM_VERS = 2.0\nCREATION_DATE = 2024-03-01T00:00:00\nORIGINATOR = TEST\n\n\
+             META_START\nOBJECT_NAME = TESTSAT\nOBJECT_ID = 99999\nCENTER_NAME = EARTH\n\
+             REF_FRAME = EME2000\nTIME_SYSTEM = UTC\nINTERPOLATION = LAGRANGE\nINTERPOLATION_DEGREE = 7\nMETA_STOP\n\
+             COMMENT sampled two-body orbit\n",
+        );
+        for k in 0..samples {
+            let t = 60.0 * k as f64;
+            let (s, c) = (n * t).sin_cos();
+            let mjd = epoch::parse_iso("2024-03-01T00:00:00").unwrap() + t / SECONDS_PER_DAY;
+            text.push_str(&format!(
+                "{} {:.9} {:.9} 0.0 {:.12} {:.12} 0.0\n",
+                epoch::format_iso(mjd),
+                radius * c,
+                radius * s,
+                -speed * s,
+                speed * c
+            ));
+        }
+        text.push_str("COVARIANCE_START\nEPOCH = 2024-03-01T00:00:00\n1.0\nCOVARIANCE_STOP\n");
+        text
+    }
+
+    #[test]
+    fn test_parse_oem_metadata() {
+        let eph = parse_oem(&circular_oem(20), None).unwrap();
+        assert_eq!(eph.norad_id, 99999);
+        assert_eq!(eph.name.as_deref(), Some("TESTSAT"));
+        assert_eq!(eph.ref_frame, "EME2000");
+        assert_eq!(eph.times.len(), 20);
+        assert_eq!(eph.degree, 7);
+        assert_eq!(eph.interpolation, Interpolation::Lagrange);
+        assert!((eph.span() - 1140.0).abs() < 1e-6);
+
+        let text = circular_oem(5).replace("OBJECT_ID = 99999", "OBJECT_ID = 2024-001A");
+        assert!(parse_oem(&text, None).is_err());
+        assert_eq!(parse_oem(&text, Some(7)).unwrap().norad_id, 7);
+    }
+
+    #[test]
+    fn test_lagrange_between_samples() {
+        let eph = parse_oem(&circular_oem(20), None).unwrap();
+        let n = (MU_EARTH / 7000f64.powi(3)).sqrt();
+        let t = 425.5;
+        let (r, v) = eph.state_at_offset(t).unwrap();
+        let expected = Vector3::new(7000.0 * (n * t).cos(), 7000.0 * (n * t).sin(), 0.0);
+        assert!((r - expected).norm() < 1e-5, "{}", (r - expected).norm());
+        assert!((v.norm() - n * 7000.0).abs() < 1e-8);
+        assert!(eph.state_at_offset(-1.0).is_none());
+        assert!(eph.state_at_offset(eph.span() + 1.0).is_none());
+    }
+
+    #[test]
+    fn test_hermite_beats_lagrange_on_sparse_samples() {
+        let mut eph = parse_oem(&circular_oem(40).replace("INTERPOLATION = LAGRANGE\n", ""), None).unwrap();
+        assert_eq!(eph.interpolation, Interpolation::Hermite);
+
+        // every fifth sample: 300 s spacing
+        eph.times = eph.times.iter().step_by(5).copied().collect();
+        eph.states = eph.states.iter().step_by(5).copied().collect();
+        let n = (MU_EARTH / 7000f64.powi(3)).sqrt();
+        let t = 1050.0;
+        let expected = Vector3::new(7000.0 * (n * t).cos(), 7000.0 * (n * t).sin(), 0.0);
+
+        let (r_hermite, v_hermite) = eph.state_at_offset(t).unwrap();
+        eph.interpolation = Interpolation::Lagrange;
+        eph.degree = 3;
+        let (r_lagrange, _) = eph.state_at_offset(t).unwrap();
+
+        let hermite_error = (r_hermite - expected).norm();
+        assert!(hermite_error < 1e-3, "{}", hermite_error);
+        assert!(hermite_error < (r_lagrange - expected).norm());
+        assert!((v_hermite.norm() - n * 7000.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_to_teme_rotates_inertial_samples() {
+        let eph = parse_oem(&circular_oem(10), None).unwrap();
+        let teme = eph.to_teme().unwrap();
+        assert_eq!(teme.ref_frame, "TEME");
+        for k in [0, 9] {
+            let (r, v) = eph.states[k];
+            let (r_teme, v_teme) = teme.states[k];
+            assert!((r_teme.norm() - r.norm()).abs() < 1e-8 && (v_teme.norm() - v.norm()).abs() < 1e-6);
+            // precession since J2000 turns the frame by a few tenths of a degree
+            let angle = r_teme.angle(&r).to_degrees();
+            assert!(angle > 0.05 && angle < 1.0, "{}", angle);
+        }
+        assert_eq!(teme.to_teme().unwrap().states, teme.states);
+        let mut unknown = eph.clone();
+        unknown.ref_frame = "RTN".to_string();
+        assert!(unknown.to_teme().is_err());
+    }
+}
@@ -0,0 +1,364 @@
+//! Epochs and time scales
+//!
+//! An `Epoch` is stored as a TAI modified Julian date so that UTC, TAI and
+//! TT views differ only by table lookups. UTC conversions use the IERS
+//! leap-second table; instants before 1972 use the 1972 offset.
+
+#[cfg(feature = "python")]
+use pyo3::basic::CompareOp;
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::constants::SECONDS_PER_DAY;
+
+/// Offset between Julian date and modified Julian date
+pub const MJD_OFFSET: f64 = 2400000.5;
+
+/// TT - TAI (seconds)
+const TT_MINUS_TAI: f64 = 32.184;
+
+/// Leap seconds: (UTC MJD from which the offset applies, TAI - UTC in seconds)
+#[rustfmt::skip]
+const LEAP_SECONDS: [(f64, f64); 28] = [
+    (41317.0, 10.0), (41499.0, 11.0), (41683.0, 12.0), (42048.0, 13.0),
+    (42413.0, 14.0), (42778.0, 15.0), (43144.0, 16.0), (43509.0, 17.0),
+    (43874.0, 18.0), (44239.0, 19.0), (44786.0, 20.0), (45151.0, 21.0),
+    (45516.0, 22.0), (46247.0, 23.0), (47161.0, 24.0), (47892.0, 25.0),
+    (48257.0, 26.0), (48804.0, 27.0), (49169.0, 28.0), (49534.0, 29.0),
+    (50083.0, 30.0), (50630.0, 31.0), (51179.0, 32.0), (53736.0, 33.0),
+    (54832.0, 34.0), (56109.0, 35.0), (57204.0, 36.0), (57754.0, 37.0),
+];
+
+/// Supported time scales
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimeScale {
+    Utc,
+    Tai,
+    Tt,
+}
+
+impl std::str::FromStr for TimeScale {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_uppercase().as_str() {
+            "UTC" => Ok(TimeScale::Utc),
+            "TAI" => Ok(TimeScale::Tai),
+            "TT" => Ok(TimeScale::Tt),
+            _ => Err(format!("Unknown time scale '{}' (expected UTC, TAI or TT)", s)),
+        }
+    }
+}
+
+#[cfg(feature = "python")]
+fn parse_scale(scale: &str) -> PyResult<TimeScale> {
+    scale.parse().map_err(pyo3::exceptions::PyValueError::new_err)
+}
+
+/// TAI - UTC (seconds) at a UTC modified Julian date
+pub fn tai_minus_utc(mjd_utc: f64) -> f64 {
+    LEAP_SECONDS
+        .iter()
+        .rev()
+        .find(|(start, _)| mjd_utc >= *start)
+        .map_or(LEAP_SECONDS[0].1, |(_, offset)| *offset)
+}
+
+/// Days from 1970-01-01 for a proleptic Gregorian date (Hinnant's algorithm)
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+/// Proleptic Gregorian date for days from 1970-01-01
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    (if month <= 2 { yoe + era * 400 + 1 } else { yoe + era * 400 }, month, day)
+}
+
+/// Days in a month of the proleptic Gregorian calendar
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// MJD of 1970-01-01
+const MJD_UNIX_EPOCH: i64 = 40587;
+
+/// Supported modified Julian dates: 0001-01-01 up to (not including) 10000-01-01
+const MJD_RANGE: std::ops::Range<f64> = -678575.0..2973484.0;
+
+/// Reject a non-finite MJD or one outside the four-digit years `format_iso` writes
+pub fn check_mjd(mjd: f64) -> Result<f64, String> {
+    if MJD_RANGE.contains(&mjd) {
+        Ok(mjd)
+    } else {
+        Err(format!("Epoch MJD {:?} is not finite or outside years 1-9999", mjd))
+    }
+}
+
+/// Parse `YYYY-MM-DD[(T| )hh:mm[:ss[.fff]]][Z]` into a modified Julian date
+///
+/// The ordinal form `YYYY-DDD` used by CCSDS messages is accepted as well.
+pub fn parse_iso(text: &str) -> Result<f64, String> {
+    let err = || format!("Invalid ISO-8601 timestamp '{}'", text);
+    let text = text.trim();
+    let text = text.strip_suffix('Z').unwrap_or(text);
+    let (date, time) = match text.find(['T', ' ']) {
+        Some(idx) => (&text[..idx], &text[idx + 1..]),
+        None => (text, ""),
+    };
+
+    let parts: Vec<&str> = date.split('-').collect();
+    if !(parts.len() == 2 || parts.len() == 3) || parts[0].len() != 4 {
+        return Err(err());
+    }
+    let year: i64 = parts[0].parse().map_err(|_| err())?;
+    let days = if parts.len() == 2 {
+        let day_of_year: i64 = parts[1].parse().map_err(|_| err())?;
+        if parts[1].len() != 3 || !(1..=366).contains(&day_of_year) {
+            return Err(err());
+        }
+        days_from_civil(year, 1, 1) + day_of_year - 1
+    } else {
+        let month: i64 = parts[1].parse().map_err(|_| err())?;
+        let day: i64 = parts[2].parse().map_err(|_| err())?;
+        if !(1..=12).contains(&month) || !(1..=days_in_month(year, month)).contains(&day) {
+            return Err(err());
+        }
+        days_from_civil(year, month, day)
+    };
+
+    let mut seconds_of_day = 0.0;
+    if !time.is_empty() {
+        let fields: Vec<&str> = time.split(':').collect();
+        if fields.len() < 2 || fields.len() > 3 {
+            return Err(err());
+        }
+        let hour: f64 = fields[0].parse().map_err(|_| err())?;
+        let minute: f64 = fields[1].parse().map_err(|_| err())?;
+        let second: f64 = match fields.get(2) {
+            Some(s) => s.parse().map_err(|_| err())?,
+            None => 0.0,
+        };
+        if !(0.0..24.0).contains(&hour) || !(0.0..60.0).contains(&minute) || !(0.0..61.0).contains(&second) {
+            return Err(err());
+        }
+        seconds_of_day = hour * 3600.0 + minute * 60.0 + second;
+    }
+
+    Ok((days + MJD_UNIX_EPOCH) as f64 + seconds_of_day / SECONDS_PER_DAY)
+}
+
+/// Current UTC time as a modified Julian date
+pub fn now_mjd_utc() -> f64 {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0.0, |d| d.as_secs_f64());
+    MJD_UNIX_EPOCH as f64 + seconds / SECONDS_PER_DAY
+}
+
+/// Format a modified Julian date as `YYYY-MM-DDThh:mm:ss.ffffff`
+pub fn format_iso(mjd: f64) -> String {
+    let mut days = mjd.floor();
+    let mut micros = ((mjd - days) * SECONDS_PER_DAY * 1.0e6).round() as i64;
+    if micros >= 86_400_000_000 {
+        days += 1.0;
+        micros -= 86_400_000_000;
+    }
+    let (year, month, day) = civil_from_days(days as i64 - MJD_UNIX_EPOCH);
+    let (hour, rem) = (micros / 3_600_000_000, micros % 3_600_000_000);
+    let (minute, rem) = (rem / 60_000_000, rem % 60_000_000);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:06}",
+        year, month, day, hour, minute, rem / 1_000_000, rem % 1_000_000
+    )
+}
+
+/// Instant in time, stored as a TAI modified Julian date
+#[cfg_attr(feature = "python", pyclass(module = "orbit_core"))]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Epoch {
+    pub mjd_tai: f64,
+}
+
+impl Epoch {
+    /// Epoch from a modified Julian date in the given scale
+    pub fn from_mjd_scale(mjd: f64, scale: TimeScale) -> Self {
+        let mjd_tai = match scale {
+            TimeScale::Utc => mjd + tai_minus_utc(mjd) / SECONDS_PER_DAY,
+            TimeScale::Tai => mjd,
+            TimeScale::Tt => mjd - TT_MINUS_TAI / SECONDS_PER_DAY,
+        };
+        Epoch { mjd_tai }
+    }
+
+    /// Epoch from a UTC Julian date
+    pub fn from_jd_utc(jd: f64) -> Self {
+        Self::from_mjd_scale(jd - MJD_OFFSET, TimeScale::Utc)
+    }
+
+    /// Modified Julian date in the given scale
+    pub fn mjd_in(&self, scale: TimeScale) -> f64 {
+        match scale {
+            TimeScale::Utc => {
+                // the offset depends on UTC itself; one refinement settles it
+                let guess = self.mjd_tai - tai_minus_utc(self.mjd_tai) / SECONDS_PER_DAY;
+                self.mjd_tai - tai_minus_utc(guess) / SECONDS_PER_DAY
+            }
+            TimeScale::Tai => self.mjd_tai,
+            TimeScale::Tt => self.mjd_tai + TT_MINUS_TAI / SECONDS_PER_DAY,
+        }
+    }
+
+    /// UTC Julian date
+    pub fn jd_utc(&self) -> f64 {
+        self.mjd_in(TimeScale::Utc) + MJD_OFFSET
+    }
+
+    /// Elapsed seconds from `other` to `self`
+    pub fn seconds_since(&self, other: &Epoch) -> f64 {
+        (self.mjd_tai - other.mjd_tai) * SECONDS_PER_DAY
+    }
+
+    /// Epoch shifted by a number of SI seconds
+    pub fn plus_seconds(&self, seconds: f64) -> Self {
+        Epoch { mjd_tai: self.mjd_tai + seconds / SECONDS_PER_DAY }
+    }
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl Epoch {
+    /// Epoch from a Julian date (scale: "UTC", "TAI" or "TT")
+    #[new]
+    #[pyo3(signature = (jd, scale = "UTC"))]
+    fn new(jd: f64, scale: &str) -> PyResult<Self> {
+        let mjd = check_mjd(jd - MJD_OFFSET).map_err(pyo3::exceptions::PyValueError::new_err)?;
+        Ok(Self::from_mjd_scale(mjd, parse_scale(scale)?))
+    }
+
+    /// Epoch from a modified Julian date
+    #[staticmethod]
+    #[pyo3(signature = (mjd, scale = "UTC"))]
+    fn from_mjd(mjd: f64, scale: &str) -> PyResult<Self> {
+        let mjd = check_mjd(mjd).map_err(pyo3::exceptions::PyValueError::new_err)?;
+        Ok(Self::from_mjd_scale(mjd, parse_scale(scale)?))
+    }
+
+    /// Epoch from an ISO-8601 timestamp such as "2024-03-01T12:00:00.5Z"
+    #[staticmethod]
+    #[pyo3(signature = (text, scale = "UTC"))]
+    fn from_iso(text: &str, scale: &str) -> PyResult<Self> {
+        let mjd = parse_iso(text).map_err(pyo3::exceptions::PyValueError::new_err)?;
+        Ok(Self::from_mjd_scale(mjd, parse_scale(scale)?))
+    }
+
+    /// Julian date in the given scale
+    #[pyo3(signature = (scale = "UTC"))]
+    fn jd(&self, scale: &str) -> PyResult<f64> {
+        Ok(self.mjd_in(parse_scale(scale)?) + MJD_OFFSET)
+    }
+
+    /// Modified Julian date in the given scale
+    #[pyo3(signature = (scale = "UTC"))]
+    fn mjd(&self, scale: &str) -> PyResult<f64> {
+        Ok(self.mjd_in(parse_scale(scale)?))
+    }
+
+    /// ISO-8601 representation in the given scale (microsecond resolution)
+    #[pyo3(signature = (scale = "UTC"))]
+    fn iso(&self, scale: &str) -> PyResult<String> {
+        Ok(format_iso(self.mjd_in(parse_scale(scale)?)))
+    }
+
+    /// TAI - UTC (seconds) at this epoch
+    #[getter]
+    fn leap_seconds(&self) -> f64 {
+        tai_minus_utc(self.mjd_in(TimeScale::Utc))
+    }
+
+    /// Epoch shifted by `seconds`
+    fn __add__(&self, seconds: f64) -> PyResult<Self> {
+        let shifted = self.plus_seconds(seconds);
+        check_mjd(shifted.mjd_tai).map_err(pyo3::exceptions::PyValueError::new_err)?;
+        Ok(shifted)
+    }
+
+    /// Seconds between two epochs
+    fn __sub__(&self, other: &Epoch) -> f64 {
+        self.seconds_since(other)
+    }
+
+    fn __richcmp__(&self, other: &Epoch, op: CompareOp) -> bool {
+        op.matches(self.mjd_tai.total_cmp(&other.mjd_tai))
+    }
+
+    fn __hash__(&self) -> u64 {
+        crate::hash_key(&crate::float_key(self.mjd_tai))
+    }
+
+    /// Pickle support; the state restores the exact TAI MJD
+    fn __reduce__(&self, py: Python<'_>) -> (PyObject, (f64, &'static str), f64) {
+        (py.get_type::<Epoch>().into(), (self.mjd_tai + MJD_OFFSET, "TAI"), self.mjd_tai)
+    }
+
+    fn __setstate__(&mut self, mjd_tai: f64) {
+        self.mjd_tai = mjd_tai;
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Epoch({} UTC)", format_iso(self.mjd_in(TimeScale::Utc)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_iso_round_trip() {
+        let mjd = parse_iso("2024-03-01T12:30:15.25Z").unwrap();
+        assert!((mjd + MJD_OFFSET - 2460371.0210098).abs() < 1e-7);
+        assert_eq!(format_iso(mjd), "2024-03-01T12:30:15.250000");
+        assert_eq!(parse_iso("2000-01-01").unwrap(), 51544.0);
+        assert!(parse_iso("2024-13-01").is_err());
+        assert!(parse_iso("2024-02-31").is_err() && parse_iso("2023-02-29").is_err());
+        assert!(parse_iso("2024-02-29").is_ok() && parse_iso("2000-02-29").is_ok() && parse_iso("1900-02-29").is_err());
+        assert!(check_mjd(f64::NAN).is_err() && check_mjd(f64::INFINITY).is_err() && check_mjd(1e20).is_err());
+        assert_eq!(check_mjd(51544.0), Ok(51544.0));
+        assert_eq!(parse_iso("2024-061T12:30:15.25").unwrap(), mjd);
+    }
+
+    #[test]
+    fn test_time_scales() {
+        let epoch = Epoch::from_mjd_scale(parse_iso("2020-01-01T00:00:00").unwrap(), TimeScale::Utc);
+        let tt_minus_utc = (epoch.mjd_in(TimeScale::Tt) - epoch.mjd_in(TimeScale::Utc)) * SECONDS_PER_DAY;
+        assert!((tt_minus_utc - 69.184).abs() < 1e-6);
+
+        // 2016-12-31T23:59:60 was the most recent leap second
+        assert_eq!(tai_minus_utc(57753.5), 36.0);
+        assert_eq!(tai_minus_utc(57754.0), 37.0);
+        let before = Epoch::from_mjd_scale(57753.0, TimeScale::Utc);
+        let after = Epoch::from_mjd_scale(57754.0, TimeScale::Utc);
+        assert!((after.seconds_since(&before) - 86401.0).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,99 @@
+//! Crate error type and the Python exceptions it maps to
+//!
+//! `InvalidStateError` and `TleParseError` subclass `ValueError` and
+//! `PropagationError` subclasses `RuntimeError`, so existing handlers for the
+//! built-in exceptions keep working.
+
+#[cfg(feature = "python")]
+use pyo3::{
+    create_exception,
+    exceptions::{PyConnectionError, PyIOError, PyRuntimeError, PyValueError},
+    prelude::*,
+};
+use std::fmt;
+
+use crate::sgp4::Sgp4Error;
+
+#[cfg(feature = "python")]
+create_exception!(orbit_core, InvalidStateError, PyValueError, "A state vector, covariance or orbit is unusable.");
+#[cfg(feature = "python")]
+create_exception!(orbit_core, PropagationError, PyRuntimeError, "Propagation failed numerically.");
+#[cfg(feature = "python")]
+create_exception!(orbit_core, TleParseError, PyValueError, "A two-line element set could not be parsed.");
+
+/// Failure raised by the orbit engine
+#[derive(Debug, Clone, PartialEq)]
+pub enum OrbitError {
+    /// Malformed or physically unusable state, covariance or orbit
+    InvalidState(String),
+    /// Integrator or SGP4 failure (diverging, singular or decayed state)
+    Propagation(String),
+    /// Malformed two-line element set
+    TleParse(String),
+    /// Out-of-range argument such as a non-positive step or an empty window
+    InvalidInput(String),
+    /// A long-running screen was stopped through its cancellation token
+    Cancelled,
+    /// Database failure in the persistence layer
+    Storage(String),
+    /// Catalog download failed
+    Fetch(String),
+}
+
+impl fmt::Display for OrbitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OrbitError::InvalidState(msg)
+            | OrbitError::Propagation(msg)
+            | OrbitError::TleParse(msg)
+            | OrbitError::InvalidInput(msg)
+            | OrbitError::Storage(msg)
+            | OrbitError::Fetch(msg) => f.write_str(msg),
+            OrbitError::Cancelled => f.write_str("Screening was cancelled"),
+        }
+    }
+}
+
+impl std::error::Error for OrbitError {}
+
+impl From<Sgp4Error> for OrbitError {
+    fn from(err: Sgp4Error) -> OrbitError {
+        OrbitError::Propagation(err.to_string())
+    }
+}
+
+#[cfg(feature = "python")]
+impl From<OrbitError> for PyErr {
+    fn from(err: OrbitError) -> PyErr {
+        match err {
+            OrbitError::InvalidState(msg) => InvalidStateError::new_err(msg),
+            OrbitError::Propagation(msg) => PropagationError::new_err(msg),
+            OrbitError::TleParse(msg) => TleParseError::new_err(msg),
+            OrbitError::InvalidInput(msg) => PyValueError::new_err(msg),
+            OrbitError::Cancelled => PyRuntimeError::new_err(err.to_string()),
+            OrbitError::Storage(msg) => PyIOError::new_err(msg),
+            OrbitError::Fetch(msg) => PyConnectionError::new_err(msg),
+        }
+    }
+}
+
+/// Register the exception types on the extension module
+#[cfg(feature = "python")]
+pub fn register(py: Python<'_>, m: &PyModule) -> PyResult<()> {
+    m.add("InvalidStateError", py.get_type::<InvalidStateError>())?;
+    m.add("PropagationError", py.get_type::<PropagationError>())?;
+    m.add("TleParseError", py.get_type::<TleParseError>())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sgp4_failures_are_propagation_errors() {
+        let err = OrbitError::from(Sgp4Error::Decayed);
+        assert_eq!(err, OrbitError::Propagation("satellite has decayed".to_string()));
+        assert_eq!(err.to_string(), "satellite has decayed");
+    }
+}
@@ -0,0 +1,227 @@
+//! Merging conjunction hits into encounter events
+//!
+//! A windowed screen can report several hits for one physical encounter
+//! (slow relative motion gives multiple separation minima, and the window
+//! edges add boundary hits). Hits for the same pair whose TCAs lie within
+//! a time gap of each other are merged into one event that spans from the
+//! first to the last hit and keeps the closest one.
+
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use std::collections::HashMap;
+
+use crate::constants::SECONDS_PER_DAY;
+use crate::screening::brent;
+use crate::Conjunction;
+
+/// Tolerance on threshold crossing times (seconds)
+const CROSSING_TOLERANCE_S: f64 = 1.0e-3;
+
+/// One encounter between a pair, possibly made of several hits
+#[cfg_attr(feature = "python", pyclass(get_all))]
+#[derive(Clone)]
+pub struct ConjunctionEvent {
+    pub norad_id_1: i32,
+    pub norad_id_2: i32,
+    pub entry_jd: Option<f64>, // first hit, or threshold crossing when refined
+    pub exit_jd: Option<f64>,
+    pub hits: usize,
+    pub closest: Conjunction,
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl ConjunctionEvent {
+    /// Minimum separation over the event (km)
+    #[getter]
+    fn min_distance_km(&self) -> f64 {
+        self.closest.distance_km
+    }
+
+    /// Time of the closest approach (Julian date)
+    #[getter]
+    fn tca_jd(&self) -> Option<f64> {
+        self.closest.tca_jd
+    }
+
+    /// Seconds between entry and exit
+    #[getter]
+    #[pyo3(name = "duration")]
+    fn py_duration(&self) -> Option<f64> {
+        self.duration()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "ConjunctionEvent({} ↔ {}, min_dist={:.3}km, hits={})",
+            self.norad_id_1, self.norad_id_2, self.closest.distance_km, self.hits
+        )
+    }
+}
+
+impl ConjunctionEvent {
+    /// Seconds between entry and exit
+    pub fn duration(&self) -> Option<f64> {
+        Some((self.exit_jd? - self.entry_jd?) * SECONDS_PER_DAY)
+    }
+
+    fn from_hit(hit: Conjunction) -> Self {
+        ConjunctionEvent {
+            norad_id_1: hit.norad_id_1,
+            norad_id_2: hit.norad_id_2,
+            entry_jd: hit.tca_jd,
+            exit_jd: hit.tca_jd,
+            hits: 1,
+            closest: hit,
+        }
+    }
+
+    fn absorb(&mut self, hit: Conjunction) {
+        self.exit_jd = hit.tca_jd.or(self.exit_jd);
+        self.hits += 1;
+        if hit.distance_km < self.closest.distance_km {
+            self.closest = hit;
+        }
+    }
+
+    /// Widen entry/exit to where `distance_at(jd)` crosses `threshold_km`
+    ///
+    /// Walks outwards from the TCA in `step_s` increments, staying inside
+    /// `[start_jd, end_jd]`; an event still inside the threshold at a window
+    /// edge keeps that edge.
+    pub fn refine_boundaries<F: Fn(f64) -> Option<f64>>(
+        &mut self,
+        distance_at: F,
+        threshold_km: f64,
+        (start_jd, end_jd): (f64, f64),
+        step_s: f64,
+    ) {
+        let Some(tca) = self.closest.tca_jd else {
+            return;
+        };
+        let excess = |jd: f64| distance_at(jd).map(|d| d - threshold_km);
+        let crossing = |limit: f64, direction: f64| -> Option<f64> {
+            let step = direction * step_s / SECONDS_PER_DAY;
+            let (mut inner, mut f_inner) = (tca, excess(tca)?);
+            loop {
+                let outer = if (limit - (inner + step)) * direction <= 0.0 { limit } else { inner + step };
+                let f_outer = excess(outer)?;
+                if f_outer >= 0.0 {
+                    // root finding in seconds keeps the tolerance meaningful
+                    let seconds = |jd: f64| (jd - tca) * SECONDS_PER_DAY;
+                    let root = brent(
+                        |s| excess(tca + s / SECONDS_PER_DAY),
+                        seconds(inner),
+                        seconds(outer),
+                        f_inner,
+                        f_outer,
+                        CROSSING_TOLERANCE_S,
+                    )?;
+                    return Some(tca + root / SECONDS_PER_DAY);
+                }
+                if outer == limit {
+                    return Some(limit);
+                }
+                (inner, f_inner) = (outer, f_outer);
+            }
+        };
+        if let Some(entry) = crossing(start_jd, -1.0) {
+            self.entry_jd = Some(self.entry_jd.map_or(entry, |first| first.min(entry)));
+        }
+        if let Some(exit) = crossing(end_jd, 1.0) {
+            self.exit_jd = Some(self.exit_jd.map_or(exit, |last| last.max(exit)));
+        }
+    }
+}
+
+/// Merge hits of the same (unordered) pair whose TCAs are at most `max_gap_s` apart
+///
+/// Hits without a TCA each form their own event. Events are returned sorted
+/// by pair ids, then time.
+pub fn cluster(conjunctions: Vec<Conjunction>, max_gap_s: f64) -> Vec<ConjunctionEvent> {
+    let mut by_pair: HashMap<(i32, i32), Vec<Conjunction>> = HashMap::new();
+    for hit in conjunctions {
+        let key = (hit.norad_id_1.min(hit.norad_id_2), hit.norad_id_1.max(hit.norad_id_2));
+        by_pair.entry(key).or_default().push(hit);
+    }
+
+    let mut events = Vec::new();
+    for (_, mut hits) in by_pair {
+        hits.sort_by(|a, b| {
+            let tca = |c: &Conjunction| c.tca_jd.unwrap_or(f64::NEG_INFINITY);
+            tca(a).total_cmp(&tca(b)).then(a.distance_km.total_cmp(&b.distance_km))
+        });
+        let mut current: Option<ConjunctionEvent> = None;
+        for hit in hits {
+            let joins = match (&current, hit.tca_jd) {
+                (Some(event), Some(tca)) => {
+                    event.exit_jd.is_some_and(|last| (tca - last) * SECONDS_PER_DAY <= max_gap_s)
+                }
+                _ => false,
+            };
+            if joins {
+                if let Some(event) = current.as_mut() {
+                    event.absorb(hit);
+                }
+            } else {
+                events.extend(current.replace(ConjunctionEvent::from_hit(hit)));
+            }
+        }
+        events.extend(current);
+    }
+
+    events.sort_by(|a, b| {
+        let tca = |e: &ConjunctionEvent| e.entry_jd.unwrap_or(f64::NEG_INFINITY);
+        (a.norad_id_1, a.norad_id_2).cmp(&(b.norad_id_1, b.norad_id_2)).then(tca(a).total_cmp(&tca(b)))
+    });
+    events
+}
+
+/// Merge conjunction hits into events (hits of one pair within `max_gap_s` seconds)
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(signature = (conjunctions, max_gap_s = 600.0))]
+pub fn cluster_conjunctions(conjunctions: Vec<Conjunction>, max_gap_s: f64) -> PyResult<Vec<ConjunctionEvent>> {
+    if max_gap_s < 0.0 {
+        return Err(pyo3::exceptions::PyValueError::new_err("max_gap_s must be non-negative"));
+    }
+    Ok(cluster(conjunctions, max_gap_s))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nalgebra::Vector3;
+
+    fn hit(id1: i32, id2: i32, distance: f64, tca_s: f64) -> Conjunction {
+        let (r, v) = (Vector3::new(7000.0, 0.0, 0.0), Vector3::new(0.0, 7.5, 0.0));
+        let other = r + Vector3::new(distance, 0.0, 0.0);
+        Conjunction::from_states(id1, id2, (&r, &v), (&other, &v), Some(2460000.5 + tca_s / SECONDS_PER_DAY))
+    }
+
+    #[test]
+    fn test_hits_within_gap_merge() {
+        let hits = vec![hit(1, 2, 3.0, 0.0), hit(2, 1, 1.0, 120.0), hit(1, 2, 2.0, 240.0), hit(1, 2, 0.5, 5000.0)];
+        let events = cluster(hits, 300.0);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].hits, 3);
+        assert!((events[0].closest.distance_km - 1.0).abs() < 1e-9);
+        assert!((events[0].duration().unwrap() - 240.0).abs() < 1e-3);
+        assert_eq!(events[1].hits, 1);
+    }
+
+    #[test]
+    fn test_refine_boundaries_finds_threshold_crossings() {
+        // separation |t| km/s about TCA: crossings of a 10 km threshold at +-10 s
+        let mut event = cluster(vec![hit(1, 2, 0.0, 0.0)], 60.0).remove(0);
+        let tca = event.closest.tca_jd.unwrap();
+        let distance = |jd: f64| Some(((jd - tca) * SECONDS_PER_DAY).abs());
+        event.refine_boundaries(distance, 10.0, (tca - 1.0, tca + 1.0), 3.0);
+        assert!((event.duration().unwrap() - 20.0).abs() < 1e-2);
+
+        // clipped at the window edge
+        let mut event = cluster(vec![hit(1, 2, 0.0, 0.0)], 60.0).remove(0);
+        event.refine_boundaries(distance, 10.0, (tca - 5.0 / SECONDS_PER_DAY, tca + 1.0), 3.0);
+        assert!(((tca - event.entry_jd.unwrap()) * SECONDS_PER_DAY - 5.0).abs() < 1e-3);
+    }
+}
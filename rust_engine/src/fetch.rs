@@ -0,0 +1,479 @@
+//! Catalog downloads from Celestrak and Space-Track
+//!
+//! Element sets are requested as OMM JSON (default) or TLE text over HTTPS.
+//! Space-Track's login cookie is only ever held in memory, and its requests
+//! don't follow redirects, so credentials and session go nowhere but
+//! space-track.org. Responses are cached on disk, in a
+//! directory only the current user can read, and reused while younger than
+//! `max_age`; Space-Track entries are kept apart per account, since what a
+//! query returns depends on who asks. Both providers refresh GP data only
+//! every few hours and block clients that poll harder. Requests to the same
+//! host are spaced at least `min_interval` apart across the whole process.
+
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::error::OrbitError;
+use crate::sgp4::MeanElements;
+use crate::{omm, tle};
+
+#[cfg(feature = "python")]
+use crate::catalog::Catalog;
+#[cfg(feature = "python")]
+use crate::epoch::{self, Epoch, MJD_OFFSET};
+#[cfg(feature = "python")]
+use crate::sgp4::Sgp4Propagator;
+
+const CELESTRAK_URL: &str = "https://celestrak.org/NORAD/elements/gp.php";
+const SPACE_TRACK_URL: &str = "https://www.space-track.org";
+
+/// Last request time per host, shared by every download in the process
+static LAST_REQUEST: Mutex<Option<HashMap<String, Instant>>> = Mutex::new(None);
+
+/// Element set provider
+#[derive(Clone, Debug, PartialEq)]
+pub enum Source {
+    Celestrak,
+    /// Needs a (free) space-track.org account
+    SpaceTrack { identity: String, password: String },
+}
+
+impl Source {
+    fn host(&self) -> &'static str {
+        match self {
+            Source::Celestrak => "celestrak.org",
+            Source::SpaceTrack { .. } => "www.space-track.org",
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Source::Celestrak => "celestrak",
+            Source::SpaceTrack { .. } => "spacetrack",
+        }
+    }
+}
+
+/// Which objects to download
+#[derive(Clone, Debug, PartialEq)]
+pub enum Query {
+    /// Celestrak group such as "active", "stations" or "starlink"; Space-Track
+    /// only offers "active" (every object on orbit with a recent element set)
+    Group(String),
+    NoradIds(Vec<i32>),
+    /// Objects whose name contains the text
+    Name(String),
+}
+
+/// Wire format of the element sets
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// CCSDS Orbit Mean-elements Message as JSON; needed for 6-digit catalog numbers
+    Omm,
+    Tle,
+}
+
+impl std::str::FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "omm" | "json" => Ok(Format::Omm),
+            "tle" => Ok(Format::Tle),
+            _ => Err(format!("Unknown element format '{}' (expected 'omm' or 'tle')", s)),
+        }
+    }
+}
+
+/// Caching and politeness settings
+#[derive(Clone, Debug)]
+pub struct FetchOptions {
+    /// Where responses are cached (created owner-only); None disables the cache
+    pub cache_dir: Option<PathBuf>,
+    pub max_age: Duration,
+    pub min_interval: Duration,
+    pub timeout: Duration,
+}
+
+impl Default for FetchOptions {
+    fn default() -> Self {
+        FetchOptions {
+            // the per-user cache directory (e.g. ~/.cache on Linux); no cache without one
+            cache_dir: dirs::cache_dir().map(|dir| dir.join("orbit_core")),
+            max_age: Duration::from_secs(2 * 3600),
+            min_interval: Duration::from_secs(2),
+            timeout: Duration::from_secs(120),
+        }
+    }
+}
+
+/// URLs to download for `query`, one per request
+fn request_urls(source: &Source, query: &Query, format: Format) -> Result<Vec<String>, OrbitError> {
+    match source {
+        Source::Celestrak => {
+            let format = match format {
+                Format::Omm => "json",
+                Format::Tle => "tle",
+            };
+            let url = |key: &str, value: &str| format!("{}?{}={}&FORMAT={}", CELESTRAK_URL, key, encode(value), format);
+            Ok(match query {
+                Query::Group(group) => vec![url("GROUP", group)],
+                // the GP API takes one catalog number per request
+                Query::NoradIds(ids) => ids.iter().map(|id| url("CATNR", &id.to_string())).collect(),
+                Query::Name(name) => vec![url("NAME", name)],
+            })
+        }
+        Source::SpaceTrack { .. } => {
+            let format = match format {
+                Format::Omm => "json",
+                Format::Tle => "3le",
+            };
+            let filter = match query {
+                Query::Group(group) if group.eq_ignore_ascii_case("active") => {
+                    "decay_date/null-val/epoch/%3Enow-30".to_string()
+                }
+                Query::Group(group) => {
+                    return Err(OrbitError::InvalidInput(format!(
+                        "Space-Track has no group '{}'; use 'active', NORAD ids or a name",
+                        group
+                    )))
+                }
+                Query::NoradIds(ids) => {
+                    let ids: Vec<String> = ids.iter().map(i32::to_string).collect();
+                    format!("norad_cat_id/{}", ids.join(","))
+                }
+                Query::Name(name) => format!("object_name/~~{}", encode(name)),
+            };
+            Ok(vec![format!(
+                "{}/basicspacedata/query/class/gp/{}/orderby/norad_cat_id/format/{}",
+                SPACE_TRACK_URL, filter, format
+            )])
+        }
+    }
+}
+
+/// Percent-encode a query value
+fn encode(text: &str) -> String {
+    text.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// Cache file for a request URL, named by a stable (FNV-1a) hash of the URL
+/// and, for Space-Track, of the account it was downloaded with
+fn cache_path(dir: &Path, source: &Source, url: &str) -> PathBuf {
+    let account = match source {
+        Source::SpaceTrack { identity, .. } => identity.as_str(),
+        Source::Celestrak => "",
+    };
+    let hash = account
+        .bytes()
+        .chain([b'\n'])
+        .chain(url.bytes())
+        .fold(0xcbf2_9ce4_8422_2325_u64, |h, b| (h ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3));
+    dir.join(format!("{}_{:016x}.txt", source.name(), hash))
+}
+
+/// Create the cache directory readable by its owner only
+fn create_cache_dir(dir: &Path) -> std::io::Result<()> {
+    let mut builder = fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+    builder.create(dir)
+}
+
+/// Cached body when the file is younger than `max_age`
+fn read_fresh(path: &Path, max_age: Duration) -> Option<String> {
+    let modified = fs::metadata(path).ok()?.modified().ok()?;
+    let age = SystemTime::now().duration_since(modified).unwrap_or_default();
+    (age <= max_age).then(|| fs::read_to_string(path).ok()).flatten()
+}
+
+/// Wait until `min_interval` has passed since the last request to `host`
+fn throttle(host: &str, min_interval: Duration) {
+    let mut last = LAST_REQUEST.lock().unwrap_or_else(|e| e.into_inner());
+    let last = last.get_or_insert_with(HashMap::new);
+    if let Some(previous) = last.get(host) {
+        let wait = min_interval.saturating_sub(previous.elapsed());
+        if !wait.is_zero() {
+            std::thread::sleep(wait);
+        }
+    }
+    last.insert(host.to_string(), Instant::now());
+}
+
+/// HTTP client; `redirects` is how many hops it follows (0 for Space-Track)
+fn agent(timeout: Duration, redirects: u32) -> ureq::Agent {
+    ureq::AgentBuilder::new()
+        .timeout(timeout)
+        .redirects(redirects)
+        .user_agent(concat!("orbit_core/", env!("CARGO_PKG_VERSION")))
+        .build()
+}
+
+/// Body of a successful (2xx) response
+fn read_body(url: &str, response: Result<ureq::Response, ureq::Error>) -> Result<String, OrbitError> {
+    // the query string never holds credentials, but it can be long
+    let shown = url.split('?').next().unwrap_or(url);
+    let response = response.map_err(|e| match e {
+        ureq::Error::Status(code, response) => {
+            OrbitError::Fetch(format!("{}: HTTP {} {}", shown, code, response.status_text()))
+        }
+        ureq::Error::Transport(e) => match e.message() {
+            Some(message) => OrbitError::Fetch(format!("{}: {}: {}", shown, e.kind(), message)),
+            None => OrbitError::Fetch(format!("{}: {}", shown, e.kind())),
+        },
+    })?;
+    if !(200..300).contains(&response.status()) {
+        return Err(OrbitError::Fetch(format!("{}: unexpected HTTP {} {}", shown, response.status(), response.status_text())));
+    }
+    // the full catalogue is larger than `into_string`'s 10 MB cap
+    let mut body = String::new();
+    response
+        .into_reader()
+        .read_to_string(&mut body)
+        .map_err(|e| OrbitError::Fetch(format!("{}: {}", shown, e)))?;
+    Ok(body)
+}
+
+/// Download `url`, logging in first for Space-Track
+fn download(source: &Source, url: &str, options: &FetchOptions) -> Result<String, OrbitError> {
+    match source {
+        Source::Celestrak => {
+            throttle(source.host(), options.min_interval);
+            read_body(url, agent(options.timeout, 5).get(url).call())
+        }
+        Source::SpaceTrack { identity, password } => {
+            // the session cookie lives in this agent and is dropped with it
+            let session = agent(options.timeout, 0);
+            let login = format!("{}/ajaxauth/login", SPACE_TRACK_URL);
+            throttle(source.host(), options.min_interval);
+            read_body(&login, session.post(&login).send_form(&[("identity", identity), ("password", password)]))?;
+            throttle(source.host(), options.min_interval);
+            read_body(url, session.get(url).call())
+        }
+    }
+}
+
+/// Download (or read from cache) the element sets matching `query`
+pub fn fetch_elements(
+    source: &Source,
+    query: &Query,
+    format: Format,
+    options: &FetchOptions,
+) -> Result<Vec<MeanElements>, OrbitError> {
+    let mut elements = Vec::new();
+    for url in request_urls(source, query, format)? {
+        let cached = options.cache_dir.as_ref().map(|dir| cache_path(dir, source, &url));
+        let body = match cached.as_deref().and_then(|path| read_fresh(path, options.max_age)) {
+            Some(body) => body,
+            None => {
+                let body = download(source, &url, options)?;
+                if let Some(path) = &cached {
+                    // a cache that cannot be written only costs a re-download
+                    let _ = create_cache_dir(path.parent().unwrap_or(path)).and_then(|_| fs::write(path, &body));
+                }
+                body
+            }
+        };
+        // Celestrak answers an empty match with this text in either format
+        let parsed = match format {
+            _ if body.trim() == "No GP data found" => Ok(Vec::new()),
+            Format::Omm => omm::parse_json(&body),
+            Format::Tle => tle::parse_text(&body, true),
+        };
+        elements.extend(parsed.map_err(OrbitError::TleParse)?);
+    }
+    Ok(elements)
+}
+
+#[cfg(feature = "python")]
+#[allow(clippy::too_many_arguments)]
+fn python_request(
+    group: Option<String>,
+    norad_ids: Option<Vec<i32>>,
+    name: Option<String>,
+    source: &str,
+    identity: Option<String>,
+    password: Option<String>,
+    cache_dir: Option<String>,
+    max_age_s: f64,
+) -> PyResult<(Source, Query, FetchOptions)> {
+    let source = match source.to_ascii_lowercase().as_str() {
+        "celestrak" => Source::Celestrak,
+        "spacetrack" | "space-track" => match (identity, password) {
+            (Some(identity), Some(password)) => Source::SpaceTrack { identity, password },
+            _ => return Err(pyo3::exceptions::PyValueError::new_err("Space-Track needs identity and password")),
+        },
+        _ => {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Unknown source '{}' (expected 'celestrak' or 'spacetrack')",
+                source
+            )))
+        }
+    };
+    let query = match (group, norad_ids, name) {
+        (Some(group), None, None) => Query::Group(group),
+        (None, Some(ids), None) => Query::NoradIds(ids),
+        (None, None, Some(name)) => Query::Name(name),
+        (None, None, None) => Query::Group("active".to_string()),
+        _ => return Err(pyo3::exceptions::PyValueError::new_err("Give only one of group, norad_ids or name")),
+    };
+    if !(max_age_s >= 0.0 && max_age_s.is_finite()) {
+        return Err(pyo3::exceptions::PyValueError::new_err("max_age_s must be non-negative"));
+    }
+    let defaults = FetchOptions::default();
+    let options = FetchOptions {
+        cache_dir: match cache_dir.as_deref() {
+            Some("") => None,
+            Some(dir) => Some(PathBuf::from(dir)),
+            None => defaults.cache_dir.clone(),
+        },
+        max_age: Duration::from_secs_f64(max_age_s),
+        ..defaults
+    };
+    Ok((source, query, options))
+}
+
+/// Download mean elements from Celestrak or Space-Track
+///
+/// Select objects with one of `group` (default "active"), `norad_ids` or
+/// `name`. Space-Track needs `identity`/`password`. Responses are cached in
+/// `cache_dir` (default: the user's cache directory; "" disables) for `max_age_s`.
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(name = "fetch_elements", signature = (
+    group = None,
+    norad_ids = None,
+    name = None,
+    source = "celestrak",
+    format = "omm",
+    identity = None,
+    password = None,
+    cache_dir = None,
+    max_age_s = 7200.0
+))]
+#[allow(clippy::too_many_arguments)]
+pub fn fetch_elements_py(
+    py: Python<'_>,
+    group: Option<String>,
+    norad_ids: Option<Vec<i32>>,
+    name: Option<String>,
+    source: &str,
+    format: &str,
+    identity: Option<String>,
+    password: Option<String>,
+    cache_dir: Option<String>,
+    max_age_s: f64,
+) -> PyResult<Vec<MeanElements>> {
+    let format: Format = format.parse().map_err(pyo3::exceptions::PyValueError::new_err)?;
+    let (source, query, options) =
+        python_request(group, norad_ids, name, source, identity, password, cache_dir, max_age_s)?;
+    Ok(py.allow_threads(|| fetch_elements(&source, &query, format, &options))?)
+}
+
+/// Download mean elements and propagate them into a `Catalog` at `epoch` (default: now)
+///
+/// Arguments are as for `fetch_elements`. Objects SGP4 cannot propagate to
+/// the epoch (typically decayed ones) are left out.
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(signature = (
+    group = None,
+    norad_ids = None,
+    name = None,
+    source = "celestrak",
+    format = "omm",
+    identity = None,
+    password = None,
+    cache_dir = None,
+    max_age_s = 7200.0,
+    epoch = None
+))]
+#[allow(clippy::too_many_arguments)]
+pub fn fetch_catalog(
+    py: Python<'_>,
+    group: Option<String>,
+    norad_ids: Option<Vec<i32>>,
+    name: Option<String>,
+    source: &str,
+    format: &str,
+    identity: Option<String>,
+    password: Option<String>,
+    cache_dir: Option<String>,
+    max_age_s: f64,
+    epoch: Option<Epoch>,
+) -> PyResult<Catalog> {
+    let elements = fetch_elements_py(py, group, norad_ids, name, source, format, identity, password, cache_dir, max_age_s)?;
+    let jd = epoch.map_or_else(|| epoch::now_mjd_utc() + MJD_OFFSET, |e| e.jd_utc());
+    let mut seen = std::collections::HashSet::new();
+    let satellites = elements
+        .into_iter()
+        .filter(|e| seen.insert(e.norad_id))
+        .filter_map(|e| {
+            let propagator = Sgp4Propagator::from_mean_elements(e);
+            propagator.propagate_state(propagator.minutes_since_epoch(jd)).ok()
+        })
+        .collect();
+    Catalog::from_satellites(satellites).map_err(pyo3::exceptions::PyValueError::new_err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_urls() {
+        let urls = request_urls(&Source::Celestrak, &Query::NoradIds(vec![25544, 20580]), Format::Omm).unwrap();
+        assert_eq!(urls[0], "https://celestrak.org/NORAD/elements/gp.php?CATNR=25544&FORMAT=json");
+        assert_eq!(urls.len(), 2);
+        let named = request_urls(&Source::Celestrak, &Query::Name("ISS (ZARYA)".to_string()), Format::Tle).unwrap();
+        assert_eq!(named[0], "https://celestrak.org/NORAD/elements/gp.php?NAME=ISS%20%28ZARYA%29&FORMAT=tle");
+
+        let space_track = Source::SpaceTrack { identity: "me".to_string(), password: "pw".to_string() };
+        let active = request_urls(&space_track, &Query::Group("active".to_string()), Format::Tle).unwrap();
+        assert!(active[0].ends_with("/class/gp/decay_date/null-val/epoch/%3Enow-30/orderby/norad_cat_id/format/3le"));
+        assert!(request_urls(&space_track, &Query::Group("starlink".to_string()), Format::Omm).is_err());
+    }
+
+    #[test]
+    fn test_cache_age() {
+        let text = "[]";
+        let dir = std::env::temp_dir().join(format!("orbit_core_fetch_test_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = cache_path(&dir, &Source::Celestrak, "https://celestrak.org/NORAD/elements/gp.php?GROUP=active&FORMAT=json");
+        fs::write(&path, text).unwrap();
+        assert_eq!(read_fresh(&path, Duration::from_secs(60)).as_deref(), Some(text));
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(read_fresh(&path, Duration::from_millis(1)).is_none());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_private_cache_per_account() {
+        let url = "https://www.space-track.org/basicspacedata/query/class/gp/norad_cat_id/25544/format/json";
+        let account = |identity: &str| Source::SpaceTrack { identity: identity.to_string(), password: "pw".to_string() };
+        let dir = Path::new("cache");
+        assert_ne!(cache_path(dir, &account("alice"), url), cache_path(dir, &account("bob"), url));
+        assert_eq!(cache_path(dir, &account("alice"), url), cache_path(dir, &account("alice"), url));
+
+        let dir = std::env::temp_dir().join(format!("orbit_core_private_test_{}", std::process::id()));
+        create_cache_dir(&dir.join("nested")).unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(fs::metadata(dir.join("nested")).unwrap().permissions().mode() & 0o777, 0o700);
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
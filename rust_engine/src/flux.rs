@@ -0,0 +1,518 @@
+//! Long-term collision rates from the kinetic spatial-density model
+//!
+//! Rather than screening individual encounters, the catalog is smeared over
+//! its orbits into a time-averaged number density on an altitude/latitude
+//! grid, as in the flux models of Kessler (1981) and ORDEM. Each object
+//! spends a share of its period in every cell it crosses; J2 drifts the node
+//! and perigee over months, so both are averaged away. An asset crossing a
+//! cell of density n at relative speed v_rel meets n v_rel sigma objects per
+//! second, sigma = pi (R + r)^2 being the combined cross-section.
+//!
+//! Velocities in each cell are kept in heading buckets so the relative speed
+//! reflects crossing geometry: co-moving objects contribute little, head-on
+//! ones a lot. Altitudes are above a sphere of equatorial radius and
+//! latitudes geocentric. Orbits are osculating two-body ellipses; unbound
+//! states are skipped.
+
+use nalgebra::Vector3;
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+#[cfg(feature = "python")]
+use rayon::prelude::*;
+#[cfg(feature = "python")]
+use std::collections::HashMap;
+use std::f64::consts::PI;
+
+use crate::constants::EARTH_RADIUS_KM;
+use crate::elements::{self, KeplerianElements};
+#[cfg(feature = "python")]
+use crate::catalog::Catalog;
+#[cfg(feature = "python")]
+use crate::columnar::Column;
+#[cfg(feature = "python")]
+use crate::{parallel, Satellite};
+
+/// Heading buckets (local azimuth of the velocity) per cell
+const HEADING_BUCKETS: usize = 36;
+
+/// Eccentric anomaly samples per orbit
+const ORBIT_SAMPLES: usize = 180;
+
+/// Argument of perigee samples for eccentric orbits
+const PERIGEE_SAMPLES: usize = 8;
+
+/// Eccentricity below which the perigee is not averaged
+const CIRCULAR_ECCENTRICITY: f64 = 1.0e-3;
+
+#[cfg(feature = "python")]
+const SECONDS_PER_YEAR: f64 = 365.25 * 86400.0;
+
+/// Sums over the orbit samples falling in one cell and heading bucket
+#[derive(Clone, Copy, Debug, Default)]
+struct Bucket {
+    weight: f64,              // expected number of objects
+    radius: f64,              // weighted hard-body radii (km)
+    radius_sq: f64,           // weighted squared radii (km^2)
+    velocity: Vector3<f64>,   // weighted local (east, north, up) velocity (km/s)
+}
+
+impl Bucket {
+    fn add(&mut self, other: &Bucket, sign: f64) {
+        self.weight += sign * other.weight;
+        self.radius += sign * other.radius;
+        self.radius_sq += sign * other.radius_sq;
+        self.velocity += sign * other.velocity;
+    }
+}
+
+/// Time-averaged number density of a catalog on an altitude/latitude grid
+#[cfg_attr(feature = "python", pyclass)]
+#[derive(Clone)]
+pub struct SpatialDensity {
+    min_altitude_km: f64,
+    max_altitude_km: f64,
+    altitude_step_km: f64,
+    latitude_step_deg: f64,
+    altitude_bins: usize,
+    latitude_bins: usize,
+    buckets: Vec<Bucket>, // [altitude][latitude][heading]
+    objects: usize,
+    #[cfg(feature = "python")]
+    members: HashMap<i32, Satellite>, // catalog records, to leave an asset out of its own flux
+}
+
+/// One orbit sample: position and velocity (km, km/s) and its share of the period
+type OrbitSample = (Vector3<f64>, Vector3<f64>, f64);
+
+/// States spread uniformly in time over the osculating orbit, averaged over the perigee
+fn orbit_samples(r: &Vector3<f64>, v: &Vector3<f64>) -> Vec<OrbitSample> {
+    let Some(el) = elements::from_state(r, v) else {
+        return Vec::new();
+    };
+    let e = el.eccentricity;
+    if !(e < 1.0 && el.semi_major_axis_km > 0.0) {
+        return Vec::new();
+    }
+    let perigees = if e < CIRCULAR_ECCENTRICITY { 1 } else { PERIGEE_SAMPLES };
+    let share = 1.0 / (ORBIT_SAMPLES * perigees) as f64;
+    let (plus, minus) = ((1.0 + e).sqrt(), (1.0 - e).sqrt());
+
+    let mut samples = Vec::with_capacity(ORBIT_SAMPLES * perigees);
+    for p in 0..perigees {
+        for k in 0..ORBIT_SAMPLES {
+            // uniform in eccentric anomaly, weighted by dM/dE = 1 - e cos E
+            let big_e = 2.0 * PI * (k as f64 + 0.5) / ORBIT_SAMPLES as f64;
+            let nu = 2.0 * (plus * (0.5 * big_e).sin()).atan2(minus * (0.5 * big_e).cos());
+            let sample = KeplerianElements {
+                arg_perigee_deg: el.arg_perigee_deg + 360.0 * p as f64 / perigees as f64,
+                true_anomaly_deg: nu.to_degrees(),
+                ..el.clone()
+            };
+            if let Some((rs, vs)) = elements::to_state(&sample) {
+                samples.push((rs, vs, share * (1.0 - e * big_e.cos())));
+            }
+        }
+    }
+    samples
+}
+
+/// Velocity in the local (east, north, up) frame of a position
+fn local_velocity(r: &Vector3<f64>, v: &Vector3<f64>) -> Vector3<f64> {
+    let up = r.normalize();
+    let east = Vector3::z().cross(&up).try_normalize(1.0e-12).unwrap_or_else(Vector3::y);
+    let north = up.cross(&east);
+    Vector3::new(v.dot(&east), v.dot(&north), v.dot(&up))
+}
+
+fn heading_bucket(local: &Vector3<f64>) -> usize {
+    let heading = local.x.atan2(local.y).rem_euclid(2.0 * PI);
+    ((heading / (2.0 * PI) * HEADING_BUCKETS as f64) as usize).min(HEADING_BUCKETS - 1)
+}
+
+impl SpatialDensity {
+    /// Empty grid between two altitudes (km), with the given altitude (km) and latitude (deg) steps
+    pub fn new(
+        min_altitude_km: f64,
+        max_altitude_km: f64,
+        altitude_step_km: f64,
+        latitude_step_deg: f64,
+    ) -> Result<Self, String> {
+        if !(min_altitude_km >= 0.0 && max_altitude_km > min_altitude_km && max_altitude_km.is_finite()) {
+            return Err("Altitudes must satisfy 0 <= min_altitude_km < max_altitude_km".to_string());
+        }
+        if !(altitude_step_km > 0.0 && latitude_step_deg > 0.0 && latitude_step_deg <= 180.0) {
+            return Err("Grid steps must be positive (latitude step at most 180 deg)".to_string());
+        }
+        let altitude_bins = ((max_altitude_km - min_altitude_km) / altitude_step_km).ceil() as usize;
+        let latitude_bins = (180.0 / latitude_step_deg).ceil() as usize;
+        Ok(SpatialDensity {
+            min_altitude_km,
+            max_altitude_km,
+            altitude_step_km,
+            latitude_step_deg,
+            altitude_bins,
+            latitude_bins,
+            buckets: vec![Bucket::default(); altitude_bins * latitude_bins * HEADING_BUCKETS],
+            objects: 0,
+            #[cfg(feature = "python")]
+            members: HashMap::new(),
+        })
+    }
+
+    /// Number of objects currently smeared over the grid
+    pub fn objects(&self) -> usize {
+        self.objects
+    }
+
+    /// Grid cell (altitude, latitude) of a geocentric altitude (km) and latitude (deg)
+    fn cell(&self, altitude_km: f64, latitude_deg: f64) -> Option<(usize, usize)> {
+        if !(altitude_km >= self.min_altitude_km && altitude_km < self.max_altitude_km && latitude_deg.abs() <= 90.0) {
+            return None;
+        }
+        let i = ((altitude_km - self.min_altitude_km) / self.altitude_step_km) as usize;
+        let j = ((latitude_deg + 90.0) / self.latitude_step_deg) as usize;
+        Some((i.min(self.altitude_bins - 1), j.min(self.latitude_bins - 1)))
+    }
+
+    fn cell_of(&self, r: &Vector3<f64>) -> Option<(usize, usize)> {
+        let radius = r.norm();
+        self.cell(radius - EARTH_RADIUS_KM, (r.z / radius).asin().to_degrees())
+    }
+
+    /// Altitude (km) and latitude (deg) bounds of a cell
+    fn bounds(&self, i: usize, j: usize) -> ((f64, f64), (f64, f64)) {
+        let altitude = self.min_altitude_km + i as f64 * self.altitude_step_km;
+        let latitude = -90.0 + j as f64 * self.latitude_step_deg;
+        (
+            (altitude, (altitude + self.altitude_step_km).min(self.max_altitude_km)),
+            (latitude, (latitude + self.latitude_step_deg).min(90.0)),
+        )
+    }
+
+    /// Volume of a cell (km^3): a spherical shell sliced between two latitudes
+    fn volume(&self, i: usize, j: usize) -> f64 {
+        let ((h0, h1), (lat0, lat1)) = self.bounds(i, j);
+        let (r0, r1) = (EARTH_RADIUS_KM + h0, EARTH_RADIUS_KM + h1);
+        2.0 * PI / 3.0 * (r1.powi(3) - r0.powi(3)) * (lat1.to_radians().sin() - lat0.to_radians().sin())
+    }
+
+    fn buckets(&self, i: usize, j: usize) -> &[Bucket] {
+        let start = (i * self.latitude_bins + j) * HEADING_BUCKETS;
+        &self.buckets[start..start + HEADING_BUCKETS]
+    }
+
+    fn accumulate(&mut self, r: &Vector3<f64>, v: &Vector3<f64>, radius_km: f64, sign: f64) -> bool {
+        let samples = orbit_samples(r, v);
+        if samples.is_empty() {
+            return false;
+        }
+        for (rs, vs, share) in samples {
+            let Some((i, j)) = self.cell_of(&rs) else { continue };
+            let local = local_velocity(&rs, &vs);
+            let sample = Bucket {
+                weight: share,
+                radius: share * radius_km,
+                radius_sq: share * radius_km * radius_km,
+                velocity: share * local,
+            };
+            let index = (i * self.latitude_bins + j) * HEADING_BUCKETS + heading_bucket(&local);
+            self.buckets[index].add(&sample, sign);
+        }
+        true
+    }
+
+    /// Smear one object (state in km, km/s; hard-body radius in km) over its orbit
+    ///
+    /// Returns false, leaving the grid unchanged, for unbound or degenerate states.
+    pub fn add(&mut self, r: &Vector3<f64>, v: &Vector3<f64>, radius_km: f64) -> bool {
+        let added = self.accumulate(r, v, radius_km, 1.0);
+        self.objects += usize::from(added);
+        added
+    }
+
+    /// Take out an object previously added with the same state and radius
+    pub fn remove(&mut self, r: &Vector3<f64>, v: &Vector3<f64>, radius_km: f64) -> bool {
+        let removed = self.accumulate(r, v, radius_km, -1.0);
+        self.objects -= usize::from(removed);
+        removed
+    }
+
+    /// Add the contents of a grid with the same layout
+    #[cfg(feature = "python")]
+    fn merge(&mut self, other: &SpatialDensity) {
+        for (bucket, theirs) in self.buckets.iter_mut().zip(&other.buckets) {
+            bucket.add(theirs, 1.0);
+        }
+        self.objects += other.objects;
+    }
+
+    /// Mean number density (objects per km^3) of the cell holding an altitude (km) and latitude (deg)
+    ///
+    /// Zero outside the grid.
+    pub fn density(&self, altitude_km: f64, latitude_deg: f64) -> f64 {
+        self.cell(altitude_km, latitude_deg)
+            .map_or(0.0, |(i, j)| self.buckets(i, j).iter().map(|b| b.weight).sum::<f64>() / self.volume(i, j))
+    }
+
+    /// Orbit-averaged flux and collision rate seen by an object
+    ///
+    /// Returns the flux of catalog objects through the asset's path
+    /// (objects per km^2 per second) and the expected collisions per second
+    /// for an asset of hard-body radius `radius_km`. Parts of the orbit
+    /// outside the grid see no objects.
+    pub fn flux(&self, r: &Vector3<f64>, v: &Vector3<f64>, radius_km: f64) -> (f64, f64) {
+        let (mut flux, mut rate) = (0.0, 0.0);
+        for (rs, vs, share) in orbit_samples(r, v) {
+            let Some((i, j)) = self.cell_of(&rs) else { continue };
+            let local = local_velocity(&rs, &vs);
+            let volume = self.volume(i, j);
+            for bucket in self.buckets(i, j) {
+                if bucket.weight <= 1.0e-12 {
+                    continue; // empty, or emptied up to round-off by a removal
+                }
+                let relative_speed = (local - bucket.velocity / bucket.weight).norm();
+                let cross_section = PI
+                    * (radius_km * radius_km * bucket.weight + 2.0 * radius_km * bucket.radius + bucket.radius_sq);
+                flux += share * relative_speed * bucket.weight / volume;
+                rate += share * relative_speed * cross_section / volume;
+            }
+        }
+        (flux, rate)
+    }
+}
+
+/// Long-term collision estimate for one asset
+#[cfg(feature = "python")]
+#[pyclass(get_all)]
+#[derive(Clone, Debug)]
+pub struct FluxEstimate {
+    pub norad_id: i32,
+    pub flux: f64,           // catalog objects crossing the orbit (per m^2 per year)
+    pub collision_rate: f64, // expected collisions per year
+    pub probability: f64,    // probability of at least one collision over the horizon
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl FluxEstimate {
+    fn __repr__(&self) -> String {
+        format!(
+            "FluxEstimate(norad_id={}, flux={:.3e}/m^2/yr, collision_rate={:.3e}/yr, probability={:.3e})",
+            self.norad_id, self.flux, self.collision_rate, self.probability
+        )
+    }
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl SpatialDensity {
+    /// Smear a catalog (a `Catalog` or a list of satellites) over an altitude/latitude grid
+    ///
+    /// Altitudes in km, latitude step in degrees. Each object is weighted by
+    /// its hard-body radius when collision rates are computed.
+    #[new]
+    #[pyo3(signature = (satellites, min_altitude_km = 200.0, max_altitude_km = 2000.0, altitude_step_km = 50.0, latitude_step_deg = 10.0))]
+    fn py_new(
+        py: Python<'_>,
+        satellites: &PyAny,
+        min_altitude_km: f64,
+        max_altitude_km: f64,
+        altitude_step_km: f64,
+        latitude_step_deg: f64,
+    ) -> PyResult<Self> {
+        let satellites: Vec<Satellite> = match satellites.extract::<PyRef<Catalog>>() {
+            Ok(catalog) => catalog.satellites().to_vec(),
+            Err(_) => satellites.extract()?,
+        };
+        let empty = SpatialDensity::new(min_altitude_km, max_altitude_km, altitude_step_km, latitude_step_deg)
+            .map_err(pyo3::exceptions::PyValueError::new_err)?;
+        let mut density = py.allow_threads(|| {
+            parallel::install(|| {
+                satellites
+                    .par_iter()
+                    .fold(
+                        || empty.clone(),
+                        |mut density, sat| {
+                            density.add(&sat.r(), &sat.v(), sat.collision_radius());
+                            density
+                        },
+                    )
+                    .reduce(
+                        || empty.clone(),
+                        |mut a, b| {
+                            a.merge(&b);
+                            a
+                        },
+                    )
+            })
+        });
+        density.members = satellites.into_iter().map(|sat| (sat.norad_id, sat)).collect();
+        Ok(density)
+    }
+
+    /// Number of catalog objects on bound orbits
+    #[getter]
+    #[pyo3(name = "objects")]
+    fn py_objects(&self) -> usize {
+        self.objects
+    }
+
+    /// Number density (objects per km^3) at an altitude (km) and geocentric latitude (deg)
+    #[pyo3(name = "density")]
+    fn py_density(&self, altitude_km: f64, latitude_deg: f64) -> f64 {
+        self.density(altitude_km, latitude_deg)
+    }
+
+    /// The density grid as a dict of columns
+    ///
+    /// `altitude_km` and `latitude_deg` are the cell centres; `density` is
+    /// an (altitudes, latitudes) matrix in objects per km^3.
+    fn table(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let altitudes: Vec<f64> = (0..self.altitude_bins)
+            .map(|i| {
+                let ((h0, h1), _) = self.bounds(i, 0);
+                0.5 * (h0 + h1)
+            })
+            .collect();
+        let latitudes: Vec<f64> = (0..self.latitude_bins)
+            .map(|j| {
+                let (_, (lat0, lat1)) = self.bounds(0, j);
+                0.5 * (lat0 + lat1)
+            })
+            .collect();
+        let density: Vec<f64> = (0..self.altitude_bins * self.latitude_bins)
+            .map(|cell| {
+                let (i, j) = (cell / self.latitude_bins, cell % self.latitude_bins);
+                self.buckets(i, j).iter().map(|b| b.weight).sum::<f64>() / self.volume(i, j)
+            })
+            .collect();
+
+        let table = pyo3::types::PyDict::new(py);
+        table.set_item("altitude_km", Py::new(py, Column::float64(altitudes))?)?;
+        table.set_item("latitude_deg", Py::new(py, Column::float64(latitudes))?)?;
+        table.set_item(
+            "density",
+            Py::new(py, Column::matrix(density, self.altitude_bins, self.latitude_bins))?,
+        )?;
+        Ok(table.into())
+    }
+
+    /// Annual flux and collision probability for each asset
+    ///
+    /// An asset that is itself in the catalog (same NORAD id) is left out
+    /// of its own flux. The probability of at least one collision over
+    /// `years` follows from Poisson statistics, 1 - exp(-rate * years).
+    #[pyo3(signature = (assets, years = 1.0))]
+    fn estimate(&self, py: Python<'_>, assets: Vec<Satellite>, years: f64) -> PyResult<Vec<FluxEstimate>> {
+        if !(years > 0.0 && years.is_finite()) {
+            return Err(pyo3::exceptions::PyValueError::new_err("years must be a positive number"));
+        }
+        Ok(py.allow_threads(|| {
+            parallel::install(|| {
+                assets
+                    .par_iter()
+                    .map(|asset| {
+                        let own = self.members.get(&asset.norad_id);
+                        let without;
+                        let density = match own {
+                            Some(member) => {
+                                let mut copy = SpatialDensity { buckets: self.buckets.clone(), members: HashMap::new(), ..*self };
+                                copy.remove(&member.r(), &member.v(), member.collision_radius());
+                                without = copy;
+                                &without
+                            }
+                            None => self,
+                        };
+                        let (flux, rate) = density.flux(&asset.r(), &asset.v(), asset.collision_radius());
+                        let collision_rate = rate * SECONDS_PER_YEAR;
+                        FluxEstimate {
+                            norad_id: asset.norad_id,
+                            flux: flux * SECONDS_PER_YEAR * 1.0e-6, // per km^2 -> per m^2
+                            collision_rate,
+                            probability: -(-collision_rate * years).exp_m1(),
+                        }
+                    })
+                    .collect()
+            })
+        }))
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "SpatialDensity(objects={}, altitude={}-{} km, {} x {} cells)",
+            self.objects, self.min_altitude_km, self.max_altitude_km, self.altitude_bins, self.latitude_bins
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn circular(altitude_km: f64, inclination_deg: f64, raan_deg: f64) -> (Vector3<f64>, Vector3<f64>) {
+        elements::to_state(&KeplerianElements {
+            semi_major_axis_km: EARTH_RADIUS_KM + altitude_km,
+            eccentricity: 0.0,
+            inclination_deg,
+            raan_deg,
+            arg_perigee_deg: 0.0,
+            true_anomaly_deg: 0.0,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_density_integrates_to_object_count() {
+        let mut grid = SpatialDensity::new(200.0, 2000.0, 50.0, 10.0).unwrap();
+        let (r, v) = circular(550.0, 53.0, 0.0);
+        assert!(grid.add(&r, &v, 0.005));
+        let (r2, v2) = elements::to_state(&KeplerianElements {
+            semi_major_axis_km: EARTH_RADIUS_KM + 1000.0,
+            eccentricity: 0.05,
+            inclination_deg: 98.0,
+            raan_deg: 40.0,
+            arg_perigee_deg: 10.0,
+            true_anomaly_deg: 200.0,
+        })
+        .unwrap();
+        assert!(grid.add(&r2, &v2, 0.005));
+        // unbound states are skipped
+        assert!(!grid.add(&r, &(v * 2.0), 0.005));
+        assert_eq!(grid.objects(), 2);
+
+        let total: f64 = (0..grid.altitude_bins)
+            .flat_map(|i| (0..grid.latitude_bins).map(move |j| (i, j)))
+            .map(|(i, j)| {
+                let ((h0, h1), (lat0, lat1)) = grid.bounds(i, j);
+                grid.density(0.5 * (h0 + h1), 0.5 * (lat0 + lat1)) * grid.volume(i, j)
+            })
+            .sum();
+        assert!((total - 2.0).abs() < 1e-9, "{}", total);
+        // nothing reaches 420 km, and the 53 deg orbit stays below 60 deg latitude
+        assert_eq!(grid.density(560.0, 65.0), 0.0);
+        assert_eq!(grid.density(420.0, 10.0), 0.0);
+        assert!(grid.density(560.0, 50.0) > grid.density(560.0, 5.0));
+
+        assert!(grid.remove(&r, &v, 0.005) && grid.remove(&r2, &v2, 0.005));
+        assert_eq!(grid.objects(), 0);
+        assert!(grid.density(560.0, 50.0).abs() < 1e-20);
+    }
+
+    #[test]
+    fn test_flux_follows_crossing_geometry() {
+        // the same orbit, one population flying along it, one flying against it; with the
+        // node averaged the co-rotating shell still crosses ascending against descending
+        let (r, v) = circular(800.0, 60.0, 0.0);
+        let mut along = SpatialDensity::new(200.0, 2000.0, 50.0, 10.0).unwrap();
+        let mut against = along.clone();
+        along.add(&r, &v, 0.001);
+        against.add(&r, &-v, 0.001);
+
+        let (flux_along, _) = along.flux(&r, &v, 0.001);
+        let (flux_against, rate_against) = against.flux(&r, &v, 0.001);
+        assert!(flux_against > 0.0 && flux_along < 0.5 * flux_against);
+        // the cross-section grows with the combined radius
+        let (_, rate_point) = against.flux(&r, &v, 0.0);
+        assert!((rate_against / rate_point - 4.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,361 @@
+//! Reference frame transformations
+//!
+//! * TEME (SGP4 output) <-> ECEF via GMST (IAU-82), as in Vallado et al. 2006
+//! * GCRF <-> ECEF via the IAU-76/FK5 reduction: precession, IAU-80
+//!   nutation (leading terms), GAST and optional polar motion
+//! * ECEF <-> WGS-84 geodetic latitude/longitude/altitude
+//! * RTN (radial/in-track/cross-track) local frame of an orbiting object
+//!
+//! All epochs are Julian dates in UT1; UTC is adequate for km-level work.
+
+use nalgebra::{Rotation3, Vector3};
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use std::f64::consts::PI;
+
+use crate::constants::{EARTH_FLATTENING, EARTH_RADIUS_KM, EARTH_ROTATION_RATE};
+use crate::sgp4::gstime;
+
+const ARCSEC2RAD: f64 = PI / (180.0 * 3600.0);
+const J2000: f64 = 2451545.0;
+
+type State = (Vector3<f64>, Vector3<f64>);
+
+/// Earth rotation angle (IAU-2000) in radians
+pub fn earth_rotation_angle(jd_ut1: f64) -> f64 {
+    let du = jd_ut1 - J2000;
+    let era = 2.0 * PI * (0.7790572732640 + 0.00273781191135448 * du + du.fract());
+    era.rem_euclid(2.0 * PI)
+}
+
+/// Polar-motion matrix W taking ITRF to PEF (xp, yp in arcseconds)
+fn polar_motion(xp: f64, yp: f64) -> Rotation3<f64> {
+    Rotation3::from_axis_angle(&Vector3::x_axis(), -yp * ARCSEC2RAD)
+        * Rotation3::from_axis_angle(&Vector3::y_axis(), -xp * ARCSEC2RAD)
+}
+
+fn earth_rotation_vector() -> Vector3<f64> {
+    Vector3::new(0.0, 0.0, EARTH_ROTATION_RATE)
+}
+
+/// Rotate a pseudo-Earth-fixed state into a frame rotated by `angle` about z
+fn pef_to_rotating(r_pef: &Vector3<f64>, v_pef: &Vector3<f64>, angle: f64) -> State {
+    let rot = Rotation3::from_axis_angle(&Vector3::z_axis(), angle);
+    let v_inertial = v_pef + earth_rotation_vector().cross(r_pef);
+    (rot * r_pef, rot * v_inertial)
+}
+
+fn rotating_to_pef(r: &Vector3<f64>, v: &Vector3<f64>, angle: f64) -> State {
+    let rot = Rotation3::from_axis_angle(&Vector3::z_axis(), -angle);
+    let r_pef = rot * r;
+    let v_pef = rot * v - earth_rotation_vector().cross(&r_pef);
+    (r_pef, v_pef)
+}
+
+/// State in a frame rotated by `sidereal` from PEF, to ECEF (ITRF)
+fn sidereal_to_ecef(r: &Vector3<f64>, v: &Vector3<f64>, sidereal: f64, xp: f64, yp: f64) -> State {
+    let (r_pef, v_pef) = rotating_to_pef(r, v, sidereal);
+    let w = polar_motion(xp, yp).inverse();
+    (w * r_pef, w * v_pef)
+}
+
+/// TEME state to ECEF (ITRF) state
+pub fn teme_to_ecef_state(r: &Vector3<f64>, v: &Vector3<f64>, jd_ut1: f64, xp: f64, yp: f64) -> State {
+    sidereal_to_ecef(r, v, gstime(jd_ut1), xp, yp)
+}
+
+/// ECEF (ITRF) state to TEME state
+pub fn ecef_to_teme_state(r: &Vector3<f64>, v: &Vector3<f64>, jd_ut1: f64, xp: f64, yp: f64) -> State {
+    let w = polar_motion(xp, yp);
+    pef_to_rotating(&(w * r), &(w * v), gstime(jd_ut1))
+}
+
+/// Nutation series row: (l, l', F, D, Omega, dpsi, dpsi_t, deps, deps_t), amplitudes in 0.0001"
+type NutationTerm = (f64, f64, f64, f64, f64, f64, f64, f64, f64);
+
+/// IAU-80 nutation, leading terms (the omitted ones sum to a few 0.01")
+#[rustfmt::skip]
+const NUTATION_TERMS: [NutationTerm; 18] = [
+    ( 0.0,  0.0, 0.0,  0.0, 1.0, -171996.0, -174.2, 92025.0,  8.9),
+    ( 0.0,  0.0, 2.0, -2.0, 2.0,  -13187.0,   -1.6,  5736.0, -3.1),
+    ( 0.0,  0.0, 2.0,  0.0, 2.0,   -2274.0,   -0.2,   977.0, -0.5),
+    ( 0.0,  0.0, 0.0,  0.0, 2.0,    2062.0,    0.2,  -895.0,  0.5),
+    ( 0.0,  1.0, 0.0,  0.0, 0.0,    1426.0,   -3.4,    54.0, -0.1),
+    ( 1.0,  0.0, 0.0,  0.0, 0.0,     712.0,    0.1,    -7.0,  0.0),
+    ( 0.0,  1.0, 2.0, -2.0, 2.0,    -517.0,    1.2,   224.0, -0.6),
+    ( 0.0,  0.0, 2.0,  0.0, 1.0,    -386.0,   -0.4,   200.0,  0.0),
+    ( 1.0,  0.0, 2.0,  0.0, 2.0,    -301.0,    0.0,   129.0, -0.1),
+    ( 0.0, -1.0, 2.0, -2.0, 2.0,     217.0,   -0.5,   -95.0,  0.3),
+    ( 1.0,  0.0, 0.0, -2.0, 0.0,    -158.0,    0.0,    -1.0,  0.0),
+    ( 0.0,  0.0, 2.0, -2.0, 1.0,     129.0,    0.1,   -70.0,  0.0),
+    (-1.0,  0.0, 2.0,  0.0, 2.0,     123.0,    0.0,   -53.0,  0.0),
+    ( 1.0,  0.0, 0.0,  0.0, 1.0,      63.0,    0.1,   -33.0,  0.0),
+    ( 0.0,  0.0, 0.0,  2.0, 0.0,      63.0,    0.0,    -2.0,  0.0),
+    (-1.0,  0.0, 2.0,  2.0, 2.0,     -59.0,    0.0,    26.0,  0.0),
+    (-1.0,  0.0, 0.0,  0.0, 1.0,     -58.0,   -0.1,    32.0,  0.0),
+    ( 1.0,  0.0, 2.0,  0.0, 1.0,     -51.0,    0.0,    27.0,  0.0),
+];
+
+/// Precession-nutation-sidereal rotation from PEF to GCRF (FK5 reduction)
+fn pef_to_gcrf_rotation(jd_ut1: f64) -> (Rotation3<f64>, f64) {
+    let t = (jd_ut1 - J2000) / 36525.0;
+    let (t2, t3) = (t * t, t * t * t);
+
+    // IAU-76 precession angles
+    let zeta = (2306.2181 * t + 0.30188 * t2 + 0.017998 * t3) * ARCSEC2RAD;
+    let theta = (2004.3109 * t - 0.42665 * t2 - 0.041833 * t3) * ARCSEC2RAD;
+    let z = (2306.2181 * t + 1.09468 * t2 + 0.018203 * t3) * ARCSEC2RAD;
+    let precession = Rotation3::from_axis_angle(&Vector3::z_axis(), -zeta)
+        * Rotation3::from_axis_angle(&Vector3::y_axis(), theta)
+        * Rotation3::from_axis_angle(&Vector3::z_axis(), -z);
+
+    // IAU-80 fundamental arguments (degrees)
+    let l = 134.96298139 + (1325.0 * 360.0 + 198.8673981) * t + 0.0086972 * t2 + 1.78e-5 * t3;
+    let lp = 357.52772333 + (99.0 * 360.0 + 359.0503400) * t - 0.0001603 * t2 - 3.3e-6 * t3;
+    let f = 93.27191028 + (1342.0 * 360.0 + 82.0175381) * t - 0.0036825 * t2 + 3.1e-6 * t3;
+    let d = 297.85036306 + (1236.0 * 360.0 + 307.1114800) * t - 0.0019142 * t2 + 5.3e-6 * t3;
+    let om = 125.04452222 - (5.0 * 360.0 + 134.1362608) * t + 0.0020708 * t2 + 2.2e-6 * t3;
+    let (l, lp, f, d, om) = (l.to_radians(), lp.to_radians(), f.to_radians(), d.to_radians(), om.to_radians());
+
+    let mut dpsi = 0.0;
+    let mut deps = 0.0;
+    for &(cl, clp, cf, cd, com, ps, pst, ep, ept) in NUTATION_TERMS.iter() {
+        let arg = cl * l + clp * lp + cf * f + cd * d + com * om;
+        dpsi += (ps + pst * t) * arg.sin();
+        deps += (ep + ept * t) * arg.cos();
+    }
+    let dpsi = dpsi * 1.0e-4 * ARCSEC2RAD;
+    let deps = deps * 1.0e-4 * ARCSEC2RAD;
+
+    let mean_obliquity = (84381.448 - 46.8150 * t - 0.00059 * t2 + 0.001813 * t3) * ARCSEC2RAD;
+    let true_obliquity = mean_obliquity + deps;
+    let nutation = Rotation3::from_axis_angle(&Vector3::x_axis(), mean_obliquity)
+        * Rotation3::from_axis_angle(&Vector3::z_axis(), -dpsi)
+        * Rotation3::from_axis_angle(&Vector3::x_axis(), -true_obliquity);
+
+    // Greenwich apparent sidereal time (equation of the equinoxes, 1997+ form)
+    let gast = gstime(jd_ut1)
+        + dpsi * mean_obliquity.cos()
+        + (0.00264 * om.sin() + 0.000063 * (2.0 * om).sin()) * ARCSEC2RAD;
+
+    (precession * nutation, gast)
+}
+
+/// GCRF state to ECEF (ITRF) state
+pub fn gcrf_to_ecef_state(r: &Vector3<f64>, v: &Vector3<f64>, jd_ut1: f64, xp: f64, yp: f64) -> State {
+    let (pn, gast) = pef_to_gcrf_rotation(jd_ut1);
+    let pn_inv = pn.inverse();
+    sidereal_to_ecef(&(pn_inv * r), &(pn_inv * v), gast, xp, yp)
+}
+
+/// ECEF (ITRF) state to GCRF state
+pub fn ecef_to_gcrf_state(r: &Vector3<f64>, v: &Vector3<f64>, jd_ut1: f64, xp: f64, yp: f64) -> State {
+    let (pn, gast) = pef_to_gcrf_rotation(jd_ut1);
+    let w = polar_motion(xp, yp);
+    let (r_tod, v_tod) = pef_to_rotating(&(w * r), &(w * v), gast);
+    (pn * r_tod, pn * v_tod)
+}
+
+/// ECEF position (km) to WGS-84 geodetic (latitude rad, longitude rad, altitude km)
+pub fn ecef_to_geodetic_rad(r: &Vector3<f64>) -> (f64, f64, f64) {
+    let a = EARTH_RADIUS_KM;
+    let e2 = EARTH_FLATTENING * (2.0 - EARTH_FLATTENING);
+    let b = a * (1.0 - EARTH_FLATTENING);
+
+    let lon = r.y.atan2(r.x);
+    let p = (r.x * r.x + r.y * r.y).sqrt();
+    if p < 1.0e-9 {
+        let lat = if r.z >= 0.0 { PI / 2.0 } else { -PI / 2.0 };
+        return (lat, lon, r.z.abs() - b);
+    }
+
+    let mut lat = r.z.atan2(p * (1.0 - e2));
+    let mut alt = 0.0;
+    for _ in 0..10 {
+        let sin_lat = lat.sin();
+        let n = a / (1.0 - e2 * sin_lat * sin_lat).sqrt();
+        alt = p / lat.cos() - n;
+        let next = r.z.atan2(p * (1.0 - e2 * n / (n + alt)));
+        let converged = (next - lat).abs() < 1.0e-14;
+        lat = next;
+        if converged {
+            break;
+        }
+    }
+    (lat, lon, alt)
+}
+
+/// WGS-84 geodetic (latitude rad, longitude rad, altitude km) to ECEF position (km)
+pub fn geodetic_to_ecef_rad(lat: f64, lon: f64, alt: f64) -> Vector3<f64> {
+    let a = EARTH_RADIUS_KM;
+    let e2 = EARTH_FLATTENING * (2.0 - EARTH_FLATTENING);
+    let sin_lat = lat.sin();
+    let n = a / (1.0 - e2 * sin_lat * sin_lat).sqrt();
+    Vector3::new(
+        (n + alt) * lat.cos() * lon.cos(),
+        (n + alt) * lat.cos() * lon.sin(),
+        (n * (1.0 - e2) + alt) * sin_lat,
+    )
+}
+
+/// RTN unit vectors (radial, in-track, cross-track) of a state, or None if degenerate
+pub fn rtn_basis(r: &Vector3<f64>, v: &Vector3<f64>) -> Option<[Vector3<f64>; 3]> {
+    let h = r.cross(v);
+    if r.norm() == 0.0 || h.norm() == 0.0 {
+        return None;
+    }
+    let radial = r.normalize();
+    let cross_track = h.normalize();
+    Some([radial, cross_track.cross(&radial), cross_track])
+}
+
+/// Components of `vector` in the RTN frame of the state (r, v)
+pub fn to_rtn(r: &Vector3<f64>, v: &Vector3<f64>, vector: &Vector3<f64>) -> Option<[f64; 3]> {
+    let [radial, in_track, cross_track] = rtn_basis(r, v)?;
+    Some([vector.dot(&radial), vector.dot(&in_track), vector.dot(&cross_track)])
+}
+
+#[cfg(feature = "python")]
+pub(crate) fn vec3(values: &[f64], name: &str) -> PyResult<Vector3<f64>> {
+    if values.len() != 3 {
+        return Err(pyo3::exceptions::PyValueError::new_err(format!(
+            "{} must have 3 components",
+            name
+        )));
+    }
+    Ok(Vector3::new(values[0], values[1], values[2]))
+}
+
+#[cfg(feature = "python")]
+fn to_lists((r, v): State) -> (Vec<f64>, Vec<f64>) {
+    (r.as_slice().to_vec(), v.as_slice().to_vec())
+}
+
+/// TEME position/velocity to ECEF (ITRF); polar motion xp, yp in arcseconds
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(signature = (position, velocity, jd, xp = 0.0, yp = 0.0))]
+pub fn teme_to_ecef(position: Vec<f64>, velocity: Vec<f64>, jd: f64, xp: f64, yp: f64) -> PyResult<(Vec<f64>, Vec<f64>)> {
+    let state = teme_to_ecef_state(&vec3(&position, "position")?, &vec3(&velocity, "velocity")?, jd, xp, yp);
+    Ok(to_lists(state))
+}
+
+/// ECEF (ITRF) position/velocity to TEME
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(signature = (position, velocity, jd, xp = 0.0, yp = 0.0))]
+pub fn ecef_to_teme(position: Vec<f64>, velocity: Vec<f64>, jd: f64, xp: f64, yp: f64) -> PyResult<(Vec<f64>, Vec<f64>)> {
+    let state = ecef_to_teme_state(&vec3(&position, "position")?, &vec3(&velocity, "velocity")?, jd, xp, yp);
+    Ok(to_lists(state))
+}
+
+/// GCRF position/velocity to ECEF (ITRF)
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(signature = (position, velocity, jd, xp = 0.0, yp = 0.0))]
+pub fn gcrf_to_ecef(position: Vec<f64>, velocity: Vec<f64>, jd: f64, xp: f64, yp: f64) -> PyResult<(Vec<f64>, Vec<f64>)> {
+    let state = gcrf_to_ecef_state(&vec3(&position, "position")?, &vec3(&velocity, "velocity")?, jd, xp, yp);
+    Ok(to_lists(state))
+}
+
+/// ECEF (ITRF) position/velocity to GCRF
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(signature = (position, velocity, jd, xp = 0.0, yp = 0.0))]
+pub fn ecef_to_gcrf(position: Vec<f64>, velocity: Vec<f64>, jd: f64, xp: f64, yp: f64) -> PyResult<(Vec<f64>, Vec<f64>)> {
+    let state = ecef_to_gcrf_state(&vec3(&position, "position")?, &vec3(&velocity, "velocity")?, jd, xp, yp);
+    Ok(to_lists(state))
+}
+
+/// ECEF position (km) to geodetic (lat_deg, lon_deg, alt_km) on the WGS-84 ellipsoid
+#[cfg(feature = "python")]
+#[pyfunction]
+pub fn ecef_to_geodetic(position: Vec<f64>) -> PyResult<(f64, f64, f64)> {
+    let (lat, lon, alt) = ecef_to_geodetic_rad(&vec3(&position, "position")?);
+    Ok((lat.to_degrees(), lon.to_degrees(), alt))
+}
+
+/// Geodetic (lat_deg, lon_deg, alt_km) on the WGS-84 ellipsoid to ECEF position (km)
+#[cfg(feature = "python")]
+#[pyfunction]
+pub fn geodetic_to_ecef(lat_deg: f64, lon_deg: f64, alt_km: f64) -> Vec<f64> {
+    geodetic_to_ecef_rad(lat_deg.to_radians(), lon_deg.to_radians(), alt_km)
+        .as_slice()
+        .to_vec()
+}
+
+/// Greenwich mean sidereal time (IAU-82) in radians
+#[cfg(feature = "python")]
+#[pyfunction]
+pub fn gmst(jd: f64) -> f64 {
+    gstime(jd)
+}
+
+/// Earth rotation angle (IAU-2000) in radians
+#[cfg(feature = "python")]
+#[pyfunction]
+pub fn era(jd: f64) -> f64 {
+    earth_rotation_angle(jd)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sgp4::julian_date;
+
+    // Vallado example 3-15: 2004-04-06 07:51:28.386009 UTC, dUT1 = -0.4399619 s
+    fn vallado_jd_ut1() -> f64 {
+        julian_date(2004, 4, 6, 7, 51, 28.386009 - 0.4399619)
+    }
+    const XP: f64 = -0.140682;
+    const YP: f64 = 0.333309;
+    const R_ITRF: [f64; 3] = [-1033.4793830, 7901.2952754, 6380.3565958];
+    const V_ITRF: [f64; 3] = [-3.225636520, -2.872451450, 5.531924446];
+
+    #[test]
+    fn test_teme_to_ecef_vallado() {
+        let r_teme = Vector3::new(5094.18016210, 6127.64465950, 6380.34453270);
+        let v_teme = Vector3::new(-4.746131487, 0.785818041, 5.531931288);
+        let (r, v) = teme_to_ecef_state(&r_teme, &v_teme, vallado_jd_ut1(), XP, YP);
+        assert!((r - Vector3::from(R_ITRF)).norm() < 1e-3);
+        assert!((v - Vector3::from(V_ITRF)).norm() < 1e-6);
+
+        let (r_back, v_back) = ecef_to_teme_state(&r, &v, vallado_jd_ut1(), XP, YP);
+        assert!((r_back - r_teme).norm() < 1e-8);
+        assert!((v_back - v_teme).norm() < 1e-11);
+    }
+
+    #[test]
+    fn test_gcrf_to_ecef_vallado() {
+        let r_gcrf = Vector3::new(5102.508958, 6123.011401, 6378.136928);
+        let v_gcrf = Vector3::new(-4.74322016, 0.79053650, 5.53375528);
+        let (r, v) = gcrf_to_ecef_state(&r_gcrf, &v_gcrf, vallado_jd_ut1(), XP, YP);
+        // truncated nutation and no frame bias: agreement at the metre level
+        assert!((r - Vector3::from(R_ITRF)).norm() < 5e-3);
+        assert!((v - Vector3::from(V_ITRF)).norm() < 1e-5);
+
+        let (r_back, _) = ecef_to_gcrf_state(&r, &v, vallado_jd_ut1(), XP, YP);
+        assert!((r_back - r_gcrf).norm() < 1e-8);
+    }
+
+    #[test]
+    fn test_rtn_components() {
+        let r = Vector3::new(7000.0, 0.0, 0.0);
+        let v = Vector3::new(0.0, 7.5, 0.0);
+        let rtn = to_rtn(&r, &v, &Vector3::new(1.0, 2.0, 3.0)).unwrap();
+        assert_eq!(rtn, [1.0, 2.0, 3.0]);
+        assert!(to_rtn(&r, &Vector3::new(1.0, 0.0, 0.0), &r).is_none());
+    }
+
+    #[test]
+    fn test_geodetic_vallado() {
+        // Vallado example 3-3
+        let (lat, lon, alt) = ecef_to_geodetic_rad(&Vector3::new(6524.834, 6862.875, 6448.296));
+        assert!((lat.to_degrees() - 34.352496).abs() < 1e-5);
+        assert!((lon.to_degrees() - 46.4464).abs() < 1e-4);
+        assert!((alt - 5085.22).abs() < 1e-2);
+
+        let back = geodetic_to_ecef_rad(lat, lon, alt);
+        assert!((back - Vector3::new(6524.834, 6862.875, 6448.296)).norm() < 1e-8);
+    }
+}
@@ -0,0 +1,218 @@
+//! Gabbard diagrams and the drag evolution of fragment clouds
+//!
+//! A Gabbard diagram plots each object's apogee and perigee altitude against
+//! its orbital period. Fragments of one breakup start on an "X" centred on
+//! the parent orbit; drag then pulls the high-A/M pieces down and left,
+//! which is how analysts follow a cloud spreading. Orbits come from the
+//! mean elements of TLEs or the osculating orbit of state vectors, and are
+//! evolved with the orbit-averaged drag of `decay`, with J2 leaving the
+//! semi-major axis and eccentricity unchanged on average.
+
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use rayon::prelude::*;
+use std::f64::consts::PI;
+
+#[cfg(feature = "python")]
+use crate::columnar::Column;
+use crate::constants::{EARTH_RADIUS_KM, MU_EARTH};
+use crate::decay::{self, MeanOrbit};
+use crate::error::OrbitError;
+use crate::parallel;
+
+/// (period in minutes, apogee altitude km, perigee altitude km) of an orbit
+pub fn gabbard_point(semi_major_axis_km: f64, eccentricity: f64) -> (f64, f64, f64) {
+    let period = 2.0 * PI * (semi_major_axis_km.powi(3) / MU_EARTH).sqrt() / 60.0;
+    (
+        period,
+        semi_major_axis_km * (1.0 + eccentricity) - EARTH_RADIUS_KM,
+        semi_major_axis_km * (1.0 - eccentricity) - EARTH_RADIUS_KM,
+    )
+}
+
+#[cfg(feature = "python")]
+fn extract_orbits(objects: Vec<&PyAny>) -> PyResult<Vec<MeanOrbit>> {
+    objects.into_iter().map(MeanOrbit::extract).collect()
+}
+
+/// Gabbard data (period vs apogee and perigee) for a set of objects
+///
+/// Objects are `Satellite`s, `MeanElements` or `Sgp4Propagator`s. Returns a
+/// dict of columns: `norad_id`, `period_min`, `apogee_km` and `perigee_km`
+/// (altitudes above the equatorial radius), in input order.
+#[cfg(feature = "python")]
+#[pyfunction]
+pub fn gabbard_diagram(py: Python<'_>, objects: Vec<&PyAny>) -> PyResult<PyObject> {
+    let orbits = extract_orbits(objects)?;
+    let points: Vec<(f64, f64, f64)> =
+        orbits.iter().map(|orbit| gabbard_point(orbit.semi_major_axis_km, orbit.eccentricity)).collect();
+
+    let table = pyo3::types::PyDict::new(py);
+    table.set_item("norad_id", Py::new(py, Column::int32(orbits.iter().map(|o| o.norad_id).collect()))?)?;
+    table.set_item("period_min", Py::new(py, Column::float64(points.iter().map(|p| p.0).collect()))?)?;
+    table.set_item("apogee_km", Py::new(py, Column::float64(points.iter().map(|p| p.1).collect()))?)?;
+    table.set_item("perigee_km", Py::new(py, Column::float64(points.iter().map(|p| p.2).collect()))?)?;
+    Ok(table.into())
+}
+
+/// Gabbard points of each object at every sampled epoch
+#[derive(Clone, Debug)]
+pub struct FragmentEvolution {
+    pub days: Vec<f64>,
+    /// Per object, one (period min, apogee km, perigee km) per epoch; NaN once re-entered
+    pub points: Vec<Vec<(f64, f64, f64)>>,
+}
+
+/// Evolve `orbits` under drag for `days`, sampled every `step_days`
+///
+/// `ballistic_coefficients` (Cd * A / m in m^2/kg, one per orbit) defaults
+/// to the value implied by B*, which state vectors do not have. An object
+/// counts as re-entered once its perigee is below `reentry_altitude_km`.
+pub fn fragment_evolution(
+    orbits: &[MeanOrbit],
+    days: f64,
+    step_days: f64,
+    ballistic_coefficients: Option<&[f64]>,
+    reentry_altitude_km: f64,
+) -> Result<FragmentEvolution, OrbitError> {
+    if !(days >= 0.0 && days.is_finite()) {
+        return Err(OrbitError::InvalidInput("days must be a non-negative number".to_string()));
+    }
+    if step_days <= 0.0 {
+        return Err(OrbitError::InvalidInput("step_days must be positive".to_string()));
+    }
+    let coefficients: Vec<f64> = match ballistic_coefficients {
+        Some(values) if values.len() != orbits.len() => {
+            return Err(OrbitError::InvalidInput("ballistic_coefficients must have one value per object".to_string()))
+        }
+        Some(values) => values.to_vec(),
+        None => orbits
+            .iter()
+            .map(|orbit| {
+                orbit.implied_ballistic_coefficient.ok_or_else(|| {
+                    OrbitError::InvalidInput(format!(
+                        "Object {} is a state vector; pass ballistic_coefficients",
+                        orbit.norad_id
+                    ))
+                })
+            })
+            .collect::<Result<_, _>>()?,
+    };
+    if coefficients.iter().any(|b| !(*b >= 0.0 && b.is_finite())) {
+        return Err(OrbitError::InvalidInput(
+            "Ballistic coefficients must be non-negative (a negative B* gives no drag estimate)".to_string(),
+        ));
+    }
+
+    let n_steps = (days / step_days).ceil() as usize;
+    let times: Vec<f64> = (0..=n_steps).map(|k| (k as f64 * step_days).min(days)).collect();
+    let points = parallel::install(|| {
+        orbits
+            .par_iter()
+            .zip(&coefficients)
+            .map(|(orbit, &coefficient)| {
+                let mut state = Some((orbit.semi_major_axis_km, orbit.eccentricity));
+                let mut elapsed = 0.0;
+                times
+                    .iter()
+                    .map(|&t| {
+                        // march on from the previous sample
+                        state = state.and_then(|(a, e)| {
+                            decay::decay_forward(a, e, coefficient, reentry_altitude_km, t - elapsed)
+                        });
+                        elapsed = t;
+                        state.map_or((f64::NAN, f64::NAN, f64::NAN), |(a, e)| gabbard_point(a, e))
+                    })
+                    .collect()
+            })
+            .collect()
+    });
+    Ok(FragmentEvolution { days: times, points })
+}
+
+/// Gabbard data of a cloud evolved forward under drag
+///
+/// Every object is decayed from its own epoch for `days`, sampled every
+/// `step_days`. `ballistic_coefficients` (Cd * A / m in m^2/kg, one per
+/// object) defaults to the value implied by B* for element sets and is
+/// required for state vectors; for `breakup` fragments, 2.2 times their
+/// `area_to_mass` is the usual choice. Returns a dict with `days` (epochs),
+/// `norad_id` (objects) and `period_min`, `apogee_km` and `perigee_km`
+/// matrices of shape (epochs, objects), NaN once an object has re-entered
+/// (perigee below `reentry_altitude_km`).
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(name = "fragment_evolution", signature = (objects, days, step_days = 1.0, ballistic_coefficients = None, reentry_altitude_km = 120.0))]
+pub fn py_fragment_evolution(
+    py: Python<'_>,
+    objects: Vec<&PyAny>,
+    days: f64,
+    step_days: f64,
+    ballistic_coefficients: Option<Vec<f64>>,
+    reentry_altitude_km: f64,
+) -> PyResult<PyObject> {
+    let orbits = extract_orbits(objects)?;
+    let evolution = py.allow_threads(|| {
+        fragment_evolution(&orbits, days, step_days, ballistic_coefficients.as_deref(), reentry_altitude_km)
+    })?;
+    let (epochs, n) = (evolution.days.len(), orbits.len());
+    // (epochs, objects) layout
+    let matrix = |field: fn(&(f64, f64, f64)) -> f64| {
+        let values = (0..epochs * n).map(|cell| field(&evolution.points[cell % n][cell / n])).collect();
+        Column::matrix(values, epochs, n)
+    };
+
+    let table = pyo3::types::PyDict::new(py);
+    table.set_item("days", Py::new(py, Column::float64(evolution.days.clone()))?)?;
+    table.set_item("norad_id", Py::new(py, Column::int32(orbits.iter().map(|o| o.norad_id).collect()))?)?;
+    table.set_item("period_min", Py::new(py, matrix(|p| p.0))?)?;
+    table.set_item("apogee_km", Py::new(py, matrix(|p| p.1))?)?;
+    table.set_item("perigee_km", Py::new(py, matrix(|p| p.2))?)?;
+    Ok(table.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gabbard_point_and_decay() {
+        // a 415 km circular orbit (ISS-like) has a 92.9 min two-body period
+        let (period, apogee, perigee) = gabbard_point(EARTH_RADIUS_KM + 415.0, 0.0);
+        assert!((period - 92.87).abs() < 0.01, "{}", period);
+        assert!((apogee - 415.0).abs() < 1e-9 && (perigee - 415.0).abs() < 1e-9);
+
+        // a drag-affected eccentric fragment loses apogee much faster than perigee
+        let (a, e) = (EARTH_RADIUS_KM + 600.0, 200.0 / (EARTH_RADIUS_KM + 600.0));
+        let (a1, e1) = decay::decay_forward(a, e, 0.1, 120.0, 30.0).unwrap();
+        let (_, apogee_0, perigee_0) = gabbard_point(a, e);
+        let (period_1, apogee_1, perigee_1) = gabbard_point(a1, e1);
+        assert!(period_1 < gabbard_point(a, e).0);
+        assert!(apogee_0 - apogee_1 > 5.0 * (perigee_0 - perigee_1), "{} {}", apogee_0 - apogee_1, perigee_0 - perigee_1);
+        // chaining two 15-day steps matches one 30-day step
+        let (a_half, e_half) = decay::decay_forward(a, e, 0.1, 120.0, 15.0).unwrap();
+        let (a2, _) = decay::decay_forward(a_half, e_half, 0.1, 120.0, 15.0).unwrap();
+        assert!((a2 - a1).abs() < 0.1, "{} {}", a2, a1);
+        assert!(decay::decay_forward(a, e, 0.1, 120.0, 3650.0).is_none());
+    }
+    #[test]
+    fn test_fragment_evolution_samples_every_object() {
+        let orbit = |norad_id, altitude_km, coefficient| MeanOrbit {
+            norad_id,
+            semi_major_axis_km: EARTH_RADIUS_KM + altitude_km,
+            eccentricity: 0.0,
+            epoch_jd: None,
+            implied_ballistic_coefficient: coefficient,
+        };
+        let orbits = [orbit(1, 500.0, Some(0.01)), orbit(2, 200.0, Some(0.5))];
+        let evolution = fragment_evolution(&orbits, 25.0, 10.0, None, 120.0).unwrap();
+        assert_eq!(evolution.days, vec![0.0, 10.0, 20.0, 25.0]);
+        assert_eq!(evolution.points.len(), 2);
+        assert!((evolution.points[0][0].1 - 500.0).abs() < 1e-9 && evolution.points[0][3].1 < 500.0);
+        assert!(evolution.points[1][3].0.is_nan(), "the 200 km fragment re-enters");
+
+        let state_vector = [orbit(3, 500.0, None)];
+        assert!(fragment_evolution(&state_vector, 10.0, 1.0, None, 120.0).is_err());
+        assert!(fragment_evolution(&state_vector, 10.0, 1.0, Some(&[0.02]), 120.0).is_ok());
+    }
+}
@@ -0,0 +1,525 @@
+//! GEO screening by longitude slot and drift rate
+//!
+//! Geostationary encounters develop over days as objects drift through
+//! each other's longitude slots, so a snapshot distance check misses them.
+//! Each object in the GEO belt is reduced to its current longitude (right
+//! ascension of the position; relative longitudes are frame independent)
+//! and its drift rate relative to the Earth's rotation, n - w_E. Pairs are
+//! reported when their relative longitude comes within a slot width during
+//! the window. The miss estimate combines the along-track separation at
+//! closest longitude with the radial gap between the perigee/apogee bands,
+//! which never overstates the distance from eccentricity; refine candidate
+//! pairs by propagation.
+//!
+//! Station keeping is checked on the propagated track instead: the
+//! Earth-fixed longitude and geocentric latitude are sampled over a window
+//! and compared with the satellite's assigned box, giving the intervals
+//! spent outside it and the longitude drift rate and acceleration.
+
+use nalgebra::{Matrix3, Vector3};
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use rayon::prelude::*;
+
+use crate::constants::{EARTH_RADIUS_KM, EARTH_ROTATION_RATE, MU_EARTH, SECONDS_PER_DAY};
+use crate::error::OrbitError;
+use crate::frames;
+use crate::metadata::{GEO_ALTITUDE_KM, GEO_BAND_KM};
+use crate::screening::Trajectory;
+use crate::{check_common_epoch, parallel, sieve, Satellite};
+
+/// Slot-level state of one GEO object
+#[derive(Clone, Copy, Debug)]
+struct Slot {
+    longitude_deg: f64,
+    drift_deg_per_day: f64,
+    semi_major_axis_km: f64,
+    band: (f64, f64),
+}
+
+impl Slot {
+    fn of(sat: &Satellite) -> Option<Self> {
+        let r = sat.r();
+        let v = sat.v();
+        let energy = 0.5 * v.norm_squared() - MU_EARTH / r.norm();
+        if energy >= 0.0 {
+            return None;
+        }
+        let a = -MU_EARTH / (2.0 * energy);
+        let mean_motion = (MU_EARTH / (a * a * a)).sqrt();
+        Some(Slot {
+            longitude_deg: r.y.atan2(r.x).to_degrees(),
+            drift_deg_per_day: (mean_motion - EARTH_ROTATION_RATE).to_degrees() * SECONDS_PER_DAY,
+            semi_major_axis_km: a,
+            band: sieve::perigee_apogee(&r, &v),
+        })
+    }
+}
+
+/// Wrap an angle to (-180, 180] degrees
+fn wrap_deg(angle: f64) -> f64 {
+    let wrapped = (angle + 180.0).rem_euclid(360.0) - 180.0;
+    if wrapped == -180.0 {
+        180.0
+    } else {
+        wrapped
+    }
+}
+
+/// Smallest |relative longitude| over `[0, window_days]` and when it occurs
+///
+/// The relative longitude moves linearly, `start + rate * t`; it reaches
+/// zero whenever the unwrapped line crosses a multiple of 360 degrees.
+pub fn closest_longitude(start_deg: f64, rate_deg_per_day: f64, window_days: f64) -> (f64, f64) {
+    let start = wrap_deg(start_deg);
+    if rate_deg_per_day == 0.0 {
+        return (start.abs(), 0.0);
+    }
+    // first time the separation passes through zero (start is in (-180, 180])
+    let to_zero = -start / rate_deg_per_day;
+    let first_crossing = if to_zero >= 0.0 { to_zero } else { (start.signum() * 360.0 - start) / rate_deg_per_day };
+    if first_crossing <= window_days {
+        return (0.0, first_crossing);
+    }
+    let end = wrap_deg(start + rate_deg_per_day * window_days).abs();
+    if end < start.abs() {
+        (end, window_days)
+    } else {
+        (start.abs(), 0.0)
+    }
+}
+
+/// Slot approach between two GEO objects
+#[cfg_attr(feature = "python", pyclass(get_all))]
+#[derive(Clone, Debug)]
+pub struct GeoEncounter {
+    pub norad_id_1: i32,
+    pub norad_id_2: i32,
+    pub longitude_separation_deg: f64,   // object 2 - object 1 now, in (-180, 180]
+    pub relative_drift_deg_per_day: f64, // object 2 - object 1
+    pub min_longitude_separation_deg: f64,
+    pub days_to_closest: f64,
+    pub estimated_min_distance_km: f64,
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl GeoEncounter {
+    fn __repr__(&self) -> String {
+        format!(
+            "GeoEncounter({} ↔ {}, min_dlon={:.3}deg in {:.2}d, est_dist={:.1}km)",
+            self.norad_id_1,
+            self.norad_id_2,
+            self.min_longitude_separation_deg,
+            self.days_to_closest,
+            self.estimated_min_distance_km
+        )
+    }
+}
+
+fn encounter(
+    (id1, slot1): (i32, &Slot),
+    (id2, slot2): (i32, &Slot),
+    window_days: f64,
+) -> GeoEncounter {
+    let separation = wrap_deg(slot2.longitude_deg - slot1.longitude_deg);
+    let drift = slot2.drift_deg_per_day - slot1.drift_deg_per_day;
+    let (min_separation, days) = closest_longitude(separation, drift, window_days);
+    let radius = 0.5 * (slot1.semi_major_axis_km + slot2.semi_major_axis_km);
+    let along_track = radius * min_separation.to_radians();
+    let radial = (slot1.band.0.max(slot2.band.0) - slot1.band.1.min(slot2.band.1)).max(0.0);
+    GeoEncounter {
+        norad_id_1: id1,
+        norad_id_2: id2,
+        longitude_separation_deg: separation,
+        relative_drift_deg_per_day: drift,
+        min_longitude_separation_deg: min_separation,
+        days_to_closest: days,
+        estimated_min_distance_km: along_track.hypot(radial),
+    }
+}
+
+/// Screen objects in the GEO belt for slot approaches within a time window
+///
+/// Objects whose perigee/apogee band misses the GEO belt (geostationary
+/// altitude +- 200 km, widened by `threshold_km`) are ignored. A pair is
+/// reported when its relative longitude comes within `slot_deg` during the
+/// next `window_days` and the estimated miss is within `threshold_km`.
+/// Results are ordered by time to closest approach, then ids.
+pub fn screen_geo(
+    satellites: &[Satellite],
+    slot_deg: f64,
+    window_days: f64,
+    threshold_km: f64,
+) -> Result<Vec<GeoEncounter>, OrbitError> {
+    if slot_deg < 0.0 || window_days < 0.0 || threshold_km < 0.0 {
+        return Err(OrbitError::InvalidInput(
+            "slot_deg, window_days and threshold_km must be non-negative".to_string(),
+        ));
+    }
+    check_common_epoch(satellites)?;
+
+    let belt = (
+        EARTH_RADIUS_KM + GEO_ALTITUDE_KM - GEO_BAND_KM,
+        EARTH_RADIUS_KM + GEO_ALTITUDE_KM + GEO_BAND_KM,
+    );
+    let slots: Vec<(i32, Slot)> = satellites
+        .iter()
+        .filter_map(|sat| Some((sat.norad_id, Slot::of(sat)?)))
+        .filter(|(_, slot)| sieve::apsis_bands_overlap(slot.band, belt, threshold_km))
+        .collect();
+
+    let mut encounters: Vec<GeoEncounter> = parallel::install(|| {
+        (0..slots.len())
+            .into_par_iter()
+            .flat_map_iter(|i| {
+                let slots = &slots;
+                (i + 1..slots.len()).filter_map(move |j| {
+                    let ((id1, slot1), (id2, slot2)) = (&slots[i], &slots[j]);
+                    if !sieve::apsis_bands_overlap(slot1.band, slot2.band, threshold_km) {
+                        return None;
+                    }
+                    let event = encounter((*id1, slot1), (*id2, slot2), window_days);
+                    (event.min_longitude_separation_deg <= slot_deg && event.estimated_min_distance_km <= threshold_km)
+                        .then_some(event)
+                })
+            })
+            .collect()
+    });
+    encounters.sort_by(|a, b| {
+        a.days_to_closest
+            .total_cmp(&b.days_to_closest)
+            .then((a.norad_id_1, a.norad_id_2).cmp(&(b.norad_id_1, b.norad_id_2)))
+    });
+    Ok(encounters)
+}
+
+/// Screen objects in the GEO belt for slot approaches within a time window
+///
+/// Objects whose perigee/apogee band misses the GEO belt (geostationary
+/// altitude +- 200 km, widened by `threshold_km`) are ignored. A pair is
+/// reported when its relative longitude comes within `slot_deg` during the
+/// next `window_days` and the estimated miss is within `threshold_km`.
+/// Results are ordered by time to closest approach, then ids.
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(name = "screen_geo", signature = (satellites, slot_deg = 0.1, window_days = 14.0, threshold_km = 50.0))]
+pub fn py_screen_geo(
+    py: Python<'_>,
+    satellites: Vec<Satellite>,
+    slot_deg: f64,
+    window_days: f64,
+    threshold_km: f64,
+) -> PyResult<Vec<GeoEncounter>> {
+    Ok(py.allow_threads(|| screen_geo(&satellites, slot_deg, window_days, threshold_km))?)
+}
+
+/// Assigned station-keeping box, in degrees
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct StationBox {
+    /// East longitude of the box centre
+    pub longitude_deg: f64,
+    /// Allowed longitude offset either side of the centre
+    pub longitude_half_width_deg: f64,
+    /// Allowed geocentric latitude either side of the equator
+    pub latitude_half_width_deg: f64,
+}
+
+/// Interval spent beyond one edge of the box
+#[cfg_attr(feature = "python", pyclass(get_all))]
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoxViolation {
+    /// Julian dates; the window edges when the violation runs past them
+    pub start_jd: f64,
+    pub end_jd: f64,
+    /// "east", "west", "north" or "south"
+    pub edge: &'static str,
+    /// Largest sampled excursion beyond the edge (degrees)
+    pub max_excursion_deg: f64,
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl BoxViolation {
+    fn __repr__(&self) -> String {
+        format!(
+            "BoxViolation({} edge, {:.5}..{:.5}, max {:.4}deg)",
+            self.edge, self.start_jd, self.end_jd, self.max_excursion_deg
+        )
+    }
+}
+
+/// Station-keeping check of one GEO satellite over a window
+#[cfg_attr(feature = "python", pyclass(get_all))]
+#[derive(Clone, Debug, PartialEq)]
+pub struct StationKeepingReport {
+    pub norad_id: i32,
+    pub box_longitude_deg: f64,
+    /// Mean sampled east longitude
+    pub mean_longitude_deg: f64,
+    /// Longitude drift rate at the window start (deg/day, positive east)
+    pub drift_rate_deg_per_day: f64,
+    /// Rate of change of the drift rate (deg/day^2)
+    pub drift_acceleration_deg_per_day2: f64,
+    /// Sampled longitude offsets from the box centre: (most west, most east)
+    pub longitude_offset_range_deg: (f64, f64),
+    /// Largest sampled |geocentric latitude|
+    pub max_latitude_deg: f64,
+    /// Violations in time order
+    pub violations: Vec<BoxViolation>,
+}
+
+impl StationKeepingReport {
+    /// Start of the first violation, None if the satellite stays in the box
+    pub fn first_exit_jd(&self) -> Option<f64> {
+        self.violations.first().map(|v| v.start_jd)
+    }
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl StationKeepingReport {
+    /// Start of the first violation, None if the satellite stays in the box
+    #[getter]
+    #[pyo3(name = "first_exit_jd")]
+    fn py_first_exit_jd(&self) -> Option<f64> {
+        self.first_exit_jd()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "StationKeepingReport(norad_id={}, box={:.3}deg, drift={:+.5}deg/day, violations={})",
+            self.norad_id,
+            self.box_longitude_deg,
+            self.drift_rate_deg_per_day,
+            self.violations.len()
+        )
+    }
+}
+
+/// Intervals where the sampled `excess` is positive, with linearly interpolated crossings
+fn excursions(times: &[f64], excess: &[f64], edge: &'static str) -> Vec<BoxViolation> {
+    let mut violations = Vec::new();
+    let mut open: Option<BoxViolation> = None;
+    for k in 0..times.len() {
+        let (t, e) = (times[k], excess[k]);
+        match (&mut open, e > 0.0) {
+            (None, true) => {
+                let start = if k == 0 { t } else { crossing(times[k - 1], excess[k - 1], t, e) };
+                open = Some(BoxViolation { start_jd: start, end_jd: t, edge, max_excursion_deg: e });
+            }
+            (Some(violation), true) => {
+                violation.end_jd = t;
+                violation.max_excursion_deg = violation.max_excursion_deg.max(e);
+            }
+            (Some(violation), false) => {
+                violation.end_jd = crossing(times[k - 1], excess[k - 1], t, e);
+                violations.extend(open.take());
+            }
+            (None, false) => {}
+        }
+    }
+    violations.extend(open);
+    violations
+}
+
+/// Time where the line through (t0, e0) and (t1, e1) crosses zero
+fn crossing(t0: f64, e0: f64, t1: f64, e1: f64) -> f64 {
+    t0 + (t1 - t0) * e0 / (e0 - e1)
+}
+
+/// Check a sampled track of (Julian date, east longitude, geocentric latitude) in degrees against a box
+///
+/// Longitudes are unwrapped along the track, so a drift through 180 deg
+/// stays continuous. The drift rate and acceleration come from a quadratic
+/// least-squares fit of the longitude offset over time.
+pub fn check_station_box(
+    norad_id: i32,
+    track: &[(f64, f64, f64)],
+    station: &StationBox,
+) -> Result<StationKeepingReport, String> {
+    if track.len() < 3 {
+        return Err("At least three samples are needed to fit the drift".to_string());
+    }
+    if station.longitude_half_width_deg < 0.0 || station.latitude_half_width_deg < 0.0 {
+        return Err("Box half-widths must be non-negative".to_string());
+    }
+    let times: Vec<f64> = track.iter().map(|s| s.0).collect();
+    let mut offsets = Vec::with_capacity(track.len());
+    let mut previous = wrap_deg(track[0].1 - station.longitude_deg);
+    for &(_, longitude, _) in track {
+        previous += wrap_deg(wrap_deg(longitude - station.longitude_deg) - wrap_deg(previous));
+        offsets.push(previous);
+    }
+    let latitudes: Vec<f64> = track.iter().map(|s| s.2).collect();
+
+    // offset = c0 + c1 t + c2 t^2, t in days from the first sample
+    let mut normal = Matrix3::zeros();
+    let mut rhs = Vector3::zeros();
+    for (&t, &offset) in times.iter().zip(&offsets) {
+        let days = t - times[0];
+        let basis = Vector3::new(1.0, days, days * days);
+        normal += basis * basis.transpose();
+        rhs += basis * offset;
+    }
+    let fit = normal.lu().solve(&rhs).ok_or_else(|| "The samples span too little time to fit the drift".to_string())?;
+
+    let (half_width, half_height) = (station.longitude_half_width_deg, station.latitude_half_width_deg);
+    let mut violations = Vec::new();
+    let edges: [(&'static str, Vec<f64>); 4] = [
+        ("east", offsets.iter().map(|o| o - half_width).collect()),
+        ("west", offsets.iter().map(|o| -o - half_width).collect()),
+        ("north", latitudes.iter().map(|l| l - half_height).collect()),
+        ("south", latitudes.iter().map(|l| -l - half_height).collect()),
+    ];
+    for (edge, excess) in &edges {
+        violations.extend(excursions(&times, excess, edge));
+    }
+    violations.sort_by(|a, b| a.start_jd.total_cmp(&b.start_jd));
+
+    let mean_offset = offsets.iter().sum::<f64>() / offsets.len() as f64;
+    let offset_range = offsets.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &o| (lo.min(o), hi.max(o)));
+    Ok(StationKeepingReport {
+        norad_id,
+        box_longitude_deg: station.longitude_deg,
+        mean_longitude_deg: wrap_deg(station.longitude_deg + mean_offset),
+        drift_rate_deg_per_day: fit[1],
+        drift_acceleration_deg_per_day2: 2.0 * fit[2],
+        longitude_offset_range_deg: offset_range,
+        max_latitude_deg: latitudes.iter().fold(0.0, |m: f64, l| m.max(l.abs())),
+        violations,
+    })
+}
+
+/// Propagate a GEO satellite and check it against its station-keeping box
+///
+/// The track is sampled every `step` seconds over [start_epoch, end_epoch]
+/// (Julian dates) in Earth-fixed coordinates (UTC taken as UT1, no polar
+/// motion); samples where propagation fails are skipped. Violation times are
+/// interpolated between samples.
+pub fn check_station_keeping(
+    satellite: &Trajectory,
+    station: &StationBox,
+    start_epoch: f64,
+    end_epoch: f64,
+    step: f64,
+) -> Result<StationKeepingReport, OrbitError> {
+    if end_epoch <= start_epoch || step <= 0.0 {
+        return Err(OrbitError::InvalidInput("Need end_epoch after start_epoch and a positive step".to_string()));
+    }
+    let duration = (end_epoch - start_epoch) * SECONDS_PER_DAY;
+    let track: Vec<(f64, f64, f64)> = (0..=(duration / step).ceil() as usize)
+        .map(|k| (k as f64 * step).min(duration))
+        .filter_map(|t| {
+            let (r, v) = satellite.state_at(start_epoch, t)?;
+            let jd = start_epoch + t / SECONDS_PER_DAY;
+            let (r, _) = frames::teme_to_ecef_state(&r, &v, jd, 0.0, 0.0);
+            Some((jd, r.y.atan2(r.x).to_degrees(), (r.z / r.norm()).asin().to_degrees()))
+        })
+        .collect();
+    check_station_box(satellite.norad_id(), &track, station).map_err(OrbitError::InvalidInput)
+}
+
+/// Propagate a GEO satellite and check it against its station-keeping box
+///
+/// The track is sampled every `step` seconds over [start_epoch, end_epoch]
+/// (Julian dates) in Earth-fixed coordinates (UTC taken as UT1, no polar
+/// motion); samples where propagation fails are skipped. Box edges are in
+/// degrees: `longitude_half_width_deg` either side of `box_longitude_deg`
+/// (east) and `latitude_half_width_deg` either side of the equator.
+/// Violation times are interpolated between samples.
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(name = "check_station_keeping", signature = (
+    satellite,
+    box_longitude_deg,
+    start_epoch,
+    end_epoch,
+    longitude_half_width_deg = 0.05,
+    latitude_half_width_deg = 0.05,
+    step = 600.0
+))]
+#[allow(clippy::too_many_arguments)]
+pub fn py_check_station_keeping(
+    py: Python<'_>,
+    satellite: Trajectory,
+    box_longitude_deg: f64,
+    start_epoch: f64,
+    end_epoch: f64,
+    longitude_half_width_deg: f64,
+    latitude_half_width_deg: f64,
+    step: f64,
+) -> PyResult<StationKeepingReport> {
+    let station = StationBox { longitude_deg: box_longitude_deg, longitude_half_width_deg, latitude_half_width_deg };
+    Ok(py.allow_threads(|| check_station_keeping(&satellite, &station, start_epoch, end_epoch, step))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_closest_longitude_crossings() {
+        // closing at 1 deg/day from 3 deg: reaches zero after 3 days
+        assert_eq!(closest_longitude(3.0, -1.0, 10.0), (0.0, 3.0));
+        // too slow for the window: closest at the end
+        let (separation, days) = closest_longitude(3.0, -0.1, 10.0);
+        assert!((separation - 2.0).abs() < 1e-12 && days == 10.0);
+        // diverging: closest now
+        assert_eq!(closest_longitude(3.0, 0.5, 10.0), (3.0, 0.0));
+        // drifting forward from 179 deg wraps to -176 deg after 5 days, and meets after 181
+        assert_eq!(closest_longitude(179.0, 1.0, 5.0), (176.0, 5.0));
+        assert_eq!(closest_longitude(179.0, 1.0, 200.0), (0.0, 181.0));
+    }
+
+    #[test]
+    fn test_drift_rate_of_geostationary_orbit() {
+        let a = (MU_EARTH / (EARTH_ROTATION_RATE * EARTH_ROTATION_RATE)).cbrt();
+        let v = (MU_EARTH / a).sqrt();
+        let sat = Satellite::from_state(1, [a, 0.0, 0.0], [0.0, v, 0.0], None);
+        let slot = Slot::of(&sat).unwrap();
+        assert!(slot.drift_deg_per_day.abs() < 1e-9);
+        // 10 km lower drifts east by about 0.128 deg/day
+        let lower = Satellite::from_state(2, [a - 10.0, 0.0, 0.0], [0.0, (MU_EARTH / (a - 10.0)).sqrt(), 0.0], None);
+        assert!((Slot::of(&lower).unwrap().drift_deg_per_day - 0.128).abs() < 2e-3);
+    }
+
+    #[test]
+    fn test_station_box_violation_and_drift() {
+        // drifting east at 0.02 deg/day from 0.03 deg west of the centre, inclined 0.06 deg
+        let track: Vec<(f64, f64, f64)> = (0..=70)
+            .map(|k| {
+                let days = 0.1 * k as f64;
+                let latitude = 0.06 * (2.0 * std::f64::consts::PI * days).sin();
+                (2460000.5 + days, 75.0 - 0.03 + 0.02 * days, latitude)
+            })
+            .collect();
+        let station = StationBox { longitude_deg: 75.0, longitude_half_width_deg: 0.05, latitude_half_width_deg: 0.1 };
+        let report = check_station_box(7, &track, &station).unwrap();
+        assert!((report.drift_rate_deg_per_day - 0.02).abs() < 1e-9);
+        assert!(report.drift_acceleration_deg_per_day2.abs() < 1e-9);
+        assert_eq!(report.violations.len(), 1);
+        let exit = &report.violations[0];
+        assert_eq!(exit.edge, "east");
+        assert!((exit.start_jd - (2460000.5 + 4.0)).abs() < 1e-6);
+        assert_eq!(exit.end_jd, 2460000.5 + 7.0);
+        assert!((exit.max_excursion_deg - 0.06).abs() < 1e-9);
+
+        // a tighter latitude limit adds north and south excursions every day
+        let tight = StationBox { latitude_half_width_deg: 0.05, ..station };
+        let report = check_station_box(7, &track, &tight).unwrap();
+        assert!(report.violations.iter().filter(|v| v.edge == "north").count() >= 6);
+        assert!(report.violations.iter().filter(|v| v.edge == "south").count() >= 6);
+    }
+
+    #[test]
+    fn test_station_box_unwraps_antimeridian() {
+        let track: Vec<(f64, f64, f64)> =
+            (0..=10).map(|k| (2460000.5 + k as f64, wrap_deg(179.95 + 0.01 * k as f64), 0.0)).collect();
+        let station = StationBox { longitude_deg: -180.0, longitude_half_width_deg: 0.1, latitude_half_width_deg: 0.1 };
+        let report = check_station_box(1, &track, &station).unwrap();
+        assert!((report.drift_rate_deg_per_day - 0.01).abs() < 1e-9);
+        assert!(report.violations.is_empty());
+        assert!((report.longitude_offset_range_deg.0 + 0.05).abs() < 1e-9);
+    }
+}
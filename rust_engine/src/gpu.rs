@@ -0,0 +1,322 @@
+//! Geometric screening filter on an OpenCL device
+//!
+//! With the `opencl` feature the coarse sampled-distance test of
+//! `ScreeningFilter::Geometric` can run on a GPU through the `opencl3`
+//! crate, which links the system OpenCL library (libOpenCL). Sampled states
+//! are uploaded once per screen; the device then fills a pass/fail byte per
+//! pair for a block of rows at a time, so device memory for the results
+//! stays bounded.
+//! Devices without double precision (`cl_khr_fp64`) fail to build the
+//! kernel and are reported as unavailable.
+//!
+//! Without the feature `upload` always fails, which screening treats like
+//! a missing device and runs the filter on the CPU instead.
+//!
+//! OpenCL rather than wgpu or CUDA: WGSL has no 64-bit floats, so a wgpu
+//! kernel would test a looser f32 bound and keep different pairs than the
+//! CPU filter. CUDA would limit the backend to NVIDIA cards and need the
+//! toolkit at build time. OpenCL runs the same f64 test on any vendor's GPU.
+
+use nalgebra::Vector3;
+
+type State = (Vector3<f64>, Vector3<f64>);
+
+/// Sampled states of every screened object, resident on the device
+pub struct DeviceStates {
+    #[cfg(feature = "opencl")]
+    states: opencl3::memory::Buffer<f64>,
+    #[cfg(feature = "opencl")]
+    times: opencl3::memory::Buffer<f64>,
+    #[cfg(feature = "opencl")]
+    objects: usize,
+    #[cfg(feature = "opencl")]
+    steps: usize,
+}
+
+/// Whether an OpenCL device is usable for screening
+pub fn available() -> bool {
+    device_name().is_ok()
+}
+
+/// Name of the device screening would use, or why there is none
+pub fn device_name() -> Result<String, String> {
+    #[cfg(feature = "opencl")]
+    {
+        cl::kernel().map(|kernel| kernel.lock().unwrap_or_else(|e| e.into_inner()).device_name.clone())
+    }
+    #[cfg(not(feature = "opencl"))]
+    {
+        Err(DISABLED.to_string())
+    }
+}
+
+#[cfg(not(feature = "opencl"))]
+const DISABLED: &str = "orbit_core was built without the `opencl` feature";
+
+/// Copy sampled states (one row per object over `times`) to the device
+///
+/// Unavailable samples are sent as NaN, which the kernel skips exactly as
+/// the CPU filter skips missing states.
+pub fn upload(states: &[Vec<Option<State>>], times: &[f64]) -> Result<DeviceStates, String> {
+    #[cfg(feature = "opencl")]
+    {
+        let mut flat = Vec::with_capacity(states.len() * times.len() * 6);
+        for row in states {
+            if row.len() != times.len() {
+                return Err("Every object needs one state per sample time".to_string());
+            }
+            for state in row {
+                match state {
+                    Some((r, v)) => flat.extend(r.iter().chain(v.iter())),
+                    None => flat.extend([f64::NAN; 6]),
+                }
+            }
+        }
+        let kernel = cl::kernel()?.lock().unwrap_or_else(|e| e.into_inner());
+        Ok(DeviceStates {
+            states: kernel.read_only_buffer(&flat)?,
+            times: kernel.read_only_buffer(times)?,
+            objects: states.len(),
+            steps: times.len(),
+        })
+    }
+    #[cfg(not(feature = "opencl"))]
+    {
+        let _ = (states, times);
+        Err(DISABLED.to_string())
+    }
+}
+
+impl DeviceStates {
+    /// Geometric filter verdicts for rows `first_row..first_row + rows`
+    ///
+    /// Returns `rows * objects` bytes, row-major, non-zero where pair
+    /// (i, j) can come within `reach_km` between samples. Partners of row
+    /// i are j > i, or j >= `partners_from` when that is given; every other
+    /// byte is zero.
+    pub fn geometric_mask(
+        &self,
+        first_row: usize,
+        rows: usize,
+        partners_from: Option<usize>,
+        reach_km: f64,
+    ) -> Result<Vec<u8>, String> {
+        #[cfg(feature = "opencl")]
+        {
+            if first_row + rows > self.objects {
+                return Err("Mask rows run past the uploaded objects".to_string());
+            }
+            let kernel = cl::kernel()?.lock().unwrap_or_else(|e| e.into_inner());
+            kernel.geometric_mask(self, first_row, rows, partners_from, reach_km)
+        }
+        #[cfg(not(feature = "opencl"))]
+        {
+            let _ = (first_row, rows, partners_from, reach_km);
+            Err(DISABLED.to_string())
+        }
+    }
+}
+
+/// The kernel mirrors `screening::geometric_candidate`, in double precision
+#[cfg_attr(not(feature = "opencl"), allow(dead_code))]
+const GEOMETRIC_KERNEL: &str = r#"
+#pragma OPENCL EXTENSION cl_khr_fp64 : enable
+
+__kernel void geometric_mask(
+    __global const double *states,
+    __global const double *times,
+    const uint steps,
+    const uint objects,
+    const uint first_row,
+    const uint explicit_partners,
+    const uint partners_from,
+    const double reach_km,
+    const double max_accel,
+    __global uchar *mask)
+{
+    const uint r = get_global_id(0);
+    const uint j = get_global_id(1);
+    const uint i = first_row + r;
+    const uint first = explicit_partners ? partners_from : i + 1;
+    uchar hit = 0;
+    if (j >= first && j < objects) {
+        __global const double *a = states + (size_t)i * steps * 6;
+        __global const double *b = states + (size_t)j * steps * 6;
+        for (uint k = 0; k + 1 < steps && !hit; k++) {
+            const double3 a1r = vload3(0, a + 6 * k), a1v = vload3(1, a + 6 * k);
+            const double3 b1r = vload3(2, a + 6 * k), b1v = vload3(3, a + 6 * k);
+            const double3 a2r = vload3(0, b + 6 * k), a2v = vload3(1, b + 6 * k);
+            const double3 b2r = vload3(2, b + 6 * k), b2v = vload3(3, b + 6 * k);
+            if (isnan(a1r.x) || isnan(b1r.x) || isnan(a2r.x) || isnan(b2r.x)) {
+                continue;
+            }
+            const double h = times[k + 1] - times[k];
+            const double speed = fmax(length(a1v - a2v), length(b1v - b2v)) + max_accel * h;
+            const double da = length(a1r - a2r), db = length(b1r - b2r);
+            hit = 0.5 * (da + db - speed * h) <= reach_km;
+        }
+    }
+    mask[(size_t)r * objects + j] = hit;
+}
+"#;
+
+/// Slack on the reach so rounding differences from the CPU never drop a pair
+#[cfg_attr(not(feature = "opencl"), allow(dead_code))]
+const REACH_SLACK_KM: f64 = 1e-6;
+
+#[cfg(feature = "opencl")]
+mod cl {
+    //! The one kernel, through the `opencl3` binding
+
+    use super::{DeviceStates, GEOMETRIC_KERNEL, REACH_SLACK_KM};
+    use crate::screening::MAX_RELATIVE_ACCEL;
+    use opencl3::command_queue::CommandQueue;
+    use opencl3::context::Context;
+    use opencl3::device::{Device, CL_DEVICE_TYPE_ALL, CL_DEVICE_TYPE_GPU};
+    use opencl3::kernel::Kernel;
+    use opencl3::memory::{Buffer, ClMem, CL_MEM_COPY_HOST_PTR, CL_MEM_READ_ONLY, CL_MEM_WRITE_ONLY};
+    use opencl3::platform;
+    use opencl3::program::Program;
+    use opencl3::types::{cl_uint, CL_BLOCKING};
+    use std::ffi::c_void;
+    use std::ptr;
+    use std::sync::{Mutex, OnceLock};
+
+    fn failed(call: &str) -> impl Fn(opencl3::error_codes::ClError) -> String + '_ {
+        move |err| format!("{} failed: {}", call, err)
+    }
+
+    /// Compiled geometric kernel on the first GPU (or any device if there is none)
+    pub struct GeometricKernel {
+        context: Context,
+        queue: CommandQueue,
+        kernel: Kernel,
+        pub device_name: String,
+    }
+
+    /// The process-wide kernel, built on first use
+    ///
+    /// Kernel arguments are per kernel object, so the mutex also keeps two
+    /// screens from interleaving `set_arg` calls.
+    pub fn kernel() -> Result<&'static Mutex<GeometricKernel>, String> {
+        static KERNEL: OnceLock<Result<Mutex<GeometricKernel>, String>> = OnceLock::new();
+        KERNEL.get_or_init(|| GeometricKernel::build().map(Mutex::new)).as_ref().map_err(Clone::clone)
+    }
+
+    fn first_device() -> Result<Device, String> {
+        let platforms = platform::get_platforms().unwrap_or_default();
+        if platforms.is_empty() {
+            return Err("No OpenCL platform found".to_string());
+        }
+        for device_type in [CL_DEVICE_TYPE_GPU, CL_DEVICE_TYPE_ALL] {
+            for platform in &platforms {
+                if let Some(id) = platform.get_devices(device_type).ok().and_then(|ids| ids.first().copied()) {
+                    return Ok(Device::new(id));
+                }
+            }
+        }
+        Err("No OpenCL device found".to_string())
+    }
+
+    impl GeometricKernel {
+        fn build() -> Result<Self, String> {
+            let device = first_device()?;
+            let device_name = device.name().unwrap_or_else(|_| "unknown OpenCL device".to_string());
+            let context = Context::from_device(&device).map_err(failed("clCreateContext"))?;
+            let queue = CommandQueue::create(&context, device.id(), 0).map_err(failed("clCreateCommandQueue"))?;
+            // the error is the build log when compiling fails (no fp64, for one)
+            let program = Program::create_and_build_from_source(&context, GEOMETRIC_KERNEL, "").map_err(|log| {
+                format!("Building the screening kernel for {} failed: {}", device_name, log.trim())
+            })?;
+            let kernel = Kernel::create(&program, "geometric_mask").map_err(failed("clCreateKernel"))?;
+            Ok(GeometricKernel { context, queue, kernel, device_name })
+        }
+
+        pub fn read_only_buffer(&self, data: &[f64]) -> Result<Buffer<f64>, String> {
+            let flags = CL_MEM_READ_ONLY | CL_MEM_COPY_HOST_PTR;
+            Buffer::create(&self.context, flags, data.len(), data.as_ptr() as *mut c_void).map_err(failed("clCreateBuffer"))
+        }
+
+        pub fn geometric_mask(
+            &self,
+            uploaded: &DeviceStates,
+            first_row: usize,
+            rows: usize,
+            partners_from: Option<usize>,
+            reach_km: f64,
+        ) -> Result<Vec<u8>, String> {
+            let mut mask = vec![0u8; rows * uploaded.objects];
+            if mask.is_empty() {
+                return Ok(mask);
+            }
+            let narrow = |x: usize| cl_uint::try_from(x).map_err(|_| "Too many objects for the OpenCL kernel".to_string());
+            let output = Buffer::<u8>::create(&self.context, CL_MEM_WRITE_ONLY, mask.len(), ptr::null_mut())
+                .map_err(failed("clCreateBuffer"))?;
+            // without explicit partners row i pairs with every j > i
+            let (explicit_partners, partners_from): (cl_uint, cl_uint) = match partners_from {
+                Some(j) => (1, narrow(j)?),
+                None => (0, 0),
+            };
+            let (steps, objects, first_row) = (narrow(uploaded.steps)?, narrow(uploaded.objects)?, narrow(first_row)?);
+
+            let set_args = || -> opencl3::Result<()> {
+                self.kernel.set_arg(0, &uploaded.states.get())?;
+                self.kernel.set_arg(1, &uploaded.times.get())?;
+                self.kernel.set_arg(2, &steps)?;
+                self.kernel.set_arg(3, &objects)?;
+                self.kernel.set_arg(4, &first_row)?;
+                self.kernel.set_arg(5, &explicit_partners)?;
+                self.kernel.set_arg(6, &partners_from)?;
+                self.kernel.set_arg(7, &(reach_km + REACH_SLACK_KM))?;
+                self.kernel.set_arg(8, &MAX_RELATIVE_ACCEL)?;
+                self.kernel.set_arg(9, &output.get())
+            };
+            set_args().map_err(failed("clSetKernelArg"))?;
+
+            let global = [rows, uploaded.objects];
+            self.queue
+                .enqueue_nd_range_kernel(self.kernel.get(), 2, ptr::null(), global.as_ptr(), ptr::null(), &[])
+                .map_err(failed("clEnqueueNDRangeKernel"))?;
+            self.queue
+                .enqueue_read_buffer(&output, CL_BLOCKING, 0, &mut mask, &[])
+                .map_err(failed("clEnqueueReadBuffer"))?;
+            Ok(mask)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mask_matches_cpu_filter_or_reports_no_device() {
+        let times: Vec<f64> = (0..=10).map(|k| 60.0 * k as f64).collect();
+        // three objects on parallel straight tracks offset by 0, 50 and 400 km
+        let states: Vec<Vec<Option<State>>> = [0.0, 50.0, 400.0]
+            .iter()
+            .map(|&offset| {
+                times
+                    .iter()
+                    .map(|&t| Some((Vector3::new(7000.0, offset, 7.5 * t), Vector3::new(0.0, 0.0, 7.5))))
+                    .collect()
+            })
+            .collect();
+        let uploaded = match upload(&states, &times) {
+            Ok(uploaded) => uploaded,
+            Err(reason) => {
+                assert!(!reason.is_empty());
+                assert!(!available());
+                return;
+            }
+        };
+        let mask = uploaded.geometric_mask(0, 3, None, 100.0).unwrap();
+        assert_eq!(mask.iter().map(|&m| m != 0).collect::<Vec<_>>(), [
+            false, true, false, //
+            false, false, false, //
+            false, false, false,
+        ]);
+        let cross = uploaded.geometric_mask(1, 1, Some(2), 400.0).unwrap();
+        assert_eq!(cross.iter().map(|&m| m != 0).collect::<Vec<_>>(), [false, false, true]);
+    }
+}
@@ -0,0 +1,216 @@
+//! Conjunction graph analysis
+//!
+//! Satellites are nodes and conjunctions are undirected edges; repeated
+//! events between the same pair collapse into one edge that keeps the
+//! event count and closest miss. Connected components show congested
+//! neighbourhoods, and the degree (distinct partners) of each object ranks
+//! the most threatened ones.
+
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::Conjunction;
+
+/// Object ranked by its number of conjunction partners
+#[cfg_attr(feature = "python", pyclass(get_all))]
+#[derive(Clone, Debug)]
+pub struct ThreatenedObject {
+    pub norad_id: i32,
+    pub degree: usize, // distinct conjunction partners
+    pub events: usize,
+    pub min_distance_km: f64,
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl ThreatenedObject {
+    fn __repr__(&self) -> String {
+        format!(
+            "ThreatenedObject({}, degree={}, events={}, min_dist={:.3}km)",
+            self.norad_id, self.degree, self.events, self.min_distance_km
+        )
+    }
+}
+
+/// Undirected graph of satellites linked by conjunctions
+#[cfg_attr(feature = "python", pyclass)]
+#[derive(Clone, Debug, Default)]
+pub struct ConjunctionGraph {
+    adjacency: BTreeMap<i32, BTreeSet<i32>>,
+    events: HashMap<i32, usize>,
+    min_distance: HashMap<i32, f64>,
+}
+
+impl ConjunctionGraph {
+    pub fn from_conjunctions(conjunctions: &[Conjunction]) -> Self {
+        let mut graph = ConjunctionGraph::default();
+        for c in conjunctions {
+            for (node, other) in [(c.norad_id_1, c.norad_id_2), (c.norad_id_2, c.norad_id_1)] {
+                if node != other {
+                    graph.adjacency.entry(node).or_default().insert(other);
+                }
+                *graph.events.entry(node).or_default() += 1;
+                let closest = graph.min_distance.entry(node).or_insert(f64::INFINITY);
+                *closest = closest.min(c.distance_km);
+            }
+        }
+        graph
+    }
+
+    /// Connected components, largest first, each sorted by id
+    pub fn connected_components(&self) -> Vec<Vec<i32>> {
+        let mut seen = BTreeSet::new();
+        let mut components = Vec::new();
+        for &start in self.adjacency.keys() {
+            if !seen.insert(start) {
+                continue;
+            }
+            let mut component = vec![start];
+            let mut stack = vec![start];
+            while let Some(node) = stack.pop() {
+                for &next in &self.adjacency[&node] {
+                    if seen.insert(next) {
+                        component.push(next);
+                        stack.push(next);
+                    }
+                }
+            }
+            component.sort_unstable();
+            components.push(component);
+        }
+        // stable sort keeps components of equal size ordered by smallest id
+        components.sort_by_key(|component| std::cmp::Reverse(component.len()));
+        components
+    }
+
+    fn threatened(&self, norad_id: i32) -> ThreatenedObject {
+        ThreatenedObject {
+            norad_id,
+            degree: self.adjacency.get(&norad_id).map_or(0, BTreeSet::len),
+            events: self.events.get(&norad_id).copied().unwrap_or(0),
+            min_distance_km: self.min_distance.get(&norad_id).copied().unwrap_or(f64::INFINITY),
+        }
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.adjacency.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.adjacency.values().map(BTreeSet::len).sum::<usize>() / 2
+    }
+
+    /// Distinct conjunction partners per satellite
+    pub fn degree(&self) -> HashMap<i32, usize> {
+        self.adjacency.iter().map(|(&id, partners)| (id, partners.len())).collect()
+    }
+
+    /// Partners of one satellite, sorted; empty if it has no conjunctions
+    pub fn neighbors(&self, norad_id: i32) -> Vec<i32> {
+        self.adjacency.get(&norad_id).map_or_else(Vec::new, |partners| partners.iter().copied().collect())
+    }
+
+    /// The `n` objects with the most partners; ties go to more events, then the closer miss, then id
+    pub fn most_threatened(&self, n: usize) -> Vec<ThreatenedObject> {
+        let mut objects: Vec<ThreatenedObject> = self.adjacency.keys().map(|&id| self.threatened(id)).collect();
+        objects.sort_by(|a, b| {
+            b.degree
+                .cmp(&a.degree)
+                .then(b.events.cmp(&a.events))
+                .then(a.min_distance_km.total_cmp(&b.min_distance_km))
+                .then(a.norad_id.cmp(&b.norad_id))
+        });
+        objects.truncate(n);
+        objects
+    }
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl ConjunctionGraph {
+    #[new]
+    fn new(conjunctions: Vec<Conjunction>) -> Self {
+        ConjunctionGraph::from_conjunctions(&conjunctions)
+    }
+
+    #[getter]
+    #[pyo3(name = "node_count")]
+    fn py_node_count(&self) -> usize {
+        self.node_count()
+    }
+
+    #[getter]
+    #[pyo3(name = "edge_count")]
+    fn py_edge_count(&self) -> usize {
+        self.edge_count()
+    }
+
+    /// Connected components, largest first, each sorted by id
+    #[getter]
+    fn components(&self) -> Vec<Vec<i32>> {
+        self.connected_components()
+    }
+
+    /// Distinct conjunction partners per satellite
+    #[getter]
+    #[pyo3(name = "degree")]
+    fn py_degree(&self) -> HashMap<i32, usize> {
+        self.degree()
+    }
+
+    /// Partners of one satellite, sorted; empty if it has no conjunctions
+    #[pyo3(name = "neighbors")]
+    fn py_neighbors(&self, norad_id: i32) -> Vec<i32> {
+        self.neighbors(norad_id)
+    }
+
+    /// The `n` objects with the most partners; ties go to more events, then the closer miss, then id
+    #[pyo3(name = "most_threatened", signature = (n = 10))]
+    fn py_most_threatened(&self, n: usize) -> Vec<ThreatenedObject> {
+        self.most_threatened(n)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "ConjunctionGraph({} nodes, {} edges, {} components)",
+            self.node_count(),
+            self.edge_count(),
+            self.connected_components().len()
+        )
+    }
+}
+
+/// Build the conjunction graph of a screening result
+#[cfg(feature = "python")]
+#[pyfunction]
+pub fn conjunction_graph(conjunctions: Vec<Conjunction>) -> ConjunctionGraph {
+    ConjunctionGraph::from_conjunctions(&conjunctions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nalgebra::Vector3;
+
+    fn edge(id1: i32, id2: i32, distance: f64) -> Conjunction {
+        let (r, v) = (Vector3::new(7000.0, 0.0, 0.0), Vector3::new(0.0, 7.5, 0.0));
+        Conjunction::from_states(id1, id2, (&r, &v), (&(r + Vector3::new(distance, 0.0, 0.0)), &v), None)
+    }
+
+    #[test]
+    fn test_components_and_degree() {
+        // a star around 1, a repeated pair 10-11, and an isolated 20-21
+        let events = [edge(1, 2, 1.0), edge(1, 3, 2.0), edge(3, 4, 0.5), edge(10, 11, 3.0), edge(11, 10, 0.2), edge(21, 20, 4.0)];
+        let graph = ConjunctionGraph::from_conjunctions(&events);
+        assert_eq!(graph.connected_components(), vec![vec![1, 2, 3, 4], vec![10, 11], vec![20, 21]]);
+        assert_eq!(graph.edge_count(), 5);
+        // 1 and 3 both have two partners and two events; 3 has the closer miss
+        let top = graph.most_threatened(2);
+        assert_eq!((top[0].norad_id, top[0].degree), (3, 2));
+        assert_eq!((top[1].norad_id, top[1].degree), (1, 2));
+        let repeated = graph.threatened(10);
+        assert_eq!((repeated.degree, repeated.events), (1, 2));
+        assert!((repeated.min_distance_km - 0.2).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,525 @@
+//! Ground station visibility and pass prediction
+//!
+//! Look angles are computed in the station's topocentric ENU frame on the
+//! WGS-84 ellipsoid, from object states treated as TEME. Passes are found by
+//! sampling the elevation on a fixed grid, refining rise/set crossings with
+//! Brent's method and the culmination with a golden-section search.
+//!
+//! Contact tables add range-rate (Earth-fixed, so it includes the station's
+//! rotation) and the first-order Doppler shift -f * range_rate / c of a
+//! downlink carrier, for frequency tracking.
+
+use nalgebra::Vector3;
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+#[cfg(feature = "python")]
+use pyo3::types::PyDict;
+
+#[cfg(feature = "python")]
+use crate::columnar::Column;
+use crate::constants::{PROPAGATOR_INITIAL_STEP_S, SECONDS_PER_DAY, SPEED_OF_LIGHT_KM_S};
+use crate::frames;
+use crate::error::OrbitError;
+use crate::ground_track::{sample_states, TrackTarget};
+use crate::propagation::{ForceModel, Integrator};
+use crate::screening::{brent, Trajectory};
+use crate::sgp4::Sgp4Propagator;
+use crate::tle;
+#[cfg(feature = "python")]
+use crate::Satellite;
+
+/// Time tolerance for AOS/LOS and culmination refinement (seconds)
+const TIME_TOLERANCE_S: f64 = 1.0e-2;
+
+/// Azimuth (deg, from north through east), elevation (deg) and range (km)
+pub type LookAngles = (f64, f64, f64);
+
+/// Fixed observer on the WGS-84 ellipsoid
+#[cfg_attr(feature = "python", pyclass)]
+#[derive(Clone, Debug)]
+pub struct GroundStation {
+    pub lat_deg: f64,
+    pub lon_deg: f64,
+    pub alt_km: f64,
+    pub name: Option<String>,
+    ecef: Vector3<f64>,
+    enu: [Vector3<f64>; 3], // east, north, up unit vectors in ECEF
+}
+
+/// Per-step look angles, range, range-rate and Doppler shift of a contact
+#[derive(Clone, Debug, Default)]
+pub struct ContactTable {
+    pub jd: Vec<f64>,
+    pub azimuth_deg: Vec<f64>,
+    pub elevation_deg: Vec<f64>,
+    pub range_km: Vec<f64>,
+    pub range_rate_km_s: Vec<f64>,
+    pub doppler_hz: Option<Vec<f64>>, // received minus transmitted, when a frequency is given
+}
+
+impl GroundStation {
+    pub fn from_geodetic(lat_deg: f64, lon_deg: f64, alt_km: f64) -> Self {
+        let (lat, lon) = (lat_deg.to_radians(), lon_deg.to_radians());
+        let (sin_lat, cos_lat) = lat.sin_cos();
+        let (sin_lon, cos_lon) = lon.sin_cos();
+        GroundStation {
+            lat_deg,
+            lon_deg,
+            alt_km,
+            name: None,
+            ecef: frames::geodetic_to_ecef_rad(lat, lon, alt_km),
+            enu: [
+                Vector3::new(-sin_lon, cos_lon, 0.0),
+                Vector3::new(-sin_lat * cos_lon, -sin_lat * sin_lon, cos_lat),
+                Vector3::new(cos_lat * cos_lon, cos_lat * sin_lon, sin_lat),
+            ],
+        }
+    }
+
+    /// Station position in ECEF (km)
+    pub fn ecef_km(&self) -> Vector3<f64> {
+        self.ecef
+    }
+
+    /// East, north and up unit vectors in ECEF
+    pub fn enu_axes(&self) -> [Vector3<f64>; 3] {
+        self.enu
+    }
+
+    /// Look angles to a TEME position at Julian date `jd`
+    pub fn look_angles_teme(&self, position: &Vector3<f64>, jd: f64) -> LookAngles {
+        let (ecef, _) = frames::teme_to_ecef_state(position, &Vector3::zeros(), jd, 0.0, 0.0);
+        let rho = ecef - self.ecef;
+        let range = rho.norm();
+        let [east, north, up] = &self.enu;
+        let azimuth = rho.dot(east).atan2(rho.dot(north)).to_degrees().rem_euclid(360.0);
+        let elevation = (rho.dot(up) / range).clamp(-1.0, 1.0).asin().to_degrees();
+        (azimuth, elevation, range)
+    }
+
+    /// Range (km) and range-rate (km/s, positive when receding) to a TEME state at `jd`
+    pub fn range_and_rate_teme(&self, position: &Vector3<f64>, velocity: &Vector3<f64>, jd: f64) -> (f64, f64) {
+        let (ecef, ecef_velocity) = frames::teme_to_ecef_state(position, velocity, jd, 0.0, 0.0);
+        let rho = ecef - self.ecef;
+        let range = rho.norm();
+        (range, rho.dot(&ecef_velocity) / range)
+    }
+
+    /// Look angles of a trajectory at seconds after `start_jd`
+    fn look_at(&self, target: &Trajectory, start_jd: f64, seconds: f64) -> Option<LookAngles> {
+        let (r, _) = target.state_at(start_jd, seconds)?;
+        Some(self.look_angles_teme(&r, start_jd + seconds / SECONDS_PER_DAY))
+    }
+
+    /// Visibility passes of `target` over [start_jd, end_jd]
+    pub fn find_passes(
+        &self,
+        target: &Trajectory,
+        start_jd: f64,
+        end_jd: f64,
+        min_elevation_deg: f64,
+        step: f64,
+    ) -> Vec<Pass> {
+        let duration = (end_jd - start_jd) * SECONDS_PER_DAY;
+        let n_steps = (duration / step).ceil() as usize;
+        let times: Vec<f64> = (0..=n_steps).map(|k| (k as f64 * step).min(duration)).collect();
+        let margin: Vec<Option<f64>> = times
+            .iter()
+            .map(|&t| self.look_at(target, start_jd, t).map(|(_, el, _)| el - min_elevation_deg))
+            .collect();
+
+        let crossing = |a: usize, fa: f64, fb: f64| {
+            let f = |t: f64| self.look_at(target, start_jd, t).map(|(_, el, _)| el - min_elevation_deg);
+            brent(f, times[a], times[a + 1], fa, fb, TIME_TOLERANCE_S).unwrap_or(times[a])
+        };
+
+        // (rise, set) intervals in seconds from start
+        let mut intervals = Vec::new();
+        let mut rise = match margin.first() {
+            Some(Some(m)) if *m >= 0.0 => Some(0.0),
+            _ => None,
+        };
+        for k in 0..times.len() - 1 {
+            let (Some(fa), Some(fb)) = (margin[k], margin[k + 1]) else {
+                // propagation gap: close any open pass at the last good sample
+                if let Some(r) = rise.take() {
+                    intervals.push((r, times[k]));
+                }
+                continue;
+            };
+            if fa < 0.0 && fb >= 0.0 {
+                rise = Some(crossing(k, fa, fb));
+            } else if fa >= 0.0 && fb < 0.0 {
+                if let Some(r) = rise.take() {
+                    intervals.push((r, crossing(k, fa, fb)));
+                }
+            } else if fa >= 0.0 && rise.is_none() {
+                rise = Some(times[k]);
+            }
+        }
+        if let Some(r) = rise {
+            intervals.push((r, duration));
+        }
+
+        intervals
+            .into_iter()
+            .filter(|(aos, los)| los > aos)
+            .filter_map(|(aos, los)| self.build_pass(target, start_jd, aos, los, step))
+            .collect()
+    }
+
+    /// Contact table of `target` between two Julian dates, sampled every `step` seconds
+    ///
+    /// With `min_elevation_deg` only the steps in view are kept; steps where
+    /// propagation fails are NaN.
+    pub fn contact_table(
+        &self,
+        target: TrackTarget,
+        start_jd: f64,
+        end_jd: f64,
+        step: f64,
+        frequency_hz: Option<f64>,
+        min_elevation_deg: Option<f64>,
+    ) -> Result<ContactTable, OrbitError> {
+        if end_jd <= start_jd {
+            return Err(OrbitError::InvalidInput("end must be after start".to_string()));
+        }
+        if step <= 0.0 {
+            return Err(OrbitError::InvalidInput("step must be positive (seconds)".to_string()));
+        }
+        let duration = (end_jd - start_jd) * SECONDS_PER_DAY;
+        let n_steps = (duration / step).ceil() as usize;
+        let times: Vec<f64> = (0..=n_steps).map(|k| (k as f64 * step).min(duration)).collect();
+        let integrator = Integrator::DormandPrince { initial_step: PROPAGATOR_INITIAL_STEP_S };
+        let states = sample_states(target, start_jd, &times, ForceModel::J2, integrator)?;
+
+        let mut table = ContactTable { doppler_hz: frequency_hz.map(|_| Vec::new()), ..Default::default() };
+        for (state, &t) in states.iter().zip(&times) {
+            let jd = start_jd + t / SECONDS_PER_DAY;
+            let row = match state {
+                Some((r, v)) => {
+                    let (azimuth, elevation, _) = self.look_angles_teme(r, jd);
+                    let (range, rate) = self.range_and_rate_teme(r, v, jd);
+                    [azimuth, elevation, range, rate]
+                }
+                None => [f64::NAN; 4],
+            };
+            if min_elevation_deg.is_some_and(|min| row[1].is_nan() || row[1] < min) {
+                continue;
+            }
+            table.jd.push(jd);
+            table.azimuth_deg.push(row[0]);
+            table.elevation_deg.push(row[1]);
+            table.range_km.push(row[2]);
+            table.range_rate_km_s.push(row[3]);
+            if let (Some(doppler), Some(f)) = (table.doppler_hz.as_mut(), frequency_hz) {
+                doppler.push(-f * row[3] / SPEED_OF_LIGHT_KM_S);
+            }
+        }
+        Ok(table)
+    }
+
+    fn build_pass(&self, target: &Trajectory, start_jd: f64, aos: f64, los: f64, step: f64) -> Option<Pass> {
+        let elevation = |t: f64| self.look_at(target, start_jd, t).map_or(f64::NEG_INFINITY, |(_, el, _)| el);
+
+        // golden-section search for the culmination, seeded by the best sample
+        let mut samples: Vec<f64> = (0..)
+            .map(|k| aos + k as f64 * step)
+            .take_while(|&t| t < los)
+            .collect();
+        samples.push(los);
+        let best = samples
+            .iter()
+            .copied()
+            .max_by(|a, b| elevation(*a).total_cmp(&elevation(*b)))?;
+        let (mut lo, mut hi) = ((best - step).max(aos), (best + step).min(los));
+        let ratio = (5f64.sqrt() - 1.0) / 2.0;
+        while hi - lo > TIME_TOLERANCE_S {
+            let (m1, m2) = (hi - ratio * (hi - lo), lo + ratio * (hi - lo));
+            if elevation(m1) < elevation(m2) {
+                lo = m1;
+            } else {
+                hi = m2;
+            }
+        }
+        let culmination = 0.5 * (lo + hi);
+
+        let mut profile_times = samples;
+        profile_times.push(culmination);
+        profile_times.sort_by(f64::total_cmp);
+        let profile: Vec<(f64, f64, f64, f64)> = profile_times
+            .iter()
+            .filter_map(|&t| {
+                let (az, el, range) = self.look_at(target, start_jd, t)?;
+                Some((start_jd + t / SECONDS_PER_DAY, az, el, range))
+            })
+            .collect();
+
+        let (aos_az, _, _) = self.look_at(target, start_jd, aos)?;
+        let (los_az, _, _) = self.look_at(target, start_jd, los)?;
+        let (_, max_el, _) = self.look_at(target, start_jd, culmination)?;
+
+        Some(Pass {
+            norad_id: target.norad_id(),
+            aos_jd: start_jd + aos / SECONDS_PER_DAY,
+            los_jd: start_jd + los / SECONDS_PER_DAY,
+            max_elevation_jd: start_jd + culmination / SECONDS_PER_DAY,
+            max_elevation_deg: max_el,
+            aos_azimuth_deg: aos_az,
+            los_azimuth_deg: los_az,
+            profile,
+        })
+    }
+}
+
+/// Object to predict passes for: a propagator, an ephemeris or a (line1, line2) TLE
+#[cfg_attr(feature = "python", derive(FromPyObject))]
+#[allow(clippy::large_enum_variant)]
+pub enum PassTarget {
+    Trajectory(Trajectory),
+    Tle(String, String),
+}
+
+impl PassTarget {
+    pub fn into_trajectory(self) -> Result<Trajectory, OrbitError> {
+        match self {
+            PassTarget::Trajectory(trajectory) => Ok(trajectory),
+            PassTarget::Tle(line1, line2) => {
+                let elements = tle::parse_lines(&line1, &line2, true).map_err(OrbitError::TleParse)?;
+                Ok(Sgp4Propagator::from_mean_elements(elements).into())
+            }
+        }
+    }
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl GroundStation {
+    /// Station at geodetic latitude/longitude (degrees) and altitude (km)
+    #[new]
+    #[pyo3(signature = (lat, lon, alt = 0.0, name = None))]
+    fn new(lat: f64, lon: f64, alt: f64, name: Option<String>) -> PyResult<Self> {
+        if !(-90.0..=90.0).contains(&lat) {
+            return Err(pyo3::exceptions::PyValueError::new_err("Latitude must be in [-90, 90] degrees"));
+        }
+        let mut station = GroundStation::from_geodetic(lat, lon, alt);
+        station.name = name;
+        Ok(station)
+    }
+
+    #[getter]
+    fn lat_deg(&self) -> f64 {
+        self.lat_deg
+    }
+
+    #[getter]
+    fn lon_deg(&self) -> f64 {
+        self.lon_deg
+    }
+
+    #[getter]
+    fn alt_km(&self) -> f64 {
+        self.alt_km
+    }
+
+    #[getter]
+    fn name(&self) -> Option<String> {
+        self.name.clone()
+    }
+
+    #[setter]
+    fn set_name(&mut self, name: Option<String>) {
+        self.name = name;
+    }
+
+    /// Station position in ECEF (km)
+    #[getter]
+    fn ecef(&self) -> Vec<f64> {
+        self.ecef.as_slice().to_vec()
+    }
+
+    /// (azimuth_deg, elevation_deg, range_km) of a satellite state (TEME) at `jd`
+    ///
+    /// Uses the satellite's epoch when `jd` is omitted.
+    #[pyo3(signature = (satellite, jd = None))]
+    fn look_angles(&self, satellite: &Satellite, jd: Option<f64>) -> PyResult<LookAngles> {
+        let jd = jd.or_else(|| satellite.epoch.map(|e| e.jd_utc())).ok_or_else(|| {
+            OrbitError::InvalidState("Satellite has no epoch; pass jd explicitly".to_string())
+        })?;
+        Ok(self.look_angles_teme(&satellite.r(), jd))
+    }
+
+    /// Passes above `min_elevation` (deg) between two Julian dates, sampled every `step` seconds
+    #[pyo3(signature = (target, start, end, min_elevation = 10.0, step = 30.0))]
+    fn passes(&self, target: PassTarget, start: f64, end: f64, min_elevation: f64, step: f64) -> PyResult<Vec<Pass>> {
+        if end <= start {
+            return Err(pyo3::exceptions::PyValueError::new_err("end must be after start"));
+        }
+        if step <= 0.0 {
+            return Err(pyo3::exceptions::PyValueError::new_err("step must be positive (seconds)"));
+        }
+        let trajectory = target.into_trajectory()?;
+        Ok(self.find_passes(&trajectory, start, end, min_elevation, step))
+    }
+
+    /// (range_km, range_rate_km_s) of a satellite state (TEME) at `jd`; range-rate is positive when receding
+    #[pyo3(signature = (satellite, jd = None))]
+    fn range_rate(&self, satellite: &Satellite, jd: Option<f64>) -> PyResult<(f64, f64)> {
+        let jd = jd.or_else(|| satellite.epoch.map(|e| e.jd_utc())).ok_or_else(|| {
+            OrbitError::InvalidState("Satellite has no epoch; pass jd explicitly".to_string())
+        })?;
+        Ok(self.range_and_rate_teme(&satellite.r(), &satellite.v(), jd))
+    }
+
+    /// Per-step look angles, range, range-rate and Doppler shift between two Julian dates
+    ///
+    /// `target` is anything `ground_track` accepts. Returns a dict of
+    /// read-only `Column` arrays `jd`, `azimuth_deg`, `elevation_deg`,
+    /// `range_km` and `range_rate_km_s`, plus `doppler_hz` (received minus
+    /// transmitted frequency) when a downlink `frequency_hz` is given. With
+    /// `min_elevation` (deg) only the steps in view are kept; steps where
+    /// propagation fails are NaN.
+    #[pyo3(name = "contact_table", signature = (target, start, end, step = 10.0, frequency_hz = None, min_elevation = None))]
+    #[allow(clippy::too_many_arguments)]
+    fn py_contact_table(
+        &self,
+        py: Python<'_>,
+        target: &PyAny,
+        start: f64,
+        end: f64,
+        step: f64,
+        frequency_hz: Option<f64>,
+        min_elevation: Option<f64>,
+    ) -> PyResult<PyObject> {
+        let target: TrackTarget = target.extract()?;
+        let contacts = py.allow_threads(|| self.contact_table(target, start, end, step, frequency_hz, min_elevation))?;
+
+        let table = PyDict::new(py);
+        let columns = [
+            ("jd", Some(contacts.jd)),
+            ("azimuth_deg", Some(contacts.azimuth_deg)),
+            ("elevation_deg", Some(contacts.elevation_deg)),
+            ("range_km", Some(contacts.range_km)),
+            ("range_rate_km_s", Some(contacts.range_rate_km_s)),
+            ("doppler_hz", contacts.doppler_hz),
+        ];
+        for (name, values) in columns {
+            if let Some(values) = values {
+                table.set_item(name, Py::new(py, Column::float64(values))?)?;
+            }
+        }
+        Ok(table.into())
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "GroundStation({}lat={:.4}, lon={:.4}, alt={:.3}km)",
+            self.name.as_ref().map_or(String::new(), |n| format!("{:?}, ", n)),
+            self.lat_deg,
+            self.lon_deg,
+            self.alt_km
+        )
+    }
+}
+
+/// One visibility pass over a ground station
+#[cfg_attr(feature = "python", pyclass(get_all))]
+#[derive(Clone, Debug)]
+pub struct Pass {
+    pub norad_id: i32,
+    pub aos_jd: f64, // acquisition of signal (or window start if already visible)
+    pub los_jd: f64, // loss of signal (or window end if still visible)
+    pub max_elevation_jd: f64,
+    pub max_elevation_deg: f64,
+    pub aos_azimuth_deg: f64,
+    pub los_azimuth_deg: f64,
+    pub profile: Vec<(f64, f64, f64, f64)>, // (jd, azimuth_deg, elevation_deg, range_km)
+}
+
+impl Pass {
+    /// Pass duration in seconds
+    pub fn duration(&self) -> f64 {
+        (self.los_jd - self.aos_jd) * SECONDS_PER_DAY
+    }
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl Pass {
+    /// Pass duration in seconds
+    #[getter]
+    #[pyo3(name = "duration")]
+    fn py_duration(&self) -> f64 {
+        self.duration()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Pass(norad_id={}, aos_jd={:.6}, los_jd={:.6}, max_el={:.1}deg)",
+            self.norad_id, self.aos_jd, self.los_jd, self.max_elevation_deg
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sgp4::MeanElements;
+
+    #[test]
+    fn test_overhead_look_angles() {
+        let station = GroundStation::from_geodetic(41.0, 29.0, 0.1);
+        let jd = 2460000.5;
+        let overhead = frames::geodetic_to_ecef_rad(41f64.to_radians(), 29f64.to_radians(), 500.0);
+        let (teme, _) = frames::ecef_to_teme_state(&overhead, &Vector3::zeros(), jd, 0.0, 0.0);
+
+        let (_, el, range) = station.look_angles_teme(&teme, jd);
+        assert!((el - 90.0).abs() < 1e-6);
+        assert!((range - 499.9).abs() < 1e-6);
+
+        // due north along the surface: azimuth 0
+        let north = frames::geodetic_to_ecef_rad(43f64.to_radians(), 29f64.to_radians(), 500.0);
+        let (teme, _) = frames::ecef_to_teme_state(&north, &Vector3::zeros(), jd, 0.0, 0.0);
+        let (az, el, _) = station.look_angles_teme(&teme, jd);
+        assert!(az.min(360.0 - az) < 1e-6, "{}", az);
+        assert!(el > 0.0);
+    }
+
+    #[test]
+    fn test_range_rate_is_earth_fixed() {
+        // an object hovering over the station in ECEF has zero range-rate, though it moves in TEME
+        let station = GroundStation::from_geodetic(0.0, 30.0, 0.0);
+        let jd = 2460000.5;
+        let up = station.enu[2];
+        let above = station.ecef + up * 1000.0;
+        for (velocity_ecef, expected) in [(Vector3::zeros(), 0.0), (up * 1.5, 1.5), (station.enu[0] * 7.0, 0.0)] {
+            let (r, v) = frames::ecef_to_teme_state(&above, &velocity_ecef, jd, 0.0, 0.0);
+            assert!(v.norm() > 0.4);
+            let (range, rate) = station.range_and_rate_teme(&r, &v, jd);
+            assert!((range - 1000.0).abs() < 1e-6);
+            assert!((rate - expected).abs() < 1e-9, "{} vs {}", rate, expected);
+        }
+    }
+
+    #[test]
+    fn test_polar_orbit_passes() {
+        let elements = MeanElements::new(1, 2460000.5, 0.0, 98.0, 30.0, 0.001, 0.0, 0.0, 14.5).unwrap();
+        let target: Trajectory = Sgp4Propagator::from_mean_elements(elements).into();
+        let station = GroundStation::from_geodetic(70.0, 20.0, 0.0);
+
+        let passes = station.find_passes(&target, 2460000.5, 2460001.5, 10.0, 30.0);
+        assert!(passes.len() >= 3, "{} passes", passes.len());
+        for pass in &passes {
+            assert!(pass.aos_jd < pass.max_elevation_jd && pass.max_elevation_jd < pass.los_jd);
+            assert!(pass.max_elevation_deg >= 10.0);
+            let duration = (pass.los_jd - pass.aos_jd) * SECONDS_PER_DAY;
+            assert!(duration < 1200.0, "{}", duration);
+
+            // the rise and set are refined onto the elevation mask
+            let aos_s = (pass.aos_jd - 2460000.5) * SECONDS_PER_DAY;
+            let (_, el, _) = station.look_at(&target, 2460000.5, aos_s).unwrap();
+            assert!((el - 10.0).abs() < 0.05, "{}", el);
+            assert!(pass.profile.iter().all(|p| p.2 <= pass.max_elevation_deg + 1e-6));
+        }
+    }
+}
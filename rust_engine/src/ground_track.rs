@@ -0,0 +1,148 @@
+//! Ground track sampling
+//!
+//! Samples an object's sub-satellite point on a fixed time grid: states
+//! (TEME, or the inertial frame of a state vector) are rotated to ECEF and
+//! converted to WGS-84 geodetic coordinates. State vectors are propagated
+//! numerically under J2 from their own epoch.
+
+use nalgebra::{Vector3, Vector6};
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+#[cfg(feature = "python")]
+use pyo3::types::PyDict;
+
+#[cfg(feature = "python")]
+use crate::columnar::Column;
+use crate::constants::{PROPAGATOR_INITIAL_STEP_S, SECONDS_PER_DAY};
+use crate::frames;
+use crate::error::OrbitError;
+use crate::ground_station::PassTarget;
+use crate::propagation::{integrate, ForceModel, Integrator};
+use crate::Satellite;
+
+type State = (Vector3<f64>, Vector3<f64>);
+
+/// Object to sample: a propagator, an ephemeris, a (line1, line2) TLE or a state vector
+#[cfg_attr(feature = "python", derive(FromPyObject))]
+#[allow(clippy::large_enum_variant)]
+pub enum TrackTarget {
+    Target(PassTarget),
+    Satellite(Satellite),
+}
+
+/// Geodetic (latitude deg, longitude deg, altitude km) of a TEME position at `jd`
+pub fn sub_satellite_point(position: &Vector3<f64>, jd: f64) -> (f64, f64, f64) {
+    let (ecef, _) = frames::teme_to_ecef_state(position, &Vector3::zeros(), jd, 0.0, 0.0);
+    let (lat, lon, alt) = frames::ecef_to_geodetic_rad(&ecef);
+    (lat.to_degrees(), lon.to_degrees(), alt)
+}
+
+/// States (position, velocity) at `times` seconds after `start_jd`, None where propagation fails
+///
+/// A state vector is integrated from its own epoch to every sample, so each
+/// state is independent of which other times were requested.
+pub fn sample_states(
+    target: TrackTarget,
+    start_jd: f64,
+    times: &[f64],
+    model: ForceModel,
+    integrator: Integrator,
+) -> Result<Vec<Option<State>>, OrbitError> {
+    match target {
+        TrackTarget::Target(target) => {
+            let trajectory = target.into_trajectory()?;
+            Ok(times.iter().map(|&t| trajectory.state_at(start_jd, t)).collect())
+        }
+        TrackTarget::Satellite(sat) => {
+            let epoch = sat.epoch.ok_or_else(|| {
+                OrbitError::InvalidState(format!("Satellite {} has no epoch; sampling it needs one", sat.norad_id))
+            })?;
+            let p = &sat.position;
+            let v = &sat.velocity;
+            let initial = Vector6::new(p[0], p[1], p[2], v[0], v[1], v[2]);
+            let offset = (start_jd - epoch.jd_utc()) * SECONDS_PER_DAY;
+            Ok(times
+                .iter()
+                .map(|&t| {
+                    let dt = offset + t;
+                    let state = if dt == 0.0 { Some(initial) } else { integrate(&initial, dt, model, integrator) };
+                    state.map(|y| (Vector3::new(y[0], y[1], y[2]), Vector3::new(y[3], y[4], y[5])))
+                })
+                .collect())
+        }
+    }
+}
+
+/// Sub-satellite points sampled on a time grid
+#[derive(Clone, Debug, Default)]
+pub struct GroundTrack {
+    pub jd: Vec<f64>,
+    pub lat_deg: Vec<f64>,
+    pub lon_deg: Vec<f64>, // in (-180, 180]
+    pub alt_km: Vec<f64>,
+}
+
+/// Sub-satellite track between two Julian dates (UTC), sampled every `step` seconds
+///
+/// The end date is the last sample; samples where propagation fails are NaN.
+pub fn ground_track(target: TrackTarget, start: f64, end: f64, step: f64) -> Result<GroundTrack, OrbitError> {
+    if end <= start {
+        return Err(OrbitError::InvalidInput("end must be after start".to_string()));
+    }
+    if step <= 0.0 {
+        return Err(OrbitError::InvalidInput("step must be positive (seconds)".to_string()));
+    }
+    let duration = (end - start) * SECONDS_PER_DAY;
+    let n_steps = (duration / step).ceil() as usize;
+    let times: Vec<f64> = (0..=n_steps).map(|k| (k as f64 * step).min(duration)).collect();
+    let integrator = Integrator::DormandPrince { initial_step: PROPAGATOR_INITIAL_STEP_S };
+    let states = sample_states(target, start, &times, ForceModel::J2, integrator)?;
+
+    let jd: Vec<f64> = times.iter().map(|t| start + t / SECONDS_PER_DAY).collect();
+    let mut track = GroundTrack::default();
+    for (state, &date) in states.iter().zip(&jd) {
+        let (la, lo, al) = state.map_or((f64::NAN, f64::NAN, f64::NAN), |(r, _)| sub_satellite_point(&r, date));
+        track.lat_deg.push(la);
+        track.lon_deg.push(lo);
+        track.alt_km.push(al);
+    }
+    track.jd = jd;
+    Ok(track)
+}
+
+/// Sub-satellite track between two Julian dates (UTC), sampled every `step` seconds
+///
+/// `target` is an `Sgp4Propagator`, an `Ephemeris`, a `(line1, line2)` TLE
+/// or a `Satellite` with an epoch. Returns a dict of read-only `Column`
+/// arrays `jd`, `lat_deg`, `lon_deg` (in (-180, 180]) and `alt_km`, with
+/// the end date as the last sample; samples where propagation fails are NaN.
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(name = "ground_track", signature = (target, start, end, step = 60.0))]
+pub fn py_ground_track(py: Python<'_>, target: &PyAny, start: f64, end: f64, step: f64) -> PyResult<PyObject> {
+    let target: TrackTarget = target.extract()?;
+    let track = py.allow_threads(|| ground_track(target, start, end, step))?;
+
+    let table = PyDict::new(py);
+    let columns = [("jd", track.jd), ("lat_deg", track.lat_deg), ("lon_deg", track.lon_deg), ("alt_km", track.alt_km)];
+    for (name, values) in columns {
+        table.set_item(name, Py::new(py, Column::float64(values))?)?;
+    }
+    Ok(table.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sub_satellite_point_of_polar_position() {
+        let (lat, _, alt) = sub_satellite_point(&Vector3::new(0.0, 0.0, 7000.0), 2460000.5);
+        assert!((lat - 90.0).abs() < 1e-9);
+        assert!((alt - (7000.0 - 6356.752314245)).abs() < 1e-6);
+        // an equatorial point keeps zero latitude and its altitude whatever the Earth angle
+        let (lat, lon, alt) = sub_satellite_point(&Vector3::new(7000.0, 0.0, 0.0), 2460000.5);
+        assert!(lat.abs() < 1e-9 && (alt - (7000.0 - 6378.137)).abs() < 1e-6);
+        assert!((-180.0..=180.0).contains(&lon));
+    }
+}
@@ -0,0 +1,344 @@
+//! TLE catalogue hygiene checks
+//!
+//! Before a catalogue feeds screening it is worth knowing which element
+//! sets should not be trusted:
+//! * stale: epoch more than `max_age_days` before the reference time
+//! * outlier: mean elements outside the physical range (perigee below
+//!   `MIN_PERIGEE_ALTITUDE_KM`, eccentricity or inclination out of range),
+//!   or mean motion/inclination far from the rest of the same object's sets
+//! * duplicate: one NORAD id carrying sets with different epochs, or
+//!   different elements at the same epoch
+//! * bad BSTAR: non-finite, larger than `max_abs_bstar`, or clearly negative
+//!
+//! Checks only report; nothing is dropped, so callers decide what to do
+//! with each finding.
+
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use std::collections::BTreeMap;
+use std::f64::consts::PI;
+
+use crate::constants::{EARTH_RADIUS_KM, MU_EARTH, SECONDS_PER_DAY};
+use crate::robust;
+use crate::sgp4::MeanElements;
+
+/// Lowest plausible perigee altitude of an orbiting object (km)
+const MIN_PERIGEE_ALTITUDE_KM: f64 = 80.0;
+
+/// BSTAR below minus this (1/earth radii) is more than fit noise
+const NEGATIVE_BSTAR_TOLERANCE: f64 = 1.0e-4;
+
+/// Fewest sets of one object before its own spread is used for outliers
+const MIN_OUTLIER_SETS: usize = 5;
+
+/// Category of a hygiene finding
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TleIssueKind {
+    Stale,
+    Outlier,
+    Duplicate,
+    BadBstar,
+}
+
+impl TleIssueKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TleIssueKind::Stale => "stale",
+            TleIssueKind::Outlier => "outlier",
+            TleIssueKind::Duplicate => "duplicate",
+            TleIssueKind::BadBstar => "bad_bstar",
+        }
+    }
+}
+
+/// One finding against one element set
+#[derive(Clone, Debug, PartialEq)]
+pub struct TleIssue {
+    pub norad_id: i32,
+    pub kind: TleIssueKind,
+    pub epoch_jd: f64,
+    pub detail: String,
+}
+
+/// Limits for `check_catalog`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HygieneSettings {
+    /// Oldest acceptable epoch, in days before the reference time
+    pub max_age_days: f64,
+    /// Robust sigmas from an object's own sets that make an outlier
+    pub outlier_sigmas: f64,
+    /// Largest plausible |BSTAR| (1/earth radii)
+    pub max_abs_bstar: f64,
+}
+
+impl Default for HygieneSettings {
+    fn default() -> Self {
+        HygieneSettings { max_age_days: 30.0, outlier_sigmas: 5.0, max_abs_bstar: 0.1 }
+    }
+}
+
+/// Findings over a catalogue, in input order within each check
+#[cfg_attr(feature = "python", pyclass)]
+#[derive(Clone, Debug, Default)]
+pub struct TleHygieneReport {
+    pub checked: usize,
+    pub reference_jd: f64,
+    pub issues: Vec<TleIssue>,
+}
+
+impl TleHygieneReport {
+    /// NORAD ids with at least one issue of `kind`, ascending
+    pub fn ids(&self, kind: TleIssueKind) -> Vec<i32> {
+        let mut ids: Vec<i32> = self.issues.iter().filter(|i| i.kind == kind).map(|i| i.norad_id).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        ids
+    }
+}
+
+/// Why mean elements are physically implausible, if they are
+fn implausible(el: &MeanElements) -> Option<String> {
+    if !(0.0..=180.0).contains(&el.inclination_deg) {
+        return Some(format!("inclination {:.4} deg outside [0, 180]", el.inclination_deg));
+    }
+    if !(0.0..1.0).contains(&el.eccentricity) || el.mean_motion_rev_per_day.is_nan() || el.mean_motion_rev_per_day <= 0.0 {
+        return Some(format!(
+            "eccentricity {} or mean motion {} rev/day out of range",
+            el.eccentricity, el.mean_motion_rev_per_day
+        ));
+    }
+    let n = el.mean_motion_rev_per_day * 2.0 * PI / SECONDS_PER_DAY;
+    let perigee = (MU_EARTH / (n * n)).cbrt() * (1.0 - el.eccentricity) - EARTH_RADIUS_KM;
+    (perigee < MIN_PERIGEE_ALTITUDE_KM).then(|| format!("perigee altitude {:.1} km", perigee))
+}
+
+/// Why BSTAR is implausible, if it is
+fn bad_bstar(bstar: f64, max_abs: f64) -> Option<String> {
+    if !bstar.is_finite() || bstar.abs() > max_abs {
+        Some(format!("BSTAR {:e} beyond +/-{:e}", bstar, max_abs))
+    } else if bstar < -NEGATIVE_BSTAR_TOLERANCE {
+        Some(format!("negative BSTAR {:e}", bstar))
+    } else {
+        None
+    }
+}
+
+/// Indices of `values` more than `sigmas` robust deviations from their median
+fn robust_outliers(values: &[f64], sigmas: f64) -> Vec<usize> {
+    let (centre, spread) = robust::median_and_sigma(values);
+    if spread == 0.0 {
+        return Vec::new();
+    }
+    (0..values.len()).filter(|&k| (values[k] - centre).abs() > sigmas * spread).collect()
+}
+
+/// Run every hygiene check over `elements`
+///
+/// `reference_jd` is the time ages are measured from; by default the
+/// newest epoch in the catalogue.
+pub fn check_catalog(elements: &[MeanElements], reference_jd: Option<f64>, settings: &HygieneSettings) -> TleHygieneReport {
+    let reference_jd =
+        reference_jd.unwrap_or_else(|| elements.iter().map(|el| el.epoch_jd).fold(f64::NEG_INFINITY, f64::max));
+    let mut issues = Vec::new();
+    let mut issue = |el: &MeanElements, kind: TleIssueKind, detail: String| {
+        issues.push(TleIssue { norad_id: el.norad_id, kind, epoch_jd: el.epoch_jd, detail })
+    };
+
+    for el in elements {
+        let age = reference_jd - el.epoch_jd;
+        if age > settings.max_age_days {
+            issue(el, TleIssueKind::Stale, format!("epoch {:.1} days old", age));
+        }
+    }
+    for el in elements {
+        if let Some(detail) = implausible(el) {
+            issue(el, TleIssueKind::Outlier, detail);
+        }
+    }
+
+    let mut by_id: BTreeMap<i32, Vec<&MeanElements>> = BTreeMap::new();
+    for el in elements {
+        by_id.entry(el.norad_id).or_default().push(el);
+    }
+    for sets in by_id.values().filter(|sets| sets.len() >= MIN_OUTLIER_SETS) {
+        let plausible: Vec<&MeanElements> = sets.iter().copied().filter(|el| implausible(el).is_none()).collect();
+        if plausible.len() < MIN_OUTLIER_SETS {
+            continue;
+        }
+        let motions: Vec<f64> = plausible.iter().map(|el| el.mean_motion_rev_per_day).collect();
+        let inclinations: Vec<f64> = plausible.iter().map(|el| el.inclination_deg).collect();
+        let mut flagged = robust_outliers(&motions, settings.outlier_sigmas);
+        flagged.extend(robust_outliers(&inclinations, settings.outlier_sigmas));
+        flagged.sort_unstable();
+        flagged.dedup();
+        for k in flagged {
+            let el = plausible[k];
+            let detail = format!(
+                "mean motion {:.8} rev/day, inclination {:.4} deg inconsistent with the object's other sets",
+                el.mean_motion_rev_per_day, el.inclination_deg
+            );
+            issue(el, TleIssueKind::Outlier, detail);
+        }
+    }
+
+    for sets in by_id.values().filter(|sets| sets.len() > 1) {
+        let first = sets[0];
+        if sets.iter().all(|el| *el == first) {
+            continue; // verbatim copies agree with each other
+        }
+        let (oldest, newest) = sets
+            .iter()
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), el| (lo.min(el.epoch_jd), hi.max(el.epoch_jd)));
+        let newest_set = sets.iter().rev().find(|el| el.epoch_jd == newest).copied().unwrap_or(first);
+        let detail = if oldest == newest {
+            format!("{} different element sets at the same epoch", sets.len())
+        } else {
+            format!("{} element sets with epochs spanning {:.2} days", sets.len(), newest - oldest)
+        };
+        issue(newest_set, TleIssueKind::Duplicate, detail);
+    }
+
+    for el in elements {
+        if let Some(detail) = bad_bstar(el.bstar, settings.max_abs_bstar) {
+            issue(el, TleIssueKind::BadBstar, detail);
+        }
+    }
+
+    TleHygieneReport { checked: elements.len(), reference_jd, issues }
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl TleHygieneReport {
+    /// Number of element sets checked
+    #[getter]
+    fn checked(&self) -> usize {
+        self.checked
+    }
+
+    /// Julian date ages were measured from
+    #[getter]
+    fn reference_jd(&self) -> f64 {
+        self.reference_jd
+    }
+
+    /// All findings as (norad_id, kind, epoch_jd, detail)
+    #[getter]
+    fn issues(&self) -> Vec<(i32, &'static str, f64, String)> {
+        self.issues.iter().map(|i| (i.norad_id, i.kind.name(), i.epoch_jd, i.detail.clone())).collect()
+    }
+
+    /// NORAD ids with a stale element set
+    #[getter]
+    fn stale(&self) -> Vec<i32> {
+        self.ids(TleIssueKind::Stale)
+    }
+
+    /// NORAD ids with implausible or inconsistent mean elements
+    #[getter]
+    fn outliers(&self) -> Vec<i32> {
+        self.ids(TleIssueKind::Outlier)
+    }
+
+    /// NORAD ids carrying conflicting element sets
+    #[getter]
+    fn duplicates(&self) -> Vec<i32> {
+        self.ids(TleIssueKind::Duplicate)
+    }
+
+    /// NORAD ids with an implausible BSTAR
+    #[getter]
+    fn bad_bstar(&self) -> Vec<i32> {
+        self.ids(TleIssueKind::BadBstar)
+    }
+
+    /// True when no check found anything
+    #[getter]
+    fn is_clean(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    fn __len__(&self) -> usize {
+        self.issues.len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "TleHygieneReport(checked={}, stale={}, outliers={}, duplicates={}, bad_bstar={})",
+            self.checked,
+            self.stale().len(),
+            self.outliers().len(),
+            self.duplicates().len(),
+            self.bad_bstar().len()
+        )
+    }
+}
+
+/// Check a TLE catalogue for stale, implausible, duplicated and bad-BSTAR element sets
+///
+/// `tles` holds `MeanElements`, `Sgp4Propagator`s or (line1, line2) pairs.
+/// Ages are measured from `reference_jd` (default: the newest epoch).
+/// Returns a `TleHygieneReport`; nothing is printed or removed.
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(signature = (tles, max_age_days = 30.0, reference_jd = None, outlier_sigmas = 5.0, max_abs_bstar = 0.1))]
+pub fn check_tle_catalog(
+    tles: &PyAny,
+    max_age_days: f64,
+    reference_jd: Option<f64>,
+    outlier_sigmas: f64,
+    max_abs_bstar: f64,
+) -> PyResult<TleHygieneReport> {
+    if [max_age_days, outlier_sigmas, max_abs_bstar].iter().any(|x| x.is_nan() || *x <= 0.0) {
+        return Err(pyo3::exceptions::PyValueError::new_err("Limits must be positive"));
+    }
+    let elements = crate::maneuver_detection::extract_elements(tles)?;
+    let settings = HygieneSettings { max_age_days, outlier_sigmas, max_abs_bstar };
+    Ok(check_catalog(&elements, reference_jd, &settings))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn elements(norad_id: i32, epoch_jd: f64, bstar: f64, mean_motion: f64) -> MeanElements {
+        MeanElements::new(norad_id, epoch_jd, bstar, 51.6, 30.0, 0.001, 90.0, 0.0, mean_motion).unwrap()
+    }
+
+    #[test]
+    fn test_each_check_flags_its_case() {
+        let mut catalog = vec![
+            elements(1, 2460000.5, 1e-4, 15.5),
+            elements(2, 2459950.5, 1e-4, 15.5), // 50 days old
+            elements(3, 2460000.5, 1e-4, 17.5), // perigee below 80 km
+            elements(4, 2460000.5, 0.5, 15.5),
+            elements(5, 2460000.5, -0.01, 15.5),
+            elements(6, 2459999.5, 1e-4, 15.5),
+            elements(6, 2460000.5, 1e-4, 15.5),
+            elements(7, 2460000.5, 1e-4, 15.5),
+        ];
+        catalog.push(catalog[7].clone()); // a verbatim copy is not a conflict
+        let report = check_catalog(&catalog, None, &HygieneSettings::default());
+        assert_eq!(report.checked, 9);
+        assert_eq!(report.reference_jd, 2460000.5);
+        assert_eq!(report.ids(TleIssueKind::Stale), vec![2]);
+        assert_eq!(report.ids(TleIssueKind::Outlier), vec![3]);
+        assert_eq!(report.ids(TleIssueKind::Duplicate), vec![6]);
+        assert_eq!(report.ids(TleIssueKind::BadBstar), vec![4, 5]);
+        let duplicate = report.issues.iter().find(|i| i.kind == TleIssueKind::Duplicate).unwrap();
+        assert_eq!(duplicate.epoch_jd, 2460000.5);
+    }
+
+    #[test]
+    fn test_history_outlier() {
+        let mut history: Vec<MeanElements> =
+            (0..8).map(|k| elements(9, 2460000.5 + k as f64, 1e-4, 15.5 + 1e-5 * k as f64)).collect();
+        history[5].mean_motion_rev_per_day = 15.6;
+        let report = check_catalog(&history, Some(2460008.5), &HygieneSettings::default());
+        let outliers: Vec<&TleIssue> = report.issues.iter().filter(|i| i.kind == TleIssueKind::Outlier).collect();
+        assert_eq!(outliers.len(), 1);
+        assert_eq!(outliers[0].epoch_jd, 2460005.5);
+        assert_eq!(report.ids(TleIssueKind::Duplicate), vec![9]);
+        assert!(report.ids(TleIssueKind::Stale).is_empty());
+    }
+}
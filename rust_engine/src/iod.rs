@@ -0,0 +1,477 @@
+//! Initial orbit determination from three positions
+//!
+//! Gibbs' method (Vallado algorithm 54) finds the velocity at the middle of
+//! three coplanar position vectors from geometry alone; it loses accuracy
+//! when the vectors are only a few degrees apart. Herrick-Gibbs (algorithm
+//! 55) uses the observation times instead, a Taylor series that is accurate
+//! precisely for closely spaced observations such as one radar track.
+//! For optical sensors, Gauss' method (Curtis algorithms 5.5 and 5.6) works
+//! from three lines of sight alone: an eighth-degree polynomial gives the
+//! middle range, and the ranges are then refined with exact Lagrange
+//! coefficients. Light time and aberration are neglected.
+//!
+//! Positions are geocentric inertial (km); all are two-body estimates to be
+//! refined by differential correction.
+
+use nalgebra::Vector3;
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+
+use crate::constants::EARTH_RADIUS_KM;
+#[cfg(feature = "python")]
+use crate::constants::{MU_EARTH, SECONDS_PER_DAY};
+#[cfg(feature = "python")]
+use crate::epoch::Epoch;
+#[cfg(feature = "python")]
+use crate::frames::{self, vec3};
+#[cfg(feature = "python")]
+use crate::ground_station::GroundStation;
+use crate::lambert::stumpff;
+#[cfg(feature = "python")]
+use crate::Satellite;
+
+/// Separation (deg) between observations below which Herrick-Gibbs is preferred
+pub const HERRICK_GIBBS_MAX_SEPARATION_DEG: f64 = 3.0;
+
+/// Iteration limit of the Gauss refinement and of Kepler's equation
+const MAX_ITERATIONS: usize = 100;
+
+/// Relative change of the ranges at which the Gauss refinement stops
+const RANGE_RTOL: f64 = 1.0e-10;
+
+fn angle_between(a: &Vector3<f64>, b: &Vector3<f64>) -> f64 {
+    a.cross(b).norm().atan2(a.dot(b))
+}
+
+/// Angle (deg) between `r1` and the plane of `r2` and `r3`
+pub fn coplanarity_deg(r1: &Vector3<f64>, r2: &Vector3<f64>, r3: &Vector3<f64>) -> f64 {
+    let normal = r2.cross(r3);
+    let (n, r) = (normal.norm(), r1.norm());
+    if n == 0.0 || r == 0.0 {
+        return 0.0;
+    }
+    (normal.dot(r1) / (n * r)).clamp(-1.0, 1.0).asin().to_degrees()
+}
+
+fn check_coplanar(r1: &Vector3<f64>, r2: &Vector3<f64>, r3: &Vector3<f64>, tolerance_deg: f64) -> Result<(), String> {
+    let off_plane = coplanarity_deg(r1, r2, r3);
+    if off_plane.abs() > tolerance_deg {
+        return Err(format!(
+            "Positions are not coplanar ({:.3} deg out of plane, tolerance {} deg)",
+            off_plane, tolerance_deg
+        ));
+    }
+    Ok(())
+}
+
+/// Velocity (km/s) at `r2` from three coplanar positions by Gibbs' method
+///
+/// The positions must be in order along the orbit and within
+/// `coplanarity_deg` of one plane.
+pub fn gibbs(
+    r1: &Vector3<f64>,
+    r2: &Vector3<f64>,
+    r3: &Vector3<f64>,
+    mu: f64,
+    coplanarity_deg: f64,
+) -> Result<Vector3<f64>, String> {
+    check_coplanar(r1, r2, r3, coplanarity_deg)?;
+    let (n1, n2, n3) = (r1.norm(), r2.norm(), r3.norm());
+    let (z12, z23, z31) = (r1.cross(r2), r2.cross(r3), r3.cross(r1));
+    let n = n1 * z23 + n2 * z31 + n3 * z12;
+    let d = z12 + z23 + z31;
+    let s = (n2 - n3) * r1 + (n3 - n1) * r2 + (n1 - n2) * r3;
+    let nd = n.dot(&d);
+    if nd.is_nan() || nd <= 0.0 || d.norm() == 0.0 {
+        return Err("Gibbs' method is undefined for these positions (collinear or out of order)".to_string());
+    }
+    let l = (mu / nd).sqrt();
+    Ok(l / n2 * d.cross(r2) + l * s)
+}
+
+/// Velocity (km/s) at `r2` from three closely spaced positions at times `t1 < t2 < t3` (s)
+pub fn herrick_gibbs(
+    r1: &Vector3<f64>,
+    r2: &Vector3<f64>,
+    r3: &Vector3<f64>,
+    times: [f64; 3],
+    mu: f64,
+    coplanarity_deg: f64,
+) -> Result<Vector3<f64>, String> {
+    let [t1, t2, t3] = times;
+    if !(t1 < t2 && t2 < t3) {
+        return Err("Observation times must be strictly increasing".to_string());
+    }
+    check_coplanar(r1, r2, r3, coplanarity_deg)?;
+    let (dt21, dt31, dt32) = (t2 - t1, t3 - t1, t3 - t2);
+    let term = |r: &Vector3<f64>| mu / (12.0 * r.norm().powi(3));
+    Ok(-dt32 * (1.0 / (dt21 * dt31) + term(r1)) * r1
+        + (dt32 - dt21) * (1.0 / (dt21 * dt32) + term(r2)) * r2
+        + dt21 * (1.0 / (dt32 * dt31) + term(r3)) * r3)
+}
+
+/// Velocity at `r2`, by Herrick-Gibbs when the observations are within
+/// `HERRICK_GIBBS_MAX_SEPARATION_DEG` of each other and Gibbs otherwise
+///
+/// Returns the velocity and the name of the method used.
+pub fn initial_orbit(
+    r1: &Vector3<f64>,
+    r2: &Vector3<f64>,
+    r3: &Vector3<f64>,
+    times: [f64; 3],
+    mu: f64,
+    coplanarity_deg: f64,
+) -> Result<(Vector3<f64>, &'static str), String> {
+    let separation = angle_between(r1, r2).max(angle_between(r2, r3)).to_degrees();
+    if separation < HERRICK_GIBBS_MAX_SEPARATION_DEG {
+        Ok((herrick_gibbs(r1, r2, r3, times, mu, coplanarity_deg)?, "herrick-gibbs"))
+    } else {
+        Ok((gibbs(r1, r2, r3, mu, coplanarity_deg)?, "gibbs"))
+    }
+}
+
+/// One angles-only observation
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AnglesObservation {
+    pub time: f64,                   // seconds from any common origin
+    pub line_of_sight: Vector3<f64>, // unit vector from the site towards the object
+    pub site: Vector3<f64>,          // observer position (km), in the same inertial frame
+}
+
+impl AnglesObservation {
+    /// From topocentric right ascension and declination (radians)
+    pub fn from_ra_dec(time: f64, right_ascension: f64, declination: f64, site: Vector3<f64>) -> Self {
+        let (sin_dec, cos_dec) = declination.sin_cos();
+        let (sin_ra, cos_ra) = right_ascension.sin_cos();
+        AnglesObservation { time, line_of_sight: Vector3::new(cos_dec * cos_ra, cos_dec * sin_ra, sin_dec), site }
+    }
+}
+
+/// Lagrange coefficients (f, g) carrying (r0, v0) forward by `dt` seconds on a two-body orbit
+///
+/// Universal-variable Kepler equation (Curtis algorithm 3.3), solved by Newton's method.
+fn lagrange_coefficients(r0: &Vector3<f64>, v0: &Vector3<f64>, dt: f64, mu: f64) -> Option<(f64, f64)> {
+    let (r, sqrt_mu) = (r0.norm(), mu.sqrt());
+    let radial_speed = r0.dot(v0) / r;
+    let alpha = 2.0 / r - v0.norm_squared() / mu;
+    let mut chi = sqrt_mu * alpha.abs() * dt;
+    for _ in 0..MAX_ITERATIONS {
+        let z = alpha * chi * chi;
+        let (c, s) = stumpff(z);
+        let value = r * radial_speed / sqrt_mu * chi * chi * c + (1.0 - alpha * r) * chi.powi(3) * s + r * chi
+            - sqrt_mu * dt;
+        let slope =
+            r * radial_speed / sqrt_mu * chi * (1.0 - z * s) + (1.0 - alpha * r) * chi * chi * c + r;
+        let step = value / slope;
+        chi -= step;
+        if step.abs() <= 1.0e-12 * chi.abs().max(1.0) {
+            let (c, s) = stumpff(alpha * chi * chi);
+            return Some((1.0 - chi * chi / r * c, dt - chi.powi(3) * s / sqrt_mu));
+        }
+    }
+    None
+}
+
+/// Roots above the Earth's surface of x^8 + a x^6 + b x^3 + c
+fn gauss_polynomial_roots(a: f64, b: f64, c: f64) -> Vec<f64> {
+    let poly = |x: f64| ((x * x + a) * x.powi(3) + b) * x.powi(3) + c;
+    let mut roots = Vec::new();
+    let mut x = EARTH_RADIUS_KM;
+    while x < 1.0e6 {
+        let next = x * 1.01;
+        if poly(x).signum() != poly(next).signum() {
+            let (mut low, mut high) = (x, next);
+            for _ in 0..100 {
+                let mid = 0.5 * (low + high);
+                if poly(mid).signum() == poly(low).signum() {
+                    low = mid;
+                } else {
+                    high = mid;
+                }
+            }
+            roots.push(0.5 * (low + high));
+        }
+        x = next;
+    }
+    roots
+}
+
+/// Position and velocity (km, km/s) at the middle of three angles-only observations by Gauss' method
+///
+/// The observations must be in time order and span a small part of the
+/// orbit. When the polynomial has several roots above the Earth that give
+/// positive ranges, the largest is used. With `refine` the ranges are
+/// iterated with exact Lagrange coefficients until they settle.
+pub fn gauss(observations: &[AnglesObservation; 3], mu: f64, refine: bool) -> Result<(Vector3<f64>, Vector3<f64>), String> {
+    let [o1, o2, o3] = observations;
+    if !(o1.time < o2.time && o2.time < o3.time) {
+        return Err("Observation times must be strictly increasing".to_string());
+    }
+    let (rho1, rho2, rho3) = (o1.line_of_sight.normalize(), o2.line_of_sight.normalize(), o3.line_of_sight.normalize());
+    let (tau1, tau3) = (o1.time - o2.time, o3.time - o2.time);
+    let tau = tau3 - tau1;
+    let (p1, p2, p3) = (rho2.cross(&rho3), rho1.cross(&rho3), rho1.cross(&rho2));
+    let d0 = rho1.dot(&p1);
+    if d0.abs() < 1.0e-14 {
+        return Err("Lines of sight are coplanar; Gauss' method needs curvature in the track".to_string());
+    }
+    let sites = [o1.site, o2.site, o3.site];
+    let d = |i: usize, p: &Vector3<f64>| sites[i].dot(p);
+    let (d11, d12, d13) = (d(0, &p1), d(0, &p2), d(0, &p3));
+    let (d21, d22, d23) = (d(1, &p1), d(1, &p2), d(1, &p3));
+    let (d31, d32, d33) = (d(2, &p1), d(2, &p2), d(2, &p3));
+
+    // ranges from the coefficients c1 = g3 / (f1 g3 - f3 g1), c3 = -g1 / (f1 g3 - f3 g1)
+    let ranges = |c1: f64, c3: f64| {
+        (
+            (-d11 + d21 / c1 - d31 * c3 / c1) / d0,
+            (-c1 * d12 + d22 - c3 * d32) / d0,
+            (-d13 * c1 / c3 + d23 / c3 - d33) / d0,
+        )
+    };
+    let solve = |f1: f64, g1: f64, f3: f64, g3: f64| {
+        let det = f1 * g3 - f3 * g1;
+        let (q1, q2, q3) = ranges(g3 / det, -g1 / det);
+        let (r1, r2, r3) = (sites[0] + q1 * rho1, sites[1] + q2 * rho2, sites[2] + q3 * rho3);
+        ((q1, q2, q3), r2, (f1 * r3 - f3 * r1) / det)
+    };
+    // truncated series for f and g at a middle radius r
+    let series = |r: f64| {
+        let mu_r3 = mu / r.powi(3);
+        (
+            1.0 - 0.5 * mu_r3 * tau1 * tau1,
+            tau1 - mu_r3 * tau1.powi(3) / 6.0,
+            1.0 - 0.5 * mu_r3 * tau3 * tau3,
+            tau3 - mu_r3 * tau3.powi(3) / 6.0,
+        )
+    };
+
+    let a_coef = (-d12 * tau3 / tau + d22 + d32 * tau1 / tau) / d0;
+    let b_coef = (d12 * (tau3 * tau3 - tau * tau) * tau3 / tau + d32 * (tau * tau - tau1 * tau1) * tau1 / tau) / (6.0 * d0);
+    let e = sites[1].dot(&rho2);
+    let (a, b, c) = (
+        -(a_coef * a_coef + 2.0 * a_coef * e + sites[1].norm_squared()),
+        -2.0 * mu * b_coef * (a_coef + e),
+        -(mu * b_coef).powi(2),
+    );
+    let start = gauss_polynomial_roots(a, b, c)
+        .into_iter()
+        .rev()
+        .map(|r| {
+            let (f1, g1, f3, g3) = series(r);
+            (series(r), solve(f1, g1, f3, g3))
+        })
+        .find(|(_, ((q1, q2, q3), _, _))| *q1 > 0.0 && *q2 > 0.0 && *q3 > 0.0);
+    let Some((mut coefficients, (mut q, mut r2, mut v2))) = start else {
+        return Err("No orbit above the Earth fits these observations".to_string());
+    };
+    if !refine {
+        return Ok((r2, v2));
+    }
+
+    for _ in 0..MAX_ITERATIONS {
+        let (Some((f1, g1)), Some((f3, g3))) =
+            (lagrange_coefficients(&r2, &v2, tau1, mu), lagrange_coefficients(&r2, &v2, tau3, mu))
+        else {
+            return Err("Gauss refinement left the two-body domain".to_string());
+        };
+        // average with the previous coefficients to damp oscillation
+        let (f1o, g1o, f3o, g3o) = coefficients;
+        coefficients = (0.5 * (f1 + f1o), 0.5 * (g1 + g1o), 0.5 * (f3 + f3o), 0.5 * (g3 + g3o));
+        let (f1, g1, f3, g3) = coefficients;
+        let (next, r, v) = solve(f1, g1, f3, g3);
+        let change = (next.0 - q.0).abs().max((next.1 - q.1).abs()).max((next.2 - q.2).abs());
+        (q, r2, v2) = (next, r, v);
+        if change <= RANGE_RTOL * q.1.abs() {
+            return Ok((r2, v2));
+        }
+    }
+    Err("Gauss refinement did not converge".to_string())
+}
+
+#[cfg(feature = "python")]
+fn vectors(r1: Vec<f64>, r2: Vec<f64>, r3: Vec<f64>) -> PyResult<[Vector3<f64>; 3]> {
+    Ok([vec3(&r1, "r1")?, vec3(&r2, "r2")?, vec3(&r3, "r3")?])
+}
+
+#[cfg(feature = "python")]
+fn estimate(norad_id: i32, r2: &Vector3<f64>, v2: &Vector3<f64>, jd: Option<f64>) -> Satellite {
+    Satellite::from_state(norad_id, (*r2).into(), (*v2).into(), jd.map(Epoch::from_jd_utc))
+}
+
+/// Orbit estimate at the middle of three positions by Gibbs' method
+///
+/// Positions in km, in order along the orbit. Returns a `Satellite` at
+/// `r2`, with epoch `jd` (the time of `r2`) when given.
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(name = "gibbs", signature = (r1, r2, r3, jd = None, norad_id = 0, coplanarity_deg = 1.0))]
+pub fn py_gibbs(
+    r1: Vec<f64>,
+    r2: Vec<f64>,
+    r3: Vec<f64>,
+    jd: Option<f64>,
+    norad_id: i32,
+    coplanarity_deg: f64,
+) -> PyResult<Satellite> {
+    let [r1, r2, r3] = vectors(r1, r2, r3)?;
+    let v2 = gibbs(&r1, &r2, &r3, MU_EARTH, coplanarity_deg).map_err(pyo3::exceptions::PyValueError::new_err)?;
+    Ok(estimate(norad_id, &r2, &v2, jd))
+}
+
+/// Orbit estimate at the middle of three closely spaced timed positions by Herrick-Gibbs
+///
+/// Positions in km observed at Julian dates (UTC) `jd1 < jd2 < jd3`.
+/// Returns a `Satellite` at `r2` with epoch `jd2`.
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(name = "herrick_gibbs", signature = (r1, r2, r3, jd1, jd2, jd3, norad_id = 0, coplanarity_deg = 1.0))]
+#[allow(clippy::too_many_arguments)]
+pub fn py_herrick_gibbs(
+    r1: Vec<f64>,
+    r2: Vec<f64>,
+    r3: Vec<f64>,
+    jd1: f64,
+    jd2: f64,
+    jd3: f64,
+    norad_id: i32,
+    coplanarity_deg: f64,
+) -> PyResult<Satellite> {
+    let [r1, r2, r3] = vectors(r1, r2, r3)?;
+    let times = [0.0, (jd2 - jd1) * SECONDS_PER_DAY, (jd3 - jd1) * SECONDS_PER_DAY];
+    let v2 = herrick_gibbs(&r1, &r2, &r3, times, MU_EARTH, coplanarity_deg)
+        .map_err(pyo3::exceptions::PyValueError::new_err)?;
+    Ok(estimate(norad_id, &r2, &v2, Some(jd2)))
+}
+
+/// Orbit estimate from three timed positions, choosing the method by their spacing
+///
+/// Observations less than 3 deg apart use Herrick-Gibbs, wider ones Gibbs.
+/// `positions` holds three position vectors (km) and `jds` their Julian
+/// dates (UTC). Returns the `Satellite` at the middle observation and the
+/// method used ("gibbs" or "herrick-gibbs").
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(name = "initial_orbit", signature = (positions, jds, norad_id = 0, coplanarity_deg = 1.0))]
+pub fn py_initial_orbit(
+    positions: [Vec<f64>; 3],
+    jds: [f64; 3],
+    norad_id: i32,
+    coplanarity_deg: f64,
+) -> PyResult<(Satellite, &'static str)> {
+    let [r1, r2, r3] = positions;
+    let [r1, r2, r3] = vectors(r1, r2, r3)?;
+    let times = jds.map(|jd| (jd - jds[0]) * SECONDS_PER_DAY);
+    let (v2, method) = initial_orbit(&r1, &r2, &r3, times, MU_EARTH, coplanarity_deg)
+        .map_err(pyo3::exceptions::PyValueError::new_err)?;
+    Ok((estimate(norad_id, &r2, &v2, Some(jds[1])), method))
+}
+
+/// Orbit estimate from three optical observations by a ground station (Gauss' method)
+///
+/// `observations` holds three `(jd, right_ascension_deg, declination_deg)`
+/// topocentric angles in the TEME frame, in time order. Returns a
+/// `Satellite` at the middle observation; `refine=False` keeps the
+/// first-pass series solution.
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(signature = (station, observations, norad_id = 0, refine = true))]
+pub fn angles_only_orbit(
+    station: &GroundStation,
+    observations: [(f64, f64, f64); 3],
+    norad_id: i32,
+    refine: bool,
+) -> PyResult<Satellite> {
+    let origin = observations[0].0;
+    let observations = observations.map(|(jd, ra, dec)| {
+        let (site, _) = frames::ecef_to_teme_state(&station.ecef_km(), &Vector3::zeros(), jd, 0.0, 0.0);
+        AnglesObservation::from_ra_dec((jd - origin) * SECONDS_PER_DAY, ra.to_radians(), dec.to_radians(), site)
+    });
+    let (r2, v2) = gauss(&observations, MU_EARTH, refine).map_err(pyo3::exceptions::PyValueError::new_err)?;
+    Ok(estimate(norad_id, &r2, &v2, Some(origin + observations[1].time / SECONDS_PER_DAY)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::MU_EARTH;
+    use crate::elements::{self, KeplerianElements};
+
+    fn state(true_anomaly_deg: f64) -> (Vector3<f64>, Vector3<f64>) {
+        elements::to_state(&KeplerianElements {
+            semi_major_axis_km: 8000.0,
+            eccentricity: 0.1,
+            inclination_deg: 40.0,
+            raan_deg: 30.0,
+            arg_perigee_deg: 60.0,
+            true_anomaly_deg,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_gibbs_recovers_velocity() {
+        let ((r1, _), (r2, v2), (r3, _)) = (state(10.0), state(50.0), state(110.0));
+        let v = gibbs(&r1, &r2, &r3, MU_EARTH, 1.0).unwrap();
+        assert!((v - v2).norm() < 1e-9, "{}", (v - v2).norm());
+        assert!(initial_orbit(&r1, &r2, &r3, [0.0, 1.0, 2.0], MU_EARTH, 1.0).unwrap().1 == "gibbs");
+
+        // tilt r1 out of the plane by 2 deg
+        let tilted = nalgebra::Rotation3::from_axis_angle(&Vector3::x_axis(), 2f64.to_radians()) * r1;
+        assert!(coplanarity_deg(&tilted, &r2, &r3).abs() > 0.5);
+        assert!(gibbs(&tilted, &r2, &r3, MU_EARTH, 0.1).is_err());
+        assert!(gibbs(&r1, &r1, &r1, MU_EARTH, 1.0).is_err());
+    }
+
+    #[test]
+    fn test_herrick_gibbs_on_a_short_arc() {
+        // a circular orbit sampled 30 s apart: positions at known times
+        let a: f64 = 7000.0;
+        let n = (MU_EARTH / a.powi(3)).sqrt();
+        let circular = |t: f64| {
+            elements::to_state(&KeplerianElements {
+                semi_major_axis_km: a,
+                eccentricity: 0.0,
+                inclination_deg: 51.6,
+                raan_deg: 100.0,
+                arg_perigee_deg: 0.0,
+                true_anomaly_deg: (n * t).to_degrees(),
+            })
+            .unwrap()
+        };
+        let ((r1, _), (r2, v2), (r3, _)) = (circular(0.0), circular(30.0), circular(60.0));
+        let v = herrick_gibbs(&r1, &r2, &r3, [0.0, 30.0, 60.0], MU_EARTH, 1.0).unwrap();
+        assert!((v - v2).norm() < 1e-6, "{}", (v - v2).norm());
+        let (auto, method) = initial_orbit(&r1, &r2, &r3, [0.0, 30.0, 60.0], MU_EARTH, 1.0).unwrap();
+        assert_eq!((auto, method), (v, "herrick-gibbs"));
+        assert!(herrick_gibbs(&r1, &r2, &r3, [0.0, 60.0, 30.0], MU_EARTH, 1.0).is_err());
+    }
+
+    #[test]
+    fn test_gauss_angles_only() {
+        use crate::propagation::{integrate, ForceModel, Integrator};
+        use nalgebra::Vector6;
+
+        // a LEO object seen over five minutes from a site turning with the Earth
+        let (r0, v0) = state(20.0);
+        let y0 = Vector6::new(r0.x, r0.y, r0.z, v0.x, v0.y, v0.z);
+        let times = [0.0, 150.0, 300.0];
+        let truth = times.map(|t| integrate(&y0, t, ForceModel::TwoBody, Integrator::DormandPrince { initial_step: 10.0 }).unwrap());
+        let observations = [0, 1, 2].map(|k| {
+            let theta = 7.292115e-5 * times[k] + 0.6;
+            let site = Vector3::new(theta.cos(), theta.sin(), 0.5) * (EARTH_RADIUS_KM / 1.118);
+            let los = (truth[k].fixed_rows::<3>(0) - site).normalize();
+            AnglesObservation { time: times[k], line_of_sight: los, site }
+        });
+        let (r2, v2) = gauss(&observations, MU_EARTH, true).unwrap();
+        assert!((r2 - truth[1].fixed_rows::<3>(0)).norm() < 1e-3, "{}", (r2 - truth[1].fixed_rows::<3>(0)).norm());
+        assert!((v2 - truth[1].fixed_rows::<3>(3)).norm() < 1e-6);
+        // the truncated-series first pass is only good to tens of km
+        let (rough, _) = gauss(&observations, MU_EARTH, false).unwrap();
+        assert!((rough - r2).norm() < 200.0);
+
+        let ra_dec = AnglesObservation::from_ra_dec(0.0, 0.5, -0.2, Vector3::zeros());
+        assert!((ra_dec.line_of_sight.norm() - 1.0).abs() < 1e-15);
+        assert!((ra_dec.line_of_sight.z - (-0.2f64).sin()).abs() < 1e-15);
+    }
+}
@@ -0,0 +1,155 @@
+//! JSON serialization of core types
+//!
+//! Types derive serde traits directly; the helpers here give the JSON a
+//! readable layout (covariances as row lists, states as six-element rows)
+//! and keep NaN fields round-trippable, since JSON has no NaN literal.
+
+use nalgebra::{Matrix6, Vector3};
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+#[cfg(feature = "python")]
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[cfg(feature = "python")]
+use crate::Conjunction;
+
+/// Serialize `value`, optionally pretty-printed
+#[cfg(feature = "python")]
+pub fn to_json<T: Serialize>(value: &T, pretty: bool) -> PyResult<String> {
+    let text = if pretty { serde_json::to_string_pretty(value) } else { serde_json::to_string(value) };
+    text.map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("JSON serialization failed: {}", e)))
+}
+
+/// Parse JSON into `T`
+#[cfg(feature = "python")]
+pub fn from_json<T: DeserializeOwned>(text: &str) -> PyResult<T> {
+    serde_json::from_str(text).map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("Invalid JSON: {}", e)))
+}
+
+/// Default for NaN-able fields missing from the input
+pub fn nan() -> f64 {
+    f64::NAN
+}
+
+/// Default for NaN-able vector fields missing from the input
+pub fn nan3() -> [f64; 3] {
+    [f64::NAN; 3]
+}
+
+/// Floats written as `null` when NaN
+pub mod nan_as_null {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(value: &f64, serializer: S) -> Result<S::Ok, S::Error> {
+        if value.is_nan() {
+            serializer.serialize_none()
+        } else {
+            serializer.serialize_f64(*value)
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+        Ok(Option::<f64>::deserialize(deserializer)?.unwrap_or(f64::NAN))
+    }
+}
+
+/// Fixed-size float arrays with NaN entries written as `null`
+pub mod nan_array_as_null {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(values: &[f64; 3], serializer: S) -> Result<S::Ok, S::Error> {
+        values.map(|x| (!x.is_nan()).then_some(x)).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<[f64; 3], D::Error> {
+        Ok(<[Option<f64>; 3]>::deserialize(deserializer)?.map(|x| x.unwrap_or(f64::NAN)))
+    }
+}
+
+/// Optional 6x6 covariance as a list of rows
+pub mod covariance_rows {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(value: &Option<Matrix6<f64>>, serializer: S) -> Result<S::Ok, S::Error> {
+        value.as_ref().map(crate::covariance::to_rows).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Matrix6<f64>>, D::Error> {
+        let Some(rows) = Option::<Vec<Vec<f64>>>::deserialize(deserializer)? else {
+            return Ok(None);
+        };
+        if rows.len() != 6 || rows.iter().any(|row| row.len() != 6) {
+            return Err(serde::de::Error::custom("covariance must be a 6x6 matrix"));
+        }
+        let matrix = Matrix6::from_fn(|i, j| rows[i][j]);
+        crate::covariance::validate(&matrix).map_err(serde::de::Error::custom)?;
+        Ok(Some(matrix))
+    }
+}
+
+/// Position/velocity pairs as `[x, y, z, vx, vy, vz]` rows
+pub mod state_rows {
+    use super::*;
+
+    type State = (Vector3<f64>, Vector3<f64>);
+
+    pub fn serialize<S: Serializer>(states: &[State], serializer: S) -> Result<S::Ok, S::Error> {
+        let rows: Vec<[f64; 6]> = states.iter().map(|(r, v)| [r.x, r.y, r.z, v.x, v.y, v.z]).collect();
+        rows.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<State>, D::Error> {
+        let rows = Vec::<[f64; 6]>::deserialize(deserializer)?;
+        Ok(rows
+            .into_iter()
+            .map(|s| (Vector3::new(s[0], s[1], s[2]), Vector3::new(s[3], s[4], s[5])))
+            .collect())
+    }
+}
+
+/// Serialize a list of conjunctions as a JSON array
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(signature = (conjunctions, pretty = false))]
+pub fn conjunctions_to_json(conjunctions: Vec<Conjunction>, pretty: bool) -> PyResult<String> {
+    to_json(&conjunctions, pretty)
+}
+
+/// Parse a JSON array written by `conjunctions_to_json`
+#[cfg(feature = "python")]
+#[pyfunction]
+pub fn conjunctions_from_json(text: &str) -> PyResult<Vec<Conjunction>> {
+    from_json(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Conjunction;
+
+    #[test]
+    fn test_nan_fields_round_trip() {
+        let event = Conjunction::from_states(
+            1,
+            2,
+            (&Vector3::zeros(), &Vector3::zeros()),
+            (&Vector3::new(1.0, 0.0, 0.0), &Vector3::new(0.0, 1.0, 0.0)),
+            Some(2460000.5),
+        );
+        assert!(event.radial_km.is_nan());
+        let text = serde_json::to_string(&event).unwrap();
+        assert!(text.contains("\"radial_km\":null"));
+        let back: Conjunction = serde_json::from_str(&text).unwrap();
+        assert!(back.radial_km.is_nan());
+        assert_eq!(back.tca_jd, Some(2460000.5));
+    }
+
+    #[test]
+    #[cfg(feature = "python")]
+    fn test_covariance_rows_validated() {
+        let bad = r#"{"norad_id": 1, "position": [7000, 0, 0], "velocity": [0, 7.5, 0],
+                      "covariance": [[1, 2], [3, 4]]}"#;
+        assert!(serde_json::from_str::<crate::Satellite>(bad).is_err());
+    }
+}
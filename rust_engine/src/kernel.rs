@@ -0,0 +1,122 @@
+//! Structure-of-arrays distance kernels
+//!
+//! Positions are stored as three contiguous coordinate arrays instead of
+//! one array of points, so the inner distance loops read unit-stride
+//! slices with no per-pair allocation or bounds checks and the compiler
+//! can vectorise them. Squared distances are returned; callers take the
+//! square root only where they need it.
+
+use std::ops::{Add, Mul, Sub};
+
+/// Scalar types the kernels run on (f64, and f32 for coarse filtering)
+pub trait Coordinate: Copy + Default + Send + Sync + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> {
+    fn from_f64(value: f64) -> Self;
+}
+
+impl Coordinate for f64 {
+    fn from_f64(value: f64) -> Self {
+        value
+    }
+}
+
+impl Coordinate for f32 {
+    fn from_f64(value: f64) -> Self {
+        value as f32
+    }
+}
+
+/// Positions split into x, y and z arrays
+#[derive(Clone, Debug, Default)]
+pub struct SoaPositions<T> {
+    x: Vec<T>,
+    y: Vec<T>,
+    z: Vec<T>,
+}
+
+impl<T: Coordinate> SoaPositions<T> {
+    pub fn from_rows(rows: &[[f64; 3]]) -> Self {
+        SoaPositions {
+            x: rows.iter().map(|p| T::from_f64(p[0])).collect(),
+            y: rows.iter().map(|p| T::from_f64(p[1])).collect(),
+            z: rows.iter().map(|p| T::from_f64(p[2])).collect(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.x.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.x.is_empty()
+    }
+
+    pub fn point(&self, i: usize) -> [T; 3] {
+        [self.x[i], self.y[i], self.z[i]]
+    }
+
+    /// Squared distances from `origin` to objects `start..start + out.len()`
+    pub fn squared_distances(&self, origin: [T; 3], start: usize, out: &mut [T]) {
+        let end = start + out.len();
+        let (xs, ys, zs) = (&self.x[start..end], &self.y[start..end], &self.z[start..end]);
+        for (((d2, &x), &y), &z) in out.iter_mut().zip(xs).zip(ys).zip(zs) {
+            let (dx, dy, dz) = (x - origin[0], y - origin[1], z - origin[2]);
+            *d2 = dx * dx + dy * dy + dz * dz;
+        }
+    }
+
+    /// Squared distances from `origin` to the objects at `indices`
+    pub fn gather_squared_distances(&self, origin: [T; 3], indices: &[usize], out: &mut Vec<T>) {
+        out.clear();
+        out.extend(indices.iter().map(|&j| {
+            let (dx, dy, dz) = (self.x[j] - origin[0], self.y[j] - origin[1], self.z[j] - origin[2]);
+            dx * dx + dy * dy + dz * dz
+        }));
+    }
+}
+
+impl SoaPositions<f64> {
+    /// Nearest other object to `i` and its squared distance; None with fewer than two objects
+    ///
+    /// Scans in fixed-size blocks reusing one buffer, so the distance loop
+    /// vectorises and the argmin runs over cached values.
+    pub fn nearest(&self, i: usize) -> Option<(usize, f64)> {
+        const BLOCK: usize = 1024;
+        let origin = self.point(i);
+        let mut buffer = [0.0; BLOCK];
+        let mut best: Option<(usize, f64)> = None;
+        for start in (0..self.len()).step_by(BLOCK) {
+            let block = &mut buffer[..BLOCK.min(self.len() - start)];
+            self.squared_distances(origin, start, block);
+            for (k, &d2) in block.iter().enumerate() {
+                let j = start + k;
+                if j != i && best.is_none_or(|(_, min)| d2 < min) {
+                    best = Some((j, d2));
+                }
+            }
+        }
+        best
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kernels_match_pointwise_distances() {
+        let rows: Vec<[f64; 3]> = (0..2500).map(|k| [k as f64, (k % 7) as f64, -(k as f64) * 0.5]).collect();
+        let soa = SoaPositions::<f64>::from_rows(&rows);
+        let mut out = vec![0.0; 10];
+        soa.squared_distances(rows[3], 100, &mut out);
+        let expected = |j: usize| (0..3).map(|c| (rows[j][c] - rows[3][c]).powi(2)).sum::<f64>();
+        assert!(out.iter().enumerate().all(|(k, &d2)| d2 == expected(100 + k)));
+
+        let mut gathered = Vec::new();
+        soa.gather_squared_distances(rows[3], &[2000, 5], &mut gathered);
+        assert_eq!(gathered, vec![expected(2000), expected(5)]);
+
+        // crosses block boundaries and skips itself
+        assert_eq!(soa.nearest(1500).map(|(j, _)| j), Some(1499));
+        assert!(SoaPositions::<f64>::from_rows(&rows[..1]).nearest(0).is_none());
+    }
+}
@@ -0,0 +1,149 @@
+//! Lambert's problem
+//!
+//! Single-revolution universal-variable solver (Bate, Mueller & White;
+//! Vallado algorithm 58). The time of flight increases monotonically with
+//! the universal variable z over (-inf, 4 pi^2), so z is found by
+//! bisection, which is slower than Newton iteration but never diverges.
+
+use nalgebra::Vector3;
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use std::f64::consts::PI;
+
+#[cfg(feature = "python")]
+use crate::constants::MU_EARTH;
+#[cfg(feature = "python")]
+use crate::frames::vec3;
+
+/// Relative tolerance on the time of flight
+const TOF_RTOL: f64 = 1.0e-12;
+
+/// Bisection iteration limit
+const MAX_ITERATIONS: usize = 500;
+
+/// Stumpff functions C(z) and S(z)
+pub(crate) fn stumpff(z: f64) -> (f64, f64) {
+    if z > 1.0e-6 {
+        let s = z.sqrt();
+        ((1.0 - s.cos()) / z, (s - s.sin()) / (s * z))
+    } else if z < -1.0e-6 {
+        let s = (-z).sqrt();
+        ((s.cosh() - 1.0) / -z, (s.sinh() - s) / (s * -z))
+    } else {
+        // series about z = 0
+        (0.5 - z / 24.0 + z * z / 720.0, 1.0 / 6.0 - z / 120.0 + z * z / 5040.0)
+    }
+}
+
+/// Transfer velocities (v1, v2) from `r1` to `r2` in `tof` seconds
+///
+/// `prograde` picks the short or long way so that the transfer's angular
+/// momentum has a non-negative z component (negative when false).
+pub fn solve(
+    r1: &Vector3<f64>,
+    r2: &Vector3<f64>,
+    tof: f64,
+    mu: f64,
+    prograde: bool,
+) -> Result<(Vector3<f64>, Vector3<f64>), String> {
+    if !(tof > 0.0 && mu > 0.0) {
+        return Err("Time of flight and mu must be positive".to_string());
+    }
+    let (r1_norm, r2_norm) = (r1.norm(), r2.norm());
+    if r1_norm == 0.0 || r2_norm == 0.0 {
+        return Err("Positions must be non-zero".to_string());
+    }
+    let cos_dnu = (r1.dot(r2) / (r1_norm * r2_norm)).clamp(-1.0, 1.0);
+    let normal_z = r1.cross(r2).z;
+    let short_way = if prograde { normal_z >= 0.0 } else { normal_z < 0.0 };
+    let sin_dnu = (1.0 - cos_dnu * cos_dnu).sqrt() * if short_way { 1.0 } else { -1.0 };
+    let a = sin_dnu * (r1_norm * r2_norm / (1.0 - cos_dnu)).sqrt();
+    if !a.is_finite() || a.abs() < 1.0e-12 * (r1_norm + r2_norm) {
+        return Err("Transfer angle of 0 or 180 degrees: the transfer plane is undefined".to_string());
+    }
+
+    let y_of = |z: f64| {
+        let (c, s) = stumpff(z);
+        r1_norm + r2_norm + a * (z * s - 1.0) / c.sqrt()
+    };
+    // time of flight at z, or None where y < 0 (z too small)
+    let time_of = |z: f64| {
+        let y = y_of(z);
+        if y < 0.0 {
+            return None;
+        }
+        let (c, s) = stumpff(z);
+        Some(((y / c).powf(1.5) * s + a * y.sqrt()) / mu.sqrt())
+    };
+
+    let mut upper = 4.0 * PI * PI;
+    let mut lower = -4.0 * PI * PI;
+    while time_of(lower).is_some_and(|t| t > tof) {
+        lower *= 2.0;
+        if lower < -1.0e9 {
+            return Err("No hyperbolic transfer is fast enough".to_string());
+        }
+    }
+    let mut z = 0.5 * (lower + upper);
+    for _ in 0..MAX_ITERATIONS {
+        z = 0.5 * (lower + upper);
+        match time_of(z) {
+            Some(t) if (t - tof).abs() <= TOF_RTOL * tof => break,
+            Some(t) if t > tof => upper = z,
+            _ => lower = z,
+        }
+    }
+
+    let y = y_of(z);
+    let f = 1.0 - y / r1_norm;
+    let g = a * (y / mu).sqrt();
+    let g_dot = 1.0 - y / r2_norm;
+    Ok(((r2 - f * r1) / g, (g_dot * r2 - r1) / g))
+}
+
+/// Solve Lambert's problem: velocities (v1, v2) taking `r1` to `r2` in `tof` seconds
+///
+/// Positions in km, `mu` in km^3/s^2 (Earth by default). Single revolution;
+/// `prograde` selects the transfer moving counter-clockwise about +z.
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(signature = (r1, r2, tof, mu = MU_EARTH, prograde = true))]
+pub fn lambert(r1: Vec<f64>, r2: Vec<f64>, tof: f64, mu: f64, prograde: bool) -> PyResult<(Vec<f64>, Vec<f64>)> {
+    let (r1, r2) = (vec3(&r1, "r1")?, vec3(&r2, "r2")?);
+    let (v1, v2) = solve(&r1, &r2, tof, mu, prograde).map_err(pyo3::exceptions::PyValueError::new_err)?;
+    Ok((v1.as_slice().to_vec(), v2.as_slice().to_vec()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::MU_EARTH;
+    use crate::propagation::{integrate, ForceModel, Integrator};
+    use nalgebra::Vector6;
+
+    #[test]
+    fn test_vallado_example() {
+        // Vallado example 7-5
+        let r1 = Vector3::new(15945.34, 0.0, 0.0);
+        let r2 = Vector3::new(12214.83899, 10249.46731, 0.0);
+        let (v1, v2) = solve(&r1, &r2, 76.0 * 60.0, MU_EARTH, true).unwrap();
+        assert!((v1 - Vector3::new(2.058913, 2.915965, 0.0)).norm() < 1e-5, "{}", v1);
+        assert!((v2 - Vector3::new(-3.451565, 0.910315, 0.0)).norm() < 1e-5, "{}", v2);
+    }
+
+    #[test]
+    fn test_transfers_reach_target() {
+        let r1 = Vector3::new(7000.0, 1000.0, -500.0);
+        let r2 = Vector3::new(-3000.0, 9000.0, 2500.0);
+        let integrator = Integrator::DormandPrince { initial_step: 30.0 };
+        // elliptic short and long way, and a fast hyperbolic transfer
+        for (tof, prograde) in [(3000.0, true), (9000.0, false), (600.0, true)] {
+            let (v1, v2) = solve(&r1, &r2, tof, MU_EARTH, prograde).unwrap();
+            let y0 = Vector6::new(r1.x, r1.y, r1.z, v1.x, v1.y, v1.z);
+            let y = integrate(&y0, tof, ForceModel::TwoBody, integrator).unwrap();
+            assert!((y.fixed_rows::<3>(0) - r2).norm() < 1e-4, "tof {}", tof);
+            assert!((y.fixed_rows::<3>(3) - v2).norm() < 1e-7, "tof {}", tof);
+        }
+        assert!(solve(&r1, &(-r1 * 2.0), 3000.0, MU_EARTH, true).is_err());
+    }
+}
@@ -0,0 +1,405 @@
+//! Collision on launch assessment (COLA)
+//!
+//! The launch trajectory is given as time-tagged states from liftoff, in
+//! any frame `Ephemeris::to_teme` converts, including the Earth-fixed
+//! frames an ascent from the pad is usually delivered in. Its first sample
+//! is the nominal liftoff. Moving the liftoff by dt keeps the ascent fixed
+//! to the pad, so the trajectory is shifted by dt and rotated with the
+//! Earth by `omega dt` about the TEME z axis.
+//!
+//! Every liftoff of the window is screened against the catalogue in one
+//! pass per batch, on a grid whose step divides the liftoff step so each
+//! shifted trajectory is sampled at the same offsets from liftoff.
+//! Catalogue objects whose perigee lies above the trajectory are dropped
+//! before any propagation over the grid.
+
+use nalgebra::{Rotation3, Vector3};
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+
+use crate::constants::{EARTH_ROTATION_RATE, SECONDS_PER_DAY};
+use crate::ephemeris::Ephemeris;
+use crate::epoch::Epoch;
+use crate::error::OrbitError;
+use crate::progress::Progress;
+use crate::screening::{ScreeningPipeline, Trajectory};
+use crate::sieve;
+use crate::Conjunction;
+#[cfg(feature = "python")]
+use crate::progress::{self, CancellationToken};
+#[cfg(feature = "python")]
+use crate::screening::PairSelection;
+
+/// Allowance (km) for the osculating perigee of a catalogue object dipping below its value at the window open
+const PERIGEE_MARGIN_KM: f64 = 50.0;
+
+/// Liftoff times to screen
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LaunchWindow {
+    /// Window open and close (Julian dates, UTC)
+    pub open_jd: f64,
+    pub close_jd: f64,
+    /// Spacing of the screened liftoff times (s)
+    pub liftoff_step_s: f64,
+}
+
+impl LaunchWindow {
+    /// Screened liftoff times: from the open every `liftoff_step_s`, with the close always included
+    pub fn liftoffs(&self) -> Result<Vec<f64>, String> {
+        if self.close_jd < self.open_jd || self.close_jd.is_nan() || self.open_jd.is_nan() {
+            return Err("The launch window must close after it opens".to_string());
+        }
+        if self.liftoff_step_s <= 0.0 || self.liftoff_step_s.is_nan() {
+            return Err("liftoff_step must be positive (seconds)".to_string());
+        }
+        let length = (self.close_jd - self.open_jd) * SECONDS_PER_DAY;
+        // a close within a millisecond of a step (Julian date rounding) is that step, not one more liftoff
+        let count = ((length - 1e-3) / self.liftoff_step_s).ceil().max(0.0) as usize;
+        Ok((0..=count)
+            .map(|k| self.open_jd + (k as f64 * self.liftoff_step_s).min(length) / SECONDS_PER_DAY)
+            .collect())
+    }
+}
+
+/// Screening result of one liftoff time
+#[cfg_attr(feature = "python", pyclass(get_all))]
+#[derive(Clone)]
+pub struct LiftoffScreen {
+    pub liftoff_jd: f64,
+    /// Every close approach of the trajectory flown from this liftoff, by TCA
+    pub conjunctions: Vec<Conjunction>,
+    /// The closest of them, None if the liftoff is clear
+    pub worst: Option<Conjunction>,
+}
+
+impl LiftoffScreen {
+    pub fn is_clear(&self) -> bool {
+        self.conjunctions.is_empty()
+    }
+}
+
+/// A run of consecutive screened liftoffs that each have a close approach
+#[cfg_attr(feature = "python", pyclass(get_all))]
+#[derive(Clone, Debug, PartialEq)]
+pub struct LaunchBlackout {
+    /// First and last blocked liftoff (Julian dates, UTC)
+    pub start_jd: f64,
+    pub end_jd: f64,
+    /// Catalogue objects responsible, in increasing order
+    pub norad_ids: Vec<i32>,
+    pub min_distance_km: f64,
+}
+
+/// Outcome of a launch window screen
+#[cfg_attr(feature = "python", pyclass(get_all))]
+#[derive(Clone)]
+pub struct LaunchColaReport {
+    /// One entry per screened liftoff, in time order
+    pub liftoffs: Vec<LiftoffScreen>,
+    pub blackouts: Vec<LaunchBlackout>,
+    /// Catalogue objects left after dropping those above the trajectory
+    pub screened_objects: usize,
+}
+
+impl LaunchColaReport {
+    /// True if a liftoff at `jd` is inside the window with clear screened liftoffs on both sides
+    ///
+    /// Between two screened liftoffs nothing is known, so a liftoff next to
+    /// a blocked one counts as blocked.
+    pub fn is_clear_at(&self, jd: f64) -> bool {
+        let (Some(first), Some(last)) = (self.liftoffs.first(), self.liftoffs.last()) else {
+            return false;
+        };
+        if !(first.liftoff_jd..=last.liftoff_jd).contains(&jd) {
+            return false;
+        }
+        let after = self.liftoffs.partition_point(|l| l.liftoff_jd < jd);
+        let before = if self.liftoffs[after].liftoff_jd == jd { after } else { after - 1 };
+        self.liftoffs[before].is_clear() && self.liftoffs[after].is_clear()
+    }
+}
+
+/// The TEME launch trajectory flown from `liftoff_jd`
+///
+/// `nominal` is in TEME with its first sample at the nominal liftoff.
+pub fn trajectory_at_liftoff(nominal: &Ephemeris, liftoff_jd: f64) -> Ephemeris {
+    let liftoff = Epoch::from_jd_utc(liftoff_jd);
+    let shift = liftoff.seconds_since(&nominal.reference);
+    let rotation = Rotation3::from_axis_angle(&Vector3::z_axis(), EARTH_ROTATION_RATE * shift);
+    Ephemeris {
+        reference: liftoff,
+        states: nominal.states.iter().map(|(r, v)| (rotation * r, rotation * v)).collect(),
+        ..nominal.clone()
+    }
+}
+
+/// Runs of blocked liftoffs, with the objects and closest miss of each
+fn blackouts(liftoffs: &[LiftoffScreen]) -> Vec<LaunchBlackout> {
+    let mut runs: Vec<LaunchBlackout> = Vec::new();
+    let mut extends = false;
+    for liftoff in liftoffs {
+        let Some(worst) = &liftoff.worst else {
+            extends = false;
+            continue;
+        };
+        if !extends {
+            runs.push(LaunchBlackout {
+                start_jd: liftoff.liftoff_jd,
+                end_jd: liftoff.liftoff_jd,
+                norad_ids: Vec::new(),
+                min_distance_km: f64::INFINITY,
+            });
+            extends = true;
+        }
+        let run = runs.last_mut().expect("a run was just started");
+        run.end_jd = liftoff.liftoff_jd;
+        run.min_distance_km = run.min_distance_km.min(worst.distance_km);
+        run.norad_ids.extend(liftoff.conjunctions.iter().map(|c| c.norad_id_2));
+    }
+    for run in &mut runs {
+        run.norad_ids.sort_unstable();
+        run.norad_ids.dedup();
+    }
+    runs
+}
+
+/// Screen a launch trajectory flown from every liftoff of `window` against `catalog`
+///
+/// `step` (s) is shortened where needed to divide the liftoff step. Liftoffs
+/// are screened in batches spanning about one trajectory length, so the
+/// catalogue is sampled over at most twice the trajectory's duration at once.
+/// Close approaches with the launch vehicle before its first or after its
+/// last sample are not seen.
+pub fn screen_launch(
+    pipeline: &mut ScreeningPipeline,
+    trajectory: &Ephemeris,
+    catalog: &[Trajectory],
+    window: &LaunchWindow,
+    step: f64,
+    progress: &Progress,
+) -> Result<LaunchColaReport, OrbitError> {
+    let liftoff_jds = window.liftoffs().map_err(OrbitError::InvalidInput)?;
+    if step <= 0.0 || step.is_nan() {
+        return Err(OrbitError::InvalidInput("step must be positive (seconds)".to_string()));
+    }
+    let nominal = trajectory.to_teme().map_err(OrbitError::InvalidInput)?;
+    let step = window.liftoff_step_s / (window.liftoff_step_s / step).ceil();
+    let span = nominal.span();
+
+    // objects whose whole orbit stays above the trajectory cannot come within the threshold
+    let highest = nominal.states.iter().map(|(r, _)| r.norm()).fold(0.0, f64::max);
+    let reach = highest + pipeline.threshold_km + PERIGEE_MARGIN_KM;
+    let catalog: Vec<Trajectory> = catalog
+        .iter()
+        .filter(|object| {
+            object
+                .state_at(window.open_jd, 0.0)
+                .is_none_or(|(r, v)| sieve::perigee_apogee(&r, &v).0 <= reach)
+        })
+        .cloned()
+        .collect();
+    progress.set_total(liftoff_jds.len() * catalog.len());
+
+    let per_batch = ((span / window.liftoff_step_s).ceil() as usize).max(1);
+    let mut liftoffs = Vec::with_capacity(liftoff_jds.len());
+    for batch in liftoff_jds.chunks(per_batch) {
+        if progress.is_cancelled() {
+            return Err(Progress::cancelled_error());
+        }
+        let primaries: Vec<Trajectory> =
+            batch.iter().map(|&jd| trajectory_at_liftoff(&nominal, jd).into()).collect();
+        let (first, last) = (batch[0], batch[batch.len() - 1]);
+        let each = pipeline.screen_primaries_part(
+            &primaries,
+            &catalog,
+            first,
+            last + span / SECONDS_PER_DAY,
+            step,
+            progress,
+        )?;
+        for (&liftoff_jd, mut conjunctions) in batch.iter().zip(each) {
+            conjunctions.sort_by(|a, b| a.tca_jd.unwrap_or(f64::NAN).total_cmp(&b.tca_jd.unwrap_or(f64::NAN)));
+            let worst = conjunctions.iter().min_by(|a, b| a.distance_km.total_cmp(&b.distance_km)).cloned();
+            liftoffs.push(LiftoffScreen { liftoff_jd, conjunctions, worst });
+        }
+    }
+    if progress.is_cancelled() {
+        return Err(Progress::cancelled_error());
+    }
+    Ok(LaunchColaReport { blackouts: blackouts(&liftoffs), liftoffs, screened_objects: catalog.len() })
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl LiftoffScreen {
+    /// True if no catalogue object comes within the threshold
+    #[getter(clear)]
+    fn py_clear(&self) -> bool {
+        self.is_clear()
+    }
+
+    /// Smallest miss distance (inf if clear)
+    #[getter]
+    fn min_distance_km(&self) -> f64 {
+        self.worst.as_ref().map_or(f64::INFINITY, |c| c.distance_km)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "LiftoffScreen(liftoff_jd={:.6}, conjunctions={}, min_distance_km={:.3})",
+            self.liftoff_jd,
+            self.conjunctions.len(),
+            self.min_distance_km()
+        )
+    }
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl LaunchBlackout {
+    /// Length from the first to the last blocked liftoff (s)
+    #[getter]
+    fn duration_s(&self) -> f64 {
+        (self.end_jd - self.start_jd) * SECONDS_PER_DAY
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "LaunchBlackout(start_jd={:.6}, end_jd={:.6}, objects={:?}, min_distance_km={:.3})",
+            self.start_jd, self.end_jd, self.norad_ids, self.min_distance_km
+        )
+    }
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl LaunchColaReport {
+    /// True if a liftoff at `jd` is inside the window with clear screened liftoffs on both sides
+    #[pyo3(name = "is_clear_at")]
+    fn py_is_clear_at(&self, jd: f64) -> bool {
+        self.is_clear_at(jd)
+    }
+
+    /// Julian dates of the clear screened liftoffs
+    #[getter]
+    fn clear_liftoffs(&self) -> Vec<f64> {
+        self.liftoffs.iter().filter(|l| l.is_clear()).map(|l| l.liftoff_jd).collect()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "LaunchColaReport(liftoffs={}, blackouts={}, screened_objects={})",
+            self.liftoffs.len(),
+            self.blackouts.len(),
+            self.screened_objects
+        )
+    }
+}
+
+/// Collision on launch assessment over a launch window
+///
+/// `trajectory` is an `Ephemeris` of the launch vehicle whose first sample
+/// is the nominal liftoff (Earth-fixed or inertial frames; non-orbital
+/// ascent states are fine). It is flown from every liftoff between
+/// `window_open` and `window_close` (Julian dates, UTC) spaced
+/// `liftoff_step` seconds, and screened against `catalog` with a grid
+/// `step` aligned to the liftoffs. Returns a `LaunchColaReport` with the
+/// close approaches of each liftoff and the blackouts, runs of liftoffs
+/// with a close approach. `progress`, `cancel` and `progress_interval_s`
+/// are as for `screen_window`.
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(name = "screen_launch", signature = (
+    trajectory,
+    catalog,
+    window_open,
+    window_close,
+    threshold_km,
+    liftoff_step = 60.0,
+    step = 10.0,
+    progress = None,
+    cancel = None,
+    progress_interval_s = 1.0,
+    exclude_pairs = None
+))]
+#[allow(clippy::too_many_arguments)]
+pub fn py_screen_launch(
+    py: Python<'_>,
+    trajectory: Ephemeris,
+    catalog: Vec<Trajectory>,
+    window_open: f64,
+    window_close: f64,
+    threshold_km: f64,
+    liftoff_step: f64,
+    step: f64,
+    progress: Option<&PyAny>,
+    cancel: Option<CancellationToken>,
+    progress_interval_s: f64,
+    exclude_pairs: Option<Vec<(i32, i32)>>,
+) -> PyResult<LaunchColaReport> {
+    let window = LaunchWindow { open_jd: window_open, close_jd: window_close, liftoff_step_s: liftoff_step };
+    let counters = Progress::new(0, cancel.as_ref());
+    let mut pipeline = ScreeningPipeline::with_threshold(threshold_km);
+    pipeline.selection = PairSelection::new(exclude_pairs.unwrap_or_default(), None);
+    progress::run_monitored(py, &counters, progress, progress_interval_s, || {
+        screen_launch(&mut pipeline, &trajectory, &catalog, &window, step, &counters).map_err(PyErr::from)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ephemeris::Interpolation;
+    use crate::sgp4::{MeanElements, Sgp4Propagator};
+
+    const LIFTOFF: f64 = 2460000.5;
+
+    /// Straight vertical climb from the equator at 0 longitude, Earth-fixed, to 1000 km in 500 s
+    fn ascent() -> Ephemeris {
+        let samples = (0..=50)
+            .map(|k| {
+                let t = k as f64 * 10.0;
+                let r = Vector3::new(6378.137 + 2.0 * t, 0.0, 0.0);
+                (Epoch::from_jd_utc(LIFTOFF).plus_seconds(t), (r, Vector3::new(2.0, 0.0, 0.0)))
+            })
+            .collect();
+        Ephemeris::from_samples(90000, samples, "ECEF", Interpolation::Hermite, 7).unwrap()
+    }
+
+    #[test]
+    fn test_shift_keeps_the_pad_fixed() {
+        let nominal = ascent().to_teme().unwrap();
+        let later = LIFTOFF + 600.0 / SECONDS_PER_DAY;
+        let shifted = trajectory_at_liftoff(&nominal, later).to_teme().unwrap();
+        let direct = Ephemeris { reference: Epoch::from_jd_utc(later), ..ascent() }.to_teme().unwrap();
+        for ((r1, _), (r2, _)) in shifted.states.iter().zip(&direct.states) {
+            // precession and nutation over ten minutes are negligible
+            assert!((r1 - r2).norm() < 1e-3, "{}", (r1 - r2).norm());
+        }
+    }
+
+    #[test]
+    fn test_blackouts_where_an_equatorial_object_crosses_the_climb() {
+        // near-equatorial at 700 km: passes over the pad once per synodic period (~106 min)
+        let low = MeanElements::new(7, LIFTOFF, 0.0, 0.1, 0.0, 0.0001, 0.0, 0.0, 14.58).unwrap();
+        // far above the climb: dropped before the screen
+        let high = MeanElements::new(8, LIFTOFF, 0.0, 0.1, 0.0, 0.0001, 0.0, 0.0, 2.0).unwrap();
+        let catalog: Vec<Trajectory> =
+            [low, high].into_iter().map(|e| Sgp4Propagator::from_mean_elements(e).into()).collect();
+
+        let window = LaunchWindow { open_jd: LIFTOFF, close_jd: LIFTOFF + 0.1, liftoff_step_s: 60.0 };
+        let mut pipeline = ScreeningPipeline::with_threshold(100.0);
+        let progress = Progress::new(0, None);
+        let report = screen_launch(&mut pipeline, &ascent(), &catalog, &window, 10.0, &progress).unwrap();
+        assert_eq!(report.screened_objects, 1);
+        assert_eq!(report.liftoffs.len(), 145);
+        assert!(!report.blackouts.is_empty());
+        for blackout in &report.blackouts {
+            assert_eq!(blackout.norad_ids, vec![7]);
+            assert!(blackout.min_distance_km < 100.0);
+            assert!(!report.is_clear_at(blackout.start_jd) && !report.is_clear_at(blackout.end_jd));
+        }
+        let clear = report.liftoffs.iter().filter(|l| l.is_clear()).count();
+        assert!(clear > 100, "{} clear liftoffs", clear);
+    }
+}
@@ -1,196 +1,1688 @@
+//! Orbital mechanics engine for OrbitGuardAI
+//!
+//! The default `python` feature builds the `orbit_core` extension module.
+//! Without it only the math core is compiled (constants, frames, elements,
+//! epochs, SGP4/TLE, numerical propagation, density, eclipse, probability,
+//! Lambert, initial orbit determination, relative motion, debris flux and
+//! breakups), plus `Satellite`, `Conjunction` and the all-vs-all
+//! `find_conjunctions` screen, so Rust services and WASM targets can use it
+//! without PyO3.
+
+#[cfg(feature = "python")]
+use pyo3::basic::CompareOp;
+#[cfg(feature = "python")]
 use pyo3::prelude::*;
+#[cfg(feature = "python")]
+use pyo3::types::PyTuple;
+use nalgebra::Matrix6;
 use nalgebra::Vector3;
 use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "python")]
+pub mod batch;
+pub mod breakup;
+pub mod catalog;
+pub mod cdm;
+pub mod celestial;
+#[cfg(feature = "python")]
+pub mod columnar;
+pub mod constants;
+pub mod covariance;
+pub mod decay;
+pub mod designator;
+pub mod density;
+pub mod eclipse;
+pub mod ekf;
+pub mod elements;
+pub mod ephemeris;
+pub mod epoch;
+pub mod error;
+pub mod events;
+#[cfg(feature = "fetch")]
+pub mod fetch;
+pub mod flux;
+pub mod frames;
+pub mod gabbard;
+pub mod geo;
+pub mod gpu;
+pub mod graph;
+pub mod ground_station;
+pub mod ground_track;
+pub mod hygiene;
+pub mod iod;
+pub mod json;
+pub mod kernel;
+pub mod lambert;
+pub mod launch_cola;
+pub mod logging;
+pub mod maneuver;
+pub mod maneuver_detection;
+pub mod maneuver_trade;
+pub mod maneuvers;
+pub mod metadata;
+pub mod monte_carlo;
+pub mod observations;
+pub mod od;
+pub mod omm;
+pub mod orbit_design;
+pub mod osculating;
+pub mod parallel;
+pub mod probability;
+pub mod progress;
+pub mod propagation;
+pub(crate) mod random;
+pub mod relative;
+pub mod report;
+pub mod risk;
+pub mod robust;
+pub mod screening;
+pub mod sensor;
+pub mod sgp4;
+pub mod sieve;
+pub mod spatial;
+#[cfg(feature = "python")]
+pub mod stream;
+pub mod synthetic;
+pub mod tabular;
+#[cfg(feature = "sqlite")]
+pub mod store;
+pub mod tle;
+pub mod visibility;
+pub mod volume;
+
+#[cfg(feature = "python")]
+use elements::{EquinoctialElements, KeplerianElements};
+use epoch::Epoch;
+use error::OrbitError;
+pub use metadata::ConstellationPairs;
+use metadata::{ObjectType, RcsSize};
+#[cfg(feature = "python")]
+use metadata::Regime;
+pub use screening::PairSelection;
+#[cfg(feature = "python")]
+use sgp4::{MeanElements, Sgp4Propagator};
+use kernel::SoaPositions;
+use spatial::UniformGrid;
+use volume::ScreeningVolume;
+
+/// Bit pattern of a float for hashing and equality (NaN equals NaN, -0.0 equals 0.0)
+#[cfg(feature = "python")]
+pub(crate) fn float_key(x: f64) -> u64 {
+    if x == 0.0 {
+        0
+    } else if x.is_nan() {
+        f64::NAN.to_bits()
+    } else {
+        x.to_bits()
+    }
+}
+
+/// Stable 64-bit hash (fixed SipHash keys, so equal across processes)
+#[cfg(feature = "python")]
+pub(crate) fn hash_key<T: std::hash::Hash>(key: &T) -> u64 {
+    use std::hash::Hasher;
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    key.hash(&mut hasher);
+    hasher.finish()
+}
 
 /// Satellite representation in 3D space
-#[pyclass]
-#[derive(Clone)]
+#[cfg_attr(feature = "python", pyclass(module = "orbit_core"))]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Satellite {
-    #[pyo3(get, set)]
     pub norad_id: i32,
     
-    #[pyo3(get, set)]
-    pub position: Vec<f64>,  // [x, y, z] in km
+    pub position: [f64; 3],  // [x, y, z] in km
+    
+    pub velocity: [f64; 3],  // [vx, vy, vz] in km/s
+    
+    #[serde(default, with = "json::covariance_rows")]
+    pub covariance: Option<Matrix6<f64>>,  // position-velocity covariance (km, km/s)
+    
+    #[serde(default)]
+    pub epoch: Option<Epoch>,  // instant the state refers to
+    
+    #[serde(default)]
+    pub name: Option<String>,
     
-    #[pyo3(get, set)]
-    pub velocity: Vec<f64>,  // [vx, vy, vz] in km/s
+    #[serde(default)]
+    pub object_type: Option<ObjectType>,
+    
+    #[serde(default)]
+    pub rcs_size: Option<RcsSize>,
+    
+    #[serde(default)]
+    pub hard_body_radius: Option<f64>,  // km; falls back to the RCS class default
+    
+    #[serde(default)]
+    pub constellation: Option<String>,  // owner/constellation tag, e.g. "STARLINK"
+    
+    #[serde(default)]
+    pub international_designator: Option<String>,  // COSPAR id, "YYYY-NNNP"
 }
 
-#[pymethods]
+/// Hashable form of every `Satellite` field
+#[cfg(feature = "python")]
+type SatelliteKey<'a> =
+    (i32, Vec<u64>, Option<&'a str>, Option<ObjectType>, Option<RcsSize>, Option<&'a str>, Option<&'a str>);
+
 impl Satellite {
-    #[new]
-    fn new(norad_id: i32, position: Vec<f64>, velocity: Vec<f64>) -> PyResult<Self> {
-        if position.len() != 3 || velocity.len() != 3 {
-            return Err(pyo3::exceptions::PyValueError::new_err(
-                "Position and velocity must have 3 components"
-            ));
-        }
-        
-        Ok(Satellite {
+    /// Bare state with no covariance or metadata
+    pub fn from_state(norad_id: i32, position: [f64; 3], velocity: [f64; 3], epoch: Option<Epoch>) -> Self {
+        Satellite {
             norad_id,
             position,
             velocity,
+            covariance: None,
+            epoch,
+            name: None,
+            object_type: None,
+            rcs_size: None,
+            hard_body_radius: None,
+            constellation: None,
+            international_designator: None,
+        }
+    }
+    
+    /// Position (km) as a vector, without allocating
+    pub fn r(&self) -> Vector3<f64> {
+        Vector3::from(self.position)
+    }
+    
+    /// Velocity (km/s) as a vector, without allocating
+    pub fn v(&self) -> Vector3<f64> {
+        Vector3::from(self.velocity)
+    }
+    
+    /// Hard-body radius used for screening and Pc (km); zero for a point object
+    pub fn collision_radius(&self) -> f64 {
+        self.hard_body_radius
+            .or_else(|| self.rcs_size.map(|size| size.default_radius_km()))
+            .unwrap_or(0.0)
+    }
+    
+    /// Whether both satellites carry the same constellation tag
+    pub fn same_constellation(&self, other: &Satellite) -> bool {
+        metadata::same_constellation(self.constellation.as_deref(), other.constellation.as_deref())
+    }
+    
+    /// Perigee and apogee radii (km) of the osculating two-body orbit
+    pub fn perigee_apogee(&self) -> (f64, f64) {
+        sieve::perigee_apogee(&self.r(), &self.v())
+    }
+    
+    /// Distance to another satellite (km)
+    pub fn distance_to(&self, other: &Satellite) -> f64 {
+        (self.r() - other.r()).norm()
+    }
+}
+
+#[cfg(feature = "python")]
+impl Satellite {
+    /// Every field in hashable form; equal keys mean equal satellites
+    fn key(&self) -> SatelliteKey<'_> {
+        let mut floats: Vec<u64> = self.position.iter().chain(&self.velocity).map(|&x| float_key(x)).collect();
+        if let Some(cov) = &self.covariance {
+            floats.push(1);
+            floats.extend(cov.iter().map(|&x| float_key(x)));
+        }
+        floats.push(self.epoch.map_or(u64::MAX, |e| float_key(e.mjd_tai)));
+        floats.push(self.hard_body_radius.map_or(u64::MAX, float_key));
+        (
+            self.norad_id,
+            floats,
+            self.name.as_deref(),
+            self.object_type,
+            self.rcs_size,
+            self.constellation.as_deref(),
+            self.international_designator.as_deref(),
+        )
+    }
+}
+
+/// Three-component vector from a Python sequence
+#[cfg(feature = "python")]
+fn vector3_from_py(values: Vec<f64>) -> PyResult<[f64; 3]> {
+    values
+        .try_into()
+        .map_err(|_| OrbitError::InvalidState("Position and velocity must have 3 components".to_string()).into())
+}
+
+#[cfg(feature = "python")]
+fn check_radius(radius: Option<f64>) -> PyResult<Option<f64>> {
+    match radius {
+        Some(r) if !(r >= 0.0 && r.is_finite()) => {
+            Err(OrbitError::InvalidState("hard_body_radius must be a non-negative number of km".to_string()).into())
+        }
+        _ => Ok(radius),
+    }
+}
+
+#[cfg(feature = "python")]
+fn check_designator(value: Option<&str>) -> PyResult<Option<String>> {
+    value
+        .map(designator::parse_international_designator)
+        .transpose()
+        .map_err(pyo3::exceptions::PyValueError::new_err)
+}
+
+#[cfg(feature = "python")]
+fn parse_metadata<T: std::str::FromStr<Err = String>>(value: &str) -> PyResult<T> {
+    value.parse().map_err(pyo3::exceptions::PyValueError::new_err)
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl Satellite {
+    #[new]
+    #[pyo3(signature = (
+        norad_id,
+        position,
+        velocity,
+        covariance = None,
+        epoch = None,
+        name = None,
+        object_type = None,
+        rcs_size = None,
+        hard_body_radius = None,
+        constellation = None,
+        international_designator = None
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        norad_id: &PyAny,
+        position: Vec<f64>,
+        velocity: Vec<f64>,
+        covariance: Option<&PyAny>,
+        epoch: Option<Epoch>,
+        name: Option<String>,
+        object_type: Option<&str>,
+        rcs_size: Option<&str>,
+        hard_body_radius: Option<f64>,
+        constellation: Option<String>,
+        international_designator: Option<&str>,
+    ) -> PyResult<Self> {
+        Ok(Satellite {
+            norad_id: designator::norad_id_from_py(norad_id)?,
+            position: vector3_from_py(position)?,
+            velocity: vector3_from_py(velocity)?,
+            covariance: covariance.map(covariance::covariance_from_py).transpose()?,
+            epoch,
+            name,
+            object_type: object_type.map(parse_metadata).transpose()?,
+            rcs_size: rcs_size.map(parse_metadata).transpose()?,
+            hard_body_radius: check_radius(hard_body_radius)?,
+            constellation,
+            international_designator: check_designator(international_designator)?,
+        })
+    }
+    
+    /// NORAD catalog number
+    #[getter]
+    fn get_norad_id(&self) -> i32 {
+        self.norad_id
+    }
+    
+    #[setter]
+    fn set_norad_id(&mut self, value: i32) {
+        self.norad_id = value;
+    }
+    
+    /// Position [x, y, z] (km)
+    #[getter]
+    fn get_position(&self) -> Vec<f64> {
+        self.position.to_vec()
+    }
+    
+    #[setter]
+    fn set_position(&mut self, value: Vec<f64>) -> PyResult<()> {
+        self.position = vector3_from_py(value)?;
+        Ok(())
+    }
+    
+    /// Velocity [vx, vy, vz] (km/s)
+    #[getter]
+    fn get_velocity(&self) -> Vec<f64> {
+        self.velocity.to_vec()
+    }
+    
+    #[setter]
+    fn set_velocity(&mut self, value: Vec<f64>) -> PyResult<()> {
+        self.velocity = vector3_from_py(value)?;
+        Ok(())
+    }
+    
+    /// 6x6 position-velocity covariance as nested lists, or None
+    #[getter]
+    fn get_covariance(&self) -> Option<Vec<Vec<f64>>> {
+        self.covariance.as_ref().map(covariance::to_rows)
+    }
+    
+    /// Set the covariance from a flat 36-element list, a 6x6 list or a numpy array
+    #[setter]
+    fn set_covariance(&mut self, value: Option<&PyAny>) -> PyResult<()> {
+        self.covariance = value.map(covariance::covariance_from_py).transpose()?;
+        Ok(())
+    }
+    
+    /// Instant the state refers to, or None
+    #[getter]
+    fn get_epoch(&self) -> Option<Epoch> {
+        self.epoch
+    }
+    
+    #[setter]
+    fn set_epoch(&mut self, value: Option<Epoch>) {
+        self.epoch = value;
+    }
+    
+    #[getter]
+    fn get_name(&self) -> Option<String> {
+        self.name.clone()
+    }
+    
+    #[setter]
+    fn set_name(&mut self, value: Option<String>) {
+        self.name = value;
+    }
+    
+    /// Object type ("PAYLOAD", "ROCKET BODY", "DEBRIS", "UNKNOWN") or None
+    #[getter]
+    fn get_object_type(&self) -> Option<&'static str> {
+        self.object_type.map(|t| t.as_str())
+    }
+    
+    #[setter]
+    fn set_object_type(&mut self, value: Option<&str>) -> PyResult<()> {
+        self.object_type = value.map(parse_metadata).transpose()?;
+        Ok(())
+    }
+    
+    /// RCS size class ("SMALL", "MEDIUM", "LARGE") or None
+    #[getter]
+    fn get_rcs_size(&self) -> Option<&'static str> {
+        self.rcs_size.map(|size| size.as_str())
+    }
+    
+    #[setter]
+    fn set_rcs_size(&mut self, value: Option<&str>) -> PyResult<()> {
+        self.rcs_size = value.map(parse_metadata).transpose()?;
+        Ok(())
+    }
+    
+    /// Hard-body radius (km); None falls back to the RCS class default
+    #[getter]
+    fn get_hard_body_radius(&self) -> Option<f64> {
+        self.hard_body_radius
+    }
+    
+    #[setter]
+    fn set_hard_body_radius(&mut self, value: Option<f64>) -> PyResult<()> {
+        self.hard_body_radius = check_radius(value)?;
+        Ok(())
+    }
+    
+    /// Owner/constellation tag, e.g. "STARLINK"
+    #[getter]
+    fn get_constellation(&self) -> Option<String> {
+        self.constellation.clone()
+    }
+    
+    #[setter]
+    fn set_constellation(&mut self, value: Option<String>) {
+        self.constellation = value;
+    }
+    
+    /// COSPAR id as "YYYY-NNNP", or None
+    #[getter]
+    fn get_international_designator(&self) -> Option<String> {
+        self.international_designator.clone()
+    }
+    
+    /// Set the international designator ("1998-067A" or TLE "98067A"); stored as "YYYY-NNNP"
+    #[setter]
+    fn set_international_designator(&mut self, value: Option<&str>) -> PyResult<()> {
+        self.international_designator = check_designator(value)?;
+        Ok(())
+    }
+    
+    /// Five-character catalog number ("25544", Alpha-5 "A0001"), or None above 339999
+    #[getter]
+    fn alpha5(&self) -> Option<String> {
+        designator::format_alpha5(self.norad_id).ok()
+    }
+    
+    fn __richcmp__(&self, other: &PyAny, op: CompareOp, py: Python<'_>) -> PyObject {
+        let Ok(other) = other.extract::<PyRef<Satellite>>() else {
+            return py.NotImplemented();
+        };
+        match op {
+            CompareOp::Eq => (self.key() == other.key()).into_py(py),
+            CompareOp::Ne => (self.key() != other.key()).into_py(py),
+            _ => py.NotImplemented(),
+        }
+    }
+    
+    fn __hash__(&self) -> u64 {
+        hash_key(&self.key())
+    }
+    
+    /// Pickle support: rebuild through the constructor
+    fn __reduce__(&self, py: Python<'_>) -> (PyObject, PyObject) {
+        let args = (
+            self.norad_id,
+            self.get_position(),
+            self.get_velocity(),
+            self.get_covariance(),
+            self.epoch,
+            self.name.clone(),
+            self.get_object_type(),
+            self.get_rcs_size(),
+            self.hard_body_radius,
+            self.constellation.clone(),
+            self.international_designator.clone(),
+        );
+        (py.get_type::<Satellite>().into(), args.into_py(py))
+    }
+    
+    /// JSON object with every field; `pretty` indents it
+    #[pyo3(signature = (pretty = false))]
+    fn to_json(&self, pretty: bool) -> PyResult<String> {
+        json::to_json(self, pretty)
+    }
+    
+    /// Satellite from `to_json` output
+    #[staticmethod]
+    fn from_json(text: &str) -> PyResult<Self> {
+        let satellite: Satellite = json::from_json(text)?;
+        check_radius(satellite.hard_body_radius)?;
+        Ok(satellite)
+    }
+    
+    /// Effective hard-body radius (km): explicit value, else RCS class default, else 0
+    #[pyo3(name = "collision_radius")]
+    fn py_collision_radius(&self) -> f64 {
+        self.collision_radius()
+    }
+    
+    /// Mahalanobis distance to another satellite under the combined position covariance
+    fn mahalanobis_distance(&self, other: &Satellite) -> PyResult<f64> {
+        let combined = match (&self.covariance, &other.covariance) {
+            (Some(a), Some(b)) => covariance::position_block(a) + covariance::position_block(b),
+            (Some(a), None) | (None, Some(a)) => covariance::position_block(a),
+            (None, None) => {
+                return Err(pyo3::exceptions::PyValueError::new_err(
+                    "At least one satellite must have a covariance"
+                ));
+            }
+        };
+        covariance::mahalanobis(&(self.r() - other.r()), &combined).ok_or_else(|| {
+            pyo3::exceptions::PyValueError::new_err("Combined position covariance is singular")
         })
     }
     
     /// Calculate distance to another satellite (km)
-    fn distance_to(&self, other: &Satellite) -> f64 {
-        let pos_self = Vector3::from_vec(self.position.clone());
-        let pos_other = Vector3::from_vec(other.position.clone());
-        (pos_self - pos_other).norm()
+    #[pyo3(name = "distance_to")]
+    fn py_distance_to(&self, other: &Satellite) -> f64 {
+        self.distance_to(other)
     }
     
     /// Calculate relative velocity (km/s)
     fn relative_velocity(&self, other: &Satellite) -> f64 {
-        let vel_self = Vector3::from_vec(self.velocity.clone());
-        let vel_other = Vector3::from_vec(other.velocity.clone());
-        (vel_self - vel_other).norm()
+        (self.v() - other.v()).norm()
     }
     
     /// Get current altitude above Earth surface (km)
     fn altitude(&self) -> f64 {
         const EARTH_RADIUS: f64 = 6371.0;  // km
-        let pos = Vector3::from_vec(self.position.clone());
-        pos.norm() - EARTH_RADIUS
+        self.r().norm() - EARTH_RADIUS
+    }
+    
+    /// Geodetic (lat_deg, lon_deg, alt_km) on WGS-84, treating the state as TEME at `jd`
+    fn geodetic(&self, jd: f64) -> (f64, f64, f64) {
+        let (ecef, _) = frames::teme_to_ecef_state(&self.r(), &Vector3::zeros(), jd, 0.0, 0.0);
+        let (lat, lon, alt) = frames::ecef_to_geodetic_rad(&ecef);
+        (lat.to_degrees(), lon.to_degrees(), alt)
+    }
+    
+    /// Perigee and apogee radii (km) of the osculating two-body orbit
+    #[pyo3(name = "perigee_apogee")]
+    fn py_perigee_apogee(&self) -> (f64, f64) {
+        self.perigee_apogee()
+    }
+    
+    /// Raise `InvalidStateError` for NaN/infinite components or a zero position
+    ///
+    /// With `strict=True` a position below the Earth's surface is rejected too.
+    #[pyo3(signature = (strict = false))]
+    fn validate(&self, strict: bool) -> PyResult<()> {
+        Ok(check_state(self.norad_id, &self.position, &self.velocity, strict)?)
+    }
+    
+    /// Orbit regime ("LEO", "MEO", "GEO", "HEO" or "OTHER") from perigee/apogee
+    fn regime(&self) -> &'static str {
+        let (perigee, apogee) = self.perigee_apogee();
+        Regime::classify(perigee, apogee).as_str()
+    }
+    
+    /// Classical orbital elements of the osculating orbit
+    fn to_keplerian(&self) -> PyResult<KeplerianElements> {
+        elements::from_state(&self.r(), &self.v())
+            .ok_or_else(|| OrbitError::InvalidState("State has zero angular momentum".to_string()).into())
+    }
+    
+    /// Build a satellite from classical elements (km, degrees)
+    #[staticmethod]
+    #[allow(clippy::too_many_arguments)]
+    fn from_keplerian(
+        a: f64,
+        e: f64,
+        i: f64,
+        raan: f64,
+        argp: f64,
+        nu: f64,
+        norad_id: i32,
+    ) -> PyResult<Satellite> {
+        let el = KeplerianElements {
+            semi_major_axis_km: a,
+            eccentricity: e,
+            inclination_deg: i,
+            raan_deg: raan,
+            arg_perigee_deg: argp,
+            true_anomaly_deg: nu,
+        };
+        let (pos, vel) = elements::to_state(&el).ok_or_else(|| {
+            OrbitError::InvalidState(
+                "Invalid elements: semi-latus rectum must be positive and the anomaly reachable".to_string()
+            )
+        })?;
+        
+        Ok(Satellite::from_state(norad_id, pos.into(), vel.into(), None))
     }
     
+    /// Equinoctial elements of the osculating orbit, defined for circular and equatorial orbits
+    fn to_equinoctial(&self) -> PyResult<EquinoctialElements> {
+        elements::equinoctial_from_state(&self.r(), &self.v())
+            .ok_or_else(|| OrbitError::InvalidState("State is not on a bound orbit".to_string()).into())
+    }
+
+    /// Build a satellite from equinoctial elements
+    #[staticmethod]
+    fn from_equinoctial(elements: &EquinoctialElements, norad_id: i32) -> PyResult<Satellite> {
+        let (pos, vel) = elements::equinoctial_to_state(elements).ok_or_else(|| {
+            OrbitError::InvalidState("Invalid elements: orbit must be bound (a > 0, h^2 + k^2 < 1)".to_string())
+        })?;
+        Ok(Satellite::from_state(norad_id, pos.into(), vel.into(), None))
+    }
+
     /// Get orbital speed (km/s)
     fn speed(&self) -> f64 {
-        let vel = Vector3::from_vec(self.velocity.clone());
-        vel.norm()
+        self.v().norm()
     }
     
     fn __repr__(&self) -> String {
-        format!(
-            "Satellite(norad_id={}, alt={:.1}km, speed={:.2}km/s)",
-            self.norad_id,
-            self.altitude(),
-            self.speed()
-        )
+        match &self.name {
+            Some(name) => format!(
+                "Satellite(norad_id={}, name='{}', alt={:.1}km, speed={:.2}km/s)",
+                self.norad_id,
+                name,
+                self.altitude(),
+                self.speed()
+            ),
+            None => format!(
+                "Satellite(norad_id={}, alt={:.1}km, speed={:.2}km/s)",
+                self.norad_id,
+                self.altitude(),
+                self.speed()
+            ),
+        }
     }
 }
 
 /// Conjunction event between two satellites
-#[pyclass]
-#[derive(Clone)]
+#[cfg_attr(feature = "python", pyclass(module = "orbit_core", get_all))]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Conjunction {
-    #[pyo3(get)]
     pub norad_id_1: i32,
     
-    #[pyo3(get)]
     pub norad_id_2: i32,
     
-    #[pyo3(get)]
     pub distance_km: f64,
     
-    #[pyo3(get)]
     pub relative_velocity_km_s: f64,
+    
+    #[serde(default)]
+    pub tca_jd: Option<f64>,  // time of closest approach (Julian date), if screened over time
+    
+    #[serde(default)]
+    pub probability: Option<f64>,  // collision probability, when covariance is available
+    
+    #[serde(default = "json::nan", with = "json::nan_as_null")]
+    pub radial_km: f64,  // miss vector (secondary - primary) in the primary's RTN frame
+    
+    #[serde(default = "json::nan", with = "json::nan_as_null")]
+    pub in_track_km: f64,
+    
+    #[serde(default = "json::nan", with = "json::nan_as_null")]
+    pub cross_track_km: f64,
+    
+    #[serde(default = "json::nan3", with = "json::nan_array_as_null")]
+    pub relative_velocity_rtn: [f64; 3],  // secondary - primary velocity in the primary's RTN frame (km/s)
+    
+    #[serde(default)]
+    pub state_1: Option<[f64; 6]>,  // primary [x, y, z, vx, vy, vz] (km, km/s) at TCA, in the screening frame
+    
+    #[serde(default)]
+    pub state_2: Option<[f64; 6]>,
+    
+    #[serde(default)]
+    pub time_to_tca_s: Option<f64>,  // seconds from the start of the screening window to TCA
+    
+    #[serde(default)]
+    pub intra_constellation: bool,  // both objects carry the same constellation tag
+    
+    #[serde(default)]
+    pub international_designator_1: Option<String>,  // COSPAR ids, when the screened objects carry them
+    
+    #[serde(default)]
+    pub international_designator_2: Option<String>,
 }
 
+impl Conjunction {
+    /// Build an event from both states; object 1 is the primary
+    pub(crate) fn from_states(
+        norad_id_1: i32,
+        norad_id_2: i32,
+        state_1: (&Vector3<f64>, &Vector3<f64>),
+        state_2: (&Vector3<f64>, &Vector3<f64>),
+        tca_jd: Option<f64>,
+    ) -> Self {
+        let miss = state_2.0 - state_1.0;
+        let relative_velocity = state_2.1 - state_1.1;
+        let row = |(r, v): (&Vector3<f64>, &Vector3<f64>)| [r.x, r.y, r.z, v.x, v.y, v.z];
+        // undefined for a primary with zero angular momentum
+        let [radial, in_track, cross_track] =
+            frames::to_rtn(state_1.0, state_1.1, &miss).unwrap_or([f64::NAN; 3]);
+        
+        Conjunction {
+            norad_id_1,
+            norad_id_2,
+            distance_km: miss.norm(),
+            relative_velocity_km_s: relative_velocity.norm(),
+            tca_jd,
+            probability: None,
+            radial_km: radial,
+            in_track_km: in_track,
+            cross_track_km: cross_track,
+            relative_velocity_rtn: frames::to_rtn(state_1.0, state_1.1, &relative_velocity).unwrap_or([f64::NAN; 3]),
+            state_1: Some(row(state_1)),
+            state_2: Some(row(state_2)),
+            time_to_tca_s: None,
+            intra_constellation: false,
+            international_designator_1: None,
+            international_designator_2: None,
+        }
+    }
+}
+
+impl Conjunction {
+    /// B-plane axes in the primary's RTN frame and the miss coordinates (B.T, B.R) in km
+    ///
+    /// S is along the relative velocity, T = S x N / |S x N| lies in the
+    /// primary's orbit plane and R = S x T. Falls back to the radial axis as
+    /// reference when the relative velocity is cross-track. None when the
+    /// RTN components are undefined or the relative velocity is zero.
+    pub fn b_plane(&self) -> Option<([Vector3<f64>; 3], [f64; 2])> {
+        let miss = Vector3::new(self.radial_km, self.in_track_km, self.cross_track_km);
+        let velocity = Vector3::from(self.relative_velocity_rtn);
+        if !miss.iter().chain(velocity.iter()).all(|x| x.is_finite()) || velocity.norm() == 0.0 {
+            return None;
+        }
+        let s = velocity.normalize();
+        let reference = if s.cross(&Vector3::z()).norm() > 1e-9 { Vector3::z() } else { Vector3::x() };
+        let t = s.cross(&reference).normalize();
+        let r = s.cross(&t);
+        Some(([s, t, r], [miss.dot(&t), miss.dot(&r)]))
+    }
+    
+    /// Angle between the two velocity vectors at TCA (degrees), when the states are known
+    ///
+    /// Near 0 for co-moving objects, near 180 for head-on approaches.
+    pub fn approach_angle_deg(&self) -> Option<f64> {
+        let (s1, s2) = (self.state_1?, self.state_2?);
+        let (v1, v2) = (Vector3::new(s1[3], s1[4], s1[5]), Vector3::new(s2[3], s2[4], s2[5]));
+        let norms = v1.norm() * v2.norm();
+        (norms > 0.0).then(|| (v1.dot(&v2) / norms).clamp(-1.0, 1.0).acos().to_degrees())
+    }
+    
+    #[cfg(feature = "python")]
+    #[allow(clippy::type_complexity)]
+    fn key(&self) -> (i32, i32, [u64; 12], [Option<[u64; 6]>; 2], [Option<&str>; 2]) {
+        let optional = |x: Option<f64>| x.map_or(u64::MAX, float_key);
+        let state = |s: Option<[f64; 6]>| s.map(|s| s.map(float_key));
+        (
+            self.norad_id_1,
+            self.norad_id_2,
+            [
+                float_key(self.distance_km),
+                float_key(self.relative_velocity_km_s),
+                optional(self.tca_jd),
+                optional(self.probability),
+                float_key(self.radial_km),
+                float_key(self.in_track_km),
+                float_key(self.cross_track_km),
+                float_key(self.relative_velocity_rtn[0]),
+                float_key(self.relative_velocity_rtn[1]),
+                float_key(self.relative_velocity_rtn[2]),
+                optional(self.time_to_tca_s),
+                u64::from(self.intra_constellation),
+            ],
+            [state(self.state_1), state(self.state_2)],
+            [self.international_designator_1.as_deref(), self.international_designator_2.as_deref()],
+        )
+    }
+}
+
+#[cfg(feature = "python")]
 #[pymethods]
 impl Conjunction {
+    #[new]
+    #[pyo3(signature = (
+        norad_id_1,
+        norad_id_2,
+        distance_km,
+        relative_velocity_km_s,
+        tca_jd = None,
+        probability = None,
+        radial_km = f64::NAN,
+        in_track_km = f64::NAN,
+        cross_track_km = f64::NAN,
+        relative_velocity_rtn = [f64::NAN; 3],
+        state_1 = None,
+        state_2 = None,
+        time_to_tca_s = None,
+        intra_constellation = false,
+        international_designator_1 = None,
+        international_designator_2 = None
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        norad_id_1: i32,
+        norad_id_2: i32,
+        distance_km: f64,
+        relative_velocity_km_s: f64,
+        tca_jd: Option<f64>,
+        probability: Option<f64>,
+        radial_km: f64,
+        in_track_km: f64,
+        cross_track_km: f64,
+        relative_velocity_rtn: [f64; 3],
+        state_1: Option<[f64; 6]>,
+        state_2: Option<[f64; 6]>,
+        time_to_tca_s: Option<f64>,
+        intra_constellation: bool,
+        international_designator_1: Option<&str>,
+        international_designator_2: Option<&str>,
+    ) -> PyResult<Self> {
+        Ok(Conjunction {
+            norad_id_1,
+            norad_id_2,
+            distance_km,
+            relative_velocity_km_s,
+            tca_jd,
+            probability,
+            radial_km,
+            in_track_km,
+            cross_track_km,
+            relative_velocity_rtn,
+            state_1,
+            state_2,
+            time_to_tca_s,
+            intra_constellation,
+            international_designator_1: check_designator(international_designator_1)?,
+            international_designator_2: check_designator(international_designator_2)?,
+        })
+    }
+    
+    #[setter]
+    fn set_probability(&mut self, value: Option<f64>) {
+        self.probability = value;
+    }
+    
+    /// Five-character catalog number of object 1 ("25544", Alpha-5 "A0001"), or None above 339999
+    #[getter]
+    fn alpha5_1(&self) -> Option<String> {
+        designator::format_alpha5(self.norad_id_1).ok()
+    }
+    
+    /// Five-character catalog number of object 2
+    #[getter]
+    fn alpha5_2(&self) -> Option<String> {
+        designator::format_alpha5(self.norad_id_2).ok()
+    }
+    
+    /// Miss components (radial, in-track, cross-track) in km
+    fn rtn(&self) -> (f64, f64, f64) {
+        (self.radial_km, self.in_track_km, self.cross_track_km)
+    }
+    
+    /// Angle between the velocity vectors at TCA (degrees), or None without states
+    #[getter(approach_angle_deg)]
+    fn py_approach_angle_deg(&self) -> Option<f64> {
+        self.approach_angle_deg()
+    }
+    
+    /// Both objects at TCA as satellites (primary, secondary), or None without states
+    ///
+    /// The satellites carry the TCA as epoch, so they can be propagated onwards directly.
+    fn states_at_tca(&self) -> Option<(Satellite, Satellite)> {
+        let epoch = self.tca_jd.map(Epoch::from_jd_utc);
+        let satellite = |norad_id: i32, s: [f64; 6], designator: &Option<String>| Satellite {
+            international_designator: designator.clone(),
+            ..Satellite::from_state(norad_id, [s[0], s[1], s[2]], [s[3], s[4], s[5]], epoch)
+        };
+        Some((
+            satellite(self.norad_id_1, self.state_1?, &self.international_designator_1),
+            satellite(self.norad_id_2, self.state_2?, &self.international_designator_2),
+        ))
+    }
+    
+    /// Miss coordinates (B.T, B.R) in the B-plane (km), or None if undefined
+    #[getter(b_plane)]
+    fn py_b_plane(&self) -> Option<(f64, f64)> {
+        self.b_plane().map(|(_, [b_t, b_r])| (b_t, b_r))
+    }
+    
+    /// B-plane unit axes (S, T, R) as RTN components of the primary, or None if undefined
+    fn b_plane_axes(&self) -> Option<[[f64; 3]; 3]> {
+        self.b_plane().map(|(axes, _)| axes.map(|axis| [axis.x, axis.y, axis.z]))
+    }
+    
+    /// JSON object with every field (NaN RTN components as null)
+    #[pyo3(signature = (pretty = false))]
+    fn to_json(&self, pretty: bool) -> PyResult<String> {
+        json::to_json(self, pretty)
+    }
+    
+    /// Conjunction from `to_json` output
+    #[staticmethod]
+    fn from_json(text: &str) -> PyResult<Self> {
+        json::from_json(text)
+    }
+    
+    fn __richcmp__(&self, other: &PyAny, op: CompareOp, py: Python<'_>) -> PyObject {
+        let Ok(other) = other.extract::<PyRef<Conjunction>>() else {
+            return py.NotImplemented();
+        };
+        match op {
+            CompareOp::Eq => (self.key() == other.key()).into_py(py),
+            CompareOp::Ne => (self.key() != other.key()).into_py(py),
+            _ => py.NotImplemented(),
+        }
+    }
+    
+    fn __hash__(&self) -> u64 {
+        hash_key(&self.key())
+    }
+    
+    /// Pickle support: rebuild through the constructor
+    fn __reduce__(&self, py: Python<'_>) -> (PyObject, PyObject) {
+        // more fields than pyo3 converts as one tuple
+        let args = [
+            self.norad_id_1.into_py(py),
+            self.norad_id_2.into_py(py),
+            self.distance_km.into_py(py),
+            self.relative_velocity_km_s.into_py(py),
+            self.tca_jd.into_py(py),
+            self.probability.into_py(py),
+            self.radial_km.into_py(py),
+            self.in_track_km.into_py(py),
+            self.cross_track_km.into_py(py),
+            self.relative_velocity_rtn.into_py(py),
+            self.state_1.into_py(py),
+            self.state_2.into_py(py),
+            self.time_to_tca_s.into_py(py),
+            self.intra_constellation.into_py(py),
+            self.international_designator_1.clone().into_py(py),
+            self.international_designator_2.clone().into_py(py),
+        ];
+        (py.get_type::<Conjunction>().into(), PyTuple::new(py, args).into())
+    }
+    
     fn __repr__(&self) -> String {
-        format!(
-            "Conjunction({} ↔ {}, dist={:.2}km, rel_vel={:.2}km/s)",
-            self.norad_id_1, self.norad_id_2, self.distance_km, self.relative_velocity_km_s
-        )
+        match self.tca_jd {
+            Some(tca) => format!(
+                "Conjunction({} ↔ {}, dist={:.2}km, rel_vel={:.2}km/s, tca_jd={:.6})",
+                self.norad_id_1, self.norad_id_2, self.distance_km, self.relative_velocity_km_s, tca
+            ),
+            None => format!(
+                "Conjunction({} ↔ {}, dist={:.2}km, rel_vel={:.2}km/s)",
+                self.norad_id_1, self.norad_id_2, self.distance_km, self.relative_velocity_km_s
+            ),
+        }
+    }
+}
+
+/// Ordering applied to conjunction lists returned to Python
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SortOrder {
+    /// By (norad_id_1, norad_id_2), then TCA (default)
+    #[default]
+    Ids,
+    /// By miss distance, ties broken by ids
+    Distance,
+    /// By TCA, ties broken by ids
+    Tca,
+    /// Leave the order in which workers produced the events
+    Unsorted,
+}
+
+impl std::str::FromStr for SortOrder {
+    type Err = String;
+    
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "ids" | "id" => Ok(SortOrder::Ids),
+            "distance" => Ok(SortOrder::Distance),
+            "tca" => Ok(SortOrder::Tca),
+            "none" => Ok(SortOrder::Unsorted),
+            _ => Err(format!("Unknown sort order '{}' (expected ids, distance, tca or none)", s)),
+        }
+    }
+}
+
+/// Sort conjunctions in place; the full key makes the result independent of thread count
+pub fn sort_conjunctions(conjunctions: &mut [Conjunction], order: SortOrder) {
+    let ids = |c: &Conjunction| (c.norad_id_1, c.norad_id_2);
+    let tca = |c: &Conjunction| c.tca_jd.unwrap_or(f64::NEG_INFINITY);
+    match order {
+        SortOrder::Ids => conjunctions.sort_by(|a, b| {
+            ids(a).cmp(&ids(b)).then(tca(a).total_cmp(&tca(b))).then(a.distance_km.total_cmp(&b.distance_km))
+        }),
+        SortOrder::Distance => conjunctions.sort_by(closest_first),
+        SortOrder::Tca => conjunctions.sort_by(|a, b| {
+            tca(a).total_cmp(&tca(b)).then(ids(a).cmp(&ids(b))).then(a.distance_km.total_cmp(&b.distance_km))
+        }),
+        SortOrder::Unsorted => {}
+    }
+}
+
+/// Distance order, ties broken by ids then TCA
+fn closest_first(a: &Conjunction, b: &Conjunction) -> std::cmp::Ordering {
+    let tca = |c: &Conjunction| c.tca_jd.unwrap_or(f64::NEG_INFINITY);
+    a.distance_km
+        .total_cmp(&b.distance_km)
+        .then((a.norad_id_1, a.norad_id_2).cmp(&(b.norad_id_1, b.norad_id_2)))
+        .then(tca(a).total_cmp(&tca(b)))
+}
+
+/// Filtering and truncation of a result list before it crosses to Python
+///
+/// Events closer than `min_separation_km` are dropped first (docked or
+/// co-located objects), then only the `top_k_by_distance` closest are kept;
+/// the survivors are sorted and cut to `max_results`. The default keeps
+/// everything.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ResultLimits {
+    pub max_results: Option<usize>,
+    pub top_k_by_distance: Option<usize>,
+    pub min_separation_km: Option<f64>,
+}
+
+impl ResultLimits {
+    pub fn validate(&self) -> Result<(), String> {
+        match self.min_separation_km {
+            Some(min) if !(min.is_finite() && min >= 0.0) => {
+                Err(format!("min_separation_km must be finite and non-negative, got {}", min))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Apply the limits and sort the survivors in `order`
+    pub fn apply(&self, mut conjunctions: Vec<Conjunction>, order: SortOrder) -> Vec<Conjunction> {
+        if let Some(min) = self.min_separation_km {
+            conjunctions.retain(|c| c.distance_km >= min);
+        }
+        if let Some(k) = self.top_k_by_distance {
+            if k < conjunctions.len() {
+                // linear-time selection; the full key keeps the chosen set independent of input order
+                if k > 0 {
+                    conjunctions.select_nth_unstable_by(k - 1, closest_first);
+                }
+                conjunctions.truncate(k);
+            }
+        }
+        sort_conjunctions(&mut conjunctions, order);
+        if let Some(n) = self.max_results {
+            conjunctions.truncate(n);
+        }
+        conjunctions
     }
 }
 
+/// Parse a `sort_by` argument and sort
+#[cfg(feature = "python")]
+pub(crate) fn sorted(mut conjunctions: Vec<Conjunction>, sort_by: &str) -> PyResult<Vec<Conjunction>> {
+    let order: SortOrder = sort_by.parse().map_err(pyo3::exceptions::PyValueError::new_err)?;
+    sort_conjunctions(&mut conjunctions, order);
+    Ok(conjunctions)
+}
+
 /// Find all close approaches between satellites (parallelized)
+///
+/// Candidates are pre-filtered with a uniform grid whose cell edge equals
+/// the threshold, so only objects in neighbouring cells are compared. With
+/// `apogee_perigee_filter` enabled, pairs whose perigee/apogee bands are
+/// further apart than the threshold are skipped before any distance check.
+///
+/// The threshold applies between object surfaces: each pair is widened by
+/// its combined hard-body radius. When either object carries a covariance
+/// and the combined radius is positive, the event's Pc is filled in.
+/// `threshold_km` may also be a `ScreeningVolume`, an ellipsoid in the RTN
+/// frame of the lower-index object of each pair.
+///
+/// `precision="mixed"` runs the coarse distance filter in single precision
+/// (with a rounding margin, so no pair is lost) and only the survivors in
+/// double precision; results are identical to the default "f64".
+///
+/// `sort_by` is "ids" (default), "distance", "tca" or "none". The GIL is
+/// released while screening, so other Python threads keep running.
+///
+/// Pairs of satellites with the same `constellation` tag are flagged
+/// `intra_constellation`; `constellation_pairs` is "include" (default),
+/// "exclude" to drop them or "only" to keep nothing else. `exclude_pairs`
+/// lists (norad_id_1, norad_id_2) pairs never to report, in either order;
+/// with a `watch_list` of NORAD ids only pairs involving one of them are kept.
+///
+/// Large result lists can be cut down before they reach Python:
+/// `min_separation_km` drops events closer than that (docked or co-located
+/// objects), `top_k_by_distance` keeps only the K closest of the rest, and
+/// `max_results` truncates the sorted list.
+///
+/// With `validate=True` (default) a NaN/infinite or zero-norm state raises
+/// `InvalidStateError` instead of producing meaningless distances.
+#[cfg(feature = "python")]
 #[pyfunction]
-fn find_conjunctions(satellites: Vec<Satellite>, threshold_km: f64) -> PyResult<Vec<Conjunction>> {
-    if satellites.is_empty() {
+#[pyo3(signature = (
+    satellites,
+    threshold_km,
+    apogee_perigee_filter = false,
+    sort_by = "ids",
+    precision = "f64",
+    validate = true,
+    constellation_pairs = "include",
+    exclude_pairs = None,
+    watch_list = None,
+    max_results = None,
+    top_k_by_distance = None,
+    min_separation_km = None
+))]
+#[pyo3(name = "find_conjunctions")]
+#[allow(clippy::too_many_arguments)]
+fn py_find_conjunctions(
+    py: Python<'_>,
+    satellites: Vec<Satellite>,
+    threshold_km: &PyAny,
+    apogee_perigee_filter: bool,
+    sort_by: &str,
+    precision: &str,
+    validate: bool,
+    constellation_pairs: &str,
+    exclude_pairs: Option<Vec<(i32, i32)>>,
+    watch_list: Option<Vec<i32>>,
+    max_results: Option<usize>,
+    top_k_by_distance: Option<usize>,
+    min_separation_km: Option<f64>,
+) -> PyResult<Vec<Conjunction>> {
+    let precision: Precision = precision.parse().map_err(pyo3::exceptions::PyValueError::new_err)?;
+    let order: SortOrder = sort_by.parse().map_err(pyo3::exceptions::PyValueError::new_err)?;
+    let pairs: ConstellationPairs = constellation_pairs.parse().map_err(pyo3::exceptions::PyValueError::new_err)?;
+    let selection = PairSelection::new(exclude_pairs.unwrap_or_default(), watch_list);
+    let limits = ResultLimits { max_results, top_k_by_distance, min_separation_km };
+    limits.validate().map_err(pyo3::exceptions::PyValueError::new_err)?;
+    if validate {
+        check_states(&satellites)?;
+    }
+    let volume = volume::extract_threshold(threshold_km)?;
+    let options = ScreenOptions { apogee_perigee_filter, precision, order, constellation_pairs: pairs, selection, limits };
+    py.allow_threads(|| Ok(screen_satellites(&satellites, volume, &options)?))
+}
+
+/// All-vs-all snapshot screen with Python's `find_conjunctions` defaults
+///
+/// States are validated, must share an epoch (within a millisecond) and the
+/// events come back sorted by ids; see `screen_satellites` for the options.
+pub fn find_conjunctions(
+    satellites: &[Satellite],
+    volume: impl Into<ScreeningVolume>,
+) -> Result<Vec<Conjunction>, OrbitError> {
+    check_states(satellites)?;
+    screen_satellites(satellites, volume, &ScreenOptions::default())
+}
+
+/// Options of `screen_satellites`; the default keeps every pair, sorted by ids
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ScreenOptions {
+    pub apogee_perigee_filter: bool,
+    pub precision: Precision,
+    pub order: SortOrder,
+    pub constellation_pairs: ConstellationPairs,
+    pub selection: PairSelection,
+    pub limits: ResultLimits,
+}
+
+/// All-vs-all snapshot screening behind `find_conjunctions`
+pub fn screen_satellites(
+    satellites: &[Satellite],
+    volume: impl Into<ScreeningVolume>,
+    options: &ScreenOptions,
+) -> Result<Vec<Conjunction>, OrbitError> {
+    let volume = volume.into();
+    if satellites.is_empty() || volume.is_empty() {
         return Ok(Vec::new());
     }
+    check_common_epoch(satellites)?;
     
-    // Parallel conjunction detection using Rayon
-    let conjunctions: Vec<Conjunction> = (0..satellites.len())
-        .into_par_iter()
-        .flat_map(|i| {
-            (i + 1..satellites.len())
-                .filter_map(|j| {
-                    let sat1 = &satellites[i];
-                    let sat2 = &satellites[j];
-                    let dist = sat1.distance_to(sat2);
-                    
-                    if dist < threshold_km {
-                        Some(Conjunction {
-                            norad_id_1: sat1.norad_id,
-                            norad_id_2: sat2.norad_id,
-                            distance_km: dist,
-                            relative_velocity_km_s: sat1.relative_velocity(sat2),
-                        })
-                    } else {
-                        None
-                    }
-                })
-                .collect::<Vec<_>>()
+    let positions: Vec<[f64; 3]> = satellites.iter().map(|sat| sat.position).collect();
+    let velocities: Vec<[f64; 3]> = satellites.iter().map(|sat| sat.velocity).collect();
+    let radii: Vec<f64> = satellites.iter().map(Satellite::collision_radius).collect();
+    
+    let conjunctions: Vec<Conjunction> = parallel::install(|| {
+        close_pairs(&positions, &velocities, &radii, volume, options.apogee_perigee_filter, options.precision)
+            .into_par_iter()
+            .filter(|&(i, j)| options.selection.keeps(satellites[i].norad_id, satellites[j].norad_id))
+            .filter(|&(i, j)| options.constellation_pairs.keeps(satellites[i].same_constellation(&satellites[j])))
+            .map(|(i, j)| satellite_conjunction(&satellites[i], &satellites[j]))
+            .collect()
+    });
+    Ok(options.limits.apply(conjunctions, options.order))
+}
+
+/// Snapshot conjunction between two satellites, with Pc when a covariance is known
+///
+/// Pc uses the combined hard-body radius and is left unset for point objects.
+pub(crate) fn satellite_conjunction(sat1: &Satellite, sat2: &Satellite) -> Conjunction {
+    let (r1, v1) = (sat1.r(), sat1.v());
+    let (r2, v2) = (sat2.r(), sat2.v());
+    let mut conjunction = Conjunction::from_states(sat1.norad_id, sat2.norad_id, (&r1, &v1), (&r2, &v2), None);
+    conjunction.intra_constellation = sat1.same_constellation(sat2);
+    conjunction.international_designator_1 = sat1.international_designator.clone();
+    conjunction.international_designator_2 = sat2.international_designator.clone();
+    
+    let combined_cov = match (&sat1.covariance, &sat2.covariance) {
+        (Some(a), Some(b)) => Some(covariance::position_block(a) + covariance::position_block(b)),
+        (Some(a), None) | (None, Some(a)) => Some(covariance::position_block(a)),
+        (None, None) => None,
+    };
+    let radius = sat1.collision_radius() + sat2.collision_radius();
+    if let Some(cov) = combined_cov.filter(|_| radius > 0.0) {
+        conjunction.probability = probability::foster_pc(&(r2 - r1), &(v2 - v1), &cov, radius);
+    }
+    conjunction
+}
+
+/// Maximum epoch spread (seconds) tolerated between states compared directly
+const EPOCH_TOLERANCE_S: f64 = 1.0e-3;
+
+/// Reject snapshots whose states refer to different instants
+///
+/// States without an epoch are assumed to be simultaneous with the rest.
+fn check_common_epoch(satellites: &[Satellite]) -> Result<(), OrbitError> {
+    let mut epochs = satellites.iter().filter_map(|sat| sat.epoch);
+    if let Some(first) = epochs.next() {
+        if let Some(other) = epochs.find(|e| e.seconds_since(&first).abs() > EPOCH_TOLERANCE_S) {
+            return Err(OrbitError::InvalidState(format!(
+                "Satellites have different epochs ({} vs {} UTC); propagate to a common epoch first",
+                epoch::format_iso(first.mjd_in(epoch::TimeScale::Utc)),
+                epoch::format_iso(other.mjd_in(epoch::TimeScale::Utc))
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Reject a state with NaN/infinite components or a zero position vector
+///
+/// `strict` also rejects positions below the WGS-84 ellipsoid. Geodetic
+/// altitude does not depend on Earth rotation, so the inertial position is
+/// used as is.
+pub fn check_state(norad_id: i32, position: &[f64; 3], velocity: &[f64; 3], strict: bool) -> Result<(), OrbitError> {
+    if !position.iter().chain(velocity).all(|x| x.is_finite()) {
+        return Err(OrbitError::InvalidState(format!("Object {} has a NaN or infinite state component", norad_id)));
+    }
+    let r = Vector3::from(*position);
+    if r.norm() == 0.0 {
+        return Err(OrbitError::InvalidState(format!("Object {} has a zero position vector", norad_id)));
+    }
+    let (_, _, altitude) = frames::ecef_to_geodetic_rad(&r);
+    if strict && altitude < 0.0 {
+        return Err(OrbitError::InvalidState(format!(
+            "Object {} is {:.3} km below the Earth's surface",
+            norad_id, -altitude
+        )));
+    }
+    Ok(())
+}
+
+/// Non-strict `check_state` over a batch, behind the `validate` flags
+pub(crate) fn check_states(satellites: &[Satellite]) -> Result<(), OrbitError> {
+    for sat in satellites {
+        check_state(sat.norad_id, &sat.position, &sat.velocity, false)?;
+    }
+    Ok(())
+}
+
+/// Grid-filtered conjunction search over plain state arrays (point objects)
+///
+/// Results are unsorted; see `sort_conjunctions`.
+pub fn conjunctions_from_states(
+    ids: &[i32],
+    positions: &[[f64; 3]],
+    velocities: &[[f64; 3]],
+    volume: impl Into<ScreeningVolume>,
+    apogee_perigee_filter: bool,
+    precision: Precision,
+) -> Vec<Conjunction> {
+    close_pairs(positions, velocities, &[], volume, apogee_perigee_filter, precision)
+        .into_iter()
+        .map(|(i, j)| {
+            Conjunction::from_states(
+                ids[i],
+                ids[j],
+                (&Vector3::from(positions[i]), &Vector3::from(velocities[i])),
+                (&Vector3::from(positions[j]), &Vector3::from(velocities[j])),
+                None,
+            )
         })
-        .collect();
+        .collect()
+}
+
+/// Index pairs `(i, j)`, `i < j`, inside the screening volume padded by their radii
+///
+/// `radii` holds per-object hard-body radii (km); an empty slice treats
+/// every object as a point.
+pub(crate) fn close_pairs(
+    positions: &[[f64; 3]],
+    velocities: &[[f64; 3]],
+    radii: &[f64],
+    volume: impl Into<ScreeningVolume>,
+    apogee_perigee_filter: bool,
+    precision: Precision,
+) -> Vec<(usize, usize)> {
+    let volume = volume.into();
+    if positions.is_empty() || volume.is_empty() {
+        return Vec::new();
+    }
+    parallel::install(|| {
+        let index = PairIndex::build(positions, velocities, radii, volume, apogee_perigee_filter, precision);
+        
+        // Parallel conjunction detection using Rayon
+        (0..positions.len())
+            .into_par_iter()
+            .flat_map(|i| index.partners(i).into_iter().map(|j| (i, j)).collect::<Vec<_>>())
+            .collect()
+    })
+}
+
+/// Arithmetic used for the coarse candidate distance check
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Precision {
+    /// Every distance in f64 (default)
+    #[default]
+    Double,
+    /// Coarse sphere test in f32, exact volume test in f64
+    Mixed,
+}
+
+impl std::str::FromStr for Precision {
+    type Err = String;
     
-    Ok(conjunctions)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "f64" | "double" => Ok(Precision::Double),
+            "mixed" | "f32" => Ok(Precision::Mixed),
+            _ => Err(format!("Unknown precision '{}' (expected f64 or mixed)", s)),
+        }
+    }
 }
 
-/// Calculate pairwise distances between all satellites
-#[pyfunction]
-fn pairwise_distances(satellites: Vec<Satellite>) -> PyResult<Vec<Vec<f64>>> {
-    let n = satellites.len();
-    let mut distances = vec![vec![0.0; n]; n];
+/// Single-precision copy of the positions for the coarse filter
+///
+/// Rounding each coordinate to f32 moves a point by at most
+/// sqrt(3) * 2^-24 * |r|, so distances between two points are off by less
+/// than `margin_km`; widening the coarse bound by it keeps every true pair.
+struct CoarsePositions {
+    positions: SoaPositions<f32>,
+    margin_km: f64,
+}
+
+impl CoarsePositions {
+    fn build(positions: &[[f64; 3]]) -> Self {
+        let max_norm = positions.iter().map(|p| Vector3::from(*p).norm()).fold(0.0, f64::max);
+        CoarsePositions {
+            positions: SoaPositions::from_rows(positions),
+            margin_km: 4.0 * f32::EPSILON as f64 * max_norm,
+        }
+    }
     
-    for i in 0..n {
-        for j in (i + 1)..n {
-            let dist = satellites[i].distance_to(&satellites[j]);
-            distances[i][j] = dist;
-            distances[j][i] = dist;
+    /// Drop candidates `j` that are certainly further from `i` than `limit(j)`
+    fn retain_within(&self, i: usize, candidates: &mut Vec<usize>, limit: impl Fn(usize) -> f64) {
+        let mut d2 = Vec::with_capacity(candidates.len());
+        self.positions.gather_squared_distances(self.positions.point(i), candidates, &mut d2);
+        *candidates = candidates
+            .iter()
+            .zip(&d2)
+            .filter(|&(&j, &d2)| {
+                let bound = (limit(j) + self.margin_km) as f32;
+                d2 <= bound * bound * (1.0 + 8.0 * f32::EPSILON)
+            })
+            .map(|(&j, _)| j)
+            .collect();
+    }
+}
+
+/// Grid and apsis bands for answering "which objects are close to object i"
+pub(crate) struct PairIndex {
+    positions: Vec<[f64; 3]>,
+    velocities: Vec<[f64; 3]>,
+    radii: Vec<f64>,
+    volume: ScreeningVolume,
+    bands: Option<Vec<(f64, f64)>>,
+    coarse: Option<CoarsePositions>,
+    grid: UniformGrid,
+}
+
+impl PairIndex {
+    pub(crate) fn build(
+        positions: &[[f64; 3]],
+        velocities: &[[f64; 3]],
+        radii: &[f64],
+        volume: ScreeningVolume,
+        apogee_perigee_filter: bool,
+        precision: Precision,
+    ) -> Self {
+        let max_radius = radii.iter().copied().fold(0.0, f64::max);
+        let bands = apogee_perigee_filter.then(|| {
+            (0..positions.len())
+                .into_par_iter()
+                .map(|i| sieve::perigee_apogee(&Vector3::from(positions[i]), &Vector3::from(velocities[i])))
+                .collect()
+        });
+        
+        PairIndex {
+            positions: positions.to_vec(),
+            velocities: velocities.to_vec(),
+            radii: radii.to_vec(),
+            volume,
+            bands,
+            coarse: (precision == Precision::Mixed).then(|| CoarsePositions::build(positions)),
+            grid: UniformGrid::build(positions, volume.reach() + volume.pad(max_radius, max_radius)),
         }
     }
     
-    Ok(distances)
+    #[cfg(feature = "python")]
+    pub(crate) fn len(&self) -> usize {
+        self.positions.len()
+    }
+    
+    fn radius(&self, k: usize) -> f64 {
+        self.radii.get(k).copied().unwrap_or(0.0)
+    }
+    
+    fn pad(&self, i: usize, j: usize) -> f64 {
+        self.volume.pad(self.radius(i), self.radius(j))
+    }
+    
+    /// Sorted indices `j > i` inside the volume (padded by both radii) around object `i`
+    pub(crate) fn partners(&self, i: usize) -> Vec<usize> {
+        let mut candidates: Vec<usize> = self
+            .grid
+            .neighbors(&self.positions[i])
+            .into_iter()
+            .filter(|&j| j > i)
+            .filter(|&j| {
+                let limit = self.volume.reach() + self.pad(i, j);
+                self.bands.as_ref().is_none_or(|bands| sieve::apsis_bands_overlap(bands[i], bands[j], limit))
+            })
+            .collect();
+        candidates.sort_unstable();
+        if let Some(coarse) = &self.coarse {
+            coarse.retain_within(i, &mut candidates, |j| self.volume.reach() + self.pad(i, j));
+        }
+        
+        let primary = (Vector3::from(self.positions[i]), Vector3::from(self.velocities[i]));
+        candidates.retain(|&j| {
+            self.volume.contains((&primary.0, &primary.1), &Vector3::from(self.positions[j]), self.pad(i, j))
+        });
+        candidates
+    }
+}
+
+/// Calculate pairwise distances between all satellites (parallelized)
+///
+/// Returns a read-only float64 `Column`: the symmetric N x N matrix, or with
+/// `condensed=True` the upper triangle row by row (the N(N-1)/2 layout of
+/// `scipy.spatial.distance.squareform`). `numpy.asarray` views it without
+/// copying; indexing and `to_list()` work without numpy. With `squared=True`
+/// the square root is skipped.
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(signature = (satellites, condensed = false, squared = false))]
+fn pairwise_distances(py: Python<'_>, satellites: Vec<Satellite>, condensed: bool, squared: bool) -> columnar::Column {
+    py.allow_threads(|| distance_matrix(&satellites, condensed, squared))
+}
+
+/// Distance matrix behind `pairwise_distances`
+#[cfg(feature = "python")]
+fn distance_matrix(satellites: &[Satellite], condensed: bool, squared: bool) -> columnar::Column {
+    let n = satellites.len();
+    let positions = SoaPositions::<f64>::from_rows(&satellites.iter().map(|sat| sat.position).collect::<Vec<_>>());
+    
+    parallel::install(|| {
+        let mut values = vec![0.0; if condensed { n * n.saturating_sub(1) / 2 } else { n * n }];
+        // split the output into per-row slices, each filled by the SoA kernel
+        let mut rows: Vec<(usize, &mut [f64])> = Vec::with_capacity(n);
+        let mut rest = values.as_mut_slice();
+        for i in 0..n {
+            let (row, tail) = rest.split_at_mut(if condensed { n - i - 1 } else { n });
+            rows.push((i, row));
+            rest = tail;
+        }
+        rows.into_par_iter().for_each(|(i, row)| {
+            // (a - b) and (b - a) square to identical values, so the matrix is exactly symmetric
+            positions.squared_distances(positions.point(i), if condensed { i + 1 } else { 0 }, row);
+            if !squared {
+                row.iter_mut().for_each(|d2| *d2 = d2.sqrt());
+            }
+        });
+        if condensed {
+            columnar::Column::float64(values)
+        } else {
+            columnar::Column::matrix(values, n, n)
+        }
+    })
 }
 
 /// Find closest approach for each satellite
+#[cfg(feature = "python")]
 #[pyfunction]
-fn find_closest_approaches(satellites: Vec<Satellite>) -> PyResult<Vec<(i32, i32, f64)>> {
+fn find_closest_approaches(py: Python<'_>, satellites: Vec<Satellite>) -> PyResult<Vec<(i32, i32, f64)>> {
     if satellites.len() < 2 {
         return Ok(Vec::new());
     }
     
-    let results: Vec<(i32, i32, f64)> = (0..satellites.len())
-        .into_par_iter()
-        .map(|i| {
-            let mut min_dist = f64::MAX;
-            let mut closest_idx = 0;
-            
-            for j in 0..satellites.len() {
-                if i != j {
-                    let dist = satellites[i].distance_to(&satellites[j]);
-                    if dist < min_dist {
-                        min_dist = dist;
-                        closest_idx = j;
-                    }
-                }
-            }
-            
-            (
-                satellites[i].norad_id,
-                satellites[closest_idx].norad_id,
-                min_dist,
-            )
-        })
-        .collect();
+    let positions = SoaPositions::<f64>::from_rows(&satellites.iter().map(|sat| sat.position).collect::<Vec<_>>());
+    let results: Vec<(i32, i32, f64)> = py.allow_threads(|| parallel::install(|| {
+        (0..satellites.len())
+            .into_par_iter()
+            .map(|i| {
+                let (closest, d2) = positions.nearest(i).expect("at least two satellites");
+                (satellites[i].norad_id, satellites[closest].norad_id, d2.sqrt())
+            })
+            .collect()
+    }));
     
     Ok(results)
 }
 
 /// Python module definition
+#[cfg(feature = "python")]
 #[pymodule]
-fn orbit_core(_py: Python, m: &PyModule) -> PyResult<()> {
+fn orbit_core(py: Python, m: &PyModule) -> PyResult<()> {
     m.add_class::<Satellite>()?;
     m.add_class::<Conjunction>()?;
-    m.add_function(wrap_pyfunction!(find_conjunctions, m)?)?;
+    m.add_class::<KeplerianElements>()?;
+    m.add_class::<EquinoctialElements>()?;
+    m.add_class::<Epoch>()?;
+    m.add_class::<MeanElements>()?;
+    m.add_class::<Sgp4Propagator>()?;
+    m.add_class::<ephemeris::Ephemeris>()?;
+    m.add_class::<ground_station::GroundStation>()?;
+    m.add_class::<ground_station::Pass>()?;
+    m.add_class::<sensor::Sensor>()?;
+    m.add_class::<sensor::Access>()?;
+    m.add_class::<maneuver::ManeuverOption>()?;
+    m.add_class::<maneuvers::Transfer>()?;
+    m.add_class::<maneuver_detection::DetectedManeuver>()?;
+    m.add_class::<maneuver_trade::ManeuverOutcome>()?;
+    m.add_class::<launch_cola::LiftoffScreen>()?;
+    m.add_class::<launch_cola::LaunchBlackout>()?;
+    m.add_class::<launch_cola::LaunchColaReport>()?;
+    m.add_class::<hygiene::TleHygieneReport>()?;
+    m.add_class::<monte_carlo::MonteCarloPc>()?;
+    m.add_class::<screening::ScreeningPipeline>()?;
+    m.add_class::<stream::ConjunctionStream>()?;
+    m.add_class::<events::ConjunctionEvent>()?;
+    m.add_class::<catalog::Catalog>()?;
+    m.add_class::<catalog::CatalogDiff>()?;
+    m.add_class::<catalog::ElementChange>()?;
+    m.add_class::<geo::GeoEncounter>()?;
+    m.add_class::<geo::BoxViolation>()?;
+    m.add_class::<geo::StationKeepingReport>()?;
+    m.add_class::<flux::SpatialDensity>()?;
+    m.add_class::<flux::FluxEstimate>()?;
+    m.add_class::<od::OrbitFit>()?;
+    m.add_class::<ekf::EkfTracker>()?;
+    m.add_class::<ekf::EkfUpdate>()?;
+    m.add_class::<decay::ReentryPrediction>()?;
+    m.add_class::<decay::OrbitLifetime>()?;
+    m.add_class::<orbit_design::RepeatGroundTrack>()?;
+    m.add_class::<density::SpaceWeather>()?;
+    m.add_class::<columnar::Column>()?;
+    m.add_class::<risk::RiskScorer>()?;
+    m.add_class::<risk::RiskScore>()?;
+    m.add_class::<graph::ConjunctionGraph>()?;
+    m.add_class::<graph::ThreatenedObject>()?;
+    m.add_class::<volume::ScreeningVolume>()?;
+    m.add_class::<progress::CancellationToken>()?;
+    #[cfg(feature = "sqlite")]
+    m.add_class::<store::CatalogStore>()?;
+    m.add_function(wrap_pyfunction!(py_find_conjunctions, m)?)?;
+    m.add_function(wrap_pyfunction!(stream::iter_conjunctions, m)?)?;
+    m.add_function(wrap_pyfunction!(catalog::screen_against_catalog, m)?)?;
+    m.add_function(wrap_pyfunction!(geo::py_screen_geo, m)?)?;
+    m.add_function(wrap_pyfunction!(geo::py_check_station_keeping, m)?)?;
+    m.add_function(wrap_pyfunction!(decay::py_predict_reentry, m)?)?;
+    m.add_function(wrap_pyfunction!(decay::py_orbit_lifetime, m)?)?;
+    m.add_function(wrap_pyfunction!(decay::py_orbit_lifetime_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(gabbard::gabbard_diagram, m)?)?;
+    m.add_function(wrap_pyfunction!(gabbard::py_fragment_evolution, m)?)?;
+    m.add_function(wrap_pyfunction!(density::atmospheric_density, m)?)?;
+    m.add_function(wrap_pyfunction!(celestial::py_sun_position, m)?)?;
+    m.add_function(wrap_pyfunction!(celestial::py_moon_position, m)?)?;
+    m.add_function(wrap_pyfunction!(eclipse::eclipse_state, m)?)?;
+    m.add_function(wrap_pyfunction!(eclipse::eclipse_states, m)?)?;
+    m.add_function(wrap_pyfunction!(visibility::py_line_of_sight, m)?)?;
+    m.add_function(wrap_pyfunction!(visibility::crosslink_visibility, m)?)?;
+    m.add_function(wrap_pyfunction!(ground_track::py_ground_track, m)?)?;
+    m.add_function(wrap_pyfunction!(sensor::py_sensor_access, m)?)?;
+    m.add_function(wrap_pyfunction!(lambert::lambert, m)?)?;
+    m.add_function(wrap_pyfunction!(orbit_design::py_sun_synchronous_inclination, m)?)?;
+    m.add_function(wrap_pyfunction!(orbit_design::py_repeat_ground_track, m)?)?;
+    m.add_function(wrap_pyfunction!(orbit_design::py_repeat_ground_track_solutions, m)?)?;
+    m.add_function(wrap_pyfunction!(orbit_design::py_frozen_orbit, m)?)?;
+    m.add_function(wrap_pyfunction!(iod::py_gibbs, m)?)?;
+    m.add_function(wrap_pyfunction!(iod::py_herrick_gibbs, m)?)?;
+    m.add_function(wrap_pyfunction!(iod::py_initial_orbit, m)?)?;
+    m.add_function(wrap_pyfunction!(iod::angles_only_orbit, m)?)?;
+    m.add_function(wrap_pyfunction!(od::fit_orbit, m)?)?;
+    m.add_function(wrap_pyfunction!(observations::simulate_observations, m)?)?;
+    m.add_function(wrap_pyfunction!(maneuvers::py_hohmann_transfer, m)?)?;
+    m.add_function(wrap_pyfunction!(maneuvers::py_bielliptic_transfer, m)?)?;
+    m.add_function(wrap_pyfunction!(maneuvers::py_plane_change, m)?)?;
+    m.add_function(wrap_pyfunction!(maneuvers::py_phasing_orbit, m)?)?;
+    m.add_function(wrap_pyfunction!(relative::relative_propagate, m)?)?;
+    m.add_function(wrap_pyfunction!(relative::relative_trajectory, m)?)?;
+    m.add_function(wrap_pyfunction!(sieve::py_moid, m)?)?;
+    m.add_function(wrap_pyfunction!(graph::conjunction_graph, m)?)?;
+    m.add_function(wrap_pyfunction!(batch::find_conjunctions_array, m)?)?;
+    m.add_function(wrap_pyfunction!(columnar::find_conjunctions_columnar, m)?)?;
     m.add_function(wrap_pyfunction!(pairwise_distances, m)?)?;
     m.add_function(wrap_pyfunction!(find_closest_approaches, m)?)?;
+    m.add_function(wrap_pyfunction!(probability::collision_probability, m)?)?;
+    m.add_function(wrap_pyfunction!(probability::compare_pc_methods, m)?)?;
+    m.add_function(wrap_pyfunction!(probability::pc_sensitivity, m)?)?;
+    m.add_function(wrap_pyfunction!(probability::max_collision_probability, m)?)?;
+    m.add_function(wrap_pyfunction!(monte_carlo::py_monte_carlo_pc, m)?)?;
+    m.add_function(wrap_pyfunction!(screening::screen_window, m)?)?;
+    m.add_function(wrap_pyfunction!(screening::screen_ephemerides, m)?)?;
+    m.add_function(wrap_pyfunction!(screening::gpu_device, m)?)?;
+    m.add_function(wrap_pyfunction!(screening::screen_events, m)?)?;
+    m.add_function(wrap_pyfunction!(events::cluster_conjunctions, m)?)?;
+    m.add_function(wrap_pyfunction!(maneuver::py_plan_avoidance_maneuver, m)?)?;
+    m.add_function(wrap_pyfunction!(maneuver_detection::py_detect_maneuvers, m)?)?;
+    m.add_function(wrap_pyfunction!(maneuver_detection::detect_maneuvers_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(maneuver_trade::screen_maneuver_candidates, m)?)?;
+    m.add_function(wrap_pyfunction!(launch_cola::py_screen_launch, m)?)?;
+    m.add_function(wrap_pyfunction!(hygiene::check_tle_catalog, m)?)?;
+    m.add_function(wrap_pyfunction!(propagation::propagate_two_body, m)?)?;
+    m.add_function(wrap_pyfunction!(propagation::propagate_j2, m)?)?;
+    m.add_function(wrap_pyfunction!(propagation::propagate_with_drag, m)?)?;
+    m.add_function(wrap_pyfunction!(propagation::propagate_perturbed, m)?)?;
+    m.add_function(wrap_pyfunction!(propagation::state_transition_matrix, m)?)?;
+    m.add_function(wrap_pyfunction!(propagation::propagate_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(cdm::conjunction_to_cdm, m)?)?;
+    m.add_function(wrap_pyfunction!(report::conjunction_report, m)?)?;
+    m.add_function(wrap_pyfunction!(tabular::py_write_csv, m)?)?;
+    #[cfg(feature = "parquet")]
+    m.add_function(wrap_pyfunction!(tabular::py_write_parquet, m)?)?;
+    m.add_function(wrap_pyfunction!(ephemeris::read_oem, m)?)?;
+    m.add_function(wrap_pyfunction!(ephemeris::parse_oem_text, m)?)?;
+    m.add_function(wrap_pyfunction!(tle::parse_tle, m)?)?;
+    m.add_function(wrap_pyfunction!(tle::parse_tle_file, m)?)?;
+    m.add_function(wrap_pyfunction!(omm::parse_omm, m)?)?;
+    m.add_function(wrap_pyfunction!(omm::read_omm, m)?)?;
+    m.add_function(wrap_pyfunction!(designator::norad_to_alpha5, m)?)?;
+    m.add_function(wrap_pyfunction!(designator::alpha5_to_norad, m)?)?;
+    m.add_function(wrap_pyfunction!(designator::py_parse_international_designator, m)?)?;
+    m.add_function(wrap_pyfunction!(designator::py_tle_international_designator, m)?)?;
+    m.add_function(wrap_pyfunction!(osculating::py_osculating_to_mean, m)?)?;
+    m.add_function(wrap_pyfunction!(osculating::py_mean_to_osculating, m)?)?;
+    m.add_function(wrap_pyfunction!(osculating::py_kozai_to_brouwer, m)?)?;
+    m.add_function(wrap_pyfunction!(osculating::py_brouwer_to_kozai, m)?)?;
+    m.add_function(wrap_pyfunction!(synthetic::py_benchmark_synthetic_catalog, m)?)?;
+    m.add_function(wrap_pyfunction!(synthetic::generate_catalog, m)?)?;
+    m.add_function(wrap_pyfunction!(breakup::breakup, m)?)?;
+    m.add_function(wrap_pyfunction!(frames::teme_to_ecef, m)?)?;
+    m.add_function(wrap_pyfunction!(frames::ecef_to_teme, m)?)?;
+    m.add_function(wrap_pyfunction!(frames::gcrf_to_ecef, m)?)?;
+    m.add_function(wrap_pyfunction!(frames::ecef_to_gcrf, m)?)?;
+    m.add_function(wrap_pyfunction!(frames::ecef_to_geodetic, m)?)?;
+    m.add_function(wrap_pyfunction!(frames::geodetic_to_ecef, m)?)?;
+    m.add_function(wrap_pyfunction!(frames::gmst, m)?)?;
+    m.add_function(wrap_pyfunction!(frames::era, m)?)?;
+    m.add_function(wrap_pyfunction!(parallel::set_num_threads, m)?)?;
+    m.add_function(wrap_pyfunction!(parallel::get_num_threads, m)?)?;
+    m.add_function(wrap_pyfunction!(json::conjunctions_to_json, m)?)?;
+    m.add_function(wrap_pyfunction!(json::conjunctions_from_json, m)?)?;
+    #[cfg(feature = "fetch")]
+    m.add_function(wrap_pyfunction!(fetch::fetch_elements_py, m)?)?;
+    #[cfg(feature = "fetch")]
+    m.add_function(wrap_pyfunction!(fetch::fetch_catalog, m)?)?;
+    error::register(py, m)?;
+    logging::install_python_layer();
     Ok(())
 }
 
@@ -200,40 +1692,193 @@ mod tests {
 
     #[test]
     fn test_satellite_distance() {
-        let sat1 = Satellite {
-            norad_id: 1,
-            position: vec![7000.0, 0.0, 0.0],
-            velocity: vec![0.0, 7.5, 0.0],
-        };
+        let sat1 = Satellite::from_state(1, [7000.0, 0.0, 0.0], [0.0, 7.5, 0.0], None);
         
-        let sat2 = Satellite {
-            norad_id: 2,
-            position: vec![7010.0, 0.0, 0.0],
-            velocity: vec![0.0, 7.5, 0.0],
-        };
+        let sat2 = Satellite::from_state(2, [7010.0, 0.0, 0.0], [0.0, 7.5, 0.0], None);
         
-        let dist = sat1.distance_to(&sat2);
+        let dist = (sat1.r() - sat2.r()).norm();
         assert!((dist - 10.0).abs() < 0.001);
     }
     
     #[test]
     fn test_conjunction_detection() {
         let satellites = vec![
-            Satellite {
-                norad_id: 1,
-                position: vec![7000.0, 0.0, 0.0],
-                velocity: vec![0.0, 7.5, 0.0],
-            },
-            Satellite {
-                norad_id: 2,
-                position: vec![7005.0, 0.0, 0.0],
-                velocity: vec![0.0, 7.5, 0.0],
-            },
+            Satellite::from_state(1, [7000.0, 0.0, 0.0], [0.0, 7.5, 0.0], None),
+            Satellite::from_state(2, [7005.0, 0.0, 0.0], [0.0, 7.5, 0.0], None),
         ];
         
-        let conjunctions = find_conjunctions(satellites, 10.0).unwrap();
+        let conjunctions = screen_satellites(&satellites, 10.0, &ScreenOptions::default()).unwrap();
         assert_eq!(conjunctions.len(), 1);
         assert_eq!(conjunctions[0].norad_id_1, 1);
         assert_eq!(conjunctions[0].norad_id_2, 2);
+        assert!((conjunctions[0].radial_km - 5.0).abs() < 1e-12);
+        assert!(conjunctions[0].in_track_km.abs() < 1e-12);
+        
+        // the plain Rust entry point validates the states first
+        assert_eq!(find_conjunctions(&satellites, 10.0).unwrap().len(), 1);
+        let broken = Satellite::from_state(3, [f64::NAN, 0.0, 0.0], [0.0, 7.5, 0.0], None);
+        assert!(find_conjunctions(&[satellites[0].clone(), broken], 10.0).is_err());
+    }
+    
+    #[test]
+    fn test_grid_matches_brute_force() {
+        // Deterministic pseudo-random cloud around a LEO shell
+        let mut seed: u64 = 42;
+        let mut next = || {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (seed >> 11) as f64 / (1u64 << 53) as f64
+        };
+        let satellites: Vec<Satellite> = (0..400)
+            .map(|id| {
+                let position = [7000.0 + 200.0 * next(), 200.0 * next(), 200.0 * next()];
+                Satellite::from_state(id, position, [0.0, 7.5, 0.0], None)
+            })
+            .collect();
+        
+        let threshold = 15.0;
+        let mut expected = Vec::new();
+        for i in 0..satellites.len() {
+            for j in (i + 1)..satellites.len() {
+                if (satellites[i].r() - satellites[j].r()).norm() < threshold {
+                    expected.push((satellites[i].norad_id, satellites[j].norad_id));
+                }
+            }
+        }
+        
+        let found: Vec<(i32, i32)> = screen_satellites(&satellites, threshold, &ScreenOptions::default())
+            .unwrap()
+            .iter()
+            .map(|c| (c.norad_id_1, c.norad_id_2))
+            .collect();
+        assert!(!expected.is_empty());
+        assert_eq!(found, expected);
+        
+        // The sieve is exact for osculating bands, so it never drops a real pair
+        let sieved = screen_satellites(&satellites, threshold, &ScreenOptions { apogee_perigee_filter: true, ..ScreenOptions::default() }).unwrap();
+        assert_eq!(sieved.len(), expected.len());
+    }
+    
+    #[test]
+    fn test_mixed_precision_keeps_borderline_pairs() {
+        // f32 spacing at GEO radius is about 4 m, far coarser than the gaps here
+        let x = 42164.0003;
+        let satellites: Vec<Satellite> = [0.0, 0.9999, 2.0, 2.9998]
+            .iter()
+            .enumerate()
+            .map(|(id, dx)| Satellite::from_state(id as i32, [x + dx, 0.0, 0.0], [0.0, 3.07, 0.0], None))
+            .collect();
+        let pairs = |precision| -> Vec<(i32, i32)> {
+            screen_satellites(&satellites, 1.0, &ScreenOptions { precision, ..ScreenOptions::default() })
+                .unwrap()
+                .iter()
+                .map(|c| (c.norad_id_1, c.norad_id_2))
+                .collect()
+        };
+        assert_eq!(pairs(Precision::Double), vec![(0, 1), (2, 3)]);
+        assert_eq!(pairs(Precision::Mixed), pairs(Precision::Double));
+        assert!("single".parse::<Precision>().is_err());
+    }
+    
+    #[test]
+    fn test_hard_body_radius_widens_threshold_and_sets_pc() {
+        let mut sat1 = Satellite::from_state(1, [7000.0, 0.0, 0.0], [0.0, 7.5, 0.0], None);
+        let mut sat2 = Satellite::from_state(2, [7000.0, 0.0, 0.011], [0.0, 0.0, 7.5], None);
+        assert!(screen_satellites(&[sat1.clone(), sat2.clone()], 0.01, &ScreenOptions::default()).unwrap().is_empty());
+        
+        sat1.hard_body_radius = Some(0.002);
+        sat2.rcs_size = Some(RcsSize::Large);
+        sat1.covariance = Some(Matrix6::identity() * 1.0e-4);
+        let found = screen_satellites(&[sat1, sat2], 0.01, &ScreenOptions::default()).unwrap();
+        assert_eq!(found.len(), 1);
+        assert!(found[0].probability.unwrap() > 0.0);
+    }
+    
+    #[test]
+    fn test_sort_orders_are_total() {
+        let event = |id1: i32, id2: i32, dist: f64| {
+            let (r, v) = (Vector3::new(7000.0, 0.0, 0.0), Vector3::new(0.0, 7.5, 0.0));
+            Conjunction::from_states(id1, id2, (&r, &v), (&(r + Vector3::new(dist, 0.0, 0.0)), &v), None)
+        };
+        let events = vec![event(5, 2, 1.0), event(1, 9, 3.0), event(1, 3, 1.0), event(4, 4, 2.0)];
+        let key = |list: &[Conjunction]| list.iter().map(|c| (c.norad_id_1, c.norad_id_2)).collect::<Vec<_>>();
+        
+        let mut by_ids = events.clone();
+        sort_conjunctions(&mut by_ids, SortOrder::Ids);
+        assert_eq!(key(&by_ids), vec![(1, 3), (1, 9), (4, 4), (5, 2)]);
+        
+        let mut by_distance: Vec<Conjunction> = events.into_iter().rev().collect();
+        sort_conjunctions(&mut by_distance, SortOrder::Distance);
+        assert_eq!(key(&by_distance), vec![(1, 3), (5, 2), (4, 4), (1, 9)]);
+    }
+
+    #[test]
+    fn test_result_limits() {
+        let event = |id2: i32, dist: f64| {
+            let (r, v) = (Vector3::new(7000.0, 0.0, 0.0), Vector3::new(0.0, 7.5, 0.0));
+            Conjunction::from_states(1, id2, (&r, &v), (&(r + Vector3::new(dist, 0.0, 0.0)), &v), None)
+        };
+        let events: Vec<Conjunction> = (2..12).rev().map(|id| event(id, id as f64 * 0.1)).collect();
+        let ids = |list: Vec<Conjunction>| list.iter().map(|c| c.norad_id_2).collect::<Vec<_>>();
+
+        assert_eq!(ResultLimits::default().apply(events.clone(), SortOrder::Unsorted).len(), 10);
+        let top = ResultLimits { top_k_by_distance: Some(3), ..Default::default() };
+        assert_eq!(ids(top.apply(events.clone(), SortOrder::Ids)), vec![2, 3, 4]);
+        let limits = ResultLimits { max_results: Some(2), top_k_by_distance: Some(4), min_separation_km: Some(0.25) };
+        assert_eq!(ids(limits.apply(events.clone(), SortOrder::Distance)), vec![3, 4]);
+        let none = ResultLimits { top_k_by_distance: Some(0), ..Default::default() };
+        assert!(none.apply(events, SortOrder::Ids).is_empty());
+        assert!(ResultLimits { min_separation_km: Some(-1.0), ..Default::default() }.validate().is_err());
+    }
+
+    #[cfg(feature = "python")]
+    #[test]
+    fn test_equality_keys() {
+        let mut sat = Satellite::from_state(1, [7000.0, 0.0, -0.0], [0.0, 7.5, 0.0], None);
+        let mut copy = sat.clone();
+        copy.position[2] = 0.0;
+        assert!(sat.key() == copy.key());
+        assert_eq!(hash_key(&sat.key()), hash_key(&copy.key()));
+        sat.name = Some("SAT".to_string());
+        assert!(sat.key() != copy.key());
+        
+        // NaN RTN components (degenerate primary) still compare equal to themselves
+        let event = Conjunction::new(1, 2, 1.0, 10.0, None, None, f64::NAN, f64::NAN, f64::NAN, [f64::NAN; 3], None, None, None, false, None, None).unwrap();
+        assert!(event.key() == event.clone().key());
+    }
+    
+    #[test]
+    fn test_b_plane_coordinates() {
+        // head-on in-track encounter: S along -T of the primary, miss split radial/cross-track
+        let (r1, v1) = (Vector3::new(7000.0, 0.0, 0.0), Vector3::new(0.0, 7.5, 0.0));
+        let (r2, v2) = (Vector3::new(7000.3, 0.0, 0.4), Vector3::new(0.0, -7.5, 0.0));
+        let event = Conjunction::from_states(1, 2, (&r1, &v1), (&r2, &v2), None);
+        let ([s, t, r], [b_t, b_r]) = event.b_plane().unwrap();
+        assert!((s - Vector3::new(0.0, -1.0, 0.0)).norm() < 1e-12);
+        assert!((t.dot(&r)).abs() < 1e-12 && (s.dot(&t)).abs() < 1e-12);
+        assert!((b_t.hypot(b_r) - event.distance_km).abs() < 1e-9);
+        // T lies in the orbit plane (radial), R is cross-track
+        assert!((b_t.abs() - 0.3).abs() < 1e-9 && (b_r.abs() - 0.4).abs() < 1e-9);
+    
+        let parallel = Conjunction::from_states(1, 2, (&r1, &v1), (&r2, &v1), None);
+        assert!(parallel.b_plane().is_none());
+    
+        // the states at TCA travel with the event
+        assert!((event.approach_angle_deg().unwrap() - 180.0).abs() < 1e-9);
+        assert!(parallel.approach_angle_deg().unwrap().abs() < 1e-6);
+        assert_eq!(event.state_2, Some([7000.3, 0.0, 0.4, 0.0, -7.5, 0.0]));
+        assert!(Conjunction { state_1: None, ..event }.approach_angle_deg().is_none());
+    }
+    
+    #[test]
+    fn test_state_validation() {
+        let velocity = [0.0, 7.5, 0.0];
+        assert!(check_state(1, &[7000.0, 0.0, 0.0], &velocity, true).is_ok());
+        assert!(check_state(1, &[f64::NAN, 0.0, 0.0], &velocity, false).is_err());
+        assert!(check_state(1, &[7000.0, 0.0, 0.0], &[0.0, f64::INFINITY, 0.0], false).is_err());
+        assert!(check_state(1, &[0.0; 3], &velocity, false).is_err());
+        // 6370 km is above the polar surface but below the equatorial one
+        assert!(check_state(1, &[0.0, 0.0, 6370.0], &velocity, true).is_ok());
+        assert!(check_state(1, &[6370.0, 0.0, 0.0], &velocity, false).is_ok());
+        assert!(check_state(1, &[6370.0, 0.0, 0.0], &velocity, true).is_err());
     }
 }
@@ -0,0 +1,186 @@
+//! Structured diagnostics from the engine
+//!
+//! Stages such as screening report what they did (pairs pruned per filter,
+//! time per stage, hits per grid step) as `tracing` events with named
+//! fields, under their module's target (`orbit_core::screening`). The Python
+//! module installs a subscriber whose `PythonLayer` forwards each event to
+//! `logging.getLogger("orbit_core.screening")`, the `orbitguard` binary one
+//! that prints to stderr; Rust callers install whatever subscriber they like.
+//! Without a subscriber nothing is formatted.
+//!
+//! Events are only emitted from the thread that called into the engine,
+//! never from rayon workers, so the Python layer can take the GIL safely.
+
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+#[cfg(feature = "python")]
+use pyo3::types::PyDict;
+use std::fmt;
+use tracing::field::{Field, Visit};
+
+/// Value of a structured field
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    Text(String),
+}
+
+/// Message and named fields of one event
+#[derive(Debug, Default, PartialEq)]
+pub struct Fields {
+    pub message: String,
+    pub fields: Vec<(&'static str, Value)>,
+}
+
+impl Fields {
+    pub fn of(event: &tracing::Event<'_>) -> Self {
+        let mut fields = Fields::default();
+        event.record(&mut fields);
+        fields
+    }
+
+    fn push(&mut self, field: &Field, value: Value) {
+        self.fields.push((field.name(), value));
+    }
+}
+
+impl Visit for Fields {
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.push(field, Value::Int(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.push(field, i64::try_from(value).map_or(Value::Float(value as f64), Value::Int));
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.push(field, Value::Float(value));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.push(field, Value::Bool(value));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.push(field, Value::Text(value.to_string()));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{:?}", value);
+        } else {
+            self.push(field, Value::Text(format!("{:?}", value)));
+        }
+    }
+}
+
+/// Layer forwarding events to Python's `logging`; fields arrive as `record.fields` (a dict)
+///
+/// The logger is named after the event's target with `::` replaced by `.`.
+#[cfg(feature = "python")]
+pub struct PythonLayer;
+
+#[cfg(feature = "python")]
+impl PythonLayer {
+    fn logger<'py>(py: Python<'py>, target: &str) -> PyResult<&'py PyAny> {
+        py.import("logging")?.call_method1("getLogger", (target.replace("::", "."),))
+    }
+
+    fn levelno(level: &tracing::Level) -> i32 {
+        match *level {
+            tracing::Level::TRACE => 5,
+            tracing::Level::DEBUG => 10,
+            tracing::Level::INFO => 20,
+            tracing::Level::WARN => 30,
+            tracing::Level::ERROR => 40,
+        }
+    }
+}
+
+#[cfg(feature = "python")]
+impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for PythonLayer {
+    // Python loggers change level at any time, so nothing is cached per callsite
+    fn register_callsite(&self, _metadata: &'static tracing::Metadata<'static>) -> tracing::subscriber::Interest {
+        tracing::subscriber::Interest::sometimes()
+    }
+
+    fn enabled(&self, metadata: &tracing::Metadata<'_>, _ctx: tracing_subscriber::layer::Context<'_, S>) -> bool {
+        Python::with_gil(|py| {
+            Self::logger(py, metadata.target())
+                .and_then(|logger| logger.call_method1("isEnabledFor", (Self::levelno(metadata.level()),))?.extract())
+                .unwrap_or(false)
+        })
+    }
+
+    fn on_event(&self, event: &tracing::Event<'_>, _ctx: tracing_subscriber::layer::Context<'_, S>) {
+        let record = Fields::of(event);
+        let metadata = event.metadata();
+        Python::with_gil(|py| {
+            let result = (|| -> PyResult<()> {
+                let fields = PyDict::new(py);
+                for (key, value) in &record.fields {
+                    match value {
+                        Value::Int(x) => fields.set_item(key, x)?,
+                        Value::Float(x) => fields.set_item(key, x)?,
+                        Value::Bool(x) => fields.set_item(key, x)?,
+                        Value::Text(text) => fields.set_item(key, text)?,
+                    }
+                }
+                let extra = PyDict::new(py);
+                extra.set_item("fields", fields)?;
+                let kwargs = PyDict::new(py);
+                kwargs.set_item("extra", extra)?;
+                Self::logger(py, metadata.target())?.call_method(
+                    "log",
+                    (Self::levelno(metadata.level()), record.message.as_str()),
+                    Some(kwargs),
+                )?;
+                Ok(())
+            })();
+            // a failing handler must not abort the computation that logged
+            if let Err(err) = result {
+                err.write_unraisable(py, None);
+            }
+        });
+    }
+}
+
+/// Route events to Python's `logging`, unless the process already has a subscriber
+#[cfg(feature = "python")]
+pub fn install_python_layer() {
+    use tracing_subscriber::layer::SubscriberExt;
+    let _ = tracing::subscriber::set_global_default(tracing_subscriber::registry().with(PythonLayer));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+
+    struct Capture(Arc<Mutex<Vec<Fields>>>);
+
+    impl<S: tracing::Subscriber> Layer<S> for Capture {
+        fn on_event(&self, event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
+            self.0.lock().unwrap().push(Fields::of(event));
+        }
+    }
+
+    #[test]
+    fn test_events_carry_fields() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let subscriber = tracing_subscriber::registry().with(Capture(events.clone()));
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::debug!(filter = "altitude", kept = 3usize, ms = 1.5, "filter {} kept {}", "altitude", 3);
+        });
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].message, "filter altitude kept 3");
+        assert_eq!(
+            events[0].fields,
+            vec![("filter", Value::Text("altitude".into())), ("kept", Value::Int(3)), ("ms", Value::Float(1.5))]
+        );
+    }
+}
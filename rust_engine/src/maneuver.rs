@@ -0,0 +1,323 @@
+//! Collision avoidance maneuver planning
+//!
+//! Sizes an in-track (tangential) burn of the primary ahead of TCA using the
+//! Clohessy-Wiltshire linearization about its near-circular orbit. A burn of
+//! dv at lead time t displaces the primary at TCA by
+//! dv * (2(1 - cos nt)/n, 4 sin(nt)/n - 3t, 0) in RTN; the new miss is
+//! measured in the encounter plane (normal to the relative velocity), where
+//! the secondary's along-track timing error does not matter.
+
+use nalgebra::{Matrix3, Vector3};
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use std::f64::consts::PI;
+
+use crate::constants::{MU_EARTH, SECONDS_PER_DAY};
+use crate::covariance;
+use crate::error::OrbitError;
+use crate::frames;
+use crate::probability::foster_pc;
+use crate::relative::cw_transition;
+use crate::{Conjunction, Satellite};
+
+/// Smallest burn tried when bracketing a Pc limit (km/s, i.e. 1 mm/s)
+const MIN_TRIAL_DV: f64 = 1.0e-6;
+
+/// RTN displacement (km) at `t` seconds after a 1 km/s in-track burn
+pub fn tangential_burn_displacement(mean_motion: f64, t: f64) -> Vector3<f64> {
+    cw_transition(mean_motion, t).fixed_view::<3, 1>(0, 4).into_owned()
+}
+
+/// Linearized encounter of the primary with a secondary at TCA
+pub struct Encounter {
+    pub miss: Vector3<f64>,              // secondary - primary (km)
+    pub relative_velocity: Vector3<f64>, // secondary - primary (km/s)
+    pub basis: [Vector3<f64>; 3],        // primary RTN unit vectors
+    pub mean_motion: f64,                // primary (rad/s)
+    pub combined_cov: Option<Matrix3<f64>>,
+    pub hard_body_radius: f64,
+}
+
+impl Encounter {
+    pub fn from_states(
+        primary: (&Vector3<f64>, &Vector3<f64>),
+        secondary: (&Vector3<f64>, &Vector3<f64>),
+        combined_cov: Option<Matrix3<f64>>,
+        hard_body_radius: f64,
+    ) -> Option<Self> {
+        let basis = frames::rtn_basis(primary.0, primary.1)?;
+        let energy = primary.1.norm_squared() / 2.0 - MU_EARTH / primary.0.norm();
+        if energy >= 0.0 {
+            return None;
+        }
+        let a = -MU_EARTH / (2.0 * energy);
+        Some(Encounter {
+            miss: secondary.0 - primary.0,
+            relative_velocity: secondary.1 - primary.1,
+            basis,
+            mean_motion: (MU_EARTH / (a * a * a)).sqrt(),
+            combined_cov,
+            hard_body_radius,
+        })
+    }
+
+    /// Project onto the encounter plane
+    fn project(&self, v: &Vector3<f64>) -> Vector3<f64> {
+        let along = self.relative_velocity.normalize();
+        v - along * v.dot(&along)
+    }
+
+    /// Inertial displacement of the primary at TCA per km/s of burn at lead time `t`
+    fn sensitivity(&self, lead_time: f64) -> Vector3<f64> {
+        let d = tangential_burn_displacement(self.mean_motion, lead_time);
+        self.basis[0] * d.x + self.basis[1] * d.y + self.basis[2] * d.z
+    }
+
+    /// Miss vector after a burn of `dv` km/s at lead time `t`
+    fn miss_after(&self, dv: f64, lead_time: f64) -> Vector3<f64> {
+        self.miss - self.sensitivity(lead_time) * dv
+    }
+
+    /// Encounter-plane miss distance after the burn (km)
+    pub fn miss_distance_after(&self, dv: f64, lead_time: f64) -> f64 {
+        self.project(&self.miss_after(dv, lead_time)).norm()
+    }
+
+    /// Collision probability after the burn, if a covariance is available
+    pub fn probability_after(&self, dv: f64, lead_time: f64) -> Option<f64> {
+        let cov = self.combined_cov.as_ref()?;
+        foster_pc(&self.miss_after(dv, lead_time), &self.relative_velocity, cov, self.hard_body_radius)
+    }
+
+    /// Burn of sign `direction` reaching an encounter-plane miss of `target_km`
+    fn dv_for_miss(&self, lead_time: f64, target_km: f64, direction: f64) -> Option<f64> {
+        let a = self.project(&self.miss);
+        let b = self.project(&self.sensitivity(lead_time));
+        if a.norm() >= target_km {
+            return Some(0.0);
+        }
+        let bb = b.norm_squared();
+        if bb == 0.0 {
+            return None;
+        }
+        // |a - x b|^2 = target^2 has one root of each sign when |a| < target
+        let ab = a.dot(&b);
+        let disc = (ab * ab - bb * (a.norm_squared() - target_km * target_km)).sqrt();
+        Some(if direction > 0.0 { (ab + disc) / bb } else { (ab - disc) / bb })
+    }
+
+    /// Smallest burn of sign `direction` bringing Pc to `max_pc` or below
+    fn dv_for_pc(&self, lead_time: f64, max_pc: f64, direction: f64, max_dv: f64) -> Option<f64> {
+        let pc = |dv: f64| self.probability_after(dv, lead_time);
+        if pc(0.0)? <= max_pc {
+            return Some(0.0);
+        }
+        let mut low = 0.0;
+        let mut high = MIN_TRIAL_DV;
+        while pc(direction * high)? > max_pc {
+            low = high;
+            high *= 2.0;
+            if high > max_dv {
+                return None;
+            }
+        }
+        for _ in 0..60 {
+            let mid = 0.5 * (low + high);
+            if pc(direction * mid)? > max_pc {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        Some(direction * high)
+    }
+
+    /// Smallest burn at `lead_time` meeting every given requirement
+    pub fn required_dv(&self, lead_time: f64, target_km: Option<f64>, max_pc: Option<f64>, max_dv: f64) -> Option<f64> {
+        [1.0, -1.0]
+            .iter()
+            .filter_map(|&direction| {
+                let by_miss = match target_km {
+                    Some(target) => self.dv_for_miss(lead_time, target, direction)?,
+                    None => 0.0,
+                };
+                let by_pc = match max_pc {
+                    Some(limit) => self.dv_for_pc(lead_time, limit, direction, max_dv)?,
+                    None => 0.0,
+                };
+                let dv = if by_miss.abs() >= by_pc.abs() { by_miss } else { by_pc };
+                (dv.abs() <= max_dv && (dv == 0.0 || dv.signum() == direction)).then_some(dv)
+            })
+            .min_by(|a, b| a.abs().total_cmp(&b.abs()))
+    }
+}
+
+/// Candidate avoidance burn
+#[cfg_attr(feature = "python", pyclass(get_all))]
+#[derive(Clone, Debug)]
+pub struct ManeuverOption {
+    pub burn_jd: f64,
+    pub lead_time_s: f64,         // seconds before TCA
+    pub delta_v_m_s: f64,         // in-track; positive is posigrade
+    pub miss_distance_km: f64,    // encounter-plane miss after the burn
+    pub probability: Option<f64>, // Pc after the burn, when covariance is available
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl ManeuverOption {
+    fn __repr__(&self) -> String {
+        format!(
+            "ManeuverOption(burn_jd={:.6}, lead={:.0}s, dv={:+.4}m/s, miss={:.3}km)",
+            self.burn_jd, self.lead_time_s, self.delta_v_m_s, self.miss_distance_km
+        )
+    }
+}
+
+/// In-track avoidance burns for the primary of a conjunction
+///
+/// `primary`/`secondary` are the states at TCA. Burn epochs are placed
+/// `lead_orbits` primary periods before TCA; options that cannot meet the
+/// target miss distance and/or Pc limit within `max_delta_v_m_s` are dropped.
+/// Results are sorted by burn magnitude.
+pub fn plan_avoidance_maneuver(
+    conjunction: &Conjunction,
+    (primary, secondary): (&Satellite, &Satellite),
+    target_miss_km: Option<f64>,
+    max_pc: Option<f64>,
+    hard_body_radius: f64,
+    lead_orbits: &[f64],
+    max_delta_v_m_s: f64,
+) -> Result<Vec<ManeuverOption>, OrbitError> {
+    if target_miss_km.is_none() && max_pc.is_none() {
+        return Err(OrbitError::InvalidInput("Give a target_miss_km, a max_pc or both".to_string()));
+    }
+    let tca_jd = conjunction
+        .tca_jd
+        .or_else(|| primary.epoch.map(|e| e.jd_utc()))
+        .ok_or_else(|| OrbitError::InvalidInput("Conjunction has no TCA".to_string()))?;
+
+    let combined_cov = match (&primary.covariance, &secondary.covariance) {
+        (Some(a), Some(b)) => Some(covariance::position_block(a) + covariance::position_block(b)),
+        (Some(a), None) | (None, Some(a)) => Some(covariance::position_block(a)),
+        (None, None) => None,
+    };
+    if max_pc.is_some() && combined_cov.is_none() {
+        return Err(OrbitError::InvalidInput(
+            "max_pc requires a covariance on at least one satellite".to_string(),
+        ));
+    }
+
+    let r1 = primary.r();
+    let v1 = primary.v();
+    let r2 = secondary.r();
+    let v2 = secondary.v();
+    let encounter = Encounter::from_states((&r1, &v1), (&r2, &v2), combined_cov, hard_body_radius)
+        .ok_or_else(|| OrbitError::InvalidInput("Primary must be on a bound orbit".to_string()))?;
+
+    let period = 2.0 * PI / encounter.mean_motion;
+    let max_dv = max_delta_v_m_s / 1.0e3;
+    let mut options: Vec<ManeuverOption> = lead_orbits
+        .iter()
+        .filter(|&&orbits| orbits > 0.0)
+        .filter_map(|&orbits| {
+            let lead = orbits * period;
+            let dv = encounter.required_dv(lead, target_miss_km, max_pc, max_dv)?;
+            Some(ManeuverOption {
+                burn_jd: tca_jd - lead / SECONDS_PER_DAY,
+                lead_time_s: lead,
+                delta_v_m_s: dv * 1.0e3,
+                miss_distance_km: encounter.miss_distance_after(dv, lead),
+                probability: encounter.probability_after(dv, lead),
+            })
+        })
+        .collect();
+    options.sort_by(|a, b| a.delta_v_m_s.abs().total_cmp(&b.delta_v_m_s.abs()));
+    Ok(options)
+}
+
+/// In-track avoidance burns for the primary of a conjunction
+///
+/// `primary`/`secondary` are the states at TCA. Burn epochs are placed
+/// `lead_orbits` primary periods before TCA; options that cannot meet the
+/// target miss distance and/or Pc limit within `max_delta_v_m_s` are dropped.
+/// Results are sorted by burn magnitude.
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(name = "plan_avoidance_maneuver", signature = (
+    conjunction,
+    primary,
+    secondary,
+    target_miss_km = None,
+    max_pc = None,
+    hard_body_radius = 0.02,
+    lead_orbits = vec![0.5, 1.0, 1.5, 2.0],
+    max_delta_v_m_s = 10.0
+))]
+#[allow(clippy::too_many_arguments)]
+pub fn py_plan_avoidance_maneuver(
+    conjunction: &Conjunction,
+    primary: &Satellite,
+    secondary: &Satellite,
+    target_miss_km: Option<f64>,
+    max_pc: Option<f64>,
+    hard_body_radius: f64,
+    lead_orbits: Vec<f64>,
+    max_delta_v_m_s: f64,
+) -> PyResult<Vec<ManeuverOption>> {
+    Ok(plan_avoidance_maneuver(
+        conjunction,
+        (primary, secondary),
+        target_miss_km,
+        max_pc,
+        hard_body_radius,
+        &lead_orbits,
+        max_delta_v_m_s,
+    )?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Crossing encounter: primary equatorial, secondary polar, 100 m radial miss
+    fn encounter(cov: Option<Matrix3<f64>>) -> Encounter {
+        let speed = (MU_EARTH / 7000.0f64).sqrt();
+        let (r1, v1) = (Vector3::new(7000.0, 0.0, 0.0), Vector3::new(0.0, speed, 0.0));
+        let (r2, v2) = (Vector3::new(7000.1, 0.0, 0.0), Vector3::new(0.0, 0.0, speed));
+        Encounter::from_states((&r1, &v1), (&r2, &v2), cov, 0.02).unwrap()
+    }
+
+    #[test]
+    fn test_half_orbit_burn_is_radial() {
+        let n = (MU_EARTH / 7000f64.powi(3)).sqrt();
+        let d = tangential_burn_displacement(n, PI / n);
+        // 4/n km of radial displacement per km/s at half an orbit
+        assert!((d.x - 4.0 / n).abs() < 1e-9);
+        assert!((d.y + 3.0 * PI / n).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_miss_target_reached() {
+        let enc = encounter(None);
+        let period = 2.0 * PI / enc.mean_motion;
+        for lead in [0.5 * period, 1.25 * period] {
+            let dv = enc.required_dv(lead, Some(1.0), None, 0.01).unwrap();
+            assert!(dv != 0.0);
+            assert!((enc.miss_distance_after(dv, lead) - 1.0).abs() < 1e-9);
+        }
+        assert_eq!(enc.required_dv(period, Some(0.05), None, 0.01), Some(0.0));
+    }
+
+    #[test]
+    fn test_pc_limit_reached() {
+        let enc = encounter(Some(Matrix3::from_diagonal(&Vector3::new(0.01, 0.04, 0.01))));
+        let lead = 0.5 * 2.0 * PI / enc.mean_motion;
+        let before = enc.probability_after(0.0, lead).unwrap();
+        assert!(before > 1e-4);
+
+        let dv = enc.required_dv(lead, None, Some(1e-6), 0.01).unwrap();
+        let after = enc.probability_after(dv, lead).unwrap();
+        assert!(after <= 1e-6 && after > 0.5e-6, "{}", after);
+    }
+}
@@ -0,0 +1,300 @@
+//! Manoeuvre detection from a TLE history
+//!
+//! Each element set is propagated with SGP4 to the epoch of the next one
+//! and both states are converted to osculating elements at that common
+//! instant, so the short-period J2 terms cancel and what is left is the
+//! inconsistency between consecutive TLEs. Fit noise and drag mismodelling
+//! keep that small; a burn shows up as a jump in semi-major axis (in-plane)
+//! or inclination (out-of-plane) well above it.
+//!
+//! Thresholds are absolute floors; with enough pairs they are raised to a
+//! multiple of the history's own robust spread (median absolute deviation),
+//! so one set of settings serves both well- and poorly-tracked objects.
+
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+#[cfg(feature = "python")]
+use rayon::prelude::*;
+
+use crate::constants::MU_EARTH;
+use crate::elements::{self, KeplerianElements};
+#[cfg(feature = "python")]
+use crate::parallel;
+use crate::robust;
+use crate::sgp4::{MeanElements, Sgp4Propagator};
+
+const MINUTES_PER_DAY: f64 = 1440.0;
+
+/// Fewest consecutive pairs before the spread of the history is trusted
+const MIN_ROBUST_PAIRS: usize = 5;
+
+/// Detection thresholds for consecutive-TLE inconsistencies
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ManeuverThresholds {
+    /// Semi-major axis jump floor (km)
+    pub sma_km: f64,
+    /// Inclination jump floor (degrees)
+    pub inclination_deg: f64,
+    /// Robust sigmas of the history's own jumps a detection must also exceed
+    pub robust_sigmas: f64,
+}
+
+impl Default for ManeuverThresholds {
+    fn default() -> Self {
+        ManeuverThresholds { sma_km: 0.5, inclination_deg: 0.01, robust_sigmas: 5.0 }
+    }
+}
+
+/// A likely manoeuvre between two consecutive element sets
+#[cfg_attr(feature = "python", pyclass(get_all))]
+#[derive(Clone, Debug, PartialEq)]
+pub struct DetectedManeuver {
+    pub norad_id: i32,
+    /// Epoch of the last element set before the manoeuvre (Julian date, UTC)
+    pub before_jd: f64,
+    /// Epoch of the first element set after it
+    pub after_jd: f64,
+    /// Semi-major axis change (km)
+    pub sma_change_km: f64,
+    /// Inclination change (degrees)
+    pub inclination_change_deg: f64,
+    /// Smallest impulsive delta-v producing both changes (km/s)
+    pub delta_v_km_s: f64,
+    /// "in-plane", "out-of-plane" or "combined"
+    pub kind: String,
+}
+
+/// Osculating elements of `elements` propagated to `jd` with SGP4
+fn osculating_at(elements: &MeanElements, jd: f64) -> Option<KeplerianElements> {
+    let minutes = (jd - elements.epoch_jd) * MINUTES_PER_DAY;
+    let (r, v) = Sgp4Propagator::from_mean_elements(elements.clone()).position_velocity(minutes).ok()?;
+    elements::from_state(&r.into(), &v.into())
+}
+
+/// Threshold raised to `sigmas` robust standard deviations of `jumps`
+fn robust_threshold(jumps: &[f64], floor: f64, sigmas: f64) -> f64 {
+    if jumps.len() < MIN_ROBUST_PAIRS {
+        return floor;
+    }
+    let (_, spread) = robust::median_and_sigma(jumps);
+    floor.max(sigmas * spread)
+}
+
+/// Likely manoeuvres in the TLE history of one object, in epoch order
+///
+/// Element sets are sorted by epoch and repeated epochs dropped; pairs
+/// where SGP4 fails are skipped. Histories mixing NORAD ids are rejected.
+pub fn detect_maneuvers(history: &[MeanElements], thresholds: &ManeuverThresholds) -> Result<Vec<DetectedManeuver>, String> {
+    if let Some(other) = history.iter().find(|el| el.norad_id != history[0].norad_id) {
+        return Err(format!(
+            "TLE history mixes NORAD ids {} and {}",
+            history[0].norad_id, other.norad_id
+        ));
+    }
+    let mut sorted: Vec<&MeanElements> = history.iter().collect();
+    sorted.sort_by(|a, b| a.epoch_jd.total_cmp(&b.epoch_jd));
+    sorted.dedup_by(|a, b| a.epoch_jd == b.epoch_jd);
+
+    // (before, after, predicted, observed) for every pair SGP4 can bridge
+    let pairs: Vec<_> = sorted
+        .windows(2)
+        .filter_map(|w| {
+            let predicted = osculating_at(w[0], w[1].epoch_jd)?;
+            let observed = osculating_at(w[1], w[1].epoch_jd)?;
+            Some((w[0].epoch_jd, w[1].epoch_jd, predicted, observed))
+        })
+        .collect();
+    let sma_jumps: Vec<f64> = pairs.iter().map(|(_, _, p, o)| o.semi_major_axis_km - p.semi_major_axis_km).collect();
+    let inclination_jumps: Vec<f64> = pairs.iter().map(|(_, _, p, o)| o.inclination_deg - p.inclination_deg).collect();
+    let sma_limit = robust_threshold(&sma_jumps, thresholds.sma_km, thresholds.robust_sigmas);
+    let inclination_limit = robust_threshold(&inclination_jumps, thresholds.inclination_deg, thresholds.robust_sigmas);
+
+    Ok(pairs
+        .iter()
+        .zip(sma_jumps.iter().zip(&inclination_jumps))
+        .filter_map(|((before, after, predicted, _), (&da, &di))| {
+            let kind = match (da.abs() > sma_limit, di.abs() > inclination_limit) {
+                (true, true) => "combined",
+                (true, false) => "in-plane",
+                (false, true) => "out-of-plane",
+                (false, false) => return None,
+            };
+            // tangential burn for the energy change, normal burn for the plane change
+            let a = predicted.semi_major_axis_km;
+            let speed = (MU_EARTH / a).sqrt();
+            let in_plane = speed * da.abs() / (2.0 * a);
+            let out_of_plane = 2.0 * speed * (0.5 * di.to_radians()).abs().sin();
+            Some(DetectedManeuver {
+                norad_id: history[0].norad_id,
+                before_jd: *before,
+                after_jd: *after,
+                sma_change_km: da,
+                inclination_change_deg: di,
+                delta_v_km_s: in_plane.hypot(out_of_plane),
+                kind: kind.to_string(),
+            })
+        })
+        .collect())
+}
+
+/// TLE record from Python: parsed elements, a propagator or (line1, line2)
+#[cfg(feature = "python")]
+#[derive(FromPyObject)]
+#[allow(clippy::large_enum_variant)]
+enum TleRecord {
+    Elements(MeanElements),
+    Propagator(Sgp4Propagator),
+    Lines(String, String),
+}
+
+#[cfg(feature = "python")]
+impl TleRecord {
+    fn into_elements(self) -> PyResult<MeanElements> {
+        match self {
+            TleRecord::Elements(elements) => Ok(elements),
+            TleRecord::Propagator(prop) => Ok(prop.elements),
+            TleRecord::Lines(line1, line2) => {
+                crate::tle::parse_lines(&line1, &line2, true).map_err(pyo3::exceptions::PyValueError::new_err)
+            }
+        }
+    }
+}
+
+/// Mean elements from a Python list of TLE records
+#[cfg(feature = "python")]
+pub(crate) fn extract_elements(history: &PyAny) -> PyResult<Vec<MeanElements>> {
+    history
+        .extract::<Vec<TleRecord>>()?
+        .into_iter()
+        .map(TleRecord::into_elements)
+        .collect()
+}
+
+#[cfg(feature = "python")]
+fn thresholds(sma_threshold_km: f64, inclination_threshold_deg: f64, robust_sigmas: f64) -> PyResult<ManeuverThresholds> {
+    if [sma_threshold_km, inclination_threshold_deg, robust_sigmas].iter().any(|x| x.is_nan() || *x < 0.0) {
+        return Err(pyo3::exceptions::PyValueError::new_err("Thresholds must be non-negative"));
+    }
+    Ok(ManeuverThresholds { sma_km: sma_threshold_km, inclination_deg: inclination_threshold_deg, robust_sigmas })
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl DetectedManeuver {
+    fn __repr__(&self) -> String {
+        format!(
+            "DetectedManeuver(norad_id={}, after_jd={:.5}, kind={}, da={:.3}km, di={:.4}deg, dv={:.2}m/s)",
+            self.norad_id,
+            self.after_jd,
+            self.kind,
+            self.sma_change_km,
+            self.inclination_change_deg,
+            self.delta_v_km_s * 1e3
+        )
+    }
+}
+
+/// Likely manoeuvres in one object's TLE history
+///
+/// `tles` holds `MeanElements` (e.g. from `parse_tle_file`),
+/// `Sgp4Propagator`s or (line1, line2) pairs in any order. A consecutive
+/// pair is flagged when the semi-major axis or inclination of the later
+/// set differs from the earlier set propagated to its epoch by more than
+/// `sma_threshold_km` / `inclination_threshold_deg`, raised to
+/// `robust_sigmas` times the history's own spread once there are enough
+/// pairs to estimate it.
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(name = "detect_maneuvers", signature = (tles, sma_threshold_km = 0.5, inclination_threshold_deg = 0.01, robust_sigmas = 5.0))]
+pub fn py_detect_maneuvers(
+    tles: &PyAny,
+    sma_threshold_km: f64,
+    inclination_threshold_deg: f64,
+    robust_sigmas: f64,
+) -> PyResult<Vec<DetectedManeuver>> {
+    let thresholds = thresholds(sma_threshold_km, inclination_threshold_deg, robust_sigmas)?;
+    let history = extract_elements(tles)?;
+    detect_maneuvers(&history, &thresholds).map_err(pyo3::exceptions::PyValueError::new_err)
+}
+
+/// `detect_maneuvers` over many objects' histories in parallel, one list per history
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(signature = (histories, sma_threshold_km = 0.5, inclination_threshold_deg = 0.01, robust_sigmas = 5.0))]
+pub fn detect_maneuvers_batch(
+    py: Python<'_>,
+    histories: Vec<&PyAny>,
+    sma_threshold_km: f64,
+    inclination_threshold_deg: f64,
+    robust_sigmas: f64,
+) -> PyResult<Vec<Vec<DetectedManeuver>>> {
+    let thresholds = thresholds(sma_threshold_km, inclination_threshold_deg, robust_sigmas)?;
+    let histories: Vec<Vec<MeanElements>> = histories.into_iter().map(extract_elements).collect::<PyResult<_>>()?;
+    py.allow_threads(|| {
+        parallel::install(|| {
+            histories
+                .par_iter()
+                .map(|history| detect_maneuvers(history, &thresholds))
+                .collect::<Result<Vec<_>, String>>()
+        })
+    })
+    .map_err(pyo3::exceptions::PyValueError::new_err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::osculating::osculating_to_mean;
+    use nalgebra::Vector3;
+
+    /// Daily element sets refitted from one SGP4 orbit, with a burn (RTN, km/s) before day `burn_day`
+    fn history(burn_day: usize, burn: Vector3<f64>) -> Vec<MeanElements> {
+        let mut current = MeanElements::new(7, 2460000.5, 0.0, 51.6, 30.0, 0.001, 90.0, 0.0, 15.5).unwrap();
+        let mut sets = vec![current.clone()];
+        for day in 1..10 {
+            let jd = 2460000.5 + day as f64;
+            let minutes = (jd - current.epoch_jd) * MINUTES_PER_DAY;
+            let (r, v) = Sgp4Propagator::from_mean_elements(current.clone()).position_velocity(minutes).unwrap();
+            let (r, mut v) = (Vector3::from(r), Vector3::from(v));
+            if day == burn_day {
+                let [radial, transverse, normal] = crate::frames::rtn_basis(&r, &v).unwrap();
+                v += radial * burn.x + transverse * burn.y + normal * burn.z;
+            }
+            current = osculating_to_mean(7, &r, &v, jd, 0.0).unwrap();
+            sets.push(current.clone());
+        }
+        sets
+    }
+
+    #[test]
+    fn test_detects_in_plane_and_plane_change_burns() {
+        let thresholds = ManeuverThresholds::default();
+        assert!(detect_maneuvers(&history(0, Vector3::zeros()), &thresholds).unwrap().is_empty());
+
+        // 1 m/s prograde raises a by about 2 km at this altitude
+        let raise = detect_maneuvers(&history(4, Vector3::new(0.0, 1e-3, 0.0)), &thresholds).unwrap();
+        assert_eq!(raise.len(), 1, "{:?}", raise);
+        assert_eq!((raise[0].before_jd, raise[0].after_jd), (2460003.5, 2460004.5));
+        assert_eq!(raise[0].kind, "in-plane");
+        assert!(raise[0].sma_change_km > 1.5 && raise[0].sma_change_km < 2.5, "{}", raise[0].sma_change_km);
+        assert!((raise[0].delta_v_km_s - 1e-3).abs() < 2e-4, "{}", raise[0].delta_v_km_s);
+
+        let mut shuffled = history(6, Vector3::new(0.0, 0.0, 5e-3));
+        shuffled.reverse();
+        let plane = detect_maneuvers(&shuffled, &thresholds).unwrap();
+        assert_eq!(plane.len(), 1, "{:?}", plane);
+        assert_eq!(plane[0].kind, "out-of-plane");
+        assert_eq!(plane[0].after_jd, 2460006.5);
+    }
+
+    #[test]
+    fn test_robust_threshold_and_mixed_ids() {
+        assert_eq!(robust_threshold(&[1.0, 2.0], 0.5, 5.0), 0.5);
+        let noisy = [0.3, -0.2, 0.25, -0.3, 0.2, -0.25];
+        assert!(robust_threshold(&noisy, 0.5, 5.0) > 1.0);
+
+        let mut mixed = history(0, Vector3::zeros());
+        mixed[3].norad_id = 8;
+        assert!(detect_maneuvers(&mixed, &ManeuverThresholds::default()).is_err());
+    }
+}
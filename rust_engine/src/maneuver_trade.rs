@@ -0,0 +1,319 @@
+//! Screening a primary's candidate manoeuvres against the catalogue
+//!
+//! Each candidate is one impulsive burn (epoch and RTN delta-v). The burned
+//! primary follows its nominal trajectory up to the burn; afterwards the
+//! burn's effect, the difference between J2 propagations of the burned and
+//! unburned states, is added to the nominal trajectory. The nominal force
+//! model (SGP4 drag, operator ephemeris) is kept, and a zero burn
+//! reproduces the nominal screen exactly.
+//!
+//! All candidates are tabulated as ephemerides and screened against the
+//! catalogue in one pass, so the catalogue is propagated once however many
+//! burns are traded.
+
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+
+use nalgebra::{Vector3, Vector6};
+
+use crate::constants::SECONDS_PER_DAY;
+use crate::ephemeris::{Ephemeris, Interpolation};
+use crate::epoch::Epoch;
+use crate::error::OrbitError;
+use crate::frames;
+use crate::progress::Progress;
+use crate::propagation::{self, ForceModel, Integrator};
+use crate::screening::{ScreeningPipeline, Trajectory};
+use crate::Conjunction;
+#[cfg(feature = "python")]
+use crate::progress::{self, CancellationToken};
+#[cfg(feature = "python")]
+use crate::screening::PairSelection;
+
+/// Spacing of the tabulated burned trajectories (s)
+const SAMPLE_STEP_S: f64 = 60.0;
+
+/// Interpolation degree of the tabulated trajectories
+const SAMPLE_DEGREE: usize = 9;
+
+/// First integrator step of the burn-effect propagations (s)
+const INITIAL_STEP_S: f64 = 60.0;
+
+/// One impulsive burn of the primary
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CandidateManeuver {
+    /// Burn epoch (Julian date, UTC)
+    pub epoch_jd: f64,
+    /// Delta-v in the primary's radial / in-track / cross-track frame (km/s)
+    pub delta_v_rtn: Vector3<f64>,
+}
+
+/// Screening result of one candidate
+#[cfg_attr(feature = "python", pyclass(get_all))]
+#[derive(Clone)]
+pub struct ManeuverOutcome {
+    /// Position of the candidate in the list traded
+    pub candidate: usize,
+    pub epoch_jd: f64,
+    /// Delta-v (radial, in-track, cross-track) in km/s
+    pub delta_v_rtn: (f64, f64, f64),
+    /// Every close approach of the burned primary, by TCA
+    pub conjunctions: Vec<Conjunction>,
+    /// The closest of them, None if the window is clear
+    pub worst: Option<Conjunction>,
+}
+
+impl ManeuverOutcome {
+    /// Smallest miss distance over the window (infinite if clear)
+    pub fn min_distance_km(&self) -> f64 {
+        self.worst.as_ref().map_or(f64::INFINITY, |c| c.distance_km)
+    }
+}
+
+/// State of `y` after `dt` seconds under J2
+fn carried(y: &Vector6<f64>, dt: f64) -> Option<Vector6<f64>> {
+    if dt == 0.0 {
+        return Some(*y);
+    }
+    propagation::integrate(y, dt, ForceModel::J2, Integrator::DormandPrince { initial_step: INITIAL_STEP_S })
+}
+
+fn stacked((r, v): (Vector3<f64>, Vector3<f64>)) -> Vector6<f64> {
+    Vector6::new(r.x, r.y, r.z, v.x, v.y, v.z)
+}
+
+/// `primary` with `maneuver` applied, tabulated over [start_jd, end_jd]
+///
+/// Samples before the burn are the nominal trajectory; the burn epoch
+/// itself is sampled (post-burn) when it falls inside the window. Nominal
+/// states that are unavailable (SGP4 failure, outside an ephemeris) are
+/// left out of the table.
+pub fn burned_trajectory(
+    primary: &Trajectory,
+    maneuver: &CandidateManeuver,
+    start_jd: f64,
+    end_jd: f64,
+) -> Result<Trajectory, String> {
+    if end_jd <= start_jd {
+        return Err("end_epoch must be after start_epoch".to_string());
+    }
+    let burn_s = (maneuver.epoch_jd - start_jd) * SECONDS_PER_DAY;
+    let duration = (end_jd - start_jd) * SECONDS_PER_DAY;
+    let mut times: Vec<f64> = (0..=(duration / SAMPLE_STEP_S).ceil() as usize)
+        .map(|k| (k as f64 * SAMPLE_STEP_S).min(duration))
+        .collect();
+    if burn_s > 0.0 && burn_s < duration && !times.contains(&burn_s) {
+        let at = times.partition_point(|&t| t < burn_s);
+        times.insert(at, burn_s);
+    }
+
+    // Burned and unburned states carried together from the burn
+    let nominal_at_burn = primary
+        .state_at(start_jd, burn_s)
+        .ok_or_else(|| "The primary has no state at the burn epoch".to_string())?;
+    let basis = frames::rtn_basis(&nominal_at_burn.0, &nominal_at_burn.1)
+        .ok_or_else(|| "The primary's RTN frame is undefined at the burn epoch".to_string())?;
+    let dv = maneuver.delta_v_rtn;
+    let delta_v = basis[0] * dv.x + basis[1] * dv.y + basis[2] * dv.z;
+    let mut unburned = stacked(nominal_at_burn);
+    let mut burned = unburned + Vector6::new(0.0, 0.0, 0.0, delta_v.x, delta_v.y, delta_v.z);
+    let mut carried_to = burn_s;
+
+    let reference = Epoch::from_jd_utc(start_jd);
+    let mut samples = Vec::with_capacity(times.len());
+    for &t in &times {
+        let Some(nominal) = primary.state_at(start_jd, t) else {
+            continue;
+        };
+        let state = if t < burn_s {
+            nominal
+        } else {
+            let failed = || format!("Propagating the burn effect to {} s into the window failed", t);
+            unburned = carried(&unburned, t - carried_to).ok_or_else(failed)?;
+            burned = carried(&burned, t - carried_to).ok_or_else(failed)?;
+            carried_to = t;
+            let offset = burned - unburned;
+            (nominal.0 + offset.fixed_rows::<3>(0), nominal.1 + offset.fixed_rows::<3>(3))
+        };
+        samples.push((reference.plus_seconds(t), state));
+    }
+    Ephemeris::from_samples(primary.norad_id(), samples, "TEME", Interpolation::Hermite, SAMPLE_DEGREE)
+        .map(Trajectory::from)
+}
+
+/// Screen every candidate burn of `primary` against `catalog` in one pass
+///
+/// Outcomes come back in candidate order. Catalogue entries with the
+/// primary's NORAD id are its own nominal trajectory and are skipped.
+#[allow(clippy::too_many_arguments)]
+pub fn screen_maneuvers(
+    pipeline: &mut ScreeningPipeline,
+    primary: &Trajectory,
+    candidates: &[CandidateManeuver],
+    catalog: &[Trajectory],
+    start_jd: f64,
+    end_jd: f64,
+    step: f64,
+    progress: &Progress,
+) -> Result<Vec<ManeuverOutcome>, OrbitError> {
+    let primaries = candidates
+        .iter()
+        .map(|maneuver| burned_trajectory(primary, maneuver, start_jd, end_jd))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(OrbitError::InvalidInput)?;
+    let each = pipeline.screen_primaries_each(&primaries, catalog, start_jd, end_jd, step, progress)?;
+    Ok(candidates
+        .iter()
+        .zip(each)
+        .enumerate()
+        .map(|(candidate, (maneuver, mut conjunctions))| {
+            conjunctions.sort_by(|a, b| a.tca_jd.unwrap_or(f64::NAN).total_cmp(&b.tca_jd.unwrap_or(f64::NAN)));
+            let worst = conjunctions.iter().min_by(|a, b| a.distance_km.total_cmp(&b.distance_km)).cloned();
+            let dv = maneuver.delta_v_rtn;
+            ManeuverOutcome {
+                candidate,
+                epoch_jd: maneuver.epoch_jd,
+                delta_v_rtn: (dv.x, dv.y, dv.z),
+                conjunctions,
+                worst,
+            }
+        })
+        .collect())
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl ManeuverOutcome {
+    /// Smallest miss distance over the window (inf if clear)
+    #[getter(min_distance_km)]
+    fn py_min_distance_km(&self) -> f64 {
+        self.min_distance_km()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "ManeuverOutcome(candidate={}, epoch_jd={:.6}, delta_v_rtn={:?}, conjunctions={}, min_distance_km={:.3})",
+            self.candidate,
+            self.epoch_jd,
+            self.delta_v_rtn,
+            self.conjunctions.len(),
+            self.min_distance_km()
+        )
+    }
+}
+
+/// Screen candidate avoidance burns of one primary against the catalogue
+///
+/// `candidates` are (burn_epoch_jd, (dv_radial, dv_in_track, dv_cross_track))
+/// with delta-v in km/s; include a zero burn to get the nominal screen for
+/// comparison. Returns one `ManeuverOutcome` per candidate, in order, each
+/// with all its conjunctions and the worst (closest) one, so a burn that
+/// clears one conjunction but creates another shows up in a single run.
+/// `progress`, `cancel` and `progress_interval_s` are as for `screen_window`.
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(signature = (
+    primary,
+    candidates,
+    catalog,
+    start_epoch,
+    end_epoch,
+    threshold_km,
+    step = 60.0,
+    progress = None,
+    cancel = None,
+    progress_interval_s = 1.0,
+    exclude_pairs = None
+))]
+#[allow(clippy::too_many_arguments)]
+pub fn screen_maneuver_candidates(
+    py: Python<'_>,
+    primary: Trajectory,
+    candidates: Vec<(f64, (f64, f64, f64))>,
+    catalog: Vec<Trajectory>,
+    start_epoch: f64,
+    end_epoch: f64,
+    threshold_km: f64,
+    step: f64,
+    progress: Option<&PyAny>,
+    cancel: Option<CancellationToken>,
+    progress_interval_s: f64,
+    exclude_pairs: Option<Vec<(i32, i32)>>,
+) -> PyResult<Vec<ManeuverOutcome>> {
+    if candidates.is_empty() {
+        return Err(pyo3::exceptions::PyValueError::new_err("At least one candidate manoeuvre is required"));
+    }
+    let candidates: Vec<CandidateManeuver> = candidates
+        .into_iter()
+        .map(|(epoch_jd, (r, t, n))| CandidateManeuver { epoch_jd, delta_v_rtn: Vector3::new(r, t, n) })
+        .collect();
+    let counters = Progress::new(0, cancel.as_ref());
+    let mut pipeline = ScreeningPipeline::with_threshold(threshold_km);
+    pipeline.selection = PairSelection::new(exclude_pairs.unwrap_or_default(), None);
+    progress::run_monitored(py, &counters, progress, progress_interval_s, || {
+        screen_maneuvers(&mut pipeline, &primary, &candidates, &catalog, start_epoch, end_epoch, step, &counters)
+            .map_err(PyErr::from)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sgp4::{MeanElements, Sgp4Propagator};
+
+    fn propagator(norad_id: i32, raan_deg: f64, mean_anomaly_deg: f64) -> Trajectory {
+        let elements = MeanElements::new(
+            norad_id, 2460000.5, 0.0, 90.0, raan_deg, 0.0001, 0.0, mean_anomaly_deg, 15.0,
+        )
+        .unwrap();
+        Sgp4Propagator::from_mean_elements(elements).into()
+    }
+
+    #[test]
+    fn test_zero_burn_reproduces_nominal_screen() {
+        let (start, end) = (2460000.5, 2460000.6);
+        let primary = propagator(1, 0.0, 0.0);
+        let catalog = vec![propagator(2, 90.0, 0.0), propagator(3, 45.0, 180.0)];
+        let mut pipeline = ScreeningPipeline::with_threshold(50.0);
+        let mut nominal = pipeline
+            .screen_primaries(std::slice::from_ref(&primary), &catalog, start, end, 60.0, &Progress::new(0, None))
+            .unwrap();
+        nominal.sort_by(|a, b| a.tca_jd.unwrap().total_cmp(&b.tca_jd.unwrap()));
+        assert!(!nominal.is_empty());
+
+        let candidates = [
+            CandidateManeuver { epoch_jd: start + 0.01, delta_v_rtn: Vector3::zeros() },
+            CandidateManeuver { epoch_jd: start + 0.01, delta_v_rtn: Vector3::new(0.0, 0.01, 0.0) },
+        ];
+        let progress = Progress::new(0, None);
+        let outcomes = screen_maneuvers(&mut pipeline, &primary, &candidates, &catalog, start, end, 60.0, &progress).unwrap();
+        assert_eq!(outcomes.len(), 2);
+        assert_eq!(outcomes[0].conjunctions.len(), nominal.len());
+        for (traded, expected) in outcomes[0].conjunctions.iter().zip(&nominal) {
+            assert_eq!(traded.norad_id_2, expected.norad_id_2);
+            assert!((traded.distance_km - expected.distance_km).abs() < 1e-3);
+        }
+        // a 10 m/s in-track burn moves the primary by many kilometres within hours
+        let before = outcomes[0].min_distance_km();
+        let after = outcomes[1].min_distance_km();
+        assert!((after - before).abs() > 1.0, "{} vs {}", before, after);
+    }
+
+    #[test]
+    fn test_burn_leaves_trajectory_before_epoch_unchanged() {
+        let (start, end) = (2460000.5, 2460000.6);
+        let primary = propagator(1, 0.0, 0.0);
+        let burn = CandidateManeuver { epoch_jd: start + 0.05, delta_v_rtn: Vector3::new(0.0, 0.001, 0.0) };
+        let burned = burned_trajectory(&primary, &burn, start, end).unwrap();
+        let early = 0.02 * SECONDS_PER_DAY;
+        let (r0, _) = primary.state_at(start, early).unwrap();
+        let (r1, _) = burned.state_at(start, early).unwrap();
+        // only the tabulation error of the 60 s ephemeris
+        assert!((r1 - r0).norm() < 1e-3);
+        let late = 0.09 * SECONDS_PER_DAY;
+        let (r0, _) = primary.state_at(start, late).unwrap();
+        let (r1, _) = burned.state_at(start, late).unwrap();
+        // 1 m/s in-track: roughly 3 * dv * t of along-track drift after ~1 hour
+        assert!((r1 - r0).norm() > 5.0 && (r1 - r0).norm() < 20.0, "{}", (r1 - r0).norm());
+    }
+}
@@ -0,0 +1,299 @@
+//! Impulsive orbit transfer calculators
+//!
+//! Quick what-if sizing for orbit changes (Hohmann, bi-elliptic, plane
+//! change, phasing), as opposed to the conjunction-driven avoidance burns
+//! of `maneuver`. The initial orbit comes from a `Satellite` state or
+//! `KeplerianElements` and is treated as circular at its semi-major axis;
+//! all burns are impulsive and coplanar unless stated.
+
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use std::f64::consts::PI;
+
+use crate::constants::{EARTH_RADIUS_KM, MU_EARTH};
+use crate::elements::{self, KeplerianElements};
+use crate::error::OrbitError;
+use crate::Satellite;
+
+/// Initial orbit: a state vector or classical elements
+#[cfg_attr(feature = "python", derive(FromPyObject))]
+#[allow(clippy::large_enum_variant)]
+pub enum OrbitInput {
+    Satellite(Satellite),
+    Elements(KeplerianElements),
+}
+
+impl OrbitInput {
+    /// Classical elements of the orbit, which must be bound
+    pub fn elements(&self) -> Result<KeplerianElements, OrbitError> {
+        let elements = match self {
+            OrbitInput::Satellite(sat) => {
+                elements::from_state(&sat.r(), &sat.v())
+            }
+            OrbitInput::Elements(el) => Some(el.clone()),
+        };
+        elements
+            .filter(|el| el.eccentricity < 1.0 && el.semi_major_axis_km > 0.0)
+            .ok_or_else(|| OrbitError::InvalidInput("Initial orbit must be bound".to_string()))
+    }
+
+    /// Radius of the equivalent circular orbit (km)
+    pub fn radius(&self) -> Result<f64, OrbitError> {
+        Ok(self.elements()?.semi_major_axis_km)
+    }
+
+    /// Speed at the current position (km/s): the state's for a `Satellite`, at the true anomaly for elements
+    pub fn speed(&self) -> Result<f64, OrbitError> {
+        match self {
+            OrbitInput::Satellite(sat) => Ok(sat.v().norm()),
+            OrbitInput::Elements(el) => elements::to_state(el)
+                .map(|(_, v)| v.norm())
+                .ok_or_else(|| OrbitError::InvalidInput("Invalid orbital elements".to_string())),
+        }
+    }
+}
+
+fn circular_speed(radius: f64) -> f64 {
+    (MU_EARTH / radius).sqrt()
+}
+
+/// Vis-viva speed at `radius` on an orbit of semi-major axis `a`
+fn speed(radius: f64, a: f64) -> f64 {
+    (MU_EARTH * (2.0 / radius - 1.0 / a)).sqrt()
+}
+
+fn half_period(a: f64) -> f64 {
+    PI * (a * a * a / MU_EARTH).sqrt()
+}
+
+/// Delta-v of rotating a velocity `v1` into `v2` through `angle` (law of cosines)
+fn combined_burn(v1: f64, v2: f64, angle: f64) -> f64 {
+    (v1 * v1 + v2 * v2 - 2.0 * v1 * v2 * angle.cos()).max(0.0).sqrt()
+}
+
+fn check_radius(radius: f64, name: &str) -> Result<(), OrbitError> {
+    if radius <= EARTH_RADIUS_KM || !radius.is_finite() {
+        return Err(OrbitError::InvalidInput(format!(
+            "{} must be above the Earth's surface (km from the centre)",
+            name
+        )));
+    }
+    Ok(())
+}
+
+/// Sequence of impulsive burns
+#[cfg_attr(feature = "python", pyclass(get_all))]
+#[derive(Clone, Debug)]
+pub struct Transfer {
+    pub kind: &'static str,
+    pub delta_v_m_s: Vec<f64>,                 // magnitude of each burn, in order
+    pub time_of_flight_s: f64,                 // first to last burn
+    pub transfer_semi_major_axes_km: Vec<f64>, // of the intermediate orbits
+}
+
+impl Transfer {
+    /// Sum of burn magnitudes (m/s)
+    pub fn total_delta_v_m_s(&self) -> f64 {
+        self.delta_v_m_s.iter().sum()
+    }
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl Transfer {
+    #[getter]
+    #[pyo3(name = "total_delta_v_m_s")]
+    fn py_total_delta_v_m_s(&self) -> f64 {
+        self.total_delta_v_m_s()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Transfer({}, burns={}, total_dv={:.2}m/s, tof={:.0}s)",
+            self.kind,
+            self.delta_v_m_s.len(),
+            self.total_delta_v_m_s(),
+            self.time_of_flight_s
+        )
+    }
+}
+
+/// Hohmann transfer between circular orbits of radius `r1` and `r2` (km)
+///
+/// An inclination change is folded into the burn at the larger radius.
+pub fn hohmann(r1: f64, r2: f64, inclination_change: f64) -> Transfer {
+    let a = 0.5 * (r1 + r2);
+    let high = r1.max(r2);
+    let first = (speed(r1, a) - circular_speed(r1)).abs();
+    let second = (circular_speed(r2) - speed(r2, a)).abs();
+    // the plane change goes where the speed is lowest, at the larger radius
+    let (first, second) = if inclination_change == 0.0 {
+        (first, second)
+    } else if r2 == high {
+        (first, combined_burn(speed(high, a), circular_speed(high), inclination_change))
+    } else {
+        (combined_burn(circular_speed(high), speed(high, a), inclination_change), second)
+    };
+    Transfer {
+        kind: "hohmann",
+        delta_v_m_s: vec![first * 1.0e3, second * 1.0e3],
+        time_of_flight_s: half_period(a),
+        transfer_semi_major_axes_km: vec![a],
+    }
+}
+
+/// Bi-elliptic transfer from `r1` to `r2` through an apoapsis at `rb` (km)
+pub fn bielliptic(r1: f64, r2: f64, rb: f64) -> Transfer {
+    let (a1, a2) = (0.5 * (r1 + rb), 0.5 * (r2 + rb));
+    let burns = [
+        (speed(r1, a1) - circular_speed(r1)).abs(),
+        (speed(rb, a2) - speed(rb, a1)).abs(),
+        (speed(r2, a2) - circular_speed(r2)).abs(),
+    ];
+    Transfer {
+        kind: "bi-elliptic",
+        delta_v_m_s: burns.iter().map(|dv| dv * 1.0e3).collect(),
+        time_of_flight_s: half_period(a1) + half_period(a2),
+        transfer_semi_major_axes_km: vec![a1, a2],
+    }
+}
+
+/// Phasing manoeuvre on a circular orbit of radius `r` (km)
+///
+/// A target `phase_angle` (rad) ahead along the orbit is met after
+/// `revolutions` turns of a phasing orbit of period T (1 - phase / (2 pi k)),
+/// entered and left with equal and opposite in-track burns.
+pub fn phasing(r: f64, phase_angle: f64, revolutions: u32) -> Result<Transfer, String> {
+    let k = f64::from(revolutions);
+    let period = 2.0 * half_period(r);
+    let phasing_period = period * (1.0 - phase_angle / (2.0 * PI * k));
+    if phasing_period <= 0.0 {
+        return Err("Phase angle too large for the number of revolutions".to_string());
+    }
+    let a = (MU_EARTH * (phasing_period / (2.0 * PI)).powi(2)).cbrt();
+    if 2.0 * a - r <= EARTH_RADIUS_KM {
+        return Err("Phasing orbit would intersect the Earth; use more revolutions".to_string());
+    }
+    let dv = (speed(r, a) - circular_speed(r)).abs() * 1.0e3;
+    Ok(Transfer {
+        kind: "phasing",
+        delta_v_m_s: vec![dv, dv],
+        time_of_flight_s: k * phasing_period,
+        transfer_semi_major_axes_km: vec![a],
+    })
+}
+
+/// Hohmann transfer from the current orbit to a circular orbit of radius `target_radius_km`
+///
+/// `orbit` is a `Satellite` or `KeplerianElements`, treated as circular at its
+/// semi-major axis. A non-zero `inclination_change_deg` is combined with the
+/// burn at the larger radius.
+pub fn hohmann_transfer(orbit: &OrbitInput, target_radius_km: f64, inclination_change_deg: f64) -> Result<Transfer, OrbitError> {
+    let r1 = orbit.radius()?;
+    check_radius(target_radius_km, "target_radius_km")?;
+    Ok(hohmann(r1, target_radius_km, inclination_change_deg.to_radians()))
+}
+
+/// Bi-elliptic transfer to a circular orbit of radius `target_radius_km` via `intermediate_radius_km`
+pub fn bielliptic_transfer(orbit: &OrbitInput, target_radius_km: f64, intermediate_radius_km: f64) -> Result<Transfer, OrbitError> {
+    let r1 = orbit.radius()?;
+    check_radius(target_radius_km, "target_radius_km")?;
+    if intermediate_radius_km < r1.max(target_radius_km) {
+        return Err(OrbitError::InvalidInput(
+            "intermediate_radius_km must be at least the larger of the two orbit radii".to_string(),
+        ));
+    }
+    Ok(bielliptic(r1, target_radius_km, intermediate_radius_km))
+}
+
+/// Delta-v (m/s) of a pure plane change by `inclination_change_deg` at the current position
+pub fn plane_change(orbit: &OrbitInput, inclination_change_deg: f64) -> Result<f64, OrbitError> {
+    Ok(2.0 * orbit.speed()? * (0.5 * inclination_change_deg.to_radians()).sin().abs() * 1.0e3)
+}
+
+/// Phasing orbit to meet a target `phase_angle_deg` ahead (negative: behind) after `revolutions`
+pub fn phasing_orbit(orbit: &OrbitInput, phase_angle_deg: f64, revolutions: u32) -> Result<Transfer, OrbitError> {
+    if revolutions == 0 {
+        return Err(OrbitError::InvalidInput("revolutions must be at least 1".to_string()));
+    }
+    phasing(orbit.radius()?, phase_angle_deg.to_radians(), revolutions).map_err(OrbitError::InvalidInput)
+}
+
+/// Hohmann transfer from the current orbit to a circular orbit of radius `target_radius_km`
+///
+/// `orbit` is a `Satellite` or `KeplerianElements`, treated as circular at its
+/// semi-major axis. A non-zero `inclination_change_deg` is combined with the
+/// burn at the larger radius.
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(name = "hohmann_transfer", signature = (orbit, target_radius_km, inclination_change_deg = 0.0))]
+pub fn py_hohmann_transfer(orbit: OrbitInput, target_radius_km: f64, inclination_change_deg: f64) -> PyResult<Transfer> {
+    Ok(hohmann_transfer(&orbit, target_radius_km, inclination_change_deg)?)
+}
+
+/// Bi-elliptic transfer to a circular orbit of radius `target_radius_km` via `intermediate_radius_km`
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(name = "bielliptic_transfer")]
+pub fn py_bielliptic_transfer(orbit: OrbitInput, target_radius_km: f64, intermediate_radius_km: f64) -> PyResult<Transfer> {
+    Ok(bielliptic_transfer(&orbit, target_radius_km, intermediate_radius_km)?)
+}
+
+/// Delta-v (m/s) of a pure plane change by `inclination_change_deg` at the current position
+///
+/// Uses the state's speed for a `Satellite`, and the speed at the true anomaly for elements.
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(name = "plane_change")]
+pub fn py_plane_change(orbit: OrbitInput, inclination_change_deg: f64) -> PyResult<f64> {
+    Ok(plane_change(&orbit, inclination_change_deg)?)
+}
+
+/// Phasing orbit to meet a target `phase_angle_deg` ahead (negative: behind) after `revolutions`
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(name = "phasing_orbit", signature = (orbit, phase_angle_deg, revolutions = 1))]
+pub fn py_phasing_orbit(orbit: OrbitInput, phase_angle_deg: f64, revolutions: u32) -> PyResult<Transfer> {
+    Ok(phasing_orbit(&orbit, phase_angle_deg, revolutions)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hohmann_and_bielliptic() {
+        // LEO (300 km) to GEO: 2.43 + 1.47 km/s
+        let transfer = hohmann(6678.137, 42164.17, 0.0);
+        assert!((transfer.delta_v_m_s[0] - 2425.7).abs() < 1.0, "{:?}", transfer.delta_v_m_s);
+        assert!((transfer.delta_v_m_s[1] - 1466.7).abs() < 1.0, "{:?}", transfer.delta_v_m_s);
+        assert!((transfer.time_of_flight_s / 3600.0 - 5.27).abs() < 0.01);
+        // reversing the transfer costs the same
+        let back = hohmann(42164.17, 6678.137, 0.0);
+        assert!((back.delta_v_m_s.iter().sum::<f64>() - transfer.delta_v_m_s.iter().sum::<f64>()).abs() < 1e-6);
+        // folding the GEO plane change into the apogee burn beats doing it separately
+        let with_plane = hohmann(6678.137, 42164.17, 28.5f64.to_radians());
+        let separate = transfer.delta_v_m_s[1] + 2.0 * circular_speed(42164.17) * 1e3 * (14.25f64.to_radians()).sin();
+        assert!(with_plane.delta_v_m_s[1] < separate);
+
+        // above a radius ratio of about 15.6 a distant bi-elliptic transfer is cheaper
+        let (r1, r2) = (7000.0, 7000.0 * 20.0);
+        let total = |t: &Transfer| t.delta_v_m_s.iter().sum::<f64>();
+        assert!(total(&bielliptic(r1, r2, 60.0 * r1)) < total(&hohmann(r1, r2, 0.0)));
+        assert!(total(&bielliptic(r1, 3.0 * r1, 4.0 * r1)) > total(&hohmann(r1, 3.0 * r1, 0.0)));
+    }
+
+    #[test]
+    fn test_phasing_closes_the_gap() {
+        let r = 7000.0;
+        let phase = 20f64.to_radians();
+        let transfer = phasing(r, phase, 2).unwrap();
+        // the target, moving at the circular rate, covers 4 pi - phase in the phasing time
+        let n = (MU_EARTH / (r * r * r)).sqrt();
+        assert!((n * transfer.time_of_flight_s - (4.0 * PI - phase)).abs() < 1e-9);
+        assert!(transfer.transfer_semi_major_axes_km[0] < r);
+        // a single revolution to catch up 180 degrees dips into the atmosphere
+        assert!(phasing(r, PI, 1).is_err());
+        assert!(phasing(r, -PI, 1).is_ok());
+    }
+}
@@ -0,0 +1,252 @@
+//! Catalogue metadata attached to objects
+//!
+//! Object types follow the CDM OBJECT_TYPE keyword. RCS size classes are the
+//! public SATCAT bins (small < 0.1 m^2, medium 0.1-1 m^2, large > 1 m^2);
+//! when no hard-body radius is given, the radius of a sphere with the upper
+//! bin area is used (2 m for the open-ended large class).
+//!
+//! Orbit regimes use perigee/apogee altitudes: LEO below 2000 km, GEO
+//! within 200 km of the geostationary altitude, HEO for eccentricities of
+//! 0.25 and above, MEO between LEO and GEO; anything else is OTHER.
+//!
+//! Constellation tags group objects by owner or constellation (e.g.
+//! "STARLINK"); two objects belong to the same constellation when both are
+//! tagged and the tags match ignoring case. Screens can drop such pairs or
+//! keep only them, since operators handle them apart from cross-operator
+//! events.
+use serde::{Deserialize, Serialize};
+
+use crate::constants::EARTH_RADIUS_KM;
+
+/// Upper altitude of low Earth orbit (km)
+const LEO_CEILING_KM: f64 = 2000.0;
+
+/// Geostationary altitude and the band counted as GEO (km)
+pub(crate) const GEO_ALTITUDE_KM: f64 = 35786.0;
+pub(crate) const GEO_BAND_KM: f64 = 200.0;
+
+/// Eccentricity from which an orbit counts as highly elliptical
+const HEO_ECCENTRICITY: f64 = 0.25;
+
+
+/// Object classification
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum ObjectType {
+    Payload,
+    #[serde(rename = "ROCKET BODY")]
+    RocketBody,
+    Debris,
+    Unknown,
+}
+
+impl ObjectType {
+    /// CDM OBJECT_TYPE value
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ObjectType::Payload => "PAYLOAD",
+            ObjectType::RocketBody => "ROCKET BODY",
+            ObjectType::Debris => "DEBRIS",
+            ObjectType::Unknown => "UNKNOWN",
+        }
+    }
+}
+
+impl std::str::FromStr for ObjectType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_uppercase().replace('_', " ").as_str() {
+            "PAYLOAD" | "PAY" => Ok(ObjectType::Payload),
+            "ROCKET BODY" | "R/B" | "RB" => Ok(ObjectType::RocketBody),
+            "DEBRIS" | "DEB" => Ok(ObjectType::Debris),
+            "UNKNOWN" | "UNK" | "TBA" => Ok(ObjectType::Unknown),
+            _ => Err(format!(
+                "Unknown object type '{}' (expected payload, rocket body, debris or unknown)",
+                s
+            )),
+        }
+    }
+}
+
+/// Radar cross-section size class
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum RcsSize {
+    Small,
+    Medium,
+    Large,
+}
+
+impl RcsSize {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RcsSize::Small => "SMALL",
+            RcsSize::Medium => "MEDIUM",
+            RcsSize::Large => "LARGE",
+        }
+    }
+
+    /// Default hard-body radius for the class (km)
+    pub fn default_radius_km(&self) -> f64 {
+        match self {
+            RcsSize::Small => 0.000178,
+            RcsSize::Medium => 0.000564,
+            RcsSize::Large => 0.002,
+        }
+    }
+}
+
+impl std::str::FromStr for RcsSize {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_uppercase().as_str() {
+            "SMALL" => Ok(RcsSize::Small),
+            "MEDIUM" => Ok(RcsSize::Medium),
+            "LARGE" => Ok(RcsSize::Large),
+            _ => Err(format!("Unknown RCS size '{}' (expected small, medium or large)", s)),
+        }
+    }
+}
+
+/// Orbit regime
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Regime {
+    Leo,
+    Meo,
+    Geo,
+    Heo,
+    Other,
+}
+
+impl Regime {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Regime::Leo => "LEO",
+            Regime::Meo => "MEO",
+            Regime::Geo => "GEO",
+            Regime::Heo => "HEO",
+            Regime::Other => "OTHER",
+        }
+    }
+
+    /// Classify from perigee and apogee radii (km)
+    pub fn classify(perigee_km: f64, apogee_km: f64) -> Self {
+        let (perigee, apogee) = (perigee_km - EARTH_RADIUS_KM, apogee_km - EARTH_RADIUS_KM);
+        let eccentricity = (apogee_km - perigee_km) / (apogee_km + perigee_km);
+        let geo = (GEO_ALTITUDE_KM - GEO_BAND_KM)..=(GEO_ALTITUDE_KM + GEO_BAND_KM);
+        if apogee <= LEO_CEILING_KM {
+            Regime::Leo
+        } else if geo.contains(&perigee) && geo.contains(&apogee) {
+            Regime::Geo
+        } else if !eccentricity.is_finite() || eccentricity >= HEO_ECCENTRICITY {
+            Regime::Heo
+        } else if perigee >= LEO_CEILING_KM && apogee < *geo.start() {
+            Regime::Meo
+        } else {
+            Regime::Other
+        }
+    }
+}
+
+impl std::str::FromStr for Regime {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_uppercase().as_str() {
+            "LEO" => Ok(Regime::Leo),
+            "MEO" => Ok(Regime::Meo),
+            "GEO" => Ok(Regime::Geo),
+            "HEO" => Ok(Regime::Heo),
+            "OTHER" => Ok(Regime::Other),
+            _ => Err(format!("Unknown regime '{}' (expected LEO, MEO, GEO, HEO or OTHER)", s)),
+        }
+    }
+}
+
+/// Whether two constellation tags name the same constellation; untagged objects never match
+pub fn same_constellation(a: Option<&str>, b: Option<&str>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => a.trim().eq_ignore_ascii_case(b.trim()),
+        _ => false,
+    }
+}
+
+/// Which same-constellation pairs a screen reports
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ConstellationPairs {
+    /// Every pair (default)
+    #[default]
+    Include,
+    /// Only pairs from different constellations or with an untagged object
+    Exclude,
+    /// Only pairs within one constellation
+    Only,
+}
+
+impl ConstellationPairs {
+    /// Whether a pair is kept, given whether it is intra-constellation
+    pub fn keeps(&self, intra: bool) -> bool {
+        match self {
+            ConstellationPairs::Include => true,
+            ConstellationPairs::Exclude => !intra,
+            ConstellationPairs::Only => intra,
+        }
+    }
+}
+
+impl std::str::FromStr for ConstellationPairs {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "include" => Ok(ConstellationPairs::Include),
+            "exclude" => Ok(ConstellationPairs::Exclude),
+            "only" => Ok(ConstellationPairs::Only),
+            _ => Err(format!("Unknown constellation_pairs '{}' (expected include, exclude or only)", s)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_object_type_aliases() {
+        assert_eq!("rocket_body".parse::<ObjectType>(), Ok(ObjectType::RocketBody));
+        assert_eq!("R/B".parse::<ObjectType>(), Ok(ObjectType::RocketBody));
+        assert_eq!("deb".parse::<ObjectType>(), Ok(ObjectType::Debris));
+        assert!("station".parse::<ObjectType>().is_err());
+    }
+
+    #[test]
+    fn test_rcs_default_radius_matches_bin_area() {
+        for (size, area_m2) in [(RcsSize::Small, 0.1), (RcsSize::Medium, 1.0)] {
+            let radius_m = size.default_radius_km() * 1.0e3;
+            assert!((std::f64::consts::PI * radius_m * radius_m - area_m2).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_regime_classification() {
+        let altitude = |km: f64| EARTH_RADIUS_KM + km;
+        assert_eq!(Regime::classify(altitude(400.0), altitude(420.0)), Regime::Leo);
+        assert_eq!(Regime::classify(altitude(20100.0), altitude(20300.0)), Regime::Meo);
+        assert_eq!(Regime::classify(altitude(35780.0), altitude(35790.0)), Regime::Geo);
+        assert_eq!(Regime::classify(altitude(500.0), altitude(39000.0)), Regime::Heo);
+        assert_eq!(Regime::classify(altitude(36100.0), altitude(36200.0)), Regime::Other);
+    }
+
+    #[test]
+    fn test_constellation_pairs() {
+        assert!(same_constellation(Some("Starlink"), Some("STARLINK ")));
+        assert!(!same_constellation(Some("STARLINK"), Some("ONEWEB")));
+        assert!(!same_constellation(None, None));
+        let exclude: ConstellationPairs = "exclude".parse().unwrap();
+        assert!(exclude.keeps(false) && !exclude.keeps(true));
+        assert!(ConstellationPairs::Only.keeps(true) && !ConstellationPairs::Only.keeps(false));
+        assert!("separate".parse::<ConstellationPairs>().is_err());
+    }
+}
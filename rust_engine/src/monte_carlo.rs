@@ -0,0 +1,293 @@
+//! Monte Carlo collision probability
+//!
+//! Draws perturbed state pairs from the two 6x6 covariances, propagates each
+//! pair under two-body gravity to its own closest approach and counts the
+//! fraction that pass within the hard-body radius. Unlike the Foster 2D
+//! method this makes no straight-line or constant-covariance assumption,
+//! so it serves as a cross-check for slow or highly eccentric encounters.
+
+use nalgebra::{Matrix6, Vector3, Vector6};
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use rayon::prelude::*;
+
+#[cfg(feature = "python")]
+use crate::covariance;
+use crate::error::OrbitError;
+use crate::parallel;
+use crate::propagation::{integrate, two_body_acceleration, ForceModel, Integrator};
+use crate::random::SplitMix64;
+use crate::Satellite;
+
+type State = Vector6<f64>;
+
+/// Newton iterations used to locate each sample's closest approach
+const TCA_ITERATIONS: usize = 8;
+
+/// Convergence tolerance on the closest-approach time (s)
+const TCA_TOLERANCE_S: f64 = 1.0e-6;
+
+/// Initial step handed to the adaptive integrator (s)
+const INITIAL_STEP_S: f64 = 60.0;
+
+impl SplitMix64 {
+    /// Six standard normal deviates (Box-Muller)
+    fn normal6(&mut self) -> State {
+        let mut z = State::zeros();
+        for k in 0..3 {
+            let radius = (-2.0 * self.next_f64().ln()).sqrt();
+            let angle = 2.0 * std::f64::consts::PI * self.next_f64();
+            z[2 * k] = radius * angle.cos();
+            z[2 * k + 1] = radius * angle.sin();
+        }
+        z
+    }
+}
+
+/// Square-root factor S with S S^T = cov, tolerating singular covariances
+fn sqrt_factor(cov: &Matrix6<f64>) -> Matrix6<f64> {
+    let eigen = cov.symmetric_eigen();
+    let scales = eigen.eigenvalues.map(|value| value.max(0.0).sqrt());
+    eigen.eigenvectors * Matrix6::from_diagonal(&scales)
+}
+
+/// Closest approach (s from the state epoch, km) of two objects under two-body gravity
+///
+/// Newton iteration on the range-rate, starting from the straight-line
+/// estimate and restricted to `[-window, window]`.
+pub fn closest_approach(y1: &State, y2: &State, window: f64) -> Option<(f64, f64)> {
+    let integrator = Integrator::DormandPrince { initial_step: INITIAL_STEP_S };
+    let state_at = |y: &State, t: f64| if t == 0.0 { Some(*y) } else { integrate(y, t, ForceModel::TwoBody, integrator) };
+    let relative = |a: &State, b: &State| {
+        let r = b.fixed_rows::<3>(0) - a.fixed_rows::<3>(0);
+        let v = b.fixed_rows::<3>(3) - a.fixed_rows::<3>(3);
+        let acc = two_body_acceleration(&b.fixed_rows::<3>(0).into_owned())
+            - two_body_acceleration(&a.fixed_rows::<3>(0).into_owned());
+        (Vector3::from(r), Vector3::from(v), acc)
+    };
+
+    let (r, v, _) = relative(y1, y2);
+    let mut t = if v.norm_squared() > 0.0 { (-r.dot(&v) / v.norm_squared()).clamp(-window, window) } else { 0.0 };
+    for _ in 0..TCA_ITERATIONS {
+        let (r, v, acc) = relative(&state_at(y1, t)?, &state_at(y2, t)?);
+        let slope = v.norm_squared() + r.dot(&acc);
+        if slope <= 0.0 {
+            break;
+        }
+        let next = (t - r.dot(&v) / slope).clamp(-window, window);
+        let converged = (next - t).abs() < TCA_TOLERANCE_S;
+        t = next;
+        if converged {
+            break;
+        }
+    }
+    let (r, _, _) = relative(&state_at(y1, t)?, &state_at(y2, t)?);
+    Some((t, r.norm()))
+}
+
+/// Fraction of `n_samples` perturbed state pairs passing within `hard_body_radius`
+///
+/// Each object is given as `(mean state, 6x6 covariance)`. Returns
+/// `(hits, valid_samples)`; samples whose propagation fails are discarded.
+pub fn sample_pc(
+    (mean1, cov1): (&State, &Matrix6<f64>),
+    (mean2, cov2): (&State, &Matrix6<f64>),
+    hard_body_radius: f64,
+    n_samples: usize,
+    seed: u64,
+    window: f64,
+) -> (usize, usize) {
+    let (s1, s2) = (sqrt_factor(cov1), sqrt_factor(cov2));
+    parallel::install(|| {
+        (0..n_samples)
+            .into_par_iter()
+            .filter_map(|i| {
+                let mut rng = SplitMix64::new(seed, i as u64);
+                let y1 = mean1 + s1 * rng.normal6();
+                let y2 = mean2 + s2 * rng.normal6();
+                let (_, miss) = closest_approach(&y1, &y2, window)?;
+                Some(usize::from(miss <= hard_body_radius))
+            })
+            .fold(|| (0, 0), |(hits, valid), hit| (hits + hit, valid + 1))
+            .reduce(|| (0, 0), |a, b| (a.0 + b.0, a.1 + b.1))
+    })
+}
+
+/// Monte Carlo probability estimate
+#[cfg_attr(feature = "python", pyclass(get_all))]
+#[derive(Clone, Debug)]
+pub struct MonteCarloPc {
+    pub probability: f64,
+    pub hits: usize,
+    pub samples: usize, // samples that propagated successfully
+}
+
+impl MonteCarloPc {
+    /// Binomial standard error of the estimate
+    pub fn standard_error(&self) -> f64 {
+        if self.samples == 0 {
+            return 0.0;
+        }
+        (self.probability * (1.0 - self.probability) / self.samples as f64).sqrt()
+    }
+
+    /// 95% Wilson score interval
+    pub fn confidence_interval(&self) -> (f64, f64) {
+        if self.samples == 0 {
+            return (0.0, 1.0);
+        }
+        let (n, p, z) = (self.samples as f64, self.probability, 1.959964);
+        let centre = (p + z * z / (2.0 * n)) / (1.0 + z * z / n);
+        let half = z / (1.0 + z * z / n) * (p * (1.0 - p) / n + z * z / (4.0 * n * n)).sqrt();
+        ((centre - half).max(0.0), (centre + half).min(1.0))
+    }
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl MonteCarloPc {
+    /// Binomial standard error of the estimate
+    #[getter]
+    #[pyo3(name = "standard_error")]
+    fn py_standard_error(&self) -> f64 {
+        self.standard_error()
+    }
+
+    /// 95% Wilson score interval
+    #[pyo3(name = "confidence_interval")]
+    fn py_confidence_interval(&self) -> (f64, f64) {
+        self.confidence_interval()
+    }
+
+    fn __float__(&self) -> f64 {
+        self.probability
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "MonteCarloPc(probability={:.3e}, hits={}, samples={})",
+            self.probability, self.hits, self.samples
+        )
+    }
+}
+
+fn state_of(satellite: &Satellite) -> State {
+    let (p, v) = (&satellite.position, &satellite.velocity);
+    State::new(p[0], p[1], p[2], v[0], v[1], v[2])
+}
+
+fn covariance_or_own(value: Option<Matrix6<f64>>, satellite: &Satellite) -> Result<Matrix6<f64>, OrbitError> {
+    value.or(satellite.covariance).ok_or_else(|| {
+        OrbitError::InvalidInput(format!("No covariance given for object {}", satellite.norad_id))
+    })
+}
+
+/// Monte Carlo probability of collision between two objects
+///
+/// Each object is a state near TCA, at a common epoch, with an optional 6x6
+/// covariance (None uses the satellite's own). Each sample pair is
+/// propagated to its own closest approach within `window` seconds.
+pub fn monte_carlo_pc(
+    (sat1, cov1): (&Satellite, Option<Matrix6<f64>>),
+    (sat2, cov2): (&Satellite, Option<Matrix6<f64>>),
+    hard_body_radius: f64,
+    n_samples: usize,
+    seed: u64,
+    window: f64,
+) -> Result<MonteCarloPc, OrbitError> {
+    if hard_body_radius <= 0.0 || n_samples == 0 || window <= 0.0 {
+        return Err(OrbitError::InvalidInput(
+            "hard_body_radius, n_samples and window must be positive".to_string(),
+        ));
+    }
+    crate::check_common_epoch(&[sat1.clone(), sat2.clone()])?;
+    let (cov1, cov2) = (covariance_or_own(cov1, sat1)?, covariance_or_own(cov2, sat2)?);
+    let (mean1, mean2) = (state_of(sat1), state_of(sat2));
+
+    let (hits, samples) = sample_pc((&mean1, &cov1), (&mean2, &cov2), hard_body_radius, n_samples, seed, window);
+    if samples == 0 {
+        return Err(OrbitError::Propagation("Every sample failed to propagate".to_string()));
+    }
+    Ok(MonteCarloPc {
+        probability: hits as f64 / samples as f64,
+        hits,
+        samples,
+    })
+}
+
+/// Monte Carlo probability of collision between two objects
+///
+/// `sat1`/`sat2` are states near TCA at a common epoch; `cov1`/`cov2` are
+/// 6x6 covariances (None uses the satellite's own). Each sample pair is
+/// propagated to its own closest approach within `window` seconds.
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(name = "monte_carlo_pc", signature = (sat1, cov1, sat2, cov2, hard_body_radius, n_samples = 100_000, seed = 0, window = 600.0))]
+#[allow(clippy::too_many_arguments)]
+pub fn py_monte_carlo_pc(
+    py: Python<'_>,
+    sat1: &Satellite,
+    cov1: Option<&PyAny>,
+    sat2: &Satellite,
+    cov2: Option<&PyAny>,
+    hard_body_radius: f64,
+    n_samples: usize,
+    seed: u64,
+    window: f64,
+) -> PyResult<MonteCarloPc> {
+    let covariance = |value: Option<&PyAny>| value.filter(|value| !value.is_none()).map(covariance::covariance_from_py).transpose();
+    let (cov1, cov2) = (covariance(cov1)?, covariance(cov2)?);
+    let estimate = py.allow_threads(|| monte_carlo_pc((sat1, cov1), (sat2, cov2), hard_body_radius, n_samples, seed, window))?;
+    Ok(estimate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::MU_EARTH;
+    use crate::covariance;
+    use crate::probability::foster_pc;
+
+    fn crossing_pair(offset_km: f64) -> (State, State) {
+        let speed = (MU_EARTH / 7000.0).sqrt();
+        (
+            State::new(7000.0, 0.0, 0.0, 0.0, speed, 0.0),
+            State::new(7000.0 + offset_km, 0.0, 0.0, 0.0, 0.0, speed),
+        )
+    }
+
+    #[test]
+    fn test_closest_approach_recovers_shifted_tca() {
+        let (y1, y2) = crossing_pair(0.0);
+        let shift = 5.0;
+        let y1_early = integrate(&y1, -shift, ForceModel::TwoBody, Integrator::DormandPrince { initial_step: 60.0 }).unwrap();
+        let y2_early = integrate(&y2, -shift, ForceModel::TwoBody, Integrator::DormandPrince { initial_step: 60.0 }).unwrap();
+        let (t, miss) = closest_approach(&y1_early, &y2_early, 600.0).unwrap();
+        assert!((t - shift).abs() < 1e-4, "{}", t);
+        assert!(miss < 1e-4, "{}", miss);
+    }
+
+    #[test]
+    fn test_sampling_is_reproducible() {
+        let (y1, y2) = crossing_pair(0.05);
+        let cov = Matrix6::from_diagonal(&Vector6::new(0.01, 0.01, 0.01, 1e-10, 1e-10, 1e-10));
+        let first = sample_pc((&y1, &cov), (&y2, &cov), 0.02, 2000, 7, 600.0);
+        assert_eq!(first, sample_pc((&y1, &cov), (&y2, &cov), 0.02, 2000, 7, 600.0));
+        assert_eq!(first.1, 2000);
+    }
+
+    #[test]
+    fn test_agrees_with_foster_for_fast_encounter() {
+        let (y1, y2) = crossing_pair(0.05);
+        let cov = Matrix6::from_diagonal(&Vector6::new(0.0025, 0.0025, 0.0025, 1e-12, 1e-12, 1e-12));
+        let (hits, samples) = sample_pc((&y1, &cov), (&y2, &cov), 0.02, 20_000, 1, 600.0);
+        let mc = hits as f64 / samples as f64;
+
+        let rel_pos = (y2 - y1).fixed_rows::<3>(0).into_owned();
+        let rel_vel = (y2 - y1).fixed_rows::<3>(3).into_owned();
+        let combined = covariance::position_block(&cov) * 2.0;
+        let analytic = foster_pc(&rel_pos, &rel_vel, &combined, 0.02).unwrap();
+        let sigma = (analytic * (1.0 - analytic) / samples as f64).sqrt();
+        assert!((mc - analytic).abs() < 4.0 * sigma, "{} vs {}", mc, analytic);
+    }
+}
@@ -0,0 +1,287 @@
+//! CCSDS Orbit Mean-Elements Message (OMM) parsing
+//!
+//! Reads the three encodings providers publish (JSON as served by Celestrak
+//! and Space-Track, KVN and XML) into the same `MeanElements` the SGP4
+//! propagator takes from a TLE. Only SGP4 mean elements in UTC are accepted;
+//! elements from another theory would propagate without error but wrongly.
+
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use serde_json::Value as Json;
+use std::collections::HashMap;
+#[cfg(feature = "python")]
+use std::fs;
+
+use crate::designator;
+use crate::epoch::{self, MJD_OFFSET};
+use crate::sgp4::MeanElements;
+
+/// Keywords of one message
+type Fields = HashMap<String, String>;
+
+fn number(fields: &Fields, key: &str) -> Result<f64, String> {
+    let text = fields.get(key).ok_or_else(|| format!("OMM is missing {}", key))?;
+    text.parse().map_err(|_| format!("OMM {} is not a number: '{}'", key, text))
+}
+
+/// Mean elements from the keywords of one message
+fn elements(fields: &Fields) -> Result<MeanElements, String> {
+    if let Some(theory) = fields.get("MEAN_ELEMENT_THEORY") {
+        if !matches!(theory.to_ascii_uppercase().as_str(), "SGP4" | "SGP/SGP4") {
+            return Err(format!("Unsupported MEAN_ELEMENT_THEORY '{}' (expected SGP4)", theory));
+        }
+    }
+    if let Some(time_system) = fields.get("TIME_SYSTEM") {
+        if !time_system.eq_ignore_ascii_case("UTC") {
+            return Err(format!("Unsupported TIME_SYSTEM '{}' (expected UTC)", time_system));
+        }
+    }
+    let norad_id = number(fields, "NORAD_CAT_ID")? as i32;
+    let epoch = fields.get("EPOCH").ok_or("OMM is missing EPOCH")?;
+    let mut elements = MeanElements::new(
+        norad_id,
+        epoch::parse_iso(epoch)? + MJD_OFFSET,
+        number(fields, "BSTAR")?,
+        number(fields, "INCLINATION")?,
+        number(fields, "RA_OF_ASC_NODE")?,
+        number(fields, "ECCENTRICITY")?,
+        number(fields, "ARG_OF_PERICENTER")?,
+        number(fields, "MEAN_ANOMALY")?,
+        number(fields, "MEAN_MOTION")?,
+    )
+    .map_err(|e| format!("NORAD {}: {}", norad_id, e))?;
+    elements.name = fields.get("OBJECT_NAME").cloned();
+    elements.international_designator =
+        fields.get("OBJECT_ID").and_then(|id| designator::parse_international_designator(id).ok());
+    Ok(elements)
+}
+
+/// Parse OMM JSON: an array of flat records or a single record
+///
+/// Celestrak writes numbers, Space-Track quotes every value; both are accepted.
+pub fn parse_json(text: &str) -> Result<Vec<MeanElements>, String> {
+    let records = match serde_json::from_str(text).map_err(|e| format!("Invalid OMM JSON: {}", e))? {
+        Json::Array(records) => records,
+        record @ Json::Object(_) => vec![record],
+        _ => return Err("OMM JSON must be an object or an array of objects".to_string()),
+    };
+    records
+        .iter()
+        .enumerate()
+        .map(|(idx, record)| {
+            let object = record.as_object().ok_or_else(|| format!("record {}: expected an object", idx + 1))?;
+            let fields = object
+                .iter()
+                .filter_map(|(key, value)| {
+                    let text = match value {
+                        Json::String(text) => text.trim().to_string(),
+                        Json::Number(x) => x.to_string(),
+                        _ => return None,
+                    };
+                    Some((key.clone(), text))
+                })
+                .collect();
+            elements(&fields).map_err(|e| format!("record {}: {}", idx + 1, e))
+        })
+        .collect()
+}
+
+/// Parse OMM KVN; consecutive messages each start with CCSDS_OMM_VERS
+pub fn parse_kvn(text: &str) -> Result<Vec<MeanElements>, String> {
+    let mut messages: Vec<(usize, Fields)> = Vec::new();
+    for (idx, raw) in text.lines().enumerate() {
+        let lineno = idx + 1;
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with("COMMENT") {
+            continue;
+        }
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| format!("line {}: expected KEY = value", lineno))?;
+        let key = key.trim();
+        // drop a trailing unit such as "[rev/day]"
+        let value = value.split_once('[').map_or(value, |(v, _)| v).trim();
+        if key == "CCSDS_OMM_VERS" || messages.is_empty() {
+            messages.push((lineno, Fields::new()));
+        }
+        messages.last_mut().expect("message started").1.insert(key.to_string(), value.to_string());
+    }
+    messages
+        .iter()
+        .map(|(lineno, fields)| elements(fields).map_err(|e| format!("line {}: {}", lineno, e)))
+        .collect()
+}
+
+fn unescape_xml(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Parse OMM XML: one `<omm>` or several inside an `<ndm>` combined message
+///
+/// Leaf elements become keywords regardless of nesting, which is all the
+/// flat OMM schema needs.
+pub fn parse_xml(text: &str) -> Result<Vec<MeanElements>, String> {
+    let mut messages: Vec<Fields> = Vec::new();
+    let mut open: Vec<&str> = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('<') {
+        let content = if open.is_empty() { "" } else { &rest[..start] };
+        rest = &rest[start..];
+        let skip_to = |rest: &str, end: &str| rest.find(end).map(|k| k + end.len());
+        let consumed = if rest.starts_with("<!--") {
+            skip_to(rest, "-->")
+        } else if rest.starts_with("<?") || rest.starts_with("<!") {
+            skip_to(rest, ">")
+        } else {
+            let end = rest.find('>').ok_or("Invalid OMM XML: unterminated tag")?;
+            let tag = &rest[1..end];
+            if let Some(name) = tag.strip_prefix('/') {
+                let name = name.trim();
+                if open.pop() != Some(name) {
+                    return Err(format!("Invalid OMM XML: unexpected </{}>", name));
+                }
+                let value = content.trim();
+                if !value.is_empty() {
+                    let fields = messages.last_mut().ok_or("Invalid OMM XML: data outside an <omm> element")?;
+                    fields.insert(name.to_string(), unescape_xml(value));
+                }
+            } else if !tag.ends_with('/') {
+                let name = tag.split_whitespace().next().unwrap_or_default();
+                if name.eq_ignore_ascii_case("omm") {
+                    messages.push(Fields::new());
+                }
+                open.push(name);
+            }
+            Some(end + 1)
+        };
+        rest = &rest[consumed.ok_or("Invalid OMM XML: unterminated markup")?..];
+    }
+    if let Some(name) = open.last() {
+        return Err(format!("Invalid OMM XML: <{}> is never closed", name));
+    }
+    if messages.is_empty() {
+        return Err("Invalid OMM XML: no <omm> element".to_string());
+    }
+    messages
+        .iter()
+        .enumerate()
+        .map(|(idx, fields)| elements(fields).map_err(|e| format!("message {}: {}", idx + 1, e)))
+        .collect()
+}
+
+/// Parse OMM text in any encoding, detected from its first character
+pub fn parse_text(text: &str) -> Result<Vec<MeanElements>, String> {
+    match text.trim_start().chars().next() {
+        Some('[' | '{') => parse_json(text),
+        Some('<') => parse_xml(text),
+        Some(_) => parse_kvn(text),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Parse OMM text (JSON, KVN or XML) into mean elements
+#[cfg(feature = "python")]
+#[pyfunction]
+pub fn parse_omm(text: &str) -> PyResult<Vec<MeanElements>> {
+    parse_text(text).map_err(pyo3::exceptions::PyValueError::new_err)
+}
+
+/// Read an OMM file (JSON, KVN or XML) into mean elements
+#[cfg(feature = "python")]
+#[pyfunction]
+pub fn read_omm(path: &str) -> PyResult<Vec<MeanElements>> {
+    let text = fs::read_to_string(path)
+        .map_err(|e| pyo3::exceptions::PyIOError::new_err(format!("{}: {}", path, e)))?;
+    parse_text(&text).map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("{}: {}", path, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KVN: &str = "\
+CCSDS_OMM_VERS = 2.0
+CREATION_DATE = 2024-03-01T13:00:00
+ORIGINATOR = 18 SPCS
+OBJECT_NAME = ISS (ZARYA)
+OBJECT_ID = 1998-067A
+CENTER_NAME = EARTH
+REF_FRAME = TEME
+TIME_SYSTEM = UTC
+MEAN_ELEMENT_THEORY = SGP4
+COMMENT mean elements
+EPOCH = 2024-03-01T12:00:00.000000
+MEAN_MOTION = 15.5 [rev/day]
+ECCENTRICITY = 0.0005
+INCLINATION = 51.64 [deg]
+RA_OF_ASC_NODE = 100.0 [deg]
+ARG_OF_PERICENTER = 90.0 [deg]
+MEAN_ANOMALY = 270.0 [deg]
+NORAD_CAT_ID = 25544
+BSTAR = 0.0002 [1/ER]
+";
+
+    #[test]
+    fn test_encodings_agree() {
+        // Celestrak writes numbers, Space-Track quoted strings
+        let json = r#"[
+            {"OBJECT_NAME": "ISS (ZARYA)", "OBJECT_ID": "1998-067A", "NORAD_CAT_ID": 25544,
+             "EPOCH": "2024-03-01T12:00:00.000000",
+             "MEAN_MOTION": 15.5, "ECCENTRICITY": 0.0005, "INCLINATION": 51.64, "RA_OF_ASC_NODE": 100.0,
+             "ARG_OF_PERICENTER": 90.0, "MEAN_ANOMALY": 270.0, "BSTAR": 0.0002},
+            {"OBJECT_NAME": "OBJ", "NORAD_CAT_ID": "123456", "EPOCH": "2024-03-01T00:00:00",
+             "MEAN_MOTION": "14.0", "ECCENTRICITY": "0.001", "INCLINATION": "98.0", "RA_OF_ASC_NODE": "10.0",
+             "ARG_OF_PERICENTER": "0.0", "MEAN_ANOMALY": "0.0", "BSTAR": "0"}
+        ]"#;
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ndm>
+  <omm id="CCSDS_OMM_VERS" version="2.0">
+    <header><CREATION_DATE>2024-03-01T13:00:00</CREATION_DATE><ORIGINATOR>18 SPCS</ORIGINATOR></header>
+    <body><segment>
+      <metadata>
+        <OBJECT_NAME>ISS (ZARYA)</OBJECT_NAME>
+        <OBJECT_ID>1998-067A</OBJECT_ID>
+        <TIME_SYSTEM>UTC</TIME_SYSTEM>
+        <MEAN_ELEMENT_THEORY>SGP4</MEAN_ELEMENT_THEORY>
+      </metadata>
+      <data>
+        <!-- <NORAD_CAT_ID>1</NORAD_CAT_ID> -->
+        <meanElements>
+          <EPOCH>2024-03-01T12:00:00.000000</EPOCH>
+          <MEAN_MOTION>15.5</MEAN_MOTION>
+          <ECCENTRICITY>.0005</ECCENTRICITY>
+          <INCLINATION>51.64</INCLINATION>
+          <RA_OF_ASC_NODE>100.0</RA_OF_ASC_NODE>
+          <ARG_OF_PERICENTER>90.0</ARG_OF_PERICENTER>
+          <MEAN_ANOMALY>270.0</MEAN_ANOMALY>
+        </meanElements>
+        <tleParameters><NORAD_CAT_ID>25544</NORAD_CAT_ID><BSTAR>.2E-3</BSTAR></tleParameters>
+      </data>
+    </segment></body>
+  </omm>
+</ndm>"#;
+        let from_json = parse_text(json).unwrap();
+        assert_eq!(from_json[1].norad_id, 123456);
+        assert_eq!(from_json[0].international_designator.as_deref(), Some("1998-067A"));
+        assert!((from_json[0].epoch_jd - 2460371.0).abs() < 1e-9);
+        for parsed in [parse_text(KVN).unwrap(), parse_text(xml).unwrap()] {
+            assert_eq!(parsed.len(), 1);
+            assert_eq!(parsed[0], from_json[0]);
+        }
+        assert_eq!(parse_kvn(&format!("{}{}", KVN, KVN)).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_rejects_incomplete_or_foreign_elements() {
+        assert!(parse_json(r#"[{"NORAD_CAT_ID": 1}]"#).unwrap_err().contains("EPOCH"));
+        assert!(parse_kvn(&KVN.replace("= SGP4", "= DSST")).unwrap_err().contains("MEAN_ELEMENT_THEORY"));
+        assert!(parse_kvn(&KVN.replace("TIME_SYSTEM = UTC", "TIME_SYSTEM = TAI")).is_err());
+        assert!(parse_kvn(&KVN.replace("ECCENTRICITY = 0.0005", "ECCENTRICITY = 1.5")).is_err());
+        assert!(parse_xml("<omm><EPOCH>2024-01-01</omm>").is_err());
+        assert!(parse_text("").unwrap().is_empty());
+    }
+}
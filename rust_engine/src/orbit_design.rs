@@ -0,0 +1,286 @@
+//! Orbit design helpers: sun-synchronous, repeat-ground-track and frozen orbits
+//!
+//! All three use the secular J2 rates of the mean elements (Vallado
+//! section 9.6; the frozen condition adds J3): the node precesses at
+//! `-3/2 n J2 (R/p)^2 cos i`, the perigee at `3/4 n J2 (R/p)^2 (5 cos^2 i - 1)`
+//! and the mean anomaly gains `3/4 n J2 (R/p)^2 sqrt(1 - e^2) (3 cos^2 i - 1)`.
+//! Altitudes are mean semi-major axis minus the equatorial radius.
+
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use std::f64::consts::PI;
+
+use crate::constants::{EARTH_RADIUS_KM, EARTH_ROTATION_RATE, J2_EARTH, J3_EARTH, MU_EARTH};
+
+/// Mean motion of the Sun about the Earth (rad/s), one tropical year per revolution
+const SUN_SYNCHRONOUS_RATE: f64 = 2.0 * PI / (365.2421897 * 86400.0);
+
+/// Fixed-point iteration limit and tolerance (km) on the semi-major axis
+const MAX_ITERATIONS: usize = 100;
+const SMA_TOLERANCE_KM: f64 = 1.0e-9;
+
+/// Secular (node, perigee, mean anomaly) rates in rad/s under J2
+fn secular_rates(a: f64, e: f64, inclination_rad: f64) -> (f64, f64, f64) {
+    let n = (MU_EARTH / (a * a * a)).sqrt();
+    let p = a * (1.0 - e * e);
+    let k = 0.75 * n * J2_EARTH * (EARTH_RADIUS_KM / p).powi(2);
+    let cos_i = inclination_rad.cos();
+    (
+        -2.0 * k * cos_i,
+        k * (5.0 * cos_i * cos_i - 1.0),
+        n + k * (1.0 - e * e).sqrt() * (3.0 * cos_i * cos_i - 1.0),
+    )
+}
+
+fn check_eccentricity(eccentricity: f64) -> Result<(), String> {
+    if (0.0..1.0).contains(&eccentricity) {
+        Ok(())
+    } else {
+        Err("Eccentricity must be in [0, 1)".to_string())
+    }
+}
+
+/// Inclination (rad) whose nodal precession follows the mean Sun, for a semi-major axis in km
+fn sun_synchronous_inclination_rad(a: f64, e: f64) -> Result<f64, String> {
+    let (node_rate_at_zero, _, _) = secular_rates(a, e, 0.0);
+    let cos_i = SUN_SYNCHRONOUS_RATE / node_rate_at_zero;
+    if cos_i < -1.0 {
+        return Err(format!(
+            "No sun-synchronous inclination exists at a semi-major axis of {:.1} km",
+            a
+        ));
+    }
+    Ok(cos_i.acos())
+}
+
+/// Sun-synchronous inclination (degrees) at a mean altitude (km) and eccentricity
+pub fn sun_synchronous_inclination(altitude_km: f64, eccentricity: f64) -> Result<f64, String> {
+    check_eccentricity(eccentricity)?;
+    if altitude_km <= 0.0 {
+        return Err("Altitude must be positive".to_string());
+    }
+    sun_synchronous_inclination_rad(EARTH_RADIUS_KM + altitude_km, eccentricity).map(f64::to_degrees)
+}
+
+/// An orbit retracing its ground track every `days` nodal days after `revolutions` nodal periods
+#[cfg_attr(feature = "python", pyclass(get_all))]
+#[derive(Clone, Debug, PartialEq)]
+pub struct RepeatGroundTrack {
+    pub revolutions: u32,
+    pub days: u32,
+    pub semi_major_axis_km: f64,
+    pub altitude_km: f64,
+    pub inclination_deg: f64,
+    pub eccentricity: f64,
+}
+
+/// Solve for the repeat-ground-track semi-major axis
+///
+/// With `inclination_deg` None the orbit is also sun-synchronous and the
+/// inclination is solved alongside the semi-major axis.
+pub fn repeat_ground_track(
+    revolutions: u32,
+    days: u32,
+    inclination_deg: Option<f64>,
+    eccentricity: f64,
+) -> Result<RepeatGroundTrack, String> {
+    check_eccentricity(eccentricity)?;
+    if revolutions == 0 || days == 0 {
+        return Err("Revolutions and days must be positive".to_string());
+    }
+    let ratio = f64::from(revolutions) / f64::from(days);
+    let e = eccentricity;
+
+    // Keplerian first guess, then fixed-point iteration on the nodal period
+    let mut n = ratio * EARTH_ROTATION_RATE;
+    let mut a = (MU_EARTH / (n * n)).cbrt();
+    for _ in 0..MAX_ITERATIONS {
+        if a * (1.0 - e) <= EARTH_RADIUS_KM {
+            return Err(format!("{} revolutions in {} days would put the perigee underground", revolutions, days));
+        }
+        let i = match inclination_deg {
+            Some(degrees) => degrees.to_radians(),
+            None => sun_synchronous_inclination_rad(a, e)?,
+        };
+        let (node_rate, perigee_rate, anomaly_rate) = secular_rates(a, e, i);
+        // revolutions * 2 pi / (perigee + anomaly rate) = days * 2 pi / (earth rate - node rate)
+        let anomalistic_excess = perigee_rate + anomaly_rate - n;
+        n = ratio * (EARTH_ROTATION_RATE - node_rate) - anomalistic_excess;
+        let next = (MU_EARTH / (n * n)).cbrt();
+        let converged = (next - a).abs() < SMA_TOLERANCE_KM;
+        a = next;
+        if converged {
+            return Ok(RepeatGroundTrack {
+                revolutions,
+                days,
+                semi_major_axis_km: a,
+                altitude_km: a - EARTH_RADIUS_KM,
+                inclination_deg: i.to_degrees(),
+                eccentricity,
+            });
+        }
+    }
+    Err(format!("Repeat ground track {}/{} did not converge", revolutions, days))
+}
+
+fn gcd(a: u32, b: u32) -> u32 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// Every repeat ground track with a cycle of at most `max_days` between two mean altitudes
+///
+/// Only cycles in lowest terms are listed (15/1 but not 30/2). Sorted by altitude.
+pub fn repeat_ground_track_solutions(
+    min_altitude_km: f64,
+    max_altitude_km: f64,
+    max_days: u32,
+    inclination_deg: Option<f64>,
+    eccentricity: f64,
+) -> Result<Vec<RepeatGroundTrack>, String> {
+    if !(min_altitude_km > 0.0 && max_altitude_km >= min_altitude_km) {
+        return Err("Need 0 < min_altitude_km <= max_altitude_km".to_string());
+    }
+    let revolutions_per_day = |altitude: f64| {
+        let a: f64 = EARTH_RADIUS_KM + altitude;
+        (MU_EARTH / (a * a * a)).sqrt() / EARTH_ROTATION_RATE
+    };
+    // Keplerian bounds, widened to cover the J2 corrections
+    let (low, high) = (0.95 * revolutions_per_day(max_altitude_km), 1.05 * revolutions_per_day(min_altitude_km));
+
+    let mut solutions = Vec::new();
+    for days in 1..=max_days {
+        let first = (low * f64::from(days)).floor().max(1.0) as u32;
+        let last = (high * f64::from(days)).ceil() as u32;
+        for revolutions in first..=last {
+            if gcd(revolutions, days) != 1 {
+                continue;
+            }
+            // orbits with no solution (too high for sun-synchronism) are simply not listed
+            if let Ok(solution) = repeat_ground_track(revolutions, days, inclination_deg, eccentricity) {
+                if (min_altitude_km..=max_altitude_km).contains(&solution.altitude_km) {
+                    solutions.push(solution);
+                }
+            }
+        }
+    }
+    solutions.sort_by(|a, b| a.altitude_km.total_cmp(&b.altitude_km));
+    Ok(solutions)
+}
+
+/// Frozen-orbit (eccentricity, argument of perigee in degrees) at a mean altitude and inclination
+///
+/// Balancing the J2 and J3 perigee motion to first order in e leaves the
+/// eccentricity vector fixed at `e = -J3 R sin i / (2 J2 a)` with the
+/// perigee at 90 degrees (J3 is negative, so e is positive for any inclination).
+pub fn frozen_orbit(altitude_km: f64, inclination_deg: f64) -> Result<(f64, f64), String> {
+    if altitude_km <= 0.0 {
+        return Err("Altitude must be positive".to_string());
+    }
+    let a = EARTH_RADIUS_KM + altitude_km;
+    let eccentricity = -J3_EARTH * EARTH_RADIUS_KM * inclination_deg.to_radians().sin().abs() / (2.0 * J2_EARTH * a);
+    Ok((eccentricity, 90.0))
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl RepeatGroundTrack {
+    fn __repr__(&self) -> String {
+        format!(
+            "RepeatGroundTrack({}/{}, {:.3} km, {:.3} deg)",
+            self.revolutions, self.days, self.altitude_km, self.inclination_deg
+        )
+    }
+}
+
+/// Sun-synchronous inclination (degrees) at a mean altitude (km)
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(name = "sun_synchronous_inclination", signature = (altitude_km, eccentricity = 0.0))]
+pub fn py_sun_synchronous_inclination(altitude_km: f64, eccentricity: f64) -> PyResult<f64> {
+    sun_synchronous_inclination(altitude_km, eccentricity).map_err(pyo3::exceptions::PyValueError::new_err)
+}
+
+/// Repeat-ground-track orbit making `revolutions` revolutions in `days` nodal days
+///
+/// Leave `inclination_deg` as None for a sun-synchronous repeat orbit.
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(name = "repeat_ground_track", signature = (revolutions, days, inclination_deg = None, eccentricity = 0.0))]
+pub fn py_repeat_ground_track(
+    revolutions: u32,
+    days: u32,
+    inclination_deg: Option<f64>,
+    eccentricity: f64,
+) -> PyResult<RepeatGroundTrack> {
+    repeat_ground_track(revolutions, days, inclination_deg, eccentricity).map_err(pyo3::exceptions::PyValueError::new_err)
+}
+
+/// All repeat-ground-track orbits between two altitudes with cycles up to `max_days`
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(
+    name = "repeat_ground_track_solutions",
+    signature = (min_altitude_km, max_altitude_km, max_days = 30, inclination_deg = None, eccentricity = 0.0)
+)]
+pub fn py_repeat_ground_track_solutions(
+    py: Python<'_>,
+    min_altitude_km: f64,
+    max_altitude_km: f64,
+    max_days: u32,
+    inclination_deg: Option<f64>,
+    eccentricity: f64,
+) -> PyResult<Vec<RepeatGroundTrack>> {
+    py.allow_threads(|| {
+        repeat_ground_track_solutions(min_altitude_km, max_altitude_km, max_days, inclination_deg, eccentricity)
+    })
+    .map_err(pyo3::exceptions::PyValueError::new_err)
+}
+
+/// Frozen-orbit (eccentricity, argument of perigee in degrees) at a mean altitude and inclination
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(name = "frozen_orbit")]
+pub fn py_frozen_orbit(altitude_km: f64, inclination_deg: f64) -> PyResult<(f64, f64)> {
+    frozen_orbit(altitude_km, inclination_deg).map_err(pyo3::exceptions::PyValueError::new_err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sun_synchronous_inclination() {
+        // 800 km circular: 98.6 degrees
+        let inclination = sun_synchronous_inclination(800.0, 0.0).unwrap();
+        assert!((inclination - 98.6).abs() < 0.05, "{}", inclination);
+        let (node_rate, _, _) = secular_rates(EARTH_RADIUS_KM + 800.0, 0.0, inclination.to_radians());
+        assert!((node_rate / SUN_SYNCHRONOUS_RATE - 1.0).abs() < 1e-12);
+        assert!(sun_synchronous_inclination(7000.0, 0.0).is_err());
+    }
+
+    #[test]
+    fn test_landsat_repeat_ground_track() {
+        // Landsat 8: 233 revolutions in 16 days, sun-synchronous at about 705 km
+        let landsat = repeat_ground_track(233, 16, None, 0.0).unwrap();
+        assert!((695.0..710.0).contains(&landsat.altitude_km), "{}", landsat.altitude_km);
+        assert!((landsat.inclination_deg - 98.2).abs() < 0.1, "{}", landsat.inclination_deg);
+
+        let solutions = repeat_ground_track_solutions(690.0, 720.0, 16, None, 0.0).unwrap();
+        assert!(solutions.iter().any(|s| s.revolutions == 233 && s.days == 16));
+        assert!(solutions.windows(2).all(|w| w[0].altitude_km <= w[1].altitude_km));
+        assert!(solutions.iter().all(|s| gcd(s.revolutions, s.days) == 1));
+    }
+
+    #[test]
+    fn test_frozen_orbit() {
+        // about 0.001 at 700 km near polar, perigee over the north
+        let (eccentricity, perigee) = frozen_orbit(700.0, 98.2).unwrap();
+        assert!((0.0009..0.0012).contains(&eccentricity), "{}", eccentricity);
+        assert_eq!(perigee, 90.0);
+        assert_eq!(frozen_orbit(700.0, 0.0).unwrap().0, 0.0);
+    }
+}
@@ -0,0 +1,205 @@
+//! Osculating state <-> SGP4 mean element conversion
+//!
+//! A state vector and a TLE describe the same orbit differently: SGP4 mean
+//! elements average out the short-period J2 terms, which move a LEO
+//! position by kilometres. Screening a state-vector catalog against
+//! TLE-derived objects therefore needs both on one footing.
+//!
+//! `osculating_to_mean` inverts SGP4 at epoch: starting from the osculating
+//! elements it corrects the mean elements until SGP4 reproduces the state.
+//! The iteration runs on equinoctial elements (`elements::EquinoctialElements`),
+//! which stay well defined for circular and equatorial orbits. Mean motions follow the TLE (Kozai)
+//! convention; `sgp4::kozai_to_brouwer` gives the Brouwer value.
+
+use nalgebra::Vector3;
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use std::f64::consts::PI;
+
+use crate::constants::SECONDS_PER_DAY;
+use crate::elements::{self, EquinoctialElements};
+use crate::error::OrbitError;
+use crate::sgp4::{MeanElements, Sgp4Propagator};
+#[cfg(feature = "python")]
+use crate::epoch::Epoch;
+#[cfg(feature = "python")]
+use crate::sgp4;
+#[cfg(feature = "python")]
+use crate::Satellite;
+
+const MAX_ITERATIONS: usize = 100;
+
+/// Largest element correction accepted as converged (rad, or relative for semi-major axis)
+const TOLERANCE: f64 = 1.0e-12;
+
+fn wrap_pi(angle: f64) -> f64 {
+    (angle + PI).rem_euclid(2.0 * PI) - PI
+}
+
+fn degrees_0_360(angle: f64) -> f64 {
+    angle.to_degrees().rem_euclid(360.0)
+}
+
+/// Osculating equinoctial elements in the frame picked by `retrograde`
+fn equinoctial(r: &Vector3<f64>, v: &Vector3<f64>, retrograde: bool) -> Result<EquinoctialElements, OrbitError> {
+    elements::equinoctial_with_factor(r, v, retrograde).ok_or_else(|| {
+        OrbitError::InvalidState("State is not on a bound orbit with equinoctial elements".to_string())
+    })
+}
+
+/// SGP4 mean elements whose equinoctial form is `x`
+fn mean_elements(norad_id: i32, epoch_jd: f64, bstar: f64, x: &EquinoctialElements) -> Result<MeanElements, OrbitError> {
+    let perigee_longitude = x.longitude_of_periapsis();
+    let raan = x.p.atan2(x.q);
+    let node_longitude = if x.retrograde { -raan } else { raan };
+    MeanElements::new(
+        norad_id,
+        epoch_jd,
+        bstar,
+        x.inclination_deg(),
+        degrees_0_360(raan),
+        x.eccentricity(),
+        degrees_0_360(perigee_longitude - node_longitude),
+        degrees_0_360(x.mean_longitude_deg.to_radians() - perigee_longitude),
+        x.mean_motion() * SECONDS_PER_DAY / (2.0 * PI),
+    )
+}
+
+/// SGP4 state (TEME) at the element epoch
+pub fn mean_to_osculating(elements: &MeanElements) -> Result<(Vector3<f64>, Vector3<f64>), OrbitError> {
+    let (r, v) = Sgp4Propagator::from_mean_elements(elements.clone()).position_velocity(0.0)?;
+    Ok((Vector3::from(r), Vector3::from(v)))
+}
+
+/// SGP4 mean elements reproducing a TEME state at `epoch_jd` (UTC)
+///
+/// `bstar` is carried over unchanged; it does not affect the state at epoch.
+pub fn osculating_to_mean(
+    norad_id: i32,
+    r: &Vector3<f64>,
+    v: &Vector3<f64>,
+    epoch_jd: f64,
+    bstar: f64,
+) -> Result<MeanElements, OrbitError> {
+    let retrograde = r.cross(v).z < 0.0;
+    let target = equinoctial(r, v, retrograde)?;
+    let mut x = target.clone();
+    for _ in 0..MAX_ITERATIONS {
+        let elements = mean_elements(norad_id, epoch_jd, bstar, &x)?;
+        let (r_mean, v_mean) = mean_to_osculating(&elements)?;
+        let computed = equinoctial(&r_mean, &v_mean, retrograde)?;
+
+        let correction = [
+            target.h - computed.h,
+            target.k - computed.k,
+            target.p - computed.p,
+            target.q - computed.q,
+            wrap_pi((target.mean_longitude_deg - computed.mean_longitude_deg).to_radians()),
+        ];
+        let da = target.semi_major_axis_km - computed.semi_major_axis_km;
+        x.semi_major_axis_km += da;
+        x.h += correction[0];
+        x.k += correction[1];
+        x.p += correction[2];
+        x.q += correction[3];
+        x.mean_longitude_deg += correction[4].to_degrees();
+        let size = (da / target.semi_major_axis_km).abs().max(correction.iter().fold(0.0, |m: f64, c| m.max(c.abs())));
+        if size < TOLERANCE {
+            return mean_elements(norad_id, epoch_jd, bstar, &x);
+        }
+    }
+    Err(OrbitError::Propagation(format!(
+        "NORAD {}: mean elements did not converge in {} iterations",
+        norad_id, MAX_ITERATIONS
+    )))
+}
+
+/// SGP4 mean elements (TLE convention) reproducing a TEME state
+///
+/// The epoch is the satellite's own unless `epoch` is given.
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(name = "osculating_to_mean", signature = (satellite, bstar = 0.0, epoch = None))]
+pub fn py_osculating_to_mean(satellite: &Satellite, bstar: f64, epoch: Option<Epoch>) -> PyResult<MeanElements> {
+    let epoch = epoch.or(satellite.epoch).ok_or_else(|| {
+        pyo3::exceptions::PyValueError::new_err("The satellite has no epoch; pass epoch=")
+    })?;
+    let mut elements = osculating_to_mean(
+        satellite.norad_id,
+        &Vector3::from(satellite.position),
+        &Vector3::from(satellite.velocity),
+        epoch.jd_utc(),
+        bstar,
+    )?;
+    elements.name = satellite.name.clone();
+    Ok(elements)
+}
+
+/// Osculating TEME state of mean elements at their epoch
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(name = "mean_to_osculating")]
+pub fn py_mean_to_osculating(elements: MeanElements) -> PyResult<Satellite> {
+    Ok(Sgp4Propagator::from_mean_elements(elements).propagate_state(0.0)?)
+}
+
+/// Brouwer mean motion (rev/day) for a TLE (Kozai) mean motion
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(name = "kozai_to_brouwer")]
+pub fn py_kozai_to_brouwer(mean_motion_rev_per_day: f64, eccentricity: f64, inclination_deg: f64) -> f64 {
+    sgp4::kozai_to_brouwer(mean_motion_rev_per_day, eccentricity, inclination_deg)
+}
+
+/// TLE (Kozai) mean motion (rev/day) for a Brouwer mean motion
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(name = "brouwer_to_kozai")]
+pub fn py_brouwer_to_kozai(mean_motion_rev_per_day: f64, eccentricity: f64, inclination_deg: f64) -> f64 {
+    sgp4::brouwer_to_kozai(mean_motion_rev_per_day, eccentricity, inclination_deg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sgp4::{brouwer_to_kozai, kozai_to_brouwer};
+    use crate::tle;
+
+    #[test]
+    fn test_round_trip_reproduces_tle_elements() {
+        let iss = tle::parse_lines(
+            "1 25544U 98067A   08264.51782528 -.00002182  00000-0 -11606-4 0  2927",
+            "2 25544  51.6416 247.4627 0006703 130.5360 325.0288 15.72125391563537",
+            true,
+        )
+        .unwrap();
+        let (r, v) = mean_to_osculating(&iss).unwrap();
+        let mean = osculating_to_mean(25544, &r, &v, iss.epoch_jd, iss.bstar).unwrap();
+        assert!((mean.mean_motion_rev_per_day - iss.mean_motion_rev_per_day).abs() < 1e-8);
+        assert!((mean.eccentricity - iss.eccentricity).abs() < 1e-9);
+        assert!((mean.inclination_deg - iss.inclination_deg).abs() < 1e-8);
+        assert!((mean.raan_deg - iss.raan_deg).abs() < 1e-8);
+        // perigee and anomaly are poorly separated at e ~ 7e-4; their sum is not
+        let longitude = |e: &MeanElements| (e.arg_perigee_deg + e.mean_anomaly_deg).rem_euclid(360.0);
+        assert!((longitude(&mean) - longitude(&iss)).abs() < 1e-7);
+
+        // the osculating semi-major axis alone would be kilometres off
+        let (r2, _) = mean_to_osculating(&mean).unwrap();
+        assert!((r2 - r).norm() < 1e-6);
+    }
+
+    #[test]
+    fn test_circular_equatorial_and_deep_space() {
+        for (mean_motion, eccentricity, inclination) in [(15.0, 0.0, 0.0), (1.0027, 0.0002, 0.05), (2.006, 0.72, 63.4)] {
+            let elements = MeanElements::new(1, 2460000.5, 0.0, inclination, 40.0, eccentricity, 270.0, 10.0, mean_motion).unwrap();
+            let (r, v) = mean_to_osculating(&elements).unwrap();
+            let mean = osculating_to_mean(1, &r, &v, elements.epoch_jd, 0.0).unwrap();
+            let (r2, v2) = mean_to_osculating(&mean).unwrap();
+            assert!((r2 - r).norm() < 1e-6 && (v2 - v).norm() < 1e-9, "n={} e={} i={}", mean_motion, eccentricity, inclination);
+        }
+
+        let brouwer = kozai_to_brouwer(15.5, 0.001, 51.6);
+        assert!((brouwer - 15.5).abs() > 1e-4);
+        assert!((brouwer_to_kozai(brouwer, 0.001, 51.6) - 15.5).abs() < 1e-10);
+    }
+}
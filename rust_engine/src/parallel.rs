@@ -0,0 +1,90 @@
+//! Thread pool used for parallel work
+//!
+//! By default work runs on Rayon's global pool (one thread per CPU). A
+//! process that runs several screenings at once can instead cap the total
+//! with `set_num_threads`; every parallel section then executes inside a
+//! shared pool of that size, and `set_num_threads(1)` runs single-threaded.
+//!
+//! On `wasm32` there are no threads: Rayon's global pool falls back to the
+//! calling thread, so the same code runs sequentially and `configure` only
+//! accepts the default.
+
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use std::sync::{Arc, RwLock};
+
+static POOL: RwLock<Option<Arc<ThreadPool>>> = RwLock::new(None);
+
+fn configured_pool() -> Option<Arc<ThreadPool>> {
+    POOL.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Run `op` inside the configured pool, or directly when none is set
+pub fn install<OP, R>(op: OP) -> R
+where
+    OP: FnOnce() -> R + Send,
+    R: Send,
+{
+    match configured_pool() {
+        Some(pool) => pool.install(op),
+        None => op(),
+    }
+}
+
+/// Use a dedicated pool of `threads` workers; None or 0 restores the global pool
+pub fn configure(threads: Option<usize>) -> Result<(), String> {
+    #[cfg(target_arch = "wasm32")]
+    if threads.is_some_and(|n| n > 1) {
+        return Err("Threads are not available on wasm32".to_string());
+    }
+    let pool = match threads {
+        None | Some(0) => None,
+        Some(n) => Some(Arc::new(
+            ThreadPoolBuilder::new()
+                .num_threads(n)
+                .thread_name(|i| format!("orbit-core-{}", i))
+                .build()
+                .map_err(|e| format!("Could not start thread pool: {}", e))?,
+        )),
+    };
+    *POOL.write().unwrap_or_else(|e| e.into_inner()) = pool;
+    Ok(())
+}
+
+/// Number of threads parallel sections will use
+pub fn num_threads() -> usize {
+    configured_pool().map_or_else(rayon::current_num_threads, |pool| pool.current_num_threads())
+}
+
+/// Limit parallel work to `n` threads (1 runs single-threaded; None or 0 uses all CPUs)
+///
+/// The limit is process-wide and shared by concurrent calls, so several
+/// screenings running at once do not oversubscribe the machine.
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(signature = (n = None))]
+pub fn set_num_threads(n: Option<usize>) -> PyResult<()> {
+    configure(n).map_err(pyo3::exceptions::PyRuntimeError::new_err)
+}
+
+/// Number of threads used by parallel work
+#[cfg(feature = "python")]
+#[pyfunction]
+pub fn get_num_threads() -> usize {
+    num_threads()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_configured_pool_limits_threads() {
+        configure(Some(2)).unwrap();
+        assert_eq!(num_threads(), 2);
+        assert_eq!(install(rayon::current_num_threads), 2);
+        configure(None).unwrap();
+        assert_eq!(num_threads(), rayon::current_num_threads());
+    }
+}
@@ -0,0 +1,650 @@
+//! Collision probability (Pc) for short-term encounters
+//!
+//! The combined position covariance is projected onto the encounter plane
+//! (perpendicular to the relative velocity) and the resulting bivariate
+//! Gaussian is integrated over the hard-body disk centred on the primary.
+//! Four published evaluations of that integral are available: Foster/Akella
+//! (2D quadrature, the default), Patera (contour integral), Alfano (erf
+//! series over strips) and Chan (analytic series for the equal-area
+//! isotropic encounter, approximate for very elongated covariances).
+
+use nalgebra::{Matrix3, Vector3};
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+#[cfg(feature = "python")]
+use std::collections::HashMap;
+use std::f64::consts::PI;
+
+#[cfg(feature = "python")]
+use crate::columnar::Column;
+#[cfg(feature = "python")]
+use crate::Satellite;
+
+/// Minimum number of Simpson intervals across the hard-body disk
+const MIN_INTERVALS: usize = 128;
+
+/// Upper bound on Simpson intervals (guards against degenerate covariances)
+const MAX_INTERVALS: usize = 20000;
+
+/// Covariance scale compared with the nominal to detect the dilution region
+const DILUTION_STEP: f64 = 1.01;
+
+/// Golden-section iterations bracketing the worst-case covariance scale
+const MAX_PC_ITERATIONS: usize = 60;
+
+/// Search bracket around the analytic worst-case scale (factor each way)
+const MAX_PC_BRACKET: f64 = 100.0;
+
+/// Covariance scale factors swept by default: 0.25 to 4 in quarter octaves
+#[cfg(feature = "python")]
+const DEFAULT_SCALE_STEPS: i32 = 8;
+
+/// Complementary error function (Numerical Recipes Chebyshev fit, rel. error < 1.2e-7)
+pub fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let ans = t * (-z * z - 1.26551223
+        + t * (1.00002368
+            + t * (0.37409196
+                + t * (0.09678418
+                    + t * (-0.18628806
+                        + t * (0.27886807
+                            + t * (-1.13520398
+                                + t * (1.48851587 + t * (-0.82215223 + t * 0.17087277)))))))))
+        .exp();
+    if x >= 0.0 {
+        ans
+    } else {
+        2.0 - ans
+    }
+}
+
+/// Error function
+pub fn erf(x: f64) -> f64 {
+    1.0 - erfc(x)
+}
+
+/// Encounter-plane basis (x along the miss vector, z completing the frame)
+///
+/// Returns the 2x3 projection rows and the miss distance components.
+fn encounter_plane(rel_pos: &Vector3<f64>, rel_vel: &Vector3<f64>) -> Option<([Vector3<f64>; 2], [f64; 2])> {
+    let speed = rel_vel.norm();
+    if speed == 0.0 {
+        return None;
+    }
+    let y_hat = rel_vel / speed;
+
+    // miss vector projected onto the plane normal to the relative velocity
+    let miss = rel_pos - y_hat * rel_pos.dot(&y_hat);
+    let x_hat = if miss.norm() > 0.0 {
+        miss.normalize()
+    } else {
+        // head-on with zero miss: pick any direction in the plane
+        let trial = if y_hat.x.abs() < 0.9 { Vector3::x() } else { Vector3::y() };
+        (trial - y_hat * trial.dot(&y_hat)).normalize()
+    };
+    let z_hat = x_hat.cross(&y_hat);
+
+    Some(([x_hat, z_hat], [miss.dot(&x_hat), miss.dot(&z_hat)]))
+}
+
+/// Probability algorithm for short-term encounters
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PcMethod {
+    /// Foster/Akella 2D integral over the hard-body disk
+    Foster,
+    /// Patera's 1D contour integral around the (scaled) hard-body boundary
+    Patera,
+    /// Alfano's erf series over strips of the hard-body disk
+    Alfano,
+    /// Chan's analytic series for the equivalent isotropic encounter
+    Chan,
+}
+
+impl PcMethod {
+    pub const ALL: [PcMethod; 4] = [PcMethod::Foster, PcMethod::Patera, PcMethod::Alfano, PcMethod::Chan];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PcMethod::Foster => "foster",
+            PcMethod::Patera => "patera",
+            PcMethod::Alfano => "alfano",
+            PcMethod::Chan => "chan",
+        }
+    }
+}
+
+impl std::str::FromStr for PcMethod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "foster" | "akella" => Ok(PcMethod::Foster),
+            "patera" => Ok(PcMethod::Patera),
+            "alfano" => Ok(PcMethod::Alfano),
+            "chan" => Ok(PcMethod::Chan),
+            _ => Err(format!("Unknown Pc method '{}' (expected foster, patera, alfano or chan)", s)),
+        }
+    }
+}
+
+/// Encounter-plane Gaussian in its principal axes
+///
+/// The hard-body disk is centred on the origin and the Gaussian on
+/// `(mu, mw)` with standard deviations `sigma_u`, `sigma_w`.
+struct Encounter {
+    sigma_u: f64,
+    sigma_w: f64,
+    mu: f64,
+    mw: f64,
+}
+
+impl Encounter {
+    fn project(rel_pos: &Vector3<f64>, rel_vel: &Vector3<f64>, combined_cov: &Matrix3<f64>) -> Option<Self> {
+        let (axes, miss) = encounter_plane(rel_pos, rel_vel)?;
+
+        // 2x2 covariance in the encounter plane
+        let cxx = axes[0].dot(&(combined_cov * axes[0]));
+        let czz = axes[1].dot(&(combined_cov * axes[1]));
+        let cxz = axes[0].dot(&(combined_cov * axes[1]));
+
+        // rotate to principal axes so the inner integral is separable
+        let theta = 0.5 * (2.0 * cxz).atan2(cxx - czz);
+        let (sin_t, cos_t) = theta.sin_cos();
+        let var_u = cxx * cos_t * cos_t + 2.0 * cxz * sin_t * cos_t + czz * sin_t * sin_t;
+        let var_w = cxx * sin_t * sin_t - 2.0 * cxz * sin_t * cos_t + czz * cos_t * cos_t;
+        if var_u <= 0.0 || var_w <= 0.0 {
+            return None;
+        }
+        Some(Encounter {
+            sigma_u: var_u.sqrt(),
+            sigma_w: var_w.sqrt(),
+            mu: miss[0] * cos_t + miss[1] * sin_t,
+            mw: -miss[0] * sin_t + miss[1] * cos_t,
+        })
+    }
+
+    /// Even number of quadrature intervals resolving the disk
+    fn intervals(&self, radius: f64) -> usize {
+        let resolution = (2.0 * radius / self.sigma_u.min(self.sigma_w) * 8.0).ceil() as usize;
+        resolution.clamp(MIN_INTERVALS, MAX_INTERVALS) & !1
+    }
+
+    /// Mass of the w-marginal over the chord of half-length `half_chord`
+    fn strip(&self, half_chord: f64) -> f64 {
+        let scale = 2f64.sqrt() * self.sigma_w;
+        erf((half_chord - self.mw) / scale) - erf((-half_chord - self.mw) / scale)
+    }
+
+    fn foster(&self, r: f64) -> f64 {
+        // outer Simpson integration across the disk (u = R sin(phi) removes the
+        // square-root singularity at the disk edge), inner integral via erf
+        let n = self.intervals(r);
+        let h = PI / n as f64;
+        let var_u = self.sigma_u * self.sigma_u;
+
+        let integrand = |phi: f64| {
+            let (sin_phi, cos_phi) = phi.sin_cos();
+            let u = r * sin_phi;
+            let half_chord = r * cos_phi;
+            (-(u - self.mu) * (u - self.mu) / (2.0 * var_u)).exp() * self.strip(half_chord) * half_chord
+        };
+
+        let mut sum = integrand(-PI / 2.0) + integrand(PI / 2.0);
+        for k in 1..n {
+            let weight = if k % 2 == 1 { 4.0 } else { 2.0 };
+            sum += weight * integrand(-PI / 2.0 + k as f64 * h);
+        }
+        sum * h / 3.0 / (2.0 * (2.0 * PI).sqrt() * self.sigma_u)
+    }
+
+    fn alfano(&self, r: f64) -> f64 {
+        // Simpson sum over x-strips of the disk with closed-form chord integrals
+        let n = self.intervals(r);
+        let h = 2.0 * r / n as f64;
+        let var_u = self.sigma_u * self.sigma_u;
+
+        let integrand = |u: f64| {
+            let half_chord = (r * r - u * u).max(0.0).sqrt();
+            (-(u - self.mu) * (u - self.mu) / (2.0 * var_u)).exp() * self.strip(half_chord)
+        };
+
+        let mut sum = integrand(-r) + integrand(r);
+        for k in 1..n {
+            let weight = if k % 2 == 1 { 4.0 } else { 2.0 };
+            sum += weight * integrand(-r + k as f64 * h);
+        }
+        sum * h / 3.0 / (2.0 * (2.0 * PI).sqrt() * self.sigma_u)
+    }
+
+    fn patera(&self, r: f64) -> f64 {
+        // scale w so the Gaussian is isotropic; the disk becomes an ellipse.
+        // Pc = (1/2pi) * contour integral of (1 - exp(-rho^2 / 2 sigma^2)) dtheta,
+        // with rho, theta polar coordinates about the Gaussian centre. The
+        // integrand is smooth and periodic, so the trapezoidal rule converges fast.
+        let scale = self.sigma_u / self.sigma_w;
+        let two_var = 2.0 * self.sigma_u * self.sigma_u;
+        let n = 2 * self.intervals(r);
+        let h = 2.0 * PI / n as f64;
+
+        let sum: f64 = (0..n)
+            .map(|k| {
+                let (sin_phi, cos_phi) = (k as f64 * h).sin_cos();
+                let x = r * cos_phi - self.mu;
+                let y = (r * sin_phi - self.mw) * scale;
+                let (dx, dy) = (-r * sin_phi, r * cos_phi * scale);
+                let rho2 = x * x + y * y;
+                // (1 - exp(-rho^2 / 2 sigma^2)) / rho^2, finite as rho -> 0
+                let weight = if rho2 > 0.0 { -(-rho2 / two_var).exp_m1() / rho2 } else { 1.0 / two_var };
+                weight * (x * dy - y * dx)
+            })
+            .sum();
+        sum * h / (2.0 * PI)
+    }
+
+    fn chan(&self, r: f64) -> f64 {
+        // Chan's series: Pc = sum_m Poisson(m; v/2) * P(Poisson(u/2) > m), with
+        // u = R^2 / (sigma_u sigma_w) (equal-area disk) and v the squared
+        // Mahalanobis miss. Weights are built in log space so large misses
+        // underflow to zero instead of overflowing.
+        let u = r * r / (self.sigma_u * self.sigma_w);
+        let v = (self.mu / self.sigma_u).powi(2) + (self.mw / self.sigma_w).powi(2);
+        let (half_u, half_v) = (0.5 * u, 0.5 * v);
+        let terms = (half_v + 10.0 * half_v.sqrt() + 30.0).ceil() as usize;
+
+        let mut tail = -(-half_u).exp_m1(); // P(Poisson(u/2) > 0)
+        let mut log_factorial = 0.0;
+        let mut sum = 0.0;
+        for m in 0..=terms {
+            if m > 0 {
+                log_factorial += (m as f64).ln();
+                tail -= (-half_u + m as f64 * half_u.ln() - log_factorial).exp();
+                if tail <= 0.0 {
+                    break;
+                }
+            }
+            let weight = if m == 0 {
+                (-half_v).exp()
+            } else if half_v > 0.0 {
+                (-half_v + m as f64 * half_v.ln() - log_factorial).exp()
+            } else {
+                0.0
+            };
+            sum += weight * tail;
+        }
+        sum
+    }
+}
+
+/// Probability of collision with the chosen method
+///
+/// `rel_pos`/`rel_vel` are secondary minus primary at TCA (km, km/s),
+/// `combined_cov` is the summed 3x3 position covariance (km^2) and
+/// `hard_body_radius` the combined object radius (km).
+pub fn pc_with_method(
+    method: PcMethod,
+    rel_pos: &Vector3<f64>,
+    rel_vel: &Vector3<f64>,
+    combined_cov: &Matrix3<f64>,
+    hard_body_radius: f64,
+) -> Option<f64> {
+    if hard_body_radius <= 0.0 {
+        return Some(0.0);
+    }
+    let encounter = Encounter::project(rel_pos, rel_vel, combined_cov)?;
+    let pc = match method {
+        PcMethod::Foster => encounter.foster(hard_body_radius),
+        PcMethod::Patera => encounter.patera(hard_body_radius),
+        PcMethod::Alfano => encounter.alfano(hard_body_radius),
+        PcMethod::Chan => encounter.chan(hard_body_radius),
+    };
+    Some(pc.clamp(0.0, 1.0))
+}
+
+/// Foster 2D probability of collision (see `pc_with_method` for arguments)
+pub fn foster_pc(
+    rel_pos: &Vector3<f64>,
+    rel_vel: &Vector3<f64>,
+    combined_cov: &Matrix3<f64>,
+    hard_body_radius: f64,
+) -> Option<f64> {
+    pc_with_method(PcMethod::Foster, rel_pos, rel_vel, combined_cov, hard_body_radius)
+}
+
+/// Pc over covariance scale factors (rows) and hard-body radii (columns), row-major
+///
+/// Each scale multiplies the combined covariance matrix (the sigmas by its
+/// square root).
+pub fn pc_sweep(
+    method: PcMethod,
+    rel_pos: &Vector3<f64>,
+    rel_vel: &Vector3<f64>,
+    combined_cov: &Matrix3<f64>,
+    scales: &[f64],
+    radii: &[f64],
+) -> Option<Vec<f64>> {
+    scales
+        .iter()
+        .flat_map(|scale| radii.iter().map(move |radius| (scale, radius)))
+        .map(|(scale, radius)| pc_with_method(method, rel_pos, rel_vel, &(combined_cov * *scale), *radius))
+        .collect()
+}
+
+/// Whether inflating the covariance lowers Pc (the dilution region)
+///
+/// There a low Pc reflects poor knowledge of the orbits rather than a safe
+/// geometry, so a "green" event is not robust.
+pub fn in_dilution_region(
+    method: PcMethod,
+    rel_pos: &Vector3<f64>,
+    rel_vel: &Vector3<f64>,
+    combined_cov: &Matrix3<f64>,
+    hard_body_radius: f64,
+) -> Option<bool> {
+    let pc = |scale: f64| pc_with_method(method, rel_pos, rel_vel, &(combined_cov * scale), hard_body_radius);
+    Some(pc(DILUTION_STEP)? < pc(1.0)?)
+}
+
+/// Worst-case Pc over all scalings of the covariance (Alfano/Frisbee maximum Pc)
+///
+/// Returns the maximum probability and the factor multiplying
+/// `combined_cov` at which it occurs. For a small hard body the Pc of the
+/// scaled covariance behaves as `exp(-d^2 / 2k) / k`, with `d` the
+/// Mahalanobis miss distance, peaking at `k = d^2 / 2`; the exact maximum
+/// is refined from there by golden-section search in log scale. A miss
+/// inside the hard-body disk gives 1 as the covariance shrinks to zero
+/// (scale 0).
+pub fn max_pc(
+    method: PcMethod,
+    rel_pos: &Vector3<f64>,
+    rel_vel: &Vector3<f64>,
+    combined_cov: &Matrix3<f64>,
+    hard_body_radius: f64,
+) -> Option<(f64, f64)> {
+    let encounter = Encounter::project(rel_pos, rel_vel, combined_cov)?;
+    if hard_body_radius <= 0.0 {
+        return Some((0.0, 1.0));
+    }
+    if encounter.mu.hypot(encounter.mw) <= hard_body_radius {
+        return Some((1.0, 0.0));
+    }
+    let mahalanobis2 = (encounter.mu / encounter.sigma_u).powi(2) + (encounter.mw / encounter.sigma_w).powi(2);
+    let pc = |log_scale: f64| {
+        pc_with_method(method, rel_pos, rel_vel, &(combined_cov * log_scale.exp()), hard_body_radius)
+    };
+
+    let centre = (0.5 * mahalanobis2).ln();
+    let (mut a, mut b) = (centre - MAX_PC_BRACKET.ln(), centre + MAX_PC_BRACKET.ln());
+    let ratio = (5f64.sqrt() - 1.0) / 2.0;
+    let (mut c, mut d) = (b - ratio * (b - a), a + ratio * (b - a));
+    let (mut pc_c, mut pc_d) = (pc(c)?, pc(d)?);
+    for _ in 0..MAX_PC_ITERATIONS {
+        if pc_c > pc_d {
+            b = d;
+            (d, pc_d) = (c, pc_c);
+            c = b - ratio * (b - a);
+            pc_c = pc(c)?;
+        } else {
+            a = c;
+            (c, pc_c) = (d, pc_d);
+            d = a + ratio * (b - a);
+            pc_d = pc(d)?;
+        }
+    }
+    let log_scale = 0.5 * (a + b);
+    Some((pc(log_scale)?, log_scale.exp()))
+}
+
+/// Parse a 3x3 or 6x6 covariance (nested lists, km^2) into its position block
+#[cfg(feature = "python")]
+pub(crate) fn position_covariance(cov: &[Vec<f64>]) -> PyResult<Matrix3<f64>> {
+    let n = cov.len();
+    if !(n == 3 || n == 6) || cov.iter().any(|row| row.len() != n) {
+        return Err(pyo3::exceptions::PyValueError::new_err(
+            "Covariance must be a 3x3 or 6x6 matrix"
+        ));
+    }
+    Ok(Matrix3::from_fn(|i, j| cov[i][j]))
+}
+
+/// Relative state (secondary - primary) and combined position covariance
+#[cfg(feature = "python")]
+fn relative_encounter(
+    state1: &Satellite,
+    cov1: &[Vec<f64>],
+    state2: &Satellite,
+    cov2: &[Vec<f64>],
+) -> PyResult<(Vector3<f64>, Vector3<f64>, Matrix3<f64>)> {
+    let combined = position_covariance(cov1)? + position_covariance(cov2)?;
+    let rel_pos = state2.r() - state1.r();
+    let rel_vel = state2.v() - state1.v();
+    Ok((rel_pos, rel_vel, combined))
+}
+
+#[cfg(feature = "python")]
+fn degenerate() -> PyErr {
+    pyo3::exceptions::PyValueError::new_err(
+        "Degenerate encounter: zero relative velocity or singular projected covariance"
+    )
+}
+
+/// Probability of collision between two objects at TCA
+///
+/// `method` is "foster" (default), "patera", "alfano" or "chan".
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(signature = (state1, cov1, state2, cov2, hard_body_radius, method = "foster"))]
+pub fn collision_probability(
+    state1: &Satellite,
+    cov1: Vec<Vec<f64>>,
+    state2: &Satellite,
+    cov2: Vec<Vec<f64>>,
+    hard_body_radius: f64,
+    method: &str,
+) -> PyResult<f64> {
+    let method: PcMethod = method.parse().map_err(pyo3::exceptions::PyValueError::new_err)?;
+    let (rel_pos, rel_vel, combined) = relative_encounter(state1, &cov1, state2, &cov2)?;
+    pc_with_method(method, &rel_pos, &rel_vel, &combined, hard_body_radius).ok_or_else(degenerate)
+}
+
+/// Pc from every method, keyed by method name, for agreement checks
+#[cfg(feature = "python")]
+#[pyfunction]
+pub fn compare_pc_methods(
+    state1: &Satellite,
+    cov1: Vec<Vec<f64>>,
+    state2: &Satellite,
+    cov2: Vec<Vec<f64>>,
+    hard_body_radius: f64,
+) -> PyResult<HashMap<&'static str, f64>> {
+    let (rel_pos, rel_vel, combined) = relative_encounter(state1, &cov1, state2, &cov2)?;
+    PcMethod::ALL
+        .iter()
+        .map(|method| {
+            pc_with_method(*method, &rel_pos, &rel_vel, &combined, hard_body_radius)
+                .map(|pc| (method.name(), pc))
+                .ok_or_else(degenerate)
+        })
+        .collect()
+}
+
+/// Pc sensitivity to covariance scaling and hard-body radius
+///
+/// Sweeps the combined covariance by `scales` (default 0.25 to 4 in
+/// quarter-octave steps, multiplying the covariance matrix) for each of
+/// `radii` (km; default just `hard_body_radius`). Returns a dict with
+/// `scale` and `hard_body_radius` columns, the `pc` matrix of shape
+/// (scales, radii), and per radius `max_pc` with the `max_pc_scale` it
+/// occurs at within the sweep and `dilution_region`: True when inflating
+/// the covariance lowers Pc, so a small Pc is no evidence of safety.
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(signature = (state1, cov1, state2, cov2, hard_body_radius, scales = None, radii = None, method = "foster"))]
+#[allow(clippy::too_many_arguments)]
+pub fn pc_sensitivity(
+    py: Python<'_>,
+    state1: &Satellite,
+    cov1: Vec<Vec<f64>>,
+    state2: &Satellite,
+    cov2: Vec<Vec<f64>>,
+    hard_body_radius: f64,
+    scales: Option<Vec<f64>>,
+    radii: Option<Vec<f64>>,
+    method: &str,
+) -> PyResult<PyObject> {
+    let method: PcMethod = method.parse().map_err(pyo3::exceptions::PyValueError::new_err)?;
+    let (rel_pos, rel_vel, combined) = relative_encounter(state1, &cov1, state2, &cov2)?;
+    let scales = scales.unwrap_or_else(|| {
+        (-DEFAULT_SCALE_STEPS..=DEFAULT_SCALE_STEPS).map(|k| 2.0_f64.powf(k as f64 / 4.0)).collect()
+    });
+    let radii = radii.unwrap_or_else(|| vec![hard_body_radius]);
+    if scales.is_empty() || scales.iter().any(|k| k.is_nan() || *k <= 0.0) {
+        return Err(pyo3::exceptions::PyValueError::new_err("Covariance scales must be positive"));
+    }
+    if radii.is_empty() || radii.iter().any(|r| r.is_nan() || *r < 0.0) {
+        return Err(pyo3::exceptions::PyValueError::new_err("Hard-body radii must be non-negative"));
+    }
+
+    let pc = pc_sweep(method, &rel_pos, &rel_vel, &combined, &scales, &radii).ok_or_else(degenerate)?;
+    let mut max_pc = vec![];
+    let mut max_pc_scale = vec![];
+    let mut dilution = vec![];
+    for (j, radius) in radii.iter().enumerate() {
+        let (row, best) = (0..scales.len())
+            .map(|i| (i, pc[i * radii.len() + j]))
+            .fold((0, f64::NEG_INFINITY), |a, b| if b.1 > a.1 { b } else { a });
+        max_pc.push(best);
+        max_pc_scale.push(scales[row]);
+        dilution.push(in_dilution_region(method, &rel_pos, &rel_vel, &combined, *radius).ok_or_else(degenerate)?);
+    }
+
+    let table = pyo3::types::PyDict::new(py);
+    table.set_item("scale", Py::new(py, Column::float64(scales.clone()))?)?;
+    table.set_item("hard_body_radius", Py::new(py, Column::float64(radii.clone()))?)?;
+    table.set_item("pc", Py::new(py, Column::matrix(pc, scales.len(), radii.len()))?)?;
+    table.set_item("max_pc", Py::new(py, Column::float64(max_pc))?)?;
+    table.set_item("max_pc_scale", Py::new(py, Column::float64(max_pc_scale))?)?;
+    table.set_item("dilution_region", dilution)?;
+    Ok(table.into())
+}
+
+/// Worst-case Pc over all covariance scalings for the encounter geometry
+///
+/// For use when covariance realism is suspect. Returns a dict with the
+/// nominal `pc`, the maximum `max_pc`, the covariance `scale` achieving it
+/// (multiplying the covariance matrix; 0 when the miss lies inside the
+/// hard-body radius, giving 1) and `dilution_region`, True when the
+/// nominal covariance is already larger than the worst case.
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(signature = (state1, cov1, state2, cov2, hard_body_radius, method = "foster"))]
+pub fn max_collision_probability(
+    py: Python<'_>,
+    state1: &Satellite,
+    cov1: Vec<Vec<f64>>,
+    state2: &Satellite,
+    cov2: Vec<Vec<f64>>,
+    hard_body_radius: f64,
+    method: &str,
+) -> PyResult<PyObject> {
+    let method: PcMethod = method.parse().map_err(pyo3::exceptions::PyValueError::new_err)?;
+    let (rel_pos, rel_vel, combined) = relative_encounter(state1, &cov1, state2, &cov2)?;
+    let pc = pc_with_method(method, &rel_pos, &rel_vel, &combined, hard_body_radius).ok_or_else(degenerate)?;
+    let (worst, scale) = max_pc(method, &rel_pos, &rel_vel, &combined, hard_body_radius).ok_or_else(degenerate)?;
+
+    let result = pyo3::types::PyDict::new(py);
+    result.set_item("pc", pc)?;
+    result.set_item("max_pc", worst.max(pc))?;
+    result.set_item("scale", scale)?;
+    result.set_item("dilution_region", scale < 1.0)?;
+    Ok(result.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_erf_values() {
+        assert!(erf(0.0).abs() < 1e-7);
+        assert!((erf(1.0) - 0.8427007929).abs() < 1e-7);
+        assert!((erf(-2.0) + 0.9953222650).abs() < 1e-7);
+    }
+
+    #[test]
+    fn test_zero_miss_isotropic_matches_closed_form() {
+        // Pc = 1 - exp(-R^2 / (2 sigma^2)) for a centred isotropic Gaussian
+        let sigma = 0.1;
+        let radius = 0.02;
+        let cov = Matrix3::identity() * sigma * sigma;
+        let pc = foster_pc(&Vector3::zeros(), &Vector3::new(0.0, 10.0, 0.0), &cov, radius).unwrap();
+        let expected = 1.0 - (-(radius * radius) / (2.0 * sigma * sigma)).exp();
+        assert!((pc - expected).abs() / expected < 1e-5);
+    }
+
+    #[test]
+    fn test_pc_decreases_with_miss_distance() {
+        let cov = Matrix3::from_diagonal(&Vector3::new(0.04, 1.0, 0.01));
+        let vel = Vector3::new(0.0, 14.0, 0.0);
+        let near = foster_pc(&Vector3::new(0.05, 0.0, 0.0), &vel, &cov, 0.01).unwrap();
+        let far = foster_pc(&Vector3::new(0.5, 0.0, 0.0), &vel, &cov, 0.01).unwrap();
+        assert!(near > far);
+        assert!(far > 0.0);
+    }
+
+    #[test]
+    fn test_pc_sweep_and_dilution() {
+        let vel = Vector3::new(0.0, 14.0, 0.0);
+        let cov = Matrix3::from_diagonal(&Vector3::new(0.01, 1.0, 0.01));
+        let scales = [0.25, 1.0, 4.0];
+        let radii = [0.01, 0.02];
+
+        // a near-zero miss only gets less likely as the covariance grows
+        let centred = pc_sweep(PcMethod::Foster, &Vector3::new(0.01, 0.0, 0.0), &vel, &cov, &scales, &radii).unwrap();
+        assert_eq!(centred.len(), 6);
+        assert!(centred[0] > centred[2] && centred[2] > centred[4]);
+        assert!(centred[1] > centred[0]);
+        assert!(in_dilution_region(PcMethod::Foster, &Vector3::new(0.01, 0.0, 0.0), &vel, &cov, 0.01).unwrap());
+
+        // a miss of several sigmas: a larger covariance raises Pc, the robust side of the peak
+        let far = Vector3::new(0.5, 0.0, 0.0);
+        let swept = pc_sweep(PcMethod::Foster, &far, &vel, &cov, &scales, &radii).unwrap();
+        assert!(swept[0] < swept[2] && swept[2] < swept[4]);
+        assert!(!in_dilution_region(PcMethod::Foster, &far, &vel, &cov, 0.01).unwrap());
+    }
+
+    #[test]
+    fn test_max_pc_matches_isotropic_closed_form() {
+        // isotropic sigma^2 k, miss d >> R: Pc ~ R^2 exp(-d^2 / 2 sigma^2 k) / (2 sigma^2 k),
+        // maximal at sigma^2 k = d^2 / 2 with Pc = R^2 / (e d^2)
+        let vel = Vector3::new(0.0, 14.0, 0.0);
+        let cov = Matrix3::identity() * 0.01;
+        let (miss, radius) = (0.5, 0.005);
+        let (worst, scale) = max_pc(PcMethod::Foster, &Vector3::new(miss, 0.0, 0.0), &vel, &cov, radius).unwrap();
+        let expected = radius * radius / (std::f64::consts::E * miss * miss);
+        assert!((worst - expected).abs() / expected < 1e-3, "{} {}", worst, expected);
+        assert!((scale * 0.01 - miss * miss / 2.0).abs() / (miss * miss / 2.0) < 1e-2, "{}", scale);
+
+        let nominal = foster_pc(&Vector3::new(miss, 0.0, 0.0), &vel, &cov, radius).unwrap();
+        assert!(worst >= nominal);
+        let inside = max_pc(PcMethod::Foster, &Vector3::new(0.001, 0.0, 0.0), &vel, &cov, radius).unwrap();
+        assert_eq!(inside, (1.0, 0.0));
+    }
+
+    #[test]
+    fn test_methods_agree() {
+        let cov = Matrix3::new(0.04, 0.01, 0.0, 0.01, 1.0, 0.0, 0.0, 0.0, 0.02);
+        let vel = Vector3::new(0.0, 14.0, 0.0);
+        for miss in [Vector3::zeros(), Vector3::new(0.1, 0.0, 0.05), Vector3::new(0.4, 0.0, -0.2)] {
+            let foster = foster_pc(&miss, &vel, &cov, 0.02).unwrap();
+            // Alfano's strips keep the square-root edge singularity, so it converges slower
+            for (method, tolerance) in [(PcMethod::Patera, 1e-6), (PcMethod::Alfano, 1e-3)] {
+                let pc = pc_with_method(method, &miss, &vel, &cov, 0.02).unwrap();
+                assert!((pc - foster).abs() / foster < tolerance, "{:?}: {} vs {}", method, pc, foster);
+            }
+            // equal-area approximation: close for small radius / sigma
+            let chan = pc_with_method(PcMethod::Chan, &miss, &vel, &cov, 0.02).unwrap();
+            assert!((chan - foster).abs() / foster < 1e-2, "chan: {} vs {}", chan, foster);
+        }
+    }
+}
@@ -0,0 +1,185 @@
+//! Progress reporting and cancellation for long-running screens
+//!
+//! The work runs on a worker thread without the GIL while the calling
+//! thread wakes up every `interval_s` seconds, re-acquires the GIL only to
+//! call the Python callback with (percent, pairs_processed, hits), and
+//! checks for Ctrl-C. A callback that raises, a signal, or a
+//! `CancellationToken` stops the workers at the next pair row; the screen
+//! then raises instead of returning a partial result.
+//!
+//! `Progress` and `CancellationToken` are plain Rust; only `run_monitored`
+//! needs Python.
+
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+#[cfg(feature = "python")]
+use std::time::{Duration, Instant};
+
+use crate::error::OrbitError;
+
+/// How often the waiting thread checks whether the work has finished
+#[cfg(feature = "python")]
+const POLL: Duration = Duration::from_millis(5);
+
+/// Flag shared with running screens; `cancel()` makes them stop early
+#[cfg_attr(feature = "python", pyclass)]
+#[derive(Clone, Default)]
+pub struct CancellationToken {
+    flag: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    /// Request cancellation; safe to call from any thread or callback
+    pub fn cancel(&self) {
+        self.flag.store(true, Ordering::Relaxed);
+    }
+
+    pub fn cancelled(&self) -> bool {
+        self.flag.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl CancellationToken {
+    #[new]
+    fn py_new() -> Self {
+        CancellationToken::default()
+    }
+
+    /// Request cancellation; safe to call from any thread or callback
+    #[pyo3(name = "cancel")]
+    fn py_cancel(&self) {
+        self.cancel();
+    }
+
+    #[getter(cancelled)]
+    fn py_cancelled(&self) -> bool {
+        self.cancelled()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("CancellationToken(cancelled={})", self.cancelled())
+    }
+}
+
+/// Counters updated by the workers and read by the monitoring thread
+pub struct Progress {
+    total: AtomicUsize,
+    pairs: AtomicUsize,
+    hits: AtomicUsize,
+    cancel: Arc<AtomicBool>,
+}
+
+impl Progress {
+    /// Counters for `total` units of work, sharing the token's flag when given
+    pub fn new(total: usize, token: Option<&CancellationToken>) -> Self {
+        Progress {
+            total: AtomicUsize::new(total),
+            pairs: AtomicUsize::new(0),
+            hits: AtomicUsize::new(0),
+            cancel: token.map_or_else(Arc::default, |token| Arc::clone(&token.flag)),
+        }
+    }
+
+    /// Reset the work size once it is known
+    pub fn set_total(&self, total: usize) {
+        self.total.store(total, Ordering::Relaxed);
+    }
+
+    pub fn advance(&self, pairs: usize, hits: usize) {
+        self.pairs.fetch_add(pairs, Ordering::Relaxed);
+        self.hits.fetch_add(hits, Ordering::Relaxed);
+    }
+
+    pub fn cancel(&self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.load(Ordering::Relaxed)
+    }
+
+    /// (percent complete, pairs processed, hits so far)
+    pub fn snapshot(&self) -> (f64, usize, usize) {
+        let (total, pairs) = (self.total.load(Ordering::Relaxed), self.pairs.load(Ordering::Relaxed));
+        let percent = if total == 0 { 100.0 } else { (100.0 * pairs as f64 / total as f64).min(100.0) };
+        (percent, pairs, self.hits.load(Ordering::Relaxed))
+    }
+
+    /// Error raised by a screen that stopped early
+    pub fn cancelled_error() -> OrbitError {
+        OrbitError::Cancelled
+    }
+}
+
+/// Run `work` without the GIL, reporting to `callback` every `interval_s` seconds
+///
+/// The callback receives (percent, pairs_processed, hits) and is called a
+/// final time on success. An exception from it, or a pending signal such
+/// as Ctrl-C, cancels the work and is re-raised once the workers stop.
+#[cfg(feature = "python")]
+pub fn run_monitored<T: Send>(
+    py: Python<'_>,
+    progress: &Progress,
+    callback: Option<&PyAny>,
+    interval_s: f64,
+    work: impl FnOnce() -> PyResult<T> + Send,
+) -> PyResult<T> {
+    if !(interval_s > 0.0 && interval_s.is_finite()) {
+        return Err(pyo3::exceptions::PyValueError::new_err("progress_interval_s must be positive"));
+    }
+    let interval = Duration::from_secs_f64(interval_s);
+    std::thread::scope(|scope| {
+        let worker = scope.spawn(work);
+        let mut failure: Option<PyErr> = None;
+        while !worker.is_finished() {
+            py.allow_threads(|| {
+                let deadline = Instant::now() + interval;
+                while !worker.is_finished() && Instant::now() < deadline {
+                    std::thread::sleep(POLL.min(interval));
+                }
+            });
+            if failure.is_some() || worker.is_finished() {
+                continue;
+            }
+            let report = py.check_signals().and_then(|_| match callback {
+                Some(callback) => callback.call1(progress.snapshot()).map(|_| ()),
+                None => Ok(()),
+            });
+            if let Err(err) = report {
+                progress.cancel();
+                failure = Some(err);
+            }
+        }
+        let result = worker.join().unwrap_or_else(|panic| std::panic::resume_unwind(panic));
+        if let Some(err) = failure {
+            return Err(err);
+        }
+        let value = result?;
+        if let Some(callback) = callback {
+            callback.call1(progress.snapshot())?;
+        }
+        Ok(value)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_and_shared_cancellation() {
+        let token = CancellationToken::default();
+        let progress = Progress::new(200, Some(&token));
+        progress.advance(50, 2);
+        progress.advance(50, 1);
+        assert_eq!(progress.snapshot(), (50.0, 100, 3));
+        assert!(!progress.is_cancelled());
+        token.cancel();
+        assert!(progress.is_cancelled());
+        assert_eq!(Progress::new(0, None).snapshot().0, 100.0);
+    }
+}
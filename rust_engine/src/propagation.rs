@@ -0,0 +1,1050 @@
+//! Numerical propagation of state vectors
+//!
+//! Point-mass (two-body) and J2 force models integrated with either a
+//! fixed-step classical RK4 or an adaptive Dormand-Prince 5(4) scheme.
+//! Intended for objects known by state vector only (no TLE). Atmospheric
+//! drag can be added on top of either gravity model, with any density
+//! model from `density`; the atmosphere co-rotates with the Earth. Lunar
+//! and solar third-body gravity and solar radiation pressure (cylindrical
+//! Earth shadow) use the ephemerides of `celestial`; over multi-day arcs,
+//! GEO objects in particular, they dominate the perturbations beyond J2.
+//!
+//! Covariances are mapped with the state transition matrix, integrated
+//! alongside the state from the variational equations of the same force
+//! model: P(t) = Phi(t) P(0) Phi(t)^T. For strongly nonlinear cases (high
+//! eccentricity, large uncertainties) the unscented transform propagates
+//! sigma points through the full dynamics instead.
+
+use nalgebra::{Matrix3, Matrix6, SVector, Vector3, Vector6};
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use rayon::prelude::*;
+use std::str::FromStr;
+
+use crate::constants::{
+    AU_KM, EARTH_RADIUS_KM, EARTH_ROTATION_RATE, J2_EARTH, MU_EARTH, MU_MOON, MU_SUN, SECONDS_PER_DAY,
+    SOLAR_PRESSURE_1AU,
+};
+#[cfg(feature = "python")]
+use crate::density::SpaceWeather;
+use crate::density::Atmosphere;
+use crate::eclipse::{self, ShadowModel};
+#[cfg(feature = "python")]
+use crate::error::OrbitError;
+use crate::celestial;
+#[cfg(feature = "python")]
+use crate::columnar::Column;
+#[cfg(feature = "python")]
+use crate::epoch::Epoch;
+#[cfg(feature = "python")]
+use crate::ground_track::{sample_states, TrackTarget};
+#[cfg(feature = "python")]
+use crate::{check_state, check_states, parallel, Satellite};
+
+/// Relative tolerance of the adaptive integrator
+const RTOL: f64 = 1.0e-11;
+
+/// Absolute tolerance of the adaptive integrator (km, km/s)
+const ATOL: f64 = 1.0e-9;
+
+/// Step-size safety factor and growth limits for Dormand-Prince
+const SAFETY: f64 = 0.9;
+const MIN_SCALE: f64 = 0.2;
+const MAX_SCALE: f64 = 5.0;
+
+/// Upper bound on integrator steps per call
+const MAX_STEPS: usize = 1_000_000;
+
+type State = Vector6<f64>;
+
+/// State followed by the column-major 6x6 state transition matrix
+type Augmented = SVector<f64, 42>;
+
+/// Gravity model used for the equations of motion
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ForceModel {
+    TwoBody,
+    J2,
+}
+
+/// Atmospheric drag
+#[derive(Clone, Copy, Debug)]
+pub struct Drag {
+    pub ballistic_coefficient: f64, // Cd * A / m (m^2/kg)
+    pub atmosphere: Atmosphere,
+}
+
+impl Drag {
+    /// Drag acceleration (km/s^2) relative to the co-rotating atmosphere
+    pub fn acceleration(&self, r: &Vector3<f64>, v: &Vector3<f64>) -> Vector3<f64> {
+        let v_rel = v - Vector3::new(0.0, 0.0, EARTH_ROTATION_RATE).cross(r);
+        // 1/2 B rho |v|^2 with B in m^2/kg, rho in kg/m^3 and v in km/s, scaled to km/s^2
+        -0.5e3 * self.ballistic_coefficient * self.atmosphere.density(r) * v_rel.norm() * v_rel
+    }
+
+    /// Partials of the acceleration with respect to position and velocity (central differences)
+    fn jacobian(&self, r: &Vector3<f64>, v: &Vector3<f64>) -> (Matrix3<f64>, Matrix3<f64>) {
+        let mut d_position = Matrix3::zeros();
+        let mut d_velocity = Matrix3::zeros();
+        for j in 0..3 {
+            let mut step = Vector3::zeros();
+            step[j] = 1.0e-3;
+            d_position.set_column(j, &((self.acceleration(&(r + step), v) - self.acceleration(&(r - step), v)) / 2.0e-3));
+            step[j] = 1.0e-6;
+            d_velocity.set_column(j, &((self.acceleration(r, &(v + step)) - self.acceleration(r, &(v - step))) / 2.0e-6));
+        }
+        (d_position, d_velocity)
+    }
+}
+
+/// Lunar and solar point-mass gravity
+#[derive(Clone, Copy, Debug)]
+pub struct ThirdBody {
+    pub epoch_jd: f64, // Julian date at the start of the integration
+    pub sun: bool,
+    pub moon: bool,
+}
+
+impl ThirdBody {
+    /// (gravitational parameter, geocentric position) of each body `t` seconds from the epoch
+    fn bodies(&self, t: f64) -> [Option<(f64, Vector3<f64>)>; 2] {
+        let jd = self.epoch_jd + t / SECONDS_PER_DAY;
+        [
+            self.sun.then(|| (MU_SUN, celestial::sun_position(jd))),
+            self.moon.then(|| (MU_MOON, celestial::moon_position(jd))),
+        ]
+    }
+
+    /// Perturbing acceleration (km/s^2): attraction of the satellite minus that of the Earth
+    pub fn acceleration(&self, t: f64, r: &Vector3<f64>) -> Vector3<f64> {
+        self.bodies(t)
+            .iter()
+            .flatten()
+            .map(|(mu, s)| {
+                let d = s - r;
+                *mu * (d / d.norm().powi(3) - s / s.norm().powi(3))
+            })
+            .sum()
+    }
+
+    /// Gradient of the acceleration with respect to position (1/s^2)
+    fn gradient(&self, t: f64, r: &Vector3<f64>) -> Matrix3<f64> {
+        self.bodies(t)
+            .iter()
+            .flatten()
+            .map(|(mu, s)| {
+                let d = r - s;
+                let d2 = d.norm_squared();
+                -*mu / (d2 * d2.sqrt()) * (Matrix3::identity() - 3.0 * d * d.transpose() / d2)
+            })
+            .sum()
+    }
+}
+
+/// Solar radiation pressure on a flat-plate (cannonball) model
+#[derive(Clone, Copy, Debug)]
+pub struct RadiationPressure {
+    pub epoch_jd: f64, // Julian date at the start of the integration
+    pub reflectivity_area_to_mass: f64, // C_R * A / m (m^2/kg)
+}
+
+impl RadiationPressure {
+    /// Acceleration (km/s^2) away from the Sun; zero inside the cylindrical Earth shadow
+    pub fn acceleration(&self, t: f64, r: &Vector3<f64>) -> Vector3<f64> {
+        let sun = celestial::sun_position(self.epoch_jd + t / SECONDS_PER_DAY);
+        if eclipse::sunlit_fraction(r, &sun, ShadowModel::Cylindrical) == 0.0 {
+            return Vector3::zeros();
+        }
+        let d = r - sun;
+        let distance = d.norm();
+        // P (AU / d)^2 C_R A / m in m/s^2, scaled to km/s^2
+        let magnitude = SOLAR_PRESSURE_1AU * self.reflectivity_area_to_mass * (AU_KM / distance).powi(2) * 1.0e-3;
+        magnitude * d / distance
+    }
+}
+
+/// Equations of motion: a gravity model plus optional perturbations
+#[derive(Clone, Copy, Debug)]
+pub struct Dynamics {
+    pub gravity: ForceModel,
+    pub drag: Option<Drag>,
+    pub third_body: Option<ThirdBody>,
+    pub radiation_pressure: Option<RadiationPressure>,
+}
+
+impl Dynamics {
+    /// The same forces for an integration starting `seconds` after the original epoch
+    pub fn advanced(&self, seconds: f64) -> Self {
+        let shift = seconds / SECONDS_PER_DAY;
+        Dynamics {
+            third_body: self.third_body.map(|b| ThirdBody { epoch_jd: b.epoch_jd + shift, ..b }),
+            radiation_pressure: self.radiation_pressure.map(|p| RadiationPressure { epoch_jd: p.epoch_jd + shift, ..p }),
+            ..*self
+        }
+    }
+}
+
+impl From<ForceModel> for Dynamics {
+    fn from(gravity: ForceModel) -> Self {
+        Dynamics { gravity, drag: None, third_body: None, radiation_pressure: None }
+    }
+}
+
+/// Integration scheme
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Integrator {
+    /// Classical fourth-order Runge-Kutta with a fixed step (seconds)
+    Rk4 { step: f64 },
+    /// Adaptive Dormand-Prince 5(4) with an initial step (seconds)
+    DormandPrince { initial_step: f64 },
+}
+
+#[cfg(feature = "python")]
+impl Integrator {
+    pub(crate) fn parse(method: &str, step: f64) -> PyResult<Self> {
+        if step <= 0.0 || !step.is_finite() {
+            return Err(pyo3::exceptions::PyValueError::new_err("step must be positive (seconds)"));
+        }
+        match method.to_ascii_lowercase().as_str() {
+            "rk4" => Ok(Integrator::Rk4 { step }),
+            "dp45" | "dopri" | "dormand-prince" => Ok(Integrator::DormandPrince { initial_step: step }),
+            _ => Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Unknown method '{}' (expected 'rk4' or 'dp45')",
+                method
+            ))),
+        }
+    }
+}
+
+/// Two-body acceleration (km/s^2)
+pub fn two_body_acceleration(r: &Vector3<f64>) -> Vector3<f64> {
+    let r_norm = r.norm();
+    -MU_EARTH / (r_norm * r_norm * r_norm) * r
+}
+
+/// J2 perturbing acceleration (km/s^2), z along the Earth's rotation axis
+pub fn j2_acceleration(r: &Vector3<f64>) -> Vector3<f64> {
+    let r2 = r.norm_squared();
+    let r_norm = r2.sqrt();
+    let z2 = r.z * r.z / r2;
+    let factor = -1.5 * J2_EARTH * MU_EARTH * EARTH_RADIUS_KM * EARTH_RADIUS_KM / (r2 * r2 * r_norm);
+    Vector3::new(
+        factor * r.x * (1.0 - 5.0 * z2),
+        factor * r.y * (1.0 - 5.0 * z2),
+        factor * r.z * (3.0 - 5.0 * z2),
+    )
+}
+
+/// Gradient of the acceleration with respect to position (1/s^2)
+pub fn gravity_gradient(r: &Vector3<f64>, model: ForceModel) -> Matrix3<f64> {
+    let r2 = r.norm_squared();
+    let r_norm = r2.sqrt();
+    let r_hat = r / r_norm;
+    let mut gradient = -MU_EARTH / (r2 * r_norm) * (Matrix3::identity() - 3.0 * r_hat * r_hat.transpose());
+
+    if model == ForceModel::J2 {
+        // a_i = k x_i c_i / r^5 with c = (1 - 5s, 1 - 5s, 3 - 5s), s = z^2 / r^2
+        let k = -1.5 * J2_EARTH * MU_EARTH * EARTH_RADIUS_KM * EARTH_RADIUS_KM;
+        let s = r.z * r.z / r2;
+        let c = [1.0 - 5.0 * s, 1.0 - 5.0 * s, 3.0 - 5.0 * s];
+        let ds = Vector3::new(0.0, 0.0, 2.0 * r.z / r2) - 2.0 * r.z * r.z / (r2 * r2) * r;
+        let scale = k / (r2 * r2 * r_norm);
+        for i in 0..3 {
+            for j in 0..3 {
+                let diagonal = if i == j { c[i] } else { 0.0 };
+                gradient[(i, j)] += scale * (diagonal - 5.0 * r[i] * ds[j] - 5.0 * r[i] * r[j] * c[i] / r2);
+            }
+        }
+    }
+    gradient
+}
+
+fn derivative(t: f64, y: &State, dynamics: &Dynamics) -> State {
+    let r = Vector3::new(y[0], y[1], y[2]);
+    let mut a = two_body_acceleration(&r);
+    if dynamics.gravity == ForceModel::J2 {
+        a += j2_acceleration(&r);
+    }
+    if let Some(drag) = &dynamics.drag {
+        a += drag.acceleration(&r, &Vector3::new(y[3], y[4], y[5]));
+    }
+    if let Some(third_body) = &dynamics.third_body {
+        a += third_body.acceleration(t, &r);
+    }
+    if let Some(radiation_pressure) = &dynamics.radiation_pressure {
+        a += radiation_pressure.acceleration(t, &r);
+    }
+    Vector6::new(y[3], y[4], y[5], a.x, a.y, a.z)
+}
+
+/// Equations of motion plus variational equations dPhi/dt = A(t) Phi
+///
+/// Radiation pressure partials are neglected (of order P A / m / AU).
+fn augmented_derivative(t: f64, y: &Augmented, dynamics: &Dynamics) -> Augmented {
+    let state = y.fixed_rows::<6>(0).into_owned();
+    let phi = Matrix6::from_column_slice(&y.as_slice()[6..]);
+    let r = Vector3::new(y[0], y[1], y[2]);
+    let mut gradient = gravity_gradient(&r, dynamics.gravity);
+    let mut d_velocity = Matrix3::zeros();
+    if let Some(drag) = &dynamics.drag {
+        let (d_position, d_v) = drag.jacobian(&r, &Vector3::new(y[3], y[4], y[5]));
+        gradient += d_position;
+        d_velocity = d_v;
+    }
+    if let Some(third_body) = &dynamics.third_body {
+        gradient += third_body.gradient(t, &r);
+    }
+
+    let mut dphi = Matrix6::zeros();
+    dphi.fixed_view_mut::<3, 6>(0, 0).copy_from(&phi.fixed_view::<3, 6>(3, 0));
+    dphi.fixed_view_mut::<3, 6>(3, 0).copy_from(
+        &(gradient * phi.fixed_view::<3, 6>(0, 0) + d_velocity * phi.fixed_view::<3, 6>(3, 0)),
+    );
+
+    let mut dy = Augmented::zeros();
+    dy.fixed_rows_mut::<6>(0).copy_from(&derivative(t, &state, dynamics));
+    dy.as_mut_slice()[6..].copy_from_slice(dphi.as_slice());
+    dy
+}
+
+fn rk4_step<const N: usize, F: Fn(f64, &SVector<f64, N>) -> SVector<f64, N>>(
+    t: f64,
+    y: &SVector<f64, N>,
+    h: f64,
+    f: &F,
+) -> SVector<f64, N> {
+    let k1 = f(t, y);
+    let k2 = f(t + h / 2.0, &(y + k1 * (h / 2.0)));
+    let k3 = f(t + h / 2.0, &(y + k2 * (h / 2.0)));
+    let k4 = f(t + h, &(y + k3 * h));
+    y + (k1 + k2 * 2.0 + k3 * 2.0 + k4) * (h / 6.0)
+}
+
+/// One Dormand-Prince step: fifth-order solution and error estimate
+fn dopri_step<const N: usize, F: Fn(f64, &SVector<f64, N>) -> SVector<f64, N>>(
+    t: f64,
+    y: &SVector<f64, N>,
+    h: f64,
+    f: &F,
+) -> (SVector<f64, N>, SVector<f64, N>) {
+    let k1 = f(t, y);
+    let k2 = f(t + h / 5.0, &(y + k1 * (h / 5.0)));
+    let k3 = f(t + h * (3.0 / 10.0), &(y + (k1 * (3.0 / 40.0) + k2 * (9.0 / 40.0)) * h));
+    let k4 = f(t + h * (4.0 / 5.0), &(y + (k1 * (44.0 / 45.0) - k2 * (56.0 / 15.0) + k3 * (32.0 / 9.0)) * h));
+    let k5 = f(t + h * (8.0 / 9.0), &(y + (k1 * (19372.0 / 6561.0) - k2 * (25360.0 / 2187.0) + k3 * (64448.0 / 6561.0)
+        - k4 * (212.0 / 729.0))
+        * h));
+    let k6 = f(t + h, &(y + (k1 * (9017.0 / 3168.0) - k2 * (355.0 / 33.0) + k3 * (46732.0 / 5247.0)
+        + k4 * (49.0 / 176.0)
+        - k5 * (5103.0 / 18656.0))
+        * h));
+    let y5 = y + (k1 * (35.0 / 384.0) + k3 * (500.0 / 1113.0) + k4 * (125.0 / 192.0)
+        - k5 * (2187.0 / 6784.0)
+        + k6 * (11.0 / 84.0))
+        * h;
+    let k7 = f(t + h, &y5);
+    let error = (k1 * (71.0 / 57600.0) - k3 * (71.0 / 16695.0) + k4 * (71.0 / 1920.0)
+        - k5 * (17253.0 / 339200.0)
+        + k6 * (22.0 / 525.0)
+        - k7 * (1.0 / 40.0))
+        * h;
+    (y5, error)
+}
+
+/// Integrate `dy/dt = f(t, y)` from t = 0 over `dt` seconds (negative for backward propagation)
+fn solve<const N: usize, F: Fn(f64, &SVector<f64, N>) -> SVector<f64, N>>(
+    y0: &SVector<f64, N>,
+    dt: f64,
+    integrator: Integrator,
+    f: F,
+) -> Option<SVector<f64, N>> {
+    let direction = dt.signum();
+    let mut y = *y0;
+    let mut t = 0.0;
+    let mut steps = 0;
+
+    match integrator {
+        Integrator::Rk4 { step } => {
+            while (dt - t) * direction > 0.0 {
+                let h = direction * step.min((dt - t).abs());
+                y = rk4_step(t, &y, h, &f);
+                t += h;
+                steps += 1;
+                if steps > MAX_STEPS {
+                    return None;
+                }
+            }
+        }
+        Integrator::DormandPrince { initial_step } => {
+            let mut h_abs = initial_step;
+            while (dt - t) * direction > 0.0 {
+                let h = direction * h_abs.min((dt - t).abs());
+                let (y_new, error) = dopri_step(t, &y, h, &f);
+                let err_norm = (0..N)
+                    .map(|k| error[k].abs() / (ATOL + RTOL * y[k].abs().max(y_new[k].abs())))
+                    .fold(0.0, f64::max);
+                if !err_norm.is_finite() {
+                    return None;
+                }
+                if err_norm <= 1.0 {
+                    y = y_new;
+                    t += h;
+                }
+                let scale = if err_norm == 0.0 { MAX_SCALE } else { SAFETY * err_norm.powf(-0.2) };
+                h_abs = h.abs() * scale.clamp(MIN_SCALE, MAX_SCALE);
+                steps += 1;
+                if steps > MAX_STEPS {
+                    return None;
+                }
+            }
+        }
+    }
+
+    y.iter().all(|v| v.is_finite()).then_some(y)
+}
+
+/// Integrate a state over `dt` seconds (negative for backward propagation)
+pub fn integrate(y0: &State, dt: f64, dynamics: impl Into<Dynamics>, integrator: Integrator) -> Option<State> {
+    let dynamics = dynamics.into();
+    solve(y0, dt, integrator, |t, y| derivative(t, y, &dynamics))
+}
+
+/// Integrate a state together with its state transition matrix
+pub fn integrate_with_stm(
+    y0: &State,
+    dt: f64,
+    dynamics: impl Into<Dynamics>,
+    integrator: Integrator,
+) -> Option<(State, Matrix6<f64>)> {
+    let dynamics = dynamics.into();
+    let mut start = Augmented::zeros();
+    start.fixed_rows_mut::<6>(0).copy_from(y0);
+    start.as_mut_slice()[6..].copy_from_slice(Matrix6::<f64>::identity().as_slice());
+    let y = solve(&start, dt, integrator, |t, y| augmented_derivative(t, y, &dynamics))?;
+    Some((y.fixed_rows::<6>(0).into_owned(), Matrix6::from_column_slice(&y.as_slice()[6..])))
+}
+
+/// Map a covariance through a state transition matrix (result symmetrised)
+pub fn map_covariance(stm: &Matrix6<f64>, covariance: &Matrix6<f64>) -> Matrix6<f64> {
+    let mapped = stm * covariance * stm.transpose();
+    (mapped + mapped.transpose()) * 0.5
+}
+
+/// How a covariance is carried through the propagator
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CovarianceMethod {
+    /// Linear mapping with the state transition matrix
+    #[default]
+    Stm,
+    /// Unscented transform: sigma points through the nonlinear propagator
+    Unscented,
+}
+
+impl FromStr for CovarianceMethod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "stm" | "linear" => Ok(CovarianceMethod::Stm),
+            "unscented" | "ut" | "sigma_points" => Ok(CovarianceMethod::Unscented),
+            _ => Err(format!("Unknown covariance method '{}' (expected 'stm' or 'unscented')", s)),
+        }
+    }
+}
+
+#[cfg(feature = "python")]
+impl CovarianceMethod {
+    pub(crate) fn parse(method: &str) -> PyResult<Self> {
+        method.parse().map_err(pyo3::exceptions::PyValueError::new_err)
+    }
+}
+
+/// Unscented transform of a state and covariance over `dt` seconds
+///
+/// The 2n symmetric sigma points y0 +- sqrt(n) L_i (L the Cholesky factor,
+/// or the eigen square root of a semi-definite covariance) are propagated
+/// with the full dynamics and weighted equally. Returns the propagated
+/// nominal state and the sigma-point covariance about it: the curvature of
+/// the uncertainty along the orbit, which the linear mapping drops, both
+/// shapes the covariance and shifts the mean, and the shift is kept as a
+/// widening rather than lost.
+pub fn unscented_transform(
+    y0: &State,
+    covariance: &Matrix6<f64>,
+    dt: f64,
+    dynamics: impl Into<Dynamics>,
+    integrator: Integrator,
+) -> Option<(State, Matrix6<f64>)> {
+    let dynamics = dynamics.into();
+    let root = match covariance.cholesky() {
+        Some(cholesky) => cholesky.l(),
+        None => {
+            let eigen = covariance.symmetric_eigen();
+            eigen.eigenvectors * Matrix6::from_diagonal(&eigen.eigenvalues.map(|l| l.max(0.0).sqrt()))
+        }
+    };
+    let scale = 6.0_f64.sqrt();
+    let nominal = integrate(y0, dt, dynamics, integrator)?;
+    let points: Vec<State> = (0..12)
+        .into_par_iter()
+        .map(|k| {
+            let sign = if k < 6 { 1.0 } else { -1.0 };
+            integrate(&(y0 + sign * scale * root.column(k % 6)), dt, dynamics, integrator)
+        })
+        .collect::<Option<_>>()?;
+
+    let mapped = points
+        .iter()
+        .map(|point| {
+            let d = point - nominal;
+            d * d.transpose()
+        })
+        .sum::<Matrix6<f64>>()
+        / 12.0;
+    Some((nominal, (mapped + mapped.transpose()) * 0.5))
+}
+
+/// Propagate a satellite state by `dt_seconds`; metadata is kept and a covariance is propagated by `covariance_method`
+#[cfg(feature = "python")]
+pub fn propagate_satellite(
+    satellite: &Satellite,
+    dt_seconds: f64,
+    dynamics: impl Into<Dynamics>,
+    integrator: Integrator,
+    covariance_method: CovarianceMethod,
+) -> Option<Satellite> {
+    let dynamics = dynamics.into();
+    let p = &satellite.position;
+    let v = &satellite.velocity;
+    let y0 = Vector6::new(p[0], p[1], p[2], v[0], v[1], v[2]);
+    let (y, covariance) = match (&satellite.covariance, covariance_method) {
+        (Some(cov), CovarianceMethod::Stm) => {
+            let (y, stm) = integrate_with_stm(&y0, dt_seconds, dynamics, integrator)?;
+            (y, Some(map_covariance(&stm, cov)))
+        }
+        (Some(cov), CovarianceMethod::Unscented) => {
+            let (y, mapped) = unscented_transform(&y0, cov, dt_seconds, dynamics, integrator)?;
+            (y, Some(mapped))
+        }
+        (None, _) => (integrate(&y0, dt_seconds, dynamics, integrator)?, None),
+    };
+
+    Some(Satellite {
+        position: [y[0], y[1], y[2]],
+        velocity: [y[3], y[4], y[5]],
+        covariance,
+        epoch: satellite.epoch.map(|e| e.plus_seconds(dt_seconds)),
+        ..satellite.clone()
+    })
+}
+
+#[cfg(feature = "python")]
+fn propagate_checked(
+    satellite: &Satellite,
+    dt_seconds: f64,
+    dynamics: impl Into<Dynamics>,
+    integrator: Integrator,
+    covariance_method: CovarianceMethod,
+) -> PyResult<Satellite> {
+    propagate_satellite(satellite, dt_seconds, dynamics, integrator, covariance_method).ok_or_else(|| {
+        OrbitError::Propagation(format!(
+            "Propagation of object {} failed (singular or diverging state)",
+            satellite.norad_id
+        ))
+        .into()
+    })
+}
+
+/// Propagate under point-mass gravity (method: "dp45" or "rk4"); a covariance is propagated too
+///
+/// `covariance_method` is "stm" (linear mapping, default) or "unscented"
+/// (sigma points through the nonlinear propagator), here and in the other
+/// `propagate_*` functions.
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(signature = (satellite, dt_seconds, method = "dp45", step = 30.0, covariance_method = "stm"))]
+pub fn propagate_two_body(
+    py: Python<'_>,
+    satellite: &Satellite,
+    dt_seconds: f64,
+    method: &str,
+    step: f64,
+    covariance_method: &str,
+) -> PyResult<Satellite> {
+    let integrator = Integrator::parse(method, step)?;
+    let covariance_method = CovarianceMethod::parse(covariance_method)?;
+    py.allow_threads(|| propagate_checked(satellite, dt_seconds, ForceModel::TwoBody, integrator, covariance_method))
+}
+
+/// Propagate under point-mass gravity plus J2 (method: "dp45" or "rk4"); a covariance is propagated too
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(signature = (satellite, dt_seconds, method = "dp45", step = 30.0, covariance_method = "stm"))]
+pub fn propagate_j2(
+    py: Python<'_>,
+    satellite: &Satellite,
+    dt_seconds: f64,
+    method: &str,
+    step: f64,
+    covariance_method: &str,
+) -> PyResult<Satellite> {
+    let integrator = Integrator::parse(method, step)?;
+    let covariance_method = CovarianceMethod::parse(covariance_method)?;
+    py.allow_threads(|| propagate_checked(satellite, dt_seconds, ForceModel::J2, integrator, covariance_method))
+}
+
+/// Propagate with atmospheric drag on top of point-mass gravity (plus J2 by default)
+///
+/// `ballistic_coefficient` is Cd * A / m in m^2/kg. `atmosphere` is
+/// "exponential" or "harris-priester" (which needs the satellite epoch to
+/// place the diurnal bulge); `space_weather` defaults to mean conditions.
+/// A covariance is propagated too, including the drag partials.
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(signature = (
+    satellite,
+    dt_seconds,
+    ballistic_coefficient,
+    atmosphere = "exponential",
+    space_weather = None,
+    j2 = true,
+    method = "dp45",
+    step = 30.0,
+    covariance_method = "stm"
+))]
+#[allow(clippy::too_many_arguments)]
+pub fn propagate_with_drag(
+    py: Python<'_>,
+    satellite: &Satellite,
+    dt_seconds: f64,
+    ballistic_coefficient: f64,
+    atmosphere: &str,
+    space_weather: Option<SpaceWeather>,
+    j2: bool,
+    method: &str,
+    step: f64,
+    covariance_method: &str,
+) -> PyResult<Satellite> {
+    let integrator = Integrator::parse(method, step)?;
+    let covariance_method = CovarianceMethod::parse(covariance_method)?;
+    let dynamics = Dynamics {
+        drag: Some(drag_model(satellite, ballistic_coefficient, atmosphere, space_weather)?),
+        ..Dynamics::from(if j2 { ForceModel::J2 } else { ForceModel::TwoBody })
+    };
+    py.allow_threads(|| propagate_checked(satellite, dt_seconds, dynamics, integrator, covariance_method))
+}
+
+#[cfg(feature = "python")]
+fn drag_model(
+    satellite: &Satellite,
+    ballistic_coefficient: f64,
+    atmosphere: &str,
+    space_weather: Option<SpaceWeather>,
+) -> PyResult<Drag> {
+    if !(ballistic_coefficient >= 0.0 && ballistic_coefficient.is_finite()) {
+        return Err(pyo3::exceptions::PyValueError::new_err("ballistic_coefficient must be non-negative"));
+    }
+    let atmosphere = Atmosphere::parse(atmosphere, space_weather, satellite.epoch.map(|e| e.jd_utc()))?;
+    Ok(Drag { ballistic_coefficient, atmosphere })
+}
+
+/// Propagate with any combination of perturbations on top of point-mass gravity
+///
+/// `sun` and `moon` add third-body gravity and `srp_area_to_mass`
+/// (C_R * A / m, m^2/kg) solar radiation pressure; these need the satellite
+/// epoch. `ballistic_coefficient` adds drag as in `propagate_with_drag`.
+/// A covariance is propagated too (radiation pressure partials are neglected).
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(signature = (
+    satellite,
+    dt_seconds,
+    j2 = true,
+    sun = false,
+    moon = false,
+    srp_area_to_mass = None,
+    ballistic_coefficient = None,
+    atmosphere = "exponential",
+    space_weather = None,
+    method = "dp45",
+    step = 30.0,
+    covariance_method = "stm"
+))]
+#[allow(clippy::too_many_arguments)]
+pub fn propagate_perturbed(
+    py: Python<'_>,
+    satellite: &Satellite,
+    dt_seconds: f64,
+    j2: bool,
+    sun: bool,
+    moon: bool,
+    srp_area_to_mass: Option<f64>,
+    ballistic_coefficient: Option<f64>,
+    atmosphere: &str,
+    space_weather: Option<SpaceWeather>,
+    method: &str,
+    step: f64,
+    covariance_method: &str,
+) -> PyResult<Satellite> {
+    let integrator = Integrator::parse(method, step)?;
+    let covariance_method = CovarianceMethod::parse(covariance_method)?;
+    let epoch_jd = || {
+        satellite.epoch.map(|e| e.jd_utc()).ok_or_else(|| {
+            OrbitError::InvalidState("Third-body and radiation pressure forces need the satellite epoch".to_string())
+        })
+    };
+    let third_body = if sun || moon { Some(ThirdBody { epoch_jd: epoch_jd()?, sun, moon }) } else { None };
+    let radiation_pressure = match srp_area_to_mass {
+        Some(area_to_mass) if !(area_to_mass >= 0.0 && area_to_mass.is_finite()) => {
+            return Err(pyo3::exceptions::PyValueError::new_err("srp_area_to_mass must be non-negative"));
+        }
+        Some(area_to_mass) => Some(RadiationPressure { epoch_jd: epoch_jd()?, reflectivity_area_to_mass: area_to_mass }),
+        None => None,
+    };
+    let drag = ballistic_coefficient
+        .map(|coefficient| drag_model(satellite, coefficient, atmosphere, space_weather))
+        .transpose()?;
+    let dynamics = Dynamics {
+        gravity: if j2 { ForceModel::J2 } else { ForceModel::TwoBody },
+        drag,
+        third_body,
+        radiation_pressure,
+    };
+    py.allow_threads(|| propagate_checked(satellite, dt_seconds, dynamics, integrator, covariance_method))
+}
+
+/// 6x6 state transition matrix d(state at t + dt) / d(state at t)
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(signature = (satellite, dt_seconds, j2 = true, method = "dp45", step = 30.0))]
+pub fn state_transition_matrix(
+    py: Python<'_>,
+    satellite: &Satellite,
+    dt_seconds: f64,
+    j2: bool,
+    method: &str,
+    step: f64,
+) -> PyResult<Vec<Vec<f64>>> {
+    let integrator = Integrator::parse(method, step)?;
+    let model = if j2 { ForceModel::J2 } else { ForceModel::TwoBody };
+    let p = &satellite.position;
+    let v = &satellite.velocity;
+    let y0 = Vector6::new(p[0], p[1], p[2], v[0], v[1], v[2]);
+    let (_, stm) = py.allow_threads(|| integrate_with_stm(&y0, dt_seconds, model, integrator)).ok_or_else(|| {
+        OrbitError::Propagation(format!(
+            "Propagation of object {} failed (singular or diverging state)",
+            satellite.norad_id
+        ))
+    })?;
+    Ok(crate::covariance::to_rows(&stm))
+}
+
+/// Epochs of a multi-epoch batch: `Epoch` objects or Julian dates (UTC)
+/// One covariance method for every object, or one per object
+#[cfg(feature = "python")]
+#[derive(FromPyObject)]
+enum CovarianceMethods {
+    All(String),
+    PerObject(Vec<String>),
+}
+
+#[cfg(feature = "python")]
+#[derive(FromPyObject)]
+enum BatchEpochs {
+    Epochs(Vec<Epoch>),
+    Jd(Vec<f64>),
+}
+
+/// Propagate a whole catalog in parallel
+///
+/// With a number of seconds every `Satellite` is advanced by it and a list
+/// of satellites is returned. With a sequence of epochs (`Epoch` objects or
+/// Julian dates, UTC) each object - a `Satellite` with an epoch, an
+/// `Sgp4Propagator`, an `Ephemeris` or a `(line1, line2)` TLE - is sampled
+/// at every epoch, and the result is a read-only array of shape
+/// (satellites, epochs, 6) holding position (km) and velocity (km/s);
+/// `numpy.asarray` views it without copying. Samples where propagation
+/// fails are NaN. `j2`, `method` and `step` apply to state vectors only.
+///
+/// `covariance_method` ("stm" or "unscented") applies when advancing by
+/// seconds; a list gives one method per satellite, for example the
+/// unscented transform for highly eccentric objects only.
+///
+/// With `validate=True` (default) a NaN/infinite or zero-norm state raises
+/// `InvalidStateError` before any propagation.
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(signature = (satellites, times, j2 = true, method = "dp45", step = 30.0, validate = true, covariance_method = None))]
+#[allow(clippy::too_many_arguments)]
+pub fn propagate_batch(
+    py: Python<'_>,
+    satellites: &PyAny,
+    times: &PyAny,
+    j2: bool,
+    method: &str,
+    step: f64,
+    validate: bool,
+    covariance_method: Option<&PyAny>,
+) -> PyResult<PyObject> {
+    let integrator = Integrator::parse(method, step)?;
+    let model = if j2 { ForceModel::J2 } else { ForceModel::TwoBody };
+    if let Ok(dt_seconds) = times.extract::<f64>() {
+        let satellites: Vec<Satellite> = satellites.extract()?;
+        if validate {
+            check_states(&satellites)?;
+        }
+        let methods: Vec<CovarianceMethod> = match covariance_method.map(|m| m.extract::<CovarianceMethods>()).transpose()? {
+            None => vec![CovarianceMethod::Stm; satellites.len()],
+            Some(CovarianceMethods::All(method)) => vec![CovarianceMethod::parse(&method)?; satellites.len()],
+            Some(CovarianceMethods::PerObject(methods)) if methods.len() == satellites.len() => {
+                methods.iter().map(|m| CovarianceMethod::parse(m)).collect::<PyResult<_>>()?
+            }
+            Some(CovarianceMethods::PerObject(_)) => {
+                return Err(pyo3::exceptions::PyValueError::new_err(
+                    "covariance_method must be one method or one per satellite",
+                ))
+            }
+        };
+        let propagated: Vec<Satellite> = py.allow_threads(|| {
+            parallel::install(|| {
+                satellites
+                    .par_iter()
+                    .zip(&methods)
+                    .map(|(sat, &covariance_method)| propagate_checked(sat, dt_seconds, model, integrator, covariance_method))
+                    .collect::<PyResult<_>>()
+            })
+        })?;
+        return Ok(propagated.into_py(py));
+    }
+
+    let epochs: Vec<f64> = match times.extract::<BatchEpochs>().map_err(|_| {
+        pyo3::exceptions::PyTypeError::new_err("times must be seconds or a sequence of Epoch objects / Julian dates")
+    })? {
+        BatchEpochs::Epochs(epochs) => epochs.iter().map(Epoch::jd_utc).collect(),
+        BatchEpochs::Jd(jd) => jd,
+    };
+    let targets: Vec<TrackTarget> = satellites.extract()?;
+    if validate {
+        for target in &targets {
+            if let TrackTarget::Satellite(sat) = target {
+                check_state(sat.norad_id, &sat.position, &sat.velocity, false)?;
+            }
+        }
+    }
+    let start_jd = epochs.first().copied().unwrap_or(0.0);
+    let offsets: Vec<f64> = epochs.iter().map(|jd| (jd - start_jd) * SECONDS_PER_DAY).collect();
+    // a Julian date only resolves tens of microseconds, so state vectors are
+    // integrated over offsets rounded to the millisecond (evenly spaced epochs
+    // give evenly spaced steps); the other targets are evaluated at the dates
+    let rounded: Vec<f64> = offsets.iter().map(|t| (t * 1e3).round() / 1e3).collect();
+    let rows: Vec<Vec<f64>> = py.allow_threads(|| {
+        parallel::install(|| {
+            targets
+                .into_par_iter()
+                .map(|target| {
+                    let times = if matches!(target, TrackTarget::Satellite(_)) { &rounded } else { &offsets };
+                    let states = sample_states(target, start_jd, times, model, integrator)?;
+                    Ok(states
+                        .iter()
+                        .flat_map(|state| match state {
+                            Some((r, v)) => [r.x, r.y, r.z, v.x, v.y, v.z],
+                            None => [f64::NAN; 6],
+                        })
+                        .collect())
+                })
+                .collect::<PyResult<_>>()
+        })
+    })?;
+    let shape = [rows.len(), epochs.len(), 6];
+    Ok(Py::new(py, Column::array(rows.concat(), &shape))?.into_py(py))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::density::{DensityModel, SpaceWeather};
+    use std::f64::consts::PI;
+
+    fn circular(radius: f64, inclination: f64) -> State {
+        let v = (MU_EARTH / radius).sqrt();
+        Vector6::new(radius, 0.0, 0.0, 0.0, v * inclination.cos(), v * inclination.sin())
+    }
+
+    #[test]
+    fn test_unscented_covariance_tracks_monte_carlo() {
+        use crate::random::SplitMix64;
+
+        let integrator = Integrator::DormandPrince { initial_step: 60.0 };
+        // GTO-like orbit with km-level uncertainty, propagated back to near perigee
+        let (perigee, apogee) = (6678.0, 42164.0);
+        let a = (perigee + apogee) / 2.0;
+        let y0 = Vector6::new(perigee, 0.0, 0.0, 0.0, (MU_EARTH * (2.0 / perigee - 1.0 / a)).sqrt(), 0.0);
+        let sigma = Vector6::new(5.0, 5.0, 5.0, 5e-3, 5e-3, 5e-3);
+        let covariance = Matrix6::from_diagonal(&sigma.component_mul(&sigma));
+        let dt = 0.98 * 2.0 * PI * (a.powi(3) / MU_EARTH).sqrt();
+
+        let (nominal, linear) = integrate_with_stm(&y0, dt, ForceModel::TwoBody, integrator).unwrap();
+        let linear = map_covariance(&linear, &covariance);
+        let (state, unscented) = unscented_transform(&y0, &covariance, dt, ForceModel::TwoBody, integrator).unwrap();
+        assert!((state - nominal).fixed_rows::<3>(0).norm() < 1e-3);
+
+        let samples: Vec<State> = (0..400u64)
+            .into_par_iter()
+            .map(|k| {
+                let mut rng = SplitMix64::new(7, k);
+                let offset = Vector6::from_fn(|i, _| sigma[i] * rng.normal());
+                integrate(&(y0 + offset), dt, ForceModel::TwoBody, integrator).unwrap()
+            })
+            .collect();
+        let sampled = samples.iter().map(|y| (y - nominal) * (y - nominal).transpose()).sum::<Matrix6<f64>>() / 400.0;
+        let error = |c: &Matrix6<f64>| (c - sampled).fixed_view::<3, 3>(0, 0).norm();
+        assert!(error(&unscented) < 0.7 * error(&linear), "{} {}", error(&unscented), error(&linear));
+
+        // small uncertainties stay in the linear regime, where both agree
+        let small = covariance * 1e-6;
+        let (_, stm) = integrate_with_stm(&y0, 3600.0, ForceModel::TwoBody, integrator).unwrap();
+        let (_, mapped) = unscented_transform(&y0, &small, 3600.0, ForceModel::TwoBody, integrator).unwrap();
+        let linear = map_covariance(&stm, &small);
+        assert!((mapped - linear).norm() < 1e-3 * linear.norm(), "{}", (mapped - linear).norm() / linear.norm());
+    }
+
+    #[test]
+    fn test_two_body_period_closure() {
+        let radius = 7000.0;
+        let y0 = circular(radius, 0.5);
+        let period = 2.0 * PI * (radius.powi(3) / MU_EARTH).sqrt();
+
+        for integrator in [Integrator::Rk4 { step: 5.0 }, Integrator::DormandPrince { initial_step: 30.0 }] {
+            let y = integrate(&y0, period, ForceModel::TwoBody, integrator).unwrap();
+            let position_error = (y - y0).fixed_rows::<3>(0).norm();
+            assert!(position_error < 1e-5, "{:?}: {}", integrator, position_error);
+        }
+
+        let back = integrate(&y0, -period / 3.0, ForceModel::TwoBody, Integrator::DormandPrince { initial_step: 30.0 })
+            .unwrap();
+        let forward = integrate(&back, period / 3.0, ForceModel::TwoBody, Integrator::DormandPrince { initial_step: 30.0 })
+            .unwrap();
+        assert!((forward - y0).fixed_rows::<3>(0).norm() < 1e-6);
+    }
+
+    #[test]
+    fn test_j2_nodal_regression() {
+        // Secular RAAN rate: -1.5 n J2 (R/a)^2 cos(i) for a circular orbit
+        let radius = 7000.0;
+        let inclination: f64 = 45f64.to_radians();
+        let y0 = circular(radius, inclination);
+        let dt = 86400.0;
+        let y = integrate(&y0, dt, ForceModel::J2, Integrator::DormandPrince { initial_step: 30.0 }).unwrap();
+
+        let node = |s: &State| {
+            let h = Vector3::new(s[0], s[1], s[2]).cross(&Vector3::new(s[3], s[4], s[5]));
+            h.x.atan2(-h.y)
+        };
+        let n = (MU_EARTH / radius.powi(3)).sqrt();
+        let expected = -1.5 * n * J2_EARTH * (EARTH_RADIUS_KM / radius).powi(2) * inclination.cos() * dt;
+        let drift = node(&y) - node(&y0);
+        assert!((drift - expected).abs() < 0.05 * expected.abs(), "{} vs {}", drift, expected);
+    }
+
+    #[test]
+    fn test_stm_matches_finite_differences() {
+        let y0 = circular(7000.0, 0.9);
+        let dt = 1800.0;
+        let integrator = Integrator::DormandPrince { initial_step: 30.0 };
+        let (y, stm) = integrate_with_stm(&y0, dt, ForceModel::J2, integrator).unwrap();
+        assert!((y - integrate(&y0, dt, ForceModel::J2, integrator).unwrap()).norm() < 1e-6);
+
+        for (j, delta) in [1e-3, 1e-3, 1e-3, 1e-6, 1e-6, 1e-6].into_iter().enumerate() {
+            let mut plus = y0;
+            let mut minus = y0;
+            plus[j] += delta;
+            minus[j] -= delta;
+            let column = (integrate(&plus, dt, ForceModel::J2, integrator).unwrap()
+                - integrate(&minus, dt, ForceModel::J2, integrator).unwrap())
+                / (2.0 * delta);
+            let error = (column - stm.column(j)).norm() / column.norm();
+            assert!(error < 1e-5, "column {}: relative error {}", j, error);
+        }
+    }
+
+    #[test]
+    fn test_drag_lowers_orbit_and_enters_stm() {
+        let y0 = circular(EARTH_RADIUS_KM + 300.0, 0.9);
+        let dt = 3.0 * 5400.0;
+        let integrator = Integrator::DormandPrince { initial_step: 30.0 };
+        let atmosphere = Atmosphere::new(DensityModel::Exponential, SpaceWeather::default(), None).unwrap();
+        let dynamics = Dynamics {
+            drag: Some(Drag { ballistic_coefficient: 0.02, atmosphere }),
+            ..Dynamics::from(ForceModel::TwoBody)
+        };
+        let energy = |s: &State| {
+            0.5 * s.fixed_rows::<3>(3).norm_squared() - MU_EARTH / s.fixed_rows::<3>(0).norm()
+        };
+        let y = integrate(&y0, dt, dynamics, integrator).unwrap();
+        assert!(energy(&y) < energy(&y0));
+
+        // the along-track drift from drag shows up in the velocity partials
+        let (_, stm) = integrate_with_stm(&y0, dt, dynamics, integrator).unwrap();
+        let (_, stm_vacuum) = integrate_with_stm(&y0, dt, ForceModel::TwoBody, integrator).unwrap();
+        for (j, delta) in [(4, 1e-6), (0, 1e-3)] {
+            let mut plus = y0;
+            let mut minus = y0;
+            plus[j] += delta;
+            minus[j] -= delta;
+            let column = (integrate(&plus, dt, dynamics, integrator).unwrap()
+                - integrate(&minus, dt, dynamics, integrator).unwrap())
+                / (2.0 * delta);
+            let error = (column - stm.column(j)).norm();
+            assert!(error < 1e-2 * (column - stm_vacuum.column(j)).norm(), "column {}: error {}", j, error);
+        }
+    }
+
+    #[test]
+    fn test_lunisolar_perturbation_of_geo() {
+        let radius = 42164.17;
+        let y0 = circular(radius, 0.0);
+        let epoch_jd = 2460000.5;
+        let dt = 7.0 * SECONDS_PER_DAY;
+        let integrator = Integrator::DormandPrince { initial_step: 300.0 };
+        let third_body = ThirdBody { epoch_jd, sun: true, moon: true };
+        let dynamics = Dynamics { third_body: Some(third_body), ..Dynamics::from(ForceModel::TwoBody) };
+
+        // lunisolar gravity tilts the orbit plane by about 0.85 deg/year
+        let y = integrate(&y0, dt, dynamics, integrator).unwrap();
+        let inclination = |s: &State| {
+            let h = Vector3::new(s[0], s[1], s[2]).cross(&Vector3::new(s[3], s[4], s[5]));
+            (h.z / h.norm()).acos().to_degrees()
+        };
+        let drift = inclination(&y);
+        assert!(drift > 0.005 && drift < 0.05, "{}", drift);
+
+        let gradient = third_body.gradient(0.0, &Vector3::new(radius, 0.0, 0.0));
+        for j in 0..3 {
+            let mut step = Vector3::zeros();
+            step[j] = 1.0;
+            let r = Vector3::new(radius, 0.0, 0.0);
+            let column = (third_body.acceleration(0.0, &(r + step)) - third_body.acceleration(0.0, &(r - step))) / 2.0;
+            assert!((column - gradient.column(j)).norm() < 1e-6 * gradient.norm());
+        }
+
+        // radiation pressure at 0.02 m^2/kg is 9.1e-11 km/s^2 at 1 AU (+-3.4% over the year), zero in the shadow
+        let srp = RadiationPressure { epoch_jd, reflectivity_area_to_mass: 0.02 };
+        let sun_hat = celestial::sun_position(epoch_jd).normalize();
+        let lit = srp.acceleration(0.0, &(sun_hat * radius));
+        assert!((lit.norm() / 9.12e-11 - 1.0).abs() < 0.035 && lit.dot(&sun_hat) < 0.0);
+        assert_eq!(srp.acceleration(0.0, &(-sun_hat * radius)), Vector3::zeros());
+    }
+
+    #[test]
+    fn test_gravity_gradient_matches_acceleration() {
+        let r = Vector3::new(5000.0, -3000.0, 4000.0);
+        let gradient = gravity_gradient(&r, ForceModel::J2);
+        let acceleration = |r: &Vector3<f64>| two_body_acceleration(r) + j2_acceleration(r);
+        for j in 0..3 {
+            let mut step = Vector3::zeros();
+            step[j] = 1e-2;
+            let column = (acceleration(&(r + step)) - acceleration(&(r - step))) / 2e-2;
+            assert!((column - gradient.column(j)).norm() / column.norm() < 1e-8);
+        }
+    }
+}
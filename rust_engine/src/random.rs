@@ -0,0 +1,40 @@
+//! Seeded pseudo-random numbers
+//!
+//! SplitMix64 is small, fast and statistically sound enough for sampling;
+//! separate streams derived from one seed keep parallel results independent
+//! of thread scheduling.
+
+/// SplitMix64 generator
+pub(crate) struct SplitMix64(u64);
+
+impl SplitMix64 {
+    /// Generator for `stream` under `seed`
+    pub(crate) fn new(seed: u64, stream: u64) -> Self {
+        let mut rng = SplitMix64(seed ^ stream.wrapping_mul(0xD1B5_4A32_D192_ED03));
+        rng.next_u64();
+        rng
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in (0, 1]
+    pub(crate) fn next_f64(&mut self) -> f64 {
+        ((self.next_u64() >> 11) + 1) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Uniform in [low, high)
+    pub(crate) fn uniform(&mut self, low: f64, high: f64) -> f64 {
+        high - (high - low) * self.next_f64()
+    }
+
+    /// Standard normal deviate (Box-Muller, one of the pair)
+    pub(crate) fn normal(&mut self) -> f64 {
+        (-2.0 * self.next_f64().ln()).sqrt() * (2.0 * std::f64::consts::PI * self.next_f64()).cos()
+    }
+}
@@ -0,0 +1,223 @@
+//! Linearized relative motion (Hill / Clohessy-Wiltshire)
+//!
+//! The deputy's offset from the chief is expressed in the chief's rotating
+//! RTN frame (x radial, y in-track, z cross-track) and propagated with the
+//! closed-form CW solution for a circular chief orbit of mean motion n.
+//! Accurate while the separation is small compared with the orbit radius
+//! and the chief's eccentricity is small; no perturbations are modelled.
+//!
+//! Relative trajectories sample both objects and express the exact offset
+//! in the chief's rotating frame at every step, either RTN or LVLH in the
+//! CCSDS convention (x along V-bar, y opposite the orbit normal, z towards
+//! nadir along R-bar) that rendezvous analyses use.
+
+use nalgebra::{Matrix3, Matrix6, Vector3, Vector6};
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+#[cfg(feature = "python")]
+use pyo3::types::PyDict;
+
+use crate::constants::MU_EARTH;
+use crate::frames;
+#[cfg(feature = "python")]
+use crate::columnar::Column;
+#[cfg(feature = "python")]
+use crate::ground_track::{sample_states, TrackTarget};
+#[cfg(feature = "python")]
+use crate::propagation::{ForceModel, Integrator};
+#[cfg(feature = "python")]
+use crate::Satellite;
+#[cfg(feature = "python")]
+use crate::constants::{PROPAGATOR_INITIAL_STEP_S, SECONDS_PER_DAY};
+
+/// CW state transition matrix for relative state (x, y, z, vx, vy, vz) over `t` seconds
+pub fn cw_transition(n: f64, t: f64) -> Matrix6<f64> {
+    let (s, c) = (n * t).sin_cos();
+    let nt = n * t;
+    #[rustfmt::skip]
+    let stm = Matrix6::new(
+        4.0 - 3.0 * c,        0.0, 0.0,     s / n,               2.0 * (1.0 - c) / n,       0.0,
+        6.0 * (s - nt),       1.0, 0.0,     -2.0 * (1.0 - c) / n, (4.0 * s - 3.0 * nt) / n, 0.0,
+        0.0,                  0.0, c,       0.0,                 0.0,                       s / n,
+        3.0 * n * s,          0.0, 0.0,     c,                   2.0 * s,                   0.0,
+        -6.0 * n * (1.0 - c), 0.0, 0.0,     -2.0 * s,            4.0 * c - 3.0,             0.0,
+        0.0,                  0.0, -n * s,  0.0,                 0.0,                       c,
+    );
+    stm
+}
+
+/// Relative state of the deputy in the chief's rotating RTN frame, and the chief's mean motion
+pub fn relative_state(
+    chief: (&Vector3<f64>, &Vector3<f64>),
+    deputy: (&Vector3<f64>, &Vector3<f64>),
+) -> Option<(Vector6<f64>, f64)> {
+    let (rc, vc) = chief;
+    let energy = 0.5 * vc.norm_squared() - MU_EARTH / rc.norm();
+    if energy >= 0.0 {
+        return None;
+    }
+    let a = -MU_EARTH / (2.0 * energy);
+    let n = (MU_EARTH / (a * a * a)).sqrt();
+    Some((rotating_rtn_state(chief, deputy)?, n))
+}
+
+/// Deputy position and velocity relative to the chief in its rotating RTN frame
+///
+/// The velocity is measured in the rotating frame. None when the chief has
+/// no angular momentum.
+pub fn rotating_rtn_state(chief: (&Vector3<f64>, &Vector3<f64>), deputy: (&Vector3<f64>, &Vector3<f64>)) -> Option<Vector6<f64>> {
+    let (rc, vc) = chief;
+    let [radial, in_track, cross_track] = frames::rtn_basis(rc, vc)?;
+    let to_rtn = Matrix3::from_rows(&[radial.transpose(), in_track.transpose(), cross_track.transpose()]);
+
+    // the frame rotates at h / r^2 about the cross-track axis
+    let omega = Vector3::new(0.0, 0.0, rc.cross(vc).norm() / rc.norm_squared());
+    let rho = to_rtn * (deputy.0 - rc);
+    let rho_dot = to_rtn * (deputy.1 - vc) - omega.cross(&rho);
+    Some(Vector6::new(rho.x, rho.y, rho.z, rho_dot.x, rho_dot.y, rho_dot.z))
+}
+
+/// RTN relative state re-expressed in LVLH: (in-track, -cross-track, -radial)
+pub fn rtn_to_lvlh(state: &Vector6<f64>) -> Vector6<f64> {
+    Vector6::new(state[1], -state[2], -state[0], state[4], -state[5], -state[3])
+}
+
+/// Propagate the deputy relative to the chief by `dt` seconds with the CW equations
+///
+/// Both objects are inertial states at the same instant. Returns the
+/// deputy's relative position (km) and velocity (km/s) in the chief's RTN
+/// frame after `dt`, the velocity being measured in the rotating frame.
+#[cfg(feature = "python")]
+#[pyfunction]
+pub fn relative_propagate(chief: &Satellite, deputy: &Satellite, dt: f64) -> PyResult<(Vec<f64>, Vec<f64>)> {
+    let rc = chief.r();
+    let vc = chief.v();
+    let rd = deputy.r();
+    let vd = deputy.v();
+    let (state, n) = relative_state((&rc, &vc), (&rd, &vd))
+        .ok_or_else(|| pyo3::exceptions::PyValueError::new_err("Chief must be on a bound, non-degenerate orbit"))?;
+    let propagated = cw_transition(n, dt) * state;
+    Ok((
+        vec![propagated[0], propagated[1], propagated[2]],
+        vec![propagated[3], propagated[4], propagated[5]],
+    ))
+}
+
+/// Deputy's position and velocity in the chief's rotating frame over a time window
+///
+/// Chief and deputy are each a `Satellite` with an epoch, an
+/// `Sgp4Propagator`, an `Ephemeris` or a `(line1, line2)` TLE, sampled every
+/// `step` seconds between two Julian dates. `frame` is "lvlh" (default: x
+/// V-bar, y opposite the orbit normal, z R-bar towards nadir) or "rtn".
+/// Returns a dict of columns: `jd`, `position` and `velocity` (n x 3, km
+/// and km/s in the rotating frame) and `range_km`; NaN where propagation fails.
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(signature = (chief, deputy, start, end, step = 60.0, frame = "lvlh"))]
+pub(crate) fn relative_trajectory(
+    py: Python<'_>,
+    chief: TrackTarget,
+    deputy: TrackTarget,
+    start: f64,
+    end: f64,
+    step: f64,
+    frame: &str,
+) -> PyResult<PyObject> {
+    let lvlh = match frame.to_ascii_lowercase().as_str() {
+        "lvlh" => true,
+        "rtn" => false,
+        _ => return Err(pyo3::exceptions::PyValueError::new_err(format!("Unknown frame '{}' (expected lvlh or rtn)", frame))),
+    };
+    if end <= start {
+        return Err(pyo3::exceptions::PyValueError::new_err("end must be after start"));
+    }
+    if step <= 0.0 {
+        return Err(pyo3::exceptions::PyValueError::new_err("step must be positive (seconds)"));
+    }
+    let duration = (end - start) * SECONDS_PER_DAY;
+    let n_steps = (duration / step).ceil() as usize;
+    let times: Vec<f64> = (0..=n_steps).map(|k| (k as f64 * step).min(duration)).collect();
+    let integrator = Integrator::DormandPrince { initial_step: PROPAGATOR_INITIAL_STEP_S };
+    let (chief_states, deputy_states) = py.allow_threads(|| {
+        rayon::join(
+            || sample_states(chief, start, &times, ForceModel::J2, integrator),
+            || sample_states(deputy, start, &times, ForceModel::J2, integrator),
+        )
+    });
+
+    let (mut positions, mut velocities, mut ranges) = (Vec::new(), Vec::new(), Vec::new());
+    for (c, d) in chief_states?.iter().zip(&deputy_states?) {
+        let state = match (c, d) {
+            (Some((rc, vc)), Some((rd, vd))) => rotating_rtn_state((rc, vc), (rd, vd)),
+            _ => None,
+        };
+        let state = state.map_or(Vector6::repeat(f64::NAN), |s| if lvlh { rtn_to_lvlh(&s) } else { s });
+        positions.extend_from_slice(&[state[0], state[1], state[2]]);
+        velocities.extend_from_slice(&[state[3], state[4], state[5]]);
+        ranges.push(state.fixed_rows::<3>(0).norm());
+    }
+
+    let table = PyDict::new(py);
+    let jd: Vec<f64> = times.iter().map(|t| start + t / SECONDS_PER_DAY).collect();
+    table.set_item("jd", Py::new(py, Column::float64(jd))?)?;
+    table.set_item("position", Py::new(py, Column::matrix(positions, times.len(), 3))?)?;
+    table.set_item("velocity", Py::new(py, Column::matrix(velocities, times.len(), 3))?)?;
+    table.set_item("range_km", Py::new(py, Column::float64(ranges))?)?;
+    Ok(table.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::propagation::{integrate, ForceModel, Integrator};
+    use std::f64::consts::PI;
+
+    #[test]
+    fn test_cw_matches_two_body_for_close_deputy() {
+        let radius = 7000.0;
+        let speed = (MU_EARTH / radius).sqrt();
+        let (rc, vc) = (Vector3::new(radius, 0.0, 0.0), Vector3::new(0.0, speed, 0.0));
+        // deputy 1 km radially out, 0.5 km cross-track, drifting slowly
+        let (rd, vd) = (Vector3::new(radius + 1.0, 0.0, 0.5), Vector3::new(0.0, speed - 0.0005, 0.0002));
+        let (state, n) = relative_state((&rc, &vc), (&rd, &vd)).unwrap();
+
+        let dt = 1500.0;
+        let predicted = cw_transition(n, dt) * state;
+        let integrator = Integrator::DormandPrince { initial_step: 30.0 };
+        let chief = integrate(&Vector6::new(rc.x, rc.y, rc.z, vc.x, vc.y, vc.z), dt, ForceModel::TwoBody, integrator).unwrap();
+        let deputy = integrate(&Vector6::new(rd.x, rd.y, rd.z, vd.x, vd.y, vd.z), dt, ForceModel::TwoBody, integrator).unwrap();
+        let (truth, _) = relative_state(
+            (&chief.fixed_rows::<3>(0).into_owned(), &chief.fixed_rows::<3>(3).into_owned()),
+            (&deputy.fixed_rows::<3>(0).into_owned(), &deputy.fixed_rows::<3>(3).into_owned()),
+        )
+        .unwrap();
+        // linearization error is second order in separation / radius
+        assert!((predicted.fixed_rows::<3>(0) - truth.fixed_rows::<3>(0)).norm() < 0.01);
+    }
+
+    #[test]
+    fn test_no_drift_ellipse_closes() {
+        let n = (MU_EARTH / 7000f64.powi(3)).sqrt();
+        // vy = -2 n x removes the secular in-track drift
+        let state = Vector6::new(1.0, 0.0, 0.0, 0.0, -2.0 * n, 0.0);
+        let after = cw_transition(n, 2.0 * PI / n) * state;
+        assert!((after - state).norm() < 1e-9);
+        // the identity at t = 0
+        assert!((cw_transition(n, 0.0) - Matrix6::identity()).norm() < 1e-15);
+    }
+
+    #[test]
+    fn test_lvlh_axes() {
+        let radius = 7000.0;
+        let speed = (MU_EARTH / radius).sqrt();
+        let chief = (Vector3::new(radius, 0.0, 0.0), Vector3::new(0.0, speed, 0.0));
+        // deputy ahead along V-bar, 2 km above (R-bar is nadir) and 3 km along +h
+        let deputy = (Vector3::new(radius + 2.0, 1.0, 3.0), chief.1);
+        let rtn = rotating_rtn_state((&chief.0, &chief.1), (&deputy.0, &deputy.1)).unwrap();
+        let lvlh = rtn_to_lvlh(&rtn);
+        assert!((lvlh.fixed_rows::<3>(0) - Vector3::new(1.0, -3.0, -2.0)).norm() < 1e-12);
+        // a co-moving offset appears to move against the frame rotation
+        let omega = speed / radius;
+        assert!((lvlh[3] + 2.0 * omega).abs() < 1e-12 && lvlh[4] == 0.0 && (lvlh[5] + omega).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,408 @@
+//! Human-readable conjunction reports (HTML or Markdown)
+//!
+//! A screening run is rendered as a summary, a table of the closest events
+//! and one section per asset, the object 1 of its events (the primary in a
+//! primary screen). Each asset section has an SVG sketch of its encounters
+//! in the B-plane: every miss vector at (B.T, B.R) of its own encounter,
+//! with the screening threshold drawn as a circle when it is known. HTML
+//! is a standalone page; Markdown carries the SVG as inline HTML, which
+//! common renderers pass through.
+
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::cdm::escape_xml;
+use crate::epoch::{self, MJD_OFFSET};
+#[cfg(feature = "python")]
+use crate::epoch::now_mjd_utc;
+use crate::Conjunction;
+
+/// Side of the B-plane sketches (px)
+const SKETCH_SIZE: f64 = 240.0;
+
+/// Output markup
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReportFormat {
+    Html,
+    Markdown,
+}
+
+impl std::str::FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "html" => Ok(ReportFormat::Html),
+            "markdown" | "md" => Ok(ReportFormat::Markdown),
+            _ => Err(format!("Unknown report format '{}' (expected 'html' or 'markdown')", s)),
+        }
+    }
+}
+
+/// What goes into a report besides the events
+#[derive(Clone, Debug)]
+pub struct ReportOptions {
+    pub title: String,
+    /// Rows of the top events table
+    pub top: usize,
+    /// Screening threshold, drawn in the sketches
+    pub threshold_km: Option<f64>,
+    /// Object names by NORAD id
+    pub names: HashMap<i32, String>,
+    /// Generation time (MJD, UTC) stated under the title
+    pub generated_mjd: Option<f64>,
+}
+
+impl Default for ReportOptions {
+    fn default() -> Self {
+        ReportOptions {
+            title: "Conjunction report".to_string(),
+            top: 20,
+            threshold_km: None,
+            names: HashMap::new(),
+            generated_mjd: None,
+        }
+    }
+}
+
+/// Document being written in one of the formats
+struct Writer {
+    format: ReportFormat,
+    body: String,
+}
+
+impl Writer {
+    fn text(&self, text: &str) -> String {
+        match self.format {
+            ReportFormat::Html => escape_xml(text),
+            ReportFormat::Markdown => escape_xml(text).replace('|', "\\|").replace('*', "\\*").replace('_', "\\_"),
+        }
+    }
+
+    fn heading(&mut self, level: usize, text: &str) {
+        let text = self.text(text);
+        self.body.push_str(&match self.format {
+            ReportFormat::Html => format!("<h{0}>{1}</h{0}>\n", level, text),
+            ReportFormat::Markdown => format!("{} {}\n\n", "#".repeat(level), text),
+        });
+    }
+
+    fn paragraph(&mut self, text: &str) {
+        let text = self.text(text);
+        self.body.push_str(&match self.format {
+            ReportFormat::Html => format!("<p>{}</p>\n", text),
+            ReportFormat::Markdown => format!("{}\n\n", text),
+        });
+    }
+
+    /// Table with the first `left` columns left-aligned and the rest right-aligned
+    fn table(&mut self, header: &[&str], left: usize, rows: &[Vec<String>]) {
+        let align = |k: usize| if k < left { "left" } else { "right" };
+        match self.format {
+            ReportFormat::Html => {
+                self.body.push_str("<table>\n<tr>");
+                for (k, cell) in header.iter().enumerate() {
+                    let cell = format!("<th style=\"text-align:{}\">{}</th>", align(k), self.text(cell));
+                    self.body.push_str(&cell);
+                }
+                self.body.push_str("</tr>\n");
+                for row in rows {
+                    self.body.push_str("<tr>");
+                    for (k, cell) in row.iter().enumerate() {
+                        let cell = format!("<td style=\"text-align:{}\">{}</td>", align(k), self.text(cell));
+                        self.body.push_str(&cell);
+                    }
+                    self.body.push_str("</tr>\n");
+                }
+                self.body.push_str("</table>\n");
+            }
+            ReportFormat::Markdown => {
+                let columns = header.len();
+                let line = |cells: Vec<String>| format!("| {} |\n", cells.join(" | "));
+                let header = line(header.iter().map(|cell| self.text(cell)).collect());
+                let rule = line((0..columns).map(|k| if k < left { ":--" } else { "--:" }.to_string()).collect());
+                self.body.push_str(&header);
+                self.body.push_str(&rule);
+                for row in rows {
+                    self.body.push_str(&line(row.iter().map(|cell| self.text(cell)).collect()));
+                }
+                self.body.push('\n');
+            }
+        }
+    }
+
+    fn svg(&mut self, svg: &str) {
+        self.body.push_str(svg);
+        self.body.push_str(if self.format == ReportFormat::Markdown { "\n\n" } else { "\n" });
+    }
+
+    fn finish(self, title: &str) -> String {
+        match self.format {
+            ReportFormat::Html => format!(
+                "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>\n\
+                 body {{ font-family: sans-serif; margin: 2em; }}\n\
+                 table {{ border-collapse: collapse; margin-bottom: 1em; }}\n\
+                 th, td {{ border: 1px solid #ccc; padding: 0.2em 0.6em; }}\n\
+                 </style>\n</head>\n<body>\n{}</body>\n</html>\n",
+                escape_xml(title),
+                self.body
+            ),
+            ReportFormat::Markdown => self.body,
+        }
+    }
+}
+
+/// NORAD id, then the name and the international designator when known
+fn object_label(norad_id: i32, designator: Option<&str>, names: &HashMap<i32, String>) -> String {
+    let mut label = match names.get(&norad_id) {
+        Some(name) => format!("{} {}", norad_id, name),
+        None => norad_id.to_string(),
+    };
+    if let Some(designator) = designator {
+        label.push_str(&format!(" ({})", designator));
+    }
+    label
+}
+
+/// TCA as an ISO timestamp to the millisecond, or a dash for untimed events
+fn tca_label(conjunction: &Conjunction) -> String {
+    conjunction.tca_jd.map_or_else(|| "-".to_string(), |jd| format!("{}Z", &epoch::format_iso(jd - MJD_OFFSET)[..23]))
+}
+
+fn km(value: f64) -> String {
+    if value.is_finite() {
+        format!("{:.3}", value)
+    } else {
+        "-".to_string()
+    }
+}
+
+fn probability_label(conjunction: &Conjunction) -> String {
+    conjunction.probability.map_or_else(|| "-".to_string(), |p| format!("{:.2e}", p))
+}
+
+/// B-plane sketch of one asset's encounters, or None when no event has B-plane coordinates
+fn sketch(events: &[&Conjunction], threshold_km: Option<f64>) -> Option<String> {
+    let points: Vec<(i32, [f64; 2])> =
+        events.iter().filter_map(|c| c.b_plane().map(|(_, b)| (c.norad_id_2, b))).collect();
+    if points.is_empty() {
+        return None;
+    }
+    let furthest = points.iter().map(|(_, [t, r])| t.hypot(*r)).fold(0.0, f64::max);
+    let extent = 1.15 * furthest.max(threshold_km.unwrap_or(0.0)).max(1e-3);
+    let half = SKETCH_SIZE / 2.0;
+    let scale = (half - 20.0) / extent;
+    let (x, y) = (|t: f64| half + t * scale, |r: f64| half - r * scale);
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{0}\" viewBox=\"0 0 {0} {0}\" \
+         font-family=\"sans-serif\" font-size=\"10\">\n",
+        SKETCH_SIZE
+    );
+    svg.push_str(&format!("<rect width=\"{0}\" height=\"{0}\" fill=\"white\" stroke=\"#ccc\"/>\n", SKETCH_SIZE));
+    svg.push_str(&format!(
+        "<line x1=\"10\" y1=\"{0}\" x2=\"{1}\" y2=\"{0}\" stroke=\"#999\"/>\
+         <line x1=\"{0}\" y1=\"10\" x2=\"{0}\" y2=\"{1}\" stroke=\"#999\"/>\n",
+        half,
+        SKETCH_SIZE - 10.0
+    ));
+    svg.push_str(&format!(
+        "<text x=\"{}\" y=\"{}\" text-anchor=\"end\">B.T</text><text x=\"{}\" y=\"18\">B.R</text>\n",
+        SKETCH_SIZE - 12.0,
+        half - 4.0,
+        half + 4.0
+    ));
+    if let Some(threshold) = threshold_km {
+        svg.push_str(&format!(
+            "<circle cx=\"{0}\" cy=\"{0}\" r=\"{1:.1}\" fill=\"none\" stroke=\"#c33\" stroke-dasharray=\"4 3\">\
+             <title>threshold {2} km</title></circle>\n",
+            half,
+            threshold * scale,
+            threshold
+        ));
+    }
+    for (norad_id, [t, r]) in &points {
+        svg.push_str(&format!(
+            "<circle cx=\"{0:.1}\" cy=\"{1:.1}\" r=\"3\" fill=\"#236\"><title>{2}: B.T {3:.3} km, B.R {4:.3} km</title>\
+             </circle><text x=\"{5:.1}\" y=\"{6:.1}\">{2}</text>\n",
+            x(*t),
+            y(*r),
+            norad_id,
+            t,
+            r,
+            x(*t) + 5.0,
+            y(*r) - 5.0
+        ));
+    }
+    svg.push_str(&format!("<text x=\"10\" y=\"{}\">scale: {:.3} km</text>\n</svg>", SKETCH_SIZE - 8.0, extent));
+    Some(svg)
+}
+
+/// Render `conjunctions` as a report
+///
+/// The top table ranks events by miss distance; asset sections list their
+/// events by TCA. Events without a TCA are tabulated but sort last.
+pub fn render(conjunctions: &[Conjunction], format: ReportFormat, options: &ReportOptions) -> String {
+    let names = &options.names;
+    let mut writer = Writer { format, body: String::new() };
+    writer.heading(1, &options.title);
+    if let Some(mjd) = options.generated_mjd {
+        writer.paragraph(&format!("Generated {}Z", &epoch::format_iso(mjd)[..19]));
+    }
+
+    let mut objects: Vec<i32> = conjunctions.iter().flat_map(|c| [c.norad_id_1, c.norad_id_2]).collect();
+    objects.sort_unstable();
+    objects.dedup();
+    let mut by_distance: Vec<&Conjunction> = conjunctions.iter().collect();
+    by_distance.sort_by(|a, b| a.distance_km.total_cmp(&b.distance_km));
+    let tcas = || conjunctions.iter().filter_map(|c| c.tca_jd);
+    let mut summary = format!("{} conjunctions involving {} objects", conjunctions.len(), objects.len());
+    if let (Some(first), Some(last)) = (tcas().min_by(f64::total_cmp), tcas().max_by(f64::total_cmp)) {
+        let (first, last) = (first - MJD_OFFSET, last - MJD_OFFSET);
+        summary.push_str(&format!(
+            ", TCAs from {}Z to {}Z",
+            &epoch::format_iso(first)[..19],
+            &epoch::format_iso(last)[..19]
+        ));
+    }
+    if let Some(threshold) = options.threshold_km {
+        summary.push_str(&format!(" (threshold {} km)", threshold));
+    }
+    summary.push('.');
+    if let Some(closest) = by_distance.first() {
+        summary.push_str(&format!(
+            " Closest approach: {} km between {} and {}.",
+            km(closest.distance_km),
+            object_label(closest.norad_id_1, closest.international_designator_1.as_deref(), names),
+            object_label(closest.norad_id_2, closest.international_designator_2.as_deref(), names)
+        ));
+    }
+    writer.heading(2, "Summary");
+    writer.paragraph(&summary);
+
+    let event_row = |rank: Option<usize>, c: &Conjunction| {
+        let mut row: Vec<String> = rank.map(|k| k.to_string()).into_iter().collect();
+        if rank.is_some() {
+            row.push(object_label(c.norad_id_1, c.international_designator_1.as_deref(), names));
+        }
+        row.extend([
+            object_label(c.norad_id_2, c.international_designator_2.as_deref(), names),
+            tca_label(c),
+            km(c.distance_km),
+            km(c.radial_km),
+            km(c.in_track_km),
+            km(c.cross_track_km),
+            km(c.relative_velocity_km_s),
+            probability_label(c),
+        ]);
+        row
+    };
+    const COLUMNS: [&str; 8] =
+        ["Secondary", "TCA (UTC)", "Miss (km)", "Radial (km)", "In-track (km)", "Cross-track (km)", "Speed (km/s)", "Pc"];
+
+    let top: Vec<Vec<String>> =
+        by_distance.iter().take(options.top).enumerate().map(|(k, c)| event_row(Some(k + 1), c)).collect();
+    writer.heading(2, &format!("Top {} events", top.len()));
+    if top.is_empty() {
+        writer.paragraph("No conjunctions.");
+    } else {
+        let header: Vec<&str> = ["#", "Primary"].into_iter().chain(COLUMNS).collect();
+        writer.table(&header, 4, &top);
+    }
+
+    let mut assets: BTreeMap<i32, Vec<&Conjunction>> = BTreeMap::new();
+    for conjunction in conjunctions {
+        assets.entry(conjunction.norad_id_1).or_default().push(conjunction);
+    }
+    for (asset, mut events) in assets {
+        events.sort_by(|a, b| a.tca_jd.unwrap_or(f64::INFINITY).total_cmp(&b.tca_jd.unwrap_or(f64::INFINITY)));
+        let designator = events.iter().find_map(|c| c.international_designator_1.as_deref());
+        writer.heading(2, &format!("Asset {}", object_label(asset, designator, names)));
+        let closest = events.iter().map(|c| c.distance_km).fold(f64::INFINITY, f64::min);
+        writer.paragraph(&format!("{} conjunctions, closest {} km.", events.len(), km(closest)));
+        let rows: Vec<Vec<String>> = events.iter().map(|c| event_row(None, c)).collect();
+        writer.table(&COLUMNS, 2, &rows);
+        if let Some(svg) = sketch(&events, options.threshold_km) {
+            writer.svg(&svg);
+        }
+    }
+    writer.finish(&options.title)
+}
+
+/// Render a screening run as an HTML or Markdown report
+///
+/// `names` maps NORAD ids to object names; `threshold_km` is drawn in the
+/// B-plane sketches. The report states its generation time (now).
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(signature = (conjunctions, format = "html", title = "Conjunction report", top = 20, threshold_km = None, names = None))]
+pub fn conjunction_report(
+    conjunctions: Vec<Conjunction>,
+    format: &str,
+    title: &str,
+    top: usize,
+    threshold_km: Option<f64>,
+    names: Option<HashMap<i32, String>>,
+) -> PyResult<String> {
+    let format = format.parse().map_err(pyo3::exceptions::PyValueError::new_err)?;
+    let options = ReportOptions {
+        title: title.to_string(),
+        top,
+        threshold_km,
+        names: names.unwrap_or_default(),
+        generated_mjd: Some(now_mjd_utc()),
+    };
+    Ok(render(&conjunctions, format, &options))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nalgebra::Vector3;
+
+    fn events() -> Vec<Conjunction> {
+        let (r1, v1) = (Vector3::new(7000.0, 0.0, 0.0), Vector3::new(0.0, 7.5, 0.0));
+        [(2, 0.5, 2460000.6), (3, 2.0, 2460000.5), (4, 1.0, 2460000.7)]
+            .into_iter()
+            .map(|(id, offset, tca)| {
+                let (r2, v2) = (Vector3::new(7000.0 + offset, 0.0, 0.1), Vector3::new(0.0, 0.0, 7.5));
+                Conjunction::from_states(1, id, (&r1, &v1), (&r2, &v2), Some(tca))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_markdown_report() {
+        let options = ReportOptions {
+            top: 2,
+            threshold_km: Some(5.0),
+            names: HashMap::from([(2, "DEB <A|B>".to_string())]),
+            ..ReportOptions::default()
+        };
+        let mut events = events();
+        events[0].international_designator_1 = Some("1998-067A".to_string());
+        events[0].international_designator_2 = Some("2024-001AB".to_string());
+        let report = render(&events, ReportFormat::Markdown, &options);
+        assert!(report.starts_with("# Conjunction report\n"));
+        assert!(report.contains("## Top 2 events"));
+        assert!(report.contains("## Asset 1 (1998-067A)"));
+        // closest first in the top table, names escaped for the table
+        let top = report.split("## Top 2 events").nth(1).unwrap();
+        let first_row = top.lines().find(|line| line.starts_with("| 1 |")).unwrap();
+        assert!(first_row.contains("2 DEB &lt;A\\|B&gt; (2024-001AB)") && first_row.contains("| 0.500 |"), "{}", first_row);
+        assert!(!top.split("## Asset").next().unwrap().contains("| 3 |"));
+        assert!(report.contains("<svg") && report.contains("threshold 5 km"));
+    }
+
+    #[test]
+    fn test_html_report_is_a_page() {
+        let report = render(&events(), ReportFormat::Html, &ReportOptions::default());
+        assert!(report.starts_with("<!DOCTYPE html>") && report.trim_end().ends_with("</html>"));
+        assert_eq!(report.matches("<table>").count(), 2);
+        assert!(report.contains("2023-02-25T00:00:00.000Z"));
+        assert!("HTML".parse::<ReportFormat>().is_ok() && "pdf".parse::<ReportFormat>().is_err());
+        let empty = render(&[], ReportFormat::Html, &ReportOptions::default());
+        assert!(empty.contains("No conjunctions."));
+    }
+}
@@ -0,0 +1,319 @@
+//! Conjunction risk scoring
+//!
+//! A `RiskScorer` reduces each conjunction to a score in [0, 1] for
+//! ranking. Each factor is first mapped to [0, 1]:
+//! * miss distance: exp(-d / reference_miss_km)
+//! * relative velocity: v / reference_velocity_km_s, capped at 1
+//! * probability: log10(Pc) placed between `pc_floor` and `pc_ceiling`
+//! * object size: 1 - exp(-(R1 + R2) / reference_radius_km)
+//! * covariance: 1 - exp(-sigma / reference_sigma_km), sigma being the root
+//!   trace of the combined position covariance; 1 when either is missing,
+//!   so poorly known events rank higher rather than disappear
+//!
+//! The score is the weighted mean over the factors that are available:
+//! events without Pc, or scored without their satellites, are averaged over
+//! the remaining factors instead of being penalised.
+
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use rayon::prelude::*;
+
+use crate::catalog::Catalog;
+use crate::error::OrbitError;
+use crate::{parallel, Conjunction, Satellite};
+
+/// Per-factor contributions in [0, 1]; None where the input is unavailable
+#[derive(Clone, Copy, Debug, Default)]
+struct Factors {
+    miss_distance: f64,
+    relative_velocity: f64,
+    probability: Option<f64>,
+    object_size: Option<f64>,
+    covariance: Option<f64>,
+}
+
+/// Scored conjunction
+#[cfg_attr(feature = "python", pyclass(get_all))]
+#[derive(Clone)]
+pub struct RiskScore {
+    pub conjunction: Conjunction,
+    pub score: f64,
+    pub miss_distance_factor: f64,
+    pub relative_velocity_factor: f64,
+    pub probability_factor: Option<f64>,
+    pub object_size_factor: Option<f64>,
+    pub covariance_factor: Option<f64>,
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl RiskScore {
+    #[getter]
+    fn norad_id_1(&self) -> i32 {
+        self.conjunction.norad_id_1
+    }
+
+    #[getter]
+    fn norad_id_2(&self) -> i32 {
+        self.conjunction.norad_id_2
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "RiskScore({} ↔ {}, score={:.3}, dist={:.3}km)",
+            self.conjunction.norad_id_1, self.conjunction.norad_id_2, self.score, self.conjunction.distance_km
+        )
+    }
+}
+
+/// Weighted multi-factor conjunction ranking
+#[cfg_attr(feature = "python", pyclass(get_all, set_all))]
+#[derive(Clone, Debug)]
+pub struct RiskScorer {
+    pub miss_distance_weight: f64,
+    pub relative_velocity_weight: f64,
+    pub probability_weight: f64,
+    pub object_size_weight: f64,
+    pub covariance_weight: f64,
+    pub reference_miss_km: f64,
+    pub reference_velocity_km_s: f64,
+    pub pc_floor: f64, // Pc mapped to 0
+    pub pc_ceiling: f64, // Pc mapped to 1
+    pub reference_radius_km: f64,
+    pub reference_sigma_km: f64,
+}
+
+impl Default for RiskScorer {
+    fn default() -> Self {
+        RiskScorer {
+            miss_distance_weight: 0.3,
+            relative_velocity_weight: 0.1,
+            probability_weight: 0.4,
+            object_size_weight: 0.1,
+            covariance_weight: 0.1,
+            reference_miss_km: 1.0,
+            reference_velocity_km_s: 15.0,
+            pc_floor: 1.0e-10,
+            pc_ceiling: 1.0e-3,
+            reference_radius_km: 0.01,
+            reference_sigma_km: 1.0,
+        }
+    }
+}
+
+impl RiskScorer {
+    /// Check the weights, reference scales and Pc range
+    pub fn validate(&self) -> Result<(), OrbitError> {
+        let weights = self.weights();
+        if weights.iter().any(|w| !(*w >= 0.0 && w.is_finite())) || weights.iter().sum::<f64>() <= 0.0 {
+            return Err(OrbitError::InvalidInput("Weights must be non-negative with a positive sum".to_string()));
+        }
+        let scales = [self.reference_miss_km, self.reference_velocity_km_s, self.reference_radius_km, self.reference_sigma_km];
+        if scales.iter().any(|s| s.is_nan() || *s <= 0.0) {
+            return Err(OrbitError::InvalidInput("Reference scales must be positive".to_string()));
+        }
+        if !(self.pc_floor > 0.0 && self.pc_ceiling > self.pc_floor) {
+            return Err(OrbitError::InvalidInput("Need 0 < pc_floor < pc_ceiling".to_string()));
+        }
+        Ok(())
+    }
+
+    fn weights(&self) -> [f64; 5] {
+        [
+            self.miss_distance_weight,
+            self.relative_velocity_weight,
+            self.probability_weight,
+            self.object_size_weight,
+            self.covariance_weight,
+        ]
+    }
+
+    fn factors(&self, conjunction: &Conjunction, objects: Option<(&Satellite, &Satellite)>) -> Factors {
+        let probability = conjunction.probability.map(|pc| {
+            let (low, high) = (self.pc_floor.log10(), self.pc_ceiling.log10());
+            ((pc.max(f64::MIN_POSITIVE).log10() - low) / (high - low)).clamp(0.0, 1.0)
+        });
+        let object_size = objects.map(|(primary, secondary)| {
+            1.0 - (-(primary.collision_radius() + secondary.collision_radius()) / self.reference_radius_km).exp()
+        });
+        let covariance = objects.map(|(primary, secondary)| match (&primary.covariance, &secondary.covariance) {
+            (Some(p1), Some(p2)) => {
+                let sigma = (p1.fixed_view::<3, 3>(0, 0).trace() + p2.fixed_view::<3, 3>(0, 0).trace()).max(0.0).sqrt();
+                1.0 - (-sigma / self.reference_sigma_km).exp()
+            }
+            _ => 1.0,
+        });
+        Factors {
+            miss_distance: (-conjunction.distance_km / self.reference_miss_km).exp(),
+            relative_velocity: (conjunction.relative_velocity_km_s / self.reference_velocity_km_s).min(1.0),
+            probability,
+            object_size,
+            covariance,
+        }
+    }
+
+    /// Score one event; `objects` are (primary, secondary) when known
+    pub fn evaluate(&self, conjunction: &Conjunction, objects: Option<(&Satellite, &Satellite)>) -> RiskScore {
+        let factors = self.factors(conjunction, objects);
+        let values = [
+            Some(factors.miss_distance),
+            Some(factors.relative_velocity),
+            factors.probability,
+            factors.object_size,
+            factors.covariance,
+        ];
+        let (total, weight) = values
+            .iter()
+            .zip(self.weights())
+            .filter_map(|(value, weight)| value.map(|v| (v * weight, weight)))
+            .fold((0.0, 0.0), |(s, w), (sv, wv)| (s + sv, w + wv));
+        RiskScore {
+            conjunction: conjunction.clone(),
+            score: if weight > 0.0 { total / weight } else { 0.0 },
+            miss_distance_factor: factors.miss_distance,
+            relative_velocity_factor: factors.relative_velocity,
+            probability_factor: factors.probability,
+            object_size_factor: factors.object_size,
+            covariance_factor: factors.covariance,
+        }
+    }
+
+    /// Score and rank conjunctions, highest risk first
+    ///
+    /// `catalog` supplies sizes and covariances by NORAD id; events whose
+    /// objects are missing skip those factors. Ties are broken by miss
+    /// distance, then ids.
+    pub fn rank(&self, conjunctions: &[Conjunction], catalog: Option<&Catalog>) -> Result<Vec<RiskScore>, OrbitError> {
+        self.validate()?;
+        let mut scores: Vec<RiskScore> = parallel::install(|| {
+            conjunctions
+                .par_iter()
+                .map(|c| {
+                    let objects = catalog.and_then(|catalog| catalog.get(c.norad_id_1).zip(catalog.get(c.norad_id_2)));
+                    self.evaluate(c, objects)
+                })
+                .collect()
+        });
+        scores.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then(a.conjunction.distance_km.total_cmp(&b.conjunction.distance_km))
+                .then((a.conjunction.norad_id_1, a.conjunction.norad_id_2).cmp(&(b.conjunction.norad_id_1, b.conjunction.norad_id_2)))
+        });
+        Ok(scores)
+    }
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl RiskScorer {
+    #[new]
+    #[pyo3(signature = (
+        miss_distance = 0.3,
+        relative_velocity = 0.1,
+        probability = 0.4,
+        object_size = 0.1,
+        covariance = 0.1
+    ))]
+    fn new(
+        miss_distance: f64,
+        relative_velocity: f64,
+        probability: f64,
+        object_size: f64,
+        covariance: f64,
+    ) -> PyResult<Self> {
+        let scorer = RiskScorer {
+            miss_distance_weight: miss_distance,
+            relative_velocity_weight: relative_velocity,
+            probability_weight: probability,
+            object_size_weight: object_size,
+            covariance_weight: covariance,
+            ..RiskScorer::default()
+        };
+        scorer.validate()?;
+        Ok(scorer)
+    }
+
+    /// Score one conjunction; pass both satellites to include size and covariance
+    #[pyo3(signature = (conjunction, primary = None, secondary = None))]
+    fn score(
+        &self,
+        conjunction: &Conjunction,
+        primary: Option<Satellite>,
+        secondary: Option<Satellite>,
+    ) -> PyResult<RiskScore> {
+        self.validate()?;
+        Ok(self.evaluate(conjunction, primary.as_ref().zip(secondary.as_ref())))
+    }
+
+    /// Score and rank conjunctions, highest risk first
+    ///
+    /// `satellites` (a `Catalog` or a list) supplies sizes and covariances
+    /// by NORAD id; events whose objects are missing skip those factors.
+    /// Ties are broken by miss distance, then ids.
+    #[pyo3(name = "rank", signature = (conjunctions, satellites = None))]
+    fn py_rank(&self, conjunctions: Vec<Conjunction>, satellites: Option<&PyAny>) -> PyResult<Vec<RiskScore>> {
+        let (borrowed, owned);
+        let catalog: Option<&Catalog> = match satellites {
+            None => None,
+            Some(obj) => match obj.extract::<PyRef<Catalog>>() {
+                Ok(catalog) => {
+                    borrowed = catalog;
+                    Some(&borrowed)
+                }
+                Err(_) => {
+                    owned = Catalog::from_satellites(obj.extract()?).map_err(pyo3::exceptions::PyValueError::new_err)?;
+                    Some(&owned)
+                }
+            },
+        };
+        Ok(self.rank(&conjunctions, catalog)?)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "RiskScorer(miss_distance={}, relative_velocity={}, probability={}, object_size={}, covariance={})",
+            self.miss_distance_weight,
+            self.relative_velocity_weight,
+            self.probability_weight,
+            self.object_size_weight,
+            self.covariance_weight
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nalgebra::Vector3;
+
+    fn event(distance: f64, speed: f64, probability: Option<f64>) -> Conjunction {
+        let (r, v) = (Vector3::new(7000.0, 0.0, 0.0), Vector3::new(0.0, 7.5, 0.0));
+        let mut c = Conjunction::from_states(1, 2, (&r, &v), (&(r + Vector3::new(0.0, 0.0, distance)), &(v + Vector3::new(speed, 0.0, 0.0))), None);
+        c.probability = probability;
+        c
+    }
+
+    #[test]
+    fn test_closer_and_likelier_rank_higher() {
+        let scorer = RiskScorer::default();
+        let near = scorer.evaluate(&event(0.1, 10.0, None), None);
+        let far = scorer.evaluate(&event(5.0, 10.0, None), None);
+        assert!(near.score > far.score);
+        let likely = scorer.evaluate(&event(1.0, 10.0, Some(1e-4)), None);
+        let unlikely = scorer.evaluate(&event(1.0, 10.0, Some(1e-9)), None);
+        assert!(likely.score > unlikely.score);
+        // 1e-4 is six of the seven decades between the floor and the ceiling
+        assert!((likely.probability_factor.unwrap() - 6.0 / 7.0).abs() < 1e-12);
+        assert!(likely.object_size_factor.is_none() && likely.covariance_factor.is_none());
+    }
+
+    #[test]
+    fn test_missing_factors_are_renormalised() {
+        let scorer = RiskScorer { relative_velocity_weight: 0.0, ..RiskScorer::default() };
+        // with only the miss factor available the score equals it
+        let scored = scorer.evaluate(&event(0.5, 10.0, None), None);
+        assert!((scored.score - (-0.5f64).exp()).abs() < 1e-12);
+        assert!(RiskScorer { pc_floor: 1e-3, ..RiskScorer::default() }.validate().is_err());
+    }
+}
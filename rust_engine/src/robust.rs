@@ -0,0 +1,40 @@
+//! Robust statistics for outlier screening
+//!
+//! TLE histories contain the very jumps we look for, so their spread is
+//! measured with the median absolute deviation (MAD), scaled to the sigma
+//! of a Gaussian, rather than a standard deviation the outliers inflate.
+
+/// Scale from median absolute deviation to a Gaussian sigma
+pub(crate) const MAD_TO_SIGMA: f64 = 1.4826;
+
+/// Median of a non-empty slice (reorders it)
+pub(crate) fn median(values: &mut [f64]) -> f64 {
+    values.sort_by(f64::total_cmp);
+    let mid = values.len() / 2;
+    if values.len().is_multiple_of(2) {
+        0.5 * (values[mid - 1] + values[mid])
+    } else {
+        values[mid]
+    }
+}
+
+/// Median and robust sigma (scaled MAD) of a non-empty slice
+pub(crate) fn median_and_sigma(values: &[f64]) -> (f64, f64) {
+    let centre = median(&mut values.to_vec());
+    let mut deviations: Vec<f64> = values.iter().map(|v| (v - centre).abs()).collect();
+    (centre, MAD_TO_SIGMA * median(&mut deviations))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_median_and_sigma_ignore_outliers() {
+        assert_eq!(median(&mut [3.0, 1.0, 2.0]), 2.0);
+        assert_eq!(median(&mut [4.0, 1.0, 3.0, 2.0]), 2.5);
+        let (centre, sigma) = median_and_sigma(&[1.0, 2.0, 3.0, 4.0, 1000.0]);
+        assert_eq!(centre, 3.0);
+        assert!((sigma - MAD_TO_SIGMA).abs() < 1e-12);
+    }
+}
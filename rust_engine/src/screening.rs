@@ -0,0 +1,1401 @@
+//! Time-windowed conjunction screening
+//!
+//! States are sampled on a fixed grid; every sign change of the relative
+//! range-rate from negative to positive brackets a local minimum of the
+//! separation, which is then refined with Brent's method to obtain the
+//! time of closest approach (TCA). The TCA is a root of the range-rate to
+//! `TCA_TOLERANCE_S`, not a grid sample, so it does not move with the grid
+//! phase or step and the states handed to Pc are those at the true minimum.
+//!
+//! `ScreeningPipeline` runs optional pre-filters on every pair before that
+//! fine search; `screen_window` is the pipeline with no filters. Objects may
+//! be SGP4 propagators or tabulated ephemerides (e.g. read from OEM files).
+//! A `PairSelection` (excluded NORAD id pairs, watch list) is applied before
+//! any filter, so pairs nobody wants reported cost nothing beyond the shared
+//! propagation. `screen_primaries` evaluates only primary/catalogue pairs,
+//! which is how operator OEMs are screened against TLE-propagated objects.
+//!
+//! Long screens accept a `progress` callback and a `CancellationToken`; the
+//! search then runs without the GIL and reports pairs processed and hits.
+//! `ScreeningPipeline::screen` itself is plain Rust and is what the
+//! `orbitguard` command-line tool runs.
+
+use nalgebra::Vector3;
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use rayon::prelude::*;
+#[cfg(feature = "python")]
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+use crate::constants::{EARTH_RADIUS_KM, MU_EARTH, SECONDS_PER_DAY};
+use crate::ephemeris::Ephemeris;
+#[cfg(feature = "python")]
+use crate::ephemeris::parse_oem;
+use crate::epoch::Epoch;
+use crate::error::OrbitError;
+#[cfg(feature = "python")]
+use crate::events::{self, ConjunctionEvent};
+use crate::gpu;
+use crate::parallel;
+use crate::progress::Progress;
+#[cfg(feature = "python")]
+use crate::progress::{self, CancellationToken};
+use crate::sgp4::Sgp4Propagator;
+use crate::sieve::{self, OrbitGeometry};
+use crate::Conjunction;
+#[cfg(feature = "python")]
+use crate::sorted;
+
+/// Relative-time tolerance for the TCA root finder (seconds)
+///
+/// The root is found in seconds from the window start, and the states and
+/// miss distance are evaluated there. At LEO crossing speeds (~15 km/s) an
+/// error this size moves the relative position by up to ~1.5 cm. The miss
+/// distance is stationary at TCA, so it changes far less. The reported
+/// `tca_jd` is coarser still: an f64 Julian date resolves only ~40 us.
+const TCA_TOLERANCE_S: f64 = 1.0e-6;
+
+type State = (Vector3<f64>, Vector3<f64>);
+
+/// Source of states for screening: an SGP4 propagator or a tabulated ephemeris
+///
+/// Catalogs are dominated by SGP4 objects, so that variant is stored inline.
+#[derive(Clone)]
+#[cfg_attr(feature = "python", derive(FromPyObject))]
+#[allow(clippy::large_enum_variant)]
+pub enum Trajectory {
+    Sgp4(Sgp4Propagator),
+    Ephemeris(Ephemeris),
+}
+
+impl Trajectory {
+    pub fn norad_id(&self) -> i32 {
+        match self {
+            Trajectory::Sgp4(prop) => prop.elements.norad_id,
+            Trajectory::Ephemeris(eph) => eph.norad_id,
+        }
+    }
+
+    /// COSPAR id from the element set; ephemerides carry none
+    pub fn international_designator(&self) -> Option<&str> {
+        match self {
+            Trajectory::Sgp4(prop) => prop.elements.international_designator.as_deref(),
+            Trajectory::Ephemeris(_) => None,
+        }
+    }
+
+    /// Position (km) and velocity (km/s) at seconds after `start_jd`
+    ///
+    /// None where SGP4 fails or outside an ephemeris' span.
+    pub fn state_at(&self, start_jd: f64, seconds: f64) -> Option<State> {
+        match self {
+            Trajectory::Sgp4(prop) => {
+                let minutes = prop.minutes_since_epoch(start_jd) + seconds / 60.0;
+                prop.position_velocity(minutes)
+                    .ok()
+                    .map(|(r, v)| (Vector3::from(r), Vector3::from(v)))
+            }
+            Trajectory::Ephemeris(eph) => {
+                let offset = Epoch::from_jd_utc(start_jd).seconds_since(&eph.reference);
+                eph.state_at_offset(offset + seconds)
+            }
+        }
+    }
+}
+
+impl From<Sgp4Propagator> for Trajectory {
+    fn from(prop: Sgp4Propagator) -> Self {
+        Trajectory::Sgp4(prop)
+    }
+}
+
+impl From<Ephemeris> for Trajectory {
+    fn from(eph: Ephemeris) -> Self {
+        Trajectory::Ephemeris(eph)
+    }
+}
+
+/// Relative range-rate numerator d(|r|^2)/dt / 2 = dr . dv
+fn range_rate(s1: &State, s2: &State) -> f64 {
+    (s1.0 - s2.0).dot(&(s1.1 - s2.1))
+}
+
+/// Brent's method for a root of `f` bracketed by [a, b]
+pub(crate) fn brent<F: Fn(f64) -> Option<f64>>(
+    f: F,
+    mut a: f64,
+    mut b: f64,
+    mut fa: f64,
+    mut fb: f64,
+    tol: f64,
+) -> Option<f64> {
+    if fa * fb > 0.0 {
+        return None;
+    }
+    let mut c = a;
+    let mut fc = fa;
+    let mut d = b - a;
+    let mut e = d;
+
+    for _ in 0..100 {
+        if fb * fc > 0.0 {
+            c = a;
+            fc = fa;
+            d = b - a;
+            e = d;
+        }
+        if fc.abs() < fb.abs() {
+            a = b;
+            b = c;
+            c = a;
+            fa = fb;
+            fb = fc;
+            fc = fa;
+        }
+
+        let tol1 = 2.0 * f64::EPSILON * b.abs() + 0.5 * tol;
+        let xm = 0.5 * (c - b);
+        if xm.abs() <= tol1 || fb == 0.0 {
+            return Some(b);
+        }
+
+        if e.abs() >= tol1 && fa.abs() > fb.abs() {
+            // attempt inverse quadratic interpolation
+            let s = fb / fa;
+            let (mut p, mut q);
+            if a == c {
+                p = 2.0 * xm * s;
+                q = 1.0 - s;
+            } else {
+                let qa = fa / fc;
+                let r = fb / fc;
+                p = s * (2.0 * xm * qa * (qa - r) - (b - a) * (r - 1.0));
+                q = (qa - 1.0) * (r - 1.0) * (s - 1.0);
+            }
+            if p > 0.0 {
+                q = -q;
+            }
+            p = p.abs();
+            if 2.0 * p < (3.0 * xm * q - (tol1 * q).abs()).min((e * q).abs()) {
+                e = d;
+                d = p / q;
+            } else {
+                d = xm;
+                e = d;
+            }
+        } else {
+            d = xm;
+            e = d;
+        }
+
+        a = b;
+        fa = fb;
+        b += if d.abs() > tol1 { d } else { tol1.copysign(xm) };
+        fb = f(b)?;
+    }
+
+    Some(b)
+}
+
+/// Closest approaches of one pair over the sampled grid
+fn pair_conjunctions(
+    p1: &Trajectory,
+    p2: &Trajectory,
+    states1: &[Option<State>],
+    states2: &[Option<State>],
+    times: &[f64],
+    start_jd: f64,
+    threshold_km: f64,
+) -> Vec<Conjunction> {
+    let mut events = Vec::new();
+    let last = times.len() - 1;
+
+    let mut record = |t: f64, s1: &State, s2: &State| {
+        let miss = (s1.0 - s2.0).norm();
+        if miss < threshold_km {
+            let mut event = Conjunction::from_states(
+                p1.norad_id(),
+                p2.norad_id(),
+                (&s1.0, &s1.1),
+                (&s2.0, &s2.1),
+                Some(start_jd + t / SECONDS_PER_DAY),
+            );
+            event.time_to_tca_s = Some(t);
+            event.international_designator_1 = p1.international_designator().map(str::to_string);
+            event.international_designator_2 = p2.international_designator().map(str::to_string);
+            events.push(event);
+        }
+    };
+
+    for k in 0..=last {
+        let (Some(a1), Some(a2)) = (&states1[k], &states2[k]) else {
+            continue;
+        };
+        let fa = range_rate(a1, a2);
+
+        // separation already increasing at window start, or still closing at window end
+        if (k == 0 && fa > 0.0) || (k == last && fa < 0.0) {
+            record(times[k], a1, a2);
+        }
+        if k == last {
+            break;
+        }
+
+        let (Some(b1), Some(b2)) = (&states1[k + 1], &states2[k + 1]) else {
+            continue;
+        };
+        let fb = range_rate(b1, b2);
+        if !(fa < 0.0 && fb >= 0.0) {
+            continue;
+        }
+
+        let rate_at = |t: f64| {
+            let s1 = p1.state_at(start_jd, t)?;
+            let s2 = p2.state_at(start_jd, t)?;
+            Some(range_rate(&s1, &s2))
+        };
+        let Some(tca) = brent(rate_at, times[k], times[k + 1], fa, fb, TCA_TOLERANCE_S) else {
+            continue;
+        };
+        if let (Some(s1), Some(s2)) = (p1.state_at(start_jd, tca), p2.state_at(start_jd, tca)) {
+            record(tca, &s1, &s2);
+        }
+    }
+
+    events
+}
+
+/// Upper bound on relative acceleration (km/s^2): surface gravity on both objects
+pub(crate) const MAX_RELATIVE_ACCEL: f64 = 2.0 * MU_EARTH / (EARTH_RADIUS_KM * EARTH_RADIUS_KM);
+
+/// True if the sampled pair can come within `reach_km` between grid points
+///
+/// Between samples the separation falls at most at the relative speed
+/// (bounded by its end values plus the acceleration over the step).
+fn geometric_candidate(states1: &[Option<State>], states2: &[Option<State>], times: &[f64], reach_km: f64) -> bool {
+    (0..times.len() - 1).any(|k| {
+        let (Some(a1), Some(a2), Some(b1), Some(b2)) = (&states1[k], &states2[k], &states1[k + 1], &states2[k + 1]) else {
+            return false;
+        };
+        let h = times[k + 1] - times[k];
+        let speed = (a1.1 - a2.1).norm().max((b1.1 - b2.1).norm()) + MAX_RELATIVE_ACCEL * h;
+        let (da, db) = ((a1.0 - a2.0).norm(), (b1.0 - b2.0).norm());
+        0.5 * (da + db - speed * h) <= reach_km
+    })
+}
+
+/// Note that the device could not take the geometric filter, which then runs on the CPU
+fn gpu_fallback(reason: &str) {
+    tracing::warn!(reason, "GPU screening unavailable, using the CPU: {}", reason);
+}
+
+/// Pre-filter stage of a screening pipeline
+///
+/// Each filter screens at the threshold plus its own `pad_km`, which should
+/// cover how far the fixed-orbit assumption can drift over the window.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ScreeningFilter {
+    /// Perigee/apogee bands must overlap
+    AltitudeBand { pad_km: f64 },
+    /// Minimum distance between the two orbits (MOID) must be small enough
+    OrbitPath { pad_km: f64 },
+    /// Both objects must be near the mutual node line at the same time
+    Time { pad_km: f64 },
+    /// Sampled separations must allow an approach between grid points
+    Geometric { pad_km: f64 },
+}
+
+impl ScreeningFilter {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ScreeningFilter::AltitudeBand { .. } => "altitude",
+            ScreeningFilter::OrbitPath { .. } => "orbit_path",
+            ScreeningFilter::Time { .. } => "time",
+            ScreeningFilter::Geometric { .. } => "geometric",
+        }
+    }
+}
+
+/// Pairs to screen by NORAD id: known pairs to skip and an optional watch list
+///
+/// Excluded pairs (formation flyers, docked objects) match in either order.
+/// With a watch list only pairs involving at least one watched object are
+/// kept. The default selection keeps every pair.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PairSelection {
+    excluded: HashSet<(i32, i32)>,
+    watch_list: Option<HashSet<i32>>,
+}
+
+impl PairSelection {
+    pub fn new(excluded: impl IntoIterator<Item = (i32, i32)>, watch_list: Option<Vec<i32>>) -> Self {
+        PairSelection {
+            excluded: excluded.into_iter().map(|(a, b)| (a.min(b), a.max(b))).collect(),
+            watch_list: watch_list.map(|ids| ids.into_iter().collect()),
+        }
+    }
+
+    /// Skip `a`-`b` in addition to the pairs already excluded
+    pub fn exclude(&mut self, a: i32, b: i32) {
+        self.excluded.insert((a.min(b), a.max(b)));
+    }
+
+    /// Restrict screening to pairs involving these objects (None: every object)
+    pub fn set_watch_list(&mut self, watch_list: Option<Vec<i32>>) {
+        self.watch_list = watch_list.map(|ids| ids.into_iter().collect());
+    }
+
+    /// Whether the selection keeps every pair
+    pub fn is_everything(&self) -> bool {
+        self.excluded.is_empty() && self.watch_list.is_none()
+    }
+
+    /// Whether the pair of objects `a` and `b` is screened
+    pub fn keeps(&self, a: i32, b: i32) -> bool {
+        let watched = self.watch_list.as_ref().is_none_or(|ids| ids.contains(&a) || ids.contains(&b));
+        watched && !self.excluded.contains(&(a.min(b), a.max(b)))
+    }
+}
+
+/// Which pairs of the objects handed to one search are evaluated
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum PairSet {
+    /// Every pair
+    All,
+    /// The first `n` objects against the rest, skipping shared NORAD ids
+    Primaries(usize),
+    /// The first `n` objects (one tile) against the rest (another tile)
+    Between(usize),
+}
+
+impl PairSet {
+    /// Objects whose partners are searched
+    fn rows(&self, n: usize) -> usize {
+        match *self {
+            PairSet::All => n,
+            PairSet::Primaries(p) | PairSet::Between(p) => p,
+        }
+    }
+
+    /// Index of the first partner of object `i`; partners run to the end
+    fn first_partner(&self, i: usize) -> usize {
+        match *self {
+            PairSet::All => i + 1,
+            PairSet::Primaries(p) | PairSet::Between(p) => p,
+        }
+    }
+
+    fn skips_same_id(&self) -> bool {
+        matches!(self, PairSet::Primaries(_))
+    }
+
+    /// Number of pairs among `n` objects
+    fn count(&self, n: usize) -> usize {
+        match *self {
+            PairSet::All => n * n.saturating_sub(1) / 2,
+            PairSet::Primaries(p) | PairSet::Between(p) => p * (n - p),
+        }
+    }
+}
+
+/// Per-object data the filters share
+struct ScreeningContext<'a> {
+    states: &'a [Vec<Option<State>>],
+    times: &'a [f64],
+    bands: Vec<Option<(f64, f64)>>,
+    orbits: Vec<Option<OrbitGeometry>>,
+    threshold_km: f64,
+}
+
+impl ScreeningContext<'_> {
+    /// Whether pair (i, j) survives `filter`; missing data always passes
+    fn passes(&self, filter: &ScreeningFilter, i: usize, j: usize) -> bool {
+        let duration = self.times[self.times.len() - 1];
+        match *filter {
+            ScreeningFilter::AltitudeBand { pad_km } => match (self.bands[i], self.bands[j]) {
+                (Some(a), Some(b)) => sieve::apsis_bands_overlap(a, b, self.threshold_km + pad_km),
+                _ => true,
+            },
+            ScreeningFilter::OrbitPath { pad_km } => match (&self.orbits[i], &self.orbits[j]) {
+                (Some(a), Some(b)) => sieve::orbit_path_distance(a, b) <= self.threshold_km + pad_km,
+                _ => true,
+            },
+            ScreeningFilter::Time { pad_km } => match (&self.orbits[i], &self.orbits[j]) {
+                (Some(a), Some(b)) => sieve::node_windows_overlap(a, b, self.threshold_km + pad_km, duration),
+                _ => true,
+            },
+            ScreeningFilter::Geometric { pad_km } => {
+                geometric_candidate(&self.states[i], &self.states[j], self.times, self.threshold_km + pad_km)
+            }
+        }
+    }
+}
+
+/// Where the geometric pre-filter runs
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ScreeningBackend {
+    /// Rayon threads, alongside the other stages
+    #[default]
+    Cpu,
+    /// An OpenCL device (`opencl` feature), falling back to the CPU when there is none
+    Gpu,
+}
+
+impl ScreeningBackend {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ScreeningBackend::Cpu => "cpu",
+            ScreeningBackend::Gpu => "gpu",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name.to_ascii_lowercase().as_str() {
+            "cpu" => Ok(ScreeningBackend::Cpu),
+            "gpu" | "opencl" => Ok(ScreeningBackend::Gpu),
+            _ => Err(format!("Unknown screening backend '{}' (expected 'cpu' or 'gpu')", name)),
+        }
+    }
+}
+
+/// Rows of pairs handled per device call; bounds the verdict buffer to this many bytes
+const GPU_MASK_BYTES: usize = 1 << 26;
+
+/// Conjunction screening with a configurable chain of pre-filters
+///
+/// Pairs outside the selection are skipped first; filters then run in the
+/// order they were added, and only pairs that pass all of them reach the
+/// sampled TCA search. With the GPU backend geometric filters are evaluated
+/// on an OpenCL device; `last_backend` records where the last run actually
+/// evaluated them.
+#[cfg_attr(feature = "python", pyclass)]
+#[derive(Clone)]
+pub struct ScreeningPipeline {
+    pub threshold_km: f64,
+
+    pub filters: Vec<ScreeningFilter>,
+
+    pub selection: PairSelection,
+
+    pub backend: ScreeningBackend,
+
+    pub last_stats: Vec<(String, usize)>,
+
+    pub last_backend: ScreeningBackend,
+}
+
+impl ScreeningPipeline {
+    /// Empty pipeline (fine search only)
+    pub fn with_threshold(threshold_km: f64) -> Self {
+        ScreeningPipeline {
+            threshold_km,
+            filters: Vec::new(),
+            selection: PairSelection::default(),
+            backend: ScreeningBackend::Cpu,
+            last_stats: Vec::new(),
+            last_backend: ScreeningBackend::Cpu,
+        }
+    }
+
+    /// Screen objects over [start_jd, end_jd] with a grid `step` in seconds
+    pub fn screen(
+        &mut self,
+        satellites: &[Trajectory],
+        start_epoch: f64,
+        end_epoch: f64,
+        step: f64,
+    ) -> Result<Vec<Conjunction>, OrbitError> {
+        self.screen_with_progress(satellites, start_epoch, end_epoch, step, &Progress::new(0, None))
+    }
+
+    /// As `screen`, counting pairs and hits in `progress` and stopping once it is cancelled
+    pub fn screen_with_progress(
+        &mut self,
+        satellites: &[Trajectory],
+        start_epoch: f64,
+        end_epoch: f64,
+        step: f64,
+        progress: &Progress,
+    ) -> Result<Vec<Conjunction>, OrbitError> {
+        progress.set_total(PairSet::All.count(satellites.len()));
+        let rows = self.screen_pairs(satellites, PairSet::All, start_epoch, end_epoch, step, progress)?;
+        Ok(rows.into_iter().flatten().collect())
+    }
+
+    /// Screen only primary/catalogue pairs; object 1 of every hit is the primary
+    ///
+    /// Catalogue entries sharing a primary's NORAD id are the primary itself
+    /// and are skipped, so an operator ephemeris supersedes the object's TLE.
+    pub fn screen_primaries(
+        &mut self,
+        primaries: &[Trajectory],
+        catalog: &[Trajectory],
+        start_epoch: f64,
+        end_epoch: f64,
+        step: f64,
+        progress: &Progress,
+    ) -> Result<Vec<Conjunction>, OrbitError> {
+        let each = self.screen_primaries_each(primaries, catalog, start_epoch, end_epoch, step, progress)?;
+        Ok(each.into_iter().flatten().collect())
+    }
+
+    /// As `screen_primaries`, with the hits of each primary listed separately
+    ///
+    /// Entry k holds the hits of `primaries[k]`. Primaries are never
+    /// screened against each other, so they may be alternatives for the same
+    /// object (e.g. candidate manoeuvres) sharing one NORAD id.
+    pub fn screen_primaries_each(
+        &mut self,
+        primaries: &[Trajectory],
+        catalog: &[Trajectory],
+        start_epoch: f64,
+        end_epoch: f64,
+        step: f64,
+        progress: &Progress,
+    ) -> Result<Vec<Vec<Conjunction>>, OrbitError> {
+        progress.set_total(primaries.len() * catalog.len());
+        self.screen_primaries_part(primaries, catalog, start_epoch, end_epoch, step, progress)
+    }
+
+    /// As `screen_primaries_each`, leaving the progress total to a caller screening in several parts
+    pub(crate) fn screen_primaries_part(
+        &mut self,
+        primaries: &[Trajectory],
+        catalog: &[Trajectory],
+        start_epoch: f64,
+        end_epoch: f64,
+        step: f64,
+        progress: &Progress,
+    ) -> Result<Vec<Vec<Conjunction>>, OrbitError> {
+        let satellites: Vec<Trajectory> = primaries.iter().chain(catalog).cloned().collect();
+        let pairs = PairSet::Primaries(primaries.len());
+        let mut each = self.screen_pairs(&satellites, pairs, start_epoch, end_epoch, step, progress)?;
+        each.resize(primaries.len(), Vec::new());
+        Ok(each)
+    }
+
+    /// As `screen_with_progress`, holding at most two tiles of `tile_size` objects at a time
+    ///
+    /// The catalogue is split into tiles and every pair of tiles (each with
+    /// itself included) is screened in turn, so sampled states and candidate
+    /// pairs are bounded by the tile size instead of the catalogue size. Each
+    /// object is propagated once per tile pair it takes part in, trading
+    /// time for memory. Hits are the same as an untiled screen, in a
+    /// different order; `last_stats` sums over the tiles.
+    pub fn screen_tiled(
+        &mut self,
+        satellites: &[Trajectory],
+        start_epoch: f64,
+        end_epoch: f64,
+        step: f64,
+        tile_size: usize,
+        progress: &Progress,
+    ) -> Result<Vec<Conjunction>, OrbitError> {
+        if tile_size == 0 {
+            return Err(OrbitError::InvalidInput("tile_size must be positive".to_string()));
+        }
+        if satellites.len() <= tile_size {
+            return self.screen_with_progress(satellites, start_epoch, end_epoch, step, progress);
+        }
+        progress.set_total(PairSet::All.count(satellites.len()));
+        let tiles: Vec<&[Trajectory]> = satellites.chunks(tile_size).collect();
+        let mut conjunctions = Vec::new();
+        let mut totals: Vec<(String, usize)> = Vec::new();
+        let mut on_device = true;
+        for (a, tile) in tiles.iter().enumerate() {
+            for other in &tiles[a..] {
+                let found = if std::ptr::eq(*tile, *other) {
+                    self.screen_pairs(tile, PairSet::All, start_epoch, end_epoch, step, progress)?
+                } else {
+                    let both: Vec<Trajectory> = tile.iter().chain(other.iter()).cloned().collect();
+                    self.screen_pairs(&both, PairSet::Between(tile.len()), start_epoch, end_epoch, step, progress)?
+                };
+                conjunctions.extend(found.into_iter().flatten());
+                on_device &= self.last_backend == ScreeningBackend::Gpu;
+                if totals.is_empty() {
+                    totals = self.last_stats.clone();
+                } else {
+                    for (total, (_, count)) in totals.iter_mut().zip(&self.last_stats) {
+                        total.1 += count;
+                    }
+                }
+            }
+        }
+        self.last_stats = totals;
+        self.last_backend = if on_device { ScreeningBackend::Gpu } else { ScreeningBackend::Cpu };
+        Ok(conjunctions)
+    }
+
+    /// Screen the pairs of `satellites` selected by `pairs`, returning the hits of each searched object
+    ///
+    /// Progress totals are set by the caller, which may screen in several parts.
+    fn screen_pairs(
+        &mut self,
+        satellites: &[Trajectory],
+        pairs: PairSet,
+        start_epoch: f64,
+        end_epoch: f64,
+        step: f64,
+        progress: &Progress,
+    ) -> Result<Vec<Vec<Conjunction>>, OrbitError> {
+        if end_epoch <= start_epoch {
+            return Err(OrbitError::InvalidInput("end_epoch must be after start_epoch".to_string()));
+        }
+        if step <= 0.0 {
+            return Err(OrbitError::InvalidInput("step must be positive (seconds)".to_string()));
+        }
+        self.last_stats.clear();
+        self.last_backend = ScreeningBackend::Cpu;
+        if satellites.len() < 2 {
+            return Ok(Vec::new());
+        }
+
+        let duration = (end_epoch - start_epoch) * SECONDS_PER_DAY;
+        let n_steps = (duration / step).ceil() as usize;
+        let times: Vec<f64> = (0..=n_steps)
+            .map(|k| (k as f64 * step).min(duration))
+            .collect();
+
+        let (filters, selection, threshold_km, n) = (&self.filters, &self.selection, self.threshold_km, satellites.len());
+        let traced = tracing::enabled!(tracing::Level::DEBUG);
+        if traced {
+            tracing::debug!(objects = n, steps = times.len(), step_s = step, threshold_km, "window");
+        }
+        let ids: Vec<i32> = satellites.iter().map(Trajectory::norad_id).collect();
+        let started = Instant::now();
+        let backend = self.backend;
+        let (selected, survivors, conjunctions, propagated, used) = parallel::install(|| {
+            // Propagate every object over the grid once
+            let states: Vec<Vec<Option<State>>> = satellites
+                .par_iter()
+                .map(|prop| {
+                    times
+                        .iter()
+                        .map(|&t| prop.state_at(start_epoch, t))
+                        .collect()
+                })
+                .collect();
+            let propagated = started.elapsed();
+
+            // Osculating orbits at the window start feed the orbit-based filters
+            let bands = states.iter().map(|s| s[0].map(|(r, v)| sieve::perigee_apogee(&r, &v))).collect();
+            let orbits = states
+                .iter()
+                .map(|s| s[0].and_then(|(r, v)| OrbitGeometry::from_state(&r, &v)))
+                .collect();
+            let context = ScreeningContext { states: &states, times: &times, bands, orbits, threshold_km };
+
+            let selected = AtomicUsize::new(0);
+            let survivors: Vec<AtomicUsize> = filters.iter().map(|_| AtomicUsize::new(0)).collect();
+
+            // Row i of the block starting at `first_row`; `masks` holds device verdicts per filter
+            let screen_row = |i: usize, first_row: usize, masks: &[Option<Vec<u8>>]| -> Vec<Conjunction> {
+                if progress.is_cancelled() {
+                    return Vec::new();
+                }
+                let first = pairs.first_partner(i);
+                let row: Vec<Conjunction> = (first..n)
+                    .filter(|&j| !pairs.skips_same_id() || ids[i] != ids[j])
+                    .filter(|&j| selection.keeps(ids[i], ids[j]))
+                    .inspect(|_| {
+                        selected.fetch_add(1, Ordering::Relaxed);
+                    })
+                    .filter(|&j| {
+                        filters.iter().zip(&survivors).zip(masks).all(|((filter, count), mask)| {
+                            let pass = match mask {
+                                Some(mask) => mask[(i - first_row) * n + j] != 0,
+                                None => context.passes(filter, i, j),
+                            };
+                            if pass {
+                                count.fetch_add(1, Ordering::Relaxed);
+                            }
+                            pass
+                        })
+                    })
+                    .flat_map(|j| {
+                        pair_conjunctions(
+                            &satellites[i],
+                            &satellites[j],
+                            &states[i],
+                            &states[j],
+                            &times,
+                            start_epoch,
+                            threshold_km,
+                        )
+                    })
+                    .collect();
+                progress.advance(n - first, row.len());
+                row
+            };
+
+            // Geometric filters go to the device, if one was asked for and answers
+            let offloaded = backend == ScreeningBackend::Gpu
+                && filters.iter().any(|filter| matches!(filter, ScreeningFilter::Geometric { .. }));
+            let device = if offloaded { gpu::upload(&states, &times).map_err(|e| gpu_fallback(&e)).ok() } else { None };
+            let mut on_device = device.is_some();
+            let rows = pairs.rows(n);
+            let block = if on_device { (GPU_MASK_BYTES / n).max(1) } else { rows.max(1) };
+            let partners_from = match pairs {
+                PairSet::All => None,
+                PairSet::Primaries(p) | PairSet::Between(p) => Some(p),
+            };
+
+            let mut conjunctions = Vec::new();
+            for first_row in (0..rows).step_by(block) {
+                if progress.is_cancelled() {
+                    break;
+                }
+                let last_row = (first_row + block).min(rows);
+                let mut masks = vec![None; filters.len()];
+                if let Some(device) = device.as_ref().filter(|_| on_device) {
+                    let computed: Result<Vec<Option<Vec<u8>>>, String> = filters
+                        .iter()
+                        .map(|filter| match *filter {
+                            ScreeningFilter::Geometric { pad_km } => device
+                                .geometric_mask(first_row, last_row - first_row, partners_from, threshold_km + pad_km)
+                                .map(Some),
+                            _ => Ok(None),
+                        })
+                        .collect();
+                    match computed {
+                        Ok(computed) => masks = computed,
+                        Err(e) => {
+                            gpu_fallback(&e);
+                            on_device = false;
+                        }
+                    }
+                }
+                let found: Vec<Vec<Conjunction>> =
+                    (first_row..last_row).into_par_iter().map(|i| screen_row(i, first_row, &masks)).collect();
+                conjunctions.extend(found);
+            }
+            let used = if on_device { ScreeningBackend::Gpu } else { ScreeningBackend::Cpu };
+            (selected.into_inner(), survivors, conjunctions, propagated, used)
+        });
+        if progress.is_cancelled() {
+            return Err(Progress::cancelled_error());
+        }
+
+        self.last_backend = used;
+        self.last_stats.push(("pairs".to_string(), selected));
+        for (filter, count) in self.filters.iter().zip(&survivors) {
+            self.last_stats.push((filter.name().to_string(), count.load(Ordering::Relaxed)));
+        }
+        self.last_stats.push(("conjunctions".to_string(), conjunctions.iter().map(Vec::len).sum()));
+        if traced {
+            let all: Vec<Conjunction> = conjunctions.iter().flatten().cloned().collect();
+            self.trace_run(&all, start_epoch, step, propagated.as_secs_f64(), started.elapsed().as_secs_f64());
+        }
+
+        Ok(conjunctions)
+    }
+
+    /// Debug records for the run just finished: time per stage, pairs pruned per filter, hits per step
+    fn trace_run(&self, conjunctions: &[Conjunction], start_epoch: f64, step: f64, propagation_s: f64, total_s: f64) {
+        tracing::debug!(elapsed_ms = propagation_s * 1e3, "propagation");
+        let mut before = self.last_stats[0].1;
+        for (filter, (_, kept)) in self.filters.iter().zip(&self.last_stats[1..]) {
+            tracing::debug!(
+                filter = filter.name(),
+                kept = *kept,
+                pruned = before - kept,
+                "filter {} kept {} of {} pairs",
+                filter.name(),
+                kept,
+                before
+            );
+            before = *kept;
+        }
+
+        let mut hits: Vec<(usize, usize)> = Vec::new();
+        let mut steps: Vec<usize> = conjunctions
+            .iter()
+            .filter_map(|c| c.tca_jd)
+            .map(|jd| ((jd - start_epoch) * SECONDS_PER_DAY / step).floor().max(0.0) as usize)
+            .collect();
+        steps.sort_unstable();
+        for k in steps {
+            match hits.last_mut() {
+                Some((last, count)) if *last == k => *count += 1,
+                _ => hits.push((k, 1)),
+            }
+        }
+        for (k, count) in hits {
+            tracing::debug!(
+                step = k,
+                time_jd = start_epoch + k as f64 * step / SECONDS_PER_DAY,
+                hits = count,
+                "{} hit(s) in step {}",
+                count,
+                k
+            );
+        }
+
+        tracing::debug!(
+            candidates = before,
+            conjunctions = conjunctions.len(),
+            elapsed_ms = (total_s - propagation_s) * 1e3,
+            total_ms = total_s * 1e3,
+            "search"
+        );
+    }
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl ScreeningPipeline {
+    #[new]
+    fn new(threshold_km: f64) -> PyResult<Self> {
+        if threshold_km <= 0.0 {
+            return Err(pyo3::exceptions::PyValueError::new_err("threshold_km must be positive"));
+        }
+        Ok(Self::with_threshold(threshold_km))
+    }
+
+    #[getter]
+    fn get_threshold_km(&self) -> f64 {
+        self.threshold_km
+    }
+
+    #[setter]
+    fn set_threshold_km(&mut self, value: f64) {
+        self.threshold_km = value;
+    }
+
+    /// Reject pairs whose perigee/apogee bands are further apart than threshold + pad
+    #[pyo3(signature = (pad_km = 10.0))]
+    fn add_altitude_filter(mut slf: PyRefMut<'_, Self>, pad_km: f64) -> PyRefMut<'_, Self> {
+        slf.filters.push(ScreeningFilter::AltitudeBand { pad_km });
+        slf
+    }
+
+    /// Reject pairs whose orbits never come within threshold + pad of each other
+    #[pyo3(signature = (pad_km = 10.0))]
+    fn add_orbit_path_filter(mut slf: PyRefMut<'_, Self>, pad_km: f64) -> PyRefMut<'_, Self> {
+        slf.filters.push(ScreeningFilter::OrbitPath { pad_km });
+        slf
+    }
+
+    /// Reject pairs that are never near the mutual node line at the same time
+    #[pyo3(signature = (pad_km = 20.0))]
+    fn add_time_filter(mut slf: PyRefMut<'_, Self>, pad_km: f64) -> PyRefMut<'_, Self> {
+        slf.filters.push(ScreeningFilter::Time { pad_km });
+        slf
+    }
+
+    /// Reject pairs whose sampled separations cannot drop below threshold + pad
+    #[pyo3(signature = (pad_km = 0.0))]
+    fn add_geometric_filter(mut slf: PyRefMut<'_, Self>, pad_km: f64) -> PyRefMut<'_, Self> {
+        slf.filters.push(ScreeningFilter::Geometric { pad_km });
+        slf
+    }
+
+    /// Skip these (norad_id_1, norad_id_2) pairs, in either order, before any filter
+    fn exclude_pairs(mut slf: PyRefMut<'_, Self>, pairs: Vec<(i32, i32)>) -> PyRefMut<'_, Self> {
+        for (a, b) in pairs {
+            slf.selection.exclude(a, b);
+        }
+        slf
+    }
+
+    /// Screen only pairs involving at least one of these objects; None screens every pair
+    fn set_watch_list(mut slf: PyRefMut<'_, Self>, norad_ids: Option<Vec<i32>>) -> PyRefMut<'_, Self> {
+        slf.selection.set_watch_list(norad_ids);
+        slf
+    }
+
+    /// Evaluate geometric filters on "cpu" or "gpu" (OpenCL; falls back to the CPU without a device)
+    fn set_backend<'p>(mut slf: PyRefMut<'p, Self>, backend: &str) -> PyResult<PyRefMut<'p, Self>> {
+        slf.backend = ScreeningBackend::from_name(backend).map_err(pyo3::exceptions::PyValueError::new_err)?;
+        Ok(slf)
+    }
+
+    /// Configured backend for geometric filters
+    #[getter]
+    fn backend(&self) -> &'static str {
+        self.backend.name()
+    }
+
+    /// Where the last run evaluated geometric filters ("cpu" after a fallback)
+    #[getter]
+    fn last_backend(&self) -> &'static str {
+        self.last_backend.name()
+    }
+
+    /// Remove all filters
+    fn clear_filters(&mut self) {
+        self.filters.clear();
+    }
+
+    /// Names of the configured filters, in execution order
+    #[getter]
+    fn filters(&self) -> Vec<&'static str> {
+        self.filters.iter().map(ScreeningFilter::name).collect()
+    }
+
+    /// Surviving pair counts per stage from the last run
+    #[getter]
+    fn last_stats(&self) -> Vec<(String, usize)> {
+        self.last_stats.clone()
+    }
+
+    /// Screen propagators or ephemerides over [start_epoch, end_epoch] (Julian dates), step in seconds
+    ///
+    /// `progress(percent, pairs_processed, hits)` is called every
+    /// `progress_interval_s` seconds; `cancel` stops the run, which then
+    /// raises RuntimeError. With `tile_size` the catalogue is screened in
+    /// tiles of that many objects to bound memory on very large catalogues.
+    #[pyo3(signature = (
+        satellites,
+        start_epoch,
+        end_epoch,
+        step,
+        sort_by = "ids",
+        progress = None,
+        cancel = None,
+        progress_interval_s = 1.0,
+        tile_size = None
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn run(
+        &mut self,
+        py: Python<'_>,
+        satellites: Vec<Trajectory>,
+        start_epoch: f64,
+        end_epoch: f64,
+        step: f64,
+        sort_by: &str,
+        progress: Option<&PyAny>,
+        cancel: Option<CancellationToken>,
+        progress_interval_s: f64,
+        tile_size: Option<usize>,
+    ) -> PyResult<Vec<Conjunction>> {
+        let counters = Progress::new(0, cancel.as_ref());
+        let conjunctions = progress::run_monitored(py, &counters, progress, progress_interval_s, || {
+            match tile_size {
+                Some(size) => self.screen_tiled(&satellites, start_epoch, end_epoch, step, size, &counters),
+                None => self.screen_with_progress(&satellites, start_epoch, end_epoch, step, &counters),
+            }
+            .map_err(PyErr::from)
+        })?;
+        sorted(conjunctions, sort_by)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "ScreeningPipeline(threshold_km={}, filters=[{}])",
+            self.threshold_km,
+            self.filters.iter().map(ScreeningFilter::name).collect::<Vec<_>>().join(", ")
+        )
+    }
+}
+
+/// Screen propagators or ephemerides over [start_jd, end_jd] and refine each close approach to its TCA
+///
+/// `progress`, `cancel` and `progress_interval_s` are as for `ScreeningPipeline.run`.
+/// `exclude_pairs` lists (norad_id_1, norad_id_2) pairs never to screen;
+/// with a `watch_list` of NORAD ids only pairs involving one of them are screened.
+/// `tile_size` screens in tiles as for `ScreeningPipeline.run`.
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(signature = (
+    satellites,
+    start_epoch,
+    end_epoch,
+    step,
+    threshold_km,
+    sort_by = "ids",
+    progress = None,
+    cancel = None,
+    progress_interval_s = 1.0,
+    exclude_pairs = None,
+    watch_list = None,
+    tile_size = None
+))]
+#[allow(clippy::too_many_arguments)]
+pub fn screen_window(
+    py: Python<'_>,
+    satellites: Vec<Trajectory>,
+    start_epoch: f64,
+    end_epoch: f64,
+    step: f64,
+    threshold_km: f64,
+    sort_by: &str,
+    progress: Option<&PyAny>,
+    cancel: Option<CancellationToken>,
+    progress_interval_s: f64,
+    exclude_pairs: Option<Vec<(i32, i32)>>,
+    watch_list: Option<Vec<i32>>,
+    tile_size: Option<usize>,
+) -> PyResult<Vec<Conjunction>> {
+    let counters = Progress::new(0, cancel.as_ref());
+    let mut pipeline = ScreeningPipeline::with_threshold(threshold_km);
+    pipeline.selection = PairSelection::new(exclude_pairs.unwrap_or_default(), watch_list);
+    let conjunctions = progress::run_monitored(py, &counters, progress, progress_interval_s, || {
+        match tile_size {
+            Some(size) => pipeline.screen_tiled(&satellites, start_epoch, end_epoch, step, size, &counters),
+            None => pipeline.screen_with_progress(&satellites, start_epoch, end_epoch, step, &counters),
+        }
+        .map_err(PyErr::from)
+    })?;
+    sorted(conjunctions, sort_by)
+}
+
+/// Screen operator OEM ephemerides against a catalogue of TLE propagators
+///
+/// Each primary is read from its OEM file (manoeuvres included as
+/// delivered) and rotated into TEME; only primary/catalogue pairs are
+/// screened, with object 1 the primary, and a catalogue entry with a
+/// primary's NORAD id is skipped in favour of the OEM. The window defaults
+/// to the span covered by the primaries; outside an OEM's span that primary
+/// is not screened. Other arguments are as for `screen_window`.
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(signature = (
+    primary_oem_paths,
+    catalog,
+    threshold_km,
+    start_epoch = None,
+    end_epoch = None,
+    step = 60.0,
+    sort_by = "ids",
+    progress = None,
+    cancel = None,
+    progress_interval_s = 1.0,
+    exclude_pairs = None
+))]
+#[allow(clippy::too_many_arguments)]
+pub fn screen_ephemerides(
+    py: Python<'_>,
+    primary_oem_paths: Vec<String>,
+    catalog: Vec<Trajectory>,
+    threshold_km: f64,
+    start_epoch: Option<f64>,
+    end_epoch: Option<f64>,
+    step: f64,
+    sort_by: &str,
+    progress: Option<&PyAny>,
+    cancel: Option<CancellationToken>,
+    progress_interval_s: f64,
+    exclude_pairs: Option<Vec<(i32, i32)>>,
+) -> PyResult<Vec<Conjunction>> {
+    if primary_oem_paths.is_empty() {
+        return Err(pyo3::exceptions::PyValueError::new_err("At least one primary OEM is required"));
+    }
+    let mut ephemerides = Vec::with_capacity(primary_oem_paths.len());
+    for path in &primary_oem_paths {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| pyo3::exceptions::PyIOError::new_err(format!("{}: {}", path, e)))?;
+        let ephemeris = parse_oem(&text, None)
+            .and_then(|eph| eph.to_teme())
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("{}: {}", path, e)))?;
+        ephemerides.push(ephemeris);
+    }
+    let spans: Vec<(f64, f64)> = ephemerides
+        .iter()
+        .map(|eph| (eph.reference.jd_utc(), eph.reference.plus_seconds(eph.span()).jd_utc()))
+        .collect();
+    let start_epoch = start_epoch.unwrap_or_else(|| spans.iter().map(|s| s.0).fold(f64::INFINITY, f64::min));
+    let end_epoch = end_epoch.unwrap_or_else(|| spans.iter().map(|s| s.1).fold(f64::NEG_INFINITY, f64::max));
+
+    let counters = Progress::new(0, cancel.as_ref());
+    let mut pipeline = ScreeningPipeline::with_threshold(threshold_km);
+    pipeline.selection = PairSelection::new(exclude_pairs.unwrap_or_default(), None);
+    let primaries: Vec<Trajectory> = ephemerides.into_iter().map(Trajectory::from).collect();
+    let conjunctions = progress::run_monitored(py, &counters, progress, progress_interval_s, || {
+        pipeline
+            .screen_primaries(&primaries, &catalog, start_epoch, end_epoch, step, &counters)
+            .map_err(PyErr::from)
+    })?;
+    sorted(conjunctions, sort_by)
+}
+
+/// Name of the OpenCL device GPU screening would use, or None without one
+#[cfg(feature = "python")]
+#[pyfunction]
+pub fn gpu_device() -> Option<String> {
+    gpu::device_name().ok()
+}
+
+/// Screen over [start_jd, end_jd] and merge hits into encounter events
+///
+/// Hits of one pair within `max_gap_s` seconds become a single event whose
+/// entry/exit times are where the separation crosses `threshold_km`.
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(signature = (satellites, start_epoch, end_epoch, step, threshold_km, max_gap_s = 600.0))]
+pub fn screen_events(
+    py: Python<'_>,
+    satellites: Vec<Trajectory>,
+    start_epoch: f64,
+    end_epoch: f64,
+    step: f64,
+    threshold_km: f64,
+    max_gap_s: f64,
+) -> PyResult<Vec<ConjunctionEvent>> {
+    if max_gap_s < 0.0 {
+        return Err(pyo3::exceptions::PyValueError::new_err("max_gap_s must be non-negative"));
+    }
+    py.allow_threads(|| {
+        let hits = ScreeningPipeline::with_threshold(threshold_km).screen(&satellites, start_epoch, end_epoch, step)?;
+        let mut index: HashMap<i32, &Trajectory> = HashMap::new();
+        for trajectory in satellites.iter().rev() {
+            index.insert(trajectory.norad_id(), trajectory);
+        }
+
+        let mut events = events::cluster(hits, max_gap_s);
+        parallel::install(|| {
+            events.par_iter_mut().for_each(|event| {
+                let (p1, p2) = (index[&event.norad_id_1], index[&event.norad_id_2]);
+                let distance_at = |jd: f64| {
+                    let seconds = (jd - start_epoch) * SECONDS_PER_DAY;
+                    Some((p1.state_at(start_epoch, seconds)?.0 - p2.state_at(start_epoch, seconds)?.0).norm())
+                };
+                event.refine_boundaries(distance_at, threshold_km, (start_epoch, end_epoch), step);
+            })
+        });
+        Ok(events)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ephemeris::Interpolation;
+    use crate::sgp4::MeanElements;
+
+    fn window(sats: Vec<Trajectory>, start: f64, end: f64, step: f64, threshold_km: f64) -> Vec<Conjunction> {
+        let mut found = ScreeningPipeline::with_threshold(threshold_km).screen(&sats, start, end, step).unwrap();
+        crate::sort_conjunctions(&mut found, crate::SortOrder::Ids);
+        found
+    }
+
+    fn propagator(norad_id: i32, raan_deg: f64, mean_anomaly_deg: f64) -> Trajectory {
+        let elements = MeanElements::new(
+            norad_id, 2460000.5, 0.0, 90.0, raan_deg, 0.0001, 0.0, mean_anomaly_deg, 15.0,
+        )
+        .unwrap();
+        Sgp4Propagator::from_mean_elements(elements).into()
+    }
+
+    #[test]
+    fn test_brent_finds_root() {
+        let root = brent(|x| Some(x * x - 2.0), 0.0, 2.0, -2.0, 2.0, 1e-12).unwrap();
+        assert!((root - 2f64.sqrt()).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_polar_crossing_tca() {
+        // Two polar orbits in planes 90 degrees apart meet over the poles
+        let sats = vec![propagator(1, 0.0, 0.0), propagator(2, 90.0, 0.0)];
+        let events = window(sats, 2460000.5, 2460000.5 + 0.1, 60.0, 50.0);
+        assert!(!events.is_empty());
+
+        for event in &events {
+            let tca = event.tca_jd.unwrap();
+            assert!((2460000.5..=2460000.6).contains(&tca));
+            assert!(event.distance_km < 50.0);
+            assert!(event.relative_velocity_km_s > 1.0);
+            let rtn = (event.radial_km.powi(2) + event.in_track_km.powi(2) + event.cross_track_km.powi(2)).sqrt();
+            assert!((rtn - event.distance_km).abs() < 1e-9);
+            assert!((event.time_to_tca_s.unwrap() - (tca - 2460000.5) * SECONDS_PER_DAY).abs() < 1e-3);
+            // polar planes 90 degrees apart cross at right angles over the pole
+            assert!((event.approach_angle_deg().unwrap() - 90.0).abs() < 1.0);
+        }
+    }
+
+    #[test]
+    fn test_refined_tca_beats_grid() {
+        let sats = vec![propagator(1, 0.0, 0.0), propagator(2, 90.0, 0.0)];
+        let coarse = window(sats.clone(), 2460000.5, 2460000.6, 300.0, 100.0);
+        let fine = window(sats, 2460000.5, 2460000.6, 10.0, 100.0);
+        assert_eq!(coarse.len(), fine.len());
+        for (c, f) in coarse.iter().zip(fine.iter()) {
+            assert!((c.tca_jd.unwrap() - f.tca_jd.unwrap()).abs() * SECONDS_PER_DAY < 0.1);
+            assert!((c.distance_km - f.distance_km).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_tca_is_range_rate_root_for_any_grid_phase() {
+        // shifting the grid moves the nearest sample by up to half a step, never the TCA
+        let sats = vec![propagator(1, 0.0, 0.0), propagator(2, 90.0, 0.0)];
+        let reference = window(sats.clone(), 2460000.5, 2460000.6, 120.0, 100.0);
+        assert!(!reference.is_empty());
+        for offset_s in [17.0, 45.0, 60.0, 101.0] {
+            let start = 2460000.5 - offset_s / SECONDS_PER_DAY;
+            let shifted = window(sats.clone(), start, 2460000.6, 120.0, 100.0);
+            for event in shifted.iter().filter(|e| e.tca_jd.unwrap() >= 2460000.5) {
+                let t = (event.tca_jd.unwrap() - 2460000.5) * SECONDS_PER_DAY;
+                let rate = range_rate(&sats[0].state_at(2460000.5, t).unwrap(), &sats[1].state_at(2460000.5, t).unwrap());
+                assert!(rate.abs() / event.relative_velocity_km_s < 1e-2, "range-rate {} at TCA", rate);
+                let same = reference.iter().find(|r| (r.tca_jd.unwrap() - event.tca_jd.unwrap()).abs() * SECONDS_PER_DAY < 1e-2);
+                assert!(same.is_some_and(|r| (r.distance_km - event.distance_km).abs() < 1e-4));
+            }
+        }
+    }
+
+    #[test]
+    fn test_filters_keep_real_events() {
+        let sats = vec![
+            propagator(1, 0.0, 0.0),
+            propagator(2, 90.0, 0.0),
+            propagator(3, 45.0, 180.0),
+            propagator(4, 10.0, 90.0),
+        ];
+        let (start, end) = (2460000.5, 2460000.6);
+        let baseline = window(sats.clone(), start, end, 60.0, 50.0);
+
+        let mut pipeline = ScreeningPipeline::with_threshold(50.0);
+        pipeline.filters = vec![
+            ScreeningFilter::AltitudeBand { pad_km: 10.0 },
+            ScreeningFilter::OrbitPath { pad_km: 10.0 },
+            ScreeningFilter::Time { pad_km: 20.0 },
+            ScreeningFilter::Geometric { pad_km: 0.0 },
+        ];
+        let filtered = pipeline.screen(&sats, start, end, 60.0).unwrap();
+
+        assert_eq!(filtered.len(), baseline.len());
+        assert_eq!(pipeline.last_stats[0], ("pairs".to_string(), 6));
+        let counts: Vec<usize> = pipeline.last_stats.iter().map(|(_, n)| *n).collect();
+        assert!(counts.windows(2).take(4).all(|w| w[1] <= w[0]));
+        assert!(counts[4] < counts[0]);
+    }
+
+    #[test]
+    fn test_gpu_backend_matches_cpu_or_falls_back() {
+        let sats: Vec<Trajectory> = (0..5).map(|k| propagator(k + 1, 45.0 * k as f64, 90.0 * k as f64)).collect();
+        let (start, end) = (2460000.5, 2460000.6);
+        let mut pipeline = ScreeningPipeline::with_threshold(50.0);
+        pipeline.filters = vec![ScreeningFilter::Geometric { pad_km: 0.0 }];
+        let mut cpu = pipeline.screen(&sats, start, end, 60.0).unwrap();
+        let cpu_stats = pipeline.last_stats.clone();
+
+        pipeline.backend = ScreeningBackend::from_name("GPU").unwrap();
+        let mut gpu = pipeline.screen(&sats, start, end, 60.0).unwrap();
+        let expected = if gpu::available() { ScreeningBackend::Gpu } else { ScreeningBackend::Cpu };
+        assert_eq!(pipeline.last_backend, expected);
+        assert_eq!(pipeline.last_stats, cpu_stats);
+        crate::sort_conjunctions(&mut cpu, crate::SortOrder::Ids);
+        crate::sort_conjunctions(&mut gpu, crate::SortOrder::Ids);
+        assert_eq!(
+            gpu.iter().map(|c| (c.norad_id_1, c.norad_id_2)).collect::<Vec<_>>(),
+            cpu.iter().map(|c| (c.norad_id_1, c.norad_id_2)).collect::<Vec<_>>()
+        );
+        assert!(ScreeningBackend::from_name("tpu").is_err());
+    }
+
+    #[test]
+    fn test_pair_selection_skips_pairs_before_filters() {
+        let selection = PairSelection::new([(2, 1)], Some(vec![1, 3]));
+        assert!(!selection.keeps(1, 2) && selection.keeps(3, 1) && selection.keeps(3, 4) && !selection.keeps(2, 4));
+        assert!(PairSelection::default().is_everything() && !selection.is_everything());
+
+        let sats = vec![propagator(1, 0.0, 0.0), propagator(2, 90.0, 0.0), propagator(3, 45.0, 180.0)];
+        let (start, end) = (2460000.5, 2460000.6);
+        let baseline = window(sats.clone(), start, end, 60.0, 50.0);
+        assert!(baseline.iter().any(|c| (c.norad_id_1, c.norad_id_2) == (1, 2)));
+
+        let mut pipeline = ScreeningPipeline::with_threshold(50.0);
+        pipeline.selection.exclude(2, 1);
+        let mut found = pipeline.screen(&sats, start, end, 60.0).unwrap();
+        crate::sort_conjunctions(&mut found, crate::SortOrder::Ids);
+        let expected: Vec<_> = baseline.iter().filter(|c| (c.norad_id_1, c.norad_id_2) != (1, 2)).collect();
+        assert_eq!(found.len(), expected.len());
+        assert!(found.iter().zip(expected).all(|(a, b)| a.tca_jd == b.tca_jd));
+        assert_eq!(pipeline.last_stats[0], ("pairs".to_string(), 2));
+    }
+
+    #[test]
+    fn test_progress_counts_pairs_and_honours_cancellation() {
+        let sats = vec![propagator(1, 0.0, 0.0), propagator(2, 90.0, 0.0), propagator(3, 45.0, 180.0)];
+        let (start, end) = (2460000.5, 2460000.6);
+        let progress = Progress::new(0, None);
+        let mut pipeline = ScreeningPipeline::with_threshold(50.0);
+        let found = pipeline.screen_with_progress(&sats, start, end, 60.0, &progress).unwrap();
+        assert_eq!(progress.snapshot(), (100.0, 3, found.len()));
+
+        progress.cancel();
+        assert!(pipeline.screen_with_progress(&sats, start, end, 60.0, &progress).is_err());
+    }
+
+    #[test]
+    fn test_ephemeris_matches_propagator() {
+        let (start, end) = (2460000.5, 2460000.6);
+        let sats = vec![propagator(1, 0.0, 0.0), propagator(2, 90.0, 0.0)];
+        let Trajectory::Sgp4(prop) = &sats[1] else { unreachable!() };
+
+        // tabulate object 2 every 30 s and screen the table instead
+        let samples = (0..=300)
+            .map(|k| {
+                let t = 30.0 * k as f64;
+                let state = sats[1].state_at(start, t).unwrap();
+                (Epoch::from_jd_utc(start).plus_seconds(t), state)
+            })
+            .collect();
+        let eph = Ephemeris::from_samples(prop.elements.norad_id, samples, "TEME", Interpolation::Hermite, 9).unwrap();
+        let mixed = vec![sats[0].clone(), eph.into()];
+
+        let expected = window(sats, start, end, 60.0, 50.0);
+        let found = window(mixed, start, end, 60.0, 50.0);
+        assert_eq!(found.len(), expected.len());
+        for (f, e) in found.iter().zip(&expected) {
+            assert!((f.tca_jd.unwrap() - e.tca_jd.unwrap()).abs() * SECONDS_PER_DAY < 0.01);
+            assert!((f.distance_km - e.distance_km).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_primaries_screen_only_against_catalog() {
+        let (start, end) = (2460000.5, 2460000.6);
+        let sats = vec![propagator(1, 0.0, 0.0), propagator(2, 90.0, 0.0), propagator(3, 45.0, 180.0)];
+        let Trajectory::Sgp4(prop) = &sats[1] else { unreachable!() };
+        let samples = (0..=300)
+            .map(|k| {
+                let t = 30.0 * k as f64;
+                (Epoch::from_jd_utc(start).plus_seconds(t), sats[1].state_at(start, t).unwrap())
+            })
+            .collect();
+        let eph = Ephemeris::from_samples(prop.elements.norad_id, samples, "TEME", Interpolation::Hermite, 9).unwrap();
+        let baseline = window(sats.clone(), start, end, 60.0, 50.0);
+
+        // the catalogue still holds object 2's own TLE, which the ephemeris replaces
+        let progress = Progress::new(0, None);
+        let mut pipeline = ScreeningPipeline::with_threshold(50.0);
+        let found = pipeline.screen_primaries(&[eph.into()], &sats, start, end, 60.0, &progress).unwrap();
+        assert_eq!(pipeline.last_stats[0], ("pairs".to_string(), 2));
+        assert_eq!(progress.snapshot().1, 3);
+        assert!(found.iter().all(|c| c.norad_id_1 == 2 && c.norad_id_2 != 2));
+        let expected: Vec<_> = baseline.iter().filter(|c| c.norad_id_1 == 2 || c.norad_id_2 == 2).collect();
+        assert!(!expected.is_empty());
+        assert_eq!(found.len(), expected.len());
+        for event in &found {
+            let same = expected.iter().find(|e| (e.tca_jd.unwrap() - event.tca_jd.unwrap()).abs() * SECONDS_PER_DAY < 0.01);
+            assert!(same.is_some_and(|e| (e.distance_km - event.distance_km).abs() < 1e-3));
+        }
+    }
+
+    #[test]
+    fn test_tiled_screen_matches_untiled() {
+        let (start, end) = (2460000.5, 2460000.6);
+        let sats: Vec<Trajectory> = (0..5).map(|k| propagator(k + 1, 45.0 * k as f64, 90.0 * k as f64)).collect();
+        let expected = window(sats.clone(), start, end, 60.0, 50.0);
+        assert!(!expected.is_empty());
+
+        let progress = Progress::new(0, None);
+        let mut pipeline = ScreeningPipeline::with_threshold(50.0);
+        let mut found = pipeline.screen_tiled(&sats, start, end, 60.0, 2, &progress).unwrap();
+        crate::sort_conjunctions(&mut found, crate::SortOrder::Ids);
+        assert_eq!(pipeline.last_stats[0], ("pairs".to_string(), 10));
+        assert_eq!(progress.snapshot().1, 10);
+        assert_eq!(found.len(), expected.len());
+        for (f, e) in found.iter().zip(&expected) {
+            assert_eq!((f.norad_id_1, f.norad_id_2), (e.norad_id_1, e.norad_id_2));
+            assert!((f.distance_km - e.distance_km).abs() < 1e-9);
+        }
+        assert!(pipeline.screen_tiled(&sats, start, end, 60.0, 0, &progress).is_err());
+    }
+}
@@ -0,0 +1,445 @@
+//! Sensor field-of-view access
+//!
+//! A sensor sits on a ground station or rides on a satellite and looks along
+//! a fixed boresight: azimuth/elevation in the station's ENU frame, or a
+//! direction in the host's RTN frame (radial, along-track, cross-track).
+//! Its field of view is a cone or a rectangle; the rectangle's width is
+//! measured about the local vertical (station up, host radial). An object
+//! is in view when it lies in the FOV, within the optional maximum range
+//! and with a clear line of sight: above the horizon for ground sensors,
+//! not behind the Earth for space sensors.
+//!
+//! Access search samples every object on a shared time grid (the sensor
+//! frame is computed once per step) in parallel, then bisects each
+//! entry and exit time.
+
+use nalgebra::Vector3;
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use rayon::prelude::*;
+
+use crate::constants::SECONDS_PER_DAY;
+use crate::error::OrbitError;
+use crate::frames;
+use crate::ground_station::GroundStation;
+#[cfg(feature = "python")]
+use crate::ground_station::PassTarget;
+use crate::parallel;
+use crate::screening::Trajectory;
+use crate::visibility;
+
+/// Time tolerance of the entry/exit bisection (seconds)
+const TIME_TOLERANCE_S: f64 = 1.0e-2;
+
+/// Field of view, half-angles in radians
+#[derive(Clone, Copy, Debug)]
+pub enum FieldOfView {
+    Cone { half_angle: f64 },
+    Rectangle { half_width: f64, half_height: f64 },
+}
+
+impl FieldOfView {
+    /// Cone of `half_angle` or `rectangle` of (half-width, half-height), in degrees; exactly one is given
+    pub fn from_degrees(half_angle: Option<f64>, rectangle: Option<(f64, f64)>) -> Result<Self, OrbitError> {
+        let valid = |angle: f64| angle > 0.0 && angle < 90.0;
+        match (half_angle, rectangle) {
+            (Some(half), None) if valid(half) => Ok(FieldOfView::Cone { half_angle: half.to_radians() }),
+            (None, Some((width, height))) if valid(width) && valid(height) => Ok(FieldOfView::Rectangle {
+                half_width: width.to_radians(),
+                half_height: height.to_radians(),
+            }),
+            (Some(_), Some(_)) | (None, None) => Err(OrbitError::InvalidInput(
+                "Give exactly one of half_angle (cone) or rectangle (half-width, half-height)".to_string(),
+            )),
+            _ => Err(OrbitError::InvalidInput("FOV half-angles must be in (0, 90) degrees".to_string())),
+        }
+    }
+}
+
+/// Where the sensor is mounted and how its boresight is given
+#[derive(Clone)]
+#[allow(clippy::large_enum_variant)]
+pub enum Platform {
+    /// Boresight as an ENU unit vector
+    Ground { station: GroundStation, boresight: Vector3<f64> },
+    /// Boresight as an RTN unit vector of the host
+    Space { host: Trajectory, boresight: Vector3<f64> },
+}
+
+/// Sensor frame at one instant, in TEME
+#[derive(Clone, Copy, Debug)]
+pub struct Pointing {
+    origin: Vector3<f64>,
+    boresight: Vector3<f64>,
+    /// FOV width and height axes, perpendicular to the boresight
+    across: Vector3<f64>,
+    along: Vector3<f64>,
+    /// Local up for ground sensors (horizon check); None in space
+    up: Option<Vector3<f64>>,
+}
+
+/// Ground or space-based sensor with a cone or rectangular field of view
+#[cfg_attr(feature = "python", pyclass)]
+#[derive(Clone)]
+pub struct Sensor {
+    pub platform: Platform,
+    pub fov: FieldOfView,
+    pub max_range_km: Option<f64>,
+    pub name: Option<String>,
+}
+
+/// Unit vectors (across, along) spanning the plane normal to `boresight`
+///
+/// `across` is horizontal with respect to `vertical`; `fallback` takes its
+/// place when the boresight is along the vertical.
+fn fov_axes(boresight: &Vector3<f64>, vertical: &Vector3<f64>, fallback: &Vector3<f64>) -> (Vector3<f64>, Vector3<f64>) {
+    let across = vertical.cross(boresight);
+    let across = if across.norm() > 1e-9 { across.normalize() } else { fallback.cross(boresight).normalize() };
+    (across, boresight.cross(&across))
+}
+
+impl Sensor {
+    /// Ground sensor pointing at `azimuth`/`elevation` (deg) from a station
+    pub fn ground(station: GroundStation, azimuth: f64, elevation: f64, fov: FieldOfView, max_range_km: Option<f64>) -> Self {
+        let (az, el) = (azimuth.to_radians(), elevation.to_radians());
+        let boresight = Vector3::new(el.cos() * az.sin(), el.cos() * az.cos(), el.sin());
+        Sensor { platform: Platform::Ground { station, boresight }, fov, max_range_km, name: None }
+    }
+
+    /// Space sensor on `host` pointing along an RTN direction
+    pub fn space(host: Trajectory, boresight: Vector3<f64>, fov: FieldOfView, max_range_km: Option<f64>) -> Result<Self, OrbitError> {
+        if boresight.norm().is_nan() || boresight.norm() == 0.0 {
+            return Err(OrbitError::InvalidInput("boresight must be a non-zero vector".to_string()));
+        }
+        Ok(Sensor { platform: Platform::Space { host, boresight: boresight.normalize() }, fov, max_range_km, name: None })
+    }
+
+    /// Whether `target` is in view at Julian date `jd`
+    pub fn in_view(&self, target: &Trajectory, jd: f64) -> bool {
+        self.sees_at(target, jd, 0.0)
+    }
+
+    /// Sensor frame `seconds` after `start_jd`; None when the host cannot be propagated
+    pub fn pointing(&self, start_jd: f64, seconds: f64) -> Option<Pointing> {
+        let jd = start_jd + seconds / SECONDS_PER_DAY;
+        match &self.platform {
+            Platform::Ground { station, boresight } => {
+                // positions rotate rigidly between ECEF and TEME
+                let to_teme = |x: &Vector3<f64>| frames::ecef_to_teme_state(x, &Vector3::zeros(), jd, 0.0, 0.0).0;
+                let [east, north, up] = station.enu_axes().map(|axis| to_teme(&axis));
+                let b = east * boresight.x + north * boresight.y + up * boresight.z;
+                let (across, along) = fov_axes(&b, &up, &north);
+                Some(Pointing { origin: to_teme(&station.ecef_km()), boresight: b, across, along, up: Some(up) })
+            }
+            Platform::Space { host, boresight } => {
+                let (r, v) = host.state_at(start_jd, seconds)?;
+                let radial = r.normalize();
+                let cross_track = r.cross(&v).normalize();
+                let along_track = cross_track.cross(&radial);
+                let b = radial * boresight.x + along_track * boresight.y + cross_track * boresight.z;
+                let (across, along) = fov_axes(&b, &radial, &along_track);
+                Some(Pointing { origin: r, boresight: b, across, along, up: None })
+            }
+        }
+    }
+
+    /// Whether a TEME position is in view from `pointing`
+    pub fn sees(&self, pointing: &Pointing, position: &Vector3<f64>) -> bool {
+        let d = position - pointing.origin;
+        let range = d.norm();
+        if range == 0.0 || self.max_range_km.is_some_and(|max| range > max) {
+            return false;
+        }
+        let clear = match pointing.up {
+            Some(up) => d.dot(&up) > 0.0,
+            None => visibility::line_of_sight(&pointing.origin, position, 0.0),
+        };
+        let depth = d.dot(&pointing.boresight);
+        if !clear || depth <= 0.0 {
+            return false;
+        }
+        match self.fov {
+            FieldOfView::Cone { half_angle } => (depth / range).clamp(-1.0, 1.0).acos() <= half_angle,
+            FieldOfView::Rectangle { half_width, half_height } => {
+                d.dot(&pointing.across).atan2(depth).abs() <= half_width
+                    && d.dot(&pointing.along).atan2(depth).abs() <= half_height
+            }
+        }
+    }
+
+    fn sees_at(&self, target: &Trajectory, start_jd: f64, seconds: f64) -> bool {
+        match (self.pointing(start_jd, seconds), target.state_at(start_jd, seconds)) {
+            (Some(pointing), Some((r, _))) => self.sees(&pointing, &r),
+            _ => false,
+        }
+    }
+
+    /// Time in [a, b] where visibility switches from `visible_at_a`, by bisection
+    fn transition(&self, target: &Trajectory, start_jd: f64, mut a: f64, mut b: f64, visible_at_a: bool) -> f64 {
+        while b - a > TIME_TOLERANCE_S {
+            let mid = 0.5 * (a + b);
+            if self.sees_at(target, start_jd, mid) == visible_at_a {
+                a = mid;
+            } else {
+                b = mid;
+            }
+        }
+        0.5 * (a + b)
+    }
+
+    /// Access intervals of every target over [start_jd, end_jd], sampled every `step` seconds
+    pub fn access(&self, targets: &[Trajectory], start_jd: f64, end_jd: f64, step: f64) -> Vec<Access> {
+        let duration = (end_jd - start_jd) * SECONDS_PER_DAY;
+        let n_steps = (duration / step).ceil() as usize;
+        let times: Vec<f64> = (0..=n_steps).map(|k| (k as f64 * step).min(duration)).collect();
+        let pointings: Vec<Option<Pointing>> = times.par_iter().map(|&t| self.pointing(start_jd, t)).collect();
+
+        targets
+            .par_iter()
+            .flat_map_iter(|target| {
+                let mut accesses = Vec::new();
+                let mut open: Option<(f64, f64)> = None; // (entry, closest range)
+                let mut previous = false;
+                for (k, (&t, pointing)) in times.iter().zip(&pointings).enumerate() {
+                    let position = target.state_at(start_jd, t).map(|(r, _)| r);
+                    let (visible, range) = match (pointing, position) {
+                        (Some(p), Some(r)) if self.sees(p, &r) => (true, (r - p.origin).norm()),
+                        _ => (false, f64::INFINITY),
+                    };
+                    if visible && !previous {
+                        let entry = if k == 0 { t } else { self.transition(target, start_jd, times[k - 1], t, false) };
+                        open = Some((entry, range));
+                    } else if visible {
+                        open = open.map(|(entry, closest)| (entry, closest.min(range)));
+                    } else if previous {
+                        if let Some((entry, closest)) = open.take() {
+                            let exit = self.transition(target, start_jd, times[k - 1], t, true);
+                            accesses.push(Access::new(target.norad_id(), start_jd, entry, exit, closest));
+                        }
+                    }
+                    previous = visible;
+                }
+                if let Some((entry, closest)) = open {
+                    accesses.push(Access::new(target.norad_id(), start_jd, entry, duration, closest));
+                }
+                accesses
+            })
+            .collect()
+    }
+}
+
+/// Interval during which one object is in a sensor's field of view
+#[cfg_attr(feature = "python", pyclass(get_all))]
+#[derive(Clone, Debug)]
+pub struct Access {
+    pub norad_id: i32,
+    pub start_jd: f64,     // entry into the FOV (or window start if already in view)
+    pub end_jd: f64,       // exit (or window end if still in view)
+    pub min_range_km: f64, // closest sampled range while in view
+}
+
+impl Access {
+    fn new(norad_id: i32, start_jd: f64, entry: f64, exit: f64, min_range_km: f64) -> Self {
+        Access {
+            norad_id,
+            start_jd: start_jd + entry / SECONDS_PER_DAY,
+            end_jd: start_jd + exit / SECONDS_PER_DAY,
+            min_range_km,
+        }
+    }
+
+    /// Access duration in seconds
+    pub fn duration(&self) -> f64 {
+        (self.end_jd - self.start_jd) * SECONDS_PER_DAY
+    }
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl Access {
+    /// Access duration in seconds
+    #[getter]
+    #[pyo3(name = "duration")]
+    fn py_duration(&self) -> f64 {
+        self.duration()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Access(norad_id={}, start_jd={:.6}, end_jd={:.6}, min_range={:.1}km)",
+            self.norad_id, self.start_jd, self.end_jd, self.min_range_km
+        )
+    }
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl Sensor {
+    /// Ground sensor pointing at `azimuth`/`elevation` (deg) from a station
+    ///
+    /// The FOV is a cone of `half_angle` (deg) or a `rectangle` of
+    /// (half-width, half-height) in degrees, width measured in azimuth.
+    #[staticmethod]
+    #[pyo3(name = "ground", signature = (station, azimuth, elevation, half_angle = None, rectangle = None, max_range_km = None, name = None))]
+    #[allow(clippy::too_many_arguments)]
+    fn py_ground(
+        station: GroundStation,
+        azimuth: f64,
+        elevation: f64,
+        half_angle: Option<f64>,
+        rectangle: Option<(f64, f64)>,
+        max_range_km: Option<f64>,
+        name: Option<String>,
+    ) -> PyResult<Self> {
+        let fov = FieldOfView::from_degrees(half_angle, rectangle)?;
+        Ok(Sensor { name, ..Sensor::ground(station, azimuth, elevation, fov, max_range_km) })
+    }
+
+    /// Space sensor on `host` (a propagator, an ephemeris or a TLE) pointing along an RTN direction
+    ///
+    /// The default boresight [-1, 0, 0] looks at nadir.
+    #[staticmethod]
+    #[pyo3(name = "space", signature = (host, boresight = [-1.0, 0.0, 0.0], half_angle = None, rectangle = None, max_range_km = None, name = None))]
+    fn py_space(
+        host: PassTarget,
+        boresight: [f64; 3],
+        half_angle: Option<f64>,
+        rectangle: Option<(f64, f64)>,
+        max_range_km: Option<f64>,
+        name: Option<String>,
+    ) -> PyResult<Self> {
+        let fov = FieldOfView::from_degrees(half_angle, rectangle)?;
+        let sensor = Sensor::space(host.into_trajectory()?, Vector3::from(boresight), fov, max_range_km)?;
+        Ok(Sensor { name, ..sensor })
+    }
+
+    #[getter]
+    fn name(&self) -> Option<String> {
+        self.name.clone()
+    }
+
+    #[setter]
+    fn set_name(&mut self, name: Option<String>) {
+        self.name = name;
+    }
+
+    /// Whether `target` is in view at Julian date `jd`
+    #[pyo3(name = "in_view")]
+    fn py_in_view(&self, target: PassTarget, jd: f64) -> PyResult<bool> {
+        Ok(self.in_view(&target.into_trajectory()?, jd))
+    }
+
+    fn __repr__(&self) -> String {
+        let mount = match self.platform {
+            Platform::Ground { .. } => "ground",
+            Platform::Space { .. } => "space",
+        };
+        let fov = match self.fov {
+            FieldOfView::Cone { half_angle } => format!("cone={:.2}deg", half_angle.to_degrees()),
+            FieldOfView::Rectangle { half_width, half_height } => {
+                format!("rectangle={:.2}x{:.2}deg", half_width.to_degrees(), half_height.to_degrees())
+            }
+        };
+        format!(
+            "Sensor({}{}, {})",
+            self.name.as_ref().map_or(String::new(), |n| format!("{:?}, ", n)),
+            mount,
+            fov
+        )
+    }
+}
+
+/// Intervals during which `targets` are in a sensor's field of view
+///
+/// Objects are sampled every `step` seconds between two Julian dates, so
+/// accesses shorter than a step can be missed. Entries and exits are
+/// refined to 10 ms. Returns accesses ordered by object, then time.
+pub fn sensor_access(sensor: &Sensor, targets: &[Trajectory], start: f64, end: f64, step: f64) -> Result<Vec<Access>, OrbitError> {
+    if end <= start {
+        return Err(OrbitError::InvalidInput("end must be after start".to_string()));
+    }
+    if step <= 0.0 {
+        return Err(OrbitError::InvalidInput("step must be positive (seconds)".to_string()));
+    }
+    let mut accesses = parallel::install(|| sensor.access(targets, start, end, step));
+    accesses.sort_by(|a, b| a.norad_id.cmp(&b.norad_id).then(a.start_jd.total_cmp(&b.start_jd)));
+    Ok(accesses)
+}
+
+/// Intervals during which catalog objects are in a sensor's field of view
+///
+/// `catalog` holds propagators, ephemerides or (line1, line2) TLEs; objects
+/// are sampled every `step` seconds between two Julian dates, so accesses
+/// shorter than a step can be missed. Entries and exits are refined to
+/// 10 ms. Returns accesses ordered by object, then time.
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(name = "sensor_access", signature = (sensor, catalog, start, end, step = 10.0))]
+pub(crate) fn py_sensor_access(
+    py: Python<'_>,
+    sensor: &Sensor,
+    catalog: Vec<PassTarget>,
+    start: f64,
+    end: f64,
+    step: f64,
+) -> PyResult<Vec<Access>> {
+    let targets = catalog.into_iter().map(PassTarget::into_trajectory).collect::<Result<Vec<_>, _>>()?;
+    Ok(py.allow_threads(|| sensor_access(sensor, &targets, start, end, step))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sgp4::{MeanElements, Sgp4Propagator};
+
+    fn polar(norad_id: i32, mean_anomaly_deg: f64) -> Trajectory {
+        let elements = MeanElements::new(norad_id, 2460000.5, 0.0, 90.0, 0.0, 0.0001, 0.0, mean_anomaly_deg, 15.0).unwrap();
+        Sgp4Propagator::from_mean_elements(elements).into()
+    }
+
+    #[test]
+    fn test_rectangle_axes_follow_the_vertical() {
+        let sensor = Sensor {
+            platform: Platform::Ground {
+                station: GroundStation::from_geodetic(0.0, 0.0, 0.0),
+                boresight: Vector3::new(0.0, 0.0, 1.0),
+            },
+            fov: FieldOfView::Rectangle { half_width: 0.2, half_height: 0.05 },
+            max_range_km: Some(2000.0),
+            name: None,
+        };
+        let p = sensor.pointing(2460000.5, 0.0).unwrap();
+        let up = p.up.unwrap();
+        assert!((p.boresight - up).norm() < 1e-12);
+        assert!(p.across.dot(&p.boresight).abs() < 1e-12 && p.along.dot(&p.across).abs() < 1e-12);
+        let at = |x: f64, y: f64, range: f64| p.origin + (p.boresight + p.across * x + p.along * y).normalize() * range;
+        assert!(sensor.sees(&p, &at(0.15, 0.0, 1000.0)));
+        assert!(!sensor.sees(&p, &at(0.0, 0.15, 1000.0)));
+        assert!(!sensor.sees(&p, &at(0.0, 0.0, 2500.0)));
+        assert!(!sensor.sees(&p, &(p.origin - up * 500.0)));
+    }
+
+    #[test]
+    fn test_space_sensor_access_and_earth_blocking() {
+        // a trailing satellite on the same orbit is seen along-track, never at nadir
+        let host = polar(1, 10.0);
+        let ahead = polar(2, 12.0);
+        let forward = Sensor {
+            platform: Platform::Space { host: host.clone(), boresight: Vector3::new(0.0, 1.0, 0.0) },
+            fov: FieldOfView::Cone { half_angle: 5f64.to_radians() },
+            max_range_km: None,
+            name: None,
+        };
+        let accesses = forward.access(std::slice::from_ref(&ahead), 2460000.5, 2460000.55, 60.0);
+        assert_eq!(accesses.len(), 1);
+        assert!((accesses[0].end_jd - accesses[0].start_jd - 0.05).abs() < 1e-9);
+        assert!((accesses[0].min_range_km - 2.0 * 6900.0 * 1f64.to_radians().sin()).abs() < 30.0);
+
+        assert!(sensor_access(&forward, std::slice::from_ref(&ahead), 2460000.55, 2460000.5, 60.0).is_err());
+
+        let fov = FieldOfView::from_degrees(Some(5.0), None).unwrap();
+        let nadir = Sensor::space(host, -Vector3::x(), fov, None).unwrap();
+        assert!(sensor_access(&nadir, &[ahead], 2460000.5, 2460000.55, 60.0).unwrap().is_empty());
+        assert!(FieldOfView::from_degrees(Some(5.0), Some((1.0, 1.0))).is_err());
+    }
+}
@@ -0,0 +1,1323 @@
+//! SGP4/SDP4 analytical propagator
+//!
+//! Port of the Vallado et al. (2006) "Revisiting Spacetrack Report #3"
+//! reference implementation using WGS-72 constants and the improved
+//! ("i") operation mode. Near-earth objects use SGP4, objects with a
+//! period of 225 minutes or more use the SDP4 deep-space extensions.
+
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use std::f64::consts::PI;
+use std::fmt;
+
+use crate::tle;
+#[cfg(feature = "python")]
+use crate::epoch::Epoch;
+use crate::error::OrbitError;
+#[cfg(feature = "python")]
+use crate::Satellite;
+
+const TWO_PI: f64 = 2.0 * PI;
+const X2O3: f64 = 2.0 / 3.0;
+const DEG2RAD: f64 = PI / 180.0;
+const MINUTES_PER_DAY: f64 = 1440.0;
+const RPTIM: f64 = 4.3752690880113e-3; // earth rotation rate, rad/min
+
+// WGS-72 gravity model (the model TLEs are generated with)
+const MU: f64 = 398600.8; // km^3/s^2
+const RADIUS_EARTH_KM: f64 = 6378.135;
+const J2: f64 = 0.001082616;
+const J3: f64 = -0.00000253881;
+const J4: f64 = -0.00000165597;
+const J3OJ2: f64 = J3 / J2;
+
+/// Square root of the gravitational parameter in earth radii^1.5 / min
+fn xke() -> f64 {
+    60.0 / (RADIUS_EARTH_KM * RADIUS_EARTH_KM * RADIUS_EARTH_KM / MU).sqrt()
+}
+
+/// Propagation failure reported by SGP4
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Sgp4Error {
+    /// Mean eccentricity left the range [0, 1)
+    MeanEccentricity(f64),
+    /// Mean motion became negative
+    MeanMotion(f64),
+    /// Perturbed eccentricity left the range [0, 1]
+    PerturbedEccentricity(f64),
+    /// Semi-latus rectum became negative
+    SemiLatusRectum(f64),
+    /// Orbit radius dropped below the Earth's surface
+    Decayed,
+}
+
+impl fmt::Display for Sgp4Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Sgp4Error::MeanEccentricity(e) => write!(f, "mean eccentricity out of range: {}", e),
+            Sgp4Error::MeanMotion(n) => write!(f, "mean motion is negative: {}", n),
+            Sgp4Error::PerturbedEccentricity(e) => {
+                write!(f, "perturbed eccentricity out of range: {}", e)
+            }
+            Sgp4Error::SemiLatusRectum(p) => write!(f, "semi-latus rectum is negative: {}", p),
+            Sgp4Error::Decayed => write!(f, "satellite has decayed"),
+        }
+    }
+}
+
+impl std::error::Error for Sgp4Error {}
+
+#[cfg(feature = "python")]
+impl From<Sgp4Error> for PyErr {
+    fn from(err: Sgp4Error) -> PyErr {
+        OrbitError::from(err).into()
+    }
+}
+
+/// SGP4 mean elements as carried by a two-line element set
+#[cfg_attr(feature = "python", pyclass(get_all, set_all))]
+#[derive(Clone, Debug, PartialEq)]
+pub struct MeanElements {
+    pub norad_id: i32,
+    pub name: Option<String>, // title line of a three-line element set
+    pub international_designator: Option<String>, // COSPAR id, "YYYY-NNNP"
+    pub epoch_jd: f64, // Julian date (UTC)
+    pub bstar: f64, // 1/earth radii
+    pub inclination_deg: f64,
+    pub raan_deg: f64,
+    pub eccentricity: f64,
+    pub arg_perigee_deg: f64,
+    pub mean_anomaly_deg: f64,
+    pub mean_motion_rev_per_day: f64,
+}
+
+impl MeanElements {
+    /// Validated mean elements (eccentricity in [0, 1), positive mean motion)
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        norad_id: i32,
+        epoch_jd: f64,
+        bstar: f64,
+        inclination_deg: f64,
+        raan_deg: f64,
+        eccentricity: f64,
+        arg_perigee_deg: f64,
+        mean_anomaly_deg: f64,
+        mean_motion_rev_per_day: f64,
+    ) -> Result<Self, OrbitError> {
+        if !(0.0..1.0).contains(&eccentricity) {
+            return Err(OrbitError::InvalidState("Eccentricity must be in [0, 1)".to_string()));
+        }
+        if mean_motion_rev_per_day <= 0.0 {
+            return Err(OrbitError::InvalidState("Mean motion must be positive".to_string()));
+        }
+
+        Ok(MeanElements {
+            norad_id,
+            name: None,
+            international_designator: None,
+            epoch_jd,
+            bstar,
+            inclination_deg,
+            raan_deg,
+            eccentricity,
+            arg_perigee_deg,
+            mean_anomaly_deg,
+            mean_motion_rev_per_day,
+        })
+    }
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl MeanElements {
+    #[new]
+    #[allow(clippy::too_many_arguments)]
+    fn py_new(
+        norad_id: i32,
+        epoch_jd: f64,
+        bstar: f64,
+        inclination_deg: f64,
+        raan_deg: f64,
+        eccentricity: f64,
+        arg_perigee_deg: f64,
+        mean_anomaly_deg: f64,
+        mean_motion_rev_per_day: f64,
+    ) -> PyResult<Self> {
+        Ok(MeanElements::new(
+            norad_id,
+            epoch_jd,
+            bstar,
+            inclination_deg,
+            raan_deg,
+            eccentricity,
+            arg_perigee_deg,
+            mean_anomaly_deg,
+            mean_motion_rev_per_day,
+        )?)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "MeanElements(norad_id={}, epoch_jd={:.6}, inc={:.4}deg, ecc={:.7}, n={:.8}rev/day)",
+            self.norad_id,
+            self.epoch_jd,
+            self.inclination_deg,
+            self.eccentricity,
+            self.mean_motion_rev_per_day
+        )
+    }
+}
+
+/// Julian date from a calendar date (valid 1900-2100)
+pub fn julian_date(year: i32, month: i32, day: i32, hour: i32, minute: i32, second: f64) -> f64 {
+    let (y, m, d) = (year as f64, month as f64, day as f64);
+    367.0 * y - (7.0 * (y + ((m + 9.0) / 12.0).floor()) * 0.25).floor()
+        + (275.0 * m / 9.0).floor()
+        + d
+        + 1721013.5
+        + ((second / 60.0 + minute as f64) / 60.0 + hour as f64) / 24.0
+}
+
+/// Greenwich mean sidereal time (IAU-82) in radians
+pub fn gstime(jd_ut1: f64) -> f64 {
+    let tut1 = (jd_ut1 - 2451545.0) / 36525.0;
+    let seconds = -6.2e-6 * tut1 * tut1 * tut1
+        + 0.093104 * tut1 * tut1
+        + (876600.0 * 3600.0 + 8640184.812866) * tut1
+        + 67310.54841;
+    let theta = (seconds * DEG2RAD / 240.0) % TWO_PI;
+    if theta < 0.0 {
+        theta + TWO_PI
+    } else {
+        theta
+    }
+}
+
+/// Lunar-solar terms used by the deep-space (SDP4) branch
+#[derive(Clone, Debug, Default)]
+struct DeepSpace {
+    // periodic coefficients
+    e3: f64, ee2: f64, se2: f64, se3: f64, sgh2: f64, sgh3: f64, sgh4: f64,
+    sh2: f64, sh3: f64, si2: f64, si3: f64, sl2: f64, sl3: f64, sl4: f64,
+    xgh2: f64, xgh3: f64, xgh4: f64, xh2: f64, xh3: f64, xi2: f64, xi3: f64,
+    xl2: f64, xl3: f64, xl4: f64, zmol: f64, zmos: f64,
+
+    // secular rates
+    dedt: f64, didt: f64, dmdt: f64, dnodt: f64, domdt: f64,
+
+    // resonance terms (irez: 0 none, 1 synchronous, 2 half-day)
+    irez: u8,
+    d2201: f64, d2211: f64, d3210: f64, d3222: f64, d4410: f64,
+    d4422: f64, d5220: f64, d5232: f64, d5421: f64, d5433: f64,
+    del1: f64, del2: f64, del3: f64, xfact: f64, xlamo: f64,
+}
+
+/// Intermediate quantities shared by dscom and dsinit
+#[derive(Default)]
+struct Dscom {
+    sinim: f64, cosim: f64, emsq: f64,
+    s1: f64, s2: f64, s3: f64, s4: f64, s5: f64,
+    ss1: f64, ss2: f64, ss3: f64, ss4: f64, ss5: f64,
+    sz1: f64, sz3: f64, sz11: f64, sz13: f64, sz21: f64, sz23: f64, sz31: f64, sz33: f64,
+    z1: f64, z3: f64, z11: f64, z13: f64, z21: f64, z23: f64, z31: f64, z33: f64,
+}
+
+/// Initialised SGP4 coefficients for one element set
+#[derive(Clone, Debug)]
+struct Sgp4Record {
+    ecco: f64, argpo: f64, inclo: f64, mo: f64, no: f64, nodeo: f64, bstar: f64,
+    isimp: bool,
+    aycof: f64, con41: f64, cc1: f64, cc4: f64, cc5: f64, d2: f64, d3: f64, d4: f64,
+    delmo: f64, eta: f64, argpdot: f64, omgcof: f64, sinmao: f64,
+    t2cof: f64, t3cof: f64, t4cof: f64, t5cof: f64,
+    x1mth2: f64, x7thm1: f64, mdot: f64, nodedot: f64, xlcof: f64, xmcof: f64, nodecf: f64,
+    gsto: f64,
+    deep: Option<DeepSpace>,
+}
+
+/// SGP4 propagator for a single satellite
+#[cfg_attr(feature = "python", pyclass)]
+#[derive(Clone)]
+pub struct Sgp4Propagator {
+    pub elements: MeanElements,
+
+    record: Sgp4Record,
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl Sgp4Propagator {
+    /// Build a propagator from the two lines of a TLE
+    #[new]
+    fn py_new(line1: &str, line2: &str) -> PyResult<Self> {
+        Ok(Sgp4Propagator::new(line1, line2)?)
+    }
+
+    /// Build a propagator from already parsed mean elements
+    #[staticmethod]
+    fn from_elements(elements: MeanElements) -> Self {
+        Sgp4Propagator::from_mean_elements(elements)
+    }
+
+    #[getter]
+    fn elements(&self) -> MeanElements {
+        self.elements.clone()
+    }
+
+    #[getter]
+    fn norad_id(&self) -> i32 {
+        self.elements.norad_id
+    }
+
+    #[getter]
+    fn epoch_jd(&self) -> f64 {
+        self.elements.epoch_jd
+    }
+
+    /// True when the SDP4 deep-space branch is used (period >= 225 min)
+    #[pyo3(name = "is_deep_space")]
+    fn py_is_deep_space(&self) -> bool {
+        self.is_deep_space()
+    }
+
+    /// Propagate to minutes since the element epoch (TEME frame)
+    fn propagate(&self, minutes_since_epoch: f64) -> PyResult<Satellite> {
+        Ok(self.propagate_state(minutes_since_epoch)?)
+    }
+
+    /// Propagate to an absolute Julian date (TEME frame)
+    fn propagate_to(&self, jd: f64) -> PyResult<Satellite> {
+        self.propagate(self.minutes_since_epoch(jd))
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Sgp4Propagator(norad_id={}, epoch_jd={:.6}, deep_space={})",
+            self.elements.norad_id,
+            self.elements.epoch_jd,
+            self.record.deep.is_some()
+        )
+    }
+}
+
+impl Sgp4Propagator {
+    /// Build a propagator from the two lines of a TLE (checksums validated)
+    pub fn new(line1: &str, line2: &str) -> Result<Self, OrbitError> {
+        let elements = tle::parse_lines(line1, line2, true).map_err(OrbitError::TleParse)?;
+        Ok(Sgp4Propagator::from_mean_elements(elements))
+    }
+
+    /// Initialise the SGP4 coefficients for a set of mean elements
+    pub fn from_mean_elements(elements: MeanElements) -> Self {
+        let record = sgp4_init(&elements);
+        Sgp4Propagator { elements, record }
+    }
+
+    /// True when the SDP4 deep-space branch is used (period >= 225 min)
+    pub fn is_deep_space(&self) -> bool {
+        self.record.deep.is_some()
+    }
+
+    /// Minutes between the element epoch and a Julian date
+    pub fn minutes_since_epoch(&self, jd: f64) -> f64 {
+        (jd - self.elements.epoch_jd) * MINUTES_PER_DAY
+    }
+
+    /// Propagate to minutes since epoch, returning a Satellite state
+    #[cfg(feature = "python")]
+    pub fn propagate_state(&self, minutes_since_epoch: f64) -> Result<Satellite, Sgp4Error> {
+        let (position, velocity) = sgp4(&self.record, minutes_since_epoch)?;
+        let epoch = Epoch::from_jd_utc(self.elements.epoch_jd + minutes_since_epoch / MINUTES_PER_DAY);
+        Ok(Satellite {
+            name: self.elements.name.clone(),
+            international_designator: self.elements.international_designator.clone(),
+            ..Satellite::from_state(self.elements.norad_id, position, velocity, Some(epoch))
+        })
+    }
+
+    /// Raw TEME position (km) and velocity (km/s) at minutes since epoch
+    pub fn position_velocity(&self, minutes_since_epoch: f64) -> Result<([f64; 3], [f64; 3]), Sgp4Error> {
+        sgp4(&self.record, minutes_since_epoch)
+    }
+}
+
+/// Brouwer mean motion from the Kozai mean motion a TLE carries (rad/min)
+fn unkozai(no_kozai: f64, ecco: f64, cosio: f64) -> f64 {
+    let omeosq = 1.0 - ecco * ecco;
+    let ak = (xke() / no_kozai).powf(X2O3);
+    let d1 = 0.75 * J2 * (3.0 * cosio * cosio - 1.0) / (omeosq.sqrt() * omeosq);
+    let mut del = d1 / (ak * ak);
+    let adel = ak * (1.0 - del * del - del * (1.0 / 3.0 + 134.0 * del * del / 81.0));
+    del = d1 / (adel * adel);
+    no_kozai / (1.0 + del)
+}
+
+/// Brouwer mean motion (rev/day) for a Kozai mean motion, as SGP4 recovers it
+pub fn kozai_to_brouwer(mean_motion_rev_per_day: f64, eccentricity: f64, inclination_deg: f64) -> f64 {
+    let scale = MINUTES_PER_DAY / TWO_PI;
+    unkozai(mean_motion_rev_per_day / scale, eccentricity, (inclination_deg * DEG2RAD).cos()) * scale
+}
+
+/// Kozai mean motion (rev/day) that SGP4 maps to the given Brouwer mean motion
+pub fn brouwer_to_kozai(mean_motion_rev_per_day: f64, eccentricity: f64, inclination_deg: f64) -> f64 {
+    // the two differ by O(J2), so fixed-point iteration converges in a few steps
+    let mut kozai = mean_motion_rev_per_day;
+    for _ in 0..20 {
+        let step = mean_motion_rev_per_day - kozai_to_brouwer(kozai, eccentricity, inclination_deg);
+        kozai += step;
+        if step.abs() < 1e-13 * mean_motion_rev_per_day {
+            break;
+        }
+    }
+    kozai
+}
+
+fn sgp4_init(elements: &MeanElements) -> Sgp4Record {
+    let xke = xke();
+    let ecco = elements.eccentricity;
+    let inclo = elements.inclination_deg * DEG2RAD;
+    let nodeo = elements.raan_deg * DEG2RAD;
+    let argpo = elements.arg_perigee_deg * DEG2RAD;
+    let mo = elements.mean_anomaly_deg * DEG2RAD;
+    let no_kozai = elements.mean_motion_rev_per_day / (MINUTES_PER_DAY / TWO_PI);
+    let bstar = elements.bstar;
+    let epoch = elements.epoch_jd - 2433281.5;
+
+    let ss = 78.0 / RADIUS_EARTH_KM + 1.0;
+    let qzms2t = ((120.0 - 78.0) / RADIUS_EARTH_KM).powi(4);
+    let temp4 = 1.5e-12;
+
+    // initl: recover the original (Brouwer) mean motion and semi-major axis
+    let eccsq = ecco * ecco;
+    let omeosq = 1.0 - eccsq;
+    let rteosq = omeosq.sqrt();
+    let cosio = inclo.cos();
+    let cosio2 = cosio * cosio;
+
+    let no = unkozai(no_kozai, ecco, cosio);
+
+    let ao = (xke / no).powf(X2O3);
+    let sinio = inclo.sin();
+    let po = ao * omeosq;
+    let con42 = 1.0 - 5.0 * cosio2;
+    let con41 = -con42 - cosio2 - cosio2;
+    let posq = po * po;
+    let rp = ao * (1.0 - ecco);
+    let gsto = gstime(epoch + 2433281.5);
+
+    let mut isimp = rp < 220.0 / RADIUS_EARTH_KM + 1.0;
+    let mut sfour = ss;
+    let mut qzms24 = qzms2t;
+    let perige = (rp - 1.0) * RADIUS_EARTH_KM;
+
+    // for perigees below 156 km, s and qoms2t are altered
+    if perige < 156.0 {
+        sfour = perige - 78.0;
+        if perige < 98.0 {
+            sfour = 20.0;
+        }
+        qzms24 = ((120.0 - sfour) / RADIUS_EARTH_KM).powi(4);
+        sfour = sfour / RADIUS_EARTH_KM + 1.0;
+    }
+
+    let pinvsq = 1.0 / posq;
+    let tsi = 1.0 / (ao - sfour);
+    let eta = ao * ecco * tsi;
+    let etasq = eta * eta;
+    let eeta = ecco * eta;
+    let psisq = (1.0 - etasq).abs();
+    let coef = qzms24 * tsi.powi(4);
+    let coef1 = coef / psisq.powf(3.5);
+    let cc2 = coef1
+        * no
+        * (ao * (1.0 + 1.5 * etasq + eeta * (4.0 + etasq))
+            + 0.375 * J2 * tsi / psisq * con41 * (8.0 + 3.0 * etasq * (8.0 + etasq)));
+    let cc1 = bstar * cc2;
+    let cc3 = if ecco > 1.0e-4 {
+        -2.0 * coef * tsi * J3OJ2 * no * sinio / ecco
+    } else {
+        0.0
+    };
+    let x1mth2 = 1.0 - cosio2;
+    let cc4 = 2.0
+        * no
+        * coef1
+        * ao
+        * omeosq
+        * (eta * (2.0 + 0.5 * etasq) + ecco * (0.5 + 2.0 * etasq)
+            - J2 * tsi / (ao * psisq)
+                * (-3.0 * con41 * (1.0 - 2.0 * eeta + etasq * (1.5 - 0.5 * eeta))
+                    + 0.75 * x1mth2 * (2.0 * etasq - eeta * (1.0 + etasq)) * (2.0 * argpo).cos()));
+    let cc5 = 2.0 * coef1 * ao * omeosq * (1.0 + 2.75 * (etasq + eeta) + eeta * etasq);
+    let cosio4 = cosio2 * cosio2;
+    let temp1 = 1.5 * J2 * pinvsq * no;
+    let temp2 = 0.5 * temp1 * J2 * pinvsq;
+    let temp3 = -0.46875 * J4 * pinvsq * pinvsq * no;
+    let mdot = no
+        + 0.5 * temp1 * rteosq * con41
+        + 0.0625 * temp2 * rteosq * (13.0 - 78.0 * cosio2 + 137.0 * cosio4);
+    let argpdot = -0.5 * temp1 * con42
+        + 0.0625 * temp2 * (7.0 - 114.0 * cosio2 + 395.0 * cosio4)
+        + temp3 * (3.0 - 36.0 * cosio2 + 49.0 * cosio4);
+    let xhdot1 = -temp1 * cosio;
+    let nodedot = xhdot1
+        + (0.5 * temp2 * (4.0 - 19.0 * cosio2) + 2.0 * temp3 * (3.0 - 7.0 * cosio2)) * cosio;
+    let xpidot = argpdot + nodedot;
+    let omgcof = bstar * cc3 * argpo.cos();
+    let xmcof = if ecco > 1.0e-4 {
+        -X2O3 * coef * bstar / eeta
+    } else {
+        0.0
+    };
+    let nodecf = 3.5 * omeosq * xhdot1 * cc1;
+    let t2cof = 1.5 * cc1;
+    let xlcof = if (cosio + 1.0).abs() > 1.5e-12 {
+        -0.25 * J3OJ2 * sinio * (3.0 + 5.0 * cosio) / (1.0 + cosio)
+    } else {
+        -0.25 * J3OJ2 * sinio * (3.0 + 5.0 * cosio) / temp4
+    };
+    let aycof = -0.5 * J3OJ2 * sinio;
+    let delmo = (1.0 + eta * mo.cos()).powi(3);
+    let sinmao = mo.sin();
+    let x7thm1 = 7.0 * cosio2 - 1.0;
+
+    let mut deep = None;
+    if TWO_PI / no >= 225.0 {
+        isimp = true;
+        let (mut ds, dc) = dscom(epoch, ecco, argpo, 0.0, inclo, nodeo, no);
+        dsinit(
+            &mut ds, &dc, xke, argpo, 0.0, gsto, mo, mdot, no, nodeo, nodedot, xpidot, ecco,
+            eccsq, inclo,
+        );
+        deep = Some(ds);
+    }
+
+    let (mut d2, mut d3, mut d4) = (0.0, 0.0, 0.0);
+    let (mut t3cof, mut t4cof, mut t5cof) = (0.0, 0.0, 0.0);
+    if !isimp {
+        let cc1sq = cc1 * cc1;
+        d2 = 4.0 * ao * tsi * cc1sq;
+        let temp = d2 * tsi * cc1 / 3.0;
+        d3 = (17.0 * ao + sfour) * temp;
+        d4 = 0.5 * temp * ao * tsi * (221.0 * ao + 31.0 * sfour) * cc1;
+        t3cof = d2 + 2.0 * cc1sq;
+        t4cof = 0.25 * (3.0 * d3 + cc1 * (12.0 * d2 + 10.0 * cc1sq));
+        t5cof = 0.2
+            * (3.0 * d4 + 12.0 * cc1 * d3 + 6.0 * d2 * d2 + 15.0 * cc1sq * (2.0 * d2 + cc1sq));
+    }
+
+    Sgp4Record {
+        ecco, argpo, inclo, mo, no, nodeo, bstar,
+        isimp,
+        aycof, con41, cc1, cc4, cc5, d2, d3, d4,
+        delmo, eta, argpdot, omgcof, sinmao,
+        t2cof, t3cof, t4cof, t5cof,
+        x1mth2, x7thm1, mdot, nodedot, xlcof, xmcof, nodecf,
+        gsto,
+        deep,
+    }
+}
+
+fn sgp4(rec: &Sgp4Record, t: f64) -> Result<([f64; 3], [f64; 3]), Sgp4Error> {
+    let xke = xke();
+    let vkmpersec = RADIUS_EARTH_KM * xke / 60.0;
+    let temp4 = 1.5e-12;
+
+    // secular gravity and atmospheric drag
+    let xmdf = rec.mo + rec.mdot * t;
+    let argpdf = rec.argpo + rec.argpdot * t;
+    let nodedf = rec.nodeo + rec.nodedot * t;
+    let mut argpm = argpdf;
+    let mut mm = xmdf;
+    let t2 = t * t;
+    let mut nodem = nodedf + rec.nodecf * t2;
+    let mut tempa = 1.0 - rec.cc1 * t;
+    let mut tempe = rec.bstar * rec.cc4 * t;
+    let mut templ = rec.t2cof * t2;
+
+    if !rec.isimp {
+        let delomg = rec.omgcof * t;
+        let delm = rec.xmcof * ((1.0 + rec.eta * xmdf.cos()).powi(3) - rec.delmo);
+        let temp = delomg + delm;
+        mm = xmdf + temp;
+        argpm = argpdf - temp;
+        let t3 = t2 * t;
+        let t4 = t3 * t;
+        tempa = tempa - rec.d2 * t2 - rec.d3 * t3 - rec.d4 * t4;
+        tempe += rec.bstar * rec.cc5 * (mm.sin() - rec.sinmao);
+        templ += rec.t3cof * t3 + t4 * (rec.t4cof + t * rec.t5cof);
+    }
+
+    let mut nm = rec.no;
+    let mut em = rec.ecco;
+    let mut inclm = rec.inclo;
+    if let Some(ds) = &rec.deep {
+        dspace(
+            ds, rec, t, &mut em, &mut argpm, &mut inclm, &mut mm, &mut nodem, &mut nm,
+        );
+    }
+
+    if nm <= 0.0 {
+        return Err(Sgp4Error::MeanMotion(nm));
+    }
+    let am = (xke / nm).powf(X2O3) * tempa * tempa;
+    nm = xke / am.powf(1.5);
+    em -= tempe;
+
+    if !(-0.001..1.0).contains(&em) {
+        return Err(Sgp4Error::MeanEccentricity(em));
+    }
+    if em < 1.0e-6 {
+        em = 1.0e-6;
+    }
+    mm += rec.no * templ;
+    let mut xlm = mm + argpm + nodem;
+
+    nodem %= TWO_PI;
+    argpm %= TWO_PI;
+    xlm %= TWO_PI;
+    mm = (xlm - argpm - nodem) % TWO_PI;
+
+    // lunar-solar periodics
+    let mut ep = em;
+    let mut xincp = inclm;
+    let mut argpp = argpm;
+    let mut nodep = nodem;
+    let mut mp = mm;
+    let mut sinip = inclm.sin();
+    let mut cosip = inclm.cos();
+    let mut aycof = rec.aycof;
+    let mut xlcof = rec.xlcof;
+    let mut con41 = rec.con41;
+    let mut x1mth2 = rec.x1mth2;
+    let mut x7thm1 = rec.x7thm1;
+
+    if let Some(ds) = &rec.deep {
+        dpper(
+            ds, t, false, &mut ep, &mut xincp, &mut nodep, &mut argpp, &mut mp,
+        );
+        if xincp < 0.0 {
+            xincp = -xincp;
+            nodep += PI;
+            argpp -= PI;
+        }
+        if !(0.0..=1.0).contains(&ep) {
+            return Err(Sgp4Error::PerturbedEccentricity(ep));
+        }
+
+        // long period periodics use the perturbed inclination
+        sinip = xincp.sin();
+        cosip = xincp.cos();
+        aycof = -0.5 * J3OJ2 * sinip;
+        xlcof = if (cosip + 1.0).abs() > 1.5e-12 {
+            -0.25 * J3OJ2 * sinip * (3.0 + 5.0 * cosip) / (1.0 + cosip)
+        } else {
+            -0.25 * J3OJ2 * sinip * (3.0 + 5.0 * cosip) / temp4
+        };
+    }
+
+    let axnl = ep * argpp.cos();
+    let temp = 1.0 / (am * (1.0 - ep * ep));
+    let aynl = ep * argpp.sin() + temp * aycof;
+    let xl = mp + argpp + nodep + temp * xlcof * axnl;
+
+    // solve Kepler's equation
+    let u = (xl - nodep) % TWO_PI;
+    let mut eo1 = u;
+    let mut tem5: f64 = 9999.9;
+    let mut ktr = 1;
+    let mut sineo1 = 0.0;
+    let mut coseo1 = 0.0;
+    while tem5.abs() >= 1.0e-12 && ktr <= 10 {
+        sineo1 = eo1.sin();
+        coseo1 = eo1.cos();
+        tem5 = 1.0 - coseo1 * axnl - sineo1 * aynl;
+        tem5 = (u - aynl * coseo1 + axnl * sineo1 - eo1) / tem5;
+        if tem5.abs() >= 0.95 {
+            tem5 = 0.95f64.copysign(tem5);
+        }
+        eo1 += tem5;
+        ktr += 1;
+    }
+
+    // short period preliminary quantities
+    let ecose = axnl * coseo1 + aynl * sineo1;
+    let esine = axnl * sineo1 - aynl * coseo1;
+    let el2 = axnl * axnl + aynl * aynl;
+    let pl = am * (1.0 - el2);
+    if pl < 0.0 {
+        return Err(Sgp4Error::SemiLatusRectum(pl));
+    }
+
+    let rl = am * (1.0 - ecose);
+    let rdotl = am.sqrt() * esine / rl;
+    let rvdotl = pl.sqrt() / rl;
+    let betal = (1.0 - el2).sqrt();
+    let temp = esine / (1.0 + betal);
+    let sinu = am / rl * (sineo1 - aynl - axnl * temp);
+    let cosu = am / rl * (coseo1 - axnl + aynl * temp);
+    let mut su = sinu.atan2(cosu);
+    let sin2u = (cosu + cosu) * sinu;
+    let cos2u = 1.0 - 2.0 * sinu * sinu;
+    let temp = 1.0 / pl;
+    let temp1 = 0.5 * J2 * temp;
+    let temp2 = temp1 * temp;
+
+    if rec.deep.is_some() {
+        let cosisq = cosip * cosip;
+        con41 = 3.0 * cosisq - 1.0;
+        x1mth2 = 1.0 - cosisq;
+        x7thm1 = 7.0 * cosisq - 1.0;
+    }
+
+    // update for short period periodics
+    let mrt = rl * (1.0 - 1.5 * temp2 * betal * con41) + 0.5 * temp1 * x1mth2 * cos2u;
+    su -= 0.25 * temp2 * x7thm1 * sin2u;
+    let xnode = nodep + 1.5 * temp2 * cosip * sin2u;
+    let xinc = xincp + 1.5 * temp2 * cosip * sinip * cos2u;
+    let mvt = rdotl - nm * temp1 * x1mth2 * sin2u / xke;
+    let rvdot = rvdotl + nm * temp1 * (x1mth2 * cos2u + 1.5 * con41) / xke;
+
+    // orientation vectors
+    let (sinsu, cossu) = su.sin_cos();
+    let (snod, cnod) = xnode.sin_cos();
+    let (sini, cosi) = xinc.sin_cos();
+    let xmx = -snod * cosi;
+    let xmy = cnod * cosi;
+    let ux = xmx * sinsu + cnod * cossu;
+    let uy = xmy * sinsu + snod * cossu;
+    let uz = sini * sinsu;
+    let vx = xmx * cossu - cnod * sinsu;
+    let vy = xmy * cossu - snod * sinsu;
+    let vz = sini * cossu;
+
+    let mr = mrt * RADIUS_EARTH_KM;
+    let position = [mr * ux, mr * uy, mr * uz];
+    let velocity = [
+        (mvt * ux + rvdot * vx) * vkmpersec,
+        (mvt * uy + rvdot * vy) * vkmpersec,
+        (mvt * uz + rvdot * vz) * vkmpersec,
+    ];
+
+    if mrt < 1.0 {
+        return Err(Sgp4Error::Decayed);
+    }
+
+    Ok((position, velocity))
+}
+
+/// Deep-space common terms (lunar and solar perturbation coefficients)
+fn dscom(
+    epoch: f64,
+    ep: f64,
+    argpp: f64,
+    tc: f64,
+    inclp: f64,
+    nodep: f64,
+    np: f64,
+) -> (DeepSpace, Dscom) {
+    const ZES: f64 = 0.01675;
+    const ZEL: f64 = 0.05490;
+    const C1SS: f64 = 2.9864797e-6;
+    const C1L: f64 = 4.7968065e-7;
+    const ZSINIS: f64 = 0.39785416;
+    const ZCOSIS: f64 = 0.91744867;
+    const ZCOSGS: f64 = 0.1945905;
+    const ZSINGS: f64 = -0.98088458;
+
+    let mut ds = DeepSpace::default();
+    let mut out = Dscom::default();
+
+    let nm = np;
+    let em = ep;
+    let snodm = nodep.sin();
+    let cnodm = nodep.cos();
+    let sinomm = argpp.sin();
+    let cosomm = argpp.cos();
+    let sinim = inclp.sin();
+    let cosim = inclp.cos();
+    let emsq = em * em;
+    let betasq = 1.0 - emsq;
+    let rtemsq = betasq.sqrt();
+
+    // initialize lunar solar terms
+    let day = epoch + 18261.5 + tc / 1440.0;
+    let xnodce = (4.5236020 - 9.2422029e-4 * day) % TWO_PI;
+    let stem = xnodce.sin();
+    let ctem = xnodce.cos();
+    let zcosil = 0.91375164 - 0.03568096 * ctem;
+    let zsinil = (1.0 - zcosil * zcosil).sqrt();
+    let zsinhl = 0.089683511 * stem / zsinil;
+    let zcoshl = (1.0 - zsinhl * zsinhl).sqrt();
+    let gam = 5.8351514 + 0.0019443680 * day;
+    let zx = 0.39785416 * stem / zsinil;
+    let zy = zcoshl * ctem + 0.91744867 * zsinhl * stem;
+    let zx = gam + zx.atan2(zy) - xnodce;
+    let zcosgl = zx.cos();
+    let zsingl = zx.sin();
+
+    // do solar terms (pass 1) then lunar terms (pass 2)
+    let mut zcosg = ZCOSGS;
+    let mut zsing = ZSINGS;
+    let mut zcosi = ZCOSIS;
+    let mut zsini = ZSINIS;
+    let mut zcosh = cnodm;
+    let mut zsinh = snodm;
+    let mut cc = C1SS;
+    let xnoi = 1.0 / nm;
+
+    let (mut s1, mut s2, mut s3, mut s4, mut s5, mut s6, mut s7) = (0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0);
+    let (mut z1, mut z2, mut z3) = (0.0, 0.0, 0.0);
+    let (mut z11, mut z12, mut z13) = (0.0, 0.0, 0.0);
+    let (mut z21, mut z22, mut z23) = (0.0, 0.0, 0.0);
+    let (mut z31, mut z32, mut z33) = (0.0, 0.0, 0.0);
+    let (mut ss1, mut ss2, mut ss3, mut ss4, mut ss5, mut ss6, mut ss7) = (0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0);
+    let (mut sz1, mut sz2, mut sz3) = (0.0, 0.0, 0.0);
+    let (mut sz11, mut sz12, mut sz13) = (0.0, 0.0, 0.0);
+    let (mut sz21, mut sz22, mut sz23) = (0.0, 0.0, 0.0);
+    let (mut sz31, mut sz32, mut sz33) = (0.0, 0.0, 0.0);
+
+    for lsflg in 1..=2 {
+        let a1 = zcosg * zcosh + zsing * zcosi * zsinh;
+        let a3 = -zsing * zcosh + zcosg * zcosi * zsinh;
+        let a7 = -zcosg * zsinh + zsing * zcosi * zcosh;
+        let a8 = zsing * zsini;
+        let a9 = zsing * zsinh + zcosg * zcosi * zcosh;
+        let a10 = zcosg * zsini;
+        let a2 = cosim * a7 + sinim * a8;
+        let a4 = cosim * a9 + sinim * a10;
+        let a5 = -sinim * a7 + cosim * a8;
+        let a6 = -sinim * a9 + cosim * a10;
+
+        let x1 = a1 * cosomm + a2 * sinomm;
+        let x2 = a3 * cosomm + a4 * sinomm;
+        let x3 = -a1 * sinomm + a2 * cosomm;
+        let x4 = -a3 * sinomm + a4 * cosomm;
+        let x5 = a5 * sinomm;
+        let x6 = a6 * sinomm;
+        let x7 = a5 * cosomm;
+        let x8 = a6 * cosomm;
+
+        z31 = 12.0 * x1 * x1 - 3.0 * x3 * x3;
+        z32 = 24.0 * x1 * x2 - 6.0 * x3 * x4;
+        z33 = 12.0 * x2 * x2 - 3.0 * x4 * x4;
+        z1 = 3.0 * (a1 * a1 + a2 * a2) + z31 * emsq;
+        z2 = 6.0 * (a1 * a3 + a2 * a4) + z32 * emsq;
+        z3 = 3.0 * (a3 * a3 + a4 * a4) + z33 * emsq;
+        z11 = -6.0 * a1 * a5 + emsq * (-24.0 * x1 * x7 - 6.0 * x3 * x5);
+        z12 = -6.0 * (a1 * a6 + a3 * a5)
+            + emsq * (-24.0 * (x2 * x7 + x1 * x8) - 6.0 * (x3 * x6 + x4 * x5));
+        z13 = -6.0 * a3 * a6 + emsq * (-24.0 * x2 * x8 - 6.0 * x4 * x6);
+        z21 = 6.0 * a2 * a5 + emsq * (24.0 * x1 * x5 - 6.0 * x3 * x7);
+        z22 = 6.0 * (a4 * a5 + a2 * a6)
+            + emsq * (24.0 * (x2 * x5 + x1 * x6) - 6.0 * (x4 * x7 + x3 * x8));
+        z23 = 6.0 * a4 * a6 + emsq * (24.0 * x2 * x6 - 6.0 * x4 * x8);
+        z1 = z1 + z1 + betasq * z31;
+        z2 = z2 + z2 + betasq * z32;
+        z3 = z3 + z3 + betasq * z33;
+        s3 = cc * xnoi;
+        s2 = -0.5 * s3 / rtemsq;
+        s4 = s3 * rtemsq;
+        s1 = -15.0 * em * s4;
+        s5 = x1 * x3 + x2 * x4;
+        s6 = x2 * x3 + x1 * x4;
+        s7 = x2 * x4 - x1 * x3;
+
+        if lsflg == 1 {
+            ss1 = s1; ss2 = s2; ss3 = s3; ss4 = s4; ss5 = s5; ss6 = s6; ss7 = s7;
+            sz1 = z1; sz2 = z2; sz3 = z3;
+            sz11 = z11; sz12 = z12; sz13 = z13;
+            sz21 = z21; sz22 = z22; sz23 = z23;
+            sz31 = z31; sz32 = z32; sz33 = z33;
+            zcosg = zcosgl;
+            zsing = zsingl;
+            zcosi = zcosil;
+            zsini = zsinil;
+            zcosh = zcoshl * cnodm + zsinhl * snodm;
+            zsinh = snodm * zcoshl - cnodm * zsinhl;
+            cc = C1L;
+        }
+    }
+
+    ds.zmol = (4.7199672 + 0.22997150 * day - gam) % TWO_PI;
+    ds.zmos = (6.2565837 + 0.017201977 * day) % TWO_PI;
+
+    // solar terms
+    ds.se2 = 2.0 * ss1 * ss6;
+    ds.se3 = 2.0 * ss1 * ss7;
+    ds.si2 = 2.0 * ss2 * sz12;
+    ds.si3 = 2.0 * ss2 * (sz13 - sz11);
+    ds.sl2 = -2.0 * ss3 * sz2;
+    ds.sl3 = -2.0 * ss3 * (sz3 - sz1);
+    ds.sl4 = -2.0 * ss3 * (-21.0 - 9.0 * emsq) * ZES;
+    ds.sgh2 = 2.0 * ss4 * sz32;
+    ds.sgh3 = 2.0 * ss4 * (sz33 - sz31);
+    ds.sgh4 = -18.0 * ss4 * ZES;
+    ds.sh2 = -2.0 * ss2 * sz22;
+    ds.sh3 = -2.0 * ss2 * (sz23 - sz21);
+
+    // lunar terms
+    ds.ee2 = 2.0 * s1 * s6;
+    ds.e3 = 2.0 * s1 * s7;
+    ds.xi2 = 2.0 * s2 * z12;
+    ds.xi3 = 2.0 * s2 * (z13 - z11);
+    ds.xl2 = -2.0 * s3 * z2;
+    ds.xl3 = -2.0 * s3 * (z3 - z1);
+    ds.xl4 = -2.0 * s3 * (-21.0 - 9.0 * emsq) * ZEL;
+    ds.xgh2 = 2.0 * s4 * z32;
+    ds.xgh3 = 2.0 * s4 * (z33 - z31);
+    ds.xgh4 = -18.0 * s4 * ZEL;
+    ds.xh2 = -2.0 * s2 * z22;
+    ds.xh3 = -2.0 * s2 * (z23 - z21);
+
+    out.sinim = sinim;
+    out.cosim = cosim;
+    out.emsq = emsq;
+    out.s1 = s1; out.s2 = s2; out.s3 = s3; out.s4 = s4; out.s5 = s5;
+    out.ss1 = ss1; out.ss2 = ss2; out.ss3 = ss3; out.ss4 = ss4; out.ss5 = ss5;
+    out.sz1 = sz1; out.sz3 = sz3; out.sz11 = sz11; out.sz13 = sz13;
+    out.sz21 = sz21; out.sz23 = sz23; out.sz31 = sz31; out.sz33 = sz33;
+    out.z1 = z1; out.z3 = z3; out.z11 = z11; out.z13 = z13;
+    out.z21 = z21; out.z23 = z23; out.z31 = z31; out.z33 = z33;
+
+    (ds, out)
+}
+
+/// Deep-space secular rates and resonance initialisation
+#[allow(clippy::too_many_arguments)]
+fn dsinit(
+    ds: &mut DeepSpace,
+    dc: &Dscom,
+    xke: f64,
+    argpo: f64,
+    tc: f64,
+    gsto: f64,
+    mo: f64,
+    mdot: f64,
+    no: f64,
+    nodeo: f64,
+    nodedot: f64,
+    xpidot: f64,
+    ecco: f64,
+    eccsq: f64,
+    inclm: f64,
+) {
+    const Q22: f64 = 1.7891679e-6;
+    const Q31: f64 = 2.1460748e-6;
+    const Q33: f64 = 2.2123015e-7;
+    const ROOT22: f64 = 1.7891679e-6;
+    const ROOT44: f64 = 7.3636953e-9;
+    const ROOT54: f64 = 2.1765803e-9;
+    const ROOT32: f64 = 3.7393792e-7;
+    const ROOT52: f64 = 1.1428639e-7;
+    const ZNL: f64 = 1.5835218e-4;
+    const ZNS: f64 = 1.19459e-5;
+
+    let nm = no;
+    let em = ecco;
+    let sinim = dc.sinim;
+    let cosim = dc.cosim;
+    let emsq = dc.emsq;
+
+    // deep space resonance flag
+    ds.irez = 0;
+    if nm < 0.0052359877 && nm > 0.0034906585 {
+        ds.irez = 1;
+    }
+    if (8.26e-3..=9.24e-3).contains(&nm) && em >= 0.5 {
+        ds.irez = 2;
+    }
+
+    // solar terms
+    let ses = dc.ss1 * ZNS * dc.ss5;
+    let sis = dc.ss2 * ZNS * (dc.sz11 + dc.sz13);
+    let sls = -ZNS * dc.ss3 * (dc.sz1 + dc.sz3 - 14.0 - 6.0 * emsq);
+    let sghs = dc.ss4 * ZNS * (dc.sz31 + dc.sz33 - 6.0);
+    let mut shs = -ZNS * dc.ss2 * (dc.sz21 + dc.sz23);
+    if !(5.2359877e-2..=PI - 5.2359877e-2).contains(&inclm) {
+        shs = 0.0;
+    }
+    if sinim != 0.0 {
+        shs /= sinim;
+    }
+    let sgs = sghs - cosim * shs;
+
+    // lunar terms
+    ds.dedt = ses + dc.s1 * ZNL * dc.s5;
+    ds.didt = sis + dc.s2 * ZNL * (dc.z11 + dc.z13);
+    ds.dmdt = sls - ZNL * dc.s3 * (dc.z1 + dc.z3 - 14.0 - 6.0 * emsq);
+    let sghl = dc.s4 * ZNL * (dc.z31 + dc.z33 - 6.0);
+    let mut shll = -ZNL * dc.s2 * (dc.z21 + dc.z23);
+    if !(5.2359877e-2..=PI - 5.2359877e-2).contains(&inclm) {
+        shll = 0.0;
+    }
+    ds.domdt = sgs + sghl;
+    ds.dnodt = shs;
+    if sinim != 0.0 {
+        ds.domdt -= cosim / sinim * shll;
+        ds.dnodt += shll / sinim;
+    }
+
+    // deep space resonance effects
+    let theta = (gsto + tc * RPTIM) % TWO_PI;
+    if ds.irez == 0 {
+        return;
+    }
+    let aonv = (nm / xke).powf(X2O3);
+
+    // geopotential resonance for 12 hour orbits
+    if ds.irez == 2 {
+        let cosisq = cosim * cosim;
+        let em = ecco;
+        let emsq = eccsq;
+        let eoc = em * emsq;
+        let g201 = -0.306 - (em - 0.64) * 0.440;
+
+        let (g211, g310, g322, g410, g422, g520);
+        if em <= 0.65 {
+            g211 = 3.616 - 13.2470 * em + 16.2900 * emsq;
+            g310 = -19.302 + 117.3900 * em - 228.4190 * emsq + 156.5910 * eoc;
+            g322 = -18.9068 + 109.7927 * em - 214.6334 * emsq + 146.5816 * eoc;
+            g410 = -41.122 + 242.6940 * em - 471.0940 * emsq + 313.9530 * eoc;
+            g422 = -146.407 + 841.8800 * em - 1629.014 * emsq + 1083.4350 * eoc;
+            g520 = -532.114 + 3017.977 * em - 5740.032 * emsq + 3708.2760 * eoc;
+        } else {
+            g211 = -72.099 + 331.819 * em - 508.738 * emsq + 266.724 * eoc;
+            g310 = -346.844 + 1582.851 * em - 2415.925 * emsq + 1246.113 * eoc;
+            g322 = -342.585 + 1554.908 * em - 2366.899 * emsq + 1215.972 * eoc;
+            g410 = -1052.797 + 4758.686 * em - 7193.992 * emsq + 3651.957 * eoc;
+            g422 = -3581.690 + 16178.110 * em - 24462.770 * emsq + 12422.520 * eoc;
+            g520 = if em > 0.715 {
+                -5149.66 + 29936.92 * em - 54087.36 * emsq + 31324.56 * eoc
+            } else {
+                1464.74 - 4664.75 * em + 3763.64 * emsq
+            };
+        }
+
+        let (g533, g521, g532);
+        if em < 0.7 {
+            g533 = -919.22770 + 4988.6100 * em - 9064.7700 * emsq + 5542.21 * eoc;
+            g521 = -822.71072 + 4568.6173 * em - 8491.4146 * emsq + 5337.524 * eoc;
+            g532 = -853.66600 + 4690.2500 * em - 8624.7700 * emsq + 5341.4 * eoc;
+        } else {
+            g533 = -37995.780 + 161616.52 * em - 229838.20 * emsq + 109377.94 * eoc;
+            g521 = -51752.104 + 218913.95 * em - 309468.16 * emsq + 146349.42 * eoc;
+            g532 = -40023.880 + 170470.89 * em - 242699.48 * emsq + 115605.82 * eoc;
+        }
+
+        let sini2 = sinim * sinim;
+        let f220 = 0.75 * (1.0 + 2.0 * cosim + cosisq);
+        let f221 = 1.5 * sini2;
+        let f321 = 1.875 * sinim * (1.0 - 2.0 * cosim - 3.0 * cosisq);
+        let f322 = -1.875 * sinim * (1.0 + 2.0 * cosim - 3.0 * cosisq);
+        let f441 = 35.0 * sini2 * f220;
+        let f442 = 39.3750 * sini2 * sini2;
+        let f522 = 9.84375
+            * sinim
+            * (sini2 * (1.0 - 2.0 * cosim - 5.0 * cosisq)
+                + 0.33333333 * (-2.0 + 4.0 * cosim + 6.0 * cosisq));
+        let f523 = sinim
+            * (4.92187512 * sini2 * (-2.0 - 4.0 * cosim + 10.0 * cosisq)
+                + 6.56250012 * (1.0 + 2.0 * cosim - 3.0 * cosisq));
+        let f542 = 29.53125
+            * sinim
+            * (2.0 - 8.0 * cosim + cosisq * (-12.0 + 8.0 * cosim + 10.0 * cosisq));
+        let f543 = 29.53125
+            * sinim
+            * (-2.0 - 8.0 * cosim + cosisq * (12.0 + 8.0 * cosim - 10.0 * cosisq));
+
+        let xno2 = nm * nm;
+        let ainv2 = aonv * aonv;
+        let mut temp1 = 3.0 * xno2 * ainv2;
+        let mut temp = temp1 * ROOT22;
+        ds.d2201 = temp * f220 * g201;
+        ds.d2211 = temp * f221 * g211;
+        temp1 *= aonv;
+        temp = temp1 * ROOT32;
+        ds.d3210 = temp * f321 * g310;
+        ds.d3222 = temp * f322 * g322;
+        temp1 *= aonv;
+        temp = 2.0 * temp1 * ROOT44;
+        ds.d4410 = temp * f441 * g410;
+        ds.d4422 = temp * f442 * g422;
+        temp1 *= aonv;
+        temp = temp1 * ROOT52;
+        ds.d5220 = temp * f522 * g520;
+        ds.d5232 = temp * f523 * g532;
+        temp = 2.0 * temp1 * ROOT54;
+        ds.d5421 = temp * f542 * g521;
+        ds.d5433 = temp * f543 * g533;
+        ds.xlamo = (mo + nodeo + nodeo - theta - theta) % TWO_PI;
+        ds.xfact = mdot + ds.dmdt + 2.0 * (nodedot + ds.dnodt - RPTIM) - no;
+    }
+
+    // synchronous resonance terms
+    if ds.irez == 1 {
+        let g200 = 1.0 + emsq * (-2.5 + 0.8125 * emsq);
+        let g310 = 1.0 + 2.0 * emsq;
+        let g300 = 1.0 + emsq * (-6.0 + 6.60937 * emsq);
+        let f220 = 0.75 * (1.0 + cosim) * (1.0 + cosim);
+        let f311 = 0.9375 * sinim * sinim * (1.0 + 3.0 * cosim) - 0.75 * (1.0 + cosim);
+        let f330 = 1.875 * (1.0 + cosim).powi(3);
+        let del1 = 3.0 * nm * nm * aonv * aonv;
+        ds.del2 = 2.0 * del1 * f220 * g200 * Q22;
+        ds.del3 = 3.0 * del1 * f330 * g300 * Q33 * aonv;
+        ds.del1 = del1 * f311 * g310 * Q31 * aonv;
+        ds.xlamo = (mo + nodeo + argpo - theta) % TWO_PI;
+        ds.xfact = mdot + xpidot - RPTIM + ds.dmdt + ds.domdt + ds.dnodt - no;
+    }
+}
+
+/// Deep-space secular effects and resonance integration
+#[allow(clippy::too_many_arguments)]
+fn dspace(
+    ds: &DeepSpace,
+    rec: &Sgp4Record,
+    t: f64,
+    em: &mut f64,
+    argpm: &mut f64,
+    inclm: &mut f64,
+    mm: &mut f64,
+    nodem: &mut f64,
+    nm: &mut f64,
+) {
+    const FASX2: f64 = 0.13130908;
+    const FASX4: f64 = 2.8843198;
+    const FASX6: f64 = 0.37448087;
+    const G22: f64 = 5.7686396;
+    const G32: f64 = 0.95240898;
+    const G44: f64 = 1.8014998;
+    const G52: f64 = 1.0508330;
+    const G54: f64 = 4.4108898;
+    const STEPP: f64 = 720.0;
+    const STEPN: f64 = -720.0;
+    const STEP2: f64 = 259200.0;
+
+    let theta = (rec.gsto + t * RPTIM) % TWO_PI;
+    *em += ds.dedt * t;
+    *inclm += ds.didt * t;
+    *argpm += ds.domdt * t;
+    *nodem += ds.dnodt * t;
+    *mm += ds.dmdt * t;
+
+    if ds.irez == 0 {
+        return;
+    }
+
+    // integrate the resonance equations from epoch in fixed half-day steps
+    let mut atime = 0.0;
+    let mut xni = rec.no;
+    let mut xli = ds.xlamo;
+    let delt = if t > 0.0 { STEPP } else { STEPN };
+
+    let (xndt, xldot, xnddt, ft) = loop {
+        let (xndt, xnddt);
+        let xldot = xni + ds.xfact;
+        if ds.irez != 2 {
+            xndt = ds.del1 * (xli - FASX2).sin()
+                + ds.del2 * (2.0 * (xli - FASX4)).sin()
+                + ds.del3 * (3.0 * (xli - FASX6)).sin();
+            xnddt = (ds.del1 * (xli - FASX2).cos()
+                + 2.0 * ds.del2 * (2.0 * (xli - FASX4)).cos()
+                + 3.0 * ds.del3 * (3.0 * (xli - FASX6)).cos())
+                * xldot;
+        } else {
+            let xomi = rec.argpo + rec.argpdot * atime;
+            let x2omi = xomi + xomi;
+            let x2li = xli + xli;
+            xndt = ds.d2201 * (x2omi + xli - G22).sin()
+                + ds.d2211 * (xli - G22).sin()
+                + ds.d3210 * (xomi + xli - G32).sin()
+                + ds.d3222 * (-xomi + xli - G32).sin()
+                + ds.d4410 * (x2omi + x2li - G44).sin()
+                + ds.d4422 * (x2li - G44).sin()
+                + ds.d5220 * (xomi + xli - G52).sin()
+                + ds.d5232 * (-xomi + xli - G52).sin()
+                + ds.d5421 * (xomi + x2li - G54).sin()
+                + ds.d5433 * (-xomi + x2li - G54).sin();
+            xnddt = (ds.d2201 * (x2omi + xli - G22).cos()
+                + ds.d2211 * (xli - G22).cos()
+                + ds.d3210 * (xomi + xli - G32).cos()
+                + ds.d3222 * (-xomi + xli - G32).cos()
+                + ds.d5220 * (xomi + xli - G52).cos()
+                + ds.d5232 * (-xomi + xli - G52).cos()
+                + 2.0
+                    * (ds.d4410 * (x2omi + x2li - G44).cos()
+                        + ds.d4422 * (x2li - G44).cos()
+                        + ds.d5421 * (xomi + x2li - G54).cos()
+                        + ds.d5433 * (-xomi + x2li - G54).cos()))
+                * xldot;
+        }
+
+        if (t - atime).abs() < STEPP {
+            break (xndt, xldot, xnddt, t - atime);
+        }
+        xli += xldot * delt + xndt * STEP2;
+        xni += xndt * delt + xnddt * STEP2;
+        atime += delt;
+    };
+
+    *nm = xni + xndt * ft + xnddt * ft * ft * 0.5;
+    let xl = xli + xldot * ft + xndt * ft * ft * 0.5;
+    if ds.irez != 1 {
+        *mm = xl - 2.0 * *nodem + 2.0 * theta;
+    } else {
+        *mm = xl - *nodem - *argpm + theta;
+    }
+}
+
+/// Deep-space long-period periodic contributions
+#[allow(clippy::too_many_arguments)]
+fn dpper(
+    ds: &DeepSpace,
+    t: f64,
+    init: bool,
+    ep: &mut f64,
+    inclp: &mut f64,
+    nodep: &mut f64,
+    argpp: &mut f64,
+    mp: &mut f64,
+) {
+    const ZNS: f64 = 1.19459e-5;
+    const ZES: f64 = 0.01675;
+    const ZNL: f64 = 1.5835218e-4;
+    const ZEL: f64 = 0.05490;
+
+    // solar terms
+    let zm = if init { ds.zmos } else { ds.zmos + ZNS * t };
+    let zf = zm + 2.0 * ZES * zm.sin();
+    let sinzf = zf.sin();
+    let f2 = 0.5 * sinzf * sinzf - 0.25;
+    let f3 = -0.5 * sinzf * zf.cos();
+    let ses = ds.se2 * f2 + ds.se3 * f3;
+    let sis = ds.si2 * f2 + ds.si3 * f3;
+    let sls = ds.sl2 * f2 + ds.sl3 * f3 + ds.sl4 * sinzf;
+    let sghs = ds.sgh2 * f2 + ds.sgh3 * f3 + ds.sgh4 * sinzf;
+    let shs = ds.sh2 * f2 + ds.sh3 * f3;
+
+    // lunar terms
+    let zm = if init { ds.zmol } else { ds.zmol + ZNL * t };
+    let zf = zm + 2.0 * ZEL * zm.sin();
+    let sinzf = zf.sin();
+    let f2 = 0.5 * sinzf * sinzf - 0.25;
+    let f3 = -0.5 * sinzf * zf.cos();
+    let sel = ds.ee2 * f2 + ds.e3 * f3;
+    let sil = ds.xi2 * f2 + ds.xi3 * f3;
+    let sll = ds.xl2 * f2 + ds.xl3 * f3 + ds.xl4 * sinzf;
+    let sghl = ds.xgh2 * f2 + ds.xgh3 * f3 + ds.xgh4 * sinzf;
+    let shll = ds.xh2 * f2 + ds.xh3 * f3;
+
+    if init {
+        return;
+    }
+
+    let pe = ses + sel;
+    let pinc = sis + sil;
+    let pl = sls + sll;
+    let mut pgh = sghs + sghl;
+    let mut ph = shs + shll;
+
+    *inclp += pinc;
+    *ep += pe;
+    let sinip = inclp.sin();
+    let cosip = inclp.cos();
+
+    if *inclp >= 0.2 {
+        // apply periodics directly
+        ph /= sinip;
+        pgh -= cosip * ph;
+        *argpp += pgh;
+        *nodep += ph;
+        *mp += pl;
+    } else {
+        // apply periodics with Lyddane modification
+        let sinop = nodep.sin();
+        let cosop = nodep.cos();
+        let mut alfdp = sinip * sinop;
+        let mut betdp = sinip * cosop;
+        let dalf = ph * cosop + pinc * cosip * sinop;
+        let dbet = -ph * sinop + pinc * cosip * cosop;
+        alfdp += dalf;
+        betdp += dbet;
+        *nodep %= TWO_PI;
+        let mut xls = *mp + *argpp + cosip * *nodep;
+        let dls = pl + pgh - pinc * *nodep * sinip;
+        xls += dls;
+        let xnoh = *nodep;
+        *nodep = alfdp.atan2(betdp);
+        if (xnoh - *nodep).abs() > PI {
+            if *nodep < xnoh {
+                *nodep += TWO_PI;
+            } else {
+                *nodep -= TWO_PI;
+            }
+        }
+        *mp += pl;
+        *argpp = xls - *mp - cosip * *nodep;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VANGUARD_L1: &str = "1 00005U 58002B   00179.78495062  .00000023  00000-0  28098-4 0  4753";
+    const VANGUARD_L2: &str = "2 00005  34.2682 348.7242 1859667 331.7664  19.3264 10.82419157413667";
+
+    fn assert_close(actual: [f64; 3], expected: [f64; 3], tol: f64) {
+        for k in 0..3 {
+            assert!(
+                (actual[k] - expected[k]).abs() < tol,
+                "component {}: {} vs {}",
+                k,
+                actual[k],
+                expected[k]
+            );
+        }
+    }
+
+    #[test]
+    fn test_near_earth_vallado_vector() {
+        let prop = Sgp4Propagator::new(VANGUARD_L1, VANGUARD_L2).unwrap();
+        assert!(!prop.is_deep_space());
+
+        let (r, v) = prop.position_velocity(0.0).unwrap();
+        assert_close(r, [7022.46529266, -1400.08296755, 0.03995155], 1e-4);
+        assert_close(v, [1.893841015, 6.405893759, 4.534807250], 1e-7);
+
+        let (r, v) = prop.position_velocity(360.0).unwrap();
+        assert_close(r, [-7154.03120202, -3783.17682504, -3536.19412294], 1e-4);
+        assert_close(v, [4.741887409, -4.151817765, -2.093935425], 1e-7);
+    }
+
+    #[test]
+    fn test_deep_space_resonant_vallado_vector() {
+        let prop = Sgp4Propagator::new(
+            "1 08195U 75081A   06176.33215444  .00000099  00000-0  11873-3 0   813",
+            "2 08195  64.1586 279.0717 6877146 264.7651  20.2257  2.00491383225656",
+        )
+        .unwrap();
+        assert!(prop.is_deep_space());
+
+        let (r, v) = prop.position_velocity(120.0).unwrap();
+        assert_close(r, [15223.91713658, -17852.95881713, 25280.39558224], 1e-4);
+        assert_close(v, [1.079041732, 0.875187372, 2.485682813], 1e-7);
+    }
+
+    #[test]
+    fn test_geostationary_uses_deep_space() {
+        let elements = MeanElements::new(
+            99999, 2451545.0, 0.0, 0.05, 80.0, 0.0002, 270.0, 45.0, 1.00271,
+        )
+        .unwrap();
+        let prop = Sgp4Propagator::from_mean_elements(elements);
+        assert!(prop.is_deep_space());
+
+        for minutes in [0.0, 720.0, 1440.0 * 10.0] {
+            let (r, _) = prop.position_velocity(minutes).unwrap();
+            let radius = (r[0] * r[0] + r[1] * r[1] + r[2] * r[2]).sqrt();
+            assert!((radius - 42164.0).abs() < 50.0, "radius {}", radius);
+        }
+    }
+}
@@ -0,0 +1,431 @@
+//! Screening pre-filters ("sieves") that reject pairs which cannot conjunct
+//!
+//! Besides the apsis-band test, the orbit path and time filters follow
+//! Hoots, Crawford & Roehrich (1984): two objects can only meet where their
+//! orbits come close, and only when both pass that region at the same time.
+//! All tests use fixed two-body orbits, so callers pad the distance to
+//! absorb perturbations over the screening window.
+//!
+//! The orbit path distance is the minimum orbital intersection distance
+//! (MOID): time-independent, it bounds how close two objects can ever get
+//! while their orbits stay fixed, and is reported on its own as a long-term
+//! risk metric.
+
+use nalgebra::{Matrix2, Vector2, Vector3};
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use std::f64::consts::PI;
+
+use crate::constants::MU_EARTH;
+#[cfg(feature = "python")]
+use crate::elements::KeplerianElements;
+use crate::elements::{self, EquinoctialElements};
+#[cfg(feature = "python")]
+use crate::Satellite;
+
+/// True longitude samples per orbit in the coarse orbit-path search
+const PATH_SAMPLES: usize = 72;
+
+/// Local minima of the coarse search that are refined
+const PATH_REFINEMENTS: usize = 4;
+
+/// Newton steps polishing each refined minimum
+const NEWTON_ITERATIONS: usize = 8;
+
+/// Relative inclination (sine) below which orbits are treated as coplanar
+const COPLANAR_SIN: f64 = 1.0e-3;
+
+/// Perigee and apogee radii (km) of the osculating two-body orbit
+///
+/// Unbound (parabolic/hyperbolic) states have an infinite apogee.
+pub fn perigee_apogee(position: &Vector3<f64>, velocity: &Vector3<f64>) -> (f64, f64) {
+    let h = position.cross(velocity);
+    let r = position.norm();
+    if r == 0.0 {
+        return (0.0, f64::INFINITY);
+    }
+    let e_vec = velocity.cross(&h) / MU_EARTH - position / r;
+    let e = e_vec.norm();
+    let p = h.norm_squared() / MU_EARTH;
+
+    let perigee = p / (1.0 + e);
+    let apogee = if e < 1.0 { p / (1.0 - e) } else { f64::INFINITY };
+    (perigee, apogee)
+}
+
+/// True if two radial bands come within `threshold_km` of each other
+///
+/// Each band is `(perigee, apogee)`; objects whose bands are separated by
+/// more than the threshold can never be closer than that gap.
+pub fn apsis_bands_overlap(a: (f64, f64), b: (f64, f64), threshold_km: f64) -> bool {
+    let gap = a.0.max(b.0) - a.1.min(b.1);
+    gap <= threshold_km
+}
+
+/// Fixed two-body ellipse, parametrised by true longitude
+///
+/// Built from equinoctial elements, so circular and equatorial orbits need
+/// no special reference direction.
+#[derive(Clone, Copy, Debug)]
+pub struct OrbitGeometry {
+    pub normal: Vector3<f64>,
+    f_hat: Vector3<f64>,
+    g_hat: Vector3<f64>,
+    /// Eccentricity vector components along (g, f)
+    h: f64,
+    k: f64,
+    pub semi_latus_rectum: f64,
+    pub eccentricity: f64,
+    pub mean_motion: f64,
+    mean_longitude_at_epoch: f64,
+    pub perigee: f64,
+    pub apogee: f64,
+}
+
+impl OrbitGeometry {
+    /// Geometry of the osculating orbit; None for unbound or degenerate states
+    pub fn from_state(position: &Vector3<f64>, velocity: &Vector3<f64>) -> Option<Self> {
+        elements::equinoctial_from_state(position, velocity).map(|el| Self::from_equinoctial(&el))
+    }
+
+    /// Geometry of an element set, with the epoch at its mean longitude
+    pub fn from_equinoctial(el: &EquinoctialElements) -> Self {
+        let [f_hat, g_hat, normal] = el.frame();
+        let e = el.eccentricity();
+        let a = el.semi_major_axis_km;
+        let p = a * (1.0 - e * e);
+        OrbitGeometry {
+            normal,
+            f_hat,
+            g_hat,
+            h: el.h,
+            k: el.k,
+            semi_latus_rectum: p,
+            eccentricity: e,
+            mean_motion: el.mean_motion(),
+            mean_longitude_at_epoch: el.mean_longitude_deg.to_radians(),
+            perigee: p / (1.0 + e),
+            apogee: p / (1.0 - e),
+        }
+    }
+
+    /// Orbital period (s)
+    pub fn period(&self) -> f64 {
+        2.0 * PI / self.mean_motion
+    }
+
+    /// Position on the orbit at true longitude `l`
+    pub fn position_at(&self, l: f64) -> Vector3<f64> {
+        let radius = self.semi_latus_rectum / (1.0 + self.h * l.sin() + self.k * l.cos());
+        (self.f_hat * l.cos() + self.g_hat * l.sin()) * radius
+    }
+
+    /// Derivative of `position_at` with respect to the true longitude
+    fn tangent_at(&self, l: f64) -> Vector3<f64> {
+        let (sin, cos) = l.sin_cos();
+        let denominator = 1.0 + self.h * sin + self.k * cos;
+        let radius = self.semi_latus_rectum / denominator;
+        let radius_rate = -self.semi_latus_rectum * (self.h * cos - self.k * sin) / (denominator * denominator);
+        (self.f_hat * cos + self.g_hat * sin) * radius_rate + (self.g_hat * cos - self.f_hat * sin) * radius
+    }
+
+    /// True longitude of a direction lying in the orbit plane
+    fn true_longitude_of(&self, direction: &Vector3<f64>) -> f64 {
+        direction.dot(&self.g_hat).atan2(direction.dot(&self.f_hat))
+    }
+
+    fn mean_longitude(&self, l: f64) -> f64 {
+        let e = self.eccentricity;
+        let periapsis = self.h.atan2(self.k);
+        let f = l - periapsis;
+        let big_e = 2.0 * (((1.0 - e) / (1.0 + e)).sqrt() * (f / 2.0).tan()).atan();
+        periapsis + big_e - e * big_e.sin()
+    }
+
+    /// First time (s after the epoch, in [0, period)) the object reaches true longitude `l`
+    fn time_of(&self, l: f64) -> f64 {
+        let dm = (self.mean_longitude(l) - self.mean_longitude_at_epoch).rem_euclid(2.0 * PI);
+        dm / self.mean_motion
+    }
+}
+
+/// Minimum distance between two fixed orbits (km), by grid search and local refinement
+pub fn orbit_path_distance(a: &OrbitGeometry, b: &OrbitGeometry) -> f64 {
+    moid(a, b).0
+}
+
+/// MOID (km) and the true longitudes on `a` and `b` where it is reached
+///
+/// The orbits are sampled on a grid of true longitudes and the deepest
+/// local minima are refined by compass search, then polished with Newton
+/// steps on the squared distance: crossing orbits meet at a shallow angle,
+/// and compass moves along the two longitudes stall in that narrow valley.
+/// Nearly tangent orbits with several minima report the smallest one found.
+pub fn moid(a: &OrbitGeometry, b: &OrbitGeometry) -> (f64, f64, f64) {
+    let step = 2.0 * PI / PATH_SAMPLES as f64;
+    let points_b: Vec<Vector3<f64>> = (0..PATH_SAMPLES).map(|k| b.position_at(k as f64 * step)).collect();
+    let grid: Vec<Vec<f64>> = (0..PATH_SAMPLES)
+        .map(|i| {
+            let pa = a.position_at(i as f64 * step);
+            points_b.iter().map(|pb| (pa - pb).norm()).collect()
+        })
+        .collect();
+
+    // local minima of the periodic grid
+    let n = PATH_SAMPLES;
+    let mut minima: Vec<(f64, usize, usize)> = Vec::new();
+    for i in 0..n {
+        for j in 0..n {
+            let d = grid[i][j];
+            let is_minimum = (-1i64..=1).all(|di| {
+                (-1i64..=1).all(|dj| {
+                    let ii = (i as i64 + di).rem_euclid(n as i64) as usize;
+                    let jj = (j as i64 + dj).rem_euclid(n as i64) as usize;
+                    grid[ii][jj] >= d
+                })
+            });
+            if is_minimum {
+                minima.push((d, i, j));
+            }
+        }
+    }
+    minima.sort_by(|x, y| x.0.total_cmp(&y.0));
+
+    let distance_sq = |fa: f64, fb: f64| (a.position_at(fa) - b.position_at(fb)).norm_squared();
+    minima
+        .iter()
+        .take(PATH_REFINEMENTS)
+        .map(|&(d0, i, j)| -> (f64, f64, f64) {
+            // compass search on the two longitudes
+            let (mut fa, mut fb, mut best) = (i as f64 * step, j as f64 * step, d0 * d0);
+            let mut delta = step / 2.0;
+            while delta > 1.0e-9 {
+                let mut improved = false;
+                for (da, db) in [(delta, 0.0), (-delta, 0.0), (0.0, delta), (0.0, -delta)] {
+                    let d = distance_sq(fa + da, fb + db);
+                    if d < best {
+                        best = d;
+                        fa += da;
+                        fb += db;
+                        improved = true;
+                        break;
+                    }
+                }
+                if !improved {
+                    delta /= 2.0;
+                }
+            }
+            for _ in 0..NEWTON_ITERATIONS {
+                let Some((na, nb)) = newton_step(a, b, fa, fb) else {
+                    break;
+                };
+                let d = distance_sq(na, nb);
+                if d.is_nan() || d >= best {
+                    break;
+                }
+                (fa, fb, best) = (na, nb, d);
+            }
+            (best.sqrt(), fa.rem_euclid(2.0 * PI), fb.rem_euclid(2.0 * PI))
+        })
+        .fold((f64::INFINITY, 0.0, 0.0), |best, candidate| if candidate.0 < best.0 { candidate } else { best })
+}
+
+/// Newton step towards a stationary point of the squared distance between orbit points
+///
+/// Gradient terms are analytic; the curvature of each orbit comes from
+/// differencing its tangent. None when the Hessian is singular.
+fn newton_step(a: &OrbitGeometry, b: &OrbitGeometry, fa: f64, fb: f64) -> Option<(f64, f64)> {
+    const EPS: f64 = 1.0e-6;
+    let d = a.position_at(fa) - b.position_at(fb);
+    let (ta, tb) = (a.tangent_at(fa), b.tangent_at(fb));
+    let ca = (a.tangent_at(fa + EPS) - a.tangent_at(fa - EPS)) / (2.0 * EPS);
+    let cb = (b.tangent_at(fb + EPS) - b.tangent_at(fb - EPS)) / (2.0 * EPS);
+    let gradient = Vector2::new(d.dot(&ta), -d.dot(&tb));
+    let hessian = Matrix2::new(ta.dot(&ta) + d.dot(&ca), -ta.dot(&tb), -ta.dot(&tb), tb.dot(&tb) - d.dot(&cb));
+    let step = hessian.try_inverse()? * gradient;
+    Some((fa - step.x, fb - step.y))
+}
+
+/// Orbit given to `moid`: classical or equinoctial elements, or a state vector
+#[cfg(feature = "python")]
+#[derive(FromPyObject)]
+#[allow(clippy::large_enum_variant)]
+enum OrbitSource {
+    Keplerian(KeplerianElements),
+    Equinoctial(EquinoctialElements),
+    Satellite(Satellite),
+}
+
+#[cfg(feature = "python")]
+impl OrbitSource {
+    fn geometry(&self) -> PyResult<OrbitGeometry> {
+        let geometry = match self {
+            OrbitSource::Keplerian(el) => EquinoctialElements::from_keplerian(el).map(|el| OrbitGeometry::from_equinoctial(&el)),
+            OrbitSource::Equinoctial(el) => (el.semi_major_axis_km > 0.0 && el.eccentricity() < 1.0)
+                .then(|| OrbitGeometry::from_equinoctial(el)),
+            OrbitSource::Satellite(sat) => OrbitGeometry::from_state(&sat.r(), &sat.v()),
+        };
+        geometry.ok_or_else(|| pyo3::exceptions::PyValueError::new_err("MOID needs bound (elliptical) orbits"))
+    }
+}
+
+/// Minimum orbital intersection distance between two orbits
+///
+/// Each orbit is a `KeplerianElements`, an `EquinoctialElements` or a
+/// `Satellite` (its osculating orbit). Returns (moid_km, point_1, point_2),
+/// the closest points of the two orbits (km, in the elements' frame).
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(name = "moid")]
+pub fn py_moid(orbit_1: &PyAny, orbit_2: &PyAny) -> PyResult<(f64, Vec<f64>, Vec<f64>)> {
+    let a = orbit_1.extract::<OrbitSource>()?.geometry()?;
+    let b = orbit_2.extract::<OrbitSource>()?.geometry()?;
+    let (distance, la, lb) = moid(&a, &b);
+    Ok((distance, a.position_at(la).as_slice().to_vec(), b.position_at(lb).as_slice().to_vec()))
+}
+
+/// Time intervals (s) within [0, duration] when an object lies inside the slab
+/// of half-width `distance_km` around the other orbit's plane
+fn node_windows(orbit: &OrbitGeometry, node: &Vector3<f64>, sin_rel: f64, distance_km: f64, duration: f64) -> Vec<(f64, f64)> {
+    let ratio = distance_km / (orbit.perigee * sin_rel);
+    if ratio >= 1.0 {
+        return vec![(0.0, duration)];
+    }
+    let half_width = ratio.asin();
+    let l_node = orbit.true_longitude_of(node);
+    let period = orbit.period();
+
+    let mut windows = Vec::new();
+    for centre in [l_node, l_node + PI] {
+        let start = orbit.time_of(centre - half_width);
+        let mut end = orbit.time_of(centre + half_width);
+        if end < start {
+            end += period;
+        }
+        // the window containing the epoch may have started in the previous revolution
+        let mut offset = -period;
+        while start + offset <= duration {
+            let (lo, hi) = (start + offset, end + offset);
+            if hi >= 0.0 {
+                windows.push((lo.max(0.0), hi.min(duration)));
+            }
+            offset += period;
+        }
+    }
+    windows.sort_by(|x, y| x.0.total_cmp(&y.0));
+    windows
+}
+
+/// True if both objects can be near the mutual line of nodes at the same time
+///
+/// Coplanar orbits always pass, as the node line is undefined.
+pub fn node_windows_overlap(a: &OrbitGeometry, b: &OrbitGeometry, distance_km: f64, duration_s: f64) -> bool {
+    let node = a.normal.cross(&b.normal);
+    let sin_rel = node.norm();
+    if sin_rel < COPLANAR_SIN {
+        return true;
+    }
+    let node = node / sin_rel;
+    let wa = node_windows(a, &node, sin_rel, distance_km, duration_s);
+    let wb = node_windows(b, &node, sin_rel, distance_km, duration_s);
+
+    wa.iter().any(|&(lo_a, hi_a)| wb.iter().any(|&(lo_b, hi_b)| lo_a <= hi_b && lo_b <= hi_a))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_circular_orbit_bands() {
+        let r = 7000.0;
+        let v = (MU_EARTH / r).sqrt();
+        let (perigee, apogee) = perigee_apogee(&Vector3::new(r, 0.0, 0.0), &Vector3::new(0.0, v, 0.0));
+        assert!((perigee - r).abs() < 1e-6);
+        assert!((apogee - r).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_band_overlap() {
+        let leo = (6900.0, 7100.0);
+        let geo = (42150.0, 42180.0);
+        let gto = (6600.0, 42200.0);
+        assert!(!apsis_bands_overlap(leo, geo, 10.0));
+        assert!(apsis_bands_overlap(leo, gto, 10.0));
+        assert!(apsis_bands_overlap((7000.0, 7000.0), (7005.0, 7005.0), 10.0));
+    }
+
+    fn circular(radius: f64, inclination_deg: f64, phase_deg: f64) -> OrbitGeometry {
+        let (i, u) = (inclination_deg.to_radians(), phase_deg.to_radians());
+        let v = (MU_EARTH / radius).sqrt();
+        let r = Vector3::new(u.cos(), u.sin() * i.cos(), u.sin() * i.sin()) * radius;
+        let vel = Vector3::new(-u.sin(), u.cos() * i.cos(), u.cos() * i.sin()) * v;
+        OrbitGeometry::from_state(&r, &vel).unwrap()
+    }
+
+    #[test]
+    fn test_orbit_path_distance_crossing_shells() {
+        // Circular orbits in different planes are separated by the radius gap at the nodes
+        let d = orbit_path_distance(&circular(7000.0, 0.0, 0.0), &circular(7003.0, 60.0, 0.0));
+        assert!((d - 3.0).abs() < 1e-6, "{}", d);
+    }
+
+    #[test]
+    fn test_moid_at_the_node_of_an_inclined_ellipse() {
+        // perigee (10 km outside the circle) on the line of nodes; the orbits are closest there
+        let circle = circular(7000.0, 0.0, 0.0);
+        let elements = elements::KeplerianElements {
+            semi_major_axis_km: 7510.0,
+            eccentricity: 500.0 / 7510.0,
+            inclination_deg: 30.0,
+            raan_deg: 40.0,
+            arg_perigee_deg: 0.0,
+            true_anomaly_deg: 123.0,
+        };
+        let geometry = |el: &elements::KeplerianElements| {
+            OrbitGeometry::from_equinoctial(&EquinoctialElements::from_keplerian(el).unwrap())
+        };
+        let ellipse = geometry(&elements);
+        let (distance, l_circle, l_ellipse) = moid(&circle, &ellipse);
+        assert!((distance - 10.0).abs() < 1e-6, "{}", distance);
+        assert!(((circle.position_at(l_circle) - ellipse.position_at(l_ellipse)).norm() - distance).abs() < 1e-9);
+        assert!((l_ellipse - 40f64.to_radians()).abs() < 1e-4);
+
+        // a coplanar ellipse crossing the circle intersects it
+        let crossing = elements::KeplerianElements { inclination_deg: 0.0, semi_major_axis_km: 7100.0, ..elements };
+        let d = moid(&circle, &geometry(&crossing)).0;
+        assert!(d < 1e-6, "{}", d);
+    }
+
+    #[test]
+    fn test_geometry_from_equinoctial_elements() {
+        // circular equatorial GEO slots 5 km apart in radius, a quarter orbit apart in longitude
+        let slot = |a: f64, longitude: f64| EquinoctialElements {
+            semi_major_axis_km: a,
+            h: 0.0,
+            k: 0.0,
+            p: 0.0,
+            q: 0.0,
+            mean_longitude_deg: longitude,
+            retrograde: false,
+        };
+        let (a, b) = (OrbitGeometry::from_equinoctial(&slot(42164.0, 0.0)), OrbitGeometry::from_equinoctial(&slot(42169.0, 90.0)));
+        assert!((orbit_path_distance(&a, &b) - 5.0).abs() < 1e-6);
+        assert!((a.time_of(PI / 2.0) - 0.25 * a.period()).abs() < 1e-6);
+
+        let (r, v) = elements::equinoctial_to_state(&slot(42169.0, 90.0)).unwrap();
+        let c = OrbitGeometry::from_state(&r, &v).unwrap();
+        assert!((c.position_at(1.0) - b.position_at(1.0)).norm() < 1e-6);
+    }
+
+    #[test]
+    fn test_time_filter_phasing() {
+        // Same radius, planes 90 degrees apart; one object trails by a quarter orbit
+        let a = circular(7000.0, 0.0, 0.0);
+        let in_phase = circular(7000.0, 90.0, 0.0);
+        let out_of_phase = circular(7000.0, 90.0, 90.0);
+        let duration = 0.25 * a.period();
+        assert!(node_windows_overlap(&a, &in_phase, 10.0, duration));
+        assert!(!node_windows_overlap(&a, &out_of_phase, 10.0, duration));
+    }
+}
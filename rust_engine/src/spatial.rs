@@ -0,0 +1,91 @@
+//! Uniform grid spatial hash used to pre-filter conjunction candidates
+//!
+//! With a cell edge equal to the screening threshold, any pair closer than
+//! the threshold lies in the same or an adjacent cell, so only the 27
+//! surrounding cells need to be visited for each object.
+
+use std::collections::HashMap;
+
+type CellKey = (i64, i64, i64);
+
+/// Objects bucketed by cubic cell
+pub struct UniformGrid {
+    cell_size: f64,
+    cells: HashMap<CellKey, Vec<usize>>,
+}
+
+impl UniformGrid {
+    /// Bucket `positions` (km) into cells of edge `cell_size` (km)
+    pub fn build(positions: &[[f64; 3]], cell_size: f64) -> Self {
+        let mut cells: HashMap<CellKey, Vec<usize>> = HashMap::new();
+        for (idx, pos) in positions.iter().enumerate() {
+            cells.entry(cell_key(pos, cell_size)).or_default().push(idx);
+        }
+        UniformGrid { cell_size, cells }
+    }
+
+    /// Indices of every object in the cell containing `pos` and its 26 neighbours
+    pub fn neighbors(&self, pos: &[f64; 3]) -> Vec<usize> {
+        let (cx, cy, cz) = cell_key(pos, self.cell_size);
+        let mut found = Vec::new();
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                for dz in -1..=1 {
+                    // keys saturate for tiny cells; a neighbour past i64::MAX/MIN doesn't exist
+                    let (Some(x), Some(y), Some(z)) = (cx.checked_add(dx), cy.checked_add(dy), cz.checked_add(dz)) else {
+                        continue;
+                    };
+                    if let Some(members) = self.cells.get(&(x, y, z)) {
+                        found.extend_from_slice(members);
+                    }
+                }
+            }
+        }
+        found
+    }
+
+    /// Number of occupied cells
+    pub fn occupied_cells(&self) -> usize {
+        self.cells.len()
+    }
+}
+
+fn cell_key(pos: &[f64; 3], cell_size: f64) -> CellKey {
+    (
+        (pos[0] / cell_size).floor() as i64,
+        (pos[1] / cell_size).floor() as i64,
+        (pos[2] / cell_size).floor() as i64,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_neighbors_cover_adjacent_cells() {
+        let positions = [
+            [0.0, 0.0, 0.0],
+            [12.0, 0.0, 0.0],  // adjacent cell
+            [-0.5, -0.5, 0.5], // adjacent cell across the origin
+            [25.0, 0.0, 0.0],  // two cells away
+        ];
+        let grid = UniformGrid::build(&positions, 10.0);
+
+        let mut found = grid.neighbors(&positions[0]);
+        found.sort_unstable();
+        assert_eq!(found, vec![0, 1, 2]);
+        assert_eq!(grid.occupied_cells(), 4);
+    }
+
+    #[test]
+    fn test_tiny_cells_saturate_without_overflow() {
+        let positions = [[7000.0, 0.0, 0.0], [7000.0, 1.0, 0.0], [-7000.0, 0.0, 0.0]];
+        let grid = UniformGrid::build(&positions, 1e-300);
+
+        let mut found = grid.neighbors(&positions[0]);
+        found.sort_unstable();
+        assert_eq!(found, vec![0]);
+        assert_eq!(grid.neighbors(&positions[2]), vec![2]);
+    }
+}
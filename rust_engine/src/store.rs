@@ -0,0 +1,405 @@
+//! SQLite persistence for satellite states and screening results
+//!
+//! A `CatalogStore` keeps every saved state keyed by (NORAD id, epoch), so a
+//! catalogue can be reloaded as of any instant. Conjunctions are stored
+//! under the pair (lower id, higher id); saving one replaces the row for
+//! the same pair whose TCA lies within `TCA_MATCH_TOLERANCE_S`, so a
+//! re-screen updates its events instead of duplicating them when the
+//! refined TCA moves or the catalogue lists the two objects the other way
+//! round. Each row stores the object's JSON form next to the columns that
+//! queries filter on.
+
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::sync::{Mutex, MutexGuard};
+
+use crate::constants::SECONDS_PER_DAY;
+#[cfg(feature = "python")]
+use crate::epoch::{now_mjd_utc, MJD_OFFSET};
+use crate::error::OrbitError;
+use crate::{Conjunction, Satellite};
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS satellites (
+        norad_id INTEGER NOT NULL,
+        epoch_jd REAL NOT NULL,
+        name TEXT,
+        data TEXT NOT NULL,
+        PRIMARY KEY (norad_id, epoch_jd)
+    );
+    CREATE TABLE IF NOT EXISTS conjunctions (
+        norad_id_1 INTEGER NOT NULL,
+        norad_id_2 INTEGER NOT NULL,
+        international_designator_1 TEXT,
+        international_designator_2 TEXT,
+        tca_jd REAL NOT NULL,
+        distance_km REAL NOT NULL,
+        probability REAL,
+        screened_jd REAL NOT NULL,
+        data TEXT NOT NULL,
+        CHECK (norad_id_1 <= norad_id_2)
+    );
+    CREATE INDEX IF NOT EXISTS conjunctions_by_pair ON conjunctions (norad_id_1, norad_id_2, tca_jd);
+    CREATE INDEX IF NOT EXISTS conjunctions_by_secondary ON conjunctions (norad_id_2, tca_jd);
+    CREATE INDEX IF NOT EXISTS conjunctions_by_tca ON conjunctions (tca_jd);
+";
+
+/// Largest TCA difference (seconds) at which a saved conjunction replaces a
+/// stored one of the same pair; successive encounters of a pair are at least
+/// half an orbit (~45 minutes) apart
+pub const TCA_MATCH_TOLERANCE_S: f64 = 60.0;
+
+fn storage_error(err: rusqlite::Error) -> OrbitError {
+    OrbitError::Storage(err.to_string())
+}
+
+fn decode<T: serde::de::DeserializeOwned>(row: &rusqlite::Row<'_>) -> rusqlite::Result<T> {
+    let data: String = row.get(0)?;
+    serde_json::from_str(&data).map_err(|e| rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e)))
+}
+
+/// SQLite database of satellite states and conjunctions
+#[cfg_attr(feature = "python", pyclass(module = "orbit_core"))]
+pub struct CatalogStore {
+    path: String,
+    connection: Mutex<Connection>,
+}
+
+impl CatalogStore {
+    /// Open or create a store; ":memory:" keeps it in memory
+    pub fn open(path: &str) -> Result<Self, OrbitError> {
+        let connection = Connection::open(path).map_err(|e| OrbitError::Storage(format!("{}: {}", path, e)))?;
+        connection.execute_batch(SCHEMA).map_err(storage_error)?;
+        Ok(CatalogStore { path: path.to_string(), connection: Mutex::new(connection) })
+    }
+
+    /// Database path the store was opened with
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    fn connection(&self) -> MutexGuard<'_, Connection> {
+        self.connection.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Insert or replace states; each needs an epoch. Returns the number saved.
+    pub fn save_satellites(&self, satellites: &[Satellite]) -> Result<usize, OrbitError> {
+        let rows = satellites
+            .iter()
+            .map(|sat| {
+                let epoch = sat.epoch.ok_or_else(|| {
+                    OrbitError::InvalidState(format!("Satellite {} has no epoch; only time-tagged states can be stored", sat.norad_id))
+                })?;
+                let data = serde_json::to_string(sat).map_err(|e| OrbitError::Storage(e.to_string()))?;
+                Ok((sat.norad_id, epoch.jd_utc(), sat.name.as_deref(), data))
+            })
+            .collect::<Result<Vec<_>, OrbitError>>()?;
+
+        let mut db = self.connection();
+        let transaction = db.transaction().map_err(storage_error)?;
+        {
+            let mut insert = transaction
+                .prepare("INSERT OR REPLACE INTO satellites (norad_id, epoch_jd, name, data) VALUES (?1, ?2, ?3, ?4)")
+                .map_err(storage_error)?;
+            for (norad_id, epoch_jd, name, data) in &rows {
+                insert.execute(params![norad_id, epoch_jd, name, data]).map_err(storage_error)?;
+            }
+        }
+        transaction.commit().map_err(storage_error)?;
+        Ok(rows.len())
+    }
+
+    /// Latest state of every object, or the latest at or before `at_jd`
+    pub fn load_satellites(&self, at_jd: Option<f64>) -> Result<Vec<Satellite>, OrbitError> {
+        self.connection()
+            .prepare(
+                "SELECT s.data FROM satellites s
+                 JOIN (SELECT norad_id, MAX(epoch_jd) AS epoch_jd FROM satellites
+                       WHERE ?1 IS NULL OR epoch_jd <= ?1 GROUP BY norad_id) latest
+                 USING (norad_id, epoch_jd)
+                 ORDER BY s.norad_id",
+            )
+            .and_then(|mut query| query.query_map([at_jd], decode)?.collect())
+            .map_err(storage_error)
+    }
+
+    /// Julian dates (UTC) of the states saved for one object, oldest first
+    pub fn epochs(&self, norad_id: i32) -> Result<Vec<f64>, OrbitError> {
+        self.connection()
+            .prepare("SELECT epoch_jd FROM satellites WHERE norad_id = ?1 ORDER BY epoch_jd")
+            .and_then(|mut query| query.query_map([norad_id], |row| row.get(0))?.collect())
+            .map_err(storage_error)
+    }
+
+    /// Insert time-tagged conjunctions found by a screen run at `screened_jd`,
+    /// replacing stored events of the same pair within `TCA_MATCH_TOLERANCE_S`
+    pub fn save_conjunctions(&self, conjunctions: &[Conjunction], screened_jd: f64) -> Result<usize, OrbitError> {
+        let rows = conjunctions
+            .iter()
+            .map(|c| {
+                let tca_jd = c.tca_jd.ok_or_else(|| {
+                    OrbitError::InvalidInput(format!(
+                        "Conjunction {}-{} has no TCA; only time-tagged events can be stored",
+                        c.norad_id_1, c.norad_id_2
+                    ))
+                })?;
+                let data = serde_json::to_string(c).map_err(|e| OrbitError::Storage(e.to_string()))?;
+                // the designator columns follow the ids into (min, max) order
+                let (first, second) = (&c.international_designator_1, &c.international_designator_2);
+                let (pair, designators) = if c.norad_id_1 <= c.norad_id_2 {
+                    ((c.norad_id_1, c.norad_id_2), (first, second))
+                } else {
+                    ((c.norad_id_2, c.norad_id_1), (second, first))
+                };
+                Ok((c, pair, designators, tca_jd, data))
+            })
+            .collect::<Result<Vec<_>, OrbitError>>()?;
+
+        let tolerance_days = TCA_MATCH_TOLERANCE_S / SECONDS_PER_DAY;
+        let mut db = self.connection();
+        let transaction = db.transaction().map_err(storage_error)?;
+        {
+            let mut existing = transaction
+                .prepare(
+                    "SELECT rowid FROM conjunctions
+                     WHERE norad_id_1 = ?1 AND norad_id_2 = ?2 AND tca_jd BETWEEN ?3 - ?4 AND ?3 + ?4
+                     ORDER BY ABS(tca_jd - ?3) LIMIT 1",
+                )
+                .map_err(storage_error)?;
+            let mut update = transaction
+                .prepare(
+                    "UPDATE conjunctions SET international_designator_1 = ?2, international_designator_2 = ?3,
+                     tca_jd = ?4, distance_km = ?5, probability = ?6, screened_jd = ?7, data = ?8
+                     WHERE rowid = ?1",
+                )
+                .map_err(storage_error)?;
+            let mut insert = transaction
+                .prepare(
+                    "INSERT INTO conjunctions (norad_id_1, norad_id_2, international_designator_1, international_designator_2,
+                     tca_jd, distance_km, probability, screened_jd, data)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                )
+                .map_err(storage_error)?;
+            for (c, (id1, id2), (designator1, designator2), tca_jd, data) in &rows {
+                let row: Option<i64> = existing
+                    .query_row(params![id1, id2, tca_jd, tolerance_days], |row| row.get(0))
+                    .optional()
+                    .map_err(storage_error)?;
+                match row {
+                    Some(rowid) => update.execute(params![
+                        rowid,
+                        designator1,
+                        designator2,
+                        tca_jd,
+                        c.distance_km,
+                        c.probability,
+                        screened_jd,
+                        data
+                    ]),
+                    None => insert.execute(params![
+                        id1,
+                        id2,
+                        designator1,
+                        designator2,
+                        tca_jd,
+                        c.distance_km,
+                        c.probability,
+                        screened_jd,
+                        data
+                    ]),
+                }
+                .map_err(storage_error)?;
+            }
+        }
+        transaction.commit().map_err(storage_error)?;
+        Ok(rows.len())
+    }
+
+    /// Stored conjunctions ordered by TCA, optionally involving `norad_id`,
+    /// with TCA in [start_jd, end_jd] and a miss distance up to `max_distance_km`
+    pub fn conjunctions(
+        &self,
+        norad_id: Option<i32>,
+        start_jd: Option<f64>,
+        end_jd: Option<f64>,
+        max_distance_km: Option<f64>,
+    ) -> Result<Vec<Conjunction>, OrbitError> {
+        self.connection()
+            .prepare(
+                "SELECT data FROM conjunctions
+                 WHERE (?1 IS NULL OR norad_id_1 = ?1 OR norad_id_2 = ?1)
+                   AND (?2 IS NULL OR tca_jd >= ?2)
+                   AND (?3 IS NULL OR tca_jd <= ?3)
+                   AND (?4 IS NULL OR distance_km <= ?4)
+                 ORDER BY tca_jd, norad_id_1, norad_id_2",
+            )
+            .and_then(|mut query| {
+                query.query_map(params![norad_id, start_jd, end_jd, max_distance_km], decode)?.collect()
+            })
+            .map_err(storage_error)
+    }
+}
+
+#[cfg(feature = "python")]
+fn now_jd() -> f64 {
+    now_mjd_utc() + MJD_OFFSET
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl CatalogStore {
+    #[new]
+    fn new(path: &str) -> PyResult<Self> {
+        Ok(CatalogStore::open(path)?)
+    }
+
+    /// Database path the store was opened with
+    #[getter]
+    #[pyo3(name = "path")]
+    fn py_path(&self) -> &str {
+        self.path()
+    }
+
+    /// Save satellite states (each needs an epoch); returns how many were written
+    #[pyo3(name = "save_satellites")]
+    fn py_save_satellites(&self, py: Python<'_>, satellites: Vec<Satellite>) -> PyResult<usize> {
+        Ok(py.allow_threads(|| self.save_satellites(&satellites))?)
+    }
+
+    /// Latest saved state of every object, or the latest at or before `at_jd`
+    #[pyo3(name = "load_satellites", signature = (at_jd = None))]
+    fn py_load_satellites(&self, py: Python<'_>, at_jd: Option<f64>) -> PyResult<Vec<Satellite>> {
+        Ok(py.allow_threads(|| self.load_satellites(at_jd))?)
+    }
+
+    /// Julian dates (UTC) of the states saved for `norad_id`, oldest first
+    #[pyo3(name = "epochs")]
+    fn py_epochs(&self, norad_id: i32) -> PyResult<Vec<f64>> {
+        Ok(self.epochs(norad_id)?)
+    }
+
+    /// Save screening results; an event stored for the same pair within a minute of the TCA is replaced
+    ///
+    /// `screened_jd` records when the screen ran (default: now).
+    #[pyo3(name = "save_conjunctions", signature = (conjunctions, screened_jd = None))]
+    fn py_save_conjunctions(&self, py: Python<'_>, conjunctions: Vec<Conjunction>, screened_jd: Option<f64>) -> PyResult<usize> {
+        let screened_jd = screened_jd.unwrap_or_else(now_jd);
+        Ok(py.allow_threads(|| self.save_conjunctions(&conjunctions, screened_jd))?)
+    }
+
+    /// Stored conjunctions ordered by TCA, filtered by object, TCA window and miss distance
+    #[pyo3(name = "conjunctions", signature = (norad_id = None, start_jd = None, end_jd = None, max_distance_km = None))]
+    fn py_conjunctions(
+        &self,
+        py: Python<'_>,
+        norad_id: Option<i32>,
+        start_jd: Option<f64>,
+        end_jd: Option<f64>,
+        max_distance_km: Option<f64>,
+    ) -> PyResult<Vec<Conjunction>> {
+        Ok(py.allow_threads(|| self.conjunctions(norad_id, start_jd, end_jd, max_distance_km))?)
+    }
+
+    /// Stored conjunctions with TCA in the last `days` days, e.g. all events for one object this week
+    #[pyo3(signature = (norad_id = None, days = 7.0))]
+    fn recent_conjunctions(&self, py: Python<'_>, norad_id: Option<i32>, days: f64) -> PyResult<Vec<Conjunction>> {
+        if !(days > 0.0 && days.is_finite()) {
+            return Err(pyo3::exceptions::PyValueError::new_err("days must be positive"));
+        }
+        let now = now_jd();
+        Ok(py.allow_threads(|| self.conjunctions(norad_id, Some(now - days), Some(now), None))?)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("CatalogStore('{}')", self.path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::epoch::Epoch;
+
+    fn satellite(norad_id: i32, jd: f64) -> Satellite {
+        Satellite::from_state(norad_id, [7000.0, 0.0, jd - 2460000.0], [0.0, 7.5, 0.0], Some(Epoch::from_jd_utc(jd)))
+    }
+
+    fn conjunction(id1: i32, id2: i32, tca_jd: f64, distance_km: f64) -> Conjunction {
+        let (r1, v1) = (nalgebra::Vector3::new(7000.0, 0.0, 0.0), nalgebra::Vector3::new(0.0, 7.5, 0.0));
+        let (r2, v2) = (r1 + nalgebra::Vector3::new(distance_km, 0.0, 0.0), nalgebra::Vector3::new(0.0, 0.0, 7.5));
+        Conjunction::from_states(id1, id2, (&r1, &v1), (&r2, &v2), Some(tca_jd))
+    }
+
+    #[test]
+    fn test_latest_states_as_of_epoch() {
+        let store = CatalogStore::open(":memory:").unwrap();
+        let saved = store.save_satellites(&[satellite(1, 2460000.5), satellite(1, 2460001.5), satellite(2, 2460000.5)]);
+        assert_eq!(saved, Ok(3));
+        assert_eq!(store.save_satellites(&[satellite(1, 2460001.5)]), Ok(1));
+
+        let latest: Vec<(i32, f64)> =
+            store.load_satellites(None).unwrap().iter().map(|s| (s.norad_id, s.epoch.unwrap().jd_utc())).collect();
+        assert_eq!(latest.len(), 2);
+        assert!((latest[0].1 - 2460001.5).abs() < 1e-6);
+        let earlier = store.load_satellites(Some(2460001.0)).unwrap();
+        assert!((earlier[0].epoch.unwrap().jd_utc() - 2460000.5).abs() < 1e-6);
+        assert_eq!(store.epochs(1).unwrap().len(), 2);
+
+        let mut undated = satellite(3, 2460000.5);
+        undated.epoch = None;
+        assert!(matches!(store.save_satellites(&[undated]), Err(OrbitError::InvalidState(_))));
+    }
+
+    #[test]
+    fn test_conjunction_queries_and_resaves() {
+        let store = CatalogStore::open(":memory:").unwrap();
+        let events = [
+            conjunction(25544, 1, 2460000.6, 2.0),
+            conjunction(2, 25544, 2460003.6, 0.5),
+            conjunction(3, 4, 2460001.6, 1.0),
+        ];
+        store.save_conjunctions(&events, 2460000.5).unwrap();
+        store.save_conjunctions(&events[..1], 2460000.7).unwrap();
+        // a re-screen refines the TCA by a few milliseconds: still the same event
+        store.save_conjunctions(&[conjunction(25544, 1, 2460000.6 + 5.0e-8, 2.1)], 2460000.8).unwrap();
+
+        let iss = store.conjunctions(Some(25544), None, None, None).unwrap();
+        assert_eq!(iss.iter().map(|c| c.norad_id_1).collect::<Vec<_>>(), vec![25544, 2]);
+        assert_eq!(store.conjunctions(Some(25544), Some(2460001.0), None, None).unwrap().len(), 1);
+        assert_eq!(store.conjunctions(None, None, None, Some(1.0)).unwrap().len(), 2);
+        assert_eq!(store.conjunctions(None, None, None, None).unwrap().len(), 3);
+        assert!((store.conjunctions(Some(1), None, None, None).unwrap()[0].distance_km - 2.1).abs() < 1e-9);
+        assert!(store.save_conjunctions(&[Conjunction { tca_jd: None, ..events[0].clone() }], 2460000.5).is_err());
+    }
+    #[test]
+    fn test_rescreen_with_swapped_catalogue_order() {
+        let store = CatalogStore::open(":memory:").unwrap();
+        store.save_conjunctions(&[conjunction(25544, 1, 2460000.6, 2.0), conjunction(3, 4, 2460001.6, 1.0)], 2460000.5).unwrap();
+        // the next catalogue lists the objects the other way round and the TCA moved by 20 s
+        let shift = 20.0 / SECONDS_PER_DAY;
+        let mut rescreen = [conjunction(1, 25544, 2460000.6 + shift, 1.5), conjunction(4, 3, 2460001.6 - shift, 0.8)];
+        rescreen[0].international_designator_2 = Some("1998-067A".to_string());
+        rescreen[1].international_designator_1 = Some("2024-001A".to_string());
+        store.save_conjunctions(&rescreen, 2460000.7).unwrap();
+
+        let stored = store.conjunctions(None, None, None, None).unwrap();
+        assert_eq!(stored.len(), 2);
+        assert_eq!((stored[0].norad_id_1, stored[0].norad_id_2), (1, 25544));
+        assert!((stored[0].distance_km - 1.5).abs() < 1e-9 && (stored[1].distance_km - 0.8).abs() < 1e-9);
+        let pairs: Vec<(i32, i32, Option<String>, Option<String>)> = store
+            .connection()
+            .prepare(
+                "SELECT norad_id_1, norad_id_2, international_designator_1, international_designator_2
+                 FROM conjunctions ORDER BY norad_id_1",
+            )
+            .and_then(|mut query| query.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))?.collect())
+            .unwrap();
+        let (iss, debris) = (Some("1998-067A".to_string()), Some("2024-001A".to_string()));
+        assert_eq!(pairs, vec![(1, 25544, None, iss), (3, 4, None, debris)]);
+
+        // the pair's next encounter, an orbit later, is a separate event
+        store.save_conjunctions(&[conjunction(25544, 1, 2460000.6 + 0.0625, 3.0)], 2460000.7).unwrap();
+        assert_eq!(store.conjunctions(Some(1), None, None, None).unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,192 @@
+//! Streaming conjunction search
+//!
+//! Screening runs on a background thread that feeds a bounded channel, and
+//! Python consumes the results as an iterator. Memory stays bounded by the
+//! channel capacity rather than the number of events, and dropping or
+//! closing the iterator stops the workers early.
+
+use pyo3::prelude::*;
+use rayon::prelude::*;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{sync_channel, Receiver};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+
+use crate::parallel;
+use crate::volume::{self, ScreeningVolume};
+use crate::{check_common_epoch, check_states, satellite_conjunction, Conjunction, PairIndex, Precision, Satellite};
+
+/// Iterator over conjunctions produced by background workers
+///
+/// Events arrive in the order workers find them, not sorted.
+#[pyclass]
+pub struct ConjunctionStream {
+    receiver: Option<Receiver<Conjunction>>,
+    stop: Arc<AtomicBool>,
+    worker: Option<JoinHandle<()>>,
+    remaining: Option<usize>,
+}
+
+impl ConjunctionStream {
+    /// Start screening `satellites` on a background thread
+    pub fn spawn(
+        satellites: Vec<Satellite>,
+        volume: impl Into<ScreeningVolume>,
+        apogee_perigee_filter: bool,
+        buffer: usize,
+        limit: Option<usize>,
+    ) -> Self {
+        let (sender, receiver) = sync_channel(buffer.max(1));
+        let stop = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&stop);
+        let volume = volume.into();
+
+        let worker = std::thread::spawn(move || {
+            if satellites.is_empty() || volume.is_empty() {
+                return;
+            }
+            let positions: Vec<[f64; 3]> = satellites.iter().map(|sat| sat.position).collect();
+            let velocities: Vec<[f64; 3]> = satellites.iter().map(|sat| sat.velocity).collect();
+            let radii: Vec<f64> = satellites.iter().map(Satellite::collision_radius).collect();
+            let index = PairIndex::build(&positions, &velocities, &radii, volume, apogee_perigee_filter, Precision::Double);
+
+            // a send error means the consumer hung up; Err aborts the remaining work
+            let _ = parallel::install(|| {
+                (0..index.len()).into_par_iter().try_for_each_with(sender, |sender, i| {
+                    if flag.load(Ordering::Relaxed) {
+                        return Err(());
+                    }
+                    index
+                        .partners(i)
+                        .into_iter()
+                        .try_for_each(|j| sender.send(satellite_conjunction(&satellites[i], &satellites[j])).map_err(|_| ()))
+                })
+            });
+        });
+
+        ConjunctionStream {
+            receiver: Some(receiver),
+            stop,
+            worker: Some(worker),
+            remaining: limit,
+        }
+    }
+
+    /// Stop the workers and wait for them to exit
+    fn shutdown(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        // dropping the receiver unblocks any worker waiting on a full channel
+        self.receiver = None;
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+impl Drop for ConjunctionStream {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+#[pymethods]
+impl ConjunctionStream {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self, py: Python<'_>) -> Option<Conjunction> {
+        if self.remaining == Some(0) {
+            self.shutdown();
+            return None;
+        }
+        let receiver = self.receiver.take()?;
+        let (receiver, next) = py.allow_threads(move || {
+            let next = receiver.recv().ok();
+            (receiver, next)
+        });
+        match next {
+            Some(conjunction) => {
+                self.receiver = Some(receiver);
+                self.remaining = self.remaining.map(|n| n - 1);
+                Some(conjunction)
+            }
+            None => {
+                // channel closed: every worker has finished
+                self.shutdown();
+                None
+            }
+        }
+    }
+
+    /// Stop screening early; later iteration yields nothing
+    fn close(&mut self, py: Python<'_>) {
+        py.allow_threads(|| self.shutdown());
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __exit__(&mut self, py: Python<'_>, _exc_type: &PyAny, _exc_value: &PyAny, _traceback: &PyAny) -> bool {
+        self.close(py);
+        false
+    }
+}
+
+/// Stream close approaches as worker threads find them
+///
+/// Same screening as `find_conjunctions`, but results are yielded through a
+/// channel holding at most `buffer` pending events. Iteration ends after
+/// `limit` events when given; `close()` (or leaving a `with` block) stops
+/// the workers early. States are checked up front unless `validate=False`,
+/// so bad input raises here rather than partway through the iteration.
+#[pyfunction]
+#[pyo3(signature = (satellites, threshold_km, apogee_perigee_filter = false, buffer = 1024, limit = None, validate = true))]
+pub fn iter_conjunctions(
+    satellites: Vec<Satellite>,
+    threshold_km: &PyAny,
+    apogee_perigee_filter: bool,
+    buffer: usize,
+    limit: Option<usize>,
+    validate: bool,
+) -> PyResult<ConjunctionStream> {
+    if validate {
+        check_states(&satellites)?;
+    }
+    let volume = volume::extract_threshold(threshold_km)?;
+    check_common_epoch(&satellites)?;
+    Ok(ConjunctionStream::spawn(satellites, volume, apogee_perigee_filter, buffer, limit))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cloud(count: i32) -> Vec<Satellite> {
+        (0..count)
+            .map(|id| Satellite::from_state(id, [7000.0 + 0.5 * id as f64, 0.0, 0.0], [0.0, 7.5, 0.0], None))
+            .collect()
+    }
+
+    #[test]
+    fn test_stream_yields_every_pair() {
+        let mut stream = ConjunctionStream::spawn(cloud(20), 1.2, false, 4, None);
+        let receiver = stream.receiver.take().unwrap();
+        let mut pairs: Vec<(i32, i32)> = receiver.iter().map(|c| (c.norad_id_1, c.norad_id_2)).collect();
+        pairs.sort_unstable();
+        // each object is within 1.2 km of the next two
+        assert_eq!(pairs.len(), 19 + 18);
+        assert!(pairs.contains(&(0, 2)) && !pairs.contains(&(0, 3)));
+    }
+
+    #[test]
+    fn test_dropping_receiver_stops_workers() {
+        let mut stream = ConjunctionStream::spawn(cloud(2000), 50.0, false, 1, None);
+        let first = stream.receiver.as_ref().unwrap().recv();
+        assert!(first.is_ok());
+        // joins the worker; would hang if blocked senders were not released
+        stream.shutdown();
+        assert!(stream.worker.is_none());
+    }
+}
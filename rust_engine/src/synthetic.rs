@@ -0,0 +1,227 @@
+//! Synthetic catalogs for benchmarks, load tests and fuzzing
+//!
+//! Orbits are drawn per regime in proportions set by a `RegimeMix`; the
+//! default is shaped like the public catalog: mostly LEO in the crowded
+//! shells (sun-synchronous, Starlink-like 53 deg, ISS-like 51.6 deg), plus
+//! GNSS, GEO and Molniya/GTO objects. States come from SGP4 on the drawn
+//! mean elements, so speeds always match the altitude. Every object gets its
+//! own random stream, so a catalog of n objects is the prefix of any larger
+//! one with the same seed and mix.
+
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+#[cfg(feature = "python")]
+use std::collections::HashMap;
+
+use crate::constants::{EARTH_RADIUS_KM, MU_EARTH, SECONDS_PER_DAY};
+use crate::error::OrbitError;
+use crate::random::SplitMix64;
+use crate::sgp4::MeanElements;
+#[cfg(feature = "python")]
+use crate::sgp4::Sgp4Propagator;
+#[cfg(feature = "python")]
+use crate::Satellite;
+
+/// Common epoch of every synthetic element set (2023-02-25 00:00 UTC)
+pub const SYNTHETIC_EPOCH_JD: f64 = 2460000.5;
+
+/// Mean motion (rev/day) of an orbit with semi-major axis `a_km`
+fn mean_motion(a_km: f64) -> f64 {
+    (MU_EARTH / a_km.powi(3)).sqrt() * SECONDS_PER_DAY / (2.0 * std::f64::consts::PI)
+}
+
+/// Relative weights of the orbit regimes in a synthetic catalog
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RegimeMix {
+    /// Low Earth orbit, 350-1200 km perigee
+    pub leo: f64,
+    /// GNSS-like medium Earth orbit
+    pub meo: f64,
+    /// Geostationary belt
+    pub geo: f64,
+    /// Molniya and geostationary transfer orbits
+    pub heo: f64,
+}
+
+impl Default for RegimeMix {
+    /// Roughly the make-up of the public catalog
+    fn default() -> Self {
+        RegimeMix { leo: 0.75, meo: 0.08, geo: 0.10, heo: 0.07 }
+    }
+}
+
+impl RegimeMix {
+    /// Cumulative fractions (LEO, +MEO, +GEO), checking the weights
+    fn thresholds(&self) -> Result<[f64; 3], OrbitError> {
+        let weights = [self.leo, self.meo, self.geo, self.heo];
+        if weights.iter().any(|w| !(w.is_finite() && *w >= 0.0)) {
+            return Err(OrbitError::InvalidInput("Regime weights must be finite and non-negative".to_string()));
+        }
+        let total: f64 = weights.iter().sum();
+        if total <= 0.0 {
+            return Err(OrbitError::InvalidInput("At least one regime weight must be positive".to_string()));
+        }
+        Ok([
+            self.leo / total,
+            (self.leo + self.meo) / total,
+            (self.leo + self.meo + self.geo) / total,
+        ])
+    }
+}
+
+/// Orbit shape: (perigee altitude, apogee altitude) in km, inclination in deg, optional argument of perigee
+fn draw_orbit(rng: &mut SplitMix64, thresholds: &[f64; 3]) -> (f64, f64, f64, Option<f64>) {
+    let regime = rng.next_f64();
+    if regime <= thresholds[0] {
+        // LEO: near-circular, crowded inclination bands
+        let perigee = rng.uniform(350.0, 1200.0);
+        let apogee = perigee + (-rng.next_f64().ln() * 30.0).min(1500.0);
+        let band = rng.next_f64();
+        let inclination = if band < 0.35 {
+            rng.uniform(97.0, 99.5)
+        } else if band < 0.55 {
+            53.0 + 0.2 * rng.normal()
+        } else if band < 0.6 {
+            51.6 + 0.1 * rng.normal()
+        } else if band < 0.75 {
+            rng.uniform(70.0, 87.0)
+        } else {
+            rng.uniform(0.0, 100.0)
+        };
+        (perigee, apogee, inclination, None)
+    } else if regime <= thresholds[1] {
+        // GNSS shells around 20,200 km
+        let altitude = rng.uniform(19100.0, 23300.0);
+        (altitude, altitude + rng.uniform(0.0, 200.0), rng.uniform(54.0, 65.0), None)
+    } else if regime <= thresholds[2] {
+        // GEO belt
+        let altitude = 35786.0 + 50.0 * rng.normal();
+        (altitude, altitude + rng.uniform(0.0, 30.0), rng.uniform(0.0, 15.0), None)
+    } else if rng.next_f64() < 0.5 {
+        // Molniya: critical inclination, apogee over the northern hemisphere
+        (rng.uniform(500.0, 1000.0), rng.uniform(39000.0, 40000.0), 63.4, Some(270.0))
+    } else {
+        // GTO
+        (rng.uniform(200.0, 600.0), rng.uniform(35000.0, 36000.0), rng.uniform(0.0, 28.0), None)
+    }
+}
+
+/// `n` SGP4 element sets at `SYNTHETIC_EPOCH_JD` drawn per `mix`, NORAD ids 1..=n
+pub fn generate_elements(n: usize, mix: &RegimeMix, seed: u64) -> Result<Vec<MeanElements>, OrbitError> {
+    let thresholds = mix.thresholds()?;
+    Ok((0..n)
+        .map(|k| {
+            let mut rng = SplitMix64::new(seed, k as u64);
+            let (perigee, apogee, inclination, arg_perigee) = draw_orbit(&mut rng, &thresholds);
+            let (rp, ra) = (EARTH_RADIUS_KM + perigee, EARTH_RADIUS_KM + apogee);
+            // drag term only matters below ~1000 km
+            let bstar = if perigee < 1000.0 { 10f64.powf(rng.uniform(-5.0, -3.0)) } else { 0.0 };
+            MeanElements::new(
+                k as i32 + 1,
+                SYNTHETIC_EPOCH_JD,
+                bstar,
+                inclination,
+                rng.uniform(0.0, 360.0),
+                (ra - rp) / (ra + rp),
+                arg_perigee.unwrap_or_else(|| rng.uniform(0.0, 360.0)),
+                rng.uniform(0.0, 360.0),
+                mean_motion(0.5 * (rp + ra)),
+            )
+            .expect("synthetic orbits are bound and non-degenerate")
+        })
+        .collect())
+}
+
+/// `n` element sets in the default regime mix, the population the benchmarks screen
+pub fn benchmark_synthetic_catalog(n: usize, seed: u64) -> Vec<MeanElements> {
+    generate_elements(n, &RegimeMix::default(), seed).expect("default mix is valid")
+}
+
+/// Realistic random population of `n` SGP4 propagators (NORAD ids 1..=n) for benchmarks
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(name = "benchmark_synthetic_catalog", signature = (n, seed = 0))]
+pub fn py_benchmark_synthetic_catalog(n: usize, seed: u64) -> Vec<Sgp4Propagator> {
+    benchmark_synthetic_catalog(n, seed).into_iter().map(Sgp4Propagator::from_mean_elements).collect()
+}
+
+/// Random satellites with physically consistent states at the synthetic epoch
+///
+/// `regime_mix` maps "leo", "meo", "geo" and "heo" to relative weights
+/// (missing regimes get none); the default resembles the public catalog.
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(signature = (n, regime_mix = None, seed = 0))]
+pub fn generate_catalog(n: usize, regime_mix: Option<HashMap<String, f64>>, seed: u64) -> PyResult<Vec<Satellite>> {
+    let mix = match regime_mix {
+        None => RegimeMix::default(),
+        Some(weights) => {
+            let mut mix = RegimeMix { leo: 0.0, meo: 0.0, geo: 0.0, heo: 0.0 };
+            for (regime, weight) in weights {
+                match regime.to_ascii_lowercase().as_str() {
+                    "leo" => mix.leo = weight,
+                    "meo" => mix.meo = weight,
+                    "geo" => mix.geo = weight,
+                    "heo" => mix.heo = weight,
+                    _ => {
+                        return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                            "Unknown regime '{}' (expected leo, meo, geo or heo)",
+                            regime
+                        )))
+                    }
+                }
+            }
+            mix
+        }
+    };
+    generate_elements(n, &mix, seed)?
+        .into_iter()
+        .map(|elements| {
+            Sgp4Propagator::from_mean_elements(elements)
+                .propagate_state(0.0)
+                .map_err(|e| PyErr::from(OrbitError::from(e)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sgp4::Sgp4Propagator;
+    use nalgebra::Vector3;
+
+    #[test]
+    fn test_catalog_is_physical_and_reproducible() {
+        let catalog = benchmark_synthetic_catalog(2000, 7);
+        assert_eq!(catalog[..500], benchmark_synthetic_catalog(500, 7)[..]);
+        assert_ne!(catalog[..10], benchmark_synthetic_catalog(10, 8)[..]);
+
+        let mut leo = 0;
+        for elements in &catalog {
+            let propagator = Sgp4Propagator::from_mean_elements(elements.clone());
+            let (r, v) = propagator.position_velocity(0.0).unwrap();
+            let (r, v) = (Vector3::from(r).norm(), Vector3::from(v).norm());
+            assert!(r > EARTH_RADIUS_KM + 150.0, "NORAD {} below 150 km", elements.norad_id);
+            // vis-viva with the mean semi-major axis, allowing for SGP4's short-period terms
+            let a = (MU_EARTH / (elements.mean_motion_rev_per_day * 2.0 * std::f64::consts::PI / SECONDS_PER_DAY).powi(2)).cbrt();
+            let expected = (MU_EARTH * (2.0 / r - 1.0 / a)).sqrt();
+            assert!((v - expected).abs() < 0.05 * expected, "NORAD {}: {} vs {} km/s", elements.norad_id, v, expected);
+            if r < EARTH_RADIUS_KM + 2000.0 {
+                leo += 1;
+            }
+        }
+        assert!((1300..1650).contains(&leo), "{} LEO objects", leo);
+    }
+
+    #[test]
+    fn test_regime_mix() {
+        let geo_only = RegimeMix { leo: 0.0, meo: 0.0, geo: 2.0, heo: 0.0 };
+        for elements in generate_elements(200, &geo_only, 1).unwrap() {
+            assert!((elements.mean_motion_rev_per_day - 1.0).abs() < 0.01);
+            assert!(elements.inclination_deg <= 15.0);
+        }
+        assert_eq!(generate_elements(50, &RegimeMix::default(), 4).unwrap(), benchmark_synthetic_catalog(50, 4));
+        assert!(generate_elements(1, &RegimeMix { leo: -1.0, ..RegimeMix::default() }, 0).is_err());
+        assert!(generate_elements(1, &RegimeMix { leo: 0.0, meo: 0.0, geo: 0.0, heo: 0.0 }, 0).is_err());
+    }
+}
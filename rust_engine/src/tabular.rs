@@ -0,0 +1,284 @@
+//! CSV and Parquet output for conjunction lists and ephemerides
+//!
+//! Large runs produce millions of rows, and turning each into a Python
+//! object or dict only to write it out dominates the pipeline. Here rows
+//! are written straight from Rust. Both writers take a `Table` of named
+//! columns. CSV floats are written in shortest round-trip form, so reading
+//! them back gives the same values; a missing value is an empty field.
+//! Text fields are written unquoted, which is safe for the canonical
+//! international designators (digits, letters and a dash).
+//!
+//! The Parquet writer (feature `parquet`) goes through the Apache `parquet`
+//! crate: each column becomes an Arrow array, optional columns nullable, and
+//! the file is Snappy-compressed.
+
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use crate::ephemeris::Ephemeris;
+use crate::Conjunction;
+
+/// Values of one column
+#[derive(Clone, Debug, PartialEq)]
+pub enum ColumnValues {
+    Int32(Vec<i32>),
+    Float64(Vec<f64>),
+    /// Float64 with missing values
+    OptionalFloat64(Vec<Option<f64>>),
+    /// Text with missing values
+    OptionalText(Vec<Option<String>>),
+}
+
+impl ColumnValues {
+    fn len(&self) -> usize {
+        match self {
+            ColumnValues::Int32(values) => values.len(),
+            ColumnValues::Float64(values) => values.len(),
+            ColumnValues::OptionalFloat64(values) => values.len(),
+            ColumnValues::OptionalText(values) => values.len(),
+        }
+    }
+
+    /// CSV field of row `k`
+    fn field(&self, k: usize) -> String {
+        match self {
+            ColumnValues::Int32(values) => values[k].to_string(),
+            ColumnValues::Float64(values) => values[k].to_string(),
+            ColumnValues::OptionalFloat64(values) => values[k].map_or_else(String::new, |x| x.to_string()),
+            ColumnValues::OptionalText(values) => values[k].clone().unwrap_or_default(),
+        }
+    }
+}
+
+/// Named columns of equal length
+#[derive(Clone, Debug, PartialEq)]
+pub struct Table {
+    pub columns: Vec<(&'static str, ColumnValues)>,
+}
+
+impl Table {
+    pub fn rows(&self) -> usize {
+        self.columns.first().map_or(0, |(_, values)| values.len())
+    }
+}
+
+/// One row per conjunction; `tca_jd`, `probability` and the designators may be missing
+pub fn conjunction_table(conjunctions: &[Conjunction]) -> Table {
+    let float = |f: fn(&Conjunction) -> f64| ColumnValues::Float64(conjunctions.iter().map(f).collect());
+    let optional =
+        |f: fn(&Conjunction) -> Option<f64>| ColumnValues::OptionalFloat64(conjunctions.iter().map(f).collect());
+    let text = |f: fn(&Conjunction) -> &Option<String>| {
+        ColumnValues::OptionalText(conjunctions.iter().map(|c| f(c).clone()).collect())
+    };
+    Table {
+        columns: vec![
+            ("norad_id_1", ColumnValues::Int32(conjunctions.iter().map(|c| c.norad_id_1).collect())),
+            ("norad_id_2", ColumnValues::Int32(conjunctions.iter().map(|c| c.norad_id_2).collect())),
+            ("tca_jd", optional(|c| c.tca_jd)),
+            ("distance_km", float(|c| c.distance_km)),
+            ("relative_velocity_km_s", float(|c| c.relative_velocity_km_s)),
+            ("radial_km", float(|c| c.radial_km)),
+            ("in_track_km", float(|c| c.in_track_km)),
+            ("cross_track_km", float(|c| c.cross_track_km)),
+            ("probability", optional(|c| c.probability)),
+            ("international_designator_1", text(|c| &c.international_designator_1)),
+            ("international_designator_2", text(|c| &c.international_designator_2)),
+        ],
+    }
+}
+
+/// One row per sample: UTC Julian date, seconds from the first sample, position (km) and velocity (km/s)
+pub fn ephemeris_table(ephemeris: &Ephemeris) -> Table {
+    let component = |k: usize| {
+        ColumnValues::Float64(ephemeris.states.iter().map(|(r, v)| if k < 3 { r[k] } else { v[k - 3] }).collect())
+    };
+    Table {
+        columns: vec![
+            ("norad_id", ColumnValues::Int32(vec![ephemeris.norad_id; ephemeris.times.len()])),
+            (
+                "epoch_jd",
+                ColumnValues::Float64(
+                    ephemeris.times.iter().map(|&t| ephemeris.reference.plus_seconds(t).jd_utc()).collect(),
+                ),
+            ),
+            ("offset_s", ColumnValues::Float64(ephemeris.times.clone())),
+            ("x_km", component(0)),
+            ("y_km", component(1)),
+            ("z_km", component(2)),
+            ("vx_km_s", component(3)),
+            ("vy_km_s", component(4)),
+            ("vz_km_s", component(5)),
+        ],
+    }
+}
+
+/// Write `table` as CSV with a header row
+pub fn write_csv(table: &Table, path: &Path) -> io::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    let names: Vec<&str> = table.columns.iter().map(|(name, _)| *name).collect();
+    writeln!(out, "{}", names.join(","))?;
+    for k in 0..table.rows() {
+        let fields: Vec<String> = table.columns.iter().map(|(_, values)| values.field(k)).collect();
+        writeln!(out, "{}", fields.join(","))?;
+    }
+    out.flush()
+}
+
+/// Write `table` as a Parquet file
+#[cfg(feature = "parquet")]
+pub fn write_parquet(table: &Table, path: &Path) -> io::Result<()> {
+    parquet_file::write(table, path)
+}
+
+#[cfg(feature = "parquet")]
+mod parquet_file {
+    use super::*;
+    use arrow_array::{ArrayRef, Float64Array, Int32Array, RecordBatch, StringArray};
+    use arrow_schema::{DataType, Field, Schema};
+    use parquet::arrow::ArrowWriter;
+    use parquet::basic::Compression;
+    use parquet::file::properties::WriterProperties;
+    use std::sync::Arc;
+
+    fn array(values: &ColumnValues) -> (DataType, bool, ArrayRef) {
+        match values {
+            ColumnValues::Int32(values) => (DataType::Int32, false, Arc::new(Int32Array::from(values.clone()))),
+            ColumnValues::Float64(values) => (DataType::Float64, false, Arc::new(Float64Array::from(values.clone()))),
+            ColumnValues::OptionalFloat64(values) => {
+                (DataType::Float64, true, Arc::new(Float64Array::from(values.clone())))
+            }
+            ColumnValues::OptionalText(values) => (DataType::Utf8, true, Arc::new(StringArray::from(values.clone()))),
+        }
+    }
+
+    pub(super) fn write(table: &Table, path: &Path) -> io::Result<()> {
+        let (fields, arrays): (Vec<Field>, Vec<ArrayRef>) = table
+            .columns
+            .iter()
+            .map(|(name, values)| {
+                let (kind, nullable, array) = array(values);
+                (Field::new(*name, kind, nullable), array)
+            })
+            .unzip();
+        let schema = Arc::new(Schema::new(fields));
+        let batch = RecordBatch::try_new(schema.clone(), arrays).map_err(io::Error::other)?;
+        let properties = WriterProperties::builder().set_compression(Compression::SNAPPY).build();
+        let mut writer = ArrowWriter::try_new(File::create(path)?, schema, Some(properties)).map_err(io::Error::other)?;
+        writer.write(&batch).map_err(io::Error::other)?;
+        writer.close().map_err(io::Error::other)?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "python")]
+fn table_of(data: &PyAny) -> PyResult<Table> {
+    if let Ok(ephemeris) = data.extract::<PyRef<Ephemeris>>() {
+        return Ok(ephemeris_table(&ephemeris));
+    }
+    let conjunctions: Vec<PyRef<Conjunction>> = data.extract().map_err(|_| {
+        pyo3::exceptions::PyTypeError::new_err("Expected a list of Conjunctions or an Ephemeris")
+    })?;
+    let conjunctions: Vec<Conjunction> = conjunctions.iter().map(|c| (**c).clone()).collect();
+    Ok(conjunction_table(&conjunctions))
+}
+
+#[cfg(feature = "python")]
+pub(crate) fn io_error(path: &str, error: io::Error) -> PyErr {
+    pyo3::exceptions::PyIOError::new_err(format!("{}: {}", path, error))
+}
+
+/// Write a list of `Conjunction`s or an `Ephemeris` to `path` as CSV
+#[cfg(feature = "python")]
+#[pyfunction(name = "write_csv")]
+pub fn py_write_csv(py: Python<'_>, data: &PyAny, path: &str) -> PyResult<()> {
+    let table = table_of(data)?;
+    py.allow_threads(|| write_csv(&table, Path::new(path))).map_err(|e| io_error(path, e))
+}
+
+/// Write a list of `Conjunction`s or an `Ephemeris` to `path` as Parquet
+#[cfg(all(feature = "python", feature = "parquet"))]
+#[pyfunction(name = "write_parquet")]
+pub fn py_write_parquet(py: Python<'_>, data: &PyAny, path: &str) -> PyResult<()> {
+    let table = table_of(data)?;
+    py.allow_threads(|| write_parquet(&table, Path::new(path))).map_err(|e| io_error(path, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nalgebra::Vector3;
+
+    fn conjunctions(n: usize) -> Vec<Conjunction> {
+        let (r1, v1) = (Vector3::new(7000.0, 0.0, 0.0), Vector3::new(0.0, 7.5, 0.0));
+        (0..n)
+            .map(|k| {
+                let (r2, v2) = (Vector3::new(7000.0, 0.1 * k as f64, 0.3), Vector3::new(0.0, 0.0, 7.5));
+                let tca = (k % 3 != 0).then_some(2460000.5 + k as f64 / 1440.0);
+                let mut c = Conjunction::from_states(1, 2 + k as i32, (&r1, &v1), (&r2, &v2), tca);
+                c.probability = (k % 2 == 0).then_some(1e-6 * k as f64);
+                c.international_designator_1 = Some("1998-067A".to_string());
+                c.international_designator_2 = (k % 2 == 1).then(|| format!("2024-{:03}B", k));
+                c
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_csv_round_trips_values() {
+        let events = conjunctions(5);
+        let path = std::env::temp_dir().join(format!("orbit_core_tabular_{}.csv", std::process::id()));
+        write_csv(&conjunction_table(&events), &path).unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 6);
+        assert!(lines[0].starts_with("norad_id_1,norad_id_2,tca_jd,distance_km"));
+        for (line, event) in lines[1..].iter().zip(&events) {
+            let fields: Vec<&str> = line.split(',').collect();
+            assert_eq!(fields[1].parse::<i32>().unwrap(), event.norad_id_2);
+            assert_eq!(fields[2].parse::<f64>().ok(), event.tca_jd);
+            assert_eq!(fields[3].parse::<f64>().unwrap(), event.distance_km);
+            assert_eq!(fields[8].parse::<f64>().ok(), event.probability);
+            assert_eq!(Some(fields[9]), event.international_designator_1.as_deref());
+            assert_eq!(fields[10], event.international_designator_2.as_deref().unwrap_or(""));
+        }
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn test_parquet_reads_back() {
+        use arrow_array::cast::AsArray;
+        use arrow_array::types::{Float64Type, Int32Type};
+        use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+        let events = conjunctions(1_000);
+        let path = std::env::temp_dir().join(format!("orbit_core_tabular_{}.parquet", std::process::id()));
+        write_parquet(&conjunction_table(&events), &path).unwrap();
+        let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(&path).unwrap()).unwrap().build().unwrap();
+        let batches: Vec<_> = reader.collect::<Result<_, _>>().unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let schema = batches[0].schema();
+        assert_eq!(schema.fields().len(), 11);
+        assert_eq!(schema.field(2).name(), "tca_jd");
+        assert!(schema.field(2).is_nullable() && !schema.field(3).is_nullable());
+        let column = |k: usize| -> Vec<Option<f64>> {
+            batches.iter().flat_map(|b| b.column(k).as_primitive::<Float64Type>().iter().collect::<Vec<_>>()).collect()
+        };
+        assert_eq!(column(2), events.iter().map(|c| c.tca_jd).collect::<Vec<_>>());
+        assert_eq!(column(8), events.iter().map(|c| c.probability).collect::<Vec<_>>());
+        assert_eq!(column(3), events.iter().map(|c| Some(c.distance_km)).collect::<Vec<_>>());
+        let ids: Vec<i32> =
+            batches.iter().flat_map(|b| b.column(1).as_primitive::<Int32Type>().values().to_vec()).collect();
+        assert_eq!(ids, events.iter().map(|c| c.norad_id_2).collect::<Vec<_>>());
+        assert!(schema.field(10).is_nullable() && schema.field(10).data_type() == &arrow_schema::DataType::Utf8);
+        let designators: Vec<Option<String>> = batches
+            .iter()
+            .flat_map(|b| b.column(10).as_string::<i32>().iter().map(|d| d.map(str::to_string)).collect::<Vec<_>>())
+            .collect();
+        assert_eq!(designators, events.iter().map(|c| c.international_designator_2.clone()).collect::<Vec<_>>());
+    }
+}
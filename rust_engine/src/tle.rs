@@ -0,0 +1,269 @@
+//! Two-line element set (TLE) parsing
+//!
+//! Column layout follows the NORAD/Space-Track format. Files may mix
+//! plain two-line sets and three-line sets with a leading title line.
+
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use rayon::prelude::*;
+#[cfg(feature = "python")]
+use std::fs;
+
+use crate::designator;
+use crate::parallel;
+#[cfg(feature = "python")]
+use crate::error::OrbitError;
+use crate::sgp4::{julian_date, MeanElements};
+
+/// Length of a TLE data line including the checksum column
+const TLE_LINE_LENGTH: usize = 69;
+
+/// Modulo-10 checksum of the first 68 columns ('-' counts as 1)
+pub fn checksum(line: &str) -> u32 {
+    line.chars()
+        .take(TLE_LINE_LENGTH - 1)
+        .map(|c| match c {
+            '0'..='9' => c.to_digit(10).unwrap(),
+            '-' => 1,
+            _ => 0,
+        })
+        .sum::<u32>()
+        % 10
+}
+
+/// Parse the two data lines of a TLE into mean elements
+pub fn parse_lines(line1: &str, line2: &str, validate_checksum: bool) -> Result<MeanElements, String> {
+    let line1 = line1.trim_end();
+    let line2 = line2.trim_end();
+
+    check_line(line1, '1', validate_checksum)?;
+    check_line(line2, '2', validate_checksum)?;
+
+    let norad_id = parse_norad_id(line1)?;
+    if parse_norad_id(line2)? != norad_id {
+        return Err(format!(
+            "NORAD ID mismatch between lines: {:?} / {:?}",
+            line1, line2
+        ));
+    }
+
+    let year = number(line1, 18, 20)? as i32;
+    let day_of_year = number(line1, 20, 32)?;
+    let bstar = parse_implied_exponent(field(line1, 53, 61)?)?;
+
+    let mut elements = MeanElements::new(
+        norad_id,
+        tle_epoch_to_jd(year, day_of_year),
+        bstar,
+        number(line2, 8, 16)?,
+        number(line2, 17, 25)?,
+        parse_implied_decimal(field(line2, 26, 33)?)?,
+        number(line2, 34, 42)?,
+        number(line2, 43, 51)?,
+        number(line2, 52, 63)?,
+    )
+    .map_err(|e| format!("NORAD {}: {}", norad_id, e))?;
+    // blank or malformed designators (analyst objects) are left unset
+    elements.international_designator =
+        field(line1, 9, 17).ok().and_then(|text| designator::parse_international_designator(text).ok());
+
+    Ok(elements)
+}
+
+/// Parse the contents of a TLE file (two- or three-line format)
+pub fn parse_text(text: &str, validate_checksum: bool) -> Result<Vec<MeanElements>, String> {
+    let lines: Vec<(usize, &str)> = text
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| (idx + 1, line.trim_end()))
+        .filter(|(_, line)| !line.trim().is_empty())
+        .collect();
+
+    // group lines into (title, line1, line2) records before parsing in parallel
+    let mut records = Vec::new();
+    let mut idx = 0;
+    while idx < lines.len() {
+        let (lineno, line) = lines[idx];
+        if line.starts_with("1 ") {
+            let (_, next) = lines
+                .get(idx + 1)
+                .ok_or_else(|| format!("line {}: missing line 2 of TLE", lineno))?;
+            records.push((lineno, None, line, *next));
+            idx += 2;
+        } else {
+            let title = line.strip_prefix("0 ").unwrap_or(line).trim();
+            let (_, line1) = lines
+                .get(idx + 1)
+                .ok_or_else(|| format!("line {}: missing line 1 of TLE", lineno))?;
+            let (_, line2) = lines
+                .get(idx + 2)
+                .ok_or_else(|| format!("line {}: missing line 2 of TLE", lineno))?;
+            records.push((lineno, Some(title.to_string()), *line1, *line2));
+            idx += 3;
+        }
+    }
+
+    parallel::install(|| {
+        records
+            .into_par_iter()
+            .map(|(lineno, title, line1, line2)| {
+                let mut elements = parse_lines(line1, line2, validate_checksum)
+                    .map_err(|e| format!("line {}: {}", lineno, e))?;
+                elements.name = title;
+                Ok(elements)
+            })
+            .collect()
+    })
+}
+
+/// Parse a single TLE into mean elements
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(signature = (line1, line2, validate_checksum = true))]
+pub fn parse_tle(line1: &str, line2: &str, validate_checksum: bool) -> PyResult<MeanElements> {
+    parse_lines(line1, line2, validate_checksum).map_err(|e| OrbitError::TleParse(e).into())
+}
+
+/// Parse every TLE in a file
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(signature = (path, validate_checksum = true))]
+pub fn parse_tle_file(path: &str, validate_checksum: bool) -> PyResult<Vec<MeanElements>> {
+    let text = fs::read_to_string(path)
+        .map_err(|e| pyo3::exceptions::PyIOError::new_err(format!("{}: {}", path, e)))?;
+    parse_text(&text, validate_checksum).map_err(|e| OrbitError::TleParse(e).into())
+}
+
+fn check_line(line: &str, line_number: char, validate_checksum: bool) -> Result<(), String> {
+    if line.len() < TLE_LINE_LENGTH || !line.is_ascii() {
+        return Err(format!(
+            "TLE line {} must be {} ASCII characters: {:?}",
+            line_number, TLE_LINE_LENGTH, line
+        ));
+    }
+    if !line.starts_with(line_number) {
+        return Err(format!("expected TLE line {}: {:?}", line_number, line));
+    }
+    if validate_checksum {
+        let expected = line[68..69]
+            .parse::<u32>()
+            .map_err(|_| format!("missing checksum digit: {:?}", line))?;
+        let actual = checksum(line);
+        if actual != expected {
+            return Err(format!(
+                "checksum mismatch on line {} (expected {}, computed {}): {:?}",
+                line_number, expected, actual, line
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn field(line: &str, start: usize, end: usize) -> Result<&str, String> {
+    line.get(start..end)
+        .map(str::trim)
+        .ok_or_else(|| format!("TLE line too short: {:?}", line))
+}
+
+fn number(line: &str, start: usize, end: usize) -> Result<f64, String> {
+    let text = field(line, start, end)?;
+    text.parse::<f64>()
+        .map_err(|_| format!("invalid TLE field {:?} in {:?}", text, line))
+}
+
+/// Catalog number in columns 3-7, digits or Alpha-5
+fn parse_norad_id(line: &str) -> Result<i32, String> {
+    designator::parse_catalog_number(field(line, 2, 7)?).map_err(|_| format!("invalid NORAD ID in {:?}", line))
+}
+
+/// Parse a TLE field of the form " 12345-3" meaning 0.12345e-3
+fn parse_implied_exponent(text: &str) -> Result<f64, String> {
+    if text.is_empty() {
+        return Ok(0.0);
+    }
+    let (mantissa, exponent) = match text.rfind(['-', '+']) {
+        Some(idx) if idx > 0 => text.split_at(idx),
+        _ => (text, "0"),
+    };
+    let (sign, digits) = match mantissa.strip_prefix('-') {
+        Some(rest) => (-1.0, rest),
+        None => (1.0, mantissa.strip_prefix('+').unwrap_or(mantissa)),
+    };
+    let mantissa: f64 = format!("0.{}", digits.trim())
+        .parse()
+        .map_err(|_| format!("invalid TLE exponent field {:?}", text))?;
+    let exponent: i32 = exponent
+        .parse()
+        .map_err(|_| format!("invalid TLE exponent field {:?}", text))?;
+    Ok(sign * mantissa * 10f64.powi(exponent))
+}
+
+/// Parse a TLE field with an implied leading decimal point ("0001234")
+fn parse_implied_decimal(text: &str) -> Result<f64, String> {
+    format!("0.{}", text)
+        .parse()
+        .map_err(|_| format!("invalid TLE decimal field {:?}", text))
+}
+
+/// Convert a two-digit TLE epoch year and fractional day-of-year to a Julian date
+pub fn tle_epoch_to_jd(two_digit_year: i32, day_of_year: f64) -> f64 {
+    let year = if two_digit_year < 57 {
+        two_digit_year + 2000
+    } else {
+        two_digit_year + 1900
+    };
+    julian_date(year, 1, 1, 0, 0, 0.0) + day_of_year - 1.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ISS_L1: &str = "1 25544U 98067A   08264.51782528 -.00002182  00000-0 -11606-4 0  2927";
+    const ISS_L2: &str = "2 25544  51.6416 247.4627 0006703 130.5360 325.0288 15.72125391563537";
+
+    #[test]
+    fn test_checksum() {
+        assert_eq!(checksum(ISS_L1), 7);
+        assert_eq!(checksum(ISS_L2), 7);
+    }
+
+    #[test]
+    fn test_alpha5_catalog_number_and_designator() {
+        let (line1, line2) = (ISS_L1.replace("25544", "T0042"), ISS_L2.replace("25544", "T0042"));
+        let elements = parse_lines(&line1, &line2, false).unwrap();
+        assert_eq!(elements.norad_id, 270_042);
+        assert_eq!(elements.international_designator.as_deref(), Some("1998-067A"));
+
+        let blank = ISS_L1.replace("98067A  ", "        ");
+        assert_eq!(parse_lines(&blank, ISS_L2, false).unwrap().international_designator, None);
+    }
+
+    #[test]
+    fn test_parse_iss() {
+        let elements = parse_lines(ISS_L1, ISS_L2, true).unwrap();
+        assert_eq!(elements.norad_id, 25544);
+        assert!((elements.bstar + 0.11606e-4).abs() < 1e-12);
+        assert!((elements.eccentricity - 0.0006703).abs() < 1e-12);
+        assert!((elements.inclination_deg - 51.6416).abs() < 1e-12);
+        assert!((elements.mean_motion_rev_per_day - 15.72125391).abs() < 1e-12);
+        // 2008 day 264.51782528 = 2008-09-20 12:25:40 UTC
+        assert!((elements.epoch_jd - 2454730.01782528).abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_bad_checksum_rejected() {
+        let corrupted = ISS_L1.replace("2927", "2928");
+        assert!(parse_lines(&corrupted, ISS_L2, true).is_err());
+        assert!(parse_lines(&corrupted, ISS_L2, false).is_ok());
+    }
+
+    #[test]
+    fn test_parse_three_line_text() {
+        let text = format!("ISS (ZARYA)\n{}\n{}\n\n{}\n{}\n", ISS_L1, ISS_L2, ISS_L1, ISS_L2);
+        let parsed = parse_text(&text, true).unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[0].name.as_deref(), Some("ISS (ZARYA)"));
+        assert_eq!(parsed[1].name, None);
+    }
+}
@@ -0,0 +1,150 @@
+//! Inter-satellite line of sight
+//!
+//! Two positions see each other when the straight segment between them
+//! clears the Earth, a sphere of equatorial radius, by more than a grazing
+//! altitude. Optical and RF crosslinks are usually budgeted with a margin of
+//! tens of kilometres for atmospheric refraction and absorption.
+//!
+//! Positions are geocentric inertial (km), both at the same instant.
+
+use nalgebra::Vector3;
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+#[cfg(feature = "python")]
+use rayon::prelude::*;
+
+use crate::constants::EARTH_RADIUS_KM;
+#[cfg(feature = "python")]
+use crate::columnar::Column;
+#[cfg(feature = "python")]
+use crate::ground_track::{sample_states, TrackTarget};
+#[cfg(feature = "python")]
+use crate::propagation::{ForceModel, Integrator};
+#[cfg(feature = "python")]
+use crate::{parallel, Satellite};
+#[cfg(feature = "python")]
+use crate::constants::{PROPAGATOR_INITIAL_STEP_S, SECONDS_PER_DAY};
+
+/// Altitude (km) above the Earth of the point of segment r1-r2 closest to the geocentre
+pub fn grazing_altitude(r1: &Vector3<f64>, r2: &Vector3<f64>) -> f64 {
+    let d = r2 - r1;
+    let length_sq = d.norm_squared();
+    let s = if length_sq > 0.0 { (-r1.dot(&d) / length_sq).clamp(0.0, 1.0) } else { 0.0 };
+    (r1 + d * s).norm() - EARTH_RADIUS_KM
+}
+
+/// Whether the segment r1-r2 passes more than `grazing_altitude_km` above the Earth
+pub fn line_of_sight(r1: &Vector3<f64>, r2: &Vector3<f64>, grazing_altitude_km: f64) -> bool {
+    grazing_altitude(r1, r2) > grazing_altitude_km
+}
+
+/// Whether two satellites see each other past the Earth
+///
+/// Both satellites are states at the same instant; the link must clear the
+/// Earth by more than `grazing_altitude_km`.
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(name = "line_of_sight", signature = (sat1, sat2, grazing_altitude_km = 0.0))]
+pub fn py_line_of_sight(sat1: &Satellite, sat2: &Satellite, grazing_altitude_km: f64) -> PyResult<bool> {
+    if let (Some(e1), Some(e2)) = (sat1.epoch, sat2.epoch) {
+        if ((e1.jd_utc() - e2.jd_utc()) * SECONDS_PER_DAY).abs() > 1e-3 {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "Satellites must be at the same epoch; propagate one to the other first",
+            ));
+        }
+    }
+    Ok(line_of_sight(&sat1.r(), &sat2.r(), grazing_altitude_km))
+}
+
+/// Crosslink visibility between every pair of a constellation over a time window
+///
+/// Each object - a `Satellite` with an epoch, an `Sgp4Propagator`, an
+/// `Ephemeris` or a `(line1, line2)` TLE - is sampled every `step` seconds
+/// between two Julian dates. Returns a dict with `jd` and `visible`, an
+/// array of shape (epochs, objects, objects) holding 1.0 where the pair
+/// has line of sight (and is within `max_range_km`, if given), 0.0 where
+/// it does not and NaN where either object fails to propagate. The array
+/// is symmetric with a zero diagonal, in input order.
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(signature = (constellation, start, end, step = 60.0, grazing_altitude_km = 0.0, max_range_km = None))]
+pub(crate) fn crosslink_visibility(
+    py: Python<'_>,
+    constellation: Vec<TrackTarget>,
+    start: f64,
+    end: f64,
+    step: f64,
+    grazing_altitude_km: f64,
+    max_range_km: Option<f64>,
+) -> PyResult<PyObject> {
+    if end <= start {
+        return Err(pyo3::exceptions::PyValueError::new_err("end must be after start"));
+    }
+    if step <= 0.0 {
+        return Err(pyo3::exceptions::PyValueError::new_err("step must be positive (seconds)"));
+    }
+    let duration = (end - start) * SECONDS_PER_DAY;
+    let n_steps = (duration / step).ceil() as usize;
+    let times: Vec<f64> = (0..=n_steps).map(|k| (k as f64 * step).min(duration)).collect();
+    let n = constellation.len();
+    let integrator = Integrator::DormandPrince { initial_step: PROPAGATOR_INITIAL_STEP_S };
+
+    let visible: Vec<f64> = py.allow_threads(|| {
+        parallel::install(|| -> PyResult<Vec<f64>> {
+            let positions: Vec<Vec<Option<Vector3<f64>>>> = constellation
+                .into_par_iter()
+                .map(|target| {
+                    let states = sample_states(target, start, &times, ForceModel::J2, integrator)?;
+                    Ok(states.into_iter().map(|state| state.map(|(r, _)| r)).collect())
+                })
+                .collect::<PyResult<_>>()?;
+            Ok((0..times.len())
+                .into_par_iter()
+                .flat_map_iter(|k| {
+                    let positions = &positions;
+                    (0..n * n).map(move |cell| {
+                        let (i, j) = (cell / n, cell % n);
+                        match (positions[i][k], positions[j][k]) {
+                            _ if i == j => 0.0,
+                            (Some(r1), Some(r2)) => {
+                                let in_range = !max_range_km.is_some_and(|max| (r2 - r1).norm() > max);
+                                f64::from(u8::from(in_range && line_of_sight(&r1, &r2, grazing_altitude_km)))
+                            }
+                            _ => f64::NAN,
+                        }
+                    })
+                })
+                .collect())
+        })
+    })?;
+
+    let table = pyo3::types::PyDict::new(py);
+    let jd: Vec<f64> = times.iter().map(|t| start + t / SECONDS_PER_DAY).collect();
+    table.set_item("jd", Py::new(py, Column::float64(jd))?)?;
+    table.set_item("visible", Py::new(py, Column::array(visible, &[times.len(), n, n]))?)?;
+    Ok(table.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_of_sight_grazing_altitude() {
+        // two satellites at 7000 km radius, 90 deg apart: the chord clears the Earth
+        // by 7000 cos(45 deg) - R; opposite sides of the Earth never see each other
+        let r1 = Vector3::new(7000.0, 0.0, 0.0);
+        let r2 = Vector3::new(0.0, 7000.0, 0.0);
+        let expected = 7000.0 * std::f64::consts::FRAC_1_SQRT_2 - EARTH_RADIUS_KM;
+        assert!((grazing_altitude(&r1, &r2) - expected).abs() < 1e-9);
+        assert!(expected < 0.0 && !line_of_sight(&r1, &r2, 0.0));
+
+        let r3 = Vector3::new(7000.0 * 30f64.to_radians().cos(), 7000.0 * 30f64.to_radians().sin(), 0.0);
+        let clearance = grazing_altitude(&r1, &r3);
+        assert!(line_of_sight(&r1, &r3, 0.0) && line_of_sight(&r3, &r1, clearance - 1.0));
+        assert!(!line_of_sight(&r1, &r3, clearance + 1.0));
+        assert!(!line_of_sight(&r1, &-r1, 0.0));
+        // the segment endpoint is the closest point when the chord points away from the Earth
+        assert!((grazing_altitude(&r1, &(r1 * 2.0)) - (7000.0 - EARTH_RADIUS_KM)).abs() < 1e-9);
+    }
+}
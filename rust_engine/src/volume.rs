@@ -0,0 +1,146 @@
+//! Screening volumes
+//!
+//! A spherical threshold reports every object trailing or leading the
+//! primary along its track, where position uncertainty is largest but
+//! separations are rarely dangerous. A `ScreeningVolume` is an ellipsoid
+//! with separate radial, in-track and cross-track semi-axes in the
+//! primary's RTN frame (object 1 of each pair). Every semi-axis is widened
+//! per pair by `hard_body_scale` times the combined hard-body radius, so
+//! large objects are screened with a proportionally larger margin.
+//!
+//! Screening functions accept either a number (a sphere, widened by the
+//! combined radius as before) or a `ScreeningVolume` as `threshold_km`.
+
+use nalgebra::Vector3;
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+
+use crate::frames;
+
+/// RTN-aligned screening ellipsoid
+#[cfg_attr(feature = "python", pyclass(get_all))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ScreeningVolume {
+    pub radial_km: f64,
+    pub in_track_km: f64,
+    pub cross_track_km: f64,
+    pub hard_body_scale: f64, // multiplier on the combined hard-body radius
+}
+
+impl From<f64> for ScreeningVolume {
+    fn from(threshold_km: f64) -> Self {
+        ScreeningVolume::sphere(threshold_km)
+    }
+}
+
+impl ScreeningVolume {
+    pub fn sphere(radius_km: f64) -> Self {
+        ScreeningVolume { radial_km: radius_km, in_track_km: radius_km, cross_track_km: radius_km, hard_body_scale: 1.0 }
+    }
+
+    /// Largest semi-axis, which bounds the volume for grid and band filters
+    pub fn reach(&self) -> f64 {
+        self.radial_km.max(self.in_track_km).max(self.cross_track_km)
+    }
+
+    /// True if nothing can be inside (a non-positive threshold)
+    pub fn is_empty(&self) -> bool {
+        self.reach() <= 0.0
+    }
+
+    /// Widening of every semi-axis for objects with these hard-body radii
+    pub fn pad(&self, radius_1: f64, radius_2: f64) -> f64 {
+        self.hard_body_scale * (radius_1 + radius_2)
+    }
+
+    fn is_sphere(&self) -> bool {
+        self.radial_km == self.in_track_km && self.in_track_km == self.cross_track_km
+    }
+
+    /// Whether `r2` lies inside the volume centred on the primary state (r1, v1)
+    ///
+    /// A primary without an RTN frame (zero angular momentum) falls back to
+    /// the bounding sphere.
+    pub fn contains(&self, (r1, v1): (&Vector3<f64>, &Vector3<f64>), r2: &Vector3<f64>, pad: f64) -> bool {
+        let miss = r2 - r1;
+        if self.is_sphere() {
+            return miss.norm() < self.radial_km + pad;
+        }
+        match frames::to_rtn(r1, v1, &miss) {
+            Some(rtn) => {
+                let axes = [self.radial_km, self.in_track_km, self.cross_track_km];
+                rtn.iter().zip(axes).map(|(component, axis)| (component / (axis + pad)).powi(2)).sum::<f64>() < 1.0
+            }
+            None => miss.norm() < self.reach() + pad,
+        }
+    }
+}
+
+/// Read a `threshold_km` argument: a number (sphere) or a `ScreeningVolume`
+#[cfg(feature = "python")]
+pub(crate) fn extract_threshold(obj: &PyAny) -> PyResult<ScreeningVolume> {
+    if let Ok(radius) = obj.extract::<f64>() {
+        return Ok(ScreeningVolume::sphere(radius));
+    }
+    obj.extract::<ScreeningVolume>().map_err(|_| {
+        pyo3::exceptions::PyTypeError::new_err("threshold_km must be a number or a ScreeningVolume")
+    })
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl ScreeningVolume {
+    #[new]
+    #[pyo3(signature = (radial_km, in_track_km, cross_track_km, hard_body_scale = 1.0))]
+    fn new(radial_km: f64, in_track_km: f64, cross_track_km: f64, hard_body_scale: f64) -> PyResult<Self> {
+        let axes = [radial_km, in_track_km, cross_track_km];
+        if axes.iter().any(|axis| !(axis.is_finite() && *axis > 0.0)) {
+            return Err(pyo3::exceptions::PyValueError::new_err("Semi-axes must be positive km"));
+        }
+        if !(hard_body_scale.is_finite() && hard_body_scale >= 0.0) {
+            return Err(pyo3::exceptions::PyValueError::new_err("hard_body_scale must be non-negative"));
+        }
+        Ok(ScreeningVolume { radial_km, in_track_km, cross_track_km, hard_body_scale })
+    }
+
+    /// Sphere of `radius_km`, optionally with a different hard-body scale
+    #[staticmethod]
+    #[pyo3(name = "sphere", signature = (radius_km, hard_body_scale = 1.0))]
+    fn py_sphere(radius_km: f64, hard_body_scale: f64) -> PyResult<Self> {
+        ScreeningVolume::new(radius_km, radius_km, radius_km, hard_body_scale)
+    }
+
+    /// Whether `secondary` is inside the volume around `primary`, including hard-body padding
+    fn contains_satellite(&self, primary: &crate::Satellite, secondary: &crate::Satellite) -> bool {
+        let r1 = primary.r();
+        let v1 = primary.v();
+        let pad = self.pad(primary.collision_radius(), secondary.collision_radius());
+        self.contains((&r1, &v1), &secondary.r(), pad)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "ScreeningVolume(radial={}km, in_track={}km, cross_track={}km, hard_body_scale={})",
+            self.radial_km, self.in_track_km, self.cross_track_km, self.hard_body_scale
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ellipsoid_uses_primary_rtn_frame() {
+        let (r, v) = (Vector3::new(7000.0, 0.0, 0.0), Vector3::new(0.0, 7.5, 0.0));
+        let volume = ScreeningVolume { radial_km: 0.5, in_track_km: 5.0, cross_track_km: 1.0, hard_body_scale: 1.0 };
+        // 3 km along track is inside, 3 km radially is not
+        assert!(volume.contains((&r, &v), &(r + Vector3::new(0.0, 3.0, 0.0)), 0.0));
+        assert!(!volume.contains((&r, &v), &(r + Vector3::new(3.0, 0.0, 0.0)), 0.0));
+        // padding widens every axis
+        assert!(!volume.contains((&r, &v), &(r + Vector3::new(0.0, 0.0, 1.05)), 0.0));
+        assert!(volume.contains((&r, &v), &(r + Vector3::new(0.0, 0.0, 1.05)), volume.pad(0.05, 0.05)));
+        assert_eq!(ScreeningVolume::from(2.0).reach(), 2.0);
+        assert!(ScreeningVolume::from(0.0).is_empty());
+    }
+}
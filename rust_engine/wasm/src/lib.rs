@@ -0,0 +1,91 @@
+//! WebAssembly bindings for in-browser screening
+//!
+//! Build with `wasm-pack build --target web` from this directory. Rayon has
+//! no threads on `wasm32`, so screening runs on the calling thread; run it
+//! in a Web Worker to keep the page responsive.
+
+use orbit_core::sgp4::Sgp4Propagator;
+use orbit_core::{check_state, conjunctions_from_states, sort_conjunctions, Precision, SortOrder};
+use wasm_bindgen::prelude::*;
+
+/// Split a flat `[x0, y0, z0, x1, ...]` array into 3-vectors
+fn triples(name: &str, values: &[f64], n: usize) -> Result<Vec<[f64; 3]>, String> {
+    if values.len() != 3 * n {
+        return Err(format!("{} must hold 3 values per object ({} expected, got {})", name, 3 * n, values.len()));
+    }
+    Ok(values.chunks_exact(3).map(|c| [c[0], c[1], c[2]]).collect())
+}
+
+/// Screen a snapshot of states for pairs closer than `threshold_km`
+///
+/// `positions` (km) and `velocities` (km/s) are flat arrays of 3 values per
+/// id. Returns an array of conjunction objects with the same fields as the
+/// Python `Conjunction`, sorted by `sort_by` ("ids", "distance", "tca" or
+/// "none").
+#[wasm_bindgen(js_name = findConjunctions)]
+pub fn find_conjunctions(
+    ids: &[i32],
+    positions: &[f64],
+    velocities: &[f64],
+    threshold_km: f64,
+    sort_by: &str,
+) -> Result<JsValue, JsError> {
+    let order: SortOrder = sort_by.parse().map_err(|e: String| JsError::new(&e))?;
+    let positions = triples("positions", positions, ids.len()).map_err(|e| JsError::new(&e))?;
+    let velocities = triples("velocities", velocities, ids.len()).map_err(|e| JsError::new(&e))?;
+    for ((&id, r), v) in ids.iter().zip(&positions).zip(&velocities) {
+        check_state(id, r, v, false)?;
+    }
+    let mut conjunctions =
+        conjunctions_from_states(ids, &positions, &velocities, threshold_km, false, Precision::Double);
+    sort_conjunctions(&mut conjunctions, order);
+    Ok(serde_wasm_bindgen::to_value(&conjunctions)?)
+}
+
+/// SGP4 propagator for one two-line element set
+#[wasm_bindgen]
+pub struct Propagator {
+    inner: Sgp4Propagator,
+}
+
+#[wasm_bindgen]
+impl Propagator {
+    #[wasm_bindgen(constructor)]
+    pub fn new(line1: &str, line2: &str) -> Result<Propagator, JsError> {
+        Ok(Propagator { inner: Sgp4Propagator::new(line1, line2)? })
+    }
+
+    #[wasm_bindgen(getter, js_name = noradId)]
+    pub fn norad_id(&self) -> i32 {
+        self.inner.elements.norad_id
+    }
+
+    /// Element epoch as a Julian date (UTC)
+    #[wasm_bindgen(getter, js_name = epochJd)]
+    pub fn epoch_jd(&self) -> f64 {
+        self.inner.elements.epoch_jd
+    }
+
+    /// TEME `[x, y, z, vx, vy, vz]` (km, km/s) at minutes since epoch
+    pub fn propagate(&self, minutes_since_epoch: f64) -> Result<Vec<f64>, JsError> {
+        let (r, v) = self.inner.position_velocity(minutes_since_epoch)?;
+        Ok(r.into_iter().chain(v).collect())
+    }
+
+    /// TEME `[x, y, z, vx, vy, vz]` (km, km/s) at a Julian date
+    #[wasm_bindgen(js_name = propagateTo)]
+    pub fn propagate_to(&self, jd: f64) -> Result<Vec<f64>, JsError> {
+        self.propagate(self.inner.minutes_since_epoch(jd))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_triples_checks_length() {
+        assert_eq!(triples("positions", &[1.0, 2.0, 3.0], 1).unwrap(), vec![[1.0, 2.0, 3.0]]);
+        assert!(triples("positions", &[1.0, 2.0], 1).is_err());
+    }
+}
@@ -3,6 +3,8 @@ Tests for Rust orbit_core module
 Run with: pytest tests/test_rust.py
 """
 
+import math
+
 import pytest
 
 try:
@@ -12,6 +14,18 @@ except ImportError:
     RUST_AVAILABLE = False
 
 
+ISS_TLE = (
+    "1 25544U 98067A   08264.51782528 -.00002182  00000-0 -11606-4 0  2927",
+    "2 25544  51.6416 247.4627 0006703 130.5360 325.0288 15.72125391563537",
+)
+
+
+@pytest.fixture
+def iss_tle():
+    """ISS (ZARYA) TLE from 2008-09-20, shared by the SGP4-based tests."""
+    return ISS_TLE
+
+
 @pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
 def test_satellite_creation():
     """Test Rust Satellite object creation."""
@@ -155,6 +169,2370 @@ def test_closest_approaches():
     assert abs(closest[0][2] - 5.0) < 0.001
 
 
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_parse_tle_and_propagate(iss_tle):
+    """Test TLE parsing feeding the SGP4 propagator."""
+    line1, line2 = iss_tle
+
+    elements = orbit_core.parse_tle(line1, line2)
+    assert elements.norad_id == 25544
+    assert abs(elements.bstar + 0.11606e-4) < 1e-12
+
+    propagator = orbit_core.Sgp4Propagator.from_elements(elements)
+    sat = propagator.propagate(0.0)
+    assert sat.norad_id == 25544
+    assert 300 < sat.altitude() < 400
+
+    with pytest.raises(ValueError):
+        orbit_core.parse_tle(line1.replace("2927", "2928"), line2)
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_satellite_covariance():
+    """Test covariance assignment and Mahalanobis distance."""
+    flat = [0.0] * 36
+    for i in range(6):
+        flat[i * 6 + i] = 1.0 if i < 3 else 1e-6
+
+    sat1 = orbit_core.Satellite(1, [7000.0, 0.0, 0.0], [0.0, 7.5, 0.0], covariance=flat)
+    sat2 = orbit_core.Satellite(2, [7002.0, 0.0, 0.0], [0.0, 7.5, 0.0])
+    assert sat2.covariance is None
+    assert len(sat1.covariance) == 6
+
+    sat2.covariance = [row[:] for row in sat1.covariance]
+    # combined variance is 2 km^2 along x, so 2 km is sqrt(2) sigma
+    assert abs(sat1.mahalanobis_distance(sat2) - 2.0 ** 0.5) < 1e-9
+
+    with pytest.raises(ValueError):
+        sat2.covariance = [1.0] * 35
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_find_conjunctions_array():
+    """Test the buffer-protocol batch API against plain lists."""
+    import array
+
+    flat = [7000.0, 0.0, 0.0, 7005.0, 0.0, 0.0, 8000.0, 0.0, 0.0]
+    # memoryview exposes the same float64 (N, 3) buffer a numpy array would
+    positions = memoryview(array.array("d", flat)).cast("B").cast("d", (3, 3))
+    velocities = [[0.0, 7.5, 0.0]] * 3
+
+    conjunctions = orbit_core.find_conjunctions_array([1, 2, 3], positions, velocities, 10.0)
+    assert len(conjunctions) == 1
+    assert (conjunctions[0].norad_id_1, conjunctions[0].norad_id_2) == (1, 2)
+
+    from_lists = orbit_core.find_conjunctions_array(
+        [1, 2, 3], [flat[0:3], flat[3:6], flat[6:9]], velocities, 10.0
+    )
+    assert abs(from_lists[0].distance_km - conjunctions[0].distance_km) < 1e-12
+
+    with pytest.raises(ValueError):
+        orbit_core.find_conjunctions_array([1, 2], positions, velocities, 10.0)
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_geodetic_round_trip():
+    """Test ECEF <-> geodetic conversion and Satellite.geodetic."""
+    ecef = orbit_core.geodetic_to_ecef(41.0, 29.0, 0.5)
+    lat, lon, alt = orbit_core.ecef_to_geodetic(ecef)
+    assert abs(lat - 41.0) < 1e-9
+    assert abs(lon - 29.0) < 1e-9
+    assert abs(alt - 0.5) < 1e-9
+
+    # Over the pole the ellipsoid is ~21 km flatter than the equator
+    sat = orbit_core.Satellite(1, [0.0, 0.0, 7000.0], [7.5, 0.0, 0.0])
+    lat, _, alt = sat.geodetic(2460000.5)
+    assert abs(lat - 90.0) < 1e-9
+    assert abs(alt - (7000.0 - 6356.752)) < 1e-3
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_epoch_scales_and_guard():
+    """Test Epoch conversions and the mixed-epoch guard in find_conjunctions."""
+    epoch = orbit_core.Epoch.from_iso("2024-03-01T12:00:00Z")
+    assert abs(epoch.jd() - 2460371.0) < 1e-9
+    assert abs((epoch.jd("TT") - epoch.jd()) * 86400.0 - 69.184) < 1e-4
+    assert epoch.leap_seconds == 37.0
+    assert abs((epoch + 60.0) - epoch - 60.0) < 1e-6
+    for bad in (float("nan"), float("inf"), 1e20):
+        with pytest.raises(ValueError):
+            orbit_core.Epoch(bad)
+    with pytest.raises(ValueError):
+        orbit_core.Epoch.from_mjd(float("-inf"))
+    with pytest.raises(ValueError):
+        orbit_core.Epoch.from_iso("2024-02-31T00:00:00")
+    assert orbit_core.Epoch.from_iso("2024-02-29T00:00:00").iso().startswith("2024-02-29")
+
+    sat1 = orbit_core.Satellite(1, [7000.0, 0.0, 0.0], [0.0, 7.5, 0.0], epoch=epoch)
+    sat2 = orbit_core.Satellite(2, [7005.0, 0.0, 0.0], [0.0, 7.5, 0.0], epoch=epoch + 3600.0)
+    with pytest.raises(ValueError):
+        orbit_core.find_conjunctions([sat1, sat2], 10.0)
+
+    sat2.epoch = epoch
+    assert len(orbit_core.find_conjunctions([sat1, sat2], 10.0)) == 1
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_numerical_propagation():
+    """Test two-body/J2 propagation and the parallel batch entry point."""
+    import math
+
+    radius = 7000.0
+    speed = math.sqrt(398600.4418 / radius)
+    period = 2.0 * math.pi * math.sqrt(radius**3 / 398600.4418)
+    sat = orbit_core.Satellite(1, [radius, 0.0, 0.0], [0.0, speed, 0.0])
+
+    after = orbit_core.propagate_two_body(sat, period)
+    assert sat.distance_to(after) < 1e-5
+
+    batch = orbit_core.propagate_batch([sat, sat], 600.0, j2=True, method="rk4", step=10.0)
+    single = orbit_core.propagate_j2(sat, 600.0)
+    assert len(batch) == 2
+    assert batch[0].distance_to(single) < 1e-5
+
+    with pytest.raises(ValueError):
+        orbit_core.propagate_two_body(sat, 60.0, method="euler")
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_screening_pipeline(iss_tle):
+    """Test composing pre-filters and reading per-stage statistics."""
+    line1, line2 = iss_tle
+    iss = orbit_core.Sgp4Propagator(line1, line2)
+    start = iss.epoch_jd
+
+    pipeline = orbit_core.ScreeningPipeline(50.0)
+    pipeline.add_altitude_filter().add_orbit_path_filter(pad_km=5.0).add_geometric_filter()
+    assert pipeline.filters == ["altitude", "orbit_path", "geometric"]
+
+    events = pipeline.run([iss, iss], start, start + 0.05, 60.0)
+    baseline = orbit_core.screen_window([iss, iss], start, start + 0.05, 60.0, 50.0)
+    assert len(events) == len(baseline)
+    assert pipeline.last_stats[0] == ("pairs", 1)
+    assert pipeline.last_stats[-1][0] == "conjunctions"
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_conjunction_to_cdm():
+    """Test CCSDS CDM export in KVN and XML."""
+    # Two polar orbits in planes 90 degrees apart meet over the poles
+    start = 2460000.5
+    props = [
+        orbit_core.Sgp4Propagator.from_elements(
+            orbit_core.MeanElements(norad_id, start, 0.0, 90.0, raan, 0.0001, 0.0, 0.0, 15.0)
+        )
+        for norad_id, raan in [(1, 0.0), (2, 90.0)]
+    ]
+    events = orbit_core.screen_window(props, start, start + 0.1, 60.0, 50.0)
+    assert events
+    event = events[0]
+    sat1, sat2 = (prop.propagate_to(event.tca_jd) for prop in props)
+
+    kvn = orbit_core.conjunction_to_cdm(
+        event, sat1, sat2, metadata1={"OBJECT_NAME": "ISS (ZARYA)"}
+    )
+    assert "CCSDS_CDM_VERS" in kvn
+    assert "ISS (ZARYA)" in kvn
+    assert kvn.count("OBJECT_DESIGNATOR") == 2
+
+    xml = orbit_core.conjunction_to_cdm(event, format="xml", message_id="TEST-1")
+    assert "<MESSAGE_ID>TEST-1</MESSAGE_ID>" in xml
+
+    with pytest.raises(ValueError):
+        orbit_core.conjunction_to_cdm(event, metadata1={"COLOR": "red"})
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_read_oem_and_screen(iss_tle):
+    """Test OEM parsing, interpolation and screening an ephemeris."""
+    line1, line2 = iss_tle
+    iss = orbit_core.Sgp4Propagator(line1, line2)
+    start = orbit_core.Epoch(iss.epoch_jd)
+
+    lines = [
+        "CCSDS_OEM_VERS = 2.0",
+        "CREATION_DATE = 2008-09-20T12:00:00",
+        "ORIGINATOR = TEST",
+        "META_START",
+        "OBJECT_NAME = ISS (ZARYA)",
+        "OBJECT_ID = 1998-067A",
+        "CENTER_NAME = EARTH",
+        "REF_FRAME = TEME",
+        "TIME_SYSTEM = UTC",
+        "META_STOP",
+    ]
+    for k in range(31):
+        sat = iss.propagate(k)
+        values = " ".join(f"{x:.9f}" for x in sat.position + sat.velocity)
+        lines.append(f"{(start + 60.0 * k).iso()} {values}")
+    text = "\n".join(lines)
+
+    with pytest.raises(ValueError):
+        orbit_core.parse_oem_text(text)
+    eph = orbit_core.parse_oem_text(text, norad_id=25544)
+    assert len(eph) == 31
+    assert eph.name == "ISS (ZARYA)"
+    assert eph.ref_frame == "TEME"
+
+    mid = iss.epoch_jd + 450.0 / 86400.0
+    assert eph.propagate_to(mid).distance_to(iss.propagate_to(mid)) < 1e-3
+    with pytest.raises(ValueError):
+        eph.propagate_to(iss.epoch_jd + 1.0)
+
+    events = orbit_core.screen_window([iss, eph], iss.epoch_jd, iss.epoch_jd + 0.02, 60.0, 10.0)
+    assert isinstance(events, list)
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_ephemeris_hermite_interpolation(iss_tle):
+    """Test building an Ephemeris from Python and interpolating between samples."""
+    line1, line2 = iss_tle
+    iss = orbit_core.Sgp4Propagator(line1, line2)
+    start = orbit_core.Epoch(iss.epoch_jd)
+
+    states = [iss.propagate(2.0 * k) for k in range(16)]
+    eph = orbit_core.Ephemeris(
+        25544,
+        [start + 120.0 * k for k in range(16)],
+        [s.position for s in states],
+        [s.velocity for s in states],
+    )
+    assert eph.interpolation == "hermite"
+
+    truth = iss.propagate(15.0)
+    interpolated = eph.state_at(start + 900.0)
+    assert interpolated.distance_to(truth) < 1e-3
+    assert interpolated.relative_velocity(truth) < 1e-4
+
+    eph.interpolation = "lagrange"
+    assert eph.state_at(start + 900.0).distance_to(truth) < 1.0
+    with pytest.raises(ValueError):
+        eph.interpolation = "spline"
+    with pytest.raises(ValueError):
+        orbit_core.Ephemeris(1, [start], [[7000.0, 0.0, 0.0]], [[0.0, 7.5, 0.0]])
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_ground_station_passes(iss_tle):
+    """Test pass prediction from a TLE and from a propagator."""
+    line1, line2 = iss_tle
+    iss = orbit_core.Sgp4Propagator(line1, line2)
+    station = orbit_core.GroundStation(41.0, 29.0, 0.1, name="Istanbul")
+
+    start = iss.epoch_jd
+    passes = station.passes((line1, line2), start, start + 1.0, min_elevation=10.0)
+    assert passes
+    for p in passes:
+        assert p.aos_jd < p.max_elevation_jd < p.los_jd
+        assert p.max_elevation_deg >= 10.0
+        assert 0.0 <= p.aos_azimuth_deg < 360.0
+        assert len(p.profile[0]) == 4
+
+    same = station.passes(iss, start, start + 1.0, min_elevation=10.0)
+    assert [p.aos_jd for p in same] == [p.aos_jd for p in passes]
+
+    az, el, rng = station.look_angles(iss.propagate(0.0))
+    assert -90.0 <= el <= 90.0 and rng > 0.0
+
+    with pytest.raises(ValueError):
+        station.passes(iss, start, start - 1.0)
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_plan_avoidance_maneuver():
+    """Test in-track avoidance burn sizing for a miss-distance target and a Pc limit."""
+    import math
+
+    speed = math.sqrt(398600.4418 / 7000.0)
+    cov = [[0.0] * 6 for _ in range(6)]
+    for i, var in enumerate([0.01, 0.04, 0.01, 1e-8, 1e-8, 1e-8]):
+        cov[i][i] = var
+    epoch = orbit_core.Epoch.from_iso("2024-03-01T12:00:00Z")
+    primary = orbit_core.Satellite(1, [7000.0, 0.0, 0.0], [0.0, speed, 0.0], covariance=cov, epoch=epoch)
+    secondary = orbit_core.Satellite(2, [7000.1, 0.0, 0.0], [0.0, 0.0, speed], epoch=epoch)
+    event = orbit_core.find_conjunctions([primary, secondary], 1.0)[0]
+
+    with pytest.raises(ValueError):
+        orbit_core.plan_avoidance_maneuver(event, primary, secondary)
+
+    options = orbit_core.plan_avoidance_maneuver(
+        event, primary, secondary, target_miss_km=1.0, max_pc=1e-6, lead_orbits=[0.5, 1.0]
+    )
+    assert len(options) == 2
+    assert abs(options[0].delta_v_m_s) <= abs(options[1].delta_v_m_s)
+    for option in options:
+        assert option.miss_distance_km >= 1.0 - 1e-9
+        assert option.probability <= 1e-6
+        assert 0.0 < abs(option.delta_v_m_s) < 10.0
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_monte_carlo_pc():
+    """Test the Monte Carlo Pc against the Foster estimate for a fast crossing."""
+    import math
+
+    speed = math.sqrt(398600.4418 / 7000.0)
+    cov = [[0.0] * 6 for _ in range(6)]
+    for i, var in enumerate([0.0025, 0.0025, 0.0025, 1e-12, 1e-12, 1e-12]):
+        cov[i][i] = var
+    sat1 = orbit_core.Satellite(1, [7000.0, 0.0, 0.0], [0.0, speed, 0.0])
+    sat2 = orbit_core.Satellite(2, [7000.05, 0.0, 0.0], [0.0, 0.0, speed], covariance=cov)
+
+    result = orbit_core.monte_carlo_pc(sat1, cov, sat2, None, 0.02, n_samples=5000, seed=3)
+    analytic = orbit_core.collision_probability(sat1, cov, sat2, cov, 0.02)
+    assert result.samples == 5000
+    assert abs(result.probability - analytic) < 4.0 * result.standard_error
+    low, high = result.confidence_interval()
+    assert low <= result.probability <= high
+    assert orbit_core.monte_carlo_pc(sat1, cov, sat2, None, 0.02, n_samples=5000, seed=3).hits == result.hits
+
+    with pytest.raises(ValueError):
+        orbit_core.monte_carlo_pc(sat1, None, sat2, cov, 0.02)
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_satellite_metadata_and_hard_body_radius():
+    """Test object metadata fields and radius-aware conjunction thresholds."""
+    sat1 = orbit_core.Satellite(
+        1, [7000.0, 0.0, 0.0], [0.0, 7.5, 0.0], name="SAT-1", object_type="payload", hard_body_radius=0.0005
+    )
+    sat2 = orbit_core.Satellite(2, [7000.0, 0.0, 0.011], [0.0, 0.0, 7.5], object_type="R/B", rcs_size="large")
+    assert sat1.name == "SAT-1"
+    assert sat1.object_type == "PAYLOAD"
+    assert sat2.object_type == "ROCKET BODY"
+    assert sat2.rcs_size == "LARGE"
+    assert abs(sat2.collision_radius() - 0.002) < 1e-12
+    assert orbit_core.Satellite(3, [7000.0, 0.0, 0.0], [0.0, 7.5, 0.0]).collision_radius() == 0.0
+
+    assert len(orbit_core.find_conjunctions([sat1, sat2], 0.01)) == 1
+    sat2.rcs_size = None
+    assert orbit_core.find_conjunctions([sat1, sat2], 0.01) == []
+
+    with pytest.raises(ValueError):
+        sat1.object_type = "station"
+    with pytest.raises(ValueError):
+        sat1.hard_body_radius = -1.0
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_iter_conjunctions():
+    """Test the streaming conjunction iterator against find_conjunctions and early stop."""
+    satellites = [
+        orbit_core.Satellite(i, [7000.0 + 0.5 * i, 0.0, 0.0], [0.0, 7.5, 0.0]) for i in range(200)
+    ]
+    expected = {(c.norad_id_1, c.norad_id_2) for c in orbit_core.find_conjunctions(satellites, 1.2)}
+    streamed = {(c.norad_id_1, c.norad_id_2) for c in orbit_core.iter_conjunctions(satellites, 1.2, buffer=8)}
+    assert streamed == expected
+
+    assert len(list(orbit_core.iter_conjunctions(satellites, 1.2, limit=5))) == 5
+
+    with orbit_core.iter_conjunctions(satellites, 50.0, buffer=1) as stream:
+        first = next(stream)
+        assert first.distance_km < 50.0
+    assert list(stream) == []
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_conjunction_sort_order():
+    """Test deterministic id and distance ordering of conjunction results."""
+    satellites = [
+        orbit_core.Satellite(100 - i, [7000.0 + 0.3 * i, 0.1 * (i % 3), 0.0], [0.0, 7.5, 0.0]) for i in range(60)
+    ]
+    by_ids = orbit_core.find_conjunctions(satellites, 1.0)
+    keys = [(c.norad_id_1, c.norad_id_2) for c in by_ids]
+    assert keys == sorted(keys)
+    assert [(c.norad_id_1, c.norad_id_2) for c in orbit_core.find_conjunctions(satellites, 1.0)] == keys
+
+    by_distance = orbit_core.find_conjunctions(satellites, 1.0, sort_by="distance")
+    distances = [c.distance_km for c in by_distance]
+    assert distances == sorted(distances)
+    assert len(by_distance) == len(by_ids)
+
+    with pytest.raises(ValueError):
+        orbit_core.find_conjunctions(satellites, 1.0, sort_by="random")
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_thread_pool_control():
+    """Test limiting the worker thread count and single-threaded screening."""
+    satellites = [
+        orbit_core.Satellite(i, [7000.0 + 0.4 * i, 0.1 * (i % 5), 0.0], [0.0, 7.5, 0.0]) for i in range(100)
+    ]
+    default_threads = orbit_core.get_num_threads()
+    parallel = [(c.norad_id_1, c.norad_id_2) for c in orbit_core.find_conjunctions(satellites, 1.0)]
+    try:
+        orbit_core.set_num_threads(1)
+        assert orbit_core.get_num_threads() == 1
+        serial = [(c.norad_id_1, c.norad_id_2) for c in orbit_core.find_conjunctions(satellites, 1.0)]
+        assert serial == parallel
+    finally:
+        orbit_core.set_num_threads(None)
+    assert orbit_core.get_num_threads() == default_threads
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_screen_against_catalog():
+    """Test primary-vs-catalog screening reports only pairs involving a primary."""
+    catalog = [
+        orbit_core.Satellite(i, [7000.0 + 0.4 * i, 0.1 * (i % 5), 0.0], [0.0, 7.5, 0.0]) for i in range(200)
+    ]
+    primaries = [catalog[20], catalog[120]]
+    events = orbit_core.screen_against_catalog(primaries, catalog, 1.0)
+
+    all_pairs = orbit_core.find_conjunctions(catalog, 1.0)
+    expected = sorted(
+        (p, c.norad_id_2 if c.norad_id_1 == p else c.norad_id_1)
+        for c in all_pairs
+        for p in (20, 120)
+        if p in (c.norad_id_1, c.norad_id_2)
+    )
+    assert sorted((c.norad_id_1, c.norad_id_2) for c in events) == expected
+    assert all(c.norad_id_1 in (20, 120) for c in events)
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_screen_events_and_clustering():
+    """Test merging conjunction hits into events with entry/exit times."""
+    start = 2460000.5
+    props = [
+        orbit_core.Sgp4Propagator.from_elements(
+            orbit_core.MeanElements(norad_id, start, 0.0, 90.0, raan, 0.0001, 0.0, 0.0, 15.0)
+        )
+        for norad_id, raan in [(1, 0.0), (2, 90.0)]
+    ]
+    hits = orbit_core.screen_window(props, start, start + 0.1, 60.0, 50.0)
+    events = orbit_core.screen_events(props, start, start + 0.1, 60.0, 50.0)
+    assert events
+    event = events[0]
+    assert event.entry_jd <= event.tca_jd <= event.exit_jd
+    assert event.min_distance_km == min(h.distance_km for h in hits)
+    assert 0.0 < event.duration < 3600.0
+
+    # everything within a day merges into one event per pair
+    merged = orbit_core.cluster_conjunctions(hits, max_gap_s=86400.0)
+    assert len(merged) == 1
+    assert merged[0].hits == len(hits)
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_equality_hashing_and_pickle():
+    """Test __eq__/__hash__ and pickle round trips for Satellite, Conjunction and Epoch."""
+    import pickle
+
+    epoch = orbit_core.Epoch.from_iso("2024-03-01T12:00:00.123456Z")
+    cov = [[1e-4 if i == j else 0.0 for j in range(6)] for i in range(6)]
+    sat = orbit_core.Satellite(
+        25544, [7000.0, 0.0, 0.0], [0.0, 7.5, 0.0], covariance=cov, epoch=epoch, name="ISS", object_type="payload"
+    )
+    other = orbit_core.Satellite(2, [7000.5, 0.0, 0.0], [0.0, 7.5, 0.0], epoch=epoch)
+
+    restored = pickle.loads(pickle.dumps(sat))
+    assert restored == sat
+    assert hash(restored) == hash(sat)
+    assert restored.epoch.mjd("TAI") == epoch.mjd("TAI")
+    assert restored.object_type == "PAYLOAD"
+    assert len({sat, restored, other}) == 2
+    assert sat != other
+
+    event = orbit_core.find_conjunctions([sat, other], 1.0)[0]
+    assert pickle.loads(pickle.dumps(event)) == event
+    assert {event: "seen"}[pickle.loads(pickle.dumps(event))] == "seen"
+    assert pickle.loads(pickle.dumps(epoch)) == epoch
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_json_round_trip():
+    """Test to_json/from_json for Satellite, Conjunction, Ephemeris and conjunction lists."""
+    import json
+
+    epoch = orbit_core.Epoch.from_iso("2024-03-01T12:00:00Z")
+    cov = [[1e-4 if i == j else 0.0 for j in range(6)] for i in range(6)]
+    sat = orbit_core.Satellite(
+        25544, [7000.0, 0.0, 0.0], [0.0, 7.5, 0.0], covariance=cov, epoch=epoch, name="ISS", object_type="r/b"
+    )
+    text = sat.to_json()
+    assert json.loads(text)["object_type"] == "ROCKET BODY"
+    assert orbit_core.Satellite.from_json(text) == sat
+
+    point = orbit_core.Satellite(2, [7000.5, 0.0, 0.0], [0.0, 7.5, 0.0], epoch=epoch)
+    events = orbit_core.find_conjunctions([sat, point], 1.0)
+    archived = orbit_core.conjunctions_to_json(events, pretty=True)
+    assert orbit_core.conjunctions_from_json(archived) == events
+    # undefined RTN components are written as null and read back as NaN
+    bare = orbit_core.Conjunction(1, 2, 0.5, 0.1)
+    assert json.loads(bare.to_json())["radial_km"] is None
+    assert orbit_core.Conjunction.from_json(bare.to_json()) == bare
+
+    later = orbit_core.Epoch.from_iso("2024-03-01T12:01:00Z")
+    eph = orbit_core.Ephemeris(
+        25544, [epoch, later], [[7000.0, 0.0, 0.0], [7000.0, 450.0, 0.0]], [[0.0, 7.5, 0.0], [0.0, 7.5, 0.0]]
+    )
+    restored = orbit_core.Ephemeris.from_json(eph.to_json())
+    assert len(restored) == 2
+    assert restored.interpolation == "hermite"
+    assert restored.state_at(later).position == eph.state_at(later).position
+
+    with pytest.raises(ValueError):
+        orbit_core.Satellite.from_json('{"norad_id": 1, "position": [1, 2], "velocity": [0, 0, 0]}')
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_find_conjunctions_columnar():
+    """Test columnar results match the object API and export typed buffers."""
+    ids = list(range(50))
+    positions = [[7000.0 + 0.4 * i, 0.0, 0.0] for i in ids]
+    velocities = [[0.0, 7.5, 0.01 * i] for i in ids]
+
+    expected = orbit_core.find_conjunctions_array(ids, positions, velocities, 1.0, sort_by="distance")
+    table = orbit_core.find_conjunctions_columnar(ids, positions, velocities, 1.0, sort_by="distance")
+    assert list(table) == [
+        "norad_id_1", "norad_id_2", "distance_km", "relative_velocity_km_s",
+        "radial_km", "in_track_km", "cross_track_km",
+        "international_designator_1", "international_designator_2",
+    ]
+    assert all(len(column) == len(expected) for column in table.values())
+
+    ids_view = memoryview(table["norad_id_1"])
+    assert ids_view.format == "i" and ids_view.readonly
+    assert ids_view.tolist() == [c.norad_id_1 for c in expected]
+    distances = memoryview(table["distance_km"])
+    assert distances.format == "d" and distances.itemsize == 8
+    assert distances.tolist() == [c.distance_km for c in expected]
+    assert table["in_track_km"].to_list() == [c.in_track_km for c in expected]
+    assert table["international_designator_2"] == [None] * len(expected)
+
+    with pytest.raises(ValueError):
+        orbit_core.find_conjunctions_columnar(ids, positions, velocities, 1.0, output="parquet")
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_pc_methods():
+    """Test Foster, Patera, Alfano and Chan Pc agree on a typical encounter."""
+    sat1 = orbit_core.Satellite(1, [7000.0, 0.0, 0.0], [0.0, 7.5, 0.0])
+    sat2 = orbit_core.Satellite(2, [7000.05, 0.0, 0.02], [0.0, -7.5, 0.0])
+    cov1 = [[0.01, 0.0, 0.0], [0.0, 0.25, 0.0], [0.0, 0.0, 0.005]]
+    cov2 = [[0.02, 0.0, 0.0], [0.0, 0.5, 0.0], [0.0, 0.0, 0.01]]
+
+    results = orbit_core.compare_pc_methods(sat1, cov1, sat2, cov2, 0.015)
+    assert set(results) == {"foster", "patera", "alfano", "chan"}
+    foster = orbit_core.collision_probability(sat1, cov1, sat2, cov2, 0.015)
+    assert results["foster"] == pytest.approx(foster)
+    for name, pc in results.items():
+        assert pc == pytest.approx(foster, rel=1e-2), name
+    assert orbit_core.collision_probability(sat1, cov1, sat2, cov2, 0.015, method="patera") == pytest.approx(
+        results["patera"]
+    )
+
+    with pytest.raises(ValueError):
+        orbit_core.collision_probability(sat1, cov1, sat2, cov2, 0.015, method="monte-carlo")
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_covariance_propagation():
+    """Test propagation maps the covariance through the state transition matrix."""
+    v = (398600.4418 / 7000.0) ** 0.5
+    cov = [[1e-4 if i == j else 0.0 for j in range(6)] for i in range(6)]
+    for i in range(3, 6):
+        cov[i][i] = 1e-8
+    sat = orbit_core.Satellite(1, [7000.0, 0.0, 0.0], [0.0, v, 0.0], covariance=cov)
+
+    later = orbit_core.propagate_j2(sat, 3000.0)
+    assert later.covariance is not None
+    # velocity uncertainty turns into along-track position spread
+    assert later.covariance[1][1] + later.covariance[0][0] > 10 * cov[0][0]
+
+    stm = orbit_core.state_transition_matrix(sat, 3000.0)
+    assert len(stm) == 6 and all(len(row) == 6 for row in stm)
+    mapped = [[sum(stm[i][k] * cov[k][l] * stm[j][l] for k in range(6) for l in range(6)) for j in range(6)]
+              for i in range(6)]
+    for i in range(6):
+        assert later.covariance[i] == pytest.approx(mapped[i], rel=1e-6, abs=1e-15)
+    assert orbit_core.propagate_j2(orbit_core.Satellite(2, sat.position, sat.velocity), 60.0).covariance is None
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_b_plane_geometry():
+    """Test B-plane coordinates and axes of a head-on encounter."""
+    sat1 = orbit_core.Satellite(1, [7000.0, 0.0, 0.0], [0.0, 7.5, 0.0])
+    sat2 = orbit_core.Satellite(2, [7000.3, 0.0, 0.4], [0.0, -7.5, 0.0])
+    event = orbit_core.find_conjunctions([sat1, sat2], 1.0)[0]
+
+    assert event.relative_velocity_rtn == pytest.approx([0.0, -15.0, 0.0])
+    b_t, b_r = event.b_plane
+    assert (b_t**2 + b_r**2) ** 0.5 == pytest.approx(event.distance_km)
+    s, t, r = event.b_plane_axes()
+    assert s == pytest.approx([0.0, -1.0, 0.0])
+    assert abs(b_t) == pytest.approx(0.3) and abs(b_r) == pytest.approx(0.4)
+    assert orbit_core.Conjunction.from_json(event.to_json()).b_plane == pytest.approx([b_t, b_r])
+    assert orbit_core.Conjunction(1, 2, 0.5, 0.1).b_plane is None
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_catalog_container():
+    """Test Catalog lookup, mutation, filtering and screening."""
+    leo = [orbit_core.Satellite.from_keplerian(7000.0 + 0.3 * k, 0.001, 51.6, 0.0, 0.0, 0.0, k) for k in range(1, 4)]
+    geo = orbit_core.Satellite.from_keplerian(42164.0, 0.0001, 0.05, 0.0, 0.0, 90.0, 100)
+    catalog = orbit_core.Catalog(leo + [geo])
+
+    assert len(catalog) == 4 and 2 in catalog and 99 not in catalog
+    assert catalog[100].norad_id == 100
+    assert [sat.norad_id for sat in catalog] == [1, 2, 3, 100]
+    with pytest.raises(ValueError):
+        catalog.add(leo[0])
+    with pytest.raises(KeyError):
+        catalog[99]
+
+    assert catalog.filter(regime="GEO").ids() == [100]
+    assert catalog.filter(max_altitude_km=2000.0, min_inclination_deg=50.0).ids() == [1, 2, 3]
+    assert geo.regime() == "GEO" and leo[0].regime() == "LEO"
+
+    assert catalog.find_conjunctions(1.0) == orbit_core.find_conjunctions(catalog.satellites(), 1.0)
+    assert [c.norad_id_2 for c in catalog.screen([leo[0]], 0.5)] == [2]
+
+    moved = orbit_core.Satellite(2, [0.0, 9000.0, 0.0], [7.0, 0.0, 0.0])
+    catalog.update(moved)
+    assert catalog[2].position == [0.0, 9000.0, 0.0]
+    assert catalog.remove(1).norad_id == 1
+    assert catalog.ids() == [2, 3, 100] and catalog.get(1) is None
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_screen_geo_drift_in():
+    """Test GEO screening flags a slow drift-in that a snapshot screen misses."""
+    geo_radius = 42164.17
+    keeper = orbit_core.Satellite.from_keplerian(geo_radius, 0.0, 0.0, 0.0, 0.0, 2.0, 1)
+    drifter = orbit_core.Satellite.from_keplerian(geo_radius - 30.0, 0.0, 0.0, 0.0, 0.0, 0.0, 2)
+    distant = orbit_core.Satellite.from_keplerian(geo_radius, 0.0, 0.0, 0.0, 0.0, 90.0, 3)
+    leo = orbit_core.Satellite(4, [7000.0, 0.0, 0.0], [0.0, 7.5, 0.0])
+    satellites = [keeper, drifter, distant, leo]
+
+    assert orbit_core.find_conjunctions(satellites, 100.0) == []
+    events = orbit_core.screen_geo(satellites, slot_deg=0.1, window_days=14.0, threshold_km=100.0)
+    assert [(e.norad_id_1, e.norad_id_2) for e in events] == [(1, 2)]
+    event = events[0]
+    # 30 km below GEO drifts east at about 0.38 deg/day, closing the 2 deg gap in ~5 days
+    assert event.relative_drift_deg_per_day == pytest.approx(0.385, rel=2e-2)
+    assert event.days_to_closest == pytest.approx(2.0 / event.relative_drift_deg_per_day)
+    assert event.min_longitude_separation_deg == 0.0
+    assert event.estimated_min_distance_km == pytest.approx(30.0, rel=1e-3)
+    assert orbit_core.screen_geo(satellites, window_days=2.0, threshold_km=100.0) == []
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_predict_reentry():
+    """Test re-entry prediction from a state vector and from a TLE's B*."""
+    low = orbit_core.Satellite.from_keplerian(6378.137 + 250.0, 0.0, 51.6, 0.0, 0.0, 0.0, 1)
+    prediction = orbit_core.predict_reentry(low, ballistic_coefficient=0.01)
+    assert prediction.norad_id == 1
+    assert prediction.earliest_days < prediction.days < prediction.latest_days < 60.0
+    assert prediction.may_decay_within(60.0) and not prediction.may_decay_within(1.0)
+    assert prediction.decay_jd is None
+
+    high = orbit_core.Satellite.from_keplerian(6378.137 + 1500.0, 0.0, 51.6, 0.0, 0.0, 0.0, 2)
+    assert orbit_core.predict_reentry(high, ballistic_coefficient=0.01, max_days=3650.0).days is None
+    with pytest.raises(ValueError):
+        orbit_core.predict_reentry(low)
+
+    epoch = 2460000.5
+    elements = orbit_core.MeanElements(3, epoch, 1.0e-3, 51.6, 0.0, 0.0001, 0.0, 0.0, 16.0)
+    from_tle = orbit_core.predict_reentry(elements)
+    assert from_tle.decay_jd == pytest.approx(epoch + from_tle.days)
+    earliest, latest = from_tle.window_jd
+    assert earliest < from_tle.decay_jd < latest
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_density_models_and_drag_propagation():
+    """Test exponential/Harris-Priester densities, space weather and drag propagation."""
+    position = [6378.137 + 400.0, 0.0, 0.0]
+    assert orbit_core.atmospheric_density(position) == pytest.approx(3.725e-12, rel=1e-6)
+    quiet = orbit_core.SpaceWeather(f107=70.0, ap=4.0)
+    active = orbit_core.SpaceWeather(f107=250.0, f107_avg=220.0, ap=50.0)
+    assert active.f107_avg == 220.0 and quiet.f107_avg == 70.0
+    assert (
+        orbit_core.atmospheric_density(position, space_weather=quiet)
+        < orbit_core.atmospheric_density(position)
+        < orbit_core.atmospheric_density(position, space_weather=active)
+    )
+    hp = orbit_core.atmospheric_density(position, jd=2460000.5, model="harris-priester")
+    assert 2.249e-12 <= hp <= 7.492e-12
+    with pytest.raises(ValueError):
+        orbit_core.atmospheric_density(position, model="harris-priester")
+    with pytest.raises(ValueError):
+        orbit_core.SpaceWeather(f107=-1.0)
+
+    sat = orbit_core.Satellite.from_keplerian(6378.137 + 300.0, 0.0, 51.6, 0.0, 0.0, 0.0, 1)
+    vacuum = orbit_core.propagate_j2(sat, 86400.0)
+    dragged = orbit_core.propagate_with_drag(sat, 86400.0, 0.02)
+    stormy = orbit_core.propagate_with_drag(sat, 86400.0, 0.02, space_weather=active)
+    radius = lambda s: sum(x * x for x in s.position) ** 0.5
+    speed = lambda s: sum(x * x for x in s.velocity) ** 0.5
+    energy = lambda s: 0.5 * speed(s) ** 2 - 398600.4418 / radius(s)
+    assert energy(stormy) < energy(dragged) < energy(vacuum)
+    assert orbit_core.propagate_with_drag(sat, 600.0, 0.0).position == pytest.approx(
+        orbit_core.propagate_j2(sat, 600.0).position, abs=1e-6
+    )
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_third_body_and_radiation_pressure():
+    """Test Sun/Moon ephemerides and lunisolar and SRP perturbations on a GEO orbit."""
+    jd = 2460000.5
+    sun = orbit_core.sun_position(jd)
+    moon = orbit_core.moon_position(jd)
+    assert sum(x * x for x in sun) ** 0.5 == pytest.approx(1.496e8, rel=0.02)
+    assert 356000.0 < sum(x * x for x in moon) ** 0.5 < 407000.0
+
+    radius = 42164.17
+    speed = (398600.4418 / radius) ** 0.5
+    sat = orbit_core.Satellite(1, [radius, 0.0, 0.0], [0.0, speed, 0.0], epoch=orbit_core.Epoch(jd))
+    week = 7 * 86400.0
+    keplerian = orbit_core.propagate_two_body(sat, week)
+    lunisolar = orbit_core.propagate_perturbed(sat, week, j2=False, sun=True, moon=True)
+    with_srp = orbit_core.propagate_perturbed(sat, week, j2=False, sun=True, moon=True, srp_area_to_mass=0.02)
+    offset = lambda a, b: sum((x - y) ** 2 for x, y in zip(a.position, b.position)) ** 0.5
+    # lunisolar gravity moves a GEO object by kilometres within a week; SRP by less
+    assert offset(lunisolar, keplerian) > 5.0
+    assert 0.01 < offset(with_srp, lunisolar) < offset(lunisolar, keplerian)
+    assert orbit_core.propagate_perturbed(sat, 3600.0).position == pytest.approx(
+        orbit_core.propagate_j2(sat, 3600.0).position, abs=1e-6
+    )
+
+    with pytest.raises(ValueError):
+        orbit_core.propagate_perturbed(orbit_core.Satellite(2, [radius, 0.0, 0.0], [0.0, speed, 0.0]), 60.0, moon=True)
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_eclipse_states():
+    """Test sunlit, penumbra and umbra classification with both shadow models."""
+    jd = 2460000.5
+    sun = orbit_core.sun_position(jd)
+    norm = sum(x * x for x in sun) ** 0.5
+    sun_hat = [x / norm for x in sun]
+    day_side = [7000.0 * x for x in sun_hat]
+    night_side = [-7000.0 * x for x in sun_hat]
+    assert orbit_core.eclipse_state(day_side, jd) == ("sunlit", 1.0)
+    assert orbit_core.eclipse_state(night_side, jd) == ("umbra", 0.0)
+    assert orbit_core.eclipse_state(night_side, jd, model="cylindrical") == ("umbra", 0.0)
+
+    # scan the shadow entry: the conical model passes through a penumbra
+    perpendicular = [-sun_hat[1], sun_hat[0], 0.0]
+    scale = sum(x * x for x in perpendicular) ** 0.5
+    perpendicular = [x / scale for x in perpendicular]
+    states = []
+    for step in range(400):
+        angle = math.radians(100.0 + 0.1 * step)
+        position = [7000.0 * (math.cos(angle) * s + math.sin(angle) * p) for s, p in zip(sun_hat, perpendicular)]
+        states.append(orbit_core.eclipse_state(position, jd)[0])
+    assert "penumbra" in states and states[0] == "sunlit" and states[-1] == "umbra"
+
+    sats = [
+        orbit_core.Satellite(1, day_side, [0.0, 7.5, 0.0], epoch=orbit_core.Epoch(jd)),
+        orbit_core.Satellite(2, night_side, [0.0, 7.5, 0.0], epoch=orbit_core.Epoch(jd)),
+    ]
+    assert [state for state, _ in orbit_core.eclipse_states(sats)] == ["sunlit", "umbra"]
+    with pytest.raises(ValueError):
+        orbit_core.eclipse_state(day_side, jd, model="spherical")
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_ground_track(iss_tle):
+    """Test ground track sampling from a TLE and from a state vector."""
+    line1, line2 = iss_tle
+    start = orbit_core.parse_tle(line1, line2).epoch_jd
+    track = orbit_core.ground_track((line1, line2), start, start + 0.1, step=60.0)
+    assert sorted(track) == ["alt_km", "jd", "lat_deg", "lon_deg"]
+    lat = track["lat_deg"].to_list()
+    assert len(lat) == len(track["jd"]) >= 145
+    assert track["jd"].to_list()[-1] == pytest.approx(start + 0.1)
+    # the ISS stays within its inclination band (geodetic latitude runs slightly higher) at about 350 km
+    assert 45.0 < max(abs(x) for x in lat) < 52.0
+    assert all(300.0 < h < 400.0 for h in track["alt_km"].to_list())
+    assert all(-180.0 <= x <= 180.0 for x in track["lon_deg"].to_list())
+
+    polar = orbit_core.Satellite.from_keplerian(7000.0, 0.0, 90.0, 0.0, 0.0, 0.0, 1)
+    with pytest.raises(ValueError):
+        orbit_core.ground_track(polar, start, start + 0.1)
+    polar = orbit_core.Satellite(1, polar.position, polar.velocity, epoch=orbit_core.Epoch(start))
+    polar_track = orbit_core.ground_track(polar, start, start + 0.1, step=120.0)
+    assert max(polar_track["lat_deg"].to_list()) > 85.0
+    assert polar_track["alt_km"].to_list()[0] == pytest.approx(7000.0 - 6378.137, abs=1.0)
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_lambert():
+    """Test the Lambert solver against Vallado example 7-5 and by propagation."""
+    v1, v2 = orbit_core.lambert([15945.34, 0.0, 0.0], [12214.83899, 10249.46731, 0.0], 76.0 * 60.0)
+    assert v1 == pytest.approx([2.058913, 2.915965, 0.0], abs=1e-5)
+    assert v2 == pytest.approx([-3.451565, 0.910315, 0.0], abs=1e-5)
+
+    r1, r2 = [7000.0, 1000.0, -500.0], [-3000.0, 9000.0, 2500.0]
+    v1, v2 = orbit_core.lambert(r1, r2, 3000.0)
+    arrived = orbit_core.propagate_two_body(orbit_core.Satellite(1, r1, v1), 3000.0)
+    assert arrived.position == pytest.approx(r2, abs=1e-3)
+    assert arrived.velocity == pytest.approx(v2, abs=1e-6)
+    long_way, _ = orbit_core.lambert(r1, r2, 3000.0, prograde=False)
+    assert long_way != pytest.approx(v1)
+
+    with pytest.raises(ValueError):
+        orbit_core.lambert(r1, r2, -10.0)
+    with pytest.raises(ValueError):
+        orbit_core.lambert(r1, [0.0, 1.0], 100.0)
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_transfer_calculators():
+    """Test Hohmann, bi-elliptic, plane change and phasing delta-v from states and elements."""
+    leo = orbit_core.Satellite.from_keplerian(6678.137, 0.0, 28.5, 0.0, 0.0, 0.0, 1)
+    hohmann = orbit_core.hohmann_transfer(leo, 42164.17)
+    assert hohmann.kind == "hohmann"
+    assert hohmann.delta_v_m_s == pytest.approx([2425.7, 1466.7], abs=1.0)
+    assert hohmann.total_delta_v_m_s == pytest.approx(sum(hohmann.delta_v_m_s))
+    assert hohmann.time_of_flight_s == pytest.approx(5.27 * 3600.0, rel=2e-3)
+    elements = leo.to_keplerian()
+    assert orbit_core.hohmann_transfer(elements, 42164.17).delta_v_m_s == pytest.approx(hohmann.delta_v_m_s)
+    geo_with_plane = orbit_core.hohmann_transfer(leo, 42164.17, inclination_change_deg=28.5)
+    assert geo_with_plane.delta_v_m_s[0] == pytest.approx(hohmann.delta_v_m_s[0])
+    assert geo_with_plane.delta_v_m_s[1] > hohmann.delta_v_m_s[1]
+
+    bielliptic = orbit_core.bielliptic_transfer(leo, 20 * 6678.137, 60 * 6678.137)
+    assert len(bielliptic.delta_v_m_s) == 3
+    assert bielliptic.total_delta_v_m_s < orbit_core.hohmann_transfer(leo, 20 * 6678.137).total_delta_v_m_s
+    with pytest.raises(ValueError):
+        orbit_core.bielliptic_transfer(leo, 42164.17, 10000.0)
+
+    speed = sum(v * v for v in leo.velocity) ** 0.5
+    assert orbit_core.plane_change(leo, 10.0) == pytest.approx(2000.0 * speed * math.sin(math.radians(5.0)))
+
+    phasing = orbit_core.phasing_orbit(leo, 30.0, revolutions=3)
+    assert phasing.delta_v_m_s[0] == phasing.delta_v_m_s[1] > 0.0
+    assert phasing.transfer_semi_major_axes_km[0] < 6678.137
+    with pytest.raises(ValueError):
+        orbit_core.phasing_orbit(leo, 180.0)
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_relative_propagate():
+    """Test Clohessy-Wiltshire relative motion of a deputy about a chief."""
+    radius = 7000.0
+    speed = (398600.4418 / radius) ** 0.5
+    n = speed / radius
+    chief = orbit_core.Satellite(1, [radius, 0.0, 0.0], [0.0, speed, 0.0])
+    # 1 km radially out with the in-track rate that cancels drift: a closed 2:1 ellipse
+    deputy = orbit_core.Satellite(2, [radius + 1.0, 0.0, 0.0], [0.0, speed * (radius + 1.0) / radius - 2.0 * n, 0.0])
+    period = 2.0 * math.pi / n
+    position, velocity = orbit_core.relative_propagate(chief, deputy, period)
+    assert position == pytest.approx([1.0, 0.0, 0.0], abs=1e-6)
+    half, _ = orbit_core.relative_propagate(chief, deputy, period / 2.0)
+    assert half == pytest.approx([-1.0, 0.0, 0.0], abs=1e-6)
+
+    # a pure in-track offset stays put; the same offset with a radial one drifts
+    trailing = orbit_core.Satellite(3, [radius, -1.0, 0.0], [0.0, speed, 0.0])
+    assert orbit_core.relative_propagate(chief, trailing, period)[0][1] == pytest.approx(-1.0, abs=1e-3)
+    low = orbit_core.Satellite(4, [radius - 1.0, 0.0, 0.0], [0.0, speed, 0.0])
+    assert orbit_core.relative_propagate(chief, low, period)[0][1] > 10.0
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_risk_scorer():
+    """Test weighted conjunction risk scoring and ranking."""
+    scorer = orbit_core.RiskScorer()
+    near = orbit_core.Conjunction(1, 2, 0.1, 10.0, probability=1e-4)
+    far = orbit_core.Conjunction(3, 4, 5.0, 10.0, probability=1e-9)
+    unknown = orbit_core.Conjunction(5, 6, 0.5, 10.0)
+
+    scored = scorer.score(near)
+    assert 0.0 < scored.score <= 1.0
+    assert scored.probability_factor == pytest.approx(6.0 / 7.0)
+    assert scored.object_size_factor is None and scored.covariance_factor is None
+    assert scorer.score(unknown).probability_factor is None
+
+    ranked = scorer.rank([far, unknown, near])
+    assert [(r.norad_id_1, r.norad_id_2) for r in ranked][0] == (1, 2)
+    assert ranked[-1].norad_id_1 == 3
+
+    # objects without covariance count as poorly known
+    sats = [orbit_core.Satellite(i, [7000.0 + i, 0.0, 0.0], [0.0, 7.5, 0.0], hard_body_radius=0.005) for i in (1, 2)]
+    with_objects = scorer.rank([near], orbit_core.Catalog(sats))[0]
+    assert with_objects.covariance_factor == 1.0
+    assert with_objects.object_size_factor == pytest.approx(1.0 - math.exp(-1.0))
+    assert scorer.rank([near], sats)[0].score == pytest.approx(with_objects.score)
+
+    # miss distance alone
+    only_miss = orbit_core.RiskScorer(relative_velocity=0.0, probability=0.0, object_size=0.0, covariance=0.0)
+    assert only_miss.score(unknown).score == pytest.approx(math.exp(-0.5))
+    with pytest.raises(ValueError):
+        orbit_core.RiskScorer(miss_distance=-1.0)
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_conjunction_graph():
+    """Test connected components, degree and most threatened objects."""
+    events = [
+        orbit_core.Conjunction(1, 2, 1.0, 10.0),
+        orbit_core.Conjunction(1, 3, 2.0, 10.0),
+        orbit_core.Conjunction(1, 4, 0.5, 10.0),
+        orbit_core.Conjunction(7, 8, 0.1, 10.0),
+        orbit_core.Conjunction(8, 7, 0.3, 10.0),
+    ]
+    graph = orbit_core.conjunction_graph(events)
+    assert (graph.node_count, graph.edge_count) == (6, 4)
+    assert graph.components == [[1, 2, 3, 4], [7, 8]]
+    assert graph.degree == {1: 3, 2: 1, 3: 1, 4: 1, 7: 1, 8: 1}
+    assert graph.neighbors(1) == [2, 3, 4]
+    assert graph.neighbors(99) == []
+
+    top = graph.most_threatened(2)
+    assert [t.norad_id for t in top] == [1, 7]
+    assert top[1].events == 2 and top[1].min_distance_km == pytest.approx(0.1)
+    assert len(orbit_core.ConjunctionGraph([]).most_threatened()) == 0
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_screening_volume():
+    """Test ellipsoidal RTN screening volumes and hard-body scaling."""
+    primary = orbit_core.Satellite(1, [7000.0, 0.0, 0.0], [0.0, 7.5, 0.0])
+    trailing = orbit_core.Satellite(2, [7000.0, -3.0, 0.0], [0.0, 7.5, 0.0])
+    above = orbit_core.Satellite(3, [7003.0, 0.0, 0.0], [0.0, 7.5, 0.0])
+    sats = [primary, trailing, above]
+
+    # a 5 km sphere catches both; a thin radial shell only the in-track one
+    assert len(orbit_core.find_conjunctions(sats, 5.0)) == 3
+    volume = orbit_core.ScreeningVolume(0.5, 5.0, 1.0)
+    assert volume.contains_satellite(primary, trailing)
+    assert not volume.contains_satellite(primary, above)
+    pairs = [(c.norad_id_1, c.norad_id_2) for c in orbit_core.find_conjunctions([primary, above], volume)]
+    assert pairs == []
+    pairs = [(c.norad_id_1, c.norad_id_2) for c in orbit_core.screen_against_catalog([primary], sats, volume)]
+    assert pairs == [(1, 2)]
+    assert len(orbit_core.Catalog(sats).screen([primary], volume)) == 1
+
+    # padding by ten times the combined size reaches the radial neighbour
+    big = orbit_core.Satellite(1, [7000.0, 0.0, 0.0], [0.0, 7.5, 0.0], hard_body_radius=0.3)
+    scaled = orbit_core.ScreeningVolume(0.5, 5.0, 1.0, hard_body_scale=10.0)
+    assert scaled.contains_satellite(big, above)
+    assert not volume.contains_satellite(big, above)
+    assert orbit_core.ScreeningVolume.sphere(2.0).radial_km == 2.0
+
+    with pytest.raises(ValueError):
+        orbit_core.ScreeningVolume(0.0, 1.0, 1.0)
+    with pytest.raises(TypeError):
+        orbit_core.find_conjunctions(sats, "far")
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_pairwise_distances_buffer():
+    """Test the pairwise distance matrix buffer, condensed and squared forms."""
+    satellites = [
+        orbit_core.Satellite(1, [0.0, 0.0, 0.0], [0.0, 0.0, 0.0]),
+        orbit_core.Satellite(2, [3.0, 4.0, 0.0], [0.0, 0.0, 0.0]),
+        orbit_core.Satellite(3, [0.0, 0.0, 12.0], [0.0, 0.0, 0.0]),
+    ]
+    matrix = orbit_core.pairwise_distances(satellites)
+    assert matrix.shape == [3, 3] and matrix.dtype == "float64"
+    view = memoryview(matrix)
+    assert view.shape == (3, 3) and view.strides == (24, 8) and view.readonly
+    assert view[1, 2] == pytest.approx(13.0) and view[2, 1] == view[1, 2]
+    assert matrix.to_list()[0] == [0.0, 5.0, 12.0]
+    assert matrix[-1] == pytest.approx([12.0, 13.0, 0.0])
+
+    condensed = orbit_core.pairwise_distances(satellites, condensed=True)
+    assert condensed.shape == [3]
+    assert condensed.to_list() == pytest.approx([5.0, 12.0, 13.0])
+    squared = orbit_core.pairwise_distances(satellites, condensed=True, squared=True)
+    assert squared.to_list() == pytest.approx([25.0, 144.0, 169.0])
+    assert len(orbit_core.pairwise_distances([], condensed=True)) == 0
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_mixed_precision_screening():
+    """Test the single-precision coarse filter returns the double-precision results."""
+    satellites = [
+        orbit_core.Satellite(i, [42164.0003 + dx, 0.0, 0.0], [0.0, 3.07, 0.0])
+        for i, dx in enumerate([0.0, 0.9999, 2.0, 2.9998])
+    ]
+    exact = orbit_core.find_conjunctions(satellites, 1.0)
+    mixed = orbit_core.find_conjunctions(satellites, 1.0, precision="mixed")
+    assert [(c.norad_id_1, c.norad_id_2) for c in exact] == [(0, 1), (2, 3)]
+    assert mixed == exact
+    assert orbit_core.Catalog(satellites).find_conjunctions(1.0, precision="mixed") == exact
+
+    ids = [s.norad_id for s in satellites]
+    positions = [s.position for s in satellites]
+    velocities = [s.velocity for s in satellites]
+    assert orbit_core.find_conjunctions_array(ids, positions, velocities, 1.0, precision="mixed") == exact
+    with pytest.raises(ValueError):
+        orbit_core.find_conjunctions(satellites, 1.0, precision="half")
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_closest_approaches_large_set():
+    """Test the blocked distance kernel across block boundaries."""
+    # spacing grows along the line, so each object's nearest neighbour is the previous one
+    satellites = [
+        orbit_core.Satellite(k, [7000.0 + k * k * 1e-3, 0.0, 0.0], [0.0, 7.5, 0.0]) for k in range(1500)
+    ]
+    closest = orbit_core.find_closest_approaches(satellites)
+    assert closest[0][:2] == (0, 1)
+    assert closest[1100][:2] == (1100, 1099)
+    assert closest[1100][2] == pytest.approx((1100 ** 2 - 1099 ** 2) * 1e-3)
+    matrix = orbit_core.pairwise_distances(satellites[:1100], condensed=True)
+    assert len(matrix) == 1100 * 1099 // 2
+    assert matrix[-1] == pytest.approx((1099 ** 2 - 1098 ** 2) * 1e-3)
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_satellite_state_is_fixed_size():
+    """Test position/velocity stay three-component lists through setters and JSON."""
+    sat = orbit_core.Satellite(1, [7000.0, 0.0, 0.0], [0.0, 7.5, 0.0])
+    sat.position = (7001.0, 1.0, 2.0)
+    assert sat.position == [7001.0, 1.0, 2.0]
+    assert sat.speed() == pytest.approx(7.5)
+    with pytest.raises(ValueError):
+        sat.velocity = [0.0, 7.5]
+    with pytest.raises(ValueError):
+        orbit_core.Satellite(1, [7000.0, 0.0, 0.0, 1.0], [0.0, 7.5, 0.0])
+    text = sat.to_json().replace("[7001.0,1.0,2.0]", "[7001.0,1.0]")
+    with pytest.raises(ValueError):
+        orbit_core.Satellite.from_json(text)
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_screening_progress_and_cancellation():
+    """Test progress callbacks and cancellation of time-window screens."""
+    start = 2460000.5
+    props = [
+        orbit_core.Sgp4Propagator.from_elements(
+            orbit_core.MeanElements(norad_id, start, 0.0, 90.0, raan, 0.0001, 0.0, 0.0, 15.0)
+        )
+        for norad_id, raan in [(1, 0.0), (2, 90.0), (3, 45.0)]
+    ]
+    reports = []
+    events = orbit_core.screen_window(
+        props, start, start + 0.1, 60.0, 50.0, progress=lambda *args: reports.append(args), progress_interval_s=0.01
+    )
+    # the last report is the final tally
+    assert reports[-1] == (100.0, 3, len(events))
+    assert all(0.0 <= percent <= 100.0 for percent, _, _ in reports)
+
+    token = orbit_core.CancellationToken()
+    token.cancel()
+    assert token.cancelled
+    with pytest.raises(RuntimeError):
+        orbit_core.screen_window(props, start, start + 0.1, 60.0, 50.0, cancel=token)
+    with pytest.raises(RuntimeError):
+        orbit_core.ScreeningPipeline(50.0).run(props, start, start + 0.1, 60.0, cancel=token)
+
+    def fail(*_):
+        raise KeyError("stop")
+
+    with pytest.raises(KeyError):
+        orbit_core.screen_window(props, start, start + 0.1, 60.0, 50.0, progress=fail)
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_heavy_calls_run_concurrently_across_threads():
+    """Test that screening, distances and propagation give the same results from worker threads."""
+    from concurrent.futures import ThreadPoolExecutor
+
+    sats = [
+        orbit_core.Satellite(i, [7000.0 + 0.3 * i, 0.1 * (i % 5), 0.0], [0.0, 7.5, 0.0])
+        for i in range(200)
+    ]
+    calls = [
+        lambda: [(c.norad_id_1, c.norad_id_2) for c in orbit_core.find_conjunctions(sats, 1.0)],
+        lambda: orbit_core.pairwise_distances(sats[:50]).to_list(),
+        lambda: [s.position for s in orbit_core.propagate_batch(sats[:20], 600.0)],
+    ]
+    expected = [call() for call in calls]
+    with ThreadPoolExecutor(max_workers=4) as pool:
+        results = [pool.submit(call) for call in calls * 4]
+        assert [future.result() for future in results] == expected * 4
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_custom_exceptions(iss_tle):
+    """Test that bad states, failed propagation and bad TLEs raise distinct exceptions."""
+    assert issubclass(orbit_core.InvalidStateError, ValueError)
+    assert issubclass(orbit_core.TleParseError, ValueError)
+    assert issubclass(orbit_core.PropagationError, RuntimeError)
+
+    with pytest.raises(orbit_core.InvalidStateError):
+        orbit_core.Satellite(1, [7000.0, 0.0], [0.0, 7.5, 0.0])
+    with pytest.raises(orbit_core.InvalidStateError):
+        orbit_core.Satellite(1, [7000.0, 0.0, 0.0], [0.0, 7.5, 0.0], hard_body_radius=-1.0)
+
+    line1, line2 = iss_tle
+    with pytest.raises(orbit_core.TleParseError):
+        orbit_core.parse_tle(line1.replace("2927", "2928"), line2)
+    with pytest.raises(orbit_core.TleParseError):
+        orbit_core.Sgp4Propagator(line1, line2[:40])
+
+    origin = orbit_core.Satellite(1, [0.0, 0.0, 0.0], [0.0, 7.5, 0.0])
+    with pytest.raises(orbit_core.PropagationError):
+        orbit_core.propagate_two_body(origin, 600.0)
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_state_validation():
+    """Test rejection of NaN, zero-norm and sub-surface states."""
+    good = orbit_core.Satellite(1, [7000.0, 0.0, 0.0], [0.0, 7.5, 0.0])
+    good.validate(strict=True)
+    low = orbit_core.Satellite(2, [6000.0, 0.0, 0.0], [0.0, 7.5, 0.0])
+    low.validate()
+    with pytest.raises(orbit_core.InvalidStateError):
+        low.validate(strict=True)
+    bad = orbit_core.Satellite(3, [7000.0, float("nan"), 0.0], [0.0, 7.5, 0.0])
+    with pytest.raises(orbit_core.InvalidStateError):
+        bad.validate()
+    with pytest.raises(orbit_core.InvalidStateError):
+        orbit_core.Satellite(4, [0.0, 0.0, 0.0], [0.0, 7.5, 0.0]).validate()
+
+    with pytest.raises(orbit_core.InvalidStateError):
+        orbit_core.find_conjunctions([good, bad], 10.0)
+    assert orbit_core.find_conjunctions([good, bad], 10.0, validate=False) == []
+    with pytest.raises(orbit_core.InvalidStateError):
+        orbit_core.iter_conjunctions([good, bad], 10.0)
+    assert list(orbit_core.iter_conjunctions([good, bad], 10.0, validate=False)) == []
+    with pytest.raises(orbit_core.InvalidStateError):
+        orbit_core.propagate_batch([good, bad], 60.0)
+    with pytest.raises(orbit_core.InvalidStateError):
+        orbit_core.find_conjunctions_array([1, 3], [good.position, bad.position], [good.velocity, bad.velocity], 10.0)
+    with pytest.raises(orbit_core.InvalidStateError):
+        orbit_core.Catalog([good, bad]).find_conjunctions(10.0)
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_catalog_store():
+    """Test persisting states and conjunctions to SQLite and querying them back."""
+    import os
+    import tempfile
+
+    path = os.path.join(tempfile.mkdtemp(), "catalog.db")
+    epoch = orbit_core.Epoch.from_iso("2024-03-01T12:00:00Z")
+    sats = [
+        orbit_core.Satellite(25544, [7000.0, 0.0, 0.0], [0.0, 7.5, 0.0], epoch=epoch, name="ISS"),
+        orbit_core.Satellite(2, [7001.0, 0.0, 0.0], [0.0, 0.0, 7.5], epoch=epoch),
+    ]
+    store = orbit_core.CatalogStore(path)
+    assert store.save_satellites(sats) == 2
+    store.save_satellites([orbit_core.Satellite(25544, [7000.0, 0.0, 0.0], [0.0, 7.5, 0.0], epoch=epoch + 60.0)])
+    with pytest.raises(orbit_core.InvalidStateError):
+        store.save_satellites([orbit_core.Satellite(3, [7000.0, 0.0, 0.0], [0.0, 7.5, 0.0])])
+
+    events = [orbit_core.Conjunction(25544, 2, 1.2, 10.6, tca_jd=epoch.jd() + 0.01)]
+    store.save_conjunctions(events)
+    store.save_conjunctions(events)
+    with pytest.raises(ValueError):
+        store.save_conjunctions(orbit_core.find_conjunctions(sats, 5.0))
+    del store
+
+    reopened = orbit_core.CatalogStore(path)
+    assert len(reopened.epochs(25544)) == 2
+    loaded = {sat.norad_id: sat for sat in reopened.load_satellites()}
+    assert loaded[25544].epoch.jd() > epoch.jd()
+    assert reopened.load_satellites(at_jd=epoch.jd())[1].name == "ISS"
+    found = reopened.conjunctions(norad_id=25544)
+    assert len(found) == 1 and found[0].distance_km == pytest.approx(events[0].distance_km)
+    assert reopened.conjunctions(norad_id=25544, start_jd=epoch.jd() + 1.0) == []
+    assert reopened.recent_conjunctions(25544, days=7.0) == []
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE or not hasattr(orbit_core, "fetch_catalog"), reason="Rust engine built without fetch")
+def test_fetch_argument_checks():
+    """Test that catalog downloads reject bad queries before touching the network."""
+    with pytest.raises(ValueError):
+        orbit_core.fetch_catalog(group="active", norad_ids=[25544])
+    with pytest.raises(ValueError):
+        orbit_core.fetch_elements(source="spacetrack")
+    with pytest.raises(ValueError):
+        orbit_core.fetch_elements(format="xml")
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_parse_omm_matches_tle(iss_tle):
+    """Test OMM in KVN and JSON yielding the same elements as the TLE."""
+    import json
+
+    line1, line2 = iss_tle
+    fields = {
+        "OBJECT_NAME": "ISS (ZARYA)",
+        "NORAD_CAT_ID": 25544,
+        "EPOCH": "2008-09-20T12:25:40.104192",
+        "MEAN_MOTION": 15.72125391,
+        "ECCENTRICITY": 0.0006703,
+        "INCLINATION": 51.6416,
+        "RA_OF_ASC_NODE": 247.4627,
+        "ARG_OF_PERICENTER": 130.5360,
+        "MEAN_ANOMALY": 325.0288,
+        "BSTAR": -0.11606e-4,
+    }
+    kvn = "CCSDS_OMM_VERS = 2.0\n" + "".join(f"{k} = {v}\n" for k, v in fields.items())
+
+    tle = orbit_core.parse_tle(line1, line2)
+    for parsed in (orbit_core.parse_omm(kvn), orbit_core.parse_omm(json.dumps([fields]))):
+        assert len(parsed) == 1
+        assert parsed[0].name == "ISS (ZARYA)"
+        assert abs(parsed[0].epoch_jd - tle.epoch_jd) < 1e-8
+        assert parsed[0].mean_motion_rev_per_day == tle.mean_motion_rev_per_day
+
+    with pytest.raises(ValueError):
+        orbit_core.parse_omm(kvn.replace("BSTAR", "DRAG"))
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_screening_logs_stages(iss_tle):
+    """Test screening stages reaching Python logging with structured fields."""
+    import logging
+
+    class Collect(logging.Handler):
+        def __init__(self):
+            super().__init__(logging.DEBUG)
+            self.records = []
+
+        def emit(self, record):
+            self.records.append(record)
+
+    line1, line2 = iss_tle
+    iss = orbit_core.Sgp4Propagator(line1, line2)
+    pipeline = orbit_core.ScreeningPipeline(50.0)
+    pipeline.add_altitude_filter()
+
+    logger = logging.getLogger("orbit_core.screening")
+    handler = Collect()
+    logger.addHandler(handler)
+    try:
+        logger.setLevel(logging.INFO)
+        pipeline.run([iss, iss], iss.epoch_jd, iss.epoch_jd + 0.05, 60.0)
+        assert handler.records == []
+
+        logger.setLevel(logging.DEBUG)
+        events = pipeline.run([iss, iss], iss.epoch_jd, iss.epoch_jd + 0.05, 60.0)
+    finally:
+        logger.removeHandler(handler)
+        logger.setLevel(logging.NOTSET)
+
+    fields = [r.fields for r in handler.records]
+    assert fields[0]["objects"] == 2
+    assert {"filter": "altitude", "kept": 1, "pruned": 0} in fields
+    assert sum(f.get("hits", 0) for f in fields) == len(events)
+    assert fields[-1]["conjunctions"] == len(events)
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_benchmark_synthetic_catalog():
+    """Test the synthetic benchmark population is reproducible and screenable."""
+    catalog = orbit_core.benchmark_synthetic_catalog(300, seed=3)
+    assert [p.norad_id for p in catalog[:3]] == [1, 2, 3]
+    again = orbit_core.benchmark_synthetic_catalog(100, seed=3)
+    assert again[99].propagate(0.0).position == catalog[99].propagate(0.0).position
+
+    states = [p.propagate(0.0) for p in catalog]
+    assert all(6500 < math.hypot(*s.position) < 47000 for s in states)
+    orbit_core.find_conjunctions(states, 50.0)
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_generate_catalog():
+    """Test synthetic satellites have orbital speeds matching their altitude."""
+    catalog = orbit_core.generate_catalog(500, seed=11)
+    assert len(catalog) == 500
+    for sat in catalog:
+        r = math.hypot(*sat.position)
+        v = math.hypot(*sat.velocity)
+        # bound orbit: below escape speed, above the speed at apogee of a GTO-like orbit
+        assert 0.3 * math.sqrt(398600.4418 / r) < v < math.sqrt(2.0 * 398600.4418 / r)
+
+    geo = orbit_core.generate_catalog(50, regime_mix={"geo": 1.0}, seed=11)
+    assert all(abs(math.hypot(*s.position) - 42164.0) < 500.0 for s in geo)
+
+    with pytest.raises(ValueError):
+        orbit_core.generate_catalog(10, regime_mix={"lunar": 1.0})
+    with pytest.raises(ValueError):
+        orbit_core.generate_catalog(10, regime_mix={"leo": 0.0})
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_osculating_mean_conversion(iss_tle):
+    """Test converting an SGP4 state back to the TLE's mean elements."""
+    line1, line2 = iss_tle
+    tle = orbit_core.parse_tle(line1, line2)
+
+    state = orbit_core.mean_to_osculating(tle)
+    mean = orbit_core.osculating_to_mean(state, bstar=tle.bstar)
+    assert abs(mean.mean_motion_rev_per_day - tle.mean_motion_rev_per_day) < 1e-8
+    assert abs(mean.inclination_deg - tle.inclination_deg) < 1e-8
+    assert mean.bstar == tle.bstar
+
+    again = orbit_core.Sgp4Propagator.from_elements(mean).propagate(0.0)
+    assert again.distance_to(state) < 1e-6
+
+    brouwer = orbit_core.kozai_to_brouwer(tle.mean_motion_rev_per_day, tle.eccentricity, tle.inclination_deg)
+    kozai = orbit_core.brouwer_to_kozai(brouwer, tle.eccentricity, tle.inclination_deg)
+    assert abs(kozai - tle.mean_motion_rev_per_day) < 1e-10
+
+    with pytest.raises(ValueError):
+        orbit_core.osculating_to_mean(orbit_core.Satellite(1, [7000.0, 0.0, 0.0], [0.0, 7.5, 0.0]))
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_equinoctial_elements():
+    """Test equinoctial elements of a GEO satellite and their round trip."""
+    geo = orbit_core.Satellite.from_keplerian(42164.0, 0.0, 0.0, 0.0, 0.0, 120.0, 1)
+    eq = geo.to_equinoctial()
+    assert abs(eq.semi_major_axis_km - 42164.0) < 1e-6
+    assert abs(eq.h) < 1e-12 and abs(eq.p) < 1e-12
+    assert abs(eq.mean_longitude_deg - 120.0) < 1e-9
+    assert not eq.retrograde
+
+    again = orbit_core.Satellite.from_equinoctial(eq, 1)
+    assert again.distance_to(geo) < 1e-6
+
+    molniya = orbit_core.EquinoctialElements(26600.0, -0.7, 0.1, 0.3, 0.2, 45.0)
+    kep = molniya.to_keplerian()
+    assert abs(kep.eccentricity - molniya.eccentricity()) < 1e-12
+    back = kep.to_equinoctial()
+    assert abs(back.h - molniya.h) < 1e-9 and abs(back.mean_longitude_deg - 45.0) < 1e-9
+
+    with pytest.raises(ValueError):
+        orbit_core.Satellite.from_equinoctial(orbit_core.EquinoctialElements(7000.0, 1.0, 0.5, 0.0, 0.0, 0.0), 2)
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_propagate_batch_epochs(iss_tle):
+    """Test sampling several objects at several epochs into one state array."""
+    line1, line2 = iss_tle
+    iss = orbit_core.parse_tle(line1, line2)
+    propagator = orbit_core.Sgp4Propagator.from_elements(iss)
+    radius = 7000.0
+    speed = math.sqrt(398600.4418 / radius)
+    sat = orbit_core.Satellite(2, [radius, 0.0, 0.0], [0.0, speed, 0.0], epoch=orbit_core.Epoch(iss.epoch_jd))
+    epochs = [iss.epoch_jd + k * 0.01 for k in range(5)]
+
+    states = orbit_core.propagate_batch([propagator, sat, (line1, line2)], epochs)
+    assert states.shape == [3, 5, 6]
+    assert memoryview(states).shape == (3, 5, 6)
+    expected = propagator.propagate_to(epochs[3])
+    assert states[0][3][:3] == pytest.approx(expected.position, abs=1e-9)
+    assert states.to_list()[2] == states.to_list()[0]
+    single = orbit_core.propagate_j2(sat, 0.02 * 86400.0)
+    assert states[1][2][3:] == pytest.approx(single.velocity, abs=1e-9)
+
+    as_epochs = orbit_core.propagate_batch([propagator], [orbit_core.Epoch(jd) for jd in epochs])
+    assert as_epochs.to_list()[0] == states.to_list()[0]
+
+    with pytest.raises(TypeError):
+        orbit_core.propagate_batch([propagator], "tomorrow")
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_conjunction_states_at_tca():
+    """Test that screened conjunctions carry both states at TCA and the approach geometry."""
+    start = 2460000.5
+    props = [
+        orbit_core.Sgp4Propagator.from_elements(
+            orbit_core.MeanElements(norad_id, start, 0.0, 90.0, raan, 0.0001, 0.0, 0.0, 15.0)
+        )
+        for norad_id, raan in [(1, 0.0), (2, 90.0)]
+    ]
+    events = orbit_core.screen_window(props, start, start + 0.1, 60.0, 50.0)
+    assert events
+    event = events[0]
+    assert abs(event.time_to_tca_s - (event.tca_jd - start) * 86400.0) < 1e-3
+    assert abs(event.approach_angle_deg - 90.0) < 1.0
+
+    primary, secondary = event.states_at_tca()
+    # a Julian date resolves ~40 us, a few decimetres at LEO speed
+    expected = props[0].propagate_to(event.tca_jd)
+    assert primary.distance_to(expected) < 1e-3
+    assert list(event.state_2[:3]) == secondary.position
+    assert abs(primary.distance_to(secondary) - event.distance_km) < 1e-9
+    assert "X_DOT" in orbit_core.conjunction_to_cdm(event)
+
+    restored = orbit_core.Conjunction.from_json(event.to_json())
+    assert restored.state_1 == pytest.approx(event.state_1, rel=1e-15)
+    assert restored.time_to_tca_s == pytest.approx(event.time_to_tca_s, rel=1e-15)
+    bare = orbit_core.Conjunction(1, 2, 1.0, 10.0)
+    assert bare.states_at_tca() is None and bare.approach_angle_deg is None
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_ground_station_doppler(iss_tle):
+    """Test per-step range-rate and Doppler shift over a pass."""
+    line1, line2 = iss_tle
+    iss = orbit_core.Sgp4Propagator(line1, line2)
+    station = orbit_core.GroundStation(41.0, 29.0, 0.1)
+    p = station.passes(iss, iss.epoch_jd, iss.epoch_jd + 1.0, min_elevation=10.0)[0]
+
+    frequency = 437.8e6
+    table = station.contact_table(iss, p.aos_jd, p.los_jd, step=5.0, frequency_hz=frequency, min_elevation=10.0)
+    rates = table["range_rate_km_s"].to_list()
+    doppler = table["doppler_hz"].to_list()
+    assert len(rates) == len(table["jd"]) > 10
+    # approaching at AOS, receding at LOS; the shift follows with the opposite sign
+    assert rates[0] < -1.0 and rates[-1] > 1.0
+    assert doppler[0] > 0.0 > doppler[-1]
+    assert abs(doppler[0] + frequency * rates[0] / 299792.458) < 1e-6
+    assert max(abs(d) for d in doppler) < 11e3
+
+    jd = table["jd"][3]
+    rng, rate = station.range_rate(iss.propagate_to(jd), jd)
+    assert abs(rng - table["range_km"][3]) < 1e-3 and abs(rate - rates[3]) < 1e-6
+    assert "doppler_hz" not in station.contact_table(iss, p.aos_jd, p.los_jd)
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_sensor_access(iss_tle):
+    """Test FOV access of a zenith-pointing ground sensor against passes."""
+    line1, line2 = iss_tle
+    iss = orbit_core.Sgp4Propagator(line1, line2)
+    station = orbit_core.GroundStation(41.0, 29.0, 0.1)
+    start, end = iss.epoch_jd, iss.epoch_jd + 1.0
+
+    # a 60 deg cone around zenith sees exactly the parts of passes above 30 deg
+    sensor = orbit_core.Sensor.ground(station, 0.0, 90.0, half_angle=60.0, name="radar")
+    passes = station.passes(iss, start, end, min_elevation=30.0)
+    accesses = orbit_core.sensor_access(sensor, [iss, (line1, line2)], start, end, step=10.0)
+    assert len(accesses) == 2 * len(passes) > 0
+    for access, p in zip(accesses[::2], passes):
+        assert access.norad_id == 25544
+        assert abs(access.start_jd - p.aos_jd) * 86400.0 < 1.0
+        assert abs(access.end_jd - p.los_jd) * 86400.0 < 1.0
+        assert access.min_range_km < 800.0
+        assert sensor.in_view(iss, 0.5 * (access.start_jd + access.end_jd))
+
+    with pytest.raises(ValueError):
+        orbit_core.Sensor.ground(station, 0.0, 90.0)
+    with pytest.raises(ValueError):
+        orbit_core.Sensor.space(iss, half_angle=10.0, rectangle=(5.0, 5.0))
+    narrow = orbit_core.Sensor.space(iss, rectangle=(1.0, 1.0), max_range_km=100.0)
+    assert orbit_core.sensor_access(narrow, [iss], start, end) == []
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_crosslink_visibility():
+    """Test Earth occlusion of inter-satellite links and the constellation visibility array."""
+    epoch = orbit_core.Epoch.from_iso("2024-03-01T12:00:00Z")
+    speed = math.sqrt(398600.4418 / 7000.0)
+
+    def ring(norad_id, phase_deg):
+        phase = math.radians(phase_deg)
+        return orbit_core.Satellite(
+            norad_id,
+            [7000.0 * math.cos(phase), 7000.0 * math.sin(phase), 0.0],
+            [-speed * math.sin(phase), speed * math.cos(phase), 0.0],
+            epoch=epoch,
+        )
+
+    sats = [ring(1, 0.0), ring(2, 30.0), ring(3, 180.0)]
+    # the 30 deg chord clears the Earth by 7000 cos(15 deg) - R = 383 km
+    assert orbit_core.line_of_sight(sats[0], sats[1])
+    assert orbit_core.line_of_sight(sats[0], sats[1], grazing_altitude_km=350.0)
+    assert not orbit_core.line_of_sight(sats[0], sats[1], grazing_altitude_km=400.0)
+    assert not orbit_core.line_of_sight(sats[0], sats[2])
+
+    start = epoch.jd()
+    table = orbit_core.crosslink_visibility(sats, start, start + 1.0 / 24.0, step=600.0)
+    visible = table["visible"].to_list()
+    assert len(visible) == len(table["jd"]) == 7
+    for step in visible:
+        assert step == [[0.0, 1.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, 0.0]]
+    limited = orbit_core.crosslink_visibility(sats, start, start + 0.01, max_range_km=3000.0)
+    assert limited["visible"][0] == [[0.0, 0.0, 0.0]] * 3
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_constellation_pairs():
+    """Test flagging, excluding and isolating same-constellation conjunctions."""
+    import pickle
+
+    def sat(norad_id, x, constellation):
+        return orbit_core.Satellite(norad_id, [x, 0.0, 0.0], [0.0, 7.5, 0.0], constellation=constellation)
+
+    sats = [sat(1, 7000.0, "STARLINK"), sat(2, 7001.0, "Starlink"), sat(3, 7002.0, "ONEWEB"), sat(4, 7003.0, None)]
+    everything = orbit_core.find_conjunctions(sats, 10.0)
+    assert len(everything) == 6
+    assert [(c.norad_id_1, c.norad_id_2) for c in everything if c.intra_constellation] == [(1, 2)]
+
+    excluded = orbit_core.find_conjunctions(sats, 10.0, constellation_pairs="exclude")
+    only = orbit_core.find_conjunctions(sats, 10.0, constellation_pairs="only")
+    assert len(excluded) == 5 and not any(c.intra_constellation for c in excluded)
+    assert [(c.norad_id_1, c.norad_id_2) for c in only] == [(1, 2)]
+
+    against = orbit_core.screen_against_catalog(sats[:1], sats, 10.0, constellation_pairs="exclude")
+    assert [c.norad_id_2 for c in against] == [3, 4]
+    catalog = orbit_core.Catalog(sats)
+    assert catalog.find_conjunctions(10.0, constellation_pairs="only") == only
+
+    assert pickle.loads(pickle.dumps(sats[0])).constellation == "STARLINK"
+    assert orbit_core.Conjunction.from_json(only[0].to_json()).intra_constellation
+    with pytest.raises(ValueError):
+        orbit_core.find_conjunctions(sats, 10.0, constellation_pairs="separate")
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_screening_pair_selection():
+    """Test excluded NORAD id pairs and watch lists in snapshot and window screening."""
+    sats = [orbit_core.Satellite(i, [7000.0 + i, 0.0, 0.0], [0.0, 7.5, 0.0]) for i in (1, 2, 3, 4)]
+    ids = lambda found: [(c.norad_id_1, c.norad_id_2) for c in found]
+
+    assert len(orbit_core.find_conjunctions(sats, 10.0)) == 6
+    assert ids(orbit_core.find_conjunctions(sats, 10.0, exclude_pairs=[(2, 1), (3, 4)])) == [(1, 3), (1, 4), (2, 3), (2, 4)]
+    assert ids(orbit_core.find_conjunctions(sats, 10.0, watch_list=[4])) == [(1, 4), (2, 4), (3, 4)]
+    assert ids(orbit_core.Catalog(sats).find_conjunctions(10.0, watch_list=[4], exclude_pairs=[(4, 1)])) == [(2, 4), (3, 4)]
+    assert ids(orbit_core.screen_against_catalog(sats[:1], sats, 10.0, exclude_pairs=[(1, 3)])) == [(1, 2), (1, 4)]
+
+    # two polar orbits in planes 90 degrees apart meet over the poles
+    start = 2460000.5
+    props = [
+        orbit_core.Sgp4Propagator.from_elements(
+            orbit_core.MeanElements(norad_id, start, 0.0, 90.0, raan, 0.0001, 0.0, 0.0, 15.0)
+        )
+        for norad_id, raan in [(1, 0.0), (2, 90.0)]
+    ]
+    end = start + 0.1
+    assert orbit_core.screen_window(props, start, end, 60.0, 50.0)
+    assert orbit_core.screen_window(props, start, end, 60.0, 50.0, exclude_pairs=[(2, 1)]) == []
+    assert orbit_core.screen_window(props, start, end, 60.0, 50.0, watch_list=[2])
+    pipeline = orbit_core.ScreeningPipeline(50.0).set_watch_list([3])
+    assert pipeline.run(props, start, end, 60.0) == []
+    assert pipeline.last_stats[0] == ("pairs", 0)
+    assert pipeline.set_watch_list(None).run(props, start, end, 60.0)
+    assert pipeline.exclude_pairs([(1, 2)]).run(props, start, end, 60.0) == []
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_relative_trajectory():
+    """Test the deputy's LVLH and RTN offsets from a chief on the same orbit."""
+    epoch = orbit_core.Epoch.from_iso("2024-03-01T12:00:00Z")
+    speed = math.sqrt(398600.4418 / 7000.0)
+
+    def on_orbit(norad_id, phase_deg):
+        phase = math.radians(phase_deg)
+        return orbit_core.Satellite(
+            norad_id,
+            [7000.0 * math.cos(phase), 7000.0 * math.sin(phase), 0.0],
+            [-speed * math.sin(phase), speed * math.cos(phase), 0.0],
+            epoch=epoch,
+        )
+
+    # the deputy trails by 0.1 deg: behind on V-bar and slightly below the chief's horizon
+    # (J2 short-period terms move it by tens of metres)
+    chief, deputy = on_orbit(1, 0.1), on_orbit(2, 0.0)
+    start = epoch.jd()
+    table = orbit_core.relative_trajectory(chief, deputy, start, start + 0.05, step=120.0)
+    chord = 2.0 * 7000.0 * math.sin(math.radians(0.05))
+    assert len(table["jd"]) == len(table["position"]) == 37
+    for x, y, z in table["position"].to_list():
+        assert x == pytest.approx(-chord * math.cos(math.radians(0.05)), abs=0.05)
+        assert abs(y) < 1e-6
+        assert z == pytest.approx(chord * math.sin(math.radians(0.05)), abs=1e-4)
+    assert table["range_km"][0] == pytest.approx(chord, abs=1e-6)
+    assert max(abs(v) for row in table["velocity"].to_list() for v in row) < 1e-4
+
+    rtn = orbit_core.relative_trajectory(chief, deputy, start, start + 0.05, step=120.0, frame="rtn")
+    assert rtn["position"][0][1] == pytest.approx(table["position"][0][0])
+    assert rtn["position"][0][0] == pytest.approx(-table["position"][0][2])
+    with pytest.raises(ValueError):
+        orbit_core.relative_trajectory(chief, deputy, start, start + 0.05, frame="ric")
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_moid():
+    """Test the minimum orbital intersection distance from elements and state vectors."""
+    # circular LEO shell vs an inclined ellipse whose perigee sits 10 km outside it on the node line
+    circle = orbit_core.EquinoctialElements(7000.0, 0.0, 0.0, 0.0, 0.0, 0.0)
+    ellipse = orbit_core.Satellite.from_keplerian(7510.0, 500.0 / 7510.0, 30.0, 40.0, 0.0, 123.0, 2).to_keplerian()
+    distance, p1, p2 = orbit_core.moid(circle, ellipse)
+    assert distance == pytest.approx(10.0, abs=1e-6)
+    assert math.dist(p1, p2) == pytest.approx(distance, abs=1e-9)
+    assert math.hypot(*p1) == pytest.approx(7000.0)
+
+    # state vectors use their osculating orbits; the MOID never exceeds the current separation
+    sat1 = orbit_core.Satellite.from_keplerian(7000.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1)
+    sat2 = orbit_core.Satellite.from_keplerian(7100.0, 0.05, 0.0, 90.0, 0.0, 10.0, 2)
+    assert orbit_core.moid(sat1, sat2)[0] < 1e-6 < sat1.distance_to(sat2)
+    assert orbit_core.moid(sat1, circle)[0] < 1e-6
+    with pytest.raises(ValueError):
+        orbit_core.moid(circle, orbit_core.Satellite(3, [7000.0, 0.0, 0.0], [0.0, 12.0, 0.0]))
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_spatial_density():
+    """Test catalog spatial density and annual collision estimates."""
+    catalog = []
+    for k in range(40):
+        sat = orbit_core.Satellite.from_keplerian(6928.137, 0.0, 40.0 + 3.0 * k, 9.0 * k, 0.0, 17.0 * k, k + 1)
+        sat.hard_body_radius = 0.005
+        catalog.append(sat)
+    density = orbit_core.SpatialDensity(catalog, altitude_step_km=50.0, latitude_step_deg=10.0)
+    assert density.objects == 40
+    assert density.density(560.0, 20.0) > 0.0
+    assert density.density(1200.0, 20.0) == 0.0
+
+    table = density.table()
+    assert len(table["altitude_km"]) == 36 and len(table["latitude_deg"]) == 18
+    assert table["altitude_km"][0] == pytest.approx(225.0)
+    assert table["density"].shape == [36, 18]
+
+    # a catalog member does not meet itself; an outsider on a similar orbit meets all 40
+    member, outsider = catalog[5], orbit_core.Satellite.from_keplerian(6928.137, 0.0, 55.0, 45.0, 0.0, 85.0, 999)
+    outsider.hard_body_radius = 0.005
+    own, other = density.estimate([member, outsider], years=5.0)
+    assert own.norad_id == member.norad_id and 0.0 < own.collision_rate < other.collision_rate
+    assert other.flux > 0.0
+    assert other.probability == pytest.approx(1.0 - math.exp(-5.0 * other.collision_rate))
+
+    lonely = orbit_core.SpatialDensity([member])
+    assert lonely.estimate([member])[0].collision_rate == 0.0
+    with pytest.raises(ValueError):
+        orbit_core.SpatialDensity(catalog, min_altitude_km=800.0, max_altitude_km=500.0)
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_breakup():
+    """Test NASA standard breakup model fragment clouds."""
+    parent = orbit_core.Satellite.from_keplerian(7078.137, 0.001, 98.0, 10.0, 0.0, 0.0, 43000)
+    parent.name = "SAT"
+    cloud = orbit_core.breakup(parent, first_id=950000, seed=3)
+    fragments = cloud["satellites"]
+    # N(>10 cm) = 6 Lc^-1.6 for an explosion, less the single largest-fragment expectation
+    assert abs(len(fragments) - (6.0 * 0.1 ** -1.6 - 1.0)) < 1.0
+    assert [f.norad_id for f in fragments[:2]] == [950000, 950001]
+    assert fragments[0].name == "SAT DEB" and fragments[0].object_type == "DEBRIS"
+    assert fragments[0].position == parent.position
+    lengths = list(cloud["characteristic_length_m"])
+    assert min(lengths) >= 0.1 and len(lengths) == len(fragments)
+    dv = cloud["delta_v"]
+    assert dv.shape == [len(fragments), 3]
+    speed = [b - a for a, b in zip(parent.velocity, fragments[0].velocity)]
+    assert speed == pytest.approx(list(dv[0]), abs=1e-12)
+    assert orbit_core.breakup(parent, seed=3)["satellites"][5].velocity == fragments[5].velocity
+
+    collision = orbit_core.breakup(
+        parent, "collision", mass_kg=1000.0, projectile_mass_kg=10.0, impact_speed_km_s=10.0, min_length_m=0.2
+    )
+    assert len(collision["satellites"]) > 200
+    with pytest.raises(ValueError):
+        orbit_core.breakup(parent, "collision", mass_kg=1000.0)
+    with pytest.raises(ValueError):
+        orbit_core.breakup(parent, "implosion")
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_gabbard_and_fragment_evolution():
+    """Test Gabbard data and drag evolution of a fragment cloud."""
+    parent = orbit_core.Satellite.from_keplerian(6978.137, 0.0005, 98.0, 10.0, 0.0, 0.0, 43000)
+    parent.epoch = orbit_core.Epoch.from_iso("2024-03-01T00:00:00Z")
+    cloud = orbit_core.breakup(parent, seed=1)
+    fragments = cloud["satellites"]
+
+    gabbard = orbit_core.gabbard_diagram(fragments)
+    assert list(gabbard["norad_id"]) == [f.norad_id for f in fragments]
+    # every fragment passes through the breakup point, so the "X" crosses at the parent altitude
+    for period, apogee, perigee in zip(gabbard["period_min"], gabbard["apogee_km"], gabbard["perigee_km"]):
+        assert perigee - 1.0 <= math.hypot(*parent.position) - 6378.137 <= apogee + 1.0
+        assert period > 85.0
+    circular = orbit_core.Satellite.from_keplerian(6878.137, 0.0, 51.6, 0.0, 0.0, 0.0, 1)
+    period = 2.0 * math.pi * math.sqrt(6878.137**3 / 398600.4418) / 60.0
+    assert orbit_core.gabbard_diagram([circular])["period_min"][0] == pytest.approx(period)
+
+    coefficients = [2.2 * am for am in cloud["area_to_mass"]]
+    evolution = orbit_core.fragment_evolution(fragments, 60.0, step_days=10.0, ballistic_coefficients=coefficients)
+    assert list(evolution["days"]) == [0.0, 10.0, 20.0, 30.0, 40.0, 50.0, 60.0]
+    apogee = evolution["apogee_km"]
+    assert apogee.shape == [7, len(fragments)]
+    # fragments thrown onto orbits with a perigee below 120 km re-enter at once
+    for start, initial, perigee in zip(apogee[0], gabbard["apogee_km"], gabbard["perigee_km"]):
+        assert math.isnan(start) if perigee <= 120.0 else start == pytest.approx(initial)
+    # the highest area-to-mass fragment decays the most or re-enters
+    lightest = max(range(len(fragments)), key=lambda k: coefficients[k])
+    final = apogee[6][lightest]
+    assert math.isnan(final) or final < apogee[0][lightest] - 1.0
+
+    with pytest.raises(ValueError):
+        orbit_core.fragment_evolution(fragments, 10.0)
+    # element sets default to the ballistic coefficient implied by B*
+    elements = orbit_core.MeanElements(3, 2460000.5, 1.0e-3, 51.6, 0.0, 0.0001, 0.0, 0.0, 16.0)
+    decayed = orbit_core.fragment_evolution([elements], 30.0, step_days=30.0)["perigee_km"]
+    assert decayed.shape == [2, 1] and (math.isnan(decayed[1][0]) or decayed[1][0] < decayed[0][0])
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_initial_orbit_determination():
+    """Test Gibbs and Herrick-Gibbs orbit estimates from three positions."""
+    truth = orbit_core.Satellite.from_keplerian(7200.0, 0.02, 63.0, 20.0, 40.0, 10.0, 7)
+    jd = 2460000.5
+    later = [orbit_core.propagate_two_body(truth, t) for t in (0.0, 900.0, 1800.0)]
+    positions = [s.position for s in later]
+    estimate = orbit_core.gibbs(*positions, jd=jd, norad_id=7)
+    assert estimate.velocity == pytest.approx(later[1].velocity, abs=1e-6)
+    assert estimate.epoch.jd() == pytest.approx(jd) and estimate.norad_id == 7
+    assert orbit_core.gibbs(*positions).epoch is None
+
+    # a radar track: three positions ten seconds apart
+    track = [orbit_core.propagate_two_body(truth, t) for t in (0.0, 10.0, 20.0)]
+    jds = [jd, jd + 10.0 / 86400.0, jd + 20.0 / 86400.0]
+    estimate = orbit_core.herrick_gibbs(*[s.position for s in track], *jds)
+    assert estimate.velocity == pytest.approx(track[1].velocity, abs=1e-5)
+    chosen, method = orbit_core.initial_orbit([s.position for s in track], jds)
+    assert method == "herrick-gibbs" and chosen.velocity == estimate.velocity
+    assert orbit_core.initial_orbit(positions, [jd, jd + 900.0 / 86400.0, jd + 1800.0 / 86400.0])[1] == "gibbs"
+
+    with pytest.raises(ValueError):
+        orbit_core.gibbs(positions[0], positions[1], [positions[2][0], positions[2][1], positions[2][2] + 500.0])
+    with pytest.raises(ValueError):
+        orbit_core.herrick_gibbs(*[s.position for s in track], jds[1], jds[0], jds[2])
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_angles_only_orbit():
+    """Test Gauss angles-only orbit determination from ground-station RA/Dec."""
+    station = orbit_core.GroundStation(41.0, 29.0, 0.1)
+    jd0 = 2460000.5
+    truth = orbit_core.Satellite.from_keplerian(7000.0, 0.001, 53.0, 100.0, 0.0, 30.0, 5)
+    observations, states = [], []
+    for seconds in (0.0, 120.0, 240.0):
+        jd = jd0 + seconds / 86400.0
+        state = orbit_core.propagate_two_body(truth, seconds)
+        site, _ = orbit_core.ecef_to_teme(orbit_core.geodetic_to_ecef(41.0, 29.0, 0.1), [0.0, 0.0, 0.0], jd)
+        rho = [p - s for p, s in zip(state.position, site)]
+        ra = math.degrees(math.atan2(rho[1], rho[0]))
+        dec = math.degrees(math.asin(rho[2] / math.hypot(*rho)))
+        observations.append((jd, ra, dec))
+        states.append(state)
+
+    estimate = orbit_core.angles_only_orbit(station, observations, norad_id=5)
+    assert estimate.position == pytest.approx(states[1].position, abs=1e-2)
+    assert estimate.velocity == pytest.approx(states[1].velocity, abs=1e-5)
+    assert estimate.epoch.jd() == pytest.approx(observations[1][0], abs=1e-9)
+    rough = orbit_core.angles_only_orbit(station, observations, refine=False)
+    assert math.dist(rough.position, states[1].position) < 500.0
+    with pytest.raises(ValueError):
+        orbit_core.angles_only_orbit(station, [observations[1], observations[0], observations[2]])
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_fit_orbit():
+    """Test batch least-squares orbit fits to position fixes and ephemeris states."""
+    jd0 = 2460000.5
+    truth = orbit_core.Satellite(7, [6878.0, 0.0, 0.0], [0.0, 5.0, 5.8], epoch=orbit_core.Epoch(jd0))
+    states = [orbit_core.propagate_j2(truth, k * 300.0) for k in range(12)]
+    fixes = [(jd0 + k * 300.0 / 86400.0, state.position) for k, state in enumerate(states)]
+
+    fit = orbit_core.fit_orbit(fixes, norad_id=7, sigma_position_km=0.01)
+    assert fit.converged
+    assert fit.satellite.norad_id == 7
+    assert fit.satellite.epoch.jd() == pytest.approx(jd0, abs=1e-9)
+    # Julian-date time tags resolve to some microseconds, i.e. centimetres along track
+    assert fit.satellite.position == pytest.approx(truth.position, abs=1e-3)
+    assert fit.satellite.velocity == pytest.approx(truth.velocity, abs=1e-6)
+    assert max(fit.residuals) < 1e-3
+    cov = fit.satellite.covariance
+    assert len(fit.covariance) == 6 and 0.0 < cov[0][0] < 1e-4
+
+    # the fitted covariance feeds the Pc functions directly
+    other = orbit_core.Satellite(8, [6878.0, 0.05, 0.0], [0.0, 5.0, -5.8], covariance=cov)
+    pc = orbit_core.collision_probability(fit.satellite, cov, other, cov, 0.02)
+    assert 0.0 < pc <= 1.0
+
+    ephemeris_fit = orbit_core.fit_orbit(states[1:], initial=states[0], sigma_position_km=0.01)
+    assert ephemeris_fit.satellite.norad_id == 7
+    assert ephemeris_fit.satellite.position == pytest.approx(truth.position, abs=1e-3)
+    with pytest.raises(ValueError):
+        orbit_core.fit_orbit(fixes[:2])
+    with pytest.raises(ValueError):
+        orbit_core.fit_orbit(fixes, estimate_drag=True)
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_ekf_tracker():
+    """Test sequential EKF updates from ground-station range and angle measurements."""
+    station = orbit_core.GroundStation(0.0, 20.0, 0.0)
+    jd0 = 2460000.5
+    truth = orbit_core.Satellite(9, [7000.0, 0.0, 0.0], [0.0, 5.2, 5.2], epoch=orbit_core.Epoch(jd0))
+    cov = [[9.0 if i == j and i < 3 else (1e-5 if i == j else 0.0) for j in range(6)] for i in range(6)]
+    start = orbit_core.Satellite(9, [7002.0, -1.5, 1.0], [0.002, 5.199, 5.2015], covariance=cov, epoch=orbit_core.Epoch(jd0))
+    tracker = orbit_core.EkfTracker(start, gate=25.0)
+
+    for k in range(1, 61):
+        jd = jd0 + k * 10.0 / 86400.0
+        state = orbit_core.propagate_j2(truth, k * 10.0)
+        azimuth, elevation, distance = station.look_angles(state, jd)
+        _, rate = station.range_rate(state, jd)
+        result = tracker.update(
+            station, jd, {"range": (distance, 0.01), "range_rate": (rate, 1e-5), "azimuth": (azimuth, 0.01), "elevation": (elevation, 0.01)}
+        )
+        assert result.accepted and len(result.residuals) == 4
+
+    estimate = tracker.satellite
+    assert tracker.updates == 60 and tracker.rejected == 0
+    assert estimate.epoch.jd() == pytest.approx(jd0 + 600.0 / 86400.0, abs=1e-9)
+    assert math.dist(estimate.position, state.position) < 0.05
+    assert estimate.covariance[0][0] < 9.0
+
+    outlier = tracker.update(station, jd + 1e-4, {"range": (distance + 100.0, 0.01)})
+    assert not outlier.accepted and tracker.rejected == 1
+    with pytest.raises(ValueError):
+        tracker.update(station, jd, {"phase": (0.0, 1.0)})
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_simulate_observations():
+    """Test simulated ground-station measurements with and without noise."""
+    jd0 = 2460000.5
+    sat = orbit_core.Satellite(9, [7000.0, 0.0, 0.0], [0.0, 5.2, 5.2], epoch=orbit_core.Epoch(jd0))
+    end = jd0 + 0.02
+    track = orbit_core.ground_track(sat, jd0, end, step=600.0)
+    station = orbit_core.GroundStation(track["lat_deg"][1], track["lon_deg"][1], 0.0)  # overflown 10 min in
+
+    clean = orbit_core.simulate_observations(station, sat, jd0, end, step=30.0, min_elevation=10.0)
+    assert sorted(clean) == ["azimuth", "elevation", "jd", "range", "range_rate"]
+    assert len(clean["jd"]) > 0 and min(clean["elevation"]) >= 10.0
+    state = orbit_core.propagate_j2(sat, (clean["jd"][0] - jd0) * 86400.0)
+    azimuth, elevation, distance = station.look_angles(state, clean["jd"][0])
+    assert clean["range"][0] == pytest.approx(distance, abs=1e-6)
+    assert clean["azimuth"][0] == pytest.approx(azimuth, abs=1e-6)
+
+    noise = {"range": 0.05, "ra": 0.01, "dec": 0.01}
+    noisy = orbit_core.simulate_observations(station, sat, jd0, end, step=30.0, measurements=["range", "ra", "dec"], noise=noise, min_elevation=10.0, seed=3)
+    again = orbit_core.simulate_observations(station, sat, jd0, end, step=30.0, measurements=["range", "ra", "dec"], noise=noise, min_elevation=10.0, seed=3)
+    assert list(noisy["range"]) == list(again["range"])
+    errors = [a - b for a, b in zip(noisy["range"], clean["range"])]
+    assert 0.0 < max(abs(e) for e in errors) < 0.5
+    assert all(0.0 <= ra < 360.0 for ra in noisy["ra"])
+    with pytest.raises(ValueError):
+        orbit_core.simulate_observations(station, sat, jd0, end, measurements=["phase"])
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_unscented_covariance_propagation():
+    """Test sigma-point covariance propagation alongside the linear STM mapping."""
+    perigee, apogee = 6678.0, 42164.0
+    a = (perigee + apogee) / 2.0
+    speed = math.sqrt(398600.4418 * (2.0 / perigee - 1.0 / a))
+    sigmas = [5.0, 5.0, 5.0, 5e-3, 5e-3, 5e-3]
+    cov = [[sigmas[i] ** 2 if i == j else 0.0 for j in range(6)] for i in range(6)]
+    sat = orbit_core.Satellite(1, [perigee, 0.0, 0.0], [0.0, speed, 0.0], covariance=cov)
+    dt = 0.98 * 2.0 * math.pi * math.sqrt(a**3 / 398600.4418)
+
+    linear = orbit_core.propagate_two_body(sat, dt)
+    unscented = orbit_core.propagate_two_body(sat, dt, covariance_method="unscented")
+    assert unscented.position == pytest.approx(linear.position, abs=1e-3)
+    # the linear mapping overstates the radial spread of this banana-shaped cloud
+    assert unscented.covariance[0][0] < 0.9 * linear.covariance[0][0]
+
+    small = orbit_core.Satellite(1, [7000.0, 0.0, 0.0], [0.0, 7.5, 0.0], covariance=[[1e-6 if i == j else 0.0 for j in range(6)] for i in range(6)])
+    both = orbit_core.propagate_batch([small, sat], dt, covariance_method=["stm", "unscented"])
+    assert both[0].covariance[0][0] == pytest.approx(orbit_core.propagate_j2(small, dt).covariance[0][0], rel=1e-3)
+    assert both[1].covariance[0][0] < linear.covariance[0][0]
+    with pytest.raises(ValueError):
+        orbit_core.propagate_j2(sat, 60.0, covariance_method="particles")
+    with pytest.raises(ValueError):
+        orbit_core.propagate_batch([small, sat], 60.0, covariance_method=["stm"])
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_pc_sensitivity():
+    """Test Pc sweeps over covariance scale and hard-body radius."""
+    primary = orbit_core.Satellite(1, [7000.0, 0.0, 0.0], [0.0, 7.5, 0.0])
+    secondary = orbit_core.Satellite(2, [7000.5, 0.0, 0.0], [0.0, 0.0, 7.5])
+    cov = [[0.01 if i == j else 0.0 for j in range(3)] for i in range(3)]
+
+    sweep = orbit_core.pc_sensitivity(primary, cov, secondary, cov, 0.02)
+    assert len(sweep["scale"]) == 17
+    assert sweep["scale"][0] == pytest.approx(0.25) and sweep["scale"][-1] == pytest.approx(4.0)
+    assert sweep["pc"].shape == [17, 1]
+    nominal = orbit_core.collision_probability(primary, cov, secondary, cov, 0.02)
+    assert sweep["pc"][8][0] == pytest.approx(nominal, rel=1e-9)
+    # a 500 m miss with 100 m sigmas: Pc peaks at a larger covariance
+    assert sweep["max_pc"][0] > nominal and sweep["max_pc_scale"][0] > 1.0
+    assert sweep["dilution_region"] == [False]
+
+    close = orbit_core.Satellite(2, [7000.01, 0.0, 0.0], [0.0, 0.0, 7.5])
+    radii = orbit_core.pc_sensitivity(primary, cov, close, cov, 0.02, scales=[0.5, 1.0, 2.0], radii=[0.01, 0.02, 0.05])
+    assert radii["pc"].shape == [3, 3]
+    assert radii["dilution_region"] == [True, True, True]
+    assert radii["max_pc_scale"][0] == 0.5
+    with pytest.raises(ValueError):
+        orbit_core.pc_sensitivity(primary, cov, secondary, cov, 0.02, scales=[0.0])
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_max_collision_probability():
+    """Test the worst-case Pc over covariance scalings."""
+    primary = orbit_core.Satellite(1, [7000.0, 0.0, 0.0], [0.0, 7.5, 0.0])
+    secondary = orbit_core.Satellite(2, [7000.5, 0.0, 0.0], [0.0, 0.0, 7.5])
+    cov = [[0.01 if i == j else 0.0 for j in range(3)] for i in range(3)]
+
+    worst = orbit_core.max_collision_probability(primary, cov, secondary, cov, 0.005)
+    assert worst["pc"] == pytest.approx(orbit_core.collision_probability(primary, cov, secondary, cov, 0.005))
+    # isotropic combined sigma^2 = 0.02 km^2: worst case R^2 / (e d^2) at sigma^2 = d^2 / 2
+    assert worst["max_pc"] == pytest.approx(0.005 ** 2 / (math.e * 0.25), rel=1e-3)
+    assert worst["scale"] == pytest.approx(0.125 / 0.02, rel=1e-2)
+    assert worst["max_pc"] > worst["pc"] and not worst["dilution_region"]
+
+    close = orbit_core.Satellite(2, [7000.02, 0.0, 0.0], [0.0, 0.0, 7.5])
+    diluted = orbit_core.max_collision_probability(primary, cov, close, cov, 0.005, method="patera")
+    assert diluted["dilution_region"] and diluted["scale"] < 1.0
+    assert diluted["max_pc"] >= diluted["pc"]
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_screen_ephemerides(tmp_path):
+    """Test screening an operator OEM in EME2000 against TLE propagators."""
+    start = 2460000.5
+    props = [
+        orbit_core.Sgp4Propagator.from_elements(
+            orbit_core.MeanElements(norad_id, start, 0.0, 90.0, raan, 0.0001, 0.0, anomaly, 15.0)
+        )
+        for norad_id, raan, anomaly in [(1, 0.0, 0.0), (2, 90.0, 0.0), (3, 45.0, 180.0)]
+    ]
+    lines = [
+        "CCSDS_OEM_VERS = 2.0",
+        "CREATION_DATE = 2023-02-24T12:00:00",
+        "ORIGINATOR = OPERATOR",
+        "META_START",
+        "OBJECT_NAME = PRIMARY",
+        "OBJECT_ID = 1",
+        "CENTER_NAME = EARTH",
+        "REF_FRAME = EME2000",
+        "TIME_SYSTEM = UTC",
+        "META_STOP",
+    ]
+    epoch = orbit_core.Epoch(start)
+    for k in range(145):
+        jd = start + 60.0 * k / 86400.0
+        sat = props[0].propagate_to(jd)
+        ecef = orbit_core.teme_to_ecef(sat.position, sat.velocity, jd, 0.0, 0.0)
+        position, velocity = orbit_core.ecef_to_gcrf(*ecef, jd, 0.0, 0.0)
+        values = " ".join(f"{x:.9f}" for x in position + velocity)
+        lines.append(f"{(epoch + 60.0 * k).iso()} {values}")
+    path = tmp_path / "primary.oem"
+    path.write_text("\n".join(lines))
+
+    # the catalogue still holds the primary's own TLE, which the OEM replaces
+    found = orbit_core.screen_ephemerides([str(path)], props, 50.0)
+    expected = [e for e in orbit_core.screen_window(props, start, start + 0.1, 60.0, 50.0) if 1 in (e.norad_id_1, e.norad_id_2)]
+    assert expected and len(found) == len(expected)
+    assert all(e.norad_id_1 == 1 and e.norad_id_2 != 1 for e in found)
+    for hit in found:
+        match = min(expected, key=lambda e: abs(e.tca_jd - hit.tca_jd))
+        assert abs(match.tca_jd - hit.tca_jd) * 86400.0 < 0.1
+        assert hit.distance_km == pytest.approx(match.distance_km, abs=1e-3)
+
+    with pytest.raises(IOError):
+        orbit_core.screen_ephemerides([str(tmp_path / "missing.oem")], props, 50.0)
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_detect_maneuvers():
+    """Test flagging a burn in a daily TLE history."""
+    start = 2460000.5
+    current = orbit_core.MeanElements(7, start, 0.0, 51.6, 30.0, 0.001, 90.0, 0.0, 15.5)
+    history = [current]
+    for day in range(1, 10):
+        sat = orbit_core.Sgp4Propagator.from_elements(current).propagate_to(start + day)
+        velocity = sat.velocity
+        if day == 5:
+            # 2 m/s prograde
+            speed = math.sqrt(sum(v * v for v in velocity))
+            velocity = [v * (1.0 + 2e-3 / speed) for v in velocity]
+        state = orbit_core.Satellite(7, sat.position, velocity)
+        current = orbit_core.osculating_to_mean(state, epoch=orbit_core.Epoch(start + day))
+        history.append(current)
+
+    found = orbit_core.detect_maneuvers(history[::-1])
+    assert len(found) == 1
+    burn = found[0]
+    assert (burn.before_jd, burn.after_jd) == (start + 4, start + 5)
+    assert burn.kind == "in-plane" and burn.sma_change_km > 3.0
+    assert burn.delta_v_km_s == pytest.approx(2e-3, rel=0.2)
+
+    batch = orbit_core.detect_maneuvers_batch([history, history[:5], [orbit_core.Sgp4Propagator.from_elements(h) for h in history]])
+    assert [len(found) for found in batch] == [1, 0, 1]
+    assert orbit_core.detect_maneuvers(history, sma_threshold_km=10.0) == []
+    with pytest.raises(ValueError):
+        orbit_core.detect_maneuvers(history, robust_sigmas=-1.0)
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_check_tle_catalog(iss_tle):
+    """Test the TLE hygiene report."""
+    def elements(norad_id, epoch, bstar=1e-4, mean_motion=15.5):
+        return orbit_core.MeanElements(norad_id, epoch, bstar, 51.6, 30.0, 0.001, 90.0, 0.0, mean_motion)
+
+    line1, line2 = iss_tle
+    iss = orbit_core.Sgp4Propagator(line1, line2)
+    now = iss.epoch_jd
+    catalog = [
+        (line1, line2),
+        elements(2, now - 45.0),
+        elements(3, now, mean_motion=17.5),
+        elements(4, now, bstar=0.8),
+        elements(5, now - 1.0),
+        elements(5, now),
+    ]
+    report = orbit_core.check_tle_catalog(catalog)
+    assert report.checked == 6 and report.reference_jd == pytest.approx(now)
+    assert report.stale == [2]
+    assert report.outliers == [3]
+    assert report.bad_bstar == [4]
+    assert report.duplicates == [5]
+    assert len(report) == len(report.issues) == 4
+    assert {kind for _, kind, _, _ in report.issues} == {"stale", "outlier", "bad_bstar", "duplicate"}
+    assert not report.is_clean
+
+    assert orbit_core.check_tle_catalog([iss, (line1, line2)]).is_clean
+    assert orbit_core.check_tle_catalog(catalog, max_age_days=60.0).stale == []
+    assert orbit_core.check_tle_catalog(catalog, reference_jd=now + 40.0).stale == [2, 3, 4, 5, 25544]
+    with pytest.raises(ValueError):
+        orbit_core.check_tle_catalog(catalog, max_age_days=0.0)
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_catalog_diff(iss_tle):
+    """Test comparing two catalogue releases."""
+    line1, line2 = iss_tle
+    iss = orbit_core.Sgp4Propagator(line1, line2)
+    day1, day2 = iss.epoch_jd, iss.epoch_jd + 1.0
+
+    def release(jd, ids, burned=()):
+        sats = []
+        for norad_id in ids:
+            sat = iss.propagate_to(jd)
+            velocity = sat.velocity
+            if norad_id in burned:
+                velocity = [v * 1.001 for v in velocity]
+            sats.append(orbit_core.Satellite(norad_id, sat.position, velocity, epoch=orbit_core.Epoch(jd)))
+        return orbit_core.Catalog(sats)
+
+    old = release(day1, [1, 2, 3])
+    new = release(day2, [2, 3, 4], burned={3})
+    diff = old.diff(new)
+    assert diff.added == [4] and diff.removed == [1]
+    assert diff.unchanged == 1
+    assert [change.norad_id for change in diff.changed] == [3]
+    change = diff.changed[0]
+    assert "sma" in change.exceeded and change.sma_change_km > 10.0
+    assert old.diff(old).changed == [] and old.diff(old).unchanged == 3
+    with pytest.raises(ValueError):
+        old.diff(new, sma_threshold_km=-1.0)
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_screen_window_tiled():
+    """Screening in tiles finds the same hits as screening at once"""
+    start = 2460000.5
+    props = [
+        orbit_core.Sgp4Propagator.from_elements(
+            orbit_core.MeanElements(k + 1, start, 0.0, 90.0, 45.0 * k, 0.0001, 0.0, 90.0 * k, 15.0)
+        )
+        for k in range(5)
+    ]
+    expected = orbit_core.screen_window(props, start, start + 0.1, 60.0, 50.0)
+    tiled = orbit_core.screen_window(props, start, start + 0.1, 60.0, 50.0, tile_size=2)
+    assert expected
+    assert [(c.norad_id_1, c.norad_id_2, round(c.distance_km, 6)) for c in tiled] == [
+        (c.norad_id_1, c.norad_id_2, round(c.distance_km, 6)) for c in expected
+    ]
+    with pytest.raises(ValueError):
+        orbit_core.screen_window(props, start, start + 0.1, 60.0, 50.0, tile_size=0)
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_screening_gpu_backend():
+    """The GPU backend finds the CPU hits, falling back to the CPU without a device"""
+    start = 2460000.5
+    props = [
+        orbit_core.Sgp4Propagator.from_elements(
+            orbit_core.MeanElements(k + 1, start, 0.0, 90.0, 45.0 * k, 0.0001, 0.0, 90.0 * k, 15.0)
+        )
+        for k in range(5)
+    ]
+    pipeline = orbit_core.ScreeningPipeline(50.0).add_geometric_filter()
+    cpu = pipeline.run(props, start, start + 0.1, 60.0)
+    assert pipeline.backend == "cpu"
+    gpu = pipeline.set_backend("gpu").run(props, start, start + 0.1, 60.0)
+    assert pipeline.backend == "gpu"
+    assert pipeline.last_backend == ("gpu" if orbit_core.gpu_device() else "cpu")
+    assert [(c.norad_id_1, c.norad_id_2) for c in gpu] == [(c.norad_id_1, c.norad_id_2) for c in cpu]
+    with pytest.raises(ValueError):
+        pipeline.set_backend("tpu")
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_screen_maneuver_candidates():
+    """Candidate burns are screened in one run, a zero burn matching the nominal screen"""
+    start = 2460000.5
+    primary, *catalog = [
+        orbit_core.Sgp4Propagator.from_elements(
+            orbit_core.MeanElements(norad_id, start, 0.0, 90.0, raan, 0.0001, 0.0, anomaly, 15.0)
+        )
+        for norad_id, raan, anomaly in [(1, 0.0, 0.0), (2, 90.0, 0.0), (3, 45.0, 180.0)]
+    ]
+    nominal = orbit_core.screen_window([primary] + catalog, start, start + 0.1, 60.0, 50.0)
+    nominal = [c for c in nominal if 1 in (c.norad_id_1, c.norad_id_2)]
+    outcomes = orbit_core.screen_maneuver_candidates(
+        primary,
+        [(start + 0.01, (0.0, 0.0, 0.0)), (start + 0.01, (0.0, 0.01, 0.0))],
+        catalog,
+        start,
+        start + 0.1,
+        50.0,
+    )
+    assert [o.candidate for o in outcomes] == [0, 1]
+    assert len(outcomes[0].conjunctions) == len(nominal)
+    assert outcomes[0].min_distance_km == pytest.approx(min(c.distance_km for c in nominal), abs=1e-3)
+    assert outcomes[0].worst.distance_km == outcomes[0].min_distance_km
+    assert abs(outcomes[1].min_distance_km - outcomes[0].min_distance_km) > 1.0
+    with pytest.raises(ValueError):
+        orbit_core.screen_maneuver_candidates(primary, [], catalog, start, start + 0.1, 50.0)
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_check_station_keeping():
+    """A geostationary satellite stays in a box around its slot and leaves a box beside it"""
+    start = 2460000.5
+    geo = orbit_core.Sgp4Propagator.from_elements(
+        orbit_core.MeanElements(40000, start, 0.0, 0.02, 80.0, 0.0001, 0.0, 0.0, 1.00273791)
+    )
+    report = orbit_core.check_station_keeping(geo, 0.0, start, start + 3.0, 180.0, 1.0)
+    assert report.violations == []
+    assert abs(report.drift_rate_deg_per_day) < 0.01
+    assert 0.0 < report.max_latitude_deg < 0.1
+
+    slot = report.mean_longitude_deg
+    assert orbit_core.check_station_keeping(geo, slot, start, start + 3.0).first_exit_jd is None
+    beside = orbit_core.check_station_keeping(geo, slot + 0.2, start, start + 3.0)
+    assert beside.first_exit_jd == start
+    assert beside.violations[0].edge == "west"
+    with pytest.raises(ValueError):
+        orbit_core.check_station_keeping(geo, slot, start, start - 1.0)
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_orbit_lifetime():
+    """Test solar-cycle lifetime estimates, disposal rule checks and the batch form."""
+    epoch = 2460676.5
+    elements = orbit_core.MeanElements(5, epoch, 0.0, 97.5, 0.0, 0.0005, 0.0, 0.0, 15.2)
+    lifetime = orbit_core.orbit_lifetime(elements, area_to_mass=0.01)
+    assert lifetime.norad_id == 5 and lifetime.ballistic_coefficient == pytest.approx(0.022)
+    assert 0.5 < lifetime.years < 25.0 and lifetime.compliant_25_year
+    assert lifetime.decay_jd == pytest.approx(epoch + lifetime.days)
+    assert lifetime.complies(lifetime.years + 0.01) and not lifetime.complies(lifetime.years - 0.01)
+
+    quiet = orbit_core.orbit_lifetime(elements, area_to_mass=0.01, solar_cycle_profile=70.0)
+    active = orbit_core.orbit_lifetime(
+        elements, area_to_mass=0.01, solar_cycle_profile=[(epoch, 200.0), (epoch + 3650.0, 200.0)]
+    )
+    assert active.days < lifetime.days < quiet.days
+
+    high = orbit_core.MeanElements(6, epoch, 0.0, 97.5, 0.0, 0.0005, 0.0, 0.0, 13.0)
+    batch = orbit_core.orbit_lifetime_batch([elements, high], area_to_mass=[0.01, 0.01], max_years=50.0)
+    assert batch[0].days == pytest.approx(lifetime.days)
+    assert batch[1].days is None and not batch[1].compliant_25_year
+    with pytest.raises(ValueError):
+        orbit_core.orbit_lifetime_batch([elements, high], area_to_mass=[0.01])
+    with pytest.raises(ValueError):
+        orbit_core.orbit_lifetime(elements, area_to_mass=0.01, solar_cycle_profile=-5.0)
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_orbit_design_helpers():
+    """Test sun-synchronous, repeat-ground-track and frozen-orbit design helpers."""
+    assert orbit_core.sun_synchronous_inclination(800.0) == pytest.approx(98.6, abs=0.05)
+    with pytest.raises(ValueError):
+        orbit_core.sun_synchronous_inclination(7000.0)
+
+    landsat = orbit_core.repeat_ground_track(233, 16)
+    assert (landsat.revolutions, landsat.days) == (233, 16)
+    assert 695.0 < landsat.altitude_km < 710.0
+    assert landsat.inclination_deg == pytest.approx(orbit_core.sun_synchronous_inclination(landsat.altitude_km))
+    equatorial = orbit_core.repeat_ground_track(15, 1, inclination_deg=0.0)
+    assert equatorial.inclination_deg == 0.0
+
+    solutions = orbit_core.repeat_ground_track_solutions(690.0, 720.0, max_days=16)
+    assert any((s.revolutions, s.days) == (233, 16) for s in solutions)
+    assert all(690.0 <= s.altitude_km <= 720.0 for s in solutions)
+
+    eccentricity, argp = orbit_core.frozen_orbit(landsat.altitude_km, landsat.inclination_deg)
+    assert 0.0009 < eccentricity < 0.0012 and argp == 90.0
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_screen_launch_blackouts():
+    """Test launch COLA screening of a vertical ascent against a near-equatorial satellite."""
+    liftoff = orbit_core.Epoch(2460000.5)
+    times = [10.0 * k for k in range(51)]
+    ascent = orbit_core.Ephemeris(
+        90000,
+        [liftoff + t for t in times],
+        [[6378.137 + 2.0 * t, 0.0, 0.0] for t in times],
+        [[2.0, 0.0, 0.0] for _ in times],
+        ref_frame="ECEF",
+    )
+    low = orbit_core.Sgp4Propagator.from_elements(
+        orbit_core.MeanElements(7, 2460000.5, 0.0, 0.1, 0.0, 0.0001, 0.0, 0.0, 14.58)
+    )
+    high = orbit_core.Sgp4Propagator.from_elements(
+        orbit_core.MeanElements(8, 2460000.5, 0.0, 0.1, 0.0, 0.0001, 0.0, 0.0, 2.0)
+    )
+    report = orbit_core.screen_launch(ascent, [low, high], 2460000.5, 2460000.6, 100.0, liftoff_step=60.0)
+    assert report.screened_objects == 1 and len(report.liftoffs) == 145
+    assert report.blackouts and all(b.norad_ids == [7] for b in report.blackouts)
+    blackout = report.blackouts[0]
+    assert blackout.min_distance_km < 100.0 and blackout.duration_s >= 0.0
+    assert not report.is_clear_at(blackout.start_jd)
+    assert len(report.clear_liftoffs) > 100
+    blocked = [l for l in report.liftoffs if not l.clear]
+    assert blocked[0].worst.norad_id_2 == 7 and blocked[0].min_distance_km < 100.0
+    with pytest.raises(ValueError):
+        orbit_core.screen_launch(ascent, [low], 2460000.6, 2460000.5, 100.0)
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_conjunction_report():
+    """Test HTML and Markdown conjunction reports of a screening run."""
+    start = 2460000.5
+    props = [
+        orbit_core.Sgp4Propagator.from_elements(
+            orbit_core.MeanElements(norad_id, start, 0.0, 90.0, raan, 0.0001, 0.0, 0.0, 15.0)
+        )
+        for norad_id, raan in [(1, 0.0), (2, 90.0), (3, 45.0)]
+    ]
+    events = orbit_core.screen_window(props, start, start + 0.1, 60.0, 50.0)
+    assert events
+
+    html = orbit_core.conjunction_report(events, threshold_km=50.0, names={1: "SAT & CO"})
+    assert html.startswith("<!DOCTYPE html>") and "SAT &amp; CO" in html
+    assert "<svg" in html and html.count("<table>") == 1 + len({e.norad_id_1 for e in events})
+
+    markdown = orbit_core.conjunction_report(events, format="markdown", title="Daily screen", top=1)
+    assert markdown.startswith("# Daily screen\n") and "## Top 1 events" in markdown
+    assert "## Asset 1" in markdown
+    with pytest.raises(ValueError):
+        orbit_core.conjunction_report(events, format="pdf")
+
+
+@pytest.fixture
+def tabular_data():
+    """Screening events and a three-sample ephemeris for the writer tests."""
+    start = 2460000.5
+    props = [
+        orbit_core.Sgp4Propagator.from_elements(
+            orbit_core.MeanElements(norad_id, start, 0.0, 90.0, raan, 0.0001, 0.0, 0.0, 15.0)
+        )
+        for norad_id, raan in [(1, 0.0), (2, 90.0)]
+    ]
+    events = orbit_core.screen_window(props, start, start + 0.1, 60.0, 50.0)
+    epochs = [orbit_core.Epoch(start) + 60.0 * k for k in range(3)]
+    ephemeris = orbit_core.Ephemeris(7, epochs, [[7000.0 + k, 0.0, 0.0] for k in range(3)], [[0.0, 7.5, 0.0]] * 3)
+    return events, ephemeris
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_write_csv(tmp_path, tabular_data):
+    """Test CSV output of conjunctions and ephemerides."""
+    import csv
+
+    events, ephemeris = tabular_data
+    assert events
+
+    orbit_core.write_csv(events, str(tmp_path / "events.csv"))
+    with open(tmp_path / "events.csv") as f:
+        rows = list(csv.DictReader(f))
+    assert len(rows) == len(events)
+    assert [float(r["distance_km"]) for r in rows] == [e.distance_km for e in events]
+    assert all(r["probability"] == "" for r in rows)
+
+    ephemeris.write_csv(str(tmp_path / "eph.csv"))
+    with open(tmp_path / "eph.csv") as f:
+        rows = list(csv.DictReader(f))
+    assert [float(r["x_km"]) for r in rows] == [7000.0, 7001.0, 7002.0]
+    assert float(rows[2]["offset_s"]) == pytest.approx(120.0)
+    with pytest.raises(TypeError):
+        orbit_core.write_csv([1, 2], str(tmp_path / "bad.csv"))
+    with pytest.raises(IOError):
+        orbit_core.write_csv(events, str(tmp_path / "missing" / "x.csv"))
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE or not hasattr(orbit_core, "write_parquet"), reason="Rust engine built without parquet")
+def test_write_parquet(tmp_path, tabular_data):
+    """Test Parquet output of conjunctions and ephemerides."""
+    events, ephemeris = tabular_data
+    orbit_core.write_parquet(events, str(tmp_path / "events.parquet"))
+    data = (tmp_path / "events.parquet").read_bytes()
+    assert data[:4] == b"PAR1" and data[-4:] == b"PAR1"
+    ephemeris.write_parquet(str(tmp_path / "eph.parquet"))
+    assert (tmp_path / "eph.parquet").read_bytes()[:4] == b"PAR1"
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_find_conjunctions_result_limits():
+    """Test top-K, truncation and minimum-separation filtering of snapshot screens."""
+    sats = [
+        orbit_core.Satellite(norad_id=k, position=[7000.0 + 0.5 * k, 0.0, 0.0], velocity=[0.0, 7.5, 0.0])
+        for k in range(1, 9)
+    ]
+    everything = orbit_core.find_conjunctions(sats, 10.0, sort_by="distance")
+    assert len(everything) == 28
+
+    top = orbit_core.find_conjunctions(sats, 10.0, top_k_by_distance=5, sort_by="distance")
+    assert [c.distance_km for c in top] == [c.distance_km for c in everything[:5]]
+    assert len(orbit_core.find_conjunctions(sats, 10.0, max_results=3)) == 3
+
+    spread = orbit_core.find_conjunctions(sats, 10.0, min_separation_km=2.0, top_k_by_distance=4)
+    assert len(spread) == 4 and all(c.distance_km >= 2.0 for c in spread)
+    assert len(orbit_core.Catalog(sats).find_conjunctions(10.0, top_k_by_distance=2)) == 2
+    with pytest.raises(ValueError):
+        orbit_core.find_conjunctions(sats, 10.0, min_separation_km=-1.0)
+
+
+@pytest.mark.skipif(not RUST_AVAILABLE, reason="Rust engine not installed")
+def test_alpha5_and_international_designators(iss_tle):
+    """Test Alpha-5 catalog numbers and COSPAR designators on satellites and conjunctions."""
+    import pickle
+
+    assert orbit_core.norad_to_alpha5(25544) == "25544"
+    assert orbit_core.norad_to_alpha5(100001) == "A0001"
+    assert orbit_core.alpha5_to_norad("Z9999") == 339999
+    assert orbit_core.parse_international_designator("98067A") == "1998-067A"
+    assert orbit_core.tle_international_designator("2024-001ABC") == "24001ABC"
+    with pytest.raises(ValueError):
+        orbit_core.alpha5_to_norad("I0001")
+    with pytest.raises(ValueError):
+        orbit_core.norad_to_alpha5(340000)
+
+    line1, line2 = iss_tle
+    elements = orbit_core.parse_tle(line1.replace("25544", "E8493"), line2.replace("25544", "E8493"), False)
+    assert elements.norad_id == 148493 and elements.international_designator == "1998-067A"
+
+    sat1 = orbit_core.Satellite("A0001", [7000.0, 0.0, 0.0], [0.0, 7.5, 0.0], international_designator="24001A")
+    sat2 = orbit_core.Satellite(25544, [7001.0, 0.0, 0.0], [0.0, 7.5, 0.0], international_designator="1998-067A")
+    assert sat1.norad_id == 100001 and sat1.alpha5 == "A0001"
+    assert sat1.international_designator == "2024-001A"
+    assert pickle.loads(pickle.dumps(sat1)) == sat1
+    with pytest.raises(ValueError):
+        sat2.international_designator = "1998-67"
+
+    (event,) = orbit_core.find_conjunctions([sat1, sat2], 5.0)
+    assert (event.alpha5_1, event.alpha5_2) == ("A0001", "25544")
+    assert (event.international_designator_1, event.international_designator_2) == ("2024-001A", "1998-067A")
+    assert pickle.loads(pickle.dumps(event)) == event
+
+    start = 2460000.5
+    props = []
+    for norad_id, raan, designator in [(1, 0.0, "2024-001A"), (2, 90.0, "2024-001B")]:
+        elements = orbit_core.MeanElements(norad_id, start, 0.0, 90.0, raan, 0.0001, 0.0, 0.0, 15.0)
+        elements.international_designator = designator
+        props.append(orbit_core.Sgp4Propagator.from_elements(elements))
+    events = orbit_core.screen_window(props, start, start + 0.1, 60.0, 50.0)
+    assert events and events[0].international_designator_2 == "2024-001B"
+    cdm = orbit_core.conjunction_to_cdm(events[0])
+    assert "= 2024-001A" in cdm and "= 2024-001B" in cdm
+
+
 def test_rust_not_installed():
     """Test behavior when Rust engine is not available."""
     if not RUST_AVAILABLE: